@@ -0,0 +1,71 @@
+//! SRTP protect/unprotect throughput.
+//!
+//! Run with `cargo bench srtp`. Any further arguments filter the cases by name,
+//! e.g. `cargo bench srtp -- gcm`.
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use str0m::_internal_test_exports::bench::SrtpBench;
+
+const PAYLOAD_LENS: &[usize] = &[160, 1200];
+const DURATION: Duration = Duration::from_secs(1);
+
+fn main() {
+    // cargo passes --bench, which we don't need.
+    let filters: Vec<_> = env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with("--"))
+        .collect();
+
+    type NewFn = fn(usize) -> SrtpBench;
+
+    let profiles: [(&str, NewFn); 2] = [
+        ("aes_128_cm_sha1_80", SrtpBench::aes_128_cm_sha1_80),
+        ("aead_aes_128_gcm", SrtpBench::aead_aes_128_gcm),
+    ];
+
+    for (profile, new) in profiles {
+        for len in PAYLOAD_LENS {
+            let mut bench = new(*len);
+
+            let name = format!("srtp/{profile}/protect/{len}");
+            run(&name, &filters, || bench.protect());
+
+            let name = format!("srtp/{profile}/unprotect/{len}");
+            run(&name, &filters, || bench.unprotect());
+        }
+    }
+}
+
+fn run(name: &str, filters: &[String], mut f: impl FnMut() -> usize) {
+    if !filters.iter().all(|x| name.contains(x.as_str())) {
+        return;
+    }
+
+    // Warm up.
+    for _ in 0..1000 {
+        black_box(f());
+    }
+
+    let start = Instant::now();
+    let mut packets: u64 = 0;
+    let mut bytes: u64 = 0;
+
+    while start.elapsed() < DURATION {
+        for _ in 0..1000 {
+            bytes += black_box(f()) as u64;
+        }
+        packets += 1000;
+    }
+
+    let secs = start.elapsed().as_secs_f64();
+
+    println!(
+        "{:<40} {:>10.0} packets/s {:>10.1} MB/s",
+        name,
+        packets as f64 / secs,
+        bytes as f64 / secs / 1_000_000.0
+    );
+}
@@ -238,19 +238,12 @@ fn read_socket_input<'a>(socket: &UdpSocket, buf: &'a mut Vec<u8>) -> Option<Inp
 
             // Parse data to a DatagramRecv, which help preparse network data to
             // figure out the multiplexing of all protocols on one UDP port.
-            let Ok(contents) = buf.as_slice().try_into() else {
+            let destination = socket.local_addr().unwrap();
+            let Ok(receive) = Receive::new(Protocol::Udp, source, destination, buf) else {
                 return None;
             };
 
-            return Some(Input::Receive(
-                Instant::now(),
-                Receive {
-                    proto: Protocol::Udp,
-                    source,
-                    destination: socket.local_addr().unwrap(),
-                    contents,
-                },
-            ));
+            Some(Input::Receive(Instant::now(), receive))
         }
 
         Err(e) => match e.kind() {
@@ -616,7 +609,7 @@ impl Client {
         };
 
         // Match outgoing pt to incoming codec.
-        let Some(pt) = writer.match_params(data.params) else {
+        let Some(pt) = writer.match_params(data.params.clone()) else {
             return;
         };
 
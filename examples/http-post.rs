@@ -133,12 +133,7 @@ fn run(mut rtc: Rtc, socket: UdpSocket) -> Result<(), RtcError> {
                 buf.truncate(n);
                 Input::Receive(
                     Instant::now(),
-                    Receive {
-                        proto: Protocol::Udp,
-                        source,
-                        destination: socket.local_addr().unwrap(),
-                        contents: buf.as_slice().try_into()?,
-                    },
+                    Receive::new(Protocol::Udp, source, socket.local_addr().unwrap(), &buf)?,
                 )
             }
 
@@ -0,0 +1,71 @@
+//! Exported things for benchmarks with feature `_internal_test_exports`.
+
+use crate::crypto::{KeyingMaterial, SrtpProfile};
+use crate::rtp_::{ExtensionMap, RtpHeader, SrtpContext};
+
+/// UNSTABLE: not public API!
+///
+/// A pair of SRTP contexts sharing keying material, to protect and unprotect the same packet.
+pub struct SrtpBench {
+    tx: SrtpContext,
+    rx: SrtpContext,
+    header: RtpHeader,
+    plain: Vec<u8>,
+    protected: Vec<u8>,
+}
+
+impl SrtpBench {
+    /// UNSTABLE: not public API!
+    pub fn aes_128_cm_sha1_80(payload_len: usize) -> Self {
+        Self::new(SrtpProfile::Aes128CmSha1_80, payload_len)
+    }
+
+    /// UNSTABLE: not public API!
+    pub fn aead_aes_128_gcm(payload_len: usize) -> Self {
+        Self::new(SrtpProfile::AeadAes128Gcm, payload_len)
+    }
+
+    fn new(profile: SrtpProfile, payload_len: usize) -> Self {
+        let mat = KeyingMaterial::new((0..profile.keying_material_len() as u8).collect());
+
+        let mut tx = SrtpContext::new(profile, &mat, true);
+        let rx = SrtpContext::new(profile, &mat, true);
+
+        let mut header = RtpHeader {
+            sequence_number: 47,
+            ssrc: 42.into(),
+            ..Default::default()
+        };
+
+        let mut plain = vec![0; 100 + payload_len];
+        header.header_len = header.write_to(&mut plain, &ExtensionMap::empty());
+        plain.truncate(header.header_len + payload_len);
+
+        let protected = tx.protect_rtp(&plain, &header, 47);
+
+        SrtpBench {
+            tx,
+            rx,
+            header,
+            plain,
+            protected,
+        }
+    }
+
+    /// UNSTABLE: not public API!
+    ///
+    /// Returns the number of bytes of the protected packet.
+    pub fn protect(&mut self) -> usize {
+        self.tx.protect_rtp(&self.plain, &self.header, 47).len()
+    }
+
+    /// UNSTABLE: not public API!
+    ///
+    /// Returns the number of bytes of the unprotected payload.
+    pub fn unprotect(&mut self) -> usize {
+        self.rx
+            .unprotect_rtp(&self.protected, &self.header, 47)
+            .expect("unprotect to succeed")
+            .len()
+    }
+}
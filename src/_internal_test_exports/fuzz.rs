@@ -68,7 +68,7 @@ pub fn rtp_packet(data: &[u8]) -> Option<()> {
         let header = RtpHeader::_parse(rng.slice(len)?, &session.exts)?;
         let pkt_len = rng.usize(1500)?;
         let data = rng.slice(pkt_len)?;
-        session.handle_rtp(now, header, data);
+        session.handle_rtp(now, header, data, None);
     }
 }
 
@@ -96,7 +96,11 @@ pub fn depack(data: &[u8]) -> Option<()> {
         _ => unreachable!(),
     };
 
-    let mut depack = DepacketizingBuffer::new(codec.into(), rng.usize(300)?);
+    let hold_back = rng.usize(300)?;
+    let max_frame_size = rng.usize(100_000)?;
+    let frame_timeout = Duration::from_millis(rng.u64(10000)?);
+    let mut depack =
+        DepacketizingBuffer::new(codec.into(), hold_back, max_frame_size, frame_timeout);
 
     let exts = random_extmap(&mut rng, 10)?;
 
@@ -7,6 +7,7 @@ use crate::media::Mid;
 use crate::rtp::{ExtensionMap, RtpHeader};
 use crate::Rtc;
 
+pub mod bench;
 pub mod fuzz;
 mod rng;
 use rng::Rng;
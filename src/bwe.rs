@@ -1,9 +1,147 @@
 //! Bandwidth estimation.
 
-use crate::{rtp_::Mid, Rtc};
+use std::fmt;
+use std::panic::UnwindSafe;
+use std::time::{Duration, Instant};
+
+use crate::packet::SendSideBandwithEstimator;
+use crate::rtp_::{Mid, SeqNo, TwccSendRecord};
+use crate::Rtc;
 
 pub use crate::rtp_::Bitrate;
 
+/// A bandwidth estimator driven by transport wide feedback.
+///
+/// The built-in estimator is [`GoogCc`], this trait allows replacing it with
+/// [`Bwe::set_estimator()`]. Like the rest of str0m, the estimator is sans-IO. It is fed
+/// feedback and time, and is polled for the estimate and when it next needs a timeout.
+///
+/// The estimate is used to pace the outgoing media, and is reported via
+/// [`Event::EgressBitrateEstimate`][crate::Event::EgressBitrateEstimate].
+pub trait BandwidthEstimator: Send + Sync + UnwindSafe {
+    /// Handle a TWCC report for packets we sent.
+    ///
+    /// Packets reported as lost have no `recv_time`.
+    fn handle_feedback(&mut self, feedback: &[PacketFeedback], now: Instant);
+
+    /// Handle ECN feedback with the number of packets marked CE (congestion experienced)
+    /// out of the total received since the last feedback.
+    ///
+    /// Only called if ECN is enabled via [`RtcConfig::enable_ecn()`][crate::RtcConfig::enable_ecn].
+    fn handle_ecn(&mut self, ce: u64, total: u64, now: Instant) {
+        let _ = (ce, total, now);
+    }
+
+    /// Move time forward.
+    fn handle_timeout(&mut self, now: Instant);
+
+    /// When the estimator next needs a [`BandwidthEstimator::handle_timeout()`].
+    fn poll_timeout(&self) -> Option<Instant>;
+
+    /// The current estimate, if any.
+    fn estimate(&self) -> Option<Bitrate>;
+
+    /// Start over from the given bitrate, see [`Bwe::reset()`].
+    fn reset(&mut self, init_bitrate: Bitrate);
+
+    /// Start probing for bandwidth, see [`Bwe::start_probe()`].
+    ///
+    /// Does nothing unless implemented.
+    fn start_probe(&mut self, target_bitrate: Bitrate, duration: Duration) {
+        let _ = (target_bitrate, duration);
+    }
+
+    /// The bitrate the pacer should pad up to while probing.
+    fn probe_target(&self) -> Option<Bitrate> {
+        None
+    }
+}
+
+/// Feedback for a single packet we sent, from a TWCC report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketFeedback {
+    /// The transport wide sequence number.
+    pub seq_no: SeqNo,
+    /// The size of the packet in bytes.
+    pub size: usize,
+    /// When we sent the packet.
+    pub send_time: Instant,
+    /// When the remote peer received the packet, `None` if it was lost.
+    ///
+    /// This is in the remote peer's clock translated to an [`Instant`], and is only
+    /// comparable to other receive times.
+    pub recv_time: Option<Instant>,
+    /// The round trip time from sending the packet to receiving the feedback.
+    pub rtt: Option<Duration>,
+}
+
+impl From<&TwccSendRecord> for PacketFeedback {
+    fn from(r: &TwccSendRecord) -> Self {
+        PacketFeedback {
+            seq_no: r.seq(),
+            size: r.size(),
+            send_time: r.local_send_time(),
+            recv_time: r.remote_recv_time(),
+            rtt: r.rtt(),
+        }
+    }
+}
+
+/// The built-in Googcc bandwidth estimator.
+///
+/// This is the estimator str0m uses unless replaced via [`Bwe::set_estimator()`]. A custom
+/// [`BandwidthEstimator`] can wrap it, to adjust its input or its estimate.
+pub struct GoogCc(SendSideBandwithEstimator);
+
+impl GoogCc {
+    /// Create a new estimator starting out from `initial_bitrate`.
+    pub fn new(initial_bitrate: Bitrate) -> Self {
+        GoogCc(SendSideBandwithEstimator::new(initial_bitrate))
+    }
+}
+
+impl BandwidthEstimator for GoogCc {
+    fn handle_feedback(&mut self, feedback: &[PacketFeedback], now: Instant) {
+        self.0.update(feedback, now);
+    }
+
+    fn handle_ecn(&mut self, ce: u64, total: u64, now: Instant) {
+        self.0.handle_ecn(ce, total, now);
+    }
+
+    fn handle_timeout(&mut self, now: Instant) {
+        self.0.handle_timeout(now);
+    }
+
+    fn poll_timeout(&self) -> Option<Instant> {
+        Some(self.0.poll_timeout())
+    }
+
+    fn estimate(&self) -> Option<Bitrate> {
+        self.0.last_estimate()
+    }
+
+    fn reset(&mut self, init_bitrate: Bitrate) {
+        self.0 = SendSideBandwithEstimator::new(init_bitrate);
+    }
+
+    fn start_probe(&mut self, target_bitrate: Bitrate, duration: Duration) {
+        self.0.start_probe(target_bitrate, duration);
+    }
+
+    fn probe_target(&self) -> Option<Bitrate> {
+        self.0.probe_target()
+    }
+}
+
+impl fmt::Debug for GoogCc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GoogCc")
+            .field("estimate", &self.0.last_estimate())
+            .finish()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 /// Bandwidth estimation kind.
 pub enum BweKind {
@@ -13,6 +151,28 @@ pub enum BweKind {
     Remb(Mid, Bitrate),
 }
 
+/// A bandwidth estimate crossing a threshold registered with [`Bwe::add_threshold()`].
+///
+/// Reported via [`Event::BweThresholdCrossed`][crate::Event::BweThresholdCrossed].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BweThresholdCrossed {
+    /// The threshold that was crossed.
+    pub threshold: Bitrate,
+    /// Whether the estimate went above or below the threshold.
+    pub direction: CrossingDirection,
+    /// The estimate at the time of crossing.
+    pub estimate: Bitrate,
+}
+
+/// Direction of a [`BweThresholdCrossed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossingDirection {
+    /// The estimate rose above the threshold.
+    Up,
+    /// The estimate dropped below the threshold.
+    Down,
+}
+
 /// Access to the Bandwidth Estimate subsystem.
 pub struct Bwe<'a>(pub(crate) &'a mut Rtc);
 
@@ -94,4 +254,76 @@ impl<'a> Bwe<'a> {
     pub fn reset(&mut self, init_bitrate: Bitrate) {
         self.0.session.reset_bwe(init_bitrate);
     }
+
+    /// Probe for available bandwidth above the current send rate.
+    ///
+    /// **Note:** This only has an effect if BWE has been enabled via
+    /// [`RtcConfig::enable_bwe`][crate::RtcConfig::enable_bwe].
+    ///
+    /// For `duration`, the pacer sends at `target_bitrate` by filling up with padding, and the
+    /// rate the packets arrive at the remote peer is measured via TWCC. If the link sustains a
+    /// higher bitrate than the current estimate, the estimate is raised straight away, which is
+    /// much quicker than the gradual increase of the delay based estimator. The new estimate is
+    /// reported via [`Event::EgressBitrateEstimate`][crate::Event::EgressBitrateEstimate].
+    ///
+    /// Only one probe runs at a time, starting a new one replaces any ongoing probe. Probes
+    /// should be short, typically a few hundred milliseconds, since they can congest the link.
+    ///
+    /// ```
+    /// # use str0m::{Rtc, bwe::Bitrate};
+    /// # use std::time::Duration;
+    /// let mut rtc = Rtc::new();
+    ///
+    /// rtc.bwe().start_probe(Bitrate::mbps(2), Duration::from_millis(500));
+    /// ```
+    pub fn start_probe(&mut self, target_bitrate: Bitrate, duration: Duration) {
+        self.0.session.start_bwe_probe(target_bitrate, duration);
+    }
+
+    /// Get an event when the estimate crosses a threshold.
+    ///
+    /// **Note:** This only has an effect if BWE has been enabled via
+    /// [`RtcConfig::enable_bwe`][crate::RtcConfig::enable_bwe].
+    ///
+    /// `hysteresis` is a fraction of the threshold the estimate must pass by before it counts
+    /// as a crossing. With a threshold of 500kbit/s and a hysteresis of `0.1`, the estimate
+    /// must rise above 550kbit/s or drop below 450kbit/s. This avoids a flurry of events when
+    /// the estimate hovers around the threshold.
+    ///
+    /// Crossings are reported via
+    /// [`Event::BweThresholdCrossed`][crate::Event::BweThresholdCrossed]. The first estimate
+    /// decides which side of the threshold we start on, and does not produce an event.
+    ///
+    /// Panics if `hysteresis` is not in the range `0.0..1.0`.
+    ///
+    /// ```
+    /// # use str0m::{Rtc, bwe::Bitrate};
+    /// let mut rtc = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+    ///
+    /// rtc.bwe().add_threshold(Bitrate::kbps(500), 0.1);
+    /// rtc.bwe().add_threshold(Bitrate::mbps(2), 0.1);
+    /// ```
+    pub fn add_threshold(&mut self, threshold: Bitrate, hysteresis: f64) {
+        assert!(
+            (0.0..1.0).contains(&hysteresis),
+            "hysteresis must be in the range 0.0..1.0"
+        );
+        self.0.session.add_bwe_threshold(threshold, hysteresis);
+    }
+
+    /// Remove all thresholds added with [`Bwe::add_threshold()`].
+    pub fn clear_thresholds(&mut self) {
+        self.0.session.clear_bwe_thresholds();
+    }
+
+    /// Replace the built-in estimator with a custom one.
+    ///
+    /// **Note:** This only has an effect if BWE has been enabled via
+    /// [`RtcConfig::enable_bwe`][crate::RtcConfig::enable_bwe].
+    ///
+    /// The new estimator is used for all subsequent feedback. Any state of the previous
+    /// estimator, including ongoing probes, is dropped.
+    pub fn set_estimator(&mut self, estimator: impl BandwidthEstimator + 'static) {
+        self.0.session.set_bwe_estimator(Box::new(estimator));
+    }
 }
@@ -1,12 +1,14 @@
 use crate::channel::ChannelId;
-use crate::crypto::Fingerprint;
+use crate::crypto::{Fingerprint, KeyingMaterial};
+use crate::error::IceError;
 use crate::media::{Media, MediaKind};
-use crate::rtp_::{Mid, Rid, Ssrc};
+use crate::rtp_::{App, Mid, Rid, Ssrc};
 use crate::sctp::ChannelConfig;
 use crate::streams::{StreamRx, StreamTx, DEFAULT_RTX_CACHE_DURATION};
 use crate::IceCreds;
 use crate::Rtc;
 use crate::RtcError;
+use crate::SrtpProfile;
 
 /// Direct change strategy.
 ///
@@ -54,6 +56,14 @@ impl<'a> DirectApi<'a> {
         self.rtc.ice.set_controlling(controlling);
     }
 
+    /// Whether this peer connection is the ICE controlling agent.
+    ///
+    /// If both peers start with the same role, the conflict is resolved during ICE and one
+    /// of them switches role, which is signalled with [`Event::IceRoleChange`][crate::Event::IceRoleChange].
+    pub fn ice_controlling(&self) -> bool {
+        self.rtc.ice.controlling()
+    }
+
     /// Returns a reference to the local ICE credentials used by this peer connection.
     ///
     /// The ICE credentials consist of the username and password used by the ICE agent during
@@ -63,8 +73,15 @@ impl<'a> DirectApi<'a> {
     }
 
     /// Sets the local ICE credentials.
-    pub fn set_local_ice_credentials(&mut self, local_ice_credentials: IceCreds) {
-        self.rtc.ice.set_local_credentials(local_ice_credentials);
+    ///
+    /// Fails if the credentials are invalid according to [`IceCreds::validate()`], or if
+    /// they can't change anymore since they are in use. That happens when adding local
+    /// candidates or creating an SDP offer/answer.
+    pub fn set_local_ice_credentials(
+        &mut self,
+        local_ice_credentials: IceCreds,
+    ) -> Result<(), IceError> {
+        self.rtc.ice.set_local_credentials(local_ice_credentials)
     }
 
     /// Sets the remote ICE credentials.
@@ -95,6 +112,51 @@ impl<'a> DirectApi<'a> {
         self.rtc.init_dtls(active)
     }
 
+    /// Set up SRTP from pre-shared keying material, bypassing DTLS.
+    ///
+    /// <div class="warning"><b>This is not standard WebRTC.</b>
+    ///
+    ///  Normally the SRTP keys are exported from the DTLS handshake, which also
+    ///  authenticates the remote peer. With pre-shared keys there is no such check,
+    ///  and the keys must be distributed, and kept secret, by other means. This is
+    ///  meant for testing and specialized deployments where both ends are under
+    ///  control, such as starting media without waiting for a handshake round trip.
+    /// </div>
+    ///
+    /// The `material` has the layout exported by DTLS-SRTP (RFC 5764 4.2): the client
+    /// master key, the server master key, the client master salt and the server master
+    /// salt. `active` is the role this side takes, the same as for
+    /// [`DirectApi::start_dtls()`]. The remote peer must use the same material and profile,
+    /// with the opposite role.
+    ///
+    /// Media can flow as soon as ICE is connected, [`Rtc::is_connected()`] stays false
+    /// unless DTLS is also started. Starting DTLS replaces these keys with the ones from
+    /// the handshake.
+    ///
+    /// Panics if `material` is not the length required by `profile`, which is 60 bytes for
+    /// [`SrtpProfile::Aes128CmSha1_80`] and [`SrtpProfile::Aes128CmSha1_32`], and 56 bytes
+    /// for [`SrtpProfile::AeadAes128Gcm`].
+    pub fn set_srtp_keying_material(
+        &mut self,
+        profile: SrtpProfile,
+        material: &[u8],
+        active: bool,
+    ) {
+        assert_eq!(
+            material.len(),
+            profile.keying_material_len(),
+            "SRTP keying material length for {}",
+            profile
+        );
+
+        info!(
+            "Set pre-shared SRTP keying material and profile: {}",
+            profile
+        );
+        let mat = KeyingMaterial::new(material.to_vec());
+        self.rtc.session.set_keying_material(mat, profile, active);
+    }
+
     /// Start the SCTP over DTLS.
     pub fn start_sctp(&mut self, client: bool) {
         self.rtc.init_sctp(client)
@@ -157,7 +219,8 @@ impl<'a> DirectApi<'a> {
         };
 
         let exts = self.rtc.session.exts.cloned_with_type(kind.is_audio());
-        let m = Media::from_direct_api(mid, next_index, kind, exts);
+        let mut m = Media::from_direct_api(mid, next_index, kind, exts);
+        m.set_cname(self.rtc.session.cname.clone());
 
         self.rtc.session.medias.push(m);
         self.rtc.session.medias.last_mut().unwrap()
@@ -269,4 +332,15 @@ impl<'a> DirectApi<'a> {
     pub fn stream_tx_by_mid(&mut self, mid: Mid, rid: Option<Rid>) -> Option<&mut StreamTx> {
         self.rtc.session.streams.stream_tx_by_mid_rid(mid, rid)
     }
+
+    /// Send an RTCP APP (application-defined) packet.
+    ///
+    /// The packet is queued and sent together with the next outgoing RTCP. The remote
+    /// str0m peer receives it as [`Event::RtcpApp`][crate::Event::RtcpApp].
+    ///
+    /// Errors with [`RtcError::InvalidRtcpApp`] if the subtype is above 31, or the packet
+    /// does not fit in a single datagram.
+    pub fn send_rtcp_app(&mut self, app: App) -> Result<(), RtcError> {
+        self.rtc.session.send_rtcp_app(app)
+    }
 }
@@ -14,7 +14,7 @@
 //! some "other way" keeping the two peers in sync.
 mod sdp;
 pub(crate) use sdp::AddMedia;
-pub use sdp::{SdpAnswer, SdpApi, SdpOffer, SdpPendingOffer};
+pub use sdp::{BundlePolicy, SdpAnswer, SdpApi, SdpDescription, SdpOffer, SdpPendingOffer};
 
 mod direct;
 pub use direct::DirectApi;
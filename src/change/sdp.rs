@@ -15,17 +15,40 @@ use crate::rtp_::{Direction, Extension, ExtensionMap, Mid, Pt, Ssrc};
 use crate::sctp::ChannelConfig;
 use crate::sdp::SimulcastGroups;
 use crate::sdp::{self, MediaAttribute, MediaLine, MediaType, Msid, Sdp};
-use crate::sdp::{Proto, SessionAttribute, Setup};
+use crate::sdp::{Proto, SdpError, SessionAttribute, Setup};
 use crate::session::Session;
 use crate::Rtc;
 use crate::RtcError;
 use crate::{Candidate, IceCreds};
 
-pub use crate::sdp::{SdpAnswer, SdpOffer};
+pub use crate::sdp::{SdpAnswer, SdpDescription, SdpOffer};
 use crate::streams::Streams;
 use crate::streams::DEFAULT_RTX_CACHE_DURATION;
 
+/// Which m-lines of an offer can be negotiated on a transport of their own (JSEP).
+///
+/// str0m has a single ICE and DTLS transport. All enabled m-lines go in one
+/// `a=group:BUNDLE`, and a remote SDP without a BUNDLE group is rejected. The policy
+/// decides which m-lines new in an offer are marked `a=bundle-only` (port 0), meaning a
+/// remote peer can only use them bundled.
+///
+/// Set with [`RtcConfig::set_bundle_policy()`][crate::RtcConfig::set_bundle_policy].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BundlePolicy {
+    /// Only the first m-line is offered as a transport of its own, all others are
+    /// bundle-only.
+    MaxBundle,
+    /// The first m-line of each media type (audio, video and application) is offered as a
+    /// transport of its own, the others are bundle-only.
+    Balanced,
+    /// No m-line is bundle-only. This is the default.
+    #[default]
+    MaxCompat,
+}
+
 /// Changes to the Rtc via SDP Offer/Answer dance.
+///
+/// See [`BundlePolicy`] for how the m-lines are bundled.
 pub struct SdpApi<'a> {
     rtc: &'a mut Rtc,
     changes: Changes,
@@ -70,13 +93,11 @@ impl<'a> SdpApi<'a> {
         self.rtc.next_change_id();
 
         if offer.media_lines.is_empty() {
-            return Err(RtcError::RemoteSdp("No m-lines in offer".into()));
+            return Err(SdpError::NoMedia.into());
         }
 
         if self.rtc.ice.ice_lite() && offer.session.ice_lite() {
-            return Err(RtcError::RemoteSdp(
-                "Both peers being ICE-Lite not supported".into(),
-            ));
+            return Err(SdpError::BothIceLite.into());
         }
 
         add_ice_details(self.rtc, &offer, None)?;
@@ -86,7 +107,7 @@ impl<'a> SdpApi<'a> {
                 self.rtc.remote_fingerprint = Some(f);
             } else {
                 self.rtc.disconnect();
-                return Err(RtcError::RemoteSdp("missing a=fingerprint".into()));
+                return Err(SdpError::MissingFingerprint.into());
             }
         }
 
@@ -100,6 +121,7 @@ impl<'a> SdpApi<'a> {
         init_dtls(self.rtc, &offer)?;
 
         // Modify session with offer
+        let offer_copy = offer.clone();
         apply_offer(&mut self.rtc.session, offer)?;
 
         // Handle potentially new m=application line.
@@ -108,11 +130,17 @@ impl<'a> SdpApi<'a> {
             self.rtc.init_sctp(client);
         }
 
+        // The remote will use our credentials from the answer.
+        self.rtc.ice.lock_local_credentials();
+
         let params = AsSdpParams::new(self.rtc, None);
-        let sdp = as_sdp(&self.rtc.session, params);
+        let answer: SdpAnswer = as_sdp(&self.rtc.session, params).into();
+
+        self.rtc.local_description = Some(SdpDescription::Answer(answer.clone()));
+        self.rtc.remote_description = Some(SdpDescription::Offer(offer_copy));
 
         debug!("Create answer");
-        Ok(sdp.into())
+        Ok(answer)
     }
 
     /// Accept an answer to a previously created [`SdpOffer`].
@@ -151,9 +179,7 @@ impl<'a> SdpApi<'a> {
         }
 
         if self.rtc.ice.ice_lite() && answer.session.ice_lite() {
-            return Err(RtcError::RemoteSdp(
-                "Both peers being ICE-Lite not supported".into(),
-            ));
+            return Err(SdpError::BothIceLite.into());
         }
 
         add_ice_details(self.rtc, &answer, Some(&pending))?;
@@ -166,7 +192,7 @@ impl<'a> SdpApi<'a> {
                 self.rtc.remote_fingerprint = Some(f);
             } else {
                 self.rtc.disconnect();
-                return Err(RtcError::RemoteSdp("missing a=fingerprint".into()));
+                return Err(SdpError::MissingFingerprint.into());
             }
         }
 
@@ -174,8 +200,12 @@ impl<'a> SdpApi<'a> {
         let new_channels = pending.changes.take_new_channels();
 
         // Modify session with answer
+        let answer_copy = answer.clone();
         apply_answer(&mut self.rtc.session, pending.changes, answer)?;
 
+        self.rtc.local_description = Some(SdpDescription::Offer(pending.offer));
+        self.rtc.remote_description = Some(SdpDescription::Answer(answer_copy));
+
         // Handle potentially new m=application line.
         let client = self.rtc.dtls.is_active().expect("DTLS to be inited");
         if self.rtc.session.app().is_some() {
@@ -215,8 +245,9 @@ impl<'a> SdpApi<'a> {
     /// the mid been advertised via [`Event::MediaAdded`][crate::Event::MediaAdded].
     ///
     /// * `stream_id` is used to synchronize media. It is `a=msid-semantic: WMS <streamId>` line in SDP.
-    /// * `track_id` is becomes both the track id in `a=msid <streamId> <trackId>` as well as the
-    ///   CNAME in the RTP SDES.
+    /// * `track_id` is the track id in `a=msid <streamId> <trackId>`.
+    ///
+    /// The CNAME in the RTCP SDES is shared by all media, see [`RtcConfig::set_cname()`][crate::RtcConfig::set_cname].
     ///
     /// ```
     /// # use str0m::{Rtc, media::MediaKind, media::Direction};
@@ -268,12 +299,12 @@ impl<'a> SdpApi<'a> {
         // TODO: let user configure stream/track name.
         let msid = Msid {
             stream_id,
-            track_id: track_id.clone(),
+            track_id,
         };
 
         let add = AddMedia {
             mid,
-            cname: track_id,
+            cname: self.rtc.session.cname.clone(),
             msid,
             kind,
             dir,
@@ -308,6 +339,45 @@ impl<'a> SdpApi<'a> {
         }
     }
 
+    /// Remove an already existing media.
+    ///
+    /// The next offer will contain the m-line with port 0, and the mid is taken out of
+    /// the BUNDLE group. Once the answer is accepted, the send and receive streams for the
+    /// media are torn down, and an RTCP BYE is sent for the outgoing SSRCs.
+    ///
+    /// The m-line itself is never removed from the SDP, since that would change the order of
+    /// the m-lines. The [`Media`] stays as [`Media::rejected()`] with [`Direction::Inactive`].
+    ///
+    /// If the media doesn't exist, or is already removed, this does nothing.
+    ///
+    /// ```
+    /// # use str0m::{Rtc, media::MediaKind, media::Direction, media::Mid};
+    /// let mut rtc = Rtc::new();
+    ///
+    /// let mut changes = rtc.sdp_api();
+    ///
+    /// // The mid of a previously negotiated media.
+    /// let mid: Mid = "a".into();
+    /// changes.remove_media(mid);
+    /// ```
+    pub fn remove_media(&mut self, mid: Mid) {
+        let exists = self
+            .rtc
+            .session
+            .media_by_mid(mid)
+            .map(|m| !m.rejected())
+            .unwrap_or(false);
+
+        let already_removed = self
+            .changes
+            .iter()
+            .any(|c| matches!(c, Change::RemoveMedia(m) if *m == mid));
+
+        if exists && !already_removed {
+            self.changes.0.push(Change::RemoveMedia(mid));
+        }
+    }
+
     /// Add a new data channel and get the `id` that will be used.
     ///
     /// The first ever data channel added to a WebRTC session results in a media
@@ -358,6 +428,11 @@ impl<'a> SdpApi<'a> {
     /// candidates must be added via [`Rtc::add_local_candidate`] before connectivity can be
     /// re-established.
     ///
+    /// ICE is never restarted unless requested with this function. Other changes, such as
+    /// adding media or changing a direction, keep the ICE credentials and candidates, and
+    /// the connection is not interrupted. A remote offer with new ICE credentials always
+    /// restarts ICE, as required by the spec.
+    ///
     /// Returns the new ICE credentials that will be used going forward.
     pub fn ice_restart(&mut self, keep_local_candidates: bool) -> IceCreds {
         self.changes
@@ -370,6 +445,33 @@ impl<'a> SdpApi<'a> {
         new_creds
     }
 
+    /// Whether [`SdpApi::apply()`] will do an ICE restart.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let mut rtc = Rtc::new();
+    ///
+    /// let mut changes = rtc.sdp_api();
+    /// assert!(!changes.has_ice_restart());
+    ///
+    /// changes.ice_restart(true);
+    /// assert!(changes.has_ice_restart());
+    ///
+    /// changes.cancel_ice_restart();
+    /// assert!(!changes.has_ice_restart());
+    /// ```
+    pub fn has_ice_restart(&self) -> bool {
+        self.changes.ice_restart().is_some()
+    }
+
+    /// Remove a pending ICE restart requested with [`SdpApi::ice_restart()`].
+    ///
+    /// The other changes are kept, and are negotiated without restarting ICE.
+    pub fn cancel_ice_restart(&mut self) {
+        self.changes
+            .retain(|c| !matches!(c, Change::IceRestart(_, _)));
+    }
+
     /// Attempt to apply the changes made.
     ///
     /// If this returns [`SdpOffer`], the caller the changes are
@@ -405,6 +507,7 @@ impl<'a> SdpApi<'a> {
             let pending = SdpPendingOffer {
                 change_id,
                 changes: self.changes,
+                offer: offer.clone(),
             };
             debug!("Create offer");
             Some((offer, pending))
@@ -469,6 +572,7 @@ impl<'a> SdpApi<'a> {
 pub struct SdpPendingOffer {
     change_id: usize,
     changes: Changes,
+    offer: SdpOffer,
 }
 
 impl SdpPendingOffer {
@@ -487,6 +591,7 @@ impl SdpPendingOffer {
                     rtc.media(*m).map(|m| m.direction() != *d).unwrap_or(false)
                 }
                 Change::IceRestart(v, _) => rtc.ice.local_credentials() != v,
+                Change::RemoveMedia(m) => rtc.media(*m).map(|m| !m.rejected()).unwrap_or(false),
             }
         }
 
@@ -520,6 +625,7 @@ pub(crate) enum Change {
     AddChannel((ChannelId, ChannelConfig)),
     Direction(Mid, Direction),
     IceRestart(IceCreds, bool),
+    RemoveMedia(Mid),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -560,6 +666,7 @@ fn requires_negotiation(c: &Change) -> bool {
         Change::AddApp(_) => true,
         Change::AddChannel(_) => false,
         Change::Direction(_, _) => true,
+        Change::RemoveMedia(_) => true,
     }
 }
 
@@ -579,6 +686,9 @@ fn create_offer(rtc: &mut Rtc, changes: &Changes) -> SdpOffer {
         rtc.ice.set_controlling(!rtc.ice.ice_lite());
     }
 
+    // The remote will use our credentials from the offer.
+    rtc.ice.lock_local_credentials();
+
     let params = AsSdpParams::new(rtc, Some(changes));
     let sdp = as_sdp(&rtc.session, params);
 
@@ -591,7 +701,7 @@ fn add_ice_details(
     pending: Option<&SdpPendingOffer>,
 ) -> Result<(), RtcError> {
     let Some(creds) = sdp.ice_creds() else {
-        return Err(RtcError::RemoteSdp("missing a=ice-ufrag/pwd".into()));
+        return Err(SdpError::MissingIceCredentials.into());
     };
 
     // If we are handling an **offer** from the remote, differing ICE credentials indicate an ICE
@@ -606,13 +716,13 @@ fn add_ice_details(
     if ice_restart {
         let (new_local_creds, keep_local_candidates) = if let Some(pending) = pending {
             // Since we have a pending, this is an answer to our offer.
-            pending.changes.ice_restart().ok_or_else(||
-                // Answer contained changed remote creds, indicating an ice restart
-                // but since we have no pending ice-creds, we didn't initiate it
-                // Ice restart in an ANSWER breaks spec.
-                    RtcError::RemoteSdp(
-                    "Ice restart in answer without one in the preceeding offer".into(),
-                ))?
+            // Answer contained changed remote creds, indicating an ice restart
+            // but since we have no pending ice-creds, we didn't initiate it
+            // Ice restart in an ANSWER breaks spec.
+            pending
+                .changes
+                .ice_restart()
+                .ok_or(SdpError::UnexpectedIceRestart)?
         } else {
             // The remote OFFER had an ice restart, and we need to respond with
             // new credentials in the ANSWER.
@@ -629,6 +739,10 @@ fn add_ice_details(
         rtc.ice.add_remote_candidate(r.clone());
     }
 
+    if sdp.end_of_candidates() {
+        rtc.ice.set_remote_end_of_candidates();
+    }
+
     Ok(())
 }
 
@@ -702,8 +816,64 @@ fn as_sdp(session: &Session, params: AsSdpParams) -> Sdp {
             pending.apply_to(&mut lines);
         }
 
-        // Mids go into the session part of the SDP.
-        let mids = v.iter().map(|m| m.mid()).collect();
+        // RFC 8858: only offers have a=rtcp-mux-only, answers only a=rtcp-mux.
+        if session.rtcp_mux_only && params.pending.is_some() {
+            for line in &mut lines {
+                if line.typ != sdp::MediaType::Application {
+                    line.attrs.push(MediaAttribute::RtcpMuxOnly);
+                }
+            }
+        }
+
+        // RFC 5506: answers only have a=rtcp-rsize if the offer has it.
+        if !session.rtcp_compound && (params.pending.is_some() || session.remote_rtcp_rsize) {
+            for line in &mut lines {
+                if line.typ != sdp::MediaType::Application {
+                    line.attrs.push(MediaAttribute::RtcpRsize);
+                }
+            }
+        }
+
+        // RFC 6679: answers only have a=ecn-capable-rtp if the offer has it.
+        if session.ecn && (params.pending.is_some() || session.remote_ecn) {
+            for line in &mut lines {
+                if line.typ != sdp::MediaType::Application {
+                    line.attrs.push(MediaAttribute::EcnCapableRtp);
+                }
+            }
+        }
+
+        // JSEP (RFC 8829): new m-lines in an offer that the policy doesn't give a transport
+        // of their own can only be used bundled.
+        if params.pending.is_some() {
+            let mut tagged: Vec<sdp::MediaType> = vec![];
+
+            for (index, line) in lines.iter_mut().enumerate() {
+                if line.disabled {
+                    continue;
+                }
+
+                let own_transport = match session.bundle_policy {
+                    BundlePolicy::MaxBundle => tagged.is_empty(),
+                    BundlePolicy::Balanced => !tagged.contains(&line.typ),
+                    BundlePolicy::MaxCompat => true,
+                };
+
+                if !own_transport && index >= new_index_start {
+                    line.attrs.push(MediaAttribute::BundleOnly);
+                }
+
+                tagged.push(line.typ.clone());
+            }
+        }
+
+        // Mids go into the session part of the SDP. Disabled m-lines (port 0)
+        // are not part of the BUNDLE group (RFC 8843).
+        let mids = lines
+            .iter()
+            .filter(|l| !l.disabled)
+            .map(|l| l.mid())
+            .collect();
 
         let mut stream_ids = vec![];
         for msid in v.iter().filter_map(|v| v.msid()) {
@@ -726,7 +896,7 @@ fn as_sdp(session: &Session, params: AsSdpParams) -> Sdp {
         },
     ];
 
-    if session.ice_lite {
+    if params.ice_lite {
         attrs.push(SessionAttribute::IceLite);
     }
 
@@ -745,9 +915,9 @@ fn apply_offer(session: &mut Session, offer: SdpOffer) -> Result<(), RtcError> {
 
     update_session(session, &offer);
 
-    let new_lines = sync_medias(session, &offer).map_err(RtcError::RemoteSdp)?;
+    let new_lines = sync_medias(session, &offer)?;
 
-    add_new_lines(session, &new_lines, true).map_err(RtcError::RemoteSdp)?;
+    add_new_lines(session, &new_lines, true)?;
 
     ensure_stream_tx(session);
 
@@ -761,20 +931,33 @@ fn apply_answer(
 ) -> Result<(), RtcError> {
     answer.assert_consistency()?;
 
+    let removed: Vec<Mid> = pending
+        .iter()
+        .filter_map(|c| match c {
+            Change::RemoveMedia(mid) => Some(*mid),
+            _ => None,
+        })
+        .collect();
+
     update_session(session, &answer);
 
-    let new_lines = sync_medias(session, &answer).map_err(RtcError::RemoteSdp)?;
+    let new_lines = sync_medias(session, &answer)?;
 
     // The new_lines from the answer must correspond to what we sent in the offer.
     if let Some(err) = pending.ensure_correct_answer(&new_lines) {
-        return Err(RtcError::RemoteSdp(err));
+        return Err(err.into());
     }
 
-    add_new_lines(session, &new_lines, false).map_err(RtcError::RemoteSdp)?;
+    add_new_lines(session, &new_lines, false)?;
 
     // Add all pending changes (since we pre-allocated SSRC communicated in the Offer).
     add_pending_changes(session, pending);
 
+    // Retire the media we removed in the offer, regardless of how the remote answered.
+    for mid in removed {
+        session.retire_media(mid);
+    }
+
     ensure_stream_tx(session);
 
     Ok(())
@@ -825,6 +1008,10 @@ fn ensure_stream_tx(session: &mut Session) {
                 (ssrc, None)
             };
 
+            if session.streams.reject_stream_tx(ssrc, media.mid(), rid) {
+                continue;
+            }
+
             let stream = session
                 .streams
                 .declare_stream_tx(ssrc, rtx, media.mid(), rid);
@@ -860,8 +1047,16 @@ fn add_pending_changes(session: &mut Session, pending: Changes) {
         media.set_cname(add_media.cname);
         media.set_msid(add_media.msid);
 
+        if media.rejected() {
+            continue;
+        }
+
         for (ssrc, rtx) in add_media.ssrcs {
             // TODO: When we allow sending RID, we need to add that here.
+            if session.streams.reject_stream_tx(ssrc, add_media.mid, None) {
+                continue;
+            }
+
             let stream = session
                 .streams
                 .declare_stream_tx(ssrc, rtx, add_media.mid, None);
@@ -881,7 +1076,7 @@ fn add_pending_changes(session: &mut Session, pending: Changes) {
 ///
 /// * Existing m-lines can apply changes (such as direction change).
 /// * New m-lines are returned to the caller.
-fn sync_medias<'a>(session: &mut Session, sdp: &'a Sdp) -> Result<Vec<&'a MediaLine>, String> {
+fn sync_medias<'a>(session: &mut Session, sdp: &'a Sdp) -> Result<Vec<&'a MediaLine>, SdpError> {
     let mut new_lines = Vec::with_capacity(sdp.media_lines.len());
 
     for (idx, m) in sdp.media_lines.iter().enumerate() {
@@ -901,6 +1096,17 @@ fn sync_medias<'a>(session: &mut Session, sdp: &'a Sdp) -> Result<Vec<&'a MediaL
                         return index_err(m.mid());
                     }
 
+                    // Port 0 means the m-line is removed, which is permanent.
+                    if m.disabled {
+                        session.retire_media(m.mid());
+                        continue;
+                    }
+
+                    // Removed m-lines stay removed, even if the remote has a port.
+                    if media.rejected() {
+                        continue;
+                    }
+
                     update_media(
                         media,
                         m,
@@ -909,6 +1115,8 @@ fn sync_medias<'a>(session: &mut Session, sdp: &'a Sdp) -> Result<Vec<&'a MediaL
                         &mut session.streams,
                     );
 
+                    update_rtcp_mux(media, m, session.rtcp_mux_only);
+
                     continue;
                 }
             }
@@ -921,8 +1129,8 @@ fn sync_medias<'a>(session: &mut Session, sdp: &'a Sdp) -> Result<Vec<&'a MediaL
         new_lines.push(m);
     }
 
-    fn index_err<T>(mid: Mid) -> Result<T, String> {
-        Err(format!("Changed order for m-line with mid: {mid}"))
+    fn index_err<T>(mid: Mid) -> Result<T, SdpError> {
+        Err(SdpError::ChangedOrder(mid))
     }
 
     Ok(new_lines)
@@ -933,13 +1141,14 @@ fn add_new_lines(
     session: &mut Session,
     new_lines: &[&MediaLine],
     is_offer: bool,
-) -> Result<(), String> {
+) -> Result<(), SdpError> {
     for m in new_lines {
         let idx = session.line_count();
 
         if m.typ.is_media() {
             let mut media = Media::from_remote_media_line(m, idx, is_offer);
             media.need_open_event = is_offer;
+            media.set_cname(session.cname.clone());
 
             // Match/remap remote params.
             session
@@ -947,7 +1156,7 @@ fn add_new_lines(
                 .update_params(&m.rtp_params(), m.direction());
 
             // Remap the extension to that of the answer.
-            session.exts.remap(&m.extmaps());
+            session.remap_exts(m.mid(), &m.extmaps());
 
             update_media(
                 &mut media,
@@ -957,20 +1166,35 @@ fn add_new_lines(
                 &mut session.streams,
             );
 
+            update_rtcp_mux(&mut media, m, session.rtcp_mux_only);
+
+            if m.disabled {
+                media.set_rejected(true);
+                media.set_direction(Direction::Inactive);
+            }
+
             session.add_media(media);
         } else if m.typ.is_channel() {
             session.set_app(m.mid(), idx)?;
         } else {
-            return Err(format!(
-                "New m-line is neither media nor channel: {}",
-                m.mid()
-            ));
+            return Err(SdpError::UnsupportedMediaType(m.mid()));
         }
     }
 
     Ok(())
 }
 
+/// Reject m-lines without a=rtcp-mux when we require it.
+fn update_rtcp_mux(media: &mut Media, m: &MediaLine, rtcp_mux_only: bool) {
+    if !rtcp_mux_only || m.rtcp_mux() || media.rejected() {
+        return;
+    }
+
+    info!("Reject m-line without rtcp-mux: {}", media.mid());
+    media.set_direction(Direction::Inactive);
+    media.set_rejected(true);
+}
+
 /// Update session level properties like
 /// Extensions from offer or answer.
 fn update_session(session: &mut Session, sdp: &Sdp) {
@@ -992,6 +1216,18 @@ fn update_session(session: &mut Session, sdp: &Sdp) {
     if has_transport_cc && has_twcc_header {
         session.enable_twcc_feedback();
     }
+
+    // RTCP and ECN feedback are bundled for all m-lines, which all must support them.
+    let media_lines: Vec<_> = sdp
+        .media_lines
+        .iter()
+        .filter(|m| m.typ.is_media() && !m.disabled)
+        .collect();
+    let all =
+        |f: fn(&MediaLine) -> bool| !media_lines.is_empty() && media_lines.iter().all(|m| f(m));
+
+    session.remote_rtcp_rsize = all(MediaLine::rtcp_rsize);
+    session.remote_ecn = all(MediaLine::ecn_capable_rtp);
 }
 
 /// Returns all media/channels as `AsMediaLine` trait.
@@ -1067,6 +1303,12 @@ fn update_media(
     }
     media.set_remote_extmap(remote_extmap);
 
+    media.set_remote_bitrate(m.bandwidth_as());
+
+    // With rtcp-mux, any a=rtcp is only a placeholder.
+    let rtcp_port = m.rtcp_addr().filter(|_| !m.rtcp_mux()).map(|a| a.port);
+    media.set_remote_rtcp_port(rtcp_port);
+
     if new_dir.is_receiving() {
         // SSRC changes
         // This will always be for ReceiverSource since any incoming a=ssrc line will be
@@ -1090,6 +1332,11 @@ fn update_media(
 
                 // If remote communicated a main a=ssrc, but no RTX, we will not send nacks.
                 let suppress_nack = repair_ssrc.is_none();
+
+                if streams.reject_stream_rx(i.ssrc, media.mid(), None) {
+                    continue;
+                }
+
                 streams.expect_stream_rx(i.ssrc, repair_ssrc, media.mid(), None, suppress_nack);
             }
         }
@@ -1200,6 +1447,8 @@ impl AsSdpMediaLine for Media {
         let effective_params = params.iter().filter(|p| self.remote_pts().contains(&p.pt));
 
         let mut pts = vec![];
+        let mut p_time = None;
+        let mut max_p_time = None;
 
         for p in effective_params {
             p.as_media_attrs(&mut attrs);
@@ -1209,6 +1458,17 @@ impl AsSdpMediaLine for Media {
             if let Some(rtx) = p.resend() {
                 pts.push(rtx);
             }
+
+            // ptime/maxptime are per m-line, the first configured value wins.
+            p_time = p_time.or(p.spec.format.p_time());
+            max_p_time = max_p_time.or(p.spec.format.max_p_time());
+        }
+
+        if let Some(v) = p_time {
+            attrs.push(MediaAttribute::PTime(v));
+        }
+        if let Some(v) = max_p_time {
+            attrs.push(MediaAttribute::MaxPTime(v));
         }
 
         if let Some(s) = self.simulcast() {
@@ -1255,23 +1515,20 @@ impl AsSdpMediaLine for Media {
             }
         }
 
-        let count = ssrcs_tx.len();
-        #[allow(clippy::comparison_chain)]
-        if count == 1 {
-            let (ssrc, ssrc_rtx) = &ssrcs_tx[0];
+        // One FID group per main SSRC with a repair SSRC. Simulcast layers are
+        // communicated via a=simulcast/a=rid, so we don't write a=ssrc-group:SIM.
+        for (ssrc, ssrc_rtx) in ssrcs_tx {
             if let Some(ssrc_rtx) = ssrc_rtx {
                 attrs.push(MediaAttribute::SsrcGroup {
                     semantics: "FID".to_string(),
                     ssrcs: vec![*ssrc, *ssrc_rtx],
                 });
             }
-        } else {
-            // TODO: handle simulcast
         }
 
         MediaLine {
             typ: self.kind().into(),
-            disabled: false,
+            disabled: self.rejected(),
             proto: Proto::Srtp,
             pts,
             bw: None,
@@ -1291,39 +1548,43 @@ impl From<MediaKind> for MediaType {
 
 struct AsSdpParams<'a, 'b> {
     pub candidates: Vec<Candidate>,
+    pub end_of_candidates: bool,
     pub creds: IceCreds,
     pub fingerprint: &'a Fingerprint,
     pub setup: Setup,
+    pub ice_lite: bool,
     pub pending: Option<&'b Changes>,
 }
 
 impl<'a, 'b> AsSdpParams<'a, 'b> {
     pub fn new(rtc: &'a Rtc, pending: Option<&'b Changes>) -> Self {
-        let (creds, candidates) = if let Some((new_creds, keep_local_candidates)) =
-            pending.and_then(|p| p.ice_restart())
-        {
-            if keep_local_candidates {
-                // If we are performing an ICE restart and we are keeping the same
-                // candidates we need to use ufrag from the new ICE credentials
-                // in our offer.
-                let mut new_candidates = rtc.ice.local_candidates().to_vec();
-                for c in &mut new_candidates {
-                    c.set_ufrag(&new_creds.ufrag);
-                }
+        let (creds, candidates, end_of_candidates) =
+            if let Some((new_creds, keep_local_candidates)) = pending.and_then(|p| p.ice_restart())
+            {
+                if keep_local_candidates {
+                    // If we are performing an ICE restart and we are keeping the same
+                    // candidates we need to use ufrag from the new ICE credentials
+                    // in our offer.
+                    let mut new_candidates = rtc.ice.local_candidates().to_vec();
+                    for c in &mut new_candidates {
+                        c.set_ufrag(&new_creds.ufrag);
+                    }
 
-                (new_creds, new_candidates)
+                    (new_creds, new_candidates, rtc.ice.local_end_of_candidates())
+                } else {
+                    (new_creds, vec![], false)
+                }
             } else {
-                (new_creds, vec![])
-            }
-        } else {
-            (
-                rtc.ice.local_credentials().clone(),
-                rtc.ice.local_candidates().to_vec(),
-            )
-        };
+                (
+                    rtc.ice.local_credentials().clone(),
+                    rtc.ice.local_candidates().to_vec(),
+                    rtc.ice.local_end_of_candidates(),
+                )
+            };
 
         AsSdpParams {
             candidates,
+            end_of_candidates,
             creds,
             fingerprint: rtc.dtls.local_fingerprint(),
             setup: match rtc.dtls.is_active() {
@@ -1331,6 +1592,7 @@ impl<'a, 'b> AsSdpParams<'a, 'b> {
                 Some(false) => Setup::Passive,
                 None => Setup::ActPass,
             },
+            ice_lite: rtc.ice.ice_lite(),
             pending,
         }
     }
@@ -1347,6 +1609,10 @@ impl<'a, 'b> AsSdpParams<'a, 'b> {
             vec![]
         };
 
+        if include_candidates && self.end_of_candidates {
+            v.push(EndOfCandidates);
+        }
+
         v.push(IceUfrag(self.creds.ufrag.clone()));
         v.push(IcePwd(self.creds.pass.clone()));
         v.push(IceOptions("trickle".into()));
@@ -1392,13 +1658,12 @@ impl Changes {
     }
 
     /// Tests the given lines (from answer) corresponds to changes.
-    fn ensure_correct_answer(&self, lines: &[&MediaLine]) -> Option<String> {
+    fn ensure_correct_answer(&self, lines: &[&MediaLine]) -> Option<SdpError> {
         if self.count_new_medias() != lines.len() {
-            return Some(format!(
-                "Differing m-line count in offer vs answer: {} != {}",
-                self.count_new_medias(),
-                lines.len()
-            ));
+            return Some(SdpError::AnswerLineCount {
+                offer: self.count_new_medias(),
+                answer: lines.len(),
+            });
         }
 
         'next: for l in lines {
@@ -1409,19 +1674,13 @@ impl Changes {
                 match m {
                     AddMedia(v) if v.mid == mid => {
                         if !l.typ.is_media() {
-                            return Some(format!(
-                                "Answer m-line for mid ({}) is not of media type: {:?}",
-                                mid, l.typ
-                            ));
+                            return Some(SdpError::AnswerTypeMismatch(mid));
                         }
                         continue 'next;
                     }
                     AddApp(v) if *v == mid => {
                         if !l.typ.is_channel() {
-                            return Some(format!(
-                                "Answer m-line for mid ({}) is not a data channel: {:?}",
-                                mid, l.typ
-                            ));
+                            return Some(SdpError::AnswerTypeMismatch(mid));
                         }
                         continue 'next;
                     }
@@ -1429,7 +1688,7 @@ impl Changes {
                 }
             }
 
-            return Some(format!("Mid in answer is not in offer: {mid}"));
+            return Some(SdpError::AnswerUnknownMid(mid));
         }
 
         None
@@ -1456,11 +1715,18 @@ impl Changes {
 
     pub(crate) fn apply_to(&self, lines: &mut [MediaLine]) {
         for change in &self.0 {
-            if let Change::Direction(mid, dir) = change {
-                if let Some(line) = lines.iter_mut().find(|l| l.mid() == *mid) {
-                    if let Some(dir_pos) = line.attrs.iter().position(|a| a.is_direction()) {
-                        line.attrs[dir_pos] = (*dir).into();
-                    }
+            let (mid, dir, disable) = match change {
+                Change::Direction(mid, dir) => (mid, *dir, false),
+                Change::RemoveMedia(mid) => (mid, Direction::Inactive, true),
+                _ => continue,
+            };
+
+            if let Some(line) = lines.iter_mut().find(|l| l.mid() == *mid) {
+                if let Some(dir_pos) = line.attrs.iter().position(|a| a.is_direction()) {
+                    line.attrs[dir_pos] = dir.into();
+                }
+                if disable {
+                    line.disabled = true;
                 }
             }
         }
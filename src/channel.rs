@@ -7,7 +7,9 @@ use crate::util::already_happened;
 use crate::{Rtc, RtcError};
 
 pub use crate::sctp::ChannelConfig;
+pub use crate::sctp::DeliveryProgress;
 pub use crate::sctp::Reliability;
+pub use crate::sctp::ReliabilityStats;
 
 /// Identifier of a data channel.
 ///
@@ -54,6 +56,56 @@ impl<'a> Channel<'a> {
     pub fn write(&mut self, binary: bool, buf: &[u8]) -> Result<usize, RtcError> {
         Ok(self.rtc.sctp.write(self.sctp_stream_id, binary, buf)?)
     }
+
+    /// How much of the data written to this channel the remote peer has acknowledged.
+    ///
+    /// This follows the SCTP acks (SACK) from the remote peer, and is meant for reliable
+    /// channels where an application wants to observe delivery of its own protocol on top.
+    /// It only reads the association state and does not change what is sent.
+    ///
+    /// For partially reliable channels ([`Reliability::MaxRetransmits`] or
+    /// [`Reliability::MaxPacketLifetime`]), messages the SCTP stack abandons are
+    /// counted as `acked` once the remote peer has confirmed skipping them. See
+    /// [`Channel::reliability_stats()`] for how many messages were abandoned.
+    ///
+    /// Returns `None` if the channel is not open.
+    ///
+    /// ```no_run
+    /// # use str0m::{Rtc, channel::ChannelId};
+    /// # let mut rtc = Rtc::new();
+    /// # let id: ChannelId = todo!();
+    /// let mut channel = rtc.channel(id).unwrap();
+    /// channel.write(true, &[1, 2, 3]).unwrap();
+    ///
+    /// let progress = channel.delivery_progress().unwrap();
+    /// let in_flight = progress.written - progress.acked;
+    /// ```
+    pub fn delivery_progress(&mut self) -> Option<DeliveryProgress> {
+        self.rtc.sctp.delivery_progress(self.sctp_stream_id)
+    }
+
+    /// How many messages written to this channel were delivered or abandoned.
+    ///
+    /// For partially reliable channels ([`Reliability::MaxRetransmits`] or
+    /// [`Reliability::MaxPacketLifetime`]), this tells how lossy the channel effectively
+    /// is. A message is abandoned when the SCTP stack gives up on it before the remote
+    /// peer acknowledged it, which means it might still have arrived. Messages that are
+    /// queued or in flight are in neither count.
+    ///
+    /// Returns `None` if the channel is not open.
+    ///
+    /// ```no_run
+    /// # use str0m::{Rtc, channel::ChannelId};
+    /// # let mut rtc = Rtc::new();
+    /// # let id: ChannelId = todo!();
+    /// let channel = rtc.channel(id).unwrap();
+    ///
+    /// let stats = channel.reliability_stats().unwrap();
+    /// let total = stats.delivered + stats.abandoned;
+    /// ```
+    pub fn reliability_stats(&self) -> Option<ReliabilityStats> {
+        self.rtc.sctp.reliability_stats(self.sctp_stream_id)
+    }
 }
 
 impl fmt::Debug for ChannelData {
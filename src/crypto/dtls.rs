@@ -32,6 +32,57 @@ pub enum DtlsEvent {
     Data(Vec<u8>),
 }
 
+/// Cipher suites for the DTLS handshake.
+///
+/// Only the ECDHE suites with authenticated encryption are supported. str0m's own
+/// certificates are RSA, the ECDSA suites can only be used when the remote peer is
+/// the DTLS server and has an ECDSA certificate (as browsers do).
+///
+/// Set using [`RtcConfig::set_dtls_cipher_suites()`][crate::RtcConfig::set_dtls_cipher_suites].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DtlsCipherSuite {
+    /// `TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256`
+    EcdheEcdsaAes128GcmSha256,
+    /// `TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384`
+    EcdheEcdsaAes256GcmSha384,
+    /// `TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256`
+    EcdheEcdsaChacha20Poly1305,
+    /// `TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256`
+    EcdheRsaAes128GcmSha256,
+    /// `TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384`
+    EcdheRsaAes256GcmSha384,
+    /// `TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256`
+    EcdheRsaChacha20Poly1305,
+}
+
+impl DtlsCipherSuite {
+    /// All the supported cipher suites.
+    pub const ALL: &'static [DtlsCipherSuite] = &[
+        DtlsCipherSuite::EcdheEcdsaAes128GcmSha256,
+        DtlsCipherSuite::EcdheEcdsaAes256GcmSha384,
+        DtlsCipherSuite::EcdheEcdsaChacha20Poly1305,
+        DtlsCipherSuite::EcdheRsaAes128GcmSha256,
+        DtlsCipherSuite::EcdheRsaAes256GcmSha384,
+        DtlsCipherSuite::EcdheRsaChacha20Poly1305,
+    ];
+}
+
+impl fmt::Display for DtlsCipherSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DtlsCipherSuite::*;
+        let name = match self {
+            EcdheEcdsaAes128GcmSha256 => "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+            EcdheEcdsaAes256GcmSha384 => "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+            EcdheEcdsaChacha20Poly1305 => "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+            EcdheRsaAes128GcmSha256 => "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+            EcdheRsaAes256GcmSha384 => "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+            EcdheRsaChacha20Poly1305 => "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// Certificate used for DTLS.
 #[derive(Clone)]
 pub struct DtlsCert(DtlsCertInner);
@@ -61,11 +112,15 @@ impl DtlsCert {
         }
     }
 
-    pub(crate) fn create_dtls_impl(&self) -> Result<DtlsImpl, CryptoError> {
+    pub(crate) fn create_dtls_impl(
+        &self,
+        cipher_suites: Option<&[DtlsCipherSuite]>,
+    ) -> Result<DtlsImpl, CryptoError> {
         match &self.0 {
             #[cfg(feature = "openssl")]
             DtlsCertInner::OpenSsl(c) => Ok(DtlsImpl::OpenSsl(super::ossl::OsslDtlsImpl::new(
                 c.clone(),
+                cipher_suites,
             )?)),
             _ => unreachable!(),
         }
@@ -89,6 +144,11 @@ pub trait DtlsInner: Sized {
     /// exactly once before starting to handshake (I/O).
     fn set_active(&mut self, active: bool);
 
+    /// Set the MTU the handshake messages are fragmented to fit.
+    ///
+    /// This must be called before starting to handshake.
+    fn set_mtu(&mut self, mtu: usize) -> Result<(), CryptoError>;
+
     /// Handle the handshake. Once this succeeds, it becomes a no-op.
     fn handle_handshake(&mut self, o: &mut VecDeque<DtlsEvent>) -> Result<bool, CryptoError>;
 
@@ -106,6 +166,9 @@ pub trait DtlsInner: Sized {
 
     /// Whether the DTLS connection is established.
     fn is_connected(&self) -> bool;
+
+    /// The cipher suite negotiated in the handshake.
+    fn cipher_suite(&self) -> Option<DtlsCipherSuite>;
 }
 
 pub enum DtlsImpl {
@@ -122,6 +185,14 @@ impl DtlsImpl {
         }
     }
 
+    pub fn set_mtu(&mut self, mtu: usize) -> Result<(), CryptoError> {
+        match self {
+            #[cfg(feature = "openssl")]
+            DtlsImpl::OpenSsl(i) => i.set_mtu(mtu),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn handle_handshake(&mut self, o: &mut VecDeque<DtlsEvent>) -> Result<bool, CryptoError> {
         match self {
             #[cfg(feature = "openssl")]
@@ -173,4 +244,12 @@ impl DtlsImpl {
             _ => unreachable!(),
         }
     }
+
+    pub fn cipher_suite(&self) -> Option<DtlsCipherSuite> {
+        match self {
+            #[cfg(feature = "openssl")]
+            DtlsImpl::OpenSsl(i) => i.cipher_suite(),
+            _ => unreachable!(),
+        }
+    }
 }
@@ -7,7 +7,7 @@ use thiserror::Error;
 mod ossl;
 
 mod dtls;
-pub use dtls::{DtlsCert, DtlsEvent, DtlsImpl};
+pub use dtls::{DtlsCert, DtlsCipherSuite, DtlsEvent, DtlsImpl};
 
 mod finger;
 pub use finger::Fingerprint;
@@ -21,17 +21,33 @@ pub use srtp::{new_aes_128_cm_sha1_80, srtp_aes_128_ecb_round, SrtpProfile};
 
 /// SHA1 HMAC as used for STUN and older SRTP.
 pub fn sha1_hmac(key: &[u8], payloads: &[&[u8]]) -> [u8; 20] {
-    use hmac::Hmac;
-    use hmac::Mac;
-    use sha1::Sha1;
+    Sha1HmacKey::new(key).sign(payloads)
+}
 
-    let mut hmac = Hmac::<Sha1>::new_from_slice(key).expect("hmac to normalize size to 20");
+/// SHA1 HMAC with the key already set up.
+///
+/// Setting up the key means hashing the inner and outer pads, which is more work than
+/// signing a small packet. For SRTP the key is fixed, so we do it once per context.
+#[derive(Clone)]
+pub struct Sha1HmacKey(hmac::Hmac<sha1::Sha1>);
 
-    for payload in payloads {
-        hmac.update(payload);
+impl Sha1HmacKey {
+    pub fn new(key: &[u8]) -> Self {
+        use hmac::Mac;
+        Sha1HmacKey(hmac::Hmac::new_from_slice(key).expect("hmac to normalize size to 20"))
     }
 
-    hmac.finalize().into_bytes().into()
+    pub fn sign(&self, payloads: &[&[u8]]) -> [u8; 20] {
+        use hmac::Mac;
+
+        let mut hmac = self.0.clone();
+
+        for payload in payloads {
+            hmac.update(payload);
+        }
+
+        hmac.finalize().into_bytes().into()
+    }
 }
 
 /// Errors that can arise in DTLS.
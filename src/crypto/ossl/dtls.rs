@@ -6,7 +6,7 @@ use openssl::nid::Nid;
 use openssl::ssl::{Ssl, SslContext, SslContextBuilder, SslMethod, SslOptions, SslVerifyMode};
 
 use crate::crypto::dtls::DtlsInner;
-use crate::crypto::{DtlsEvent, SrtpProfile};
+use crate::crypto::{DtlsCipherSuite, DtlsEvent, SrtpProfile};
 use crate::io::{DATAGRAM_MTU, DATAGRAM_MTU_WARN};
 
 use super::cert::OsslDtlsCert;
@@ -32,8 +32,11 @@ pub struct OsslDtlsImpl {
 }
 
 impl OsslDtlsImpl {
-    pub fn new(cert: OsslDtlsCert) -> Result<Self, super::CryptoError> {
-        let context = dtls_create_ctx(&cert)?;
+    pub fn new(
+        cert: OsslDtlsCert,
+        cipher_suites: Option<&[DtlsCipherSuite]>,
+    ) -> Result<Self, super::CryptoError> {
+        let context = dtls_create_ctx(&cert, cipher_suites)?;
         let ssl = dtls_ssl_create(&context)?;
         Ok(OsslDtlsImpl {
             _cert: cert,
@@ -48,6 +51,11 @@ impl DtlsInner for OsslDtlsImpl {
         self.tls.set_active(active);
     }
 
+    fn set_mtu(&mut self, mtu: usize) -> Result<(), CryptoError> {
+        self.tls.inner_mut().mtu = mtu;
+        self.tls.set_mtu(mtu)
+    }
+
     fn is_active(&self) -> Option<bool> {
         self.tls.is_active()
     }
@@ -94,6 +102,11 @@ impl DtlsInner for OsslDtlsImpl {
         self.tls.is_connected()
     }
 
+    fn cipher_suite(&self) -> Option<DtlsCipherSuite> {
+        let name = self.tls.cipher_name()?;
+        DtlsCipherSuite::from_openssl_name(name)
+    }
+
     fn handle_handshake(&mut self, output: &mut VecDeque<DtlsEvent>) -> Result<bool, CryptoError> {
         if self.tls.is_handshaken() {
             // Nice. Nothing to do.
@@ -116,14 +129,27 @@ impl DtlsInner for OsslDtlsImpl {
     }
 }
 
-pub fn dtls_create_ctx(cert: &OsslDtlsCert) -> Result<SslContext, CryptoError> {
+pub fn dtls_create_ctx(
+    cert: &OsslDtlsCert,
+    cipher_suites: Option<&[DtlsCipherSuite]>,
+) -> Result<SslContext, CryptoError> {
     // TODO: Technically we want to disallow DTLS < 1.2, but that requires
     // us to use this commented out unsafe. We depend on browsers disallowing
     // it instead.
     // let method = unsafe { SslMethod::from_ptr(DTLSv1_2_method()) };
     let mut ctx = SslContextBuilder::new(SslMethod::dtls())?;
 
-    ctx.set_cipher_list(DTLS_CIPHERS)?;
+    if let Some(cipher_suites) = cipher_suites {
+        let all: Vec<_> = cipher_suites
+            .iter()
+            .map(DtlsCipherSuite::openssl_name)
+            .collect();
+
+        ctx.set_cipher_list(&all.join(":"))?;
+    } else {
+        ctx.set_cipher_list(DTLS_CIPHERS)?;
+    }
+
     let srtp_profiles = {
         // Rust can't join directly to a string, need to allocate a vec first :(
         // This happens very rarely so the extra allocations don't matter
@@ -147,6 +173,13 @@ pub fn dtls_create_ctx(cert: &OsslDtlsCert) -> Result<SslContext, CryptoError> {
     let mut options = SslOptions::empty();
     options.insert(SslOptions::SINGLE_ECDH_USE);
     options.insert(SslOptions::NO_DTLSV1);
+    // Keep the MTU we set, instead of querying the (non-existent) socket when
+    // the handshake starts.
+    options.insert(SslOptions::NO_QUERY_MTU);
+    if cipher_suites.is_some() {
+        // Configured suites are in order of preference, also when we are the server.
+        options.insert(SslOptions::CIPHER_SERVER_PREFERENCE);
+    }
     ctx.set_options(options);
 
     let ctx = ctx.build();
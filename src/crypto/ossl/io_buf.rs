@@ -1,12 +1,26 @@
 use std::collections::VecDeque;
 use std::io;
 
+use crate::io::DATAGRAM_MTU;
 use crate::net::DatagramSend;
 
-#[derive(Default)]
+/// DTLS record header: content type, version, epoch, sequence number and length.
+const RECORD_HEADER_LEN: usize = 13;
+
 pub struct IoBuffer {
     pub incoming: Vec<u8>,
     pub outgoing: VecDeque<DatagramSend>,
+    pub mtu: usize,
+}
+
+impl Default for IoBuffer {
+    fn default() -> Self {
+        Self {
+            incoming: Vec::new(),
+            outgoing: VecDeque::new(),
+            mtu: DATAGRAM_MTU,
+        }
+    }
 }
 
 impl IoBuffer {
@@ -42,9 +56,32 @@ impl io::Read for IoBuffer {
 
 impl io::Write for IoBuffer {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let dsend = buf.to_vec().into();
+        // OpenSSL fragments handshake messages to the MTU, but can still put more
+        // records in one write than fits, i.e. ChangeCipherSpec and Finished at the
+        // end of a flight. Records are independent, so we split between them.
+        let mut start = 0;
+        let mut end = 0;
 
-        self.outgoing.push_back(dsend);
+        while end < buf.len() {
+            let rest = &buf[end..];
+            let len = if rest.len() >= RECORD_HEADER_LEN {
+                let body = u16::from_be_bytes([rest[11], rest[12]]) as usize;
+                (RECORD_HEADER_LEN + body).min(rest.len())
+            } else {
+                rest.len()
+            };
+
+            if end > start && end + len - start > self.mtu {
+                self.outgoing.push_back(buf[start..end].to_vec().into());
+                start = end;
+            }
+
+            end += len;
+        }
+
+        if end > start {
+            self.outgoing.push_back(buf[start..end].to_vec().into());
+        }
 
         Ok(buf.len())
     }
@@ -1,6 +1,6 @@
 //! OpenSSL implementation of cryptographic functions.
 
-use super::{CryptoError, SrtpProfile};
+use super::{CryptoError, DtlsCipherSuite, SrtpProfile};
 
 mod cert;
 pub use cert::OsslDtlsCert;
@@ -21,7 +21,29 @@ impl SrtpProfile {
             #[cfg(feature = "_internal_test_exports")]
             SrtpProfile::PassThrough => "NULL",
             SrtpProfile::Aes128CmSha1_80 => "SRTP_AES128_CM_SHA1_80",
+            SrtpProfile::Aes128CmSha1_32 => "SRTP_AES128_CM_SHA1_32",
             SrtpProfile::AeadAes128Gcm => "SRTP_AEAD_AES_128_GCM",
         }
     }
 }
+
+impl DtlsCipherSuite {
+    /// What this cipher suite is called in OpenSSL parlance.
+    pub(crate) fn openssl_name(&self) -> &'static str {
+        match self {
+            DtlsCipherSuite::EcdheEcdsaAes128GcmSha256 => "ECDHE-ECDSA-AES128-GCM-SHA256",
+            DtlsCipherSuite::EcdheEcdsaAes256GcmSha384 => "ECDHE-ECDSA-AES256-GCM-SHA384",
+            DtlsCipherSuite::EcdheEcdsaChacha20Poly1305 => "ECDHE-ECDSA-CHACHA20-POLY1305",
+            DtlsCipherSuite::EcdheRsaAes128GcmSha256 => "ECDHE-RSA-AES128-GCM-SHA256",
+            DtlsCipherSuite::EcdheRsaAes256GcmSha384 => "ECDHE-RSA-AES256-GCM-SHA384",
+            DtlsCipherSuite::EcdheRsaChacha20Poly1305 => "ECDHE-RSA-CHACHA20-POLY1305",
+        }
+    }
+
+    pub(crate) fn from_openssl_name(name: &str) -> Option<Self> {
+        DtlsCipherSuite::ALL
+            .iter()
+            .find(|s| s.openssl_name() == name)
+            .copied()
+    }
+}
@@ -59,6 +59,14 @@ where
         self.active = Some(active);
     }
 
+    pub fn set_mtu(&mut self, mtu: usize) -> Result<(), CryptoError> {
+        let State::Init(ssl, _) = &mut self.state else {
+            panic!("set_mtu after handshake started");
+        };
+        ssl.set_mtu(mtu as u32)?;
+        Ok(())
+    }
+
     pub fn complete_handshake_until_block(&mut self) -> Result<bool, CryptoError> {
         if let Err(e) = self.handshaken() {
             if e.kind() == io::ErrorKind::WouldBlock {
@@ -95,6 +103,13 @@ where
         self.keying_mat.take()
     }
 
+    pub fn cipher_name(&self) -> Option<&'static str> {
+        let State::Established(v) = &self.state else {
+            return None;
+        };
+        v.ssl().current_cipher().map(|c| c.name())
+    }
+
     pub fn inner_mut(&mut self) -> &mut S {
         match &mut self.state {
             State::Init(_, s) => s,
@@ -217,6 +232,7 @@ impl TryFrom<SrtpProfileId> for SrtpProfile {
     fn try_from(value: SrtpProfileId) -> Result<Self, Self::Error> {
         match value {
             SrtpProfileId::SRTP_AES128_CM_SHA1_80 => Ok(SrtpProfile::Aes128CmSha1_80),
+            SrtpProfileId::SRTP_AES128_CM_SHA1_32 => Ok(SrtpProfile::Aes128CmSha1_32),
             SrtpProfileId::SRTP_AEAD_AES_128_GCM => Ok(SrtpProfile::AeadAes128Gcm),
             x => Err(io::Error::new(
                 io::ErrorKind::Other,
@@ -3,19 +3,37 @@ use std::fmt;
 use self::aead_aes_128_gcm::AeadKey;
 use self::aes_128_cm_sha1_80::AesKey;
 
+/// SRTP protection profile negotiated via the DTLS `use_srtp` extension.
+///
+/// See [`Rtc::srtp_profile()`][crate::Rtc::srtp_profile].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SrtpProfile {
     #[cfg(feature = "_internal_test_exports")]
+    #[doc(hidden)]
     PassThrough,
+    /// `SRTP_AES128_CM_HMAC_SHA1_80`, AES counter mode with an 80 bit HMAC-SHA1 tag.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc5764#section-4.1.2>
     Aes128CmSha1_80,
+    /// `SRTP_AES128_CM_HMAC_SHA1_32`, AES counter mode with a 32 bit HMAC-SHA1 tag for RTP.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc5764#section-4.1.2>
+    Aes128CmSha1_32,
+    /// `SRTP_AEAD_AES_128_GCM`, AES-GCM authenticated encryption.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc7714#section-14.2>
     AeadAes128Gcm,
 }
 
 #[allow(dead_code)]
 impl SrtpProfile {
     // All the profiles we support, ordered from most preferred to least.
-    pub(crate) const ALL: &'static [SrtpProfile] =
-        &[SrtpProfile::AeadAes128Gcm, SrtpProfile::Aes128CmSha1_80];
+    pub(crate) const ALL: &'static [SrtpProfile] = &[
+        SrtpProfile::AeadAes128Gcm,
+        SrtpProfile::Aes128CmSha1_80,
+        SrtpProfile::Aes128CmSha1_32,
+    ];
 
     /// The length of keying material to extract from the DTLS session in bytes.
     #[rustfmt::skip]
@@ -27,6 +45,7 @@ impl SrtpProfile {
              // TODO: This is a duplication of info that is held in srtp.rs, because we
              // don't want a dependency in that direction.
             SrtpProfile::Aes128CmSha1_80 => 16 * 2 + 14 * 2,
+            SrtpProfile::Aes128CmSha1_32 => 16 * 2 + 14 * 2,
             SrtpProfile::AeadAes128Gcm   => 16 * 2 + 12 * 2,
         }
     }
@@ -106,12 +125,14 @@ pub trait SrtpCryptoImpl {
 pub mod aes_128_cm_sha1_80 {
     use std::panic::UnwindSafe;
 
-    use crate::crypto::CryptoError;
+    use crate::crypto::{CryptoError, Sha1HmacKey};
 
     pub const KEY_LEN: usize = 16;
     pub const SALT_LEN: usize = 14;
     pub const HMAC_KEY_LEN: usize = 20;
     pub const HMAC_TAG_LEN: usize = 10;
+    /// SRTP tag of the AES_CM_128_HMAC_SHA1_32 profile. SRTCP always uses [`HMAC_TAG_LEN`].
+    pub const HMAC_TAG_LEN_32: usize = 4;
     pub type AesKey = [u8; 16];
     pub type RtpSalt = [u8; 14];
     pub type RtpIv = [u8; 16];
@@ -136,16 +157,19 @@ pub mod aes_128_cm_sha1_80 {
         ) -> Result<(), CryptoError>;
     }
 
-    pub fn rtp_hmac(key: &[u8], buf: &mut [u8], srtp_index: u64, hmac_start: usize) {
+    /// Writes the tag truncated to the space after `hmac_start`.
+    pub fn rtp_hmac(key: &Sha1HmacKey, buf: &mut [u8], srtp_index: u64, hmac_start: usize) {
         let roc = (srtp_index >> 16) as u32;
-        let tag = crate::crypto::sha1_hmac(key, &[&buf[..hmac_start], &roc.to_be_bytes()]);
-        buf[hmac_start..(hmac_start + HMAC_TAG_LEN)].copy_from_slice(&tag[0..HMAC_TAG_LEN]);
+        let tag = key.sign(&[&buf[..hmac_start], &roc.to_be_bytes()]);
+        let tag_len = buf.len() - hmac_start;
+        buf[hmac_start..].copy_from_slice(&tag[0..tag_len]);
     }
 
-    pub fn rtp_verify(key: &[u8], buf: &[u8], srtp_index: u64, cmp: &[u8]) -> bool {
+    /// Verifies the tag truncated to the length of `cmp`.
+    pub fn rtp_verify(key: &Sha1HmacKey, buf: &[u8], srtp_index: u64, cmp: &[u8]) -> bool {
         let roc = (srtp_index >> 16) as u32;
-        let tag = crate::crypto::sha1_hmac(key, &[buf, &roc.to_be_bytes()]);
-        &tag[0..HMAC_TAG_LEN] == cmp
+        let tag = key.sign(&[buf, &roc.to_be_bytes()]);
+        &tag[0..cmp.len()] == cmp
     }
 
     pub fn rtp_iv(salt: RtpSalt, ssrc: u32, srtp_index: u64) -> RtpIv {
@@ -162,14 +186,14 @@ pub mod aes_128_cm_sha1_80 {
         iv
     }
 
-    pub fn rtcp_hmac(key: &[u8], buf: &mut [u8], hmac_index: usize) {
-        let tag = crate::crypto::sha1_hmac(key, &[&buf[0..hmac_index]]);
+    pub fn rtcp_hmac(key: &Sha1HmacKey, buf: &mut [u8], hmac_index: usize) {
+        let tag = key.sign(&[&buf[0..hmac_index]]);
 
         buf[hmac_index..(hmac_index + HMAC_TAG_LEN)].copy_from_slice(&tag[0..HMAC_TAG_LEN]);
     }
 
-    pub fn rtcp_verify(key: &[u8], buf: &[u8], cmp: &[u8]) -> bool {
-        let tag = crate::crypto::sha1_hmac(key, &[buf]);
+    pub fn rtcp_verify(key: &Sha1HmacKey, buf: &[u8], cmp: &[u8]) -> bool {
+        let tag = key.sign(&[buf]);
 
         &tag[0..HMAC_TAG_LEN] == cmp
     }
@@ -258,6 +282,7 @@ impl fmt::Display for SrtpProfile {
             #[cfg(feature = "_internal_test_exports")]
             SrtpProfile::PassThrough => write!(f, "PassThrough"),
             SrtpProfile::Aes128CmSha1_80 => write!(f, "SRTP_AES128_CM_SHA1_80"),
+            SrtpProfile::Aes128CmSha1_32 => write!(f, "SRTP_AES128_CM_SHA1_32"),
             SrtpProfile::AeadAes128Gcm => write!(f, "SRTP_AEAD_AES_128_GCM"),
         }
     }
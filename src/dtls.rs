@@ -1,8 +1,9 @@
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 use std::{fmt, io};
 use thiserror::Error;
 
-use crate::crypto::{CryptoError, DtlsImpl, Fingerprint};
+use crate::crypto::{CryptoError, DtlsCipherSuite, DtlsImpl, Fingerprint};
 
 pub use crate::crypto::{DtlsCert, DtlsEvent};
 use crate::net::DatagramSend;
@@ -41,6 +42,33 @@ impl From<CryptoError> for DtlsError {
     }
 }
 
+/// Default initial timeout before retransmitting a handshake flight (RFC 6347 4.2.4.1).
+pub(crate) const DEFAULT_DTLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Default number of handshake flight retransmits before giving up.
+pub(crate) const DEFAULT_DTLS_MAX_RETRANSMITS: usize = 6;
+
+/// The backoff doubles the timeout up to this max (RFC 6347 4.2.4.1).
+const MAX_DTLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// State of the DTLS handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtlsState {
+    /// The handshake has not started.
+    ///
+    /// DTLS is started via SDP negotiation or [`DirectApi::start_dtls()`][crate::change::DirectApi].
+    Init,
+
+    /// The handshake is in progress.
+    Handshaking,
+
+    /// The handshake completed.
+    Connected,
+
+    /// The handshake failed after running out of retransmits.
+    TimedOut,
+}
+
 /// Encapsulation of DTLS.
 pub struct Dtls {
     dtls_impl: DtlsImpl,
@@ -53,6 +81,40 @@ pub struct Dtls {
 
     /// Events ready to be polled.
     events: VecDeque<DtlsEvent>,
+
+    /// Initial timeout before retransmitting a handshake flight.
+    handshake_timeout: Duration,
+
+    /// Number of retransmits before the handshake times out.
+    max_retransmits: usize,
+
+    /// The last flight of handshake datagrams sent, kept for retransmits.
+    flight: Vec<Vec<u8>>,
+
+    /// Set when receiving handshake data, the next datagram sent starts a new flight.
+    new_flight: bool,
+
+    /// Number of retransmits of the current flight.
+    retransmits: usize,
+
+    /// When to retransmit the current flight.
+    retransmit_at: Option<Instant>,
+
+    /// Retransmitted datagrams waiting to be polled.
+    resend: VecDeque<Vec<u8>>,
+
+    /// Record sequence number of the next unencrypted (epoch 0) record sent.
+    next_record_seq: u64,
+
+    /// Whether the handshake ran out of retransmits.
+    timed_out: bool,
+
+    /// Whether the kept flight is the one that completed the handshake. It has no timer,
+    /// and is only resent when the remote retransmits its flight (RFC 6347 4.2.4).
+    final_flight: bool,
+
+    /// Whether the timeout is yet to be reported.
+    need_timeout_event: bool,
 }
 
 impl Dtls {
@@ -60,8 +122,13 @@ impl Dtls {
     ///
     /// `active` indicates whether this side should initiate the handshake or not.
     /// This in turn is governed by the `a=setup` SDP attribute.
-    pub fn new(cert: DtlsCert) -> Result<Self, DtlsError> {
-        let dtls_impl = cert.create_dtls_impl()?;
+    ///
+    /// `cipher_suites` are offered in order of preference, `None` uses str0m's defaults.
+    pub fn new(
+        cert: DtlsCert,
+        cipher_suites: Option<&[DtlsCipherSuite]>,
+    ) -> Result<Self, DtlsError> {
+        let dtls_impl = cert.create_dtls_impl(cipher_suites)?;
         let fingerprint = cert.fingerprint();
 
         Ok(Self {
@@ -69,9 +136,31 @@ impl Dtls {
             fingerprint,
             remote_fingerprint: None,
             events: VecDeque::new(),
+            handshake_timeout: DEFAULT_DTLS_HANDSHAKE_TIMEOUT,
+            max_retransmits: DEFAULT_DTLS_MAX_RETRANSMITS,
+            flight: vec![],
+            new_flight: true,
+            retransmits: 0,
+            retransmit_at: None,
+            resend: VecDeque::new(),
+            next_record_seq: 0,
+            timed_out: false,
+            final_flight: false,
+            need_timeout_event: false,
         })
     }
 
+    /// Set the initial handshake retransmit timeout and the max number of retransmits.
+    pub fn set_retransmit(&mut self, handshake_timeout: Duration, max_retransmits: usize) {
+        self.handshake_timeout = handshake_timeout;
+        self.max_retransmits = max_retransmits;
+    }
+
+    /// Set the MTU the handshake messages are fragmented to fit.
+    pub fn set_mtu(&mut self, mtu: usize) -> Result<(), DtlsError> {
+        Ok(self.dtls_impl.set_mtu(mtu)?)
+    }
+
     /// Tells if this instance has been inited.
     ///
     /// Once true, we cannot do `set_active` anymore.
@@ -104,9 +193,106 @@ impl Dtls {
         &self.remote_fingerprint
     }
 
+    /// The cipher suite negotiated in the handshake.
+    pub fn cipher_suite(&self) -> Option<DtlsCipherSuite> {
+        self.dtls_impl.cipher_suite()
+    }
+
+    /// State of the handshake.
+    pub fn state(&self) -> DtlsState {
+        if self.is_connected() {
+            DtlsState::Connected
+        } else if self.timed_out {
+            DtlsState::TimedOut
+        } else if self.is_inited() {
+            DtlsState::Handshaking
+        } else {
+            DtlsState::Init
+        }
+    }
+
     /// Poll for the next datagram to send.
-    pub fn poll_datagram(&mut self) -> Option<DatagramSend> {
-        self.dtls_impl.poll_datagram()
+    pub fn poll_datagram(&mut self, now: Instant) -> Option<DatagramSend> {
+        if let Some(mut v) = self.resend.pop_front() {
+            renumber_records(&mut v, &mut self.next_record_seq);
+            return Some(v.into());
+        }
+
+        let mut x = self.dtls_impl.poll_datagram()?.to_vec();
+        renumber_records(&mut x, &mut self.next_record_seq);
+
+        if !self.timed_out && is_handshake(&x) {
+            // Keep the flight around until the remote answers it.
+            if self.new_flight {
+                self.new_flight = false;
+                self.flight.clear();
+                self.retransmits = 0;
+                self.final_flight = self.is_connected();
+            }
+            self.flight.push(x.clone());
+            if !self.final_flight {
+                self.retransmit_at = Some(now + self.handshake_timeout);
+            }
+        }
+
+        Some(x.into())
+    }
+
+    /// Next time the handshake needs attention, if ever.
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        if self.is_connected() || self.timed_out {
+            return None;
+        }
+        self.retransmit_at
+    }
+
+    /// Retransmit the last handshake flight, if the remote peer hasn't answered it in time.
+    pub fn handle_timeout(&mut self, now: Instant) {
+        if self.is_connected() || self.timed_out {
+            return;
+        }
+
+        let Some(at) = self.retransmit_at else {
+            return;
+        };
+
+        if now < at {
+            return;
+        }
+
+        if self.retransmits >= self.max_retransmits {
+            warn!(
+                "DTLS handshake timed out after {} retransmits",
+                self.retransmits
+            );
+            self.timed_out = true;
+            self.need_timeout_event = true;
+            self.retransmit_at = None;
+            return;
+        }
+
+        self.retransmits += 1;
+
+        // Exponential backoff.
+        let timeout = self
+            .handshake_timeout
+            .saturating_mul(1 << self.retransmits.min(16))
+            .min(MAX_DTLS_HANDSHAKE_TIMEOUT);
+
+        debug!(
+            "DTLS retransmit flight ({} datagrams), attempt {}",
+            self.flight.len(),
+            self.retransmits
+        );
+        self.resend.extend(self.flight.iter().cloned());
+        self.retransmit_at = Some(now + timeout);
+    }
+
+    /// Returns true once, when the handshake has timed out.
+    pub fn poll_timed_out(&mut self) -> bool {
+        let x = self.need_timeout_event;
+        self.need_timeout_event = false;
+        x
     }
 
     /// Poll for an event.
@@ -130,6 +316,16 @@ impl Dtls {
             return Ok(());
         }
 
+        if !self.is_connected() {
+            // The remote answered, whatever we send next is a new flight.
+            self.new_flight = true;
+        } else if self.final_flight && has_change_cipher_spec(message) {
+            // The remote retransmitted its last flight, which means it didn't get ours.
+            // Epoch 1 records are resent as is, they were never received.
+            debug!("DTLS resend final flight ({} datagrams)", self.flight.len());
+            self.resend.extend(self.flight.iter().cloned());
+        }
+
         Ok(self.dtls_impl.handle_receive(message, &mut self.events)?)
     }
 
@@ -156,6 +352,57 @@ impl Dtls {
     }
 }
 
+/// Give every unencrypted (epoch 0) record in the datagram a new sequence number.
+///
+/// The remote drops records with an already seen sequence number as replays, which means
+/// retransmitted flights need new numbers (RFC 6347 4.2.4). Without it, a flight that was
+/// received but couldn't be used, such as a ChangeCipherSpec arriving before the handshake
+/// messages it follows, can never be delivered again. Records of later epochs are
+/// protected by the keys and kept as is. Resending those is harmless: either the remote
+/// never got them, or it did and answers the retransmitted flight with its final flight.
+fn renumber_records(buf: &mut [u8], next_seq: &mut u64) {
+    let mut pos = 0;
+
+    while buf.len() >= pos + RECORD_HEADER_LEN {
+        let header = &mut buf[pos..pos + RECORD_HEADER_LEN];
+
+        let epoch = u16::from_be_bytes([header[3], header[4]]);
+        let len = u16::from_be_bytes([header[11], header[12]]) as usize;
+
+        if epoch == 0 {
+            header[5..11].copy_from_slice(&next_seq.to_be_bytes()[2..]);
+            *next_seq += 1;
+        }
+
+        pos += RECORD_HEADER_LEN + len;
+    }
+}
+
+const RECORD_HEADER_LEN: usize = 13;
+const CONTENT_TYPE_CHANGE_CIPHER_SPEC: u8 = 20;
+const CONTENT_TYPE_HANDSHAKE: u8 = 22;
+
+/// The content types of the records in the datagram.
+fn content_types(buf: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        let header = buf.get(pos..pos + RECORD_HEADER_LEN)?;
+        let len = u16::from_be_bytes([header[11], header[12]]) as usize;
+        pos += RECORD_HEADER_LEN + len;
+        Some(header[0])
+    })
+}
+
+/// Whether the datagram is part of a handshake flight, as opposed to application data.
+fn is_handshake(buf: &[u8]) -> bool {
+    content_types(buf).any(|t| t == CONTENT_TYPE_HANDSHAKE || t == CONTENT_TYPE_CHANGE_CIPHER_SPEC)
+}
+
+fn has_change_cipher_spec(buf: &[u8]) -> bool {
+    content_types(buf).any(|t| t == CONTENT_TYPE_CHANGE_CIPHER_SPEC)
+}
+
 impl fmt::Debug for DtlsEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -172,3 +419,55 @@ impl fmt::Debug for DtlsEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(epoch: u16, seq: u64, body: &[u8]) -> Vec<u8> {
+        let mut r = vec![22, 0xfe, 0xfd];
+        r.extend_from_slice(&epoch.to_be_bytes());
+        r.extend_from_slice(&seq.to_be_bytes()[2..]);
+        r.extend_from_slice(&(body.len() as u16).to_be_bytes());
+        r.extend_from_slice(body);
+        r
+    }
+
+    #[test]
+    fn renumber_epoch_0() {
+        let mut buf = [
+            record(0, 7, &[1, 2, 3]),
+            record(1, 7, &[4]),
+            record(0, 8, &[]),
+        ]
+        .concat();
+
+        let mut next_seq = 3;
+        renumber_records(&mut buf, &mut next_seq);
+
+        let expected = [
+            record(0, 3, &[1, 2, 3]),
+            record(1, 7, &[4]),
+            record(0, 4, &[]),
+        ]
+        .concat();
+        assert_eq!(buf, expected);
+        assert_eq!(next_seq, 5);
+    }
+
+    #[test]
+    fn handshake_datagrams() {
+        let mut ccs = record(0, 1, &[1]);
+        ccs[0] = 20;
+        let mut data = record(1, 2, &[1, 2]);
+        data[0] = 23;
+
+        assert!(is_handshake(&record(0, 0, &[1, 2, 3])));
+        assert!(is_handshake(&[ccs.clone(), record(1, 0, &[4])].concat()));
+        assert!(!is_handshake(&data));
+        assert!(!is_handshake(&[]));
+
+        assert!(has_change_cipher_spec(&[record(0, 0, &[1]), ccs].concat()));
+        assert!(!has_change_cipher_spec(&record(0, 0, &[1])));
+    }
+}
@@ -11,12 +11,16 @@ use crate::sdp::FormatParam;
 
 // These really don't belong anywhere, but I guess they're kind of related
 // to codecs etc.
-pub use crate::packet::{CodecExtra, H264CodecExtra, Vp8CodecExtra, Vp9CodecExtra};
+pub use crate::packet::{Av1CodecExtra, CodecExtra, H264CodecExtra};
+pub use crate::packet::{Vp8CodecExtra, Vp9CodecExtra};
 
 /// Session config for all codecs.
 #[derive(Debug, Clone, Default)]
 pub struct CodecConfig {
     params: Vec<PayloadParams>,
+
+    /// The format parameters of the remote per negotiated PT.
+    remote_formats: Vec<(Pt, FormatParams)>,
 }
 
 /// Group of parameters for a payload type (PT).
@@ -32,7 +36,7 @@ pub struct CodecConfig {
 /// a=rtcp-fb:96 nack pli
 /// a=fmtp:96 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42001f
 /// ```
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PayloadParams {
     /// The payload type that groups these parameters.
     pub(crate) pt: Pt,
@@ -59,12 +63,18 @@ pub struct PayloadParams {
     /// Whether the payload uses the REMB (Receiver Estimated Maximum Bitrate) mechanic.
     pub(crate) fb_remb: bool,
 
+    /// Whether the payload uses loss notifications (`goog-lntf`).
+    pub(crate) fb_lntf: bool,
+
     /// Whether the payload is locked by negotiation or can still be debated.
     ///
     /// If we make an OFFER or ANSWER and the direction is sendrecv/recvonly, the parameters are locked
     /// can't be further changed. If we make an OFFER for a sendonly, the parameters are only proposed
     /// and don't lock.
     pub(crate) locked: bool,
+
+    /// The packet duration to send, resolved against the remote ptime when negotiated.
+    pub(crate) send_p_time: Option<u16>,
 }
 
 // we don't want to compare "locked"
@@ -83,7 +93,7 @@ impl PartialEq for PayloadParams {
 impl Eq for PayloadParams {}
 
 /// Codec specification
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodecSpec {
     /// The codec identifier.
     pub codec: Codec,
@@ -126,7 +136,7 @@ pub enum Codec {
 }
 
 /// Codec specific format parameters.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct FormatParams {
     /// Opus specific parameter.
     ///
@@ -164,6 +174,48 @@ pub struct FormatParams {
 
     /// VP9 profile id.
     pub profile_id: Option<u32>,
+
+    /// Video decoder limits, such as the max frame size.
+    ///
+    /// In an answer, this lets the answerer cap what the offerer sends. Boxed since they
+    /// are rarely used.
+    pub decoder_limits: Option<Box<DecoderLimits>>,
+
+    /// Audio packet durations the receiver prefers and can handle.
+    ///
+    /// These are the media level `a=ptime` and `a=maxptime` SDP attributes and apply to all
+    /// audio payload types of the m-line. Boxed since they are rarely used.
+    pub packet_time: Option<Box<PacketTime>>,
+}
+
+/// Video decoder limits of a payload type, see [`FormatParams::decoder_limits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DecoderLimits {
+    /// Limit on the frame size, in 16x16 macroblocks (`max-fs`).
+    ///
+    /// Used by h264, VP8 and VP9.
+    pub max_fs: Option<u32>,
+
+    /// Limit on macroblocks processed per second (`max-mbps`).
+    ///
+    /// h264 specific parameter.
+    pub max_mbps: Option<u32>,
+
+    /// Limit on frames per second (`max-fr`).
+    ///
+    /// Used by VP8 and VP9.
+    pub max_fr: Option<u32>,
+}
+
+/// Audio packet durations of an m-line, see [`FormatParams::packet_time`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PacketTime {
+    /// Audio packet duration in milliseconds the receiver prefers (`a=ptime`).
+    pub p_time: Option<u16>,
+
+    /// The maximum audio packet duration in milliseconds the receiver can handle
+    /// (`a=maxptime`).
+    pub max_p_time: Option<u16>,
 }
 
 impl PayloadParams {
@@ -190,7 +242,12 @@ impl PayloadParams {
             fb_pli: is_video,
             fb_remb: is_video,
 
+            // Opt in, since few remote peers offer it.
+            fb_lntf: false,
+
             locked: false,
+
+            send_p_time: None,
         }
     }
 
@@ -207,7 +264,19 @@ impl PayloadParams {
 
     /// The codec with settings for this group of parameters.
     pub fn spec(&self) -> CodecSpec {
-        self.spec
+        self.spec.clone()
+    }
+
+    /// The audio packet duration in milliseconds to use when sending.
+    ///
+    /// This is the remote `a=ptime`, or if the remote has none, the local one. The
+    /// value is kept within the remote `a=maxptime` and `minptime`. `None` means no
+    /// side expressed a preference.
+    ///
+    /// str0m doesn't re-frame audio, the application must packetize the encoded audio
+    /// using this duration.
+    pub fn send_p_time(&self) -> Option<u16> {
+        self.send_p_time.or(self.spec.format.p_time())
     }
 
     /// Sets whether the payload use the TWCC feedback mechanic.
@@ -260,10 +329,29 @@ impl PayloadParams {
         self.fb_remb
     }
 
+    /// Set whether the payload uses loss notifications (`goog-lntf`).
+    ///
+    /// Defaults to false. See [`StreamRx::request_loss_notification()`][crate::rtp::StreamRx::request_loss_notification].
+    pub fn set_fb_lntf(&mut self, fb_lntf: bool) {
+        self.fb_lntf = fb_lntf
+    }
+
+    /// Whether the payload uses loss notifications (`goog-lntf`).
+    pub fn fb_lntf(&self) -> bool {
+        self.fb_lntf
+    }
+
     pub(crate) fn match_score(&self, o: &PayloadParams) -> Option<usize> {
         // we don't want to compare PT
-        let c0 = self.spec;
-        let c1 = o.spec;
+        let mut c0 = self.spec.clone();
+        let mut c1 = o.spec.clone();
+
+        // ptime/maxptime are per m-line and don't distinguish payload types. The decoder
+        // limits are declared independently by each side.
+        for c in [&mut c0, &mut c1] {
+            c.format.packet_time = None;
+            c.format.decoder_limits = None;
+        }
 
         if c0 == c1 {
             // Exact match
@@ -291,11 +379,11 @@ impl PayloadParams {
         }
 
         if c0.codec == Codec::H264 {
-            return Self::match_h264_score(c0, c1);
+            return Self::match_h264_score(&c0, &c1);
         }
 
         if c0.codec == Codec::Vp9 {
-            return Self::match_vp9_score(c0, c1);
+            return Self::match_vp9_score(&c0, &c1);
         }
 
         // TODO: Fuzzy matching for any other audio codecs
@@ -333,7 +421,7 @@ impl PayloadParams {
         score
     }
 
-    fn match_vp9_score(c0: CodecSpec, c1: CodecSpec) -> Option<usize> {
+    fn match_vp9_score(c0: &CodecSpec, c1: &CodecSpec) -> Option<usize> {
         // Default profile_id is 0. https://datatracker.ietf.org/doc/html/draft-ietf-payload-vp9-16#section-6
         let c0_profile_id = c0.format.profile_id.unwrap_or(0);
         let c1_profile_id = c1.format.profile_id.unwrap_or(0);
@@ -345,7 +433,7 @@ impl PayloadParams {
         Some(100)
     }
 
-    fn match_h264_score(c0: CodecSpec, c1: CodecSpec) -> Option<usize> {
+    fn match_h264_score(c0: &CodecSpec, c1: &CodecSpec) -> Option<usize> {
         // Default packetization mode is 0. https://www.rfc-editor.org/rfc/rfc6184#section-6.2
         let c0_packetization_mode = c0.format.packetization_mode.unwrap_or(0);
         let c1_packetization_mode = c1.format.packetization_mode.unwrap_or(0);
@@ -377,23 +465,23 @@ impl PayloadParams {
         remote_pts: &[PayloadParams],
         claimed: &mut [bool; 128],
         warn_on_locked: bool,
-    ) {
-        let Some((first, _)) = remote_pts
+    ) -> Option<FormatParams> {
+        let (first, _) = remote_pts
             .iter()
             .filter_map(|p| self.match_score(p).map(|s| (p, s)))
-            .max_by_key(|(_, s)| *s)
-        else {
-            return;
-        };
+            .max_by_key(|(_, s)| *s)?;
 
         let remote_pt = first.pt;
         let remote_rtx = first.resend;
 
+        // The remote ptime can change in a renegotiation, also for locked params.
+        self.send_p_time = resolve_p_time(self.spec.format.p_time(), &first.spec.format);
+
         if self.locked {
             // This can happen if the incoming PTs are suggestions (send-direction) rather than demanded
             // (receive-direction). We only want to warn if we get receive direction changes.
             if !warn_on_locked {
-                return;
+                return Some(first.spec.format.clone());
             }
             // Just verify it's still the same. We should validate this in apply_offer/answer instead
             // of ever seeing this error message.
@@ -413,14 +501,38 @@ impl PayloadParams {
             self.resend = remote_rtx;
             self.locked = true;
 
+            // Only keep the RTCP feedback mechanisms both sides support.
+            self.fb_transport_cc &= first.fb_transport_cc;
+            self.fb_nack &= first.fb_nack;
+            self.fb_pli &= first.fb_pli;
+            self.fb_fir &= first.fb_fir;
+            self.fb_remb &= first.fb_remb;
+            self.fb_lntf &= first.fb_lntf;
+
             claimed.assert_claim_once(remote_pt);
             if let Some(rtx) = remote_rtx {
                 claimed.assert_claim_once(rtx);
             }
         }
+
+        Some(first.spec.format.clone())
     }
 }
 
+/// The remote ptime is preferred over the local, within the remote bounds.
+fn resolve_p_time(local: Option<u16>, remote: &FormatParams) -> Option<u16> {
+    let mut p_time = remote.p_time().or(local)?;
+
+    if let Some(max) = remote.max_p_time() {
+        p_time = p_time.min(max);
+    }
+    if let Some(min) = remote.min_p_time {
+        p_time = p_time.max(min as u16);
+    }
+
+    Some(p_time)
+}
+
 impl CodecConfig {
     /// Creates a new empty config.
     pub fn empty() -> Self {
@@ -431,6 +543,7 @@ impl CodecConfig {
     pub fn new_from_payload_params(payload_params: Vec<PayloadParams>) -> Self {
         CodecConfig {
             params: payload_params,
+            ..Default::default()
         }
     }
 
@@ -455,6 +568,19 @@ impl CodecConfig {
     /// Clear all configured configs.
     pub fn clear(&mut self) {
         self.params.clear();
+        self.remote_formats.clear();
+    }
+
+    /// The format parameters the remote declared for a negotiated payload type.
+    ///
+    /// These describe the remote decoder, such as [`DecoderLimits::max_fs`], and the
+    /// application should configure its encoder to stay within them. `None` until
+    /// the PT is negotiated with the remote.
+    pub fn remote_format(&self, pt: Pt) -> Option<FormatParams> {
+        self.remote_formats
+            .iter()
+            .find(|(p, _)| *p == pt)
+            .map(|(_, f)| f.clone())
     }
 
     /// Manually configure a payload type.
@@ -487,7 +613,9 @@ impl CodecConfig {
             fb_nack,
             fb_pli,
             fb_remb,
+            fb_lntf: false,
             locked: false,
+            send_p_time: None,
         };
 
         self.params.push(p);
@@ -573,7 +701,7 @@ impl CodecConfig {
         }
     }
 
-    // TODO: AV1 depacketizer/packetizer.
+    // TODO: AV1 packetizer.
     //
     // /// Add a default AV1 payload type.
     // pub fn add_default_av1(&mut self) {
@@ -638,7 +766,7 @@ impl CodecConfig {
         remote_dir: Direction,
     ) -> Option<Pt> {
         // If we have no matching parameters locally, we can't accept the remote in any way.
-        let our_params = self.match_params(remote_params)?;
+        let our_params = self.match_params(remote_params.clone())?;
 
         if remote_dir.sdp_is_receiving() {
             // The remote is talking about its own receive requirements. The PTs are not suggestions.
@@ -691,7 +819,12 @@ impl CodecConfig {
         let warn_on_locked = remote_dir.sdp_is_receiving();
 
         for p in self.params.iter_mut() {
-            p.update_param(remote_params, &mut claimed, warn_on_locked);
+            let Some(format) = p.update_param(remote_params, &mut claimed, warn_on_locked) else {
+                continue;
+            };
+
+            self.remote_formats.retain(|(pt, _)| *pt != p.pt);
+            self.remote_formats.push((p.pt, format));
         }
 
         const PREFERED_RANGES: &[RangeInclusive<usize>] = &[
@@ -721,25 +854,27 @@ impl CodecConfig {
 
                 info!("Reassigned PT {} => {}", p.pt, pt);
                 p.pt = pt;
-
-                claimed.assert_claim_once(pt);
             }
 
-            let Some(rtx) = p.resend else {
+            // Claim the PT also when it's not reassigned, to not hand it out to a later RTX.
+            claimed.assert_claim_once(p.pt);
+
+            let Some(mut rtx) = p.resend else {
                 continue;
             };
 
             if claimed.is_claimed(rtx) {
-                let Some(rtx) = claimed.find_unclaimed(PREFERED_RANGES) else {
+                let Some(pt) = claimed.find_unclaimed(PREFERED_RANGES) else {
                     // TODO: handle this gracefully.
                     panic!("Exhausted all PT ranges, inconsistent PayloadParam state");
                 };
 
-                info!("Reassigned RTX PT {:?} => {:?}", p.resend, rtx);
-                p.resend = Some(rtx);
-
-                claimed.assert_claim_once(rtx);
+                info!("Reassigned RTX PT {:?} => {:?}", p.resend, pt);
+                p.resend = Some(pt);
+                rtx = pt;
             }
+
+            claimed.assert_claim_once(rtx);
         }
     }
 
@@ -812,12 +947,27 @@ impl FormatParams {
             PacketizationMode(v) => self.packetization_mode = Some(*v),
             ProfileLevelId(v) => self.profile_level_id = Some(*v),
             ProfileId(v) => self.profile_id = Some(*v),
+            MaxFs(v) => self.decoder_limits_mut().max_fs = Some(*v),
+            MaxMbps(v) => self.decoder_limits_mut().max_mbps = Some(*v),
+            MaxFr(v) => self.decoder_limits_mut().max_fr = Some(*v),
             Apt(_) => {}
             Unknown => {}
         }
     }
 
-    pub(crate) fn to_format_param(self) -> Vec<FormatParam> {
+    pub(crate) fn p_time(&self) -> Option<u16> {
+        self.packet_time.as_ref().and_then(|p| p.p_time)
+    }
+
+    pub(crate) fn max_p_time(&self) -> Option<u16> {
+        self.packet_time.as_ref().and_then(|p| p.max_p_time)
+    }
+
+    fn decoder_limits_mut(&mut self) -> &mut DecoderLimits {
+        self.decoder_limits.get_or_insert_with(Default::default)
+    }
+
+    pub(crate) fn to_format_param(&self) -> Vec<FormatParam> {
         use FormatParam::*;
         let mut r = Vec::with_capacity(5);
 
@@ -842,6 +992,17 @@ impl FormatParams {
         if let Some(v) = self.profile_id {
             r.push(ProfileId(v));
         }
+        if let Some(l) = &self.decoder_limits {
+            if let Some(v) = l.max_fs {
+                r.push(MaxFs(v));
+            }
+            if let Some(v) = l.max_mbps {
+                r.push(MaxMbps(v));
+            }
+            if let Some(v) = l.max_fr {
+                r.push(MaxFr(v));
+            }
+        }
 
         r
     }
@@ -951,6 +1112,8 @@ mod test {
                 packetization_mode,
                 profile_level_id,
                 profile_id: None, // VP8
+                packet_time: None,
+                decoder_limits: None,
             },
         }
     }
@@ -991,7 +1154,7 @@ mod test {
             msg,
         } in cases.into_iter()
         {
-            let matched = PayloadParams::match_h264_score(c0, c1).is_some();
+            let matched = PayloadParams::match_h264_score(&c0, &c1).is_some();
             assert_eq!(matched, must_match, "{msg}\nc0: {c0:#?}\nc1: {c1:#?}");
         }
     }
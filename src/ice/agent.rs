@@ -12,6 +12,7 @@ use crate::util::NonCryptographicRng;
 
 use super::candidate::{Candidate, CandidateKind};
 use super::pair::{CandidatePair, CheckState, PairId};
+use super::IceError;
 
 /// Handles the ICE protocol for a given peer.
 ///
@@ -45,6 +46,9 @@ pub struct IceAgent {
     /// Credentials for this side. Set on init and ice-restart.
     local_credentials: IceCreds,
 
+    /// Whether the local credentials are communicated to the remote, i.e. can't change.
+    local_credentials_locked: bool,
+
     /// Credentials for the remote side. Set when we learn about it.
     remote_credentials: Option<IceCreds>,
 
@@ -67,6 +71,19 @@ pub struct IceAgent {
     /// All remote candidates, in the order we get to know them.
     remote_candidates: Vec<Candidate>,
 
+    /// Whether the remote side signaled end-of-candidates.
+    ///
+    /// Once set, not having any viable pair means the agent is failed.
+    remote_end_of_candidates: bool,
+
+    /// Whether local gathering is complete.
+    local_end_of_candidates: bool,
+
+    /// Whether any candidate pair was checked and timed out since the last ICE restart.
+    ///
+    /// Together with end-of-candidates, this means the agent is failed.
+    checks_timed_out: bool,
+
     /// The candidate pairs.
     candidate_pairs: Vec<CandidatePair>,
 
@@ -88,6 +105,10 @@ pub struct IceAgent {
     /// if we get a better candidate for [`IceAgentEvent::NominatedSend`].
     nominated_send: Option<PairId>,
 
+    /// Pair set with [`IceAgent::force_selected_pair()`]. Overrides the nomination
+    /// as long as the pair is still around.
+    forced_pair: Option<PairId>,
+
     /// Statistics counter for the agent.
     stats: IceAgentStats,
 }
@@ -102,16 +123,22 @@ struct StunRequest {
     prio: u32,
     use_candidate: bool,
     remote_ufrag: String,
+    ice_controlling: Option<u64>,
+    ice_controlled: Option<u64>,
 }
 
 const REMOTE_PEER_REFLEXIVE_TEMP_FOUNDATION: &str = "tmp_prflx";
 
+/// STUN error code for role conflicts (RFC 8445 7.3.1.1).
+const ROLE_CONFLICT: u16 = 487;
+
 /// States the ICE connection can be in.
 ///
 /// More details on connection states can be found in the [ICE RFC][1].
 ///
 /// [1]: https://www.rfc-editor.org/rfc/rfc8445
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum IceConnectionState {
     /// The ICE agent is gathering addresses.
     New,
@@ -137,14 +164,16 @@ pub enum IceConnectionState {
     /// or during temporary disconnections. When the problem resolves, the connection
     /// may return to the connected state.
     Disconnected,
+
+    /// The remote side signaled end-of-candidates, and the checks of all candidate pairs
+    /// timed out without finding a compatible match.
+    ///
+    /// Like `Disconnected`, it's possible to "come back" from this state, for instance
+    /// by discovering a peer reflexive candidate or an ICE restart.
+    Failed,
     //
-    // NB: The failed and closed state doesn't really have a mapping in this implementation.
-    //     We never end trickle ice and it's always possible to "come back" if more remote
-    //     candidates are added.
+    // NB: The closed state doesn't really have a mapping in this implementation.
     //
-    // The ICE candidate has checked all candidates pairs against one another and has
-    // failed to find compatible matches.
-    // Failed,
     // The ICE agent has shut down and is no longer handling requests.
     // Closed,
 }
@@ -160,6 +189,11 @@ impl IceConnectionState {
     pub fn is_disconnected(&self) -> bool {
         *self == IceConnectionState::Disconnected
     }
+
+    /// Tells if this state is the failed state.
+    pub fn is_failed(&self) -> bool {
+        *self == IceConnectionState::Failed
+    }
 }
 
 /// Credentials for STUN packages.
@@ -224,6 +258,22 @@ pub enum IceAgentEvent {
         /// The remote address to send datagrams to.
         destination: SocketAddr,
     },
+
+    /// A local candidate was added.
+    ///
+    /// With trickle ICE, this should be sent to the remote peer. After an ICE restart
+    /// that keeps the local candidates, they are emitted again with the new ufrag.
+    NewLocalCandidate(Candidate),
+
+    /// Local gathering is complete, no more local candidates will be added.
+    ///
+    /// With trickle ICE, this should be sent to the remote peer as end-of-candidates.
+    LocalEndOfCandidates,
+
+    /// The role changed to resolve a role conflict with the remote peer.
+    ///
+    /// The value is whether this side is now controlling.
+    IceRoleChange(bool),
 }
 
 impl IceCreds {
@@ -239,6 +289,56 @@ impl IceCreds {
         let pass = Id::<22>::random().to_string();
         IceCreds { ufrag, pass }
     }
+
+    /// Check the lengths and characters of the username fragment and password.
+    ///
+    /// The ufrag must be 4 to 256 characters, and the password 22 to 256 characters,
+    /// using only `A-Z`, `a-z`, `0-9`, `+` and `/`.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc8445#section-5.3>
+    ///
+    /// ```
+    /// # use str0m::IceCreds;
+    /// let creds = IceCreds {
+    ///     ufrag: "abcd".into(),
+    ///     pass: "abcdefghijklmnopqrstuv".into(),
+    /// };
+    /// assert!(creds.validate().is_ok());
+    ///
+    /// let short = IceCreds {
+    ///     ufrag: "abc".into(),
+    ///     pass: "abcdefghijklmnopqrstuv".into(),
+    /// };
+    /// assert!(short.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), IceError> {
+        fn check(name: &str, value: &str, min: usize) -> Result<(), IceError> {
+            if value.len() < min || value.len() > 256 {
+                return Err(IceError::BadCredentials(format!(
+                    "{} must be {} to 256 characters: {}",
+                    name,
+                    min,
+                    value.len()
+                )));
+            }
+
+            let is_ice_char = |c: char| c.is_ascii_alphanumeric() || c == '+' || c == '/';
+
+            if let Some(c) = value.chars().find(|c| !is_ice_char(*c)) {
+                return Err(IceError::BadCredentials(format!(
+                    "{} has invalid character: {:?}",
+                    name, c
+                )));
+            }
+
+            Ok(())
+        }
+
+        check("ufrag", &self.ufrag, 4)?;
+        check("password", &self.pass, 22)?;
+
+        Ok(())
+    }
 }
 
 impl IceAgent {
@@ -255,18 +355,23 @@ impl IceAgent {
             ice_lite: false,
             max_candidate_pairs: None,
             local_credentials,
+            local_credentials_locked: false,
             remote_credentials: None,
             controlling: false,
             control_tie_breaker: NonCryptographicRng::u64(),
             state: IceConnectionState::New,
             local_candidates: vec![],
             remote_candidates: vec![],
+            remote_end_of_candidates: false,
+            local_end_of_candidates: false,
+            checks_timed_out: false,
             candidate_pairs: vec![],
             transmit: VecDeque::new(),
             events: VecDeque::new(),
             stun_server_queue: VecDeque::new(),
             discovered_recv: HashSet::new(),
             nominated_send: None,
+            forced_pair: None,
             stats: IceAgentStats::default(),
             timing_advance: Duration::from_millis(50),
         }
@@ -288,9 +393,15 @@ impl IceAgent {
 
     /// Enable or disable ice_lite.
     ///
+    /// An ice-lite agent never initiates connectivity checks, it only responds to them,
+    /// and is always the controlled side. Enabling it makes the agent controlled.
+    ///
     /// Default is disabled.
     pub fn set_ice_lite(&mut self, enabled: bool) {
         self.ice_lite = enabled;
+        if enabled {
+            self.controlling = false;
+        }
     }
 
     /// Set a new timing advance (Ta) value.
@@ -309,11 +420,32 @@ impl IceAgent {
     }
 
     /// Sets the local ice credentials.
-    pub fn set_local_credentials(&mut self, r: IceCreds) {
-        if self.local_credentials != r {
-            info!("Set local credentials: {:?}", r);
-            self.local_credentials = r;
+    ///
+    /// This is only possible before the credentials are in use, i.e. before adding local
+    /// candidates or [`IceAgent::lock_local_credentials()`]. After that, the credentials
+    /// only change with an ICE restart.
+    pub fn set_local_credentials(&mut self, r: IceCreds) -> Result<(), IceError> {
+        if self.local_credentials == r {
+            return Ok(());
+        }
+
+        if self.local_credentials_locked || !self.local_candidates.is_empty() {
+            return Err(IceError::CredentialsInUse);
         }
+
+        r.validate()?;
+
+        info!("Set local credentials: {:?}", r);
+        self.local_credentials = r;
+
+        Ok(())
+    }
+
+    /// Prevent changing the local credentials, because they are communicated to the remote.
+    ///
+    /// This happens automatically when adding local candidates.
+    pub fn lock_local_credentials(&mut self) {
+        self.local_credentials_locked = true;
     }
 
     /// Local ice candidates.
@@ -383,7 +515,9 @@ impl IceAgent {
     }
 
     /// Whether this side is controlling or controlled.
-    #[allow(unused)]
+    ///
+    /// This can change during ICE to resolve a role conflict, which is signalled
+    /// with [`IceAgentEvent::IceRoleChange`].
     pub fn controlling(&self) -> bool {
         self.controlling
     }
@@ -392,7 +526,14 @@ impl IceAgent {
     ///
     /// You should not call this function after ICE candidate pair formation
     /// has started, as the controlling state influences candidate prio!
+    ///
+    /// An ice-lite agent is always the controlled side (RFC 8445 6.1.1), and
+    /// attempts to make it controlling are ignored.
     pub fn set_controlling(&mut self, v: bool) {
+        if v && self.ice_lite {
+            debug!("Ignore set controlling in ice-lite mode");
+            return;
+        }
         self.controlling = v;
     }
 
@@ -525,11 +666,13 @@ impl IceAgent {
                 self.discard_candidate_pairs_by_local(idx);
 
                 info!("Add local candidate: {:?}", c);
+                self.emit_event(IceAgentEvent::NewLocalCandidate(c.clone()));
                 self.local_candidates.push(c);
                 self.local_candidates.len() - 1
             }
         } else {
             info!("Add local candidate: {:?}", c);
+            self.emit_event(IceAgentEvent::NewLocalCandidate(c.clone()));
             self.local_candidates.push(c);
             self.local_candidates.len() - 1
         };
@@ -654,8 +797,11 @@ impl IceAgent {
                     continue 'outer;
                 }
 
-                let prio =
-                    CandidatePair::calculate_prio(self.controlling, remote.prio(), local.prio());
+                let prio = CandidatePair::calculate_prio(
+                    self.controlling,
+                    remote.prio_pair(),
+                    local.prio_pair(),
+                );
                 let mut pair = CandidatePair::new(*local_idx, *remote_idx, prio);
 
                 trace!("Form pair local: {:?} remote: {:?}", local, remote);
@@ -781,6 +927,99 @@ impl IceAgent {
         false
     }
 
+    /// Force a candidate pair to be used for sending.
+    ///
+    /// This bypasses the normal nomination, which picks the pair with the highest
+    /// priority. Useful for reproducing path specific problems, or in controlled networks
+    /// where the best path is known. Connectivity checks continue on the forced pair like
+    /// on any other pair. Should the pair fail and be removed, the agent goes back to the
+    /// normal nomination.
+    ///
+    /// The pair must have succeeded at least one connectivity check, which means the
+    /// candidates must have been added and checked already. When controlling, the pair is
+    /// nominated to the remote peer, but the remote peer might still send on another pair.
+    ///
+    /// Returns `true` if the pair was found and is now used for sending.
+    pub fn force_selected_pair(&mut self, local: &Candidate, remote: &Candidate) -> bool {
+        let same = |v: &Candidate, c: &Candidate| {
+            !v.discarded()
+                && v.addr() == c.addr()
+                && v.base() == c.base()
+                && v.raddr() == c.raddr()
+                && v.kind() == c.kind()
+        };
+
+        let local_idx = self.local_candidates.iter().position(|v| same(v, local));
+        let remote_idx = self.remote_candidates.iter().position(|v| same(v, remote));
+
+        let (Some(local_idx), Some(remote_idx)) = (local_idx, remote_idx) else {
+            debug!("No candidates found to force: {:?} {:?}", local, remote);
+            return false;
+        };
+
+        let Some(pair) = self
+            .candidate_pairs
+            .iter()
+            .find(|p| p.local_idx() == local_idx && p.remote_idx() == remote_idx)
+        else {
+            debug!("No candidate pair to force: {:?} {:?}", local, remote);
+            return false;
+        };
+
+        if pair.state() != CheckState::Succeeded {
+            debug!("Not forcing pair without successful check: {:?}", pair);
+            return false;
+        }
+
+        info!("Force selected pair: {:?}", pair);
+        self.forced_pair = Some(pair.id());
+        self.evaluate_nomination();
+
+        true
+    }
+
+    /// Go back to normal nomination after [`IceAgent::force_selected_pair()`].
+    pub fn clear_forced_pair(&mut self) {
+        if self.forced_pair.take().is_some() {
+            debug!("Clear forced pair");
+            self.evaluate_nomination();
+        }
+    }
+
+    /// Signal that the remote side will not send any more candidates.
+    ///
+    /// If no candidate pair succeeds, this lets the agent go to
+    /// [`IceConnectionState::Failed`] as soon as the checks of all pairs timed out.
+    pub fn set_remote_end_of_candidates(&mut self) {
+        if self.remote_end_of_candidates {
+            return;
+        }
+        debug!("Remote end-of-candidates");
+        self.remote_end_of_candidates = true;
+    }
+
+    /// Whether the remote side signaled end-of-candidates.
+    pub fn remote_end_of_candidates(&self) -> bool {
+        self.remote_end_of_candidates
+    }
+
+    /// Signal that local gathering is complete.
+    ///
+    /// Emits [`IceAgentEvent::LocalEndOfCandidates`] to be sent to the remote side.
+    pub fn set_local_end_of_candidates(&mut self) {
+        if self.local_end_of_candidates {
+            return;
+        }
+        debug!("Local end-of-candidates");
+        self.local_end_of_candidates = true;
+        self.emit_event(IceAgentEvent::LocalEndOfCandidates);
+    }
+
+    /// Whether local gathering is complete.
+    pub fn local_end_of_candidates(&self) -> bool {
+        self.local_end_of_candidates
+    }
+
     /// Restart ICE.
     ///
     /// This is useful when detecting a change in network interfaces, such as
@@ -798,7 +1037,10 @@ impl IceAgent {
 
         self.remote_credentials = None;
         self.remote_candidates.clear();
+        self.remote_end_of_candidates = false;
+        self.checks_timed_out = false;
         self.candidate_pairs.clear();
+        self.forced_pair = None;
         self.transmit.clear();
         self.events.clear();
         self.discovered_recv.clear();
@@ -811,11 +1053,26 @@ impl IceAgent {
             }
         } else {
             self.local_candidates.clear();
+            self.local_end_of_candidates = false;
         }
 
         self.local_credentials = local_credentials;
 
         self.emit_event(IceAgentEvent::IceRestart(self.local_credentials.clone()));
+
+        let kept: Vec<_> = self
+            .local_candidates
+            .iter()
+            .filter(|c| !c.discarded())
+            .cloned()
+            .collect();
+        for c in kept {
+            self.emit_event(IceAgentEvent::NewLocalCandidate(c));
+        }
+        if self.local_end_of_candidates {
+            self.emit_event(IceAgentEvent::LocalEndOfCandidates);
+        }
+
         self.set_connection_state(IceConnectionState::Checking, "ice restart");
     }
 
@@ -929,6 +1186,8 @@ impl IceAgent {
             self.stun_server_handle_message(now, &packet);
         } else if packet.message.is_successful_binding_response() {
             self.stun_client_handle_response(now, packet.message);
+        } else if packet.message.is_failed_binding_response() {
+            self.stun_client_handle_error(packet.message);
         }
 
         self.emit_event(IceAgentEvent::DiscoveredRecv {
@@ -936,8 +1195,6 @@ impl IceAgent {
             source: packet.source,
         });
 
-        // TODO handle unsuccessful responses.
-
         true
     }
 
@@ -1145,6 +1402,8 @@ impl IceAgent {
             prio,
             use_candidate,
             remote_ufrag: remote_ufrag.into(),
+            ice_controlling: message.ice_controlling(),
+            ice_controlled: message.ice_controlled(),
         };
 
         if self.remote_credentials.is_some() {
@@ -1191,6 +1450,11 @@ impl IceAgent {
             return;
         }
 
+        if self.has_role_conflict(&req) {
+            self.send_role_conflict(&req);
+            return;
+        }
+
         if req.use_candidate && self.controlling {
             // the other side is not controlling, and it sent USE-CANDIDATE. that's wrong.
             debug!("STUN request rejected, USE-CANDIDATE when local is controlling");
@@ -1305,7 +1569,11 @@ impl IceAgent {
                 return; // Ignore STUN requests to discarded candidates
             }
 
-            let prio = CandidatePair::calculate_prio(self.controlling, remote.prio(), local.prio());
+            let prio = CandidatePair::calculate_prio(
+                self.controlling,
+                remote.prio_pair(),
+                local.prio_pair(),
+            );
 
             // *  Its state is set to Waiting. (this is the default)
             // *  The pair is inserted into the checklist based on its priority.
@@ -1369,11 +1637,102 @@ impl IceAgent {
             source: local_addr,
             destination: remote_addr,
             contents: buf.into(),
+            dscp: None,
+            ecn: None,
         };
 
         self.transmit.push_back(trans);
     }
 
+    /// Detects and resolves role conflicts as per RFC 8445 7.3.1.1.
+    ///
+    /// Returns true if the remote must switch role, in which case the request is answered
+    /// with a 487 (Role Conflict) error. Otherwise we switch role if needed.
+    fn has_role_conflict(&mut self, req: &StunRequest) -> bool {
+        // An ice-lite agent is always controlled.
+        if self.ice_lite {
+            return false;
+        }
+
+        let ours = self.control_tie_breaker;
+
+        if self.controlling {
+            let Some(theirs) = req.ice_controlling else {
+                return false;
+            };
+
+            if ours >= theirs {
+                return true;
+            }
+
+            self.switch_role(false);
+        } else {
+            let Some(theirs) = req.ice_controlled else {
+                return false;
+            };
+
+            if ours < theirs {
+                return true;
+            }
+
+            self.switch_role(true);
+        }
+
+        false
+    }
+
+    fn send_role_conflict(&mut self, req: &StunRequest) {
+        let (_, password) = self.stun_credentials(true);
+
+        let reply = StunMessage::error_reply(req.trans_id, ROLE_CONFLICT, "Role Conflict");
+
+        trace!(
+            "Send STUN role conflict: {} -> {} {:?}",
+            req.destination,
+            req.source,
+            reply
+        );
+
+        let mut buf = vec![0_u8; DATAGRAM_MTU];
+
+        let n = reply
+            .to_bytes(&password, &mut buf)
+            .expect("IO error writing STUN reply");
+        buf.truncate(n);
+
+        let trans = Transmit {
+            proto: req.proto,
+            source: req.destination,
+            destination: req.source,
+            contents: buf.into(),
+            dscp: None,
+            ecn: None,
+        };
+
+        self.transmit.push_back(trans);
+    }
+
+    fn switch_role(&mut self, controlling: bool) {
+        info!(
+            "Switch ICE role to resolve conflict, controlling: {}",
+            controlling
+        );
+
+        self.controlling = controlling;
+
+        // The pair priorities depend on the role.
+        for pair in &mut self.candidate_pairs {
+            let local = pair.local_candidate(&self.local_candidates);
+            let remote = pair.remote_candidate(&self.remote_candidates);
+            let prio =
+                CandidatePair::calculate_prio(controlling, remote.prio_pair(), local.prio_pair());
+            pair.set_prio(prio);
+        }
+        self.candidate_pairs.sort();
+
+        self.emit_event(IceAgentEvent::IceRoleChange(controlling));
+    }
+
     fn stun_client_binding_request(&mut self, now: Instant, pair_idx: usize) {
         let (username, password) = self.stun_credentials(false);
 
@@ -1384,7 +1743,7 @@ impl IceAgent {
         // Only the controlling side sends USE-CANDIDATE.
         let use_candidate = self.controlling && pair.is_nominated();
 
-        let trans_id = pair.new_attempt(now);
+        let trans_id = pair.new_attempt(now, self.controlling);
 
         self.stats.bind_request_sent += 1;
 
@@ -1416,6 +1775,8 @@ impl IceAgent {
             source: local.base(),
             destination: remote.addr(),
             contents: buf.into(),
+            dscp: None,
+            ecn: None,
         };
 
         self.transmit.push_back(trans);
@@ -1528,7 +1889,43 @@ impl IceAgent {
         self.evaluate_state(now);
     }
 
+    fn stun_client_handle_error(&mut self, message: StunMessage<'_>) {
+        if message.error_code() != Some(ROLE_CONFLICT) {
+            debug!("STUN error response: {:?}", message);
+            return;
+        }
+
+        let trans_id = message.trans_id();
+        let Some(pair) = self
+            .candidate_pairs
+            .iter_mut()
+            .find(|p| p.has_binding_attempt(trans_id))
+        else {
+            return;
+        };
+
+        let Some(controlling) = pair.record_role_conflict(trans_id) else {
+            return;
+        };
+
+        // The role might have been switched already since we sent the request.
+        if controlling == self.controlling && !self.ice_lite {
+            self.switch_role(!controlling);
+        }
+    }
+
     fn evaluate_nomination(&mut self) {
+        if let Some(id) = self.forced_pair {
+            if self.candidate_pairs.iter().any(|p| p.id() == id) {
+                if self.nominated_send != Some(id) {
+                    self.nominate_send(id);
+                }
+                return;
+            }
+            debug!("Forced pair is gone, back to normal nomination");
+            self.forced_pair = None;
+        }
+
         let nominated_pair_priority = self.nominated_pair_priority();
 
         let best_prio = if self.controlling {
@@ -1558,21 +1955,32 @@ impl IceAgent {
             }
             trace!("Nominating best candidate");
 
-            if !best_prio.is_nominated() && (self.controlling || self.ice_lite) {
-                // ice lite progresses pair to success straight away.
-                best_prio.nominate(self.ice_lite);
-            }
+            let id = best_prio.id();
+            self.nominate_send(id);
+        }
+    }
 
-            let local = best_prio.local_candidate(&self.local_candidates);
-            let remote = best_prio.remote_candidate(&self.remote_candidates);
+    fn nominate_send(&mut self, id: PairId) {
+        let pair = self
+            .candidate_pairs
+            .iter_mut()
+            .find(|p| p.id() == id)
+            .expect("pair to nominate");
 
-            self.nominated_send = Some(best_prio.id());
-            self.emit_event(IceAgentEvent::NominatedSend {
-                proto: local.proto(),
-                source: local.base(),
-                destination: remote.addr(),
-            })
+        if !pair.is_nominated() && (self.controlling || self.ice_lite) {
+            // ice lite progresses pair to success straight away.
+            pair.nominate(self.ice_lite);
         }
+
+        let local = pair.local_candidate(&self.local_candidates);
+        let remote = pair.remote_candidate(&self.remote_candidates);
+
+        self.nominated_send = Some(id);
+        self.emit_event(IceAgentEvent::NominatedSend {
+            proto: local.proto(),
+            source: local.base(),
+            destination: remote.addr(),
+        })
     }
 
     fn nominated_pair_priority(&self) -> Option<u64> {
@@ -1602,6 +2010,8 @@ impl IceAgent {
                 any_nomination = true;
             } else if p.is_still_possible(now) {
                 any_still_possible = true;
+            } else {
+                self.checks_timed_out = true;
             }
         }
 
@@ -1612,11 +2022,19 @@ impl IceAgent {
             any_still_possible = true;
         }
 
+        // Without end-of-candidates, more remote candidates might arrive. Without any timed
+        // out checks, there is nothing to fail on.
+        let no_pairs = if self.remote_end_of_candidates && self.checks_timed_out {
+            Failed
+        } else {
+            Disconnected
+        };
+
         match self.state {
             New => {
                 self.set_connection_state(Checking, "new connection");
             }
-            Checking | Disconnected => {
+            Checking | Disconnected | Failed => {
                 if any_nomination {
                     if self.ice_lite {
                         self.set_connection_state(Completed, "got nomination in ice lite");
@@ -1628,7 +2046,7 @@ impl IceAgent {
                         self.set_connection_state(Completed, "got nomination, no others to try");
                     }
                 } else if !any_still_possible {
-                    self.set_connection_state(Disconnected, "no possible pairs");
+                    self.set_connection_state(no_pairs, "no possible pairs");
                 }
             }
             Connected => {
@@ -1708,6 +2126,64 @@ mod test {
         assert_eq!(v, vec![65534, 65535, 65533, 65532]);
     }
 
+    fn creds(ufrag: &str, pass: &str) -> IceCreds {
+        IceCreds {
+            ufrag: ufrag.into(),
+            pass: pass.into(),
+        }
+    }
+
+    #[test]
+    fn validate_credentials() {
+        const PASS: &str = "abcdefghijklmnopqrstuv";
+
+        assert!(IceCreds::new().validate().is_ok());
+        assert!(creds("ab+/", PASS).validate().is_ok());
+        assert!(creds(&"a".repeat(256), &"b".repeat(256)).validate().is_ok());
+
+        assert!(creds("abc", PASS).validate().is_err());
+        assert!(creds("abcd", &PASS[1..]).validate().is_err());
+        assert!(creds(&"a".repeat(257), PASS).validate().is_err());
+        assert!(creds("ab:d", PASS).validate().is_err());
+        assert!(creds("abcd", &format!("{}=", PASS)).validate().is_err());
+    }
+
+    #[test]
+    fn set_local_credentials_before_use() {
+        let mut agent = IceAgent::new();
+
+        let c = creds("abcd", "abcdefghijklmnopqrstuv");
+        agent.set_local_credentials(c.clone()).unwrap();
+        assert_eq!(agent.local_credentials(), &c);
+
+        let bad = creds("abc", "abcdefghijklmnopqrstuv");
+        assert!(matches!(
+            agent.set_local_credentials(bad),
+            Err(IceError::BadCredentials(_))
+        ));
+
+        agent.add_local_candidate(Candidate::host(ipv4_1(), "udp").unwrap());
+
+        // Setting the same is fine.
+        agent.set_local_credentials(c).unwrap();
+
+        assert!(matches!(
+            agent.set_local_credentials(IceCreds::new()),
+            Err(IceError::CredentialsInUse)
+        ));
+    }
+
+    #[test]
+    fn set_local_credentials_locked() {
+        let mut agent = IceAgent::new();
+        agent.lock_local_credentials();
+
+        assert!(matches!(
+            agent.set_local_credentials(IceCreds::new()),
+            Err(IceError::CredentialsInUse)
+        ));
+    }
+
     #[test]
     fn discard_adding_redundant() {
         let mut agent = IceAgent::new();
@@ -1824,6 +2300,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn form_pairs_network_cost() {
+        let mut agent = IceAgent::new();
+
+        agent.add_local_candidate(Candidate::host(ipv4_1(), "udp").unwrap());
+
+        // Same type and prio, the cellular one is added first.
+        let cellular = "candidate:1 1 udp 2122260223 3.3.3.3 1000 typ host network-cost 900";
+        let ethernet = "candidate:2 1 udp 2122260223 4.4.4.4 1000 typ host network-cost 10";
+        agent.add_remote_candidate(Candidate::from_sdp_string(cellular).unwrap());
+        agent.add_remote_candidate(Candidate::from_sdp_string(ethernet).unwrap());
+
+        assert_eq!(agent.pair_indexes(), [(0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn form_pairs_network_cost_symmetric() {
+        let cellular = "candidate:1 1 udp 2122260223 3.3.3.3 1000 typ host network-cost 900";
+        let ethernet = "candidate:2 1 udp 2122260223 4.4.4.4 1000 typ host";
+
+        // The pair priority is the same, whichever side the costly candidate is on.
+        let mut a = IceAgent::new();
+        a.add_local_candidate(Candidate::from_sdp_string(cellular).unwrap());
+        a.add_remote_candidate(Candidate::from_sdp_string(ethernet).unwrap());
+
+        let mut b = IceAgent::new();
+        b.set_controlling(true);
+        b.add_local_candidate(Candidate::from_sdp_string(ethernet).unwrap());
+        b.add_remote_candidate(Candidate::from_sdp_string(cellular).unwrap());
+
+        assert_eq!(a.candidate_pairs[0].prio(), b.candidate_pairs[0].prio());
+    }
+
     #[test]
     fn form_pairs_skip_redundant() {
         let mut agent = IceAgent::new();
@@ -72,6 +72,12 @@ pub struct Candidate {
     /// pairs, the field is blanked to not be confusing during ice-restarts.
     ufrag: Option<String>,
 
+    /// Network cost, 0-999.
+    ///
+    /// A non-standard attribute used by browsers, where a low value is a cheap network
+    /// such as ethernet, and 900 is cellular. Set for candidates parsed from SDP.
+    network_cost: Option<u16>,
+
     /// The ice agent might assign a local preference if we have multiple candidates
     /// that are the same type.
     local_preference: Option<u32>,
@@ -123,6 +129,7 @@ impl Candidate {
             kind,
             raddr,
             ufrag,
+            network_cost: None,
             local_preference: None,
             discarded: false,
         }
@@ -364,6 +371,24 @@ impl Candidate {
         prio
     }
 
+    /// Priority used when forming candidate pairs.
+    ///
+    /// This is the priority lowered by the network cost, if any, to prefer pairs over
+    /// cheaper networks. The cost is taken from the local preference, to bias between
+    /// candidates of the same type without changing the type preference.
+    ///
+    /// Used for both the local and remote candidate, so that both agents arrive at the
+    /// same pair priority.
+    pub(crate) fn prio_pair(&self) -> u32 {
+        let prio = self.prio();
+
+        let Some(cost) = self.network_cost else {
+            return prio;
+        };
+
+        prio.saturating_sub((cost as u32) << 8).max(1)
+    }
+
     pub(crate) fn local_preference(&self) -> u32 {
         self.local_preference
             .unwrap_or_else(|| if self.addr.is_ipv6() { 65_535 } else { 65_534 })
@@ -421,6 +446,18 @@ impl Candidate {
         self.ufrag = None;
     }
 
+    /// The network cost of a candidate, when signaled.
+    ///
+    /// This is the non-standard `network-cost` attribute sent by browsers, where lower
+    /// values are cheaper networks. Pairs with cheaper candidates are preferred.
+    pub fn network_cost(&self) -> Option<u16> {
+        self.network_cost
+    }
+
+    pub(crate) fn set_network_cost(&mut self, cost: u16) {
+        self.network_cost = Some(cost);
+    }
+
     /// Generates a candidate attribute string.
     pub fn to_sdp_string(&self) -> String {
         let mut s = format!(
@@ -439,6 +476,9 @@ impl Candidate {
         if let Some(ufrag) = &self.ufrag {
             s.push_str(&format!(" ufrag {}", ufrag));
         }
+        if let Some(cost) = self.network_cost {
+            s.push_str(&format!(" network-cost {}", cost));
+        }
         s
     }
 }
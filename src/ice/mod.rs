@@ -14,9 +14,16 @@ mod pair;
 /// Errors from the ICE agent.
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum IceError {
     #[error("ICE bad candidate: {0}")]
     BadCandidate(String),
+
+    #[error("ICE bad credentials: {0}")]
+    BadCredentials(String),
+
+    #[error("ICE local credentials are already in use")]
+    CredentialsInUse,
 }
 
 #[cfg(test)]
@@ -77,6 +84,83 @@ mod test {
     use tracing::Span;
     use tracing_subscriber::util::SubscriberInitExt;
 
+    #[test]
+    pub fn drop_host_end_of_candidates() {
+        let mut a1 = TestAgent::new(info_span!("L"));
+        let mut a2 = TestAgent::new(info_span!("R"));
+
+        let c1 = host("1.1.1.1:9999", "udp"); // 9999 is just dropped by propagate
+        a1.add_local_candidate(c1.clone());
+        a2.add_remote_candidate(c1);
+        let c2 = host("2.2.2.2:1000", "udp");
+        a2.add_local_candidate(c2.clone());
+        a1.add_remote_candidate(c2);
+        a1.set_controlling(true);
+        a2.set_controlling(false);
+
+        // Only a1 knows there are no more remote candidates coming.
+        a1.set_remote_end_of_candidates();
+
+        loop {
+            if a1.state().is_failed() && a2.state().is_disconnected() {
+                break;
+            }
+            progress(&mut a1, &mut a2);
+        }
+
+        assert!(!a1.has_event(|e| {
+            *e == IceAgentEvent::IceConnectionStateChange(IceConnectionState::Disconnected)
+        }));
+    }
+
+    #[test]
+    pub fn end_of_candidates_without_candidates() {
+        let mut a1 = TestAgent::new(info_span!("L"));
+        let mut a2 = TestAgent::new(info_span!("R"));
+
+        a1.add_local_candidate(host("1.1.1.1:1000", "udp"));
+        a2.add_local_candidate(host("2.2.2.2:1000", "udp"));
+        a1.set_controlling(true);
+        a2.set_controlling(false);
+
+        // No remote candidates at all, but no checks timed out either.
+        a1.set_remote_end_of_candidates();
+
+        for _ in 0..50 {
+            progress(&mut a1, &mut a2);
+        }
+
+        assert!(!a1.state().is_failed());
+        assert!(!a1.has_event(|e| {
+            *e == IceAgentEvent::IceConnectionStateChange(IceConnectionState::Failed)
+        }));
+    }
+
+    #[test]
+    pub fn local_end_of_candidates() {
+        let mut a1 = TestAgent::new(info_span!("L"));
+
+        a1.add_local_candidate(host("1.1.1.1:1000", "udp"));
+        a1.set_local_end_of_candidates();
+        a1.set_local_end_of_candidates();
+
+        let mut events = vec![];
+        while let Some(e) = a1.poll_event() {
+            events.push(e);
+        }
+
+        let eoc = events
+            .iter()
+            .filter(|e| **e == IceAgentEvent::LocalEndOfCandidates)
+            .count();
+        assert_eq!(eoc, 1);
+        assert!(a1.local_end_of_candidates());
+
+        // An ICE restart that clears the candidates also restarts gathering.
+        a1.ice_restart(IceCreds::new(), false);
+        assert!(!a1.local_end_of_candidates());
+    }
+
     pub fn sock(s: impl Into<String>) -> SocketAddr {
         let s: String = s.into();
         s.parse().unwrap()
@@ -361,6 +445,70 @@ mod test {
         assert!(a2.time.duration_since(a2_time) < STUN_TIMEOUT);
     }
 
+    #[test]
+    pub fn force_selected_pair() {
+        let mut a1 = TestAgent::new(info_span!("L"));
+        let mut a2 = TestAgent::new(info_span!("R"));
+
+        let c1 = host("1.1.1.1:1000", "udp");
+        let c3 = host("1.1.1.1:2000", "udp");
+        a1.add_local_candidate(c1.clone());
+        a1.add_local_candidate(c3.clone());
+        a2.add_remote_candidate(c1.clone());
+        a2.add_remote_candidate(c3.clone());
+
+        let c2 = host("2.2.2.2:1000", "udp");
+        a1.add_remote_candidate(c2.clone());
+        a2.add_local_candidate(c2.clone());
+
+        a1.set_controlling(true);
+        a2.set_controlling(false);
+
+        // Not checked yet.
+        assert!(!a1.force_selected_pair(&c3, &c2));
+
+        loop {
+            if a1.state().is_connected() && a2.state().is_connected() {
+                break;
+            }
+            progress(&mut a1, &mut a2);
+        }
+
+        let last_source = |a: &TestAgent| {
+            a.events.iter().rev().find_map(|(_, e)| match e {
+                IceAgentEvent::NominatedSend { source, .. } => Some(*source),
+                _ => None,
+            })
+        };
+
+        let nominated = last_source(&a1).unwrap();
+        let (other, other_addr) = if nominated == c1.addr() {
+            (&c3, c3.addr())
+        } else {
+            (&c1, c1.addr())
+        };
+
+        // Unknown pair.
+        assert!(!a1.force_selected_pair(other, &host("5.5.5.5:1000", "udp")));
+
+        // Succeeds once the pair has been checked.
+        while !a1.force_selected_pair(other, &c2) {
+            progress(&mut a1, &mut a2);
+        }
+
+        for _ in 0..10 {
+            progress(&mut a1, &mut a2);
+        }
+        assert_eq!(last_source(&a1), Some(other_addr));
+
+        a1.clear_forced_pair();
+
+        for _ in 0..10 {
+            progress(&mut a1, &mut a2);
+        }
+        assert_eq!(last_source(&a1), Some(nominated));
+    }
+
     #[test]
     pub fn re_adding_invalidated_local_candidate() {
         let mut a1 = TestAgent::new(info_span!("L"));
@@ -502,6 +650,23 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn ice_lite_is_controlled() {
+        let mut a1 = TestAgent::new(info_span!("L"));
+
+        a1.set_controlling(true);
+        a1.agent.set_ice_lite(true);
+        assert!(!a1.agent.controlling());
+
+        // Can't become controlling while ice-lite.
+        a1.set_controlling(true);
+        assert!(!a1.agent.controlling());
+
+        a1.agent.set_ice_lite(false);
+        a1.set_controlling(true);
+        assert!(a1.agent.controlling());
+    }
+
     #[test]
     pub fn prflx_host() {
         let mut a1 = TestAgent::new(info_span!("L"));
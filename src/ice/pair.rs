@@ -104,6 +104,9 @@ pub struct BindingAttempt {
 
     /// Whether the binding attempt is nominated.
     nominated: bool,
+
+    /// Whether we were controlling when sending the binding request.
+    controlling: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -167,6 +170,10 @@ impl CandidatePair {
         self.prio
     }
 
+    pub fn set_prio(&mut self, prio: u64) {
+        self.prio = prio;
+    }
+
     pub fn state(&self) -> CheckState {
         self.state
     }
@@ -206,7 +213,7 @@ impl CandidatePair {
     /// Records a new binding request attempt.
     ///
     /// Returns the transaction id to use in the STUN message.
-    pub fn new_attempt(&mut self, now: Instant) -> TransId {
+    pub fn new_attempt(&mut self, now: Instant, controlling: bool) -> TransId {
         // calculate a new time
         self.cached_next_attempt_time = None;
 
@@ -220,6 +227,7 @@ impl CandidatePair {
             request_sent: now,
             respone_recv: None,
             nominated: self.is_nominated(),
+            controlling,
         };
 
         self.binding_attempts.push_back(attempt);
@@ -283,6 +291,30 @@ impl CandidatePair {
         trace!("Recorded binding response: {:?}", self);
     }
 
+    /// Removes a binding request attempt that got a 487 (Role Conflict) response.
+    ///
+    /// The attempt is removed to not count as unanswered, and the pair is checked again
+    /// after the role is resolved.
+    ///
+    /// Returns whether we were controlling when sending the request.
+    pub fn record_role_conflict(&mut self, trans_id: TransId) -> Option<bool> {
+        self.cached_next_attempt_time = None;
+
+        let idx = self
+            .binding_attempts
+            .iter()
+            .position(|b| b.trans_id == trans_id)?;
+
+        let attempt = self.binding_attempts.remove(idx)?;
+
+        if attempt.nominated && self.nomination_state == NominationState::Attempt {
+            // Send the nomination again.
+            self.nomination_state = NominationState::Nominated;
+        }
+
+        Some(attempt.controlling)
+    }
+
     /// The time of the last binding request attempt.
     ///
     /// `None` means there has been no attempts.
@@ -21,6 +21,9 @@ mod id;
 // a "util" crate or similar.
 pub(crate) use id::Id;
 
+mod tcp;
+pub use tcp::TcpFraming;
+
 /// Targeted MTU
 pub(crate) const DATAGRAM_MTU: usize = 1150;
 
@@ -51,6 +54,8 @@ pub enum Protocol {
     /// UDP
     Udp,
     /// TCP (See RFC 4571 for framing)
+    ///
+    /// Incoming data must be reassembled into packets, for example using [`TcpFraming`].
     Tcp,
     /// TCP with fixed SSL Hello Exchange
     /// See AsyncSSLServerSocket implementation for exchange details:
@@ -62,6 +67,7 @@ pub enum Protocol {
 
 /// An instruction to send an outgoing packet.
 #[derive(Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Transmit {
     /// Protocol the transmission should use.
     ///
@@ -113,6 +119,28 @@ pub struct Transmit {
 
     /// Contents of the datagram.
     pub contents: DatagramSend,
+
+    /// The DSCP value the datagram is intended to be marked with.
+    ///
+    /// This is set for RTP packets of a stream configured with
+    /// [`StreamTx::set_dscp()`][crate::rtp::StreamTx::set_dscp]. str0m does not touch
+    /// the socket, marking the packet (i.e. `IP_TOS`/`IPV6_TCLASS`) is the responsibility
+    /// of the application. Note the DSCP is the upper 6 bits of the ToS byte.
+    pub dscp: Option<u8>,
+
+    /// The ECN codepoint the datagram is intended to be marked with.
+    ///
+    /// This is ECT(0) for RTP packets when ECN is enabled with
+    /// [`RtcConfig::enable_ecn()`][crate::RtcConfig::enable_ecn]. Like the DSCP, marking
+    /// the packet is the responsibility of the application.
+    pub ecn: Option<Ecn>,
+}
+
+/// The IP header marking intended for an outgoing datagram.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Marking {
+    pub dscp: Option<u8>,
+    pub ecn: Option<Ecn>,
 }
 
 /// A wrapper for some payload that is to be sent.
@@ -131,7 +159,47 @@ impl From<DatagramSend> for Vec<u8> {
     }
 }
 
+/// ECN (Explicit Congestion Notification) codepoint of an IP packet.
+///
+/// These are the two lowest bits of the ToS/traffic class byte.
+///
+/// Definition: <https://www.rfc-editor.org/rfc/rfc3168#section-5>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ecn {
+    /// Not ECN-Capable Transport.
+    NotEct,
+    /// ECN-Capable Transport, ECT(1).
+    Ect1,
+    /// ECN-Capable Transport, ECT(0).
+    Ect0,
+    /// Congestion Experienced.
+    Ce,
+}
+
+impl Ecn {
+    /// The ECN codepoint from the ToS/traffic class byte.
+    pub fn from_tos(tos: u8) -> Self {
+        match tos & 0b11 {
+            0b00 => Ecn::NotEct,
+            0b01 => Ecn::Ect1,
+            0b10 => Ecn::Ect0,
+            _ => Ecn::Ce,
+        }
+    }
+
+    /// The two bits to set in the ToS/traffic class byte.
+    pub fn as_bits(&self) -> u8 {
+        match self {
+            Ecn::NotEct => 0b00,
+            Ecn::Ect1 => 0b01,
+            Ecn::Ect0 => 0b10,
+            Ecn::Ce => 0b11,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 /// Received incoming data.
 pub struct Receive<'a> {
     /// The protocol the socket this received data originated from is using.
@@ -146,6 +214,14 @@ pub struct Receive<'a> {
     /// Parsed contents of the datagram.
     #[serde(borrow)]
     pub contents: DatagramRecv<'a>,
+
+    /// The ECN codepoint the datagram was received with, if the application reads it
+    /// from the socket (i.e. `IP_RECVTOS`/`IPV6_RECVTCLASS`).
+    ///
+    /// Used for ECN feedback when enabled with
+    /// [`RtcConfig::enable_ecn()`][crate::RtcConfig::enable_ecn].
+    #[serde(default)]
+    pub ecn: Option<Ecn>,
 }
 
 impl<'a> Receive<'a> {
@@ -162,8 +238,15 @@ impl<'a> Receive<'a> {
             source,
             destination,
             contents,
+            ecn: None,
         })
     }
+
+    /// Sets the ECN marking the datagram arrived with.
+    pub fn with_ecn(mut self, ecn: Option<Ecn>) -> Self {
+        self.ecn = ecn;
+        self
+    }
 }
 
 /// An incoming STUN packet.
@@ -266,6 +349,7 @@ impl<'a> TryFrom<&'a Transmit> for Receive<'a> {
             source: t.source,
             destination: t.destination,
             contents: DatagramRecv::try_from(&t.contents[..])?,
+            ecn: t.ecn,
         })
     }
 }
@@ -277,6 +361,8 @@ impl fmt::Debug for Transmit {
             .field("source", &self.source)
             .field("destination", &self.destination)
             .field("len", &self.contents.len())
+            .field("dscp", &self.dscp)
+            .field("ecn", &self.ecn)
             .finish()
     }
 }
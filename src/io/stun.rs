@@ -75,6 +75,8 @@ pub struct StunMessage<'a> {
     attrs: Attributes<'a>,
     integrity: &'a [u8],
     integrity_len: u16,
+    /// Length of the parsed datagram, 0 for messages we construct.
+    wire_len: usize,
 }
 
 impl<'a> StunMessage<'a> {
@@ -141,6 +143,7 @@ impl<'a> StunMessage<'a> {
             attrs,
             integrity,
             integrity_len,
+            wire_len: buf.len(),
         })
     }
 
@@ -152,6 +155,11 @@ impl<'a> StunMessage<'a> {
         self.class
     }
 
+    /// Number of bytes of the datagram this message was parsed from.
+    pub(crate) fn wire_len(&self) -> usize {
+        self.wire_len
+    }
+
     /// Whether this STUN message is a BINDING request.
     pub(crate) fn is_binding_request(&self) -> bool {
         self.method == Method::Binding && self.class == Class::Request
@@ -160,11 +168,17 @@ impl<'a> StunMessage<'a> {
     /// Whether this STUN message is a _successful_ BINDING response.
     ///
     /// STUN binding requests are very simple, they just return the observed address.
-    /// As such, they cannot actually fail which is why we don't have `is_failed_binding_response`.
     pub(crate) fn is_successful_binding_response(&self) -> bool {
         self.method == Method::Binding && self.class == Class::Success
     }
 
+    /// Whether this STUN message is a _failed_ BINDING response.
+    ///
+    /// For ICE, this is mainly the 487 (Role Conflict) error.
+    pub(crate) fn is_failed_binding_response(&self) -> bool {
+        self.method == Method::Binding && self.class == Class::Failure
+    }
+
     /// The transaction ID of this STUN message.
     pub(crate) fn trans_id(&self) -> TransId {
         self.trans_id
@@ -193,6 +207,7 @@ impl<'a> StunMessage<'a> {
             },
             integrity: &[],
             integrity_len: 0,
+            wire_len: 0,
         }
     }
 
@@ -208,6 +223,23 @@ impl<'a> StunMessage<'a> {
             },
             integrity: &[],
             integrity_len: 0,
+            wire_len: 0,
+        }
+    }
+
+    /// Constructs a new STUN BINDING error reply.
+    pub(crate) fn error_reply(trans_id: TransId, code: u16, reason: &'a str) -> StunMessage<'a> {
+        StunMessage {
+            class: Class::Failure,
+            method: Method::Binding,
+            trans_id,
+            attrs: Attributes {
+                error_code: Some((code, reason)),
+                ..Default::default()
+            },
+            integrity: &[],
+            integrity_len: 0,
+            wire_len: 0,
         }
     }
 
@@ -231,6 +263,21 @@ impl<'a> StunMessage<'a> {
         self.attrs.use_candidate
     }
 
+    /// If present, returns the tie-breaker of the ICE-CONTROLLING attribute.
+    pub(crate) fn ice_controlling(&self) -> Option<u64> {
+        self.attrs.ice_controlling
+    }
+
+    /// If present, returns the tie-breaker of the ICE-CONTROLLED attribute.
+    pub(crate) fn ice_controlled(&self) -> Option<u64> {
+        self.attrs.ice_controlled
+    }
+
+    /// If present, returns the code of the ERROR-CODE attribute.
+    pub(crate) fn error_code(&self) -> Option<u16> {
+        self.attrs.error_code.map(|(code, _)| code)
+    }
+
     /// Verify the integrity of this message against the provided password.
     #[must_use]
     pub(crate) fn check_integrity(&self, password: &str) -> bool {
@@ -515,8 +562,21 @@ impl<'a> Attributes<'a> {
         } else {
             0
         };
+        let error_code = self
+            .error_code
+            .map(|(_, reason)| {
+                let len = 4 + reason.len();
+                ATTR_TLV_LENGTH + len + (4 - len % 4) % 4
+            })
+            .unwrap_or_default();
 
-        username + ice_controlled + ice_controlling + priority + address + use_candidate
+        username
+            + ice_controlled
+            + ice_controlling
+            + priority
+            + address
+            + use_candidate
+            + error_code
     }
 
     fn to_bytes(self, vec: &mut dyn Write, trans_id: &[u8]) -> io::Result<()> {
@@ -555,6 +615,16 @@ impl<'a> Attributes<'a> {
             vec.write_all(&Self::USE_CANDIDATE.to_be_bytes())?;
             vec.write_all(&0_u16.to_be_bytes())?;
         }
+        if let Some((code, reason)) = self.error_code {
+            let len = 4 + reason.len();
+            vec.write_all(&Self::ERROR_CODE.to_be_bytes())?;
+            vec.write_all(&(len as u16).to_be_bytes())?;
+            vec.write_all(&[0, 0, (code / 100) as u8, (code % 100) as u8])?;
+            vec.write_all(reason.as_bytes())?;
+            for _ in 0..(4 - len % 4) % 4 {
+                vec.write_all(&[0])?;
+            }
+        }
 
         Ok(())
     }
@@ -853,6 +923,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn error_reply_roundtrip() {
+        let trans_id = TransId::new();
+        let reply = StunMessage::error_reply(trans_id, 487, "Role Conflict");
+
+        let mut buf = vec![0; 1500];
+        let n = reply.to_bytes("pass", &mut buf).unwrap();
+        buf.truncate(n);
+
+        let message = StunMessage::parse(&buf).unwrap();
+        assert!(message.is_failed_binding_response());
+        assert_eq!(message.trans_id(), trans_id);
+        assert_eq!(message.error_code(), Some(487));
+        assert!(message.check_integrity("pass"));
+    }
+
     #[test]
     fn parse_zero_length_buffer() {
         let result = StunMessage::parse(&[]);
@@ -0,0 +1,157 @@
+/// Reassembles RFC 4571 framed packets from a TCP stream.
+///
+/// With [`Protocol::Tcp`][crate::net::Protocol::Tcp], every STUN, DTLS, RTP and RTCP packet
+/// is prefixed with a 2 byte big endian length. TCP doesn't preserve the boundaries of
+/// writes, so a read from the socket can contain part of a packet, or several packets.
+/// Push every read to this buffer and pop the complete packets to pass them on to
+/// [`Receive::new()`][crate::net::Receive::new].
+///
+/// Use one instance per TCP connection.
+///
+/// ```
+/// # use str0m::net::TcpFraming;
+/// let mut framing = TcpFraming::new();
+///
+/// // A read with one and a half packets.
+/// framing.push(&[0, 2, 1, 2, 0, 3, 1]);
+/// assert_eq!(framing.pop(), Some(&[1, 2][..]));
+/// assert_eq!(framing.pop(), None);
+///
+/// // The rest of the second packet.
+/// framing.push(&[2, 3]);
+/// assert_eq!(framing.pop(), Some(&[1, 2, 3][..]));
+/// ```
+///
+/// <https://www.rfc-editor.org/rfc/rfc4571>
+#[derive(Debug, Default)]
+pub struct TcpFraming {
+    /// Bytes read from the TCP stream.
+    buf: Vec<u8>,
+
+    /// Bytes at the start of buf that have already been popped.
+    consumed: usize,
+}
+
+impl TcpFraming {
+    /// Creates a new empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add data read from the TCP stream.
+    pub fn push(&mut self, data: &[u8]) {
+        // Drop popped packets before growing the buffer.
+        if self.consumed > 0 {
+            self.buf.drain(..self.consumed);
+            self.consumed = 0;
+        }
+
+        self.buf.extend_from_slice(data);
+    }
+
+    /// The next complete packet, without the length prefix.
+    ///
+    /// Returns `None` until enough data is pushed to complete a packet.
+    pub fn pop(&mut self) -> Option<&[u8]> {
+        let rest = &self.buf[self.consumed..];
+
+        if rest.len() < 2 {
+            return None;
+        }
+
+        let len = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+
+        if rest.len() < 2 + len {
+            return None;
+        }
+
+        let start = self.consumed + 2;
+        self.consumed = start + len;
+
+        Some(&self.buf[start..self.consumed])
+    }
+
+    /// Number of buffered bytes not yet popped, including length prefixes.
+    pub fn pending(&self) -> usize {
+        self.buf.len() - self.consumed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{DatagramRecv, StunMessage, TransId};
+
+    fn stun_frames() -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut stream = vec![];
+        let mut packets = vec![];
+
+        for _ in 0..3 {
+            let msg =
+                StunMessage::binding_request("abc:def", TransId::new(), true, 0x1234, 100, false);
+            let mut buf = vec![0; 1500];
+            let n = msg.to_bytes("password", &mut buf).unwrap();
+            buf.truncate(n);
+
+            stream.extend_from_slice(&(n as u16).to_be_bytes());
+            stream.extend_from_slice(&buf);
+            packets.push(buf);
+        }
+
+        (stream, packets)
+    }
+
+    fn pop_all(framing: &mut TcpFraming, out: &mut Vec<Vec<u8>>) {
+        while let Some(p) = framing.pop() {
+            // Every packet must be a parseable STUN message.
+            assert!(DatagramRecv::try_from(p).is_ok());
+            out.push(p.to_vec());
+        }
+    }
+
+    #[test]
+    fn all_chunk_sizes() {
+        let (stream, packets) = stun_frames();
+
+        for chunk in 1..=stream.len() {
+            let mut framing = TcpFraming::new();
+            let mut out = vec![];
+
+            for c in stream.chunks(chunk) {
+                framing.push(c);
+                pop_all(&mut framing, &mut out);
+            }
+
+            assert_eq!(out, packets, "chunk size {}", chunk);
+            assert_eq!(framing.pending(), 0);
+        }
+    }
+
+    #[test]
+    fn split_in_length_prefix() {
+        let (stream, packets) = stun_frames();
+        let first = 2 + packets[0].len();
+
+        let mut framing = TcpFraming::new();
+        let mut out = vec![];
+
+        // First packet and one byte of the next length prefix.
+        framing.push(&stream[..first + 1]);
+        pop_all(&mut framing, &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(framing.pending(), 1);
+
+        framing.push(&stream[first + 1..]);
+        pop_all(&mut framing, &mut out);
+        assert_eq!(out, packets);
+    }
+
+    #[test]
+    fn empty_packet() {
+        let mut framing = TcpFraming::new();
+        framing.push(&[0, 0, 0, 1, 7]);
+        assert_eq!(framing.pop(), Some(&[][..]));
+        assert_eq!(framing.pop(), Some(&[7][..]));
+        assert_eq!(framing.pop(), None);
+    }
+}
@@ -180,12 +180,8 @@
 //!             buf.truncate(n);
 //!             Input::Receive(
 //!                 Instant::now(),
-//!                 Receive {
-//!                     proto: Protocol::Udp,
-//!                     source,
-//!                     destination: socket.local_addr().unwrap(),
-//!                     contents: buf.as_slice().try_into().unwrap(),
-//!                 },
+//!                 Receive::new(Protocol::Udp, source, socket.local_addr().unwrap(), &buf)
+//!                     .unwrap(),
 //!             )
 //!         }
 //!
@@ -579,23 +575,37 @@
 #[macro_use]
 extern crate tracing;
 
-use bwe::{Bwe, BweKind};
-use change::{DirectApi, SdpApi};
+use bwe::{Bwe, BweKind, BweThresholdCrossed};
+use change::{BundlePolicy, DirectApi, SdpApi, SdpDescription};
 use rtp::RawPacket;
 use std::fmt;
 use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use streams::FrameBoundary;
+use streams::JitterBufferEvent;
+use streams::LayerActive;
+use streams::PacketsDropped;
 use streams::RtpPacket;
-use streams::StreamPaused;
+use streams::RtpPacketsLost;
+use streams::StreamRejected;
+use streams::StreamSsrcChanged;
+use streams::SyncGroup;
+use streams::{DemuxPolicy, UnknownPt, UnknownPtPolicy};
+use streams::{RtpTimeJump, StreamActivity, StreamPaused};
 use thiserror::Error;
 use util::InstantExt;
 
 mod crypto;
 use crypto::Fingerprint;
+pub use crypto::{DtlsCipherSuite, SrtpProfile};
 
 mod dtls;
 use dtls::DtlsCert;
+pub use dtls::DtlsState;
 use dtls::{Dtls, DtlsEvent};
+use dtls::{DEFAULT_DTLS_HANDSHAKE_TIMEOUT, DEFAULT_DTLS_MAX_RETRANSMITS};
 
 #[path = "ice/mod.rs"]
 mod ice_;
@@ -616,33 +626,47 @@ pub mod ice {
 }
 
 mod io;
-use io::DatagramRecvInner;
+use io::{DatagramRecvInner, DATAGRAM_MTU};
 
 mod packet;
 
 #[path = "rtp/mod.rs"]
 mod rtp_;
 use rtp_::Bitrate;
-use rtp_::{Extension, ExtensionMap};
+use rtp_::{Extension, ExtensionMap, RtcpObserver, Ssrc};
 
 /// Low level RTP access.
 pub mod rtp {
     /// Feedback for RTP.
     pub mod rtcp {
+        pub use crate::rtp_::{App, ReportList, Rrtr, Rtcp, RtcpObserver, Sdes, SdesType};
         pub use crate::rtp_::{Descriptions, ExtendedReport, Fir, Goodbye, Nack, Pli};
-        pub use crate::rtp_::{Dlrr, NackEntry, ReceptionReport, ReportBlock};
+        pub use crate::rtp_::{Dlrr, EcnFeedback, Lntf, NackEntry, ReceptionReport, ReportBlock};
         pub use crate::rtp_::{FirEntry, ReceiverReport, SenderInfo, SenderReport, Twcc};
-        pub use crate::rtp_::{ReportList, Rrtr, Rtcp, Sdes, SdesType};
+        pub use crate::rtp_::{JitterBufferMetrics, MetricInterval};
     }
     use self::rtcp::Rtcp;
 
     /// Video Layers Allocation RTP Header Extension
     pub mod vla;
     pub use crate::rtp_::{Extension, ExtensionMap, ExtensionSerializer};
-    pub use crate::rtp_::{ExtensionValues, UserExtensionValues};
-
-    pub use crate::rtp_::{RtpHeader, SeqNo, Ssrc, VideoOrientation};
-    pub use crate::streams::{RtpPacket, StreamPaused, StreamRx, StreamTx};
+    pub use crate::rtp_::{ExtensionValues, RawExtensionValues, UserExtensionValues};
+
+    pub use crate::rtp_::{ColorSpace, FrameMarking, HdrMetadata};
+    pub use crate::rtp_::{CsrcList, RtpHeader, SeqNo, Ssrc, VideoOrientation};
+    pub use crate::streams::{audio_mos, estimate_quality, video_mos};
+    pub use crate::streams::{DemuxBy, DemuxPolicy, UnknownPt, UnknownPtPolicy};
+    pub use crate::streams::{ExtensionStats, FrameBoundary, FrameBoundaryKind, ReorderStats};
+    pub use crate::streams::{JitterBufferEvent, JitterBufferEventKind};
+    pub use crate::streams::{
+        LayerActive, PacketsDropped, PendingStats, RtpPacket, RtpPacketsLost,
+    };
+    pub use crate::streams::{QualityEstimator, QualityInput, QualityScore};
+    pub use crate::streams::{
+        RtpTimeJump, SrInfo, StreamActivity, StreamPaused, StreamRejected, StreamRx,
+        StreamSsrcChanged, StreamTx,
+    };
+    pub use crate::streams::{SyncGroup, SyncMember};
 
     /// Debug output of the unencrypted RTP and RTCP packets.
     ///
@@ -677,8 +701,8 @@ use channel::{Channel, ChannelData, ChannelHandler, ChannelId};
 
 pub mod media;
 use media::{Direction, Media, Mid, Pt, Rid, Writer};
-use media::{KeyframeRequest, KeyframeRequestKind};
-use media::{MediaAdded, MediaChanged, MediaData};
+use media::{FrameDropped, MediaAdded, MediaChanged, MediaData, MediaKind};
+use media::{KeyframeRequest, KeyframeRequestKind, LossNotification};
 
 pub mod change;
 
@@ -686,16 +710,23 @@ mod util;
 use util::{already_happened, not_happening, Soonest};
 
 mod session;
-use session::Session;
+use session::{Session, DEFAULT_SRTP_LIMIT_MARGIN};
 
 pub mod stats;
 use stats::{MediaEgressStats, MediaIngressStats, PeerStats, Stats, StatsEvent, StatsSnapshot};
+use stats::{OverheadStats, PacerStats, SelectedPair, TransportStats};
 
 mod streams;
 
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+#[cfg(feature = "pcap")]
+mod pcap;
+
 /// Network related types to get socket data in/out of [`Rtc`].
 pub mod net {
-    pub use crate::io::{DatagramRecv, DatagramSend, Protocol, Receive, Transmit};
+    pub use crate::io::{DatagramRecv, DatagramSend, Ecn, Protocol, Receive, TcpFraming, Transmit};
 }
 
 /// Various error types.
@@ -716,10 +747,11 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[non_exhaustive]
 pub enum RtcError {
     /// Some problem with the remote SDP.
+    #[deprecated(note = "Not returned anymore, negotiation failures are RtcError::Sdp")]
     #[error("remote sdp: {0}")]
     RemoteSdp(String),
 
-    /// SDP errors.
+    /// SDP errors, including failed OFFER/ANSWER negotiations.
     #[error("{0}")]
     Sdp(#[from] error::SdpError),
 
@@ -800,6 +832,18 @@ pub enum RtcError {
     /// is an incorrect usage pattern of the str0m API.
     #[error("Consecutive calls to write() without poll_output() in between")]
     WriteWithoutPoll,
+
+    /// The mid of a trickled ICE candidate is not in the session.
+    #[error("Mid is unknown {0}")]
+    UnknownMid(Mid),
+
+    /// The RTX PT is not a dynamic PT (96-127), or is the same as the main PT.
+    #[error("RTX PT is invalid {0}")]
+    InvalidRtxPt(Pt),
+
+    /// The RTCP APP packet can't be sent, its subtype is above 31 or it is too large.
+    #[error("RTCP APP is invalid: {0}")]
+    InvalidRtcpApp(&'static str),
 }
 
 /// Instance that does WebRTC. Main struct of the entire library.
@@ -840,14 +884,19 @@ pub struct Rtc {
     stats: Option<Stats>,
     session: Session,
     remote_fingerprint: Option<Fingerprint>,
+    local_description: Option<SdpDescription>,
+    remote_description: Option<SdpDescription>,
     remote_addrs: Vec<SocketAddr>,
     send_addr: Option<SendAddr>,
     need_init_time: bool,
     last_now: Instant,
     peer_bytes_rx: u64,
     peer_bytes_tx: u64,
+    transport_stats: TransportStats,
     change_counter: usize,
     last_timeout_reason: Reason,
+    #[cfg(feature = "pcap")]
+    pcap: Option<pcap::PcapWriter>,
 }
 
 struct SendAddr {
@@ -870,6 +919,37 @@ pub enum Event {
     /// connected to the peer or not.
     IceConnectionStateChange(IceConnectionState),
 
+    /// A local ICE candidate was added, to be sent to the remote peer (trickle ICE).
+    ///
+    /// See [`Rtc::add_remote_candidate_str()`] for the receiving side.
+    LocalIceCandidate(Candidate),
+
+    /// Local gathering is complete, to be sent to the remote peer as end-of-candidates.
+    ///
+    /// Emitted after [`Rtc::set_local_end_of_candidates()`].
+    LocalEndOfCandidates,
+
+    /// The ICE role changed to resolve a role conflict, when both sides started
+    /// as controlling or as controlled.
+    ///
+    /// The value is whether this side is now controlling, see
+    /// [`DirectApi::ice_controlling()`][crate::change::DirectApi::ice_controlling].
+    IceRoleChange(bool),
+
+    /// The ICE candidate pair used for sending changed.
+    ///
+    /// Emitted on the first nomination, and each time ICE switches to another pair, such as
+    /// when the network changes from WiFi to cellular. Media, DTLS and data channels continue
+    /// on the new pair without renegotiation. See also [`Rtc::selected_candidate_pair()`].
+    SelectedCandidatePairChange(SelectedPair),
+
+    /// The DTLS handshake failed, since the remote peer didn't answer any of the
+    /// retransmits.
+    ///
+    /// See [`RtcConfig::set_dtls_handshake_timeout()`]. The instance will not connect,
+    /// and would typically be disconnected using [`Rtc::disconnect()`].
+    DtlsHandshakeTimeout,
+
     // =================== Media related events ==================
 
     /// Upon adding new media to the session. The lines are emitted.
@@ -880,11 +960,24 @@ pub enum Event {
     /// Incoming media data sent by the remote peer.
     MediaData(MediaData),
 
+    /// An incoming frame was dropped before it was complete.
+    ///
+    /// See [`RtcConfig::set_max_frame_size()`] and
+    /// [`RtcConfig::set_incomplete_frame_timeout()`].
+    FrameDropped(FrameDropped),
+
     /// Changes to the media may be emitted.
     ///
     ///. Currently only covers a change of direction.
     MediaChanged(MediaChanged),
 
+    /// An extension enabled in [`RtcConfig`] could not be negotiated for the media.
+    ///
+    /// This happens when the remote peer maps the extension to an id that is out of the
+    /// range we support, or to an id already taken by another negotiated extension.
+    /// The extension is not used for the media.
+    ExtensionNotNegotiated(Mid, Extension),
+
     // =================== Data channel related events ===================
 
     /// A data channel has opened.
@@ -923,6 +1016,10 @@ pub enum Event {
     /// A new estimate from the bandwidth estimation subsystem.
     EgressBitrateEstimate(BweKind),
 
+    /// The bandwidth estimate crossed a threshold set with
+    /// [`Bwe::add_threshold()`][crate::bwe::Bwe::add_threshold].
+    BweThresholdCrossed(BweThresholdCrossed),
+
     // =================== RTP related events ===================
 
     /// Incoming keyframe request for media that we are sending to the remote peer.
@@ -930,14 +1027,106 @@ pub enum Event {
     /// The request is either PLI (Picture Loss Indication) or FIR (Full Intra Request).
     KeyframeRequest(KeyframeRequest),
 
+    /// Incoming loss notification (`goog-lntf`) for media that we are sending to the remote peer.
+    ///
+    /// Tells which packets were lost, and whether the frames received since are decodable.
+    /// The encoder can use it to recover without a keyframe.
+    LossNotification(LossNotification),
+
     /// Whether an incoming encoded stream is paused.
     ///
     /// This means the stream has not received any data for some time (default 1.5 seconds).
     StreamPaused(StreamPaused),
 
+    /// An incoming encoded stream started receiving packets, after being created or inactive.
+    ///
+    /// Requires [`StreamRx::set_inactive_timeout()`][crate::rtp::StreamRx::set_inactive_timeout].
+    StreamActive(StreamActivity),
+
+    /// An incoming encoded stream received no packets for the inactive timeout.
+    ///
+    /// Requires [`StreamRx::set_inactive_timeout()`][crate::rtp::StreamRx::set_inactive_timeout].
+    StreamInactive(StreamActivity),
+
+    /// The RTP timestamps of an incoming encoded stream jumped implausibly.
+    ///
+    /// The media time was resynced, see [`StreamRx::set_max_time_jump()`][crate::rtp::StreamRx::set_max_time_jump].
+    RtpTimeJump(RtpTimeJump),
+
+    /// An incoming encoded stream was not added, since the max number of incoming
+    /// streams is reached.
+    ///
+    /// See [`Rtc::set_max_streams()`].
+    StreamRejected(StreamRejected),
+
+    /// The remote changed the main SSRC of an incoming encoded stream, such as when
+    /// restarting its encoder.
+    ///
+    /// The application should reset its decoder.
+    StreamSsrcChanged(StreamSsrcChanged),
+
+    /// Incoming RTP with a payload type that is not configured.
+    ///
+    /// Only emitted with [`UnknownPtPolicy::Event`][crate::rtp::UnknownPtPolicy::Event],
+    /// see [`RtcConfig::set_unknown_pt_policy()`].
+    UnknownPt(UnknownPt),
+
+    /// Incoming RTCP APP (application-defined) packet.
+    ///
+    /// str0m doesn't interpret these. Send them using
+    /// [`DirectApi::send_rtcp_app()`][crate::change::DirectApi::send_rtcp_app].
+    RtcpApp(rtp::rtcp::App),
+
+    /// Whether an outgoing simulcast layer is sent.
+    ///
+    /// Only emitted for layers managed using
+    /// [`StreamTx::set_layer_bitrate()`][crate::rtp::StreamTx::set_layer_bitrate].
+    LayerActive(LayerActive),
+
+    /// Stale packets were dropped from the send queue of an outgoing encoded stream.
+    ///
+    /// Only emitted when enabled using
+    /// [`StreamTx::set_max_queue_age()`][crate::rtp::StreamTx::set_max_queue_age]
+    /// or [`RtcConfig::set_early_media_buffer()`].
+    PacketsDropped(PacketsDropped),
+
+    /// The SRTP key is close to the number of packets it can protect.
+    ///
+    /// Holds the number of packets left, see [`Rtc::srtp_packets_left()`]. Emitted once,
+    /// when reaching the margin set using [`RtcConfig::set_srtp_limit_margin()`]. The SRTP
+    /// key can't be renewed in the same session, the application should replace the
+    /// session before it runs out.
+    SrtpLimitApproaching(u64),
+
+    /// Incoming SRTP or SRTCP packets failed to authenticate or decrypt.
+    ///
+    /// Holds the total number of failures so far, see
+    /// [`TransportStats::srtp_auth_failures`][crate::stats::TransportStats::srtp_auth_failures].
+    /// Emitted at most once per second while failures happen. Persistent failures typically
+    /// mean the peers disagree on the keying material, or that someone tampers with the packets.
+    SrtpAuthFailure(u64),
+
+    /// A frame starts or ends in an incoming encoded stream.
+    ///
+    /// Only emitted when enabled using
+    /// [`StreamRx::set_frame_boundary_events()`][crate::rtp::StreamRx::set_frame_boundary_events].
+    FrameBoundary(FrameBoundary),
+
     /// Incoming RTP data.
     RtpPacket(RtpPacket),
 
+    /// Incoming RTP packets that the jitter buffer declared lost.
+    ///
+    /// Only emitted when the jitter buffer is enabled using
+    /// [`StreamRx::set_target_delay()`][crate::rtp::StreamRx::set_target_delay].
+    RtpPacketsLost(RtpPacketsLost),
+
+    /// The jitter buffer of an incoming encoded stream underran or overran.
+    ///
+    /// Only emitted when enabled using
+    /// [`StreamRx::set_jitter_buffer_events()`][crate::rtp::StreamRx::set_jitter_buffer_events].
+    JitterBuffer(JitterBufferEvent),
+
     /// Debug output of incoming and outgoing RTCP/RTP packets.
     ///
     /// Enable using [`RtcConfig::enable_raw_packets()`].
@@ -993,6 +1182,11 @@ pub enum Reason {
     /// Includes checking candidate pairs and various cleanups.
     Ice,
 
+    /// The DTLS handshake.
+    ///
+    /// Retransmitting handshake flights that the remote peer hasn't answered.
+    Dtls,
+
     /// The SCTP subsystem.
     ///
     /// Things like handling retransmissions and keep-alive checks.
@@ -1029,6 +1223,16 @@ pub enum Reason {
     /// Whenever an RTP receive stream receives data, a new timeout is scheduled.
     PauseCheck,
 
+    /// RTP streams with activity tracking going inactive.
+    ///
+    /// Whenever such a stream receives data, a new timeout is scheduled.
+    ActivityCheck,
+
+    /// Releasing RTP packets from jitter buffers (if enabled).
+    ///
+    /// Held packets are released once they reach the playout delay.
+    JitterBuffer,
+
     /// Preprocessing of RTP packets to be sent.
     ///
     /// Housekeeping task in RTP send streams.
@@ -1106,23 +1310,34 @@ impl Rtc {
             }
         };
 
+        let cipher_suites = config.dtls_cipher_suites.as_deref();
+        let mut dtls = Dtls::new(dtls_cert, cipher_suites).expect("DTLS to init without problem");
+        dtls.set_retransmit(config.dtls_handshake_timeout, config.dtls_max_retransmits);
+        dtls.set_mtu(config.dtls_mtu)
+            .expect("DTLS MTU to be set without problem");
+
         Rtc {
             alive: true,
             ice,
-            dtls: Dtls::new(dtls_cert).expect("DTLS to init without problem"),
+            dtls,
             session,
             sctp: RtcSctp::new(),
             chan: ChannelHandler::default(),
             stats: config.stats_interval.map(Stats::new),
             remote_fingerprint: None,
+            local_description: None,
+            remote_description: None,
             remote_addrs: vec![],
             send_addr: None,
             need_init_time: true,
             last_now: already_happened(),
             peer_bytes_rx: 0,
             peer_bytes_tx: 0,
+            transport_stats: TransportStats::default(),
             change_counter: 0,
             last_timeout_reason: Reason::NotHappening,
+            #[cfg(feature = "pcap")]
+            pcap: config.pcap.clone(),
         }
     }
 
@@ -1214,6 +1429,74 @@ impl Rtc {
         self.ice.add_remote_candidate(c);
     }
 
+    /// Add a remote ICE candidate trickled from the peer in its SDP string form.
+    ///
+    /// This corresponds to the `candidate` and `sdpMid` of a browser `RTCIceCandidate`.
+    /// The `a=` prefix is optional. An empty string, or `end-of-candidates`, signals that
+    /// no more remote candidates are coming (see [`Rtc::set_remote_end_of_candidates()`]).
+    ///
+    /// str0m always uses BUNDLE, so all candidates are for the same transport. The `mid`,
+    /// if given, must be in the session.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let mut rtc = Rtc::new();
+    ///
+    /// rtc.add_remote_candidate_str("candidate:1 1 udp 2113929471 1.2.3.4 5000 typ host", None)
+    ///     .unwrap();
+    ///
+    /// // End-of-candidates.
+    /// rtc.add_remote_candidate_str("", None).unwrap();
+    ///
+    /// // The mid is not in the session.
+    /// let c = "candidate:2 1 udp 2113929471 1.2.3.4 5001 typ host";
+    /// assert!(rtc.add_remote_candidate_str(c, Some("0".into())).is_err());
+    /// ```
+    pub fn add_remote_candidate_str(
+        &mut self,
+        candidate: &str,
+        mid: Option<Mid>,
+    ) -> Result<(), RtcError> {
+        let candidate = candidate.trim();
+        let candidate = candidate.strip_prefix("a=").unwrap_or(candidate);
+
+        if candidate.is_empty() || candidate == "end-of-candidates" {
+            self.set_remote_end_of_candidates();
+            return Ok(());
+        }
+
+        if let Some(mid) = mid {
+            let is_app = self.session.app().map(|(m, _)| m) == Some(mid);
+            if !is_app && self.session.media_by_mid(mid).is_none() {
+                return Err(RtcError::UnknownMid(mid));
+            }
+        }
+
+        let c = Candidate::from_sdp_string(candidate)?;
+        self.add_remote_candidate(c);
+
+        Ok(())
+    }
+
+    /// Signal that the remote peer will not send any more candidates.
+    ///
+    /// With trickle ICE, the connection can otherwise not know whether more candidates
+    /// are coming. Once signaled, if no candidate pair succeeds, the ICE state goes to
+    /// [`IceConnectionState::Failed`] as soon as the checks of all pairs timed out.
+    ///
+    /// This is also done when the remote SDP contains `a=end-of-candidates`.
+    pub fn set_remote_end_of_candidates(&mut self) {
+        self.ice.set_remote_end_of_candidates();
+    }
+
+    /// Signal that all local candidates have been added.
+    ///
+    /// This emits [`Event::LocalEndOfCandidates`] for trickle ICE, and makes subsequent
+    /// SDP include `a=end-of-candidates`.
+    pub fn set_local_end_of_candidates(&mut self) {
+        self.ice.set_local_end_of_candidates();
+    }
+
     /// Checks if we are connected.
     ///
     /// This tests both if we have ICE connection and DTLS is ready.
@@ -1222,6 +1505,291 @@ impl Rtc {
         self.ice.state().is_connected() && self.dtls.is_connected()
     }
 
+    /// State of the DTLS handshake.
+    ///
+    /// ```
+    /// # use str0m::{Rtc, DtlsState};
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.dtls_state(), DtlsState::Init);
+    /// ```
+    pub fn dtls_state(&self) -> DtlsState {
+        self.dtls.state()
+    }
+
+    /// The SRTP protection profile negotiated in the DTLS handshake.
+    ///
+    /// `None` until the handshake has completed, which is signaled by [`Event::Connected`].
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.srtp_profile(), None);
+    /// ```
+    pub fn srtp_profile(&self) -> Option<SrtpProfile> {
+        self.session.srtp_profile()
+    }
+
+    /// The cipher suite negotiated in the DTLS handshake.
+    ///
+    /// `None` until the handshake has completed. The suites offered are set using
+    /// [`RtcConfig::set_dtls_cipher_suites()`].
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.dtls_cipher_suite(), None);
+    /// ```
+    pub fn dtls_cipher_suite(&self) -> Option<DtlsCipherSuite> {
+        self.dtls.cipher_suite()
+    }
+
+    /// The number of packets left before the SRTP key must not be used anymore.
+    ///
+    /// SRTP allows 2^48 RTP packets per SSRC, and 2^31 RTCP packets, to be protected with
+    /// the same key. This is the least number of packets left for any outgoing SSRC, or
+    /// for RTCP. The keys come from the DTLS handshake and str0m can't renew them, which
+    /// means a very long lived session must be replaced before this reaches zero.
+    /// See [`Event::SrtpLimitApproaching`].
+    ///
+    /// `None` until the DTLS handshake has completed.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.srtp_packets_left(), None);
+    /// ```
+    pub fn srtp_packets_left(&self) -> Option<u64> {
+        self.session.srtp_packets_left()
+    }
+
+    /// Counters for all datagrams sent and received on the wire.
+    ///
+    /// Unlike [`Event::PeerStats`], this is available at any time, and breaks down
+    /// the traffic by STUN, DTLS, SRTP and SRTCP. Useful to reconcile with socket counters.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// let stats = rtc.transport_stats();
+    /// assert_eq!(stats.total.bytes_tx, 0);
+    /// assert!(stats.selected_pair.is_none());
+    /// ```
+    pub fn transport_stats(&self) -> TransportStats {
+        let mut stats = self.transport_stats.clone();
+
+        stats.srtp_auth_failures = self.session.srtp_auth_failures();
+
+        stats.selected_pair = self.send_addr.as_ref().map(|s| SelectedPair {
+            proto: s.proto,
+            local: s.source,
+            remote: s.destination,
+        });
+
+        stats
+    }
+
+    /// Statistics of the queue of outgoing packets waiting to be paced out.
+    ///
+    /// A queue that keeps growing means the available bandwidth is lower than the bitrate
+    /// written, and the application should lower the bitrate of the source.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// let stats = rtc.pacer_stats();
+    /// assert_eq!(stats.queue_packets, 0);
+    /// assert_eq!(stats.max_queue_time, None);
+    /// ```
+    pub fn pacer_stats(&self) -> PacerStats {
+        self.session.streams.pacer_stats(self.last_now)
+    }
+
+    /// Payload bytes sent for media, resends and padding, summed over all outgoing streams.
+    ///
+    /// Useful to see how much the loss recovery and bandwidth estimation cost.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// let stats = rtc.overhead_stats();
+    /// assert_eq!(stats.rtx_bytes, 0);
+    /// assert_eq!(stats.rtx_percent(), None);
+    /// ```
+    pub fn overhead_stats(&self) -> OverheadStats {
+        self.session.streams.overhead_stats()
+    }
+
+    /// How much more could be sent according to the bandwidth estimate.
+    ///
+    /// This is the current BWE estimate minus the rate of outgoing RTP packets over the last
+    /// second, including resends and padding. A positive value means there is room to raise
+    /// the encoder bitrate. The value is negative when sending more than the estimate, such
+    /// as right after the estimate dropped.
+    ///
+    /// The value is derived when called, as of the last [`Rtc::handle_input()`]. None unless
+    /// BWE is enabled via [`RtcConfig::enable_bwe()`] and has an estimate.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.send_headroom(), None);
+    /// ```
+    pub fn send_headroom(&self) -> Option<Bitrate> {
+        self.session.send_headroom(self.last_now)
+    }
+
+    /// The local and remote address of the ICE candidate pair media is sent over.
+    ///
+    /// This is the pair nominated by the ICE agent. It can change over time, such as after
+    /// an ICE restart or when a better path is found. None until a pair is nominated.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.selected_candidate_pair(), None);
+    /// ```
+    pub fn selected_candidate_pair(&self) -> Option<(SocketAddr, SocketAddr)> {
+        self.send_addr.as_ref().map(|s| (s.source, s.destination))
+    }
+
+    /// Set the max size of outgoing SRTP packets.
+    ///
+    /// This is the size of the UDP payload, and should be set to fit the path MTU to
+    /// avoid IP fragmentation. Payloads are packetized to fit, taking the SRTP overhead
+    /// and the RTP header extensions into account. The size of payloads written with
+    /// [`StreamTx::write_rtp()`][crate::rtp::StreamTx::write_rtp] is up to the application,
+    /// see [`Rtc::max_payload_size()`].
+    ///
+    /// When not set, payloads are packetized to a fixed size regardless of the RTP header,
+    /// which targets a 1150 byte UDP payload for typical headers.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let mut rtc = Rtc::new();
+    /// assert_eq!(rtc.rtp_mtu(), None);
+    ///
+    /// rtc.set_rtp_mtu(1000);
+    /// assert_eq!(rtc.rtp_mtu(), Some(1000));
+    /// ```
+    pub fn set_rtp_mtu(&mut self, mtu: usize) {
+        self.session.rtp_mtu = Some(mtu);
+    }
+
+    /// Set the max number of incoming (rx) and outgoing (tx) encoded streams.
+    ///
+    /// This limits the resources a remote peer can make us use by announcing or sending
+    /// many SSRCs. New incoming streams above the limit are ignored and reported using
+    /// [`Event::StreamRejected`]. New outgoing streams from SDP negotiation above the limit
+    /// are not created. Existing streams are kept when lowering the limit.
+    ///
+    /// Streams added explicitly using the [`DirectApi`][crate::change::DirectApi] are not
+    /// limited, but count towards the limit.
+    ///
+    /// An RTX SSRC is part of its main stream and is not counted separately.
+    ///
+    /// Defaults to 1024 for both.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let mut rtc = Rtc::new();
+    /// assert_eq!(rtc.max_streams(), (1024, 1024));
+    ///
+    /// rtc.set_max_streams(50, 10);
+    /// assert_eq!(rtc.max_streams(), (50, 10));
+    /// ```
+    pub fn set_max_streams(&mut self, rx: usize, tx: usize) {
+        self.session.streams.max_streams_rx = rx;
+        self.session.streams.max_streams_tx = tx;
+    }
+
+    /// The max number of incoming (rx) and outgoing (tx) encoded streams.
+    ///
+    /// See [`Rtc::set_max_streams()`].
+    pub fn max_streams(&self) -> (usize, usize) {
+        (
+            self.session.streams.max_streams_rx,
+            self.session.streams.max_streams_tx,
+        )
+    }
+
+    /// Set a session wide limit for keyframe requests (PLI/FIR) sent to the remote.
+    ///
+    /// At most `max` keyframe requests are sent for all incoming streams, across all mids,
+    /// within any `window`. This prevents a storm of PLI to a sender when many viewers
+    /// of an SFU reconnect at once. Requests above the limit are held back in their
+    /// stream, coalesced with later requests, and sent when the limit allows. Streams
+    /// that have waited the longest go first.
+    ///
+    /// This is on top of the per stream interval set by
+    /// [`StreamRx::set_keyframe_request_interval()`][crate::rtp::StreamRx::set_keyframe_request_interval].
+    ///
+    /// Defaults to no limit.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use std::time::Duration;
+    /// let mut rtc = Rtc::new();
+    /// assert_eq!(rtc.global_keyframe_request_rate().0, usize::MAX);
+    ///
+    /// rtc.set_global_keyframe_request_rate(10, Duration::from_secs(1));
+    /// assert_eq!(
+    ///     rtc.global_keyframe_request_rate(),
+    ///     (10, Duration::from_secs(1))
+    /// );
+    /// ```
+    pub fn set_global_keyframe_request_rate(&mut self, max: usize, window: Duration) {
+        self.session.streams.keyframe_request_max = max;
+        self.session.streams.keyframe_request_window = window;
+    }
+
+    /// The session wide limit for keyframe requests, as max requests per window.
+    ///
+    /// See [`Rtc::set_global_keyframe_request_rate()`].
+    pub fn global_keyframe_request_rate(&self) -> (usize, Duration) {
+        (
+            self.session.streams.keyframe_request_max,
+            self.session.streams.keyframe_request_window,
+        )
+    }
+
+    /// The max size of outgoing SRTP packets.
+    ///
+    /// None unless set with [`Rtc::set_rtp_mtu()`].
+    pub fn rtp_mtu(&self) -> Option<usize> {
+        self.session.rtp_mtu
+    }
+
+    /// The max RTP payload size for the outgoing stream to fit [`Rtc::rtp_mtu()`].
+    ///
+    /// This is for applications packetizing the payload for
+    /// [`StreamTx::write_rtp()`][crate::rtp::StreamTx::write_rtp]. The RTP header size
+    /// depends on the extensions negotiated for the media. The header extension values
+    /// str0m sets are accounted for, and any values set by the application once packets
+    /// with them have been sent, hence the size can shrink as the stream is used.
+    ///
+    /// Without [`Rtc::set_rtp_mtu()`], this is the fixed default payload size.
+    ///
+    /// None if there is no outgoing stream with the SSRC.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let mut rtc = Rtc::new();
+    ///
+    /// assert_eq!(rtc.max_payload_size(42.into()), None);
+    /// ```
+    pub fn max_payload_size(&mut self, ssrc: Ssrc) -> Option<usize> {
+        self.session.max_payload_size(ssrc)
+    }
+
     /// Make changes to the Rtc session via SDP.
     ///
     /// ```no_run
@@ -1246,6 +1814,40 @@ impl Rtc {
         SdpApi::new(self)
     }
 
+    /// The local session description of the last completed negotiation.
+    ///
+    /// This is the [`SdpAnswer`][change::SdpAnswer] returned by
+    /// [`SdpApi::accept_offer()`][change::SdpApi::accept_offer], or the
+    /// [`SdpOffer`][change::SdpOffer] for an answer given to
+    /// [`SdpApi::accept_answer()`][change::SdpApi::accept_answer]. Offers that are
+    /// pending an answer are not included. None until the first negotiation.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert!(rtc.local_description().is_none());
+    /// ```
+    pub fn local_description(&self) -> Option<&SdpDescription> {
+        self.local_description.as_ref()
+    }
+
+    /// The remote session description of the last completed negotiation.
+    ///
+    /// This is the offer given to [`SdpApi::accept_offer()`][change::SdpApi::accept_offer],
+    /// or the answer given to [`SdpApi::accept_answer()`][change::SdpApi::accept_answer].
+    /// None until the first negotiation.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// assert!(rtc.remote_description().is_none());
+    /// ```
+    pub fn remote_description(&self) -> Option<&SdpDescription> {
+        self.remote_description.as_ref()
+    }
+
     /// Makes direct changes to the Rtc session.
     ///
     /// This is a low level API. For "normal" use via SDP, see [`Rtc::sdp_api()`].
@@ -1297,6 +1899,23 @@ impl Rtc {
         self.session.media_by_mid(mid)
     }
 
+    /// Incoming streams grouped by the source they originate from.
+    ///
+    /// Streams are grouped by the CNAME received in RTCP SDES. The members of a group
+    /// can be synchronized using their sender reports, which is what is needed for lip-sync.
+    /// Streams for which we have not yet received a CNAME are not included.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let rtc = Rtc::new();
+    ///
+    /// // No streams before any media is received.
+    /// assert!(rtc.sync_groups().is_empty());
+    /// ```
+    pub fn sync_groups(&self) -> Vec<SyncGroup> {
+        self.session.streams.sync_groups()
+    }
+
     fn init_dtls(&mut self, active: bool) -> Result<(), RtcError> {
         if self.dtls.is_inited() {
             return Ok(());
@@ -1346,6 +1965,12 @@ impl Rtc {
     /// 1. The polled timeout is reached.
     /// 2. New network input.
     ///
+    /// All events are returned before any [`Output::Transmit`]. Once a transmit is returned,
+    /// there are no further events until the next input. This means the effects of an input,
+    /// such as [`Event::MediaAdded`] or [`Event::RtpPacket`] for a received packet, are seen
+    /// before anything str0m sends in response to it. The exception is [`Event::RawPacket`]
+    /// for sent RTCP, which is produced alongside the transmit.
+    ///
     /// See [`Rtc`] instance documentation for how this is expected to be used in a loop.
     pub fn poll_output(&mut self) -> Result<Output, RtcError> {
         let o = self.do_poll_output()?;
@@ -1359,6 +1984,7 @@ impl Rtc {
             },
             Output::Transmit(t) => {
                 self.peer_bytes_tx += t.contents.len() as u64;
+                self.transport_stats.count_tx(&t.contents);
                 trace!("OUT {:?}", t)
             }
             Output::Timeout(_t) => {}
@@ -1381,6 +2007,15 @@ impl Rtc {
                 IceAgentEvent::IceConnectionStateChange(v) => {
                     return Ok(Output::Event(Event::IceConnectionStateChange(v)))
                 }
+                IceAgentEvent::NewLocalCandidate(v) => {
+                    return Ok(Output::Event(Event::LocalIceCandidate(v)))
+                }
+                IceAgentEvent::LocalEndOfCandidates => {
+                    return Ok(Output::Event(Event::LocalEndOfCandidates))
+                }
+                IceAgentEvent::IceRoleChange(v) => {
+                    return Ok(Output::Event(Event::IceRoleChange(v)))
+                }
                 IceAgentEvent::DiscoveredRecv { proto, source } => {
                     info!("ICE remote address: {:?}/{:?}", source, proto);
                     self.remote_addrs.push(source);
@@ -1397,11 +2032,27 @@ impl Rtc {
                         "ICE nominated send from: {:?} to: {:?} with protocol {:?}",
                         source, destination, proto,
                     );
+                    let changed = self
+                        .send_addr
+                        .as_ref()
+                        .map(|s| (s.proto, s.source, s.destination) != (proto, source, destination))
+                        .unwrap_or(true);
+
                     self.send_addr = Some(SendAddr {
                         proto,
                         source,
                         destination,
                     });
+
+                    if changed {
+                        return Ok(Output::Event(Event::SelectedCandidatePairChange(
+                            SelectedPair {
+                                proto,
+                                local: source,
+                                remote: destination,
+                            },
+                        )));
+                    }
                 }
             }
         }
@@ -1427,11 +2078,11 @@ impl Rtc {
                     if let Some(v2) = &self.remote_fingerprint {
                         if v1 != *v2 {
                             self.disconnect();
-                            return Err(RtcError::RemoteSdp("remote fingerprint no match".into()));
+                            return Err(error::SdpError::FingerprintMismatch.into());
                         }
                     } else {
                         self.disconnect();
-                        return Err(RtcError::RemoteSdp("no a=fingerprint before dtls".into()));
+                        return Err(error::SdpError::MissingFingerprint.into());
                     }
                 }
                 DtlsEvent::Data(v) => {
@@ -1444,25 +2095,43 @@ impl Rtc {
             return Ok(Output::Event(Event::Connected));
         }
 
-        while let Some(e) = self.sctp.poll() {
+        if self.dtls.poll_timed_out() {
+            return Ok(Output::Event(Event::DtlsHandshakeTimeout));
+        }
+
+        // Set when DTLS can't take more SCTP packets.
+        let mut transmit_blocked = false;
+
+        loop {
+            let polled = if transmit_blocked {
+                self.sctp.poll_event()
+            } else {
+                self.sctp.poll()
+            };
+
+            let Some(e) = polled else {
+                break;
+            };
+
             match e {
                 SctpEvent::Transmit { mut packets } => {
-                    if let Some(v) = packets.front() {
+                    // Hand all packets to DTLS and carry on polling, to not return
+                    // any SCTP event after the transmit it caused.
+                    while let Some(v) = packets.front() {
                         if let Err(e) = self.dtls.handle_input(v) {
                             if e.is_would_block() {
-                                self.sctp.push_back_transmit(packets);
                                 break;
                             } else {
                                 return Err(e.into());
                             }
                         }
                         packets.pop_front();
-                        // If there are still packets, they are sent on next
-                        // poll_output()
-                        if !packets.is_empty() {
-                            self.sctp.push_back_transmit(packets);
-                        }
-                        break;
+                    }
+                    // If DTLS can't take more, the rest is sent on next poll_output(). The
+                    // events are still polled, to come before the transmits DTLS did take.
+                    if !packets.is_empty() {
+                        self.sctp.push_back_transmit(packets);
+                        transmit_blocked = true;
                     }
                 }
                 SctpEvent::Open { id, label } => {
@@ -1507,22 +2176,38 @@ impl Rtc {
         }
 
         if let Some(v) = self.ice.poll_transmit() {
+            #[cfg(feature = "pcap")]
+            self.pcap_write(v.source, v.destination, Some(&v.contents));
             return Ok(Output::Transmit(v));
         }
 
         if let Some(send) = &self.send_addr {
             // These can only be sent after we got an ICE connection.
             let datagram = None
-                .or_else(|| self.dtls.poll_datagram())
+                .or_else(|| {
+                    let d = self.dtls.poll_datagram(self.last_now)?;
+                    Some((d, io::Marking::default()))
+                })
                 .or_else(|| self.session.poll_datagram(self.last_now));
 
-            if let Some(contents) = datagram {
+            if let Some((contents, marking)) = datagram {
                 let t = net::Transmit {
                     proto: send.proto,
                     source: send.source,
                     destination: send.destination,
                     contents,
+                    dscp: marking.dscp,
+                    ecn: marking.ecn,
                 };
+
+                // The session queues SRTP decrypted, DTLS is written as is.
+                #[cfg(feature = "pcap")]
+                {
+                    let is_dtls = matches!(t.contents.first(), Some(20..=63));
+                    let wire = is_dtls.then_some(&t.contents[..]);
+                    self.pcap_write(t.source, t.destination, wire);
+                }
+
                 return Ok(Output::Transmit(t));
             }
         }
@@ -1532,6 +2217,7 @@ impl Rtc {
         let time_and_reason = (None, Reason::NotHappening)
             .soonest((self.ice.poll_timeout(), Reason::Ice))
             .soonest(self.session.poll_timeout())
+            .soonest((self.dtls.poll_timeout(), Reason::Dtls))
             .soonest((self.sctp.poll_timeout(), Reason::Sctp))
             .soonest((self.chan.poll_timeout(&self.sctp), Reason::Channel))
             .soonest((stats.and_then(|s| s.poll_timeout()), Reason::Stats));
@@ -1692,6 +2378,7 @@ impl Rtc {
 
         self.last_now = now;
         self.ice.handle_timeout(now);
+        self.dtls.handle_timeout(now);
         self.sctp.handle_timeout(now);
         self.chan.handle_timeout(now, &mut self.sctp);
         self.session.handle_timeout(now)?;
@@ -1723,6 +2410,22 @@ impl Rtc {
         };
 
         self.peer_bytes_rx += bytes_rx as u64;
+        self.transport_stats.count_rx(&r.contents.inner);
+
+        #[cfg(feature = "pcap")]
+        match &r.contents.inner {
+            Stun(stun) => {
+                // The original bytes are gone, and we don't need the right password
+                // for the message integrity to inspect the capture.
+                let mut buf = vec![0; io::DATAGRAM_MAX_PACKET_SIZE];
+                if let Ok(n) = stun.to_bytes("", &mut buf) {
+                    self.pcap_write(r.source, r.destination, Some(&buf[..n]));
+                }
+            }
+            Dtls(dtls) => self.pcap_write(r.source, r.destination, Some(dtls)),
+            // Written decrypted after handling.
+            Rtp(_) | Rtcp(_) => {}
+        }
 
         match r.contents.inner {
             Stun(stun) => {
@@ -1735,13 +2438,32 @@ impl Rtc {
                 self.ice.handle_packet(now, packet);
             }
             Dtls(dtls) => self.dtls.handle_receive(dtls)?,
-            Rtp(rtp) => self.session.handle_rtp_receive(now, rtp),
+            Rtp(rtp) => self.session.handle_rtp_receive(now, rtp, r.ecn),
             Rtcp(rtcp) => self.session.handle_rtcp_receive(now, rtcp),
         }
 
+        #[cfg(feature = "pcap")]
+        self.pcap_write(r.source, r.destination, None);
+
         Ok(())
     }
 
+    /// Write a packet, and any decrypted RTP/RTCP queued by the session, to the pcap capture.
+    #[cfg(feature = "pcap")]
+    fn pcap_write(&mut self, source: SocketAddr, destination: SocketAddr, wire: Option<&[u8]>) {
+        let Some(pcap) = &self.pcap else {
+            return;
+        };
+
+        if let Some(wire) = wire {
+            pcap.write(self.last_now, source, destination, wire);
+        }
+
+        while let Some(packet) = self.session.poll_pcap_packet() {
+            pcap.write(self.last_now, source, destination, &packet);
+        }
+    }
+
     /// Obtain handle for writing to a data channel.
     ///
     /// This is first available when a [`ChannelId`] is advertised via [`Event::ChannelOpen`].
@@ -1771,6 +2493,16 @@ impl Rtc {
         Some(Channel::new(sctp_stream_id, self))
     }
 
+    /// Force sending RTCP sender/receiver reports (SR/RR) as soon as possible.
+    ///
+    /// Normally reports are sent every second for video and every 5 seconds for audio.
+    /// This makes the next [`Rtc::poll_output()`] time out immediately with
+    /// [`Reason::Feedback`], after which the regular interval resumes. This can be useful
+    /// to get a fresh RTT right before a stats snapshot.
+    pub fn schedule_immediate_rtcp(&mut self) {
+        self.session.streams.schedule_immediate_feedback();
+    }
+
     /// Configure the Bandwidth Estimate (BWE) subsystem.
     ///
     /// Only relevant if BWE was enabled in the [`RtcConfig::enable_bwe()`]
@@ -1813,6 +2545,10 @@ pub struct RtcConfig {
     local_ice_credentials: Option<IceCreds>,
     dtls_cert: Option<DtlsCert>,
     fingerprint_verification: bool,
+    dtls_handshake_timeout: Duration,
+    dtls_max_retransmits: usize,
+    dtls_mtu: usize,
+    dtls_cipher_suites: Option<Vec<DtlsCipherSuite>>,
     ice_lite: bool,
     codec_config: CodecConfig,
     exts: ExtensionMap,
@@ -1821,10 +2557,28 @@ pub struct RtcConfig {
     bwe_initial_bitrate: Option<Bitrate>,
     reordering_size_audio: usize,
     reordering_size_video: usize,
+    max_frame_size: usize,
+    incomplete_frame_timeout: Duration,
     send_buffer_audio: usize,
     send_buffer_video: usize,
     rtp_mode: bool,
     enable_raw_packets: bool,
+    rtcp_observer: Option<Arc<dyn RtcpObserver>>,
+    rtcp_compound: bool,
+    rtcp_mux_only: bool,
+    bundle_policy: BundlePolicy,
+    ecn: bool,
+    cname: Option<String>,
+    layer_thresholds: (f64, f64),
+    demux_policy: DemuxPolicy,
+    unknown_pt_policy: UnknownPtPolicy,
+    initial_seq_range: RangeInclusive<u16>,
+    pacing_priority_audio: u8,
+    pacing_priority_video: u8,
+    early_media_buffer: Option<Duration>,
+    srtp_limit_margin: u64,
+    #[cfg(feature = "pcap")]
+    pcap: Option<pcap::PcapWriter>,
 }
 
 impl RtcConfig {
@@ -1842,9 +2596,15 @@ impl RtcConfig {
     }
 
     /// Explicitly sets local ICE credentials.
-    pub fn set_local_ice_credentials(mut self, local_ice_credentials: IceCreds) -> Self {
+    ///
+    /// Fails if the credentials are invalid according to [`IceCreds::validate()`].
+    pub fn set_local_ice_credentials(
+        mut self,
+        local_ice_credentials: IceCreds,
+    ) -> Result<Self, error::IceError> {
+        local_ice_credentials.validate()?;
         self.local_ice_credentials = Some(local_ice_credentials);
-        self
+        Ok(self)
     }
 
     /// Get the configured DTLS certificate, if set.
@@ -1887,6 +2647,9 @@ impl RtcConfig {
     /// An [`Rtc`] instance in ice lite mode will not make STUN binding requests, but only
     /// answer to requests from the remote peer.
     ///
+    /// The SDP is signalled with `a=ice-lite`, and the instance is always the ICE controlled
+    /// side, using the pair nominated by the remote peer.
+    ///
     /// See [ICE RFC][1]
     ///
     /// [1]: https://www.rfc-editor.org/rfc/rfc8445#page-13
@@ -1916,6 +2679,118 @@ impl RtcConfig {
         self
     }
 
+    /// Initial timeout before retransmitting an unanswered DTLS handshake flight.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use std::time::Duration;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 1 second.
+    /// assert_eq!(config.dtls_handshake_timeout(), Duration::from_secs(1));
+    /// ```
+    pub fn dtls_handshake_timeout(&self) -> Duration {
+        self.dtls_handshake_timeout
+    }
+
+    /// Set the initial timeout before retransmitting an unanswered DTLS handshake flight.
+    ///
+    /// The timeout doubles for every retransmit (up to 60 seconds). After
+    /// [`RtcConfig::dtls_max_retransmits()`] retransmits, the handshake fails with
+    /// [`Event::DtlsHandshakeTimeout`].
+    ///
+    /// Panics if the timeout is zero.
+    pub fn set_dtls_handshake_timeout(mut self, timeout: Duration) -> Self {
+        assert!(!timeout.is_zero(), "DTLS handshake timeout must be > 0");
+        self.dtls_handshake_timeout = timeout;
+        self
+    }
+
+    /// Number of DTLS handshake flight retransmits before giving up.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 6.
+    /// assert_eq!(config.dtls_max_retransmits(), 6);
+    /// ```
+    pub fn dtls_max_retransmits(&self) -> usize {
+        self.dtls_max_retransmits
+    }
+
+    /// Set the number of DTLS handshake flight retransmits before giving up.
+    pub fn set_dtls_max_retransmits(mut self, max: usize) -> Self {
+        self.dtls_max_retransmits = max;
+        self
+    }
+
+    /// Max size of the DTLS datagrams sent during the handshake.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 1150.
+    /// assert_eq!(config.dtls_mtu(), 1150);
+    /// ```
+    pub fn dtls_mtu(&self) -> usize {
+        self.dtls_mtu
+    }
+
+    /// Set the max size of the DTLS datagrams sent during the handshake.
+    ///
+    /// Handshake messages that don't fit, typically the certificate, are fragmented
+    /// over several datagrams. Lower this for paths with a smaller MTU than the default.
+    ///
+    /// Panics if the MTU is less than 256.
+    pub fn set_dtls_mtu(mut self, mtu: usize) -> Self {
+        assert!(mtu >= 256, "DTLS MTU must be at least 256");
+        self.dtls_mtu = mtu;
+        self
+    }
+
+    /// The DTLS cipher suites offered in the handshake, if configured.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to None, which offers str0m's default selection of suites.
+    /// assert_eq!(config.dtls_cipher_suites(), None);
+    /// ```
+    pub fn dtls_cipher_suites(&self) -> Option<&[DtlsCipherSuite]> {
+        self.dtls_cipher_suites.as_deref()
+    }
+
+    /// Set the DTLS cipher suites offered in the handshake, in order of preference.
+    ///
+    /// The order is used both when str0m is the DTLS client and the server. If the remote
+    /// peer supports none of the suites, the handshake fails with an error from
+    /// [`Rtc::handle_input()`]. The suite in use is available via
+    /// [`Rtc::dtls_cipher_suite()`] once connected.
+    ///
+    /// str0m's own certificate is RSA, so at least one of the RSA suites is needed
+    /// unless the remote peer is the DTLS server with an ECDSA certificate.
+    ///
+    /// ```
+    /// # use str0m::{Rtc, DtlsCipherSuite};
+    /// let config = Rtc::builder()
+    ///     .set_dtls_cipher_suites(&[DtlsCipherSuite::EcdheRsaAes128GcmSha256]);
+    ///
+    /// assert_eq!(
+    ///     config.dtls_cipher_suites(),
+    ///     Some(&[DtlsCipherSuite::EcdheRsaAes128GcmSha256][..])
+    /// );
+    /// ```
+    ///
+    /// Panics if `suites` is empty.
+    pub fn set_dtls_cipher_suites(mut self, suites: &[DtlsCipherSuite]) -> Self {
+        assert!(!suites.is_empty(), "At least one DTLS cipher suite");
+        self.dtls_cipher_suites = Some(suites.to_vec());
+        self
+    }
+
     /// Tells whether ice lite is enabled.
     ///
     /// ```
@@ -1974,7 +2849,7 @@ impl RtcConfig {
         self
     }
 
-    // TODO: AV1 depacketizer/packetizer.
+    // TODO: AV1 packetizer.
     //
     // /// Enable AV1 video codec.
     // ///
@@ -2029,7 +2904,8 @@ impl RtcConfig {
     ///
     /// The media level will be capped by the extension enabled on session level.
     ///
-    /// The id must be 1-14 inclusive (1-indexed).
+    /// The id must be 1-16 inclusive (1-indexed). Ids above 14 are sent using the
+    /// two-byte header extension form.
     pub fn set_extension(mut self, id: u8, ext: Extension) -> Self {
         self.exts.set(id, ext);
         self
@@ -2151,6 +3027,68 @@ impl RtcConfig {
         self.reordering_size_video
     }
 
+    /// Sets the max size in bytes of an incoming frame being reassembled from packets.
+    ///
+    /// A frame growing bigger than this is dropped, and reported with
+    /// [`Event::FrameDropped`]. This bounds the memory a remote peer can make us hold,
+    /// such as by sending H264 FU-A fragments that never end.
+    ///
+    /// Default: 10 000 000
+    ///
+    /// This setting is ignored in [RTP mode][`RtcConfig::set_rtp_mode()`] where RTP
+    /// packets are not depacketized.
+    ///
+    /// Panics if `size` is zero.
+    pub fn set_max_frame_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "max frame size must be above zero");
+        self.max_frame_size = size;
+
+        self
+    }
+
+    /// Returns the max size of an incoming frame.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 10MB.
+    /// assert_eq!(config.max_frame_size(), 10_000_000);
+    /// ```
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    /// Sets how long the packets of an incomplete incoming frame are kept.
+    ///
+    /// Once the first packet of a frame is older than this, and the frame is still not
+    /// complete, it's dropped and reported with [`Event::FrameDropped`]. This is checked
+    /// when packets arrive.
+    ///
+    /// Default: 5 seconds
+    ///
+    /// This setting is ignored in [RTP mode][`RtcConfig::set_rtp_mode()`] where RTP
+    /// packets are not depacketized.
+    pub fn set_incomplete_frame_timeout(mut self, timeout: Duration) -> Self {
+        self.incomplete_frame_timeout = timeout;
+
+        self
+    }
+
+    /// Returns how long the packets of an incomplete incoming frame are kept.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use std::time::Duration;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 5 seconds.
+    /// assert_eq!(config.incomplete_frame_timeout(), Duration::from_secs(5));
+    /// ```
+    pub fn incomplete_frame_timeout(&self) -> Duration {
+        self.incomplete_frame_timeout
+    }
+
     /// Sets the buffer size for outgoing audio packets.
     ///
     /// This must be larger than 0. The value configures an internal ring buffer used as a temporary
@@ -2247,6 +3185,480 @@ impl RtcConfig {
         self
     }
 
+    /// Set an observer of the incoming RTCP.
+    ///
+    /// The observer is called with the decrypted and parsed packets before str0m handles
+    /// them internally. It can't change or prevent the handling. This is useful for logging,
+    /// or for experimental feedback that str0m doesn't handle.
+    ///
+    /// ```
+    /// # use std::time::Instant;
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::rtcp::{Rtcp, RtcpObserver};
+    /// #[derive(Debug)]
+    /// struct LogRtcp;
+    ///
+    /// impl RtcpObserver for LogRtcp {
+    ///     fn on_rtcp_rx(&self, _now: Instant, packets: &[Rtcp]) {
+    ///         println!("{:?}", packets);
+    ///     }
+    /// }
+    ///
+    /// let config = Rtc::builder().set_rtcp_observer(LogRtcp);
+    /// assert!(config.rtcp_observer().is_some());
+    /// ```
+    pub fn set_rtcp_observer(mut self, observer: impl RtcpObserver) -> Self {
+        self.rtcp_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// The observer of incoming RTCP, if set.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to None.
+    /// assert!(config.rtcp_observer().is_none());
+    /// ```
+    pub fn rtcp_observer(&self) -> Option<&dyn RtcpObserver> {
+        self.rtcp_observer.as_deref()
+    }
+
+    /// Set whether RTCP is sent as compound packets.
+    ///
+    /// Media is never coalesced, each RTP packet is sent in a datagram of its own. RTCP
+    /// is by default packed as compound packets (RFC 3550), where all pending reports and
+    /// feedback are sent together in as few datagrams as possible.
+    ///
+    /// When disabled, `a=rtcp-rsize` is added to the SDP media lines. If the remote peer
+    /// also has `a=rtcp-rsize` on all media lines, each RTCP packet is sent in a datagram
+    /// of its own as reduced-size RTCP (RFC 5506). This lowers the latency of feedback like
+    /// NACK and PLI, at the cost of more datagrams. Without a negotiated `a=rtcp-rsize`,
+    /// RTCP stays compound, since peers following RFC 3550 strictly drop RTCP not starting
+    /// with an SR or RR.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().set_rtcp_compound(false);
+    /// assert!(!config.rtcp_compound());
+    /// ```
+    pub fn set_rtcp_compound(mut self, enabled: bool) -> Self {
+        self.rtcp_compound = enabled;
+        self
+    }
+
+    /// Whether RTCP is sent as compound packets.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to true.
+    /// assert!(config.rtcp_compound());
+    /// ```
+    pub fn rtcp_compound(&self) -> bool {
+        self.rtcp_compound
+    }
+
+    /// Require RTCP multiplexing for all media in SDP negotiation.
+    ///
+    /// str0m always sends and receives RTP and RTCP on the same transport (`a=rtcp-mux`).
+    /// With this enabled, offers also carry `a=rtcp-mux-only` (RFC 8858), and m-lines of
+    /// offers or answers without `a=rtcp-mux` are rejected. A rejected m-line is answered
+    /// with port 0 and the [`Media`][crate::media::Media] is made inactive.
+    ///
+    /// Without this, such m-lines are accepted, and RTCP the remote peer sends on a separate
+    /// transport is never received.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().set_rtcp_mux_only(true);
+    /// assert!(config.rtcp_mux_only());
+    /// ```
+    pub fn set_rtcp_mux_only(mut self, enabled: bool) -> Self {
+        self.rtcp_mux_only = enabled;
+        self
+    }
+
+    /// Whether RTCP multiplexing is required for all media.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to false.
+    /// assert!(!config.rtcp_mux_only());
+    /// ```
+    pub fn rtcp_mux_only(&self) -> bool {
+        self.rtcp_mux_only
+    }
+
+    /// Set which m-lines new in an offer can be negotiated on a transport of their own.
+    ///
+    /// str0m always runs all m-lines over one transport, and they are all offered in the
+    /// `a=group:BUNDLE`. Depending on the policy, new m-lines other than the first are
+    /// marked `a=bundle-only`, which tells the remote peer they can only be used bundled.
+    /// Peers that don't support bundle might reject those, which
+    /// [`BundlePolicy::MaxCompat`] avoids.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::change::BundlePolicy;
+    /// let config = Rtc::builder().set_bundle_policy(BundlePolicy::Balanced);
+    /// assert_eq!(config.bundle_policy(), BundlePolicy::Balanced);
+    /// ```
+    pub fn set_bundle_policy(mut self, policy: BundlePolicy) -> Self {
+        self.bundle_policy = policy;
+        self
+    }
+
+    /// The bundle policy for offers.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::change::BundlePolicy;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to max-compat, no m-line is bundle-only.
+    /// assert_eq!(config.bundle_policy(), BundlePolicy::MaxCompat);
+    /// ```
+    pub fn bundle_policy(&self) -> BundlePolicy {
+        self.bundle_policy
+    }
+
+    /// Enable ECN (Explicit Congestion Notification) for RTP, as in RFC 6679.
+    ///
+    /// str0m is sans-IO, and ECN relies on the application to move the codepoints
+    /// between the socket and str0m:
+    ///
+    /// * Outgoing RTP is marked with the hint [`Transmit::ecn`][net::Transmit::ecn] ECT(0).
+    /// * The ECN of incoming datagrams is read from the socket and passed in
+    ///   [`Receive::ecn`][net::Receive::ecn].
+    ///
+    /// The receiver counts the codepoints of incoming RTP and reports them to the sender
+    /// in RTCP ECN feedback. If BWE is enabled, packets marked CE (congestion experienced)
+    /// lower the estimate like congestion detected from the delay.
+    ///
+    /// ECN is negotiated in SDP with `a=ecn-capable-rtp` and is only used when both
+    /// peers enable it.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().enable_ecn(true);
+    /// assert!(config.ecn_enabled());
+    /// ```
+    pub fn enable_ecn(mut self, enabled: bool) -> Self {
+        self.ecn = enabled;
+        self
+    }
+
+    /// Whether ECN is enabled.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to false.
+    /// assert!(!config.ecn_enabled());
+    /// ```
+    pub fn ecn_enabled(&self) -> bool {
+        self.ecn
+    }
+
+    /// Write all sent and received packets to a pcap file for analysis in Wireshark.
+    ///
+    /// STUN and DTLS are written as they are on the wire. RTP and RTCP are written
+    /// decrypted, so no SRTP keys are needed to inspect them. Use "Decode As… RTP" in
+    /// Wireshark, or enable the `rtp_udp` heuristic. Received STUN is re-encoded from
+    /// the parsed message and its MESSAGE-INTEGRITY doesn't match the original. All
+    /// packets are written as UDP, also for [`Protocol::Tcp`][net::Protocol::Tcp].
+    ///
+    /// Configs cloned from this share the writer, which means several [`Rtc`] instances
+    /// write to the same file. This is expensive and should not be enabled outside of
+    /// tests and troubleshooting.
+    ///
+    /// Requires the `pcap` feature.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let file = std::io::sink(); // std::fs::File::create("str0m.pcap")
+    /// let config = Rtc::builder().set_pcap_writer(file);
+    /// assert!(config.has_pcap_writer());
+    /// ```
+    #[cfg(feature = "pcap")]
+    pub fn set_pcap_writer(mut self, out: impl std::io::Write + Send + 'static) -> Self {
+        self.pcap = Some(pcap::PcapWriter::new(out));
+        self
+    }
+
+    /// Whether packets are written to a pcap file.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to false.
+    /// assert!(!config.has_pcap_writer());
+    /// ```
+    #[cfg(feature = "pcap")]
+    pub fn has_pcap_writer(&self) -> bool {
+        self.pcap.is_some()
+    }
+
+    /// Set the thresholds for stopping and resuming simulcast layers.
+    ///
+    /// Applies to layers managed with [`StreamTx::set_layer_bitrate()`][crate::rtp::StreamTx::set_layer_bitrate].
+    /// The thresholds are factors of the bitrate needed for a layer and all lower layers.
+    /// A layer is stopped when the available bitrate falls below `stop` times the needed
+    /// bitrate, and resumed when it reaches `resume` times the needed bitrate. A `resume`
+    /// above `stop` gives hysteresis, which prevents a layer from flapping when the available
+    /// bitrate hovers around the needed bitrate.
+    ///
+    /// Defaults to `(1.0, 1.2)`.
+    ///
+    /// panics if `resume` is less than `stop`.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().set_layer_thresholds(0.9, 1.5);
+    /// assert_eq!(config.layer_thresholds(), (0.9, 1.5));
+    /// ```
+    pub fn set_layer_thresholds(mut self, stop: f64, resume: f64) -> Self {
+        assert!(
+            resume >= stop,
+            "resume threshold must not be less than stop"
+        );
+        self.layer_thresholds = (stop, resume);
+        self
+    }
+
+    /// The thresholds for stopping and resuming simulcast layers.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to (1.0, 1.2).
+    /// assert_eq!(config.layer_thresholds(), (1.0, 1.2));
+    /// ```
+    pub fn layer_thresholds(&self) -> (f64, f64) {
+        self.layer_thresholds
+    }
+
+    /// Set how incoming RTP packets of SSRCs not known are mapped to media and streams.
+    ///
+    /// See [`DemuxPolicy`][crate::rtp::DemuxPolicy].
+    pub fn set_demux_policy(mut self, policy: DemuxPolicy) -> Self {
+        self.demux_policy = policy;
+        self
+    }
+
+    /// The demux policy.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::DemuxBy;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to mid/rid, then mid/pt without buffering.
+    /// let policy = config.demux_policy();
+    /// assert_eq!(policy.order, vec![DemuxBy::MidRid, DemuxBy::MidPt]);
+    /// assert_eq!(policy.buffer_packets, 0);
+    /// ```
+    pub fn demux_policy(&self) -> &DemuxPolicy {
+        &self.demux_policy
+    }
+
+    /// Set what happens to incoming RTP packets with a payload type that is not configured.
+    ///
+    /// See [`UnknownPtPolicy`][crate::rtp::UnknownPtPolicy].
+    pub fn set_unknown_pt_policy(mut self, policy: UnknownPtPolicy) -> Self {
+        self.unknown_pt_policy = policy;
+        self
+    }
+
+    /// The unknown payload type policy.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::UnknownPtPolicy;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to drop.
+    /// assert_eq!(config.unknown_pt_policy(), UnknownPtPolicy::Drop);
+    /// ```
+    pub fn unknown_pt_policy(&self) -> UnknownPtPolicy {
+        self.unknown_pt_policy
+    }
+
+    /// Constrain the random initial RTP sequence numbers of outgoing streams.
+    ///
+    /// The initial sequence numbers are random as recommended by RFC 3550, to make
+    /// known-plaintext attacks harder. This narrows the range they are picked from, such
+    /// as to stay clear of the wrap around when debugging. Resends use the same range.
+    ///
+    /// Defaults to the full range `0..=65535`, which is best for security.
+    ///
+    /// panics if the range is empty.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().set_initial_seq_range(1000..=2000);
+    /// assert_eq!(config.initial_seq_range(), 1000..=2000);
+    /// ```
+    pub fn set_initial_seq_range(mut self, range: RangeInclusive<u16>) -> Self {
+        assert!(!range.is_empty(), "initial sequence number range is empty");
+        self.initial_seq_range = range;
+        self
+    }
+
+    /// The range of random initial RTP sequence numbers.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to the full range.
+    /// assert_eq!(config.initial_seq_range(), 0..=u16::MAX);
+    /// ```
+    pub fn initial_seq_range(&self) -> RangeInclusive<u16> {
+        self.initial_seq_range.clone()
+    }
+
+    /// Set the priority of a media kind when sharing the pacing budget.
+    ///
+    /// When several streams have packets queued, the pacer sends from the stream with the
+    /// highest priority first, and takes turns between streams of equal priority. This
+    /// only matters for paced streams, see [`StreamTx::set_unpaced()`][crate::rtp::StreamTx::set_unpaced].
+    ///
+    /// Defaults to 1 for audio and 0 for video, which means audio is never held back by
+    /// a burst of video.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::media::MediaKind;
+    /// let config = Rtc::builder().set_pacing_priority(MediaKind::Video, 2);
+    /// assert_eq!(config.pacing_priority(MediaKind::Video), 2);
+    /// ```
+    pub fn set_pacing_priority(mut self, kind: MediaKind, priority: u8) -> Self {
+        match kind {
+            MediaKind::Audio => self.pacing_priority_audio = priority,
+            MediaKind::Video => self.pacing_priority_video = priority,
+        }
+        self
+    }
+
+    /// The priority of a media kind when sharing the pacing budget.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::media::MediaKind;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to audio before video.
+    /// assert_eq!(config.pacing_priority(MediaKind::Audio), 1);
+    /// assert_eq!(config.pacing_priority(MediaKind::Video), 0);
+    /// ```
+    pub fn pacing_priority(&self, kind: MediaKind) -> u8 {
+        match kind {
+            MediaKind::Audio => self.pacing_priority_audio,
+            MediaKind::Video => self.pacing_priority_video,
+        }
+    }
+
+    /// Set the max age of media buffered before the connection is ready.
+    ///
+    /// Media written before the DTLS handshake has completed is held back until the SRTP
+    /// keys are ready, and then sent. This lets the application start encoding immediately,
+    /// without knowing when the connection is ready. Media that has waited longer than
+    /// `max_age` is dropped, whole frames at a time from the oldest, rather than sent late.
+    /// The remote sees the first media sent as the start of the stream, so no sequence
+    /// numbers are missing. Dropped packets are reported in
+    /// [`Event::PacketsDropped`][crate::Event::PacketsDropped].
+    ///
+    /// Defaults to None, which holds back all media written until the connection is ready.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use std::time::Duration;
+    /// let config = Rtc::builder().set_early_media_buffer(Duration::from_millis(200));
+    /// assert_eq!(config.early_media_buffer(), Some(Duration::from_millis(200)));
+    /// ```
+    pub fn set_early_media_buffer(mut self, max_age: Duration) -> Self {
+        self.early_media_buffer = Some(max_age);
+        self
+    }
+
+    /// The max age of media buffered before the connection is ready.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to None.
+    /// assert_eq!(config.early_media_buffer(), None);
+    /// ```
+    pub fn early_media_buffer(&self) -> Option<Duration> {
+        self.early_media_buffer
+    }
+
+    /// Set the number of packets left for the SRTP key at which to warn.
+    ///
+    /// When fewer packets than this are left, see [`Rtc::srtp_packets_left()`],
+    /// [`Event::SrtpLimitApproaching`] is emitted.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder().set_srtp_limit_margin(1_000_000);
+    /// assert_eq!(config.srtp_limit_margin(), 1_000_000);
+    /// ```
+    pub fn set_srtp_limit_margin(mut self, margin: u64) -> Self {
+        self.srtp_limit_margin = margin;
+        self
+    }
+
+    /// The number of packets left for the SRTP key at which to warn.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to 2^24.
+    /// assert_eq!(config.srtp_limit_margin(), 1 << 24);
+    /// ```
+    pub fn srtp_limit_margin(&self) -> u64 {
+        self.srtp_limit_margin
+    }
+
+    /// Sets the CNAME used in RTCP SDES and in the `a=ssrc:<ssrc> cname:<cname>` SDP lines.
+    ///
+    /// The CNAME tells the remote peer which streams belong to the same source and
+    /// should be synchronized, such as audio and video from one camera/microphone.
+    /// All media in the [`Rtc`] instance share this CNAME.
+    ///
+    /// panics if longer than 255 bytes.
+    pub fn set_cname(mut self, cname: String) -> Self {
+        assert!(cname.len() <= 255);
+        self.cname = Some(cname);
+        self
+    }
+
+    /// Get the configured CNAME, if set.
+    ///
+    /// If not specified, a random CNAME is generated when building the [`Rtc`] instance.
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// let config = Rtc::builder();
+    ///
+    /// // Defaults to None.
+    /// assert_eq!(config.cname(), None);
+    /// ```
+    pub fn cname(&self) -> Option<&str> {
+        self.cname.as_deref()
+    }
+
     /// Create a [`Rtc`] from the configuration.
     pub fn build(self) -> Rtc {
         Rtc::new_from_config(self)
@@ -2259,6 +3671,10 @@ impl Default for RtcConfig {
             local_ice_credentials: None,
             dtls_cert: None,
             fingerprint_verification: true,
+            dtls_handshake_timeout: DEFAULT_DTLS_HANDSHAKE_TIMEOUT,
+            dtls_max_retransmits: DEFAULT_DTLS_MAX_RETRANSMITS,
+            dtls_mtu: DATAGRAM_MTU,
+            dtls_cipher_suites: None,
             ice_lite: false,
             codec_config: CodecConfig::new_with_defaults(),
             exts: ExtensionMap::standard(),
@@ -2266,10 +3682,28 @@ impl Default for RtcConfig {
             bwe_initial_bitrate: None,
             reordering_size_audio: 15,
             reordering_size_video: 30,
+            max_frame_size: 10_000_000,
+            incomplete_frame_timeout: Duration::from_secs(5),
             send_buffer_audio: 50,
             send_buffer_video: 1000,
             rtp_mode: false,
             enable_raw_packets: false,
+            rtcp_observer: None,
+            rtcp_compound: true,
+            rtcp_mux_only: false,
+            bundle_policy: BundlePolicy::default(),
+            ecn: false,
+            cname: None,
+            layer_thresholds: (1.0, 1.2),
+            demux_policy: DemuxPolicy::default(),
+            unknown_pt_policy: UnknownPtPolicy::default(),
+            initial_seq_range: 0..=u16::MAX,
+            pacing_priority_audio: 1,
+            pacing_priority_video: 0,
+            early_media_buffer: None,
+            srtp_limit_margin: DEFAULT_SRTP_LIMIT_MARGIN,
+            #[cfg(feature = "pcap")]
+            pcap: None,
         }
     }
 }
@@ -2278,11 +3712,16 @@ impl PartialEq for Event {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::IceConnectionStateChange(l0), Self::IceConnectionStateChange(r0)) => l0 == r0,
+            (Self::IceRoleChange(l0), Self::IceRoleChange(r0)) => l0 == r0,
+            (Self::LocalIceCandidate(l0), Self::LocalIceCandidate(r0)) => l0 == r0,
             (Self::MediaAdded(m0), Self::MediaAdded(m1)) => m0 == m1,
             (Self::MediaData(m1), Self::MediaData(m2)) => m1 == m2,
             (Self::ChannelOpen(l0, l1), Self::ChannelOpen(r0, r1)) => l0 == r0 && l1 == r1,
             (Self::ChannelData(l0), Self::ChannelData(r0)) => l0 == r0,
             (Self::ChannelClose(l0), Self::ChannelClose(r0)) => l0 == r0,
+            (Self::ExtensionNotNegotiated(l0, l1), Self::ExtensionNotNegotiated(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
             _ => false,
         }
     }
@@ -2355,6 +3794,7 @@ mod test {
 
     #[test]
     fn event_is_reasonably_sized() {
+        // Mostly the ExtensionValues of MediaData.
         let n = std::mem::size_of::<Event>();
         assert!(n < 450);
     }
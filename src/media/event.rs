@@ -133,6 +133,34 @@ pub struct MediaData {
     pub last_sender_info: Option<SenderInfo>,
 }
 
+/// An incoming frame that was dropped before it was complete.
+///
+/// This is obtained via [`Event::FrameDropped`][crate::Event::FrameDropped]. The frame
+/// grew bigger than [`RtcConfig::set_max_frame_size()`][crate::RtcConfig::set_max_frame_size],
+/// or its packets didn't all arrive within
+/// [`RtcConfig::set_incomplete_frame_timeout()`][crate::RtcConfig::set_incomplete_frame_timeout].
+/// The next [`MediaData`] is not contiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameDropped {
+    /// Identifier of the media in the session this frame belongs to.
+    pub mid: Mid,
+
+    /// Payload type of the frame.
+    pub pt: Pt,
+
+    /// The rid, if the frame belongs to a simulcast layer.
+    pub rid: Option<Rid>,
+
+    /// The RTP media time of the frame.
+    pub time: MediaTime,
+
+    /// The (RTP) sequence numbers of the dropped packets.
+    pub seq_range: RangeInclusive<SeqNo>,
+
+    /// Payload bytes dropped.
+    pub bytes: usize,
+}
+
 /// Details for an incoming a keyframe request (PLI or FIR).
 ///
 /// This is obtained via the [`Event::KeyframeRequest`][crate::Event::KeyframeRequest].
@@ -150,6 +178,34 @@ pub struct KeyframeRequest {
     pub kind: KeyframeRequestKind,
 }
 
+/// Incoming loss notification (`goog-lntf`) for media we are sending to the remote peer.
+///
+/// This is obtained via the [`Event::LossNotification`][crate::Event::LossNotification].
+///
+/// Packets after `last_decoded` up to `last_received` were lost or not yet decoded. If
+/// `decodable` is true, the remote can decode the frame of `last_received`, and the encoder
+/// can carry on referencing it instead of producing a keyframe.
+///
+/// Sending a loss notification is done via
+/// [`StreamRx::request_loss_notification()`][crate::rtp::StreamRx::request_loss_notification].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LossNotification {
+    /// The media identifier this loss notification is for.
+    pub mid: Mid,
+
+    /// Rid the loss notification is for. Relevant when doing simulcast.
+    pub rid: Option<Rid>,
+
+    /// Sequence number of the last packet of the last decoded frame.
+    pub last_decoded: SeqNo,
+
+    /// Sequence number of the last received packet.
+    pub last_received: SeqNo,
+
+    /// Whether the frame of the last received packet is decodable.
+    pub decodable: bool,
+}
+
 /// Type of keyframe request.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeyframeRequestKind {
@@ -1,15 +1,13 @@
 //! Media (audio/video) related content.
 
 use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::change::AddMedia;
 use crate::format::CodecConfig;
-use crate::io::{Id, DATAGRAM_MTU};
+use crate::io::Id;
 use crate::packet::{DepacketizingBuffer, Payloader, RtpMeta};
-use crate::rtp_::ExtensionMap;
-use crate::rtp_::SRTP_BLOCK_SIZE;
-use crate::rtp_::SRTP_OVERHEAD;
+use crate::rtp_::{Bitrate, Extension, ExtensionMap};
 use crate::RtcError;
 
 use crate::format::PayloadParams;
@@ -92,6 +90,21 @@ pub struct Media {
     /// [`true`] if this media was created by the remote peer, [`false`] if it was created by us.
     remote_created: bool,
 
+    /// Max bitrate the remote peer signalled with `b=AS`, if any.
+    ///
+    /// SDP property.
+    remote_bitrate: Option<Bitrate>,
+
+    /// RTCP port the remote peer signalled with `a=rtcp`, when not multiplexing RTCP.
+    ///
+    /// SDP property.
+    remote_rtcp_port: Option<u16>,
+
+    /// Whether the m-line is rejected or removed (port 0).
+    ///
+    /// SDP property.
+    rejected: bool,
+
     /// Simulcast configuration, if set.
     ///
     /// SDP property.
@@ -106,6 +119,9 @@ pub struct Media {
     /// Payloaders for outoing RTP packets.
     payloaders: HashMap<(Pt, Option<Rid>), Payloader>,
 
+    /// Incomplete frames dropped by the depayloaders, waiting to be polled.
+    frames_dropped: VecDeque<FrameDropped>,
+
     /// Samples to payload. Should typically only be 0 or 1.
     to_payload: VecDeque<ToPayload>,
 
@@ -118,6 +134,15 @@ pub struct Media {
     pub(crate) app_tmp: bool,
 }
 
+/// Limits for the depayloaders of incoming media, from the [`RtcConfig`][crate::RtcConfig].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DepayloadLimits {
+    pub reordering_size_audio: usize,
+    pub reordering_size_video: usize,
+    pub max_frame_size: usize,
+    pub frame_timeout: Duration,
+}
+
 #[derive(Debug)]
 /// Config value for [`Media::rids_rx()`]
 pub enum Rids {
@@ -250,7 +275,7 @@ impl Media {
                     mid: self.mid,
                     pt: *pt,
                     rid: *rid,
-                    params: *codec,
+                    params: codec.clone(),
                     time: dep.time,
                     network_time: dep.first_network_time(),
                     seq_range: dep.seq_range(),
@@ -265,16 +290,16 @@ impl Media {
         Ok(None)
     }
 
+    /// Depayload an incoming packet. Returns the number of incomplete frames dropped.
     pub(crate) fn depayload(
         &mut self,
         rid: Option<Rid>,
         packet: RtpPacket,
-        reordering_size_audio: usize,
-        reordering_size_video: usize,
+        limits: &DepayloadLimits,
         params: &[PayloadParams],
-    ) {
+    ) -> usize {
         if !self.dir.is_receiving() {
-            return;
+            return 0;
         }
 
         let pt = packet.header.payload_type;
@@ -292,12 +317,17 @@ impl Media {
 
             // How many packets to hold back in the jitter buffer.
             let hold_back = if codec.is_audio() {
-                reordering_size_audio
+                limits.reordering_size_audio
             } else {
-                reordering_size_video
+                limits.reordering_size_video
             };
 
-            let buffer = DepacketizingBuffer::new(codec.into(), hold_back);
+            let buffer = DepacketizingBuffer::new(
+                codec.into(),
+                hold_back,
+                limits.max_frame_size,
+                limits.frame_timeout,
+            );
 
             self.depayloaders.insert((pt, rid), buffer);
         }
@@ -314,6 +344,26 @@ impl Media {
         };
 
         buffer.push(meta, packet.payload);
+
+        let mut count = 0;
+
+        while let Some(dropped) = buffer.poll_dropped() {
+            self.frames_dropped.push_back(FrameDropped {
+                mid: self.mid,
+                pt,
+                rid,
+                time: dropped.time,
+                seq_range: dropped.seq_range,
+                bytes: dropped.bytes,
+            });
+            count += 1;
+        }
+
+        count
+    }
+
+    pub(crate) fn poll_frame_dropped(&mut self) -> Option<FrameDropped> {
+        self.frames_dropped.pop_front()
     }
 
     pub(crate) fn set_cname(&mut self, cname: String) {
@@ -343,7 +393,7 @@ impl Media {
         self.payloaders.entry((pt, rid)).or_insert_with(|| {
             // Unwrap is OK, the pt should be checked already when calling this function.
             let params = params.iter().find(|p| p.pt == pt).unwrap();
-            Payloader::new(params.spec)
+            Payloader::new(params.spec.clone())
         })
     }
 
@@ -370,6 +420,7 @@ impl Media {
         now: Instant,
         streams: &mut Streams,
         params: &[PayloadParams],
+        rtp_mtu: Option<usize>,
     ) -> Result<(), RtcError> {
         let Some(to_payload) = self.to_payload.pop_front() else {
             return Ok(());
@@ -385,16 +436,19 @@ impl Media {
             return Err(RtcError::NoSenderSource);
         };
 
+        if !stream.is_layer_active() {
+            // Drop before payloading to not use up any sequence numbers.
+            return Ok(());
+        }
+
         let pt = *pt;
 
-        let payloader = self.payloader_for(pt, *rid, params);
+        let mtu = stream.max_payload_size(rtp_mtu, &self.remote_exts, &to_payload.ext_vals);
 
-        const RTP_SIZE: usize = DATAGRAM_MTU - SRTP_OVERHEAD;
-        // align to SRTP block size to minimize padding needs
-        const MTU: usize = RTP_SIZE - RTP_SIZE % SRTP_BLOCK_SIZE;
+        let payloader = self.payloader_for(pt, *rid, params);
 
         payloader
-            .push_sample(now, to_payload, MTU, is_audio, stream)
+            .push_sample(now, to_payload, mtu, is_audio, stream)
             .map_err(|e| RtcError::Packet(self.mid, pt, e))?;
 
         Ok(())
@@ -436,10 +490,62 @@ impl Media {
         &self.remote_exts
     }
 
+    /// The negotiated id of an RTP header extension for this Media.
+    ///
+    /// The id can differ from the one configured in [`RtcConfig::extension_map()`][crate::RtcConfig::extension_map],
+    /// since negotiation uses the id of the offer. This is the id to use when writing RTP
+    /// packets with the extension, such as via [`StreamTx::write_rtp()`][crate::rtp::StreamTx::write_rtp].
+    ///
+    /// None if the extension is not negotiated for this Media.
+    pub fn extension_id(&self, ext: Extension) -> Option<u8> {
+        self.remote_exts.id_of(ext)
+    }
+
     pub(crate) fn remote_created(&self) -> bool {
         self.remote_created
     }
 
+    /// The max bitrate the remote peer signalled for this media with `b=AS`, if any.
+    ///
+    /// This limits the bitrate available to the simulcast layers set with
+    /// [`StreamTx::set_layer_bitrate()`][crate::rtp::StreamTx::set_layer_bitrate].
+    pub fn remote_bitrate(&self) -> Option<Bitrate> {
+        self.remote_bitrate
+    }
+
+    pub(crate) fn set_remote_bitrate(&mut self, bitrate: Option<Bitrate>) {
+        self.remote_bitrate = bitrate;
+    }
+
+    /// The RTCP port the remote peer signalled with `a=rtcp` (RFC 3605), if the m-line
+    /// doesn't multiplex RTCP with RTP.
+    ///
+    /// str0m has a single transport with one ICE component, and RTCP is always sent and
+    /// received together with the RTP. A remote peer expecting RTCP on this port will not
+    /// get any. Use [`RtcConfig::set_rtcp_mux_only()`][crate::RtcConfig::set_rtcp_mux_only]
+    /// to reject such m-lines instead.
+    pub fn remote_rtcp_port(&self) -> Option<u16> {
+        self.remote_rtcp_port
+    }
+
+    pub(crate) fn set_remote_rtcp_port(&mut self, port: Option<u16>) {
+        self.remote_rtcp_port = port;
+    }
+
+    /// Whether the m-line is rejected in negotiation.
+    ///
+    /// This happens with [`RtcConfig::set_rtcp_mux_only()`][crate::RtcConfig::set_rtcp_mux_only]
+    /// when the remote peer doesn't multiplex RTCP, or when the media is removed by either
+    /// side via [`SdpApi::remove_media()`][crate::change::SdpApi::remove_media].
+    /// A rejected media is inactive.
+    pub fn rejected(&self) -> bool {
+        self.rejected
+    }
+
+    pub(crate) fn set_rejected(&mut self, rejected: bool) {
+        self.rejected = rejected;
+    }
+
     pub(crate) fn first_pt_with_rtx(&self, config: &CodecConfig) -> Option<Pt> {
         config
             .all_for_kind(self.kind)
@@ -470,11 +576,15 @@ impl Default for Media {
             remote_pts: vec![],
             remote_exts: ExtensionMap::empty(),
             remote_created: false,
+            remote_bitrate: None,
+            remote_rtcp_port: None,
+            rejected: false,
             dir: Direction::SendRecv,
             simulcast: None,
             rids_rx: Rids::Any,
             payloaders: HashMap::new(),
             depayloaders: HashMap::new(),
+            frames_dropped: VecDeque::new(),
             to_payload: VecDeque::default(),
             need_open_event: true,
             need_changed_event: false,
@@ -1,7 +1,7 @@
 use std::time::Instant;
 
 use crate::format::PayloadParams;
-use crate::rtp_::VideoOrientation;
+use crate::rtp_::{ColorSpace, FrameMarking, Ssrc, VideoOrientation};
 use crate::session::Session;
 use crate::RtcError;
 
@@ -77,6 +77,16 @@ impl<'a> Writer<'a> {
         self
     }
 
+    /// Add the contributing sources of a mixed stream, with their audio levels.
+    ///
+    /// The SSRCs become the CSRC list of the RTP header, at most 15, and the levels are sent
+    /// in the [`Extension::CsrcAudioLevel`][crate::rtp::Extension::CsrcAudioLevel] header
+    /// extension, if negotiated. Audio level is measured in negative decibel.
+    pub fn csrc_audio_levels(mut self, levels: Vec<(Ssrc, i8)>) -> Self {
+        self.ext_vals.csrc_audio_levels = Some(levels.into());
+        self
+    }
+
     /// Add video orientation. This can be used by a player on the receiver end to decide
     /// whether the video requires to be rotated to show correctly.
     pub fn video_orientation(mut self, o: VideoOrientation) -> Self {
@@ -84,12 +94,35 @@ impl<'a> Writer<'a> {
         self
     }
 
+    /// Add frame marking. Start and end of frame are set by str0m on each packet the frame
+    /// is split into, the other fields are copied as is.
+    pub fn frame_marking(mut self, v: FrameMarking) -> Self {
+        self.ext_vals.frame_marking = Some(v);
+        self
+    }
+
+    /// Add color space, and optionally HDR metadata, of the video.
+    pub fn color_space(mut self, v: ColorSpace) -> Self {
+        self.ext_vals.color_space = Some(Box::new(v));
+        self
+    }
+
     /// Set a user extension value.
     pub fn user_extension_value<T: Send + Sync + 'static>(mut self, val: T) -> Self {
         self.ext_vals.user_values.set(val);
         self
     }
 
+    /// Set a raw RTP header extension value by id.
+    ///
+    /// This is for negotiated extensions str0m doesn't know about, such as the ones added
+    /// with [`Extension::with_raw_values()`][crate::rtp::Extension::with_raw_values]. The
+    /// value is not sent if the id isn't negotiated.
+    pub fn raw_extension_value(mut self, id: u8, value: &[u8]) -> Self {
+        self.ext_vals.raw_values.set(id, value);
+        self
+    }
+
     /// Write media.
     ///
     /// This operation fails if the PT doesn't match a negotiated codec, or the RID (`None` or a value)
@@ -102,7 +135,8 @@ impl<'a> Writer<'a> {
     /// [`MediaData::network_time`][crate::media::MediaData]). For better synchronization the SFU
     /// probably needs to weigh in clock drifts and data provided via the statistics.
     ///
-    /// If you write media before `IceConnectionState` is `Connected` it will be dropped.
+    /// Media written before the connection is ready is held back until it is, see
+    /// [`RtcConfig::set_early_media_buffer()`][crate::RtcConfig::set_early_media_buffer].
     ///
     /// Panics if [`RtcConfig::set_rtp_mode()`][crate::RtcConfig::set_rtp_mode] is `true`.
     pub fn write(
@@ -162,7 +196,7 @@ impl<'a> Writer<'a> {
     /// a=rtcp-fb:96 nack pli
     /// ```
     pub fn is_request_keyframe_possible(&self, kind: KeyframeRequestKind) -> bool {
-        self.session.is_request_keyframe_possible(kind)
+        self.session.is_request_keyframe_possible(self.mid, kind)
     }
 
     /// Request a keyframe from a remote peer sending media data.
@@ -0,0 +1,333 @@
+use super::{CodecExtra, Depacketizer, PacketError};
+
+/// AV1 information describing the depacketized data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Av1CodecExtra {
+    /// Flag which indicates that within [`MediaData`], there is the first frame of a
+    /// coded video sequence. Such a frame does not depend on any previous frames.
+    ///
+    /// [`MediaData`]: crate::media::MediaData
+    pub is_keyframe: bool,
+}
+
+const AGGREGATION_HEADER_Z: u8 = 0b1000_0000;
+const AGGREGATION_HEADER_Y: u8 = 0b0100_0000;
+const AGGREGATION_HEADER_N: u8 = 0b0000_1000;
+
+const OBU_HAS_EXTENSION: u8 = 0b0000_0100;
+const OBU_HAS_SIZE_FIELD: u8 = 0b0000_0010;
+
+const OBU_TYPE_TEMPORAL_DELIMITER: u8 = 2;
+const OBU_TYPE_TILE_LIST: u8 = 8;
+
+/// Depacketizes AV1 RTP packets.
+///
+/// The output is a low overhead bitstream, where every OBU has a size field, as
+/// described in the AV1 specification section 5.2.
+///
+/// <https://aomediacodec.github.io/av1-rtp-spec/>
+#[derive(Default, Debug)]
+pub struct Av1Depacketizer {
+    /// OBU fragment that continues in the next packet.
+    fragment: Vec<u8>,
+}
+
+impl Depacketizer for Av1Depacketizer {
+    fn depacketize(
+        &mut self,
+        packet: &[u8],
+        out: &mut Vec<u8>,
+        extra: &mut CodecExtra,
+    ) -> Result<(), PacketError> {
+        //  0 1 2 3 4 5 6 7
+        // +-+-+-+-+-+-+-+-+
+        // |Z|Y| W |N|-|-|-|
+        // +-+-+-+-+-+-+-+-+
+        if packet.len() < 2 {
+            return Err(PacketError::ErrShortPacket);
+        }
+
+        let header = packet[0];
+        let z = header & AGGREGATION_HEADER_Z > 0;
+        let y = header & AGGREGATION_HEADER_Y > 0;
+        let w = ((header >> 4) & 0b11) as usize;
+        let n = header & AGGREGATION_HEADER_N > 0;
+
+        let is_keyframe = if let CodecExtra::Av1(e) = extra {
+            n | e.is_keyframe
+        } else {
+            n
+        };
+        *extra = CodecExtra::Av1(Av1CodecExtra { is_keyframe });
+
+        // Without a fragment to continue, the start of the first OBU was lost.
+        let skip_first = z && self.fragment.is_empty();
+
+        let mut rest = &packet[1..];
+        let mut index = 0;
+
+        while !rest.is_empty() {
+            index += 1;
+
+            // With W set, the last OBU element has no length field.
+            let len = if index == w {
+                rest.len()
+            } else {
+                let (len, n) = read_leb128(rest).ok_or(PacketError::ErrAv1CorruptedPacket)?;
+                rest = &rest[n..];
+                len as usize
+            };
+
+            if len > rest.len() {
+                return Err(PacketError::ErrAv1CorruptedPacket);
+            }
+
+            let (element, next) = rest.split_at(len);
+            rest = next;
+
+            let is_continuation = index == 1 && z;
+
+            if is_continuation && skip_first {
+                continue;
+            }
+
+            if !is_continuation {
+                // Any unfinished fragment at this point is missing its end.
+                self.fragment.clear();
+            }
+
+            self.fragment.extend_from_slice(element);
+
+            if y && rest.is_empty() {
+                // The OBU continues in the next packet.
+                break;
+            }
+
+            write_obu(&self.fragment, out)?;
+            self.fragment.clear();
+        }
+
+        Ok(())
+    }
+
+    fn is_partition_head(&self, packet: &[u8]) -> bool {
+        packet
+            .first()
+            .map(|h| h & AGGREGATION_HEADER_Z == 0)
+            .unwrap_or(false)
+    }
+
+    fn is_partition_tail(&self, marker: bool, _packet: &[u8]) -> bool {
+        marker
+    }
+}
+
+/// Write an OBU with a size field.
+fn write_obu(obu: &[u8], out: &mut Vec<u8>) -> Result<(), PacketError> {
+    //  0 1 2 3 4 5 6 7
+    // +-+-+-+-+-+-+-+-+
+    // |F| type  |X|S|-|
+    // +-+-+-+-+-+-+-+-+
+    let header = *obu.first().ok_or(PacketError::ErrAv1CorruptedPacket)?;
+
+    let header_len = if header & OBU_HAS_EXTENSION > 0 { 2 } else { 1 };
+    if obu.len() < header_len {
+        return Err(PacketError::ErrAv1CorruptedPacket);
+    }
+
+    // These must be ignored by receivers.
+    let obu_type = (header >> 3) & 0b1111;
+    if obu_type == OBU_TYPE_TEMPORAL_DELIMITER || obu_type == OBU_TYPE_TILE_LIST {
+        return Ok(());
+    }
+
+    if header & OBU_HAS_SIZE_FIELD > 0 {
+        out.extend_from_slice(obu);
+        return Ok(());
+    }
+
+    out.push(header | OBU_HAS_SIZE_FIELD);
+    out.extend_from_slice(&obu[1..header_len]);
+    write_leb128((obu.len() - header_len) as u64, out);
+    out.extend_from_slice(&obu[header_len..]);
+
+    Ok(())
+}
+
+/// Read a leb128 value, returns the value and the number of bytes read.
+fn read_leb128(buf: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0;
+
+    for (i, b) in buf.iter().take(8).enumerate() {
+        value |= ((b & 0x7f) as u64) << (i * 7);
+        if b & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+
+    None
+}
+
+fn write_leb128(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let b = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(b);
+            return;
+        }
+        out.push(b | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // OBU_FRAME without size field.
+    const FRAME: u8 = 6 << 3;
+
+    fn depacketize(dep: &mut Av1Depacketizer, packets: &[&[u8]]) -> (Vec<u8>, CodecExtra) {
+        let mut out = vec![];
+        let mut extra = CodecExtra::None;
+        for p in packets {
+            dep.depacketize(p, &mut out, &mut extra).unwrap();
+        }
+        (out, extra)
+    }
+
+    #[test]
+    fn single_obu() {
+        let mut dep = Av1Depacketizer::default();
+
+        // W=1, one OBU without length field.
+        let (out, _) = depacketize(&mut dep, &[&[0b0001_0000, FRAME, 1, 2, 3]]);
+
+        assert_eq!(out, &[FRAME | OBU_HAS_SIZE_FIELD, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn aggregated_obus() {
+        let mut dep = Av1Depacketizer::default();
+
+        // W=0, every OBU has a length field. The temporal delimiter is dropped.
+        let packet = &[
+            0b0000_0000,
+            1,
+            OBU_TYPE_TEMPORAL_DELIMITER << 3,
+            3,
+            FRAME,
+            1,
+            2,
+            // OBU with extension header.
+            3,
+            FRAME | OBU_HAS_EXTENSION,
+            0x28,
+            4,
+        ];
+        let (out, _) = depacketize(&mut dep, &[packet]);
+
+        assert_eq!(
+            out,
+            &[
+                FRAME | OBU_HAS_SIZE_FIELD,
+                2,
+                1,
+                2,
+                FRAME | OBU_HAS_EXTENSION | OBU_HAS_SIZE_FIELD,
+                0x28,
+                1,
+                4
+            ]
+        );
+    }
+
+    #[test]
+    fn fragmented_obu() {
+        let mut dep = Av1Depacketizer::default();
+
+        // Y=1 W=1, then Z=1 W=1.
+        let (out, _) = depacketize(
+            &mut dep,
+            &[&[0b0101_0000, FRAME, 1, 2], &[0b1001_0000, 3, 4]],
+        );
+
+        assert_eq!(out, &[FRAME | OBU_HAS_SIZE_FIELD, 4, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fragment_start_lost() {
+        let mut dep = Av1Depacketizer::default();
+
+        // Z=1 W=2, the first element continues a fragment we never got.
+        let (out, _) = depacketize(&mut dep, &[&[0b1010_0000, 2, 3, 4, FRAME, 5]]);
+
+        assert_eq!(out, &[FRAME | OBU_HAS_SIZE_FIELD, 1, 5]);
+    }
+
+    #[test]
+    fn fragment_end_lost() {
+        let mut dep = Av1Depacketizer::default();
+
+        // Y=1, but the next packet starts a new OBU.
+        let (out, _) = depacketize(
+            &mut dep,
+            &[&[0b0101_0000, FRAME, 1, 2], &[0b0001_0000, FRAME, 5]],
+        );
+
+        assert_eq!(out, &[FRAME | OBU_HAS_SIZE_FIELD, 1, 5]);
+    }
+
+    #[test]
+    fn long_obu() {
+        let mut dep = Av1Depacketizer::default();
+
+        let mut packet = vec![0b0000_0000];
+        write_leb128(301, &mut packet);
+        packet.push(FRAME);
+        packet.extend_from_slice(&[7; 300]);
+
+        let (out, _) = depacketize(&mut dep, &[&packet]);
+
+        assert_eq!(&out[..3], &[FRAME | OBU_HAS_SIZE_FIELD, 0xac, 0x02]);
+        assert_eq!(read_leb128(&out[1..]), Some((300, 2)));
+        assert_eq!(out.len(), 303);
+    }
+
+    #[test]
+    fn keyframe() {
+        let mut dep = Av1Depacketizer::default();
+
+        let (_, extra) = depacketize(&mut dep, &[&[0b0001_1000, FRAME, 1]]);
+        assert_eq!(extra, CodecExtra::Av1(Av1CodecExtra { is_keyframe: true }));
+
+        let (_, extra) = depacketize(&mut dep, &[&[0b0001_0000, FRAME, 1]]);
+        assert_eq!(extra, CodecExtra::Av1(Av1CodecExtra { is_keyframe: false }));
+    }
+
+    #[test]
+    fn corrupt() {
+        let mut dep = Av1Depacketizer::default();
+        let mut out = vec![];
+        let mut extra = CodecExtra::None;
+
+        assert_eq!(
+            dep.depacketize(&[0b0001_0000], &mut out, &mut extra),
+            Err(PacketError::ErrShortPacket)
+        );
+
+        // Length field longer than the packet.
+        assert_eq!(
+            dep.depacketize(&[0b0000_0000, 5, FRAME], &mut out, &mut extra),
+            Err(PacketError::ErrAv1CorruptedPacket)
+        );
+    }
+
+    #[test]
+    fn partition_head() {
+        let dep = Av1Depacketizer::default();
+        assert!(dep.is_partition_head(&[0b0001_0000, FRAME]));
+        assert!(!dep.is_partition_head(&[0b1001_0000, FRAME]));
+        assert!(!dep.is_partition_head(&[]));
+    }
+}
@@ -1,8 +1,8 @@
 use core::panic;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::ops::{Range, RangeInclusive};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::rtp_::{ExtensionValues, MediaTime, RtpHeader, SenderInfo, SeqNo};
 
@@ -67,6 +67,14 @@ impl Depacketized {
     }
 }
 
+/// An incomplete frame dropped from a [`DepacketizingBuffer`].
+#[derive(Debug)]
+pub struct DroppedFrame {
+    pub time: MediaTime,
+    pub seq_range: RangeInclusive<SeqNo>,
+    pub bytes: usize,
+}
+
 #[derive(Debug)]
 struct Entry {
     meta: RtpMeta,
@@ -85,14 +93,26 @@ pub struct DepacketizingBuffer {
     max_time: Option<MediaTime>,
     depack_cache: Option<(Range<usize>, Depacketized)>,
     contiguity: Contiguity,
+    max_frame_size: usize,
+    /// Bytes in the queue per frame time, to check max_frame_size.
+    frame_bytes: BTreeMap<MediaTime, usize>,
+    frame_timeout: Duration,
+    last_dropped: Option<MediaTime>,
+    dropped: VecDeque<DroppedFrame>,
 }
 
 impl DepacketizingBuffer {
-    pub(crate) fn new(depack: CodecDepacketizer, hold_back: usize) -> Self {
+    pub(crate) fn new(
+        depack: CodecDepacketizer,
+        hold_back: usize,
+        max_frame_size: usize,
+        frame_timeout: Duration,
+    ) -> Self {
         let contiguity = match depack {
             CodecDepacketizer::Vp8(_) => Contiguity::Vp8(Vp8Contiguity::new()),
             CodecDepacketizer::Vp9(_) => Contiguity::Vp9(Vp9Contiguity::new()),
-            CodecDepacketizer::H264(_)
+            CodecDepacketizer::Av1(_)
+            | CodecDepacketizer::H264(_)
             | CodecDepacketizer::H265(_)
             | CodecDepacketizer::Boxed(_)
             | CodecDepacketizer::Opus(_)
@@ -108,6 +128,11 @@ impl DepacketizingBuffer {
             max_time: None,
             depack_cache: None,
             contiguity,
+            max_frame_size,
+            frame_bytes: BTreeMap::new(),
+            frame_timeout,
+            last_dropped: None,
+            dropped: VecDeque::new(),
         }
     }
 
@@ -124,6 +149,12 @@ impl DepacketizingBuffer {
             }
         }
 
+        // Late packets of a frame we already dropped.
+        if self.last_dropped == Some(meta.time) {
+            trace!("Drop packet of dropped frame: {}", meta.seq_no);
+            return;
+        }
+
         // Record that latest seen max time (used for extending time to u64).
         self.max_time = Some(if let Some(m) = self.max_time {
             m.max(meta.time)
@@ -142,6 +173,9 @@ impl DepacketizingBuffer {
             Err(i) => {
                 let head = self.depack.is_partition_head(&data);
                 let tail = self.depack.is_partition_tail(meta.header.marker, &data);
+                let time = meta.time;
+                let now = meta.received;
+                let len = data.len();
 
                 // i is insertion point to maintain order
                 let entry = Entry {
@@ -151,10 +185,102 @@ impl DepacketizingBuffer {
                     tail,
                 };
                 self.queue.insert(i, entry);
+
+                self.limit_frame_size(time, len);
+                self.drop_stale(now);
             }
         }
     }
 
+    /// Drop the frame if it grew too big, such as FU-A fragments that never end.
+    fn limit_frame_size(&mut self, time: MediaTime, len: usize) {
+        // Padding doesn't add to the size.
+        if len == 0 {
+            return;
+        }
+
+        let size = self.frame_bytes.entry(time).or_default();
+        *size += len;
+
+        if *size > self.max_frame_size {
+            debug!(
+                "Drop frame exceeding max size: {} > {}",
+                size, self.max_frame_size
+            );
+            self.drop_frame(time);
+        }
+    }
+
+    /// Remove the entries up to and including `stop` from the queue.
+    fn drain_to(&mut self, stop: usize) {
+        for e in self.queue.drain(0..=stop) {
+            if let Some(size) = self.frame_bytes.get_mut(&e.meta.time) {
+                *size -= e.data.len();
+                if *size == 0 {
+                    self.frame_bytes.remove(&e.meta.time);
+                }
+            }
+        }
+    }
+
+    /// Drop incomplete frames at the front that waited longer than the timeout.
+    fn drop_stale(&mut self, now: Instant) {
+        while let Some(front) = self.queue.front() {
+            if now < front.meta.received + self.frame_timeout {
+                break;
+            }
+            let time = front.meta.time;
+
+            // A complete frame waiting to be emitted is not stale.
+            self.update_segments();
+            if self.segments.first().map(|s| s.0) == Some(0) {
+                break;
+            }
+
+            debug!("Drop incomplete frame after timeout: {:?}", time);
+            self.drop_frame(time);
+        }
+    }
+
+    fn drop_frame(&mut self, time: MediaTime) {
+        let mut seq_range: Option<RangeInclusive<SeqNo>> = None;
+        let mut bytes = 0;
+
+        self.queue.retain(|e| {
+            if e.meta.time != time {
+                return true;
+            }
+            let seq_no = e.meta.seq_no;
+            let start = seq_range.as_ref().map(|r| *r.start()).unwrap_or(seq_no);
+            seq_range = Some(start.min(seq_no)..=seq_no);
+            bytes += e.data.len();
+            false
+        });
+
+        self.frame_bytes.remove(&time);
+
+        // Indexes have moved.
+        self.depack_cache = None;
+        self.last_dropped = Some(time);
+
+        // Padding only isn't a frame.
+        if bytes == 0 {
+            return;
+        }
+
+        if let Some(seq_range) = seq_range {
+            self.dropped.push_back(DroppedFrame {
+                time,
+                seq_range,
+                bytes,
+            });
+        }
+    }
+
+    pub fn poll_dropped(&mut self) -> Option<DroppedFrame> {
+        self.dropped.pop_front()
+    }
+
     pub fn pop(&mut self) -> Option<Result<Depacketized, PacketError>> {
         self.update_segments();
 
@@ -178,7 +304,7 @@ impl DepacketizingBuffer {
                 // this segment cannot be decoded correctly
                 // remove from the queue and return the error
                 self.last_emitted = Some((seq, CodecExtra::None));
-                self.queue.drain(0..=stop);
+                self.drain_to(stop);
                 return Some(Err(e));
             }
         };
@@ -209,7 +335,7 @@ impl DepacketizingBuffer {
 
         // We're not going to emit samples in the incorrect order, there's no point in keeping
         // stuff before the emitted range.
-        self.queue.drain(0..=stop);
+        self.drain_to(stop);
 
         if !can_emit {
             return None;
@@ -516,7 +642,8 @@ mod test {
         )],
     ) {
         let depack = CodecDepacketizer::Boxed(Box::new(TestDepack));
-        let mut buf = DepacketizingBuffer::new(depack, hold_back);
+        let mut buf =
+            DepacketizingBuffer::new(depack, hold_back, 1_000_000, Duration::from_secs(5));
 
         let mut step = 1;
 
@@ -569,6 +696,14 @@ mod test {
                 );
             }
 
+            // The running frame sizes match what is left in the queue.
+            let mut frame_bytes = BTreeMap::new();
+            for e in &buf.queue {
+                *frame_bytes.entry(e.meta.time).or_default() += e.data.len();
+            }
+            frame_bytes.retain(|_, size| *size > 0);
+            assert_eq!(buf.frame_bytes, frame_bytes, "Step {}: Frame sizes", step);
+
             step += 1;
         }
     }
@@ -596,6 +731,88 @@ mod test {
         }
     }
 
+    fn meta(seq: u64, time: u64, received: Instant, marker: bool) -> RtpMeta {
+        RtpMeta {
+            received,
+            seq_no: seq.into(),
+            time: MediaTime::from_90khz(time),
+            last_sender_info: None,
+            header: RtpHeader {
+                sequence_number: seq as u16,
+                timestamp: time as u32,
+                marker,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn never_ending_fua_is_bounded() {
+        use crate::packet::h264::{H264Depacketizer, FUA_NALU_TYPE, FU_START_BITMASK};
+
+        let depack = CodecDepacketizer::H264(H264Depacketizer::default());
+        let mut buf = DepacketizingBuffer::new(depack, 30, 10_000, Duration::from_secs(5));
+        let now = Instant::now();
+
+        let mut start = vec![0; 1000];
+        start[0] = FUA_NALU_TYPE;
+        start[1] = FU_START_BITMASK | 5;
+        buf.push(meta(1, 1, now, false), start);
+
+        // FU-A fragments that never have the end bit.
+        for seq in 2..1000 {
+            let mut middle = vec![0; 1000];
+            middle[0] = FUA_NALU_TYPE;
+            middle[1] = 5;
+            buf.push(meta(seq, 1, now, false), middle);
+
+            let size: usize = buf.queue.iter().map(|e| e.data.len()).sum();
+            assert!(size <= 10_000, "Step {}: size {}", seq, size);
+            assert!(buf.pop().is_none());
+        }
+
+        let dropped = buf.poll_dropped().unwrap();
+        assert_eq!(dropped.time, MediaTime::from_90khz(1));
+        assert_eq!(dropped.seq_range, 1.into()..=11.into());
+        assert_eq!(dropped.bytes, 11_000);
+
+        // Further fragments of the same frame are ignored.
+        assert!(buf.poll_dropped().is_none());
+        assert!(buf.queue.is_empty());
+
+        // The next frame gets through.
+        buf.push(meta(1000, 2, now, true), vec![1, 2, 3]);
+        let dep = buf.pop().unwrap().unwrap();
+        assert_eq!(dep.time, MediaTime::from_90khz(2));
+    }
+
+    #[test]
+    fn incomplete_frame_timeout() {
+        let depack = CodecDepacketizer::Boxed(Box::new(TestDepack));
+        let mut buf = DepacketizingBuffer::new(depack, 3, 1_000_000, Duration::from_secs(5));
+        let now = Instant::now();
+
+        // Head without tail, and 3 is missing.
+        buf.push(meta(1, 1, now, false), vec![1]);
+        buf.push(meta(2, 1, now, false), vec![2]);
+        assert!(buf.pop().is_none());
+
+        buf.push(meta(4, 2, now + Duration::from_secs(4), false), vec![1, 9]);
+        assert!(buf.poll_dropped().is_none());
+
+        buf.push(meta(5, 3, now + Duration::from_secs(6), false), vec![1, 9]);
+        let dropped = buf.poll_dropped().unwrap();
+        assert_eq!(dropped.time, MediaTime::from_90khz(1));
+        assert_eq!(dropped.seq_range, 1.into()..=2.into());
+        assert_eq!(dropped.bytes, 2);
+
+        // Complete frames are not dropped.
+        let times: Vec<_> = std::iter::from_fn(|| buf.pop())
+            .map(|d| d.unwrap().time.numer())
+            .collect();
+        assert_eq!(times, [2, 3]);
+    }
+
     #[test]
     fn rtp_out_of_order() {
         let construct_input =
@@ -614,6 +831,7 @@ mod test {
                             sequence_number: seq,
                             timestamp: time,
                             ssrc: Ssrc::from(2930203832),
+                            csrc: Default::default(),
                             ext_vals: ExtensionValues {
                                 transport_cc: Some(cc),
                                 ..Default::default()
@@ -739,8 +957,12 @@ mod test {
             ),
         ];
 
-        let mut buffer =
-            DepacketizingBuffer::new(CodecDepacketizer::Vp9(Vp9Depacketizer::default()), 30);
+        let mut buffer = DepacketizingBuffer::new(
+            CodecDepacketizer::Vp9(Vp9Depacketizer::default()),
+            30,
+            1_000_000,
+            Duration::from_secs(5),
+        );
 
         for input in &inputs {
             let (meta, data) = construct_input(input.clone());
@@ -750,8 +972,12 @@ mod test {
         let res0before = buffer.pop().unwrap().unwrap(); // Pop PID: 23860, `contiguous_seq == true`.
         let res1before = buffer.pop().unwrap().unwrap(); // Pop PID: 23861, `contiguous_seq == true`.
 
-        let mut buffer =
-            DepacketizingBuffer::new(CodecDepacketizer::Vp9(Vp9Depacketizer::default()), 30);
+        let mut buffer = DepacketizingBuffer::new(
+            CodecDepacketizer::Vp9(Vp9Depacketizer::default()),
+            30,
+            1_000_000,
+            Duration::from_secs(5),
+        );
 
         for input in &inputs {
             let (meta, data) = construct_input(input.clone());
@@ -7,6 +7,7 @@
 mod acked_bitrate_estimator;
 mod arrival_group;
 pub(crate) mod macros;
+mod probe;
 mod rate_control;
 mod trendline_estimator;
 
@@ -15,11 +16,13 @@ use std::collections::VecDeque;
 use std::fmt;
 use std::time::{Duration, Instant};
 
-use crate::rtp_::{Bitrate, DataSize, SeqNo, TwccSendRecord};
+use crate::bwe::PacketFeedback;
+use crate::rtp_::{Bitrate, DataSize, SeqNo};
 use crate::util::already_happened;
 
 use acked_bitrate_estimator::AckedBitrateEstimator;
 use arrival_group::{ArrivalGroupAccumulator, InterGroupDelayDelta};
+use probe::ProbeCluster;
 use rate_control::RateControl;
 use trendline_estimator::TrendlineEstimator;
 
@@ -51,6 +54,9 @@ pub struct SendSideBandwithEstimator {
     next_timeout: Instant,
     /// The last time we ingested a TWCC report.
     last_twcc_report: Instant,
+
+    /// Ongoing probe for bandwidth above the current estimate.
+    probe: Option<ProbeCluster>,
 }
 
 impl SendSideBandwithEstimator {
@@ -68,28 +74,29 @@ impl SendSideBandwithEstimator {
             mean_max_rtt: None,
             next_timeout: already_happened(),
             last_twcc_report: already_happened(),
+            probe: None,
         }
     }
 
-    /// Record a packet from a TWCC report.
-    pub(crate) fn update<'t>(
-        &mut self,
-        records: impl Iterator<Item = &'t TwccSendRecord>,
-        now: Instant,
-    ) {
+    /// Record the packets from a TWCC report.
+    pub(crate) fn update(&mut self, feedback: &[PacketFeedback], now: Instant) {
         let mut acked: Vec<AckedPacket> = Vec::new();
 
         let mut max_rtt = None;
-        for record in records {
-            let Ok(acked_packet) = record.try_into() else {
+        for packet in feedback {
+            let Ok(acked_packet) = packet.try_into() else {
                 continue;
             };
             acked.push(acked_packet);
-            max_rtt = max_rtt.max(record.rtt());
+            max_rtt = max_rtt.max(packet.rtt);
         }
         acked.sort_by(AckedPacket::order_by_receive_time);
 
         for acked_packet in acked {
+            if let Some(probe) = &mut self.probe {
+                probe.add_packet(&acked_packet);
+            }
+
             self.acked_bitrate_estimator
                 .update(acked_packet.remote_recv_time, acked_packet.size);
 
@@ -118,13 +125,45 @@ impl SendSideBandwithEstimator {
             now,
         );
         self.last_twcc_report = now;
+
+        self.maybe_finish_probe(now);
+    }
+
+    /// Update with the packets marked CE (congestion experienced) out of the total received
+    /// since the last ECN feedback.
+    ///
+    /// A CE mark means a queue on the path is building up, which is handled like overuse
+    /// detected from the delay.
+    pub(crate) fn handle_ecn(&mut self, ce: u64, total: u64, now: Instant) {
+        if ce == 0 {
+            return;
+        }
+
+        debug!("ECN congestion experienced: {} of {} packets", ce, total);
+
+        self.update_estimate(
+            BandwithUsage::Overuse,
+            self.acked_bitrate_estimator.current_estimate(),
+            self.mean_max_rtt,
+            now,
+        );
     }
 
     pub(crate) fn poll_timeout(&self) -> Instant {
-        self.next_timeout
+        let probe_at = self.probe.as_ref().and_then(|p| p.poll_timeout());
+
+        match probe_at {
+            Some(probe_at) => self.next_timeout.min(probe_at),
+            None => self.next_timeout,
+        }
     }
 
     pub(crate) fn handle_timeout(&mut self, now: Instant) {
+        if let Some(probe) = &mut self.probe {
+            probe.handle_timeout(now);
+        }
+        self.maybe_finish_probe(now);
+
         if !self.trendline_hypothesis_valid(now) {
             // We haven't received a TWCC report in a while. The trendline hypothesis can
             // no longer be considered valid. We need another TWCC report before we can update
@@ -153,6 +192,38 @@ impl SendSideBandwithEstimator {
         self.last_estimate
     }
 
+    /// Start a probe cluster, replacing any ongoing probe.
+    pub(crate) fn start_probe(&mut self, target: Bitrate, duration: Duration) {
+        self.probe = Some(ProbeCluster::new(target, duration));
+    }
+
+    /// The bitrate to send at while a probe cluster is sent.
+    pub(crate) fn probe_target(&self) -> Option<Bitrate> {
+        self.probe.as_ref().and_then(|p| p.target())
+    }
+
+    fn maybe_finish_probe(&mut self, now: Instant) {
+        let done = self.probe.as_ref().map(|p| p.is_done(now)).unwrap_or(false);
+        if !done {
+            return;
+        }
+
+        // Unwrap is OK, because done is only true when there is a probe.
+        let probe = self.probe.take().unwrap();
+
+        let Some(result) = probe.result() else {
+            debug!("Probe cluster without result");
+            return;
+        };
+
+        // A probe can only raise the estimate, the delay based estimate takes
+        // care of lowering it.
+        if result > self.rate_control.estimated_bitrate() {
+            self.rate_control.set_estimate(result, now);
+            self.last_estimate = Some(self.rate_control.estimated_bitrate());
+        }
+    }
+
     fn add_max_rtt(&mut self, max_rtt: Duration) {
         while self.max_rtt_history.len() > MAX_RTT_HISTORY_WINDOW {
             self.max_rtt_history.pop_front();
@@ -226,18 +297,18 @@ impl AckedPacket {
     }
 }
 
-impl TryFrom<&TwccSendRecord> for AckedPacket {
+impl TryFrom<&PacketFeedback> for AckedPacket {
     type Error = ();
 
-    fn try_from(value: &TwccSendRecord) -> Result<Self, Self::Error> {
-        let Some(remote_recv_time) = value.remote_recv_time() else {
+    fn try_from(value: &PacketFeedback) -> Result<Self, Self::Error> {
+        let Some(remote_recv_time) = value.recv_time else {
             return Err(());
         };
 
         Ok(Self {
-            seq_no: value.seq(),
-            size: value.size().into(),
-            local_send_time: value.local_send_time(),
+            seq_no: value.seq_no,
+            size: value.size.into(),
+            local_send_time: value.send_time,
             remote_recv_time,
         })
     }
@@ -0,0 +1,212 @@
+use std::time::{Duration, Instant};
+
+use crate::rtp_::{Bitrate, DataSize};
+
+use super::AckedPacket;
+
+/// Minimum number of acked packets in the cluster to produce a result.
+const MIN_PACKETS: usize = 5;
+/// If the receive rate is below this ratio of the send rate, the link is saturated.
+const MIN_RECEIVE_SEND_RATIO: f64 = 0.9;
+/// When the link is saturated, the result is this ratio of the receive rate.
+const TARGET_UTILIZATION: f64 = 0.95;
+/// The maximum time we wait for TWCC feedback after the cluster is sent.
+const MAX_FEEDBACK_WAIT: Duration = Duration::from_secs(1);
+
+/// A probe cluster sends at a target bitrate for a short duration to find out whether the link
+/// can sustain more than the current estimate.
+///
+/// The pacer fills up to the target with padding. All packets sent in the cluster are then
+/// compared, the rate they were sent at against the rate they arrived at according to TWCC.
+/// This corresponds roughly to the ProbeBitrateEstimator in libWebRTC.
+#[derive(Debug)]
+pub(crate) struct ProbeCluster {
+    target: Bitrate,
+    duration: Duration,
+    /// Set on first handle_timeout.
+    start: Option<Instant>,
+    /// Whether we are sending the cluster.
+    sending: bool,
+    /// Whether any packet sent after the cluster has been acked, which means we have all
+    /// feedback we are going to get.
+    complete: bool,
+    /// The acked packets sent in the cluster.
+    packets: Vec<AckedPacket>,
+}
+
+impl ProbeCluster {
+    pub fn new(target: Bitrate, duration: Duration) -> Self {
+        Self {
+            target,
+            duration,
+            start: None,
+            sending: false,
+            complete: false,
+            packets: vec![],
+        }
+    }
+
+    /// The bitrate to send at while the cluster is sent.
+    pub fn target(&self) -> Option<Bitrate> {
+        self.sending.then_some(self.target)
+    }
+
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        let start = self.start?;
+        let end = start + self.duration;
+
+        Some(if self.sending {
+            end
+        } else {
+            end + MAX_FEEDBACK_WAIT
+        })
+    }
+
+    pub fn handle_timeout(&mut self, now: Instant) {
+        let start = *self.start.get_or_insert_with(|| {
+            debug!(
+                "Start probe cluster at {} for {:?}",
+                self.target, self.duration
+            );
+            self.sending = true;
+            now
+        });
+
+        if self.sending && now >= start + self.duration {
+            self.sending = false;
+        }
+    }
+
+    pub fn add_packet(&mut self, packet: &AckedPacket) {
+        let Some(start) = self.start else {
+            return;
+        };
+
+        if packet.local_send_time < start {
+            return;
+        }
+
+        if packet.local_send_time >= start + self.duration {
+            self.complete = true;
+            return;
+        }
+
+        self.packets.push(*packet);
+    }
+
+    /// Whether the probe is done, either with all feedback or by timing out.
+    pub fn is_done(&self, now: Instant) -> bool {
+        if self.sending {
+            return false;
+        }
+
+        self.complete || self.poll_timeout().map(|t| now >= t).unwrap_or(false)
+    }
+
+    /// The bitrate the link sustained during the cluster.
+    pub fn result(&self) -> Option<Bitrate> {
+        if self.packets.len() < MIN_PACKETS {
+            return None;
+        }
+
+        let total: DataSize = self.packets.iter().map(|p| p.size).sum();
+
+        // The send rate doesn't include the last packet sent, since it's sent at the end
+        // of the interval. Conversely, the receive rate doesn't include the first received.
+        let first_send = self.packets.iter().min_by_key(|p| p.local_send_time)?;
+        let last_send = self.packets.iter().max_by_key(|p| p.local_send_time)?;
+        let first_recv = self.packets.iter().min_by_key(|p| p.remote_recv_time)?;
+        let last_recv = self.packets.iter().max_by_key(|p| p.remote_recv_time)?;
+
+        let send_interval = last_send.local_send_time - first_send.local_send_time;
+        let recv_interval = last_recv.remote_recv_time - first_recv.remote_recv_time;
+
+        if send_interval.is_zero() || recv_interval.is_zero() {
+            return None;
+        }
+
+        let send_rate = total.saturating_sub(last_send.size) / send_interval;
+        let recv_rate = total.saturating_sub(first_recv.size) / recv_interval;
+
+        let result = if recv_rate.as_f64() < send_rate.as_f64() * MIN_RECEIVE_SEND_RATIO {
+            recv_rate * TARGET_UTILIZATION
+        } else {
+            send_rate.min(recv_rate)
+        };
+
+        debug!(
+            "Probe cluster result {} (send: {} recv: {})",
+            result, send_rate, recv_rate
+        );
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn packet(seq: u64, size: usize, sent: Instant, received: Instant) -> AckedPacket {
+        AckedPacket {
+            seq_no: seq.into(),
+            size: size.into(),
+            local_send_time: sent,
+            remote_recv_time: received,
+        }
+    }
+
+    fn run(recv_spacing: Duration) -> Option<Bitrate> {
+        let start = Instant::now();
+        let mut probe = ProbeCluster::new(Bitrate::mbps(1), Duration::from_millis(100));
+
+        probe.handle_timeout(start);
+        assert_eq!(probe.target(), Some(Bitrate::mbps(1)));
+
+        // 1000 bytes every 8ms is 1Mbit/s.
+        for i in 0..10 {
+            let sent = start + Duration::from_millis(8) * i;
+            let received = start + recv_spacing * i;
+            probe.add_packet(&packet(i as u64, 1000, sent, received));
+        }
+
+        probe.handle_timeout(start + Duration::from_millis(100));
+        assert_eq!(probe.target(), None);
+        assert!(!probe.is_done(start + Duration::from_millis(100)));
+
+        // A packet sent after the cluster completes the feedback.
+        let after = start + Duration::from_millis(120);
+        probe.add_packet(&packet(10, 1000, after, after));
+        assert!(probe.is_done(after));
+
+        probe.result()
+    }
+
+    #[test]
+    fn probe_sustained() {
+        let result = run(Duration::from_millis(8)).unwrap();
+        assert!((result.as_f64() - 1_000_000.0).abs() < 1.0, "{}", result);
+    }
+
+    #[test]
+    fn probe_saturated() {
+        // Received at half the rate.
+        let result = run(Duration::from_millis(16)).unwrap();
+        assert!((result.as_f64() - 475_000.0).abs() < 1.0, "{}", result);
+    }
+
+    #[test]
+    fn probe_too_few_packets() {
+        let start = Instant::now();
+        let mut probe = ProbeCluster::new(Bitrate::mbps(1), Duration::from_millis(100));
+
+        probe.handle_timeout(start);
+        probe.add_packet(&packet(0, 1000, start, start));
+
+        let end = start + Duration::from_millis(100);
+        probe.handle_timeout(end);
+        assert!(!probe.is_done(end));
+        assert!(probe.is_done(end + MAX_FEEDBACK_WAIT));
+        assert_eq!(probe.result(), None);
+    }
+}
@@ -97,6 +97,11 @@ impl RateControl {
         self.estimated_bitrate
     }
 
+    /// Set the estimate directly, such as from the result of a probe.
+    pub(super) fn set_estimate(&mut self, bitrate: Bitrate, now: Instant) {
+        self.update_estimate(bitrate, now);
+    }
+
     fn increase(&mut self, observed_bitrate: Bitrate, now: Instant) {
         let last_estimate_update = *self.last_estimate_update.get_or_insert(now);
 
@@ -131,7 +136,10 @@ impl RateControl {
 
             self.estimated_bitrate.as_f64() + increase
         };
-        let max = observed_bitrate.as_f64() * MAX_ESTIMATE_RATIO;
+        // Like libWebRTC, the limit never lowers the current estimate, which might have been
+        // raised above the observed bitrate by a probe.
+        let max =
+            (observed_bitrate.as_f64() * MAX_ESTIMATE_RATIO).max(self.estimated_bitrate.as_f64());
         new_estimate = max.min(new_estimate);
 
         self.update_estimate(new_estimate.into(), now);
@@ -498,6 +506,27 @@ mod test {
             rate_controller.update(Signal::Normal, 70_000.into(), None, now + duration_ms(3500));
             assert_eq!(rate_controller.estimated_bitrate().as_u64(), 72552);
         }
+
+        #[test]
+        fn test_increase_keeps_probed_estimate() {
+            let now = Instant::now();
+            let mut rate_controller = make_control(100_000);
+
+            // A probe found the link sustains 1Mbit/s, but the media is only sent at
+            // 200kbit/s. 1.5 times the observed 200kbit/s must not lower the estimate.
+            rate_controller.set_estimate(1_000_000.into(), now);
+            rate_controller.update(Signal::Normal, 200_000.into(), None, now + duration_ms(500));
+            assert_eq!(rate_controller.estimated_bitrate().as_u64(), 1_000_000);
+
+            // Once the observed bitrate catches up, the estimate increases as usual.
+            rate_controller.update(
+                Signal::Normal,
+                800_000.into(),
+                None,
+                now + duration_ms(1000),
+            );
+            assert_eq!(rate_controller.estimated_bitrate().as_u64(), 1_039_231);
+        }
     }
 
     fn duration_ms(ms: u64) -> Duration {
@@ -285,7 +285,8 @@ impl Depacketizer for H264Depacketizer {
                     return Err(PacketError::ErrShortPacket);
                 }
 
-                if self.fua_buffer.is_none() {
+                // A new start discards fragments of a previous NALU that never ended.
+                if self.fua_buffer.is_none() || packet[1] & FU_START_BITMASK != 0 {
                     self.fua_buffer = Some(Vec::new());
                 }
 
@@ -534,6 +535,32 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn h264_fua_restart_discards_unfinished() -> Result<(), PacketError> {
+        let packets = vec![
+            // Start and middle, but the end never arrives.
+            &[0x1c, 0x80, 0x01, 0x02, 0x03],
+            &[0x1c, 0x00, 0x04, 0x05, 0x06],
+            // A new NALU.
+            &[0x1c, 0x80, 0x07, 0x08, 0x09],
+            &[0x1c, 0x40, 0x10, 0x11, 0x12],
+        ];
+
+        let mut pkt = H264Depacketizer::default();
+        let mut extra = CodecExtra::None;
+
+        let mut out = Vec::new();
+        for p in &packets {
+            pkt.depacketize(*p, &mut out, &mut extra)?;
+        }
+        assert_eq!(
+            out,
+            &[0x00, 0x00, 0x00, 0x01, 0x00, 0x07, 0x08, 0x09, 0x10, 0x11, 0x12]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn h264_large_out_avc() -> Result<(), PacketError> {
         let large_payload_packetized = vec![
@@ -8,6 +8,10 @@ use thiserror::Error;
 use crate::format::Codec;
 use crate::sdp::MediaType;
 
+mod av1;
+pub use av1::Av1CodecExtra;
+use av1::Av1Depacketizer;
+
 mod g7xx;
 use g7xx::{G711Packetizer, G722Packetizer};
 
@@ -85,6 +89,7 @@ pub(crate) trait Packetizer: fmt::Debug {
 /// Contains additional codec specific information which are deemed useful for
 /// managing and repackaging the sample
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CodecExtra {
     /// No extra information available
     None,
@@ -94,6 +99,8 @@ pub enum CodecExtra {
     Vp9(Vp9CodecExtra),
     /// Codec extra parameters for H264.
     H264(H264CodecExtra),
+    /// Codec extra parameters for AV1.
+    Av1(Av1CodecExtra),
 }
 
 /// Depacketizes an RTP payload.
@@ -139,6 +146,8 @@ pub enum PacketError {
     NaluTypeIsNotHandled(u8),
     #[error("VP9 corrupted packet")]
     ErrVP9CorruptedPacket,
+    #[error("AV1 corrupted packet")]
+    ErrAv1CorruptedPacket,
 }
 
 /// Helper to replace Bytes. Provides get_u8 and get_u16 over some buffer of bytes.
@@ -197,6 +206,7 @@ pub(crate) enum CodecPacketizer {
 
 #[derive(Debug)]
 pub(crate) enum CodecDepacketizer {
+    Av1(Av1Depacketizer),
     H264(H264Depacketizer),
     H265(H265Depacketizer),
     Opus(OpusDepacketizer),
@@ -231,7 +241,7 @@ impl From<Codec> for CodecDepacketizer {
             Codec::H265 => CodecDepacketizer::H265(H265Depacketizer::default()),
             Codec::Vp8 => CodecDepacketizer::Vp8(Vp8Depacketizer::default()),
             Codec::Vp9 => CodecDepacketizer::Vp9(Vp9Depacketizer::default()),
-            Codec::Av1 => unimplemented!("Missing depacketizer for AV1"),
+            Codec::Av1 => CodecDepacketizer::Av1(Av1Depacketizer::default()),
             Codec::Null => CodecDepacketizer::Null(NullDepacketizer),
             Codec::Rtx => panic!("Cant instantiate depacketizer for RTX codec"),
             Codec::Unknown => panic!("Cant instantiate depacketizer for unknown codec"),
@@ -278,6 +288,7 @@ impl Depacketizer for CodecDepacketizer {
     ) -> Result<(), PacketError> {
         use CodecDepacketizer::*;
         match self {
+            Av1(v) => v.depacketize(packet, out, extra),
             H264(v) => v.depacketize(packet, out, extra),
             H265(v) => v.depacketize(packet, out, extra),
             Opus(v) => v.depacketize(packet, out, extra),
@@ -291,6 +302,7 @@ impl Depacketizer for CodecDepacketizer {
     fn is_partition_head(&self, packet: &[u8]) -> bool {
         use CodecDepacketizer::*;
         match self {
+            Av1(v) => v.is_partition_head(packet),
             H264(v) => v.is_partition_head(packet),
             H265(v) => v.is_partition_head(packet),
             Opus(v) => v.is_partition_head(packet),
@@ -304,6 +316,7 @@ impl Depacketizer for CodecDepacketizer {
     fn is_partition_tail(&self, marker: bool, packet: &[u8]) -> bool {
         use CodecDepacketizer::*;
         match self {
+            Av1(v) => v.is_partition_tail(marker, packet),
             H264(v) => v.is_partition_tail(marker, packet),
             H265(v) => v.is_partition_tail(marker, packet),
             Opus(v) => v.is_partition_tail(marker, packet),
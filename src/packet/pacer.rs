@@ -1,3 +1,4 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::fmt;
 use std::time::{Duration, Instant};
@@ -183,6 +184,8 @@ pub struct QueueState {
     pub mid: Mid,
     pub unpaced: bool,
     pub use_for_padding: bool,
+    /// Priority when sharing the pacing budget with other queues. Higher is sent first.
+    pub pacing_priority: u8,
     pub snapshot: QueueSnapshot,
 }
 
@@ -236,8 +239,10 @@ impl Pacer for NullPacer {
             .queue_states
             .iter()
             .filter(|q| q.snapshot.packet_count > 0);
-        // Pick a queue using round robin, prioritize the least recently sent on queue.
-        let to_send_on = non_empty_queues.min_by_key(|q| self.last_sends.get(&q.mid));
+        // Pick the queue with highest pacing priority, then using round robin, prioritize
+        // the least recently sent on queue.
+        let to_send_on = non_empty_queues
+            .min_by_key(|q| (Reverse(q.pacing_priority), self.last_sends.get(&q.mid)));
 
         let result = to_send_on.map(|q| q.mid);
 
@@ -442,9 +447,16 @@ impl LeakyBucketPacer {
                 .iter()
                 .filter(|q| q.snapshot.packet_count > 0);
 
-            // Send on the non-empty queue with the lowest priority that, was least recently
-            // sent on.
-            non_empty_queues.min_by_key(|q| (q.snapshot.priority, q.snapshot.last_emitted))
+            // Send on the non-empty queue with the lowest priority, then the highest pacing
+            // priority, that was least recently sent on. This means a burst of video
+            // can't hold back audio.
+            non_empty_queues.min_by_key(|q| {
+                (
+                    q.snapshot.priority,
+                    Reverse(q.pacing_priority),
+                    q.snapshot.last_emitted,
+                )
+            })
         };
 
         if let Some(queue) = non_empty_queue {
@@ -606,6 +618,7 @@ impl QueueSnapshot {
 
 #[cfg(test)]
 mod test {
+    use std::iter;
     use std::ops::Range;
     use std::thread;
     use std::time::{Duration, Instant};
@@ -1004,6 +1017,7 @@ mod test {
             mid: Mid::from("001"),
             unpaced: false,
             use_for_padding: true,
+            pacing_priority: 0,
             snapshot: QueueSnapshot {
                 created_at: now,
                 size: 10_usize,
@@ -1019,6 +1033,7 @@ mod test {
             mid: Mid::from("002"),
             unpaced: false,
             use_for_padding: false,
+            pacing_priority: 0,
             snapshot: QueueSnapshot {
                 created_at: now,
                 size: 30_usize,
@@ -1042,6 +1057,52 @@ mod test {
         assert_eq!(state.snapshot.priority, QueuePriority::Media);
     }
 
+    #[test]
+    fn test_pacing_priority() {
+        let now = Instant::now();
+        // 2,000 bits per second, 10 bytes per pacing interval(40ms)
+        let mut pacer = LeakyBucketPacer::new((10 * 200).into());
+
+        let audio = Mid::from("001");
+        let video = Mid::from("002");
+
+        let state = |mid, pacing_priority, last_emitted| QueueState {
+            mid,
+            // Audio is paced, to compete with video for the budget.
+            unpaced: false,
+            use_for_padding: false,
+            pacing_priority,
+            snapshot: QueueSnapshot {
+                created_at: now,
+                size: 10,
+                packet_count: 1,
+                last_emitted: Some(last_emitted),
+                first_unsent: Some(now),
+                priority: QueuePriority::Media,
+                ..Default::default()
+            },
+        };
+
+        pacer.handle_timeout(now, iter::empty());
+        pacer.register_send(now, DataSize::ZERO, video);
+
+        // Video was least recently sent on, but audio has the higher priority.
+        let queues = [
+            state(audio, 1, now + duration_ms(2)),
+            state(video, 0, now + duration_ms(1)),
+        ];
+        pacer.handle_timeout(now + duration_ms(2), queues.into_iter());
+        assert_eq!(pacer.poll_queue(), Some(audio));
+
+        // Equal priorities fall back on round robin.
+        let queues = [
+            state(audio, 0, now + duration_ms(3)),
+            state(video, 0, now + duration_ms(1)),
+        ];
+        pacer.handle_timeout(now + duration_ms(3), queues.into_iter());
+        assert_eq!(pacer.poll_queue(), Some(video));
+    }
+
     #[test]
     fn test_priority_ordering() {
         assert!(QueuePriority::Media < QueuePriority::Padding);
@@ -1454,6 +1515,7 @@ mod test {
                     mid: self.mid,
                     unpaced: self.is_audio,
                     use_for_padding: !self.is_audio && self.last_emitted.is_some(),
+                    pacing_priority: u8::from(self.is_audio),
                     snapshot: QueueSnapshot {
                         created_at: now,
                         size: self.queue.iter().map(QueuedPacket::size).sum(),
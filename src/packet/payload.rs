@@ -64,13 +64,19 @@ impl Payloader {
             // TODO: delegate to self.pack to decide whether this packet is nackable.
             let nackable = !is_audio;
 
+            let mut ext_vals = ext_vals.clone();
+            if let Some(v) = &mut ext_vals.frame_marking {
+                v.start_of_frame = first;
+                v.end_of_frame = last;
+            }
+
             stream.write_rtp(
                 pt,
                 seq_no,
                 rtp_time.rebase(self.clock_rate).numer() as u32,
                 wallclock,
                 marker,
-                ext_vals.clone(),
+                ext_vals,
                 nackable,
                 data,
             );
@@ -174,7 +174,7 @@ impl Packetizer for Vp9Packetizer {
             #[cfg(not(test))]
             {
                 use crate::util::NonCryptographicRng;
-                self.picture_id = NonCryptographicRng::u16() & 0x7FFF;
+                self.picture_id = NonCryptographicRng::u16_in(0..=0x7FFF);
             }
             self.initialized = true;
         }
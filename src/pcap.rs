@@ -0,0 +1,237 @@
+//! Packet capture of everything sent and received, for offline analysis in Wireshark.
+
+use std::fmt;
+use std::io::Write;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::util::InstantExt;
+
+/// LINKTYPE_RAW, the packets start with an IPv4 or IPv6 header.
+const LINKTYPE_RAW: u32 = 101;
+
+const SNAPLEN: u32 = 65_535;
+
+const IPPROTO_UDP: u8 = 17;
+
+/// Writer of pcap files shared by configs cloned from the same [`RtcConfig`][crate::RtcConfig].
+///
+/// Several [`Rtc`][crate::Rtc] instances can write to the same file. The pcap file
+/// header is written before the first packet.
+#[derive(Clone)]
+pub(crate) struct PcapWriter(Arc<Mutex<Inner>>);
+
+struct Inner {
+    out: Box<dyn Write + Send>,
+    header_written: bool,
+    failed: bool,
+}
+
+impl PcapWriter {
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        PcapWriter(Arc::new(Mutex::new(Inner {
+            out: Box::new(out),
+            header_written: false,
+            failed: false,
+        })))
+    }
+
+    /// Write one packet as an UDP datagram between source and destination.
+    ///
+    /// Write errors are logged once, after which the capture stops.
+    pub fn write(&self, now: Instant, source: SocketAddr, destination: SocketAddr, data: &[u8]) {
+        // A panic while holding the lock only affects the capture.
+        let mut inner = match self.0.lock() {
+            Ok(v) => v,
+            Err(p) => p.into_inner(),
+        };
+
+        if inner.failed {
+            return;
+        }
+
+        let mut buf = Vec::with_capacity(24 + 16 + 48 + data.len());
+
+        if !inner.header_written {
+            write_file_header(&mut buf);
+            inner.header_written = true;
+        }
+
+        write_record(&mut buf, now, source, destination, data);
+
+        if let Err(e) = inner.out.write_all(&buf).and_then(|_| inner.out.flush()) {
+            warn!("Stop pcap capture, write failed: {:?}", e);
+            inner.failed = true;
+        }
+    }
+}
+
+impl fmt::Debug for PcapWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PcapWriter").finish()
+    }
+}
+
+fn write_file_header(buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&0xa1b2_c3d4_u32.to_le_bytes());
+    buf.extend_from_slice(&2_u16.to_le_bytes());
+    buf.extend_from_slice(&4_u16.to_le_bytes());
+    buf.extend_from_slice(&0_i32.to_le_bytes()); // thiszone
+    buf.extend_from_slice(&0_u32.to_le_bytes()); // sigfigs
+    buf.extend_from_slice(&SNAPLEN.to_le_bytes());
+    buf.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+}
+
+fn write_record(
+    buf: &mut Vec<u8>,
+    now: Instant,
+    source: SocketAddr,
+    destination: SocketAddr,
+    data: &[u8],
+) {
+    let mut packet = Vec::with_capacity(48 + data.len());
+    write_ip_udp(&mut packet, source, destination, data);
+
+    let ts = now.to_unix_duration();
+    let len = packet.len() as u32;
+
+    buf.extend_from_slice(&(ts.as_secs() as u32).to_le_bytes());
+    buf.extend_from_slice(&ts.subsec_micros().to_le_bytes());
+    buf.extend_from_slice(&len.min(SNAPLEN).to_le_bytes());
+    buf.extend_from_slice(&len.to_le_bytes());
+    buf.extend_from_slice(&packet[..(len.min(SNAPLEN) as usize)]);
+}
+
+fn write_ip_udp(buf: &mut Vec<u8>, source: SocketAddr, destination: SocketAddr, data: &[u8]) {
+    let udp_len = 8 + data.len();
+
+    match (source.ip(), destination.ip()) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => {
+            let total_len = (20 + udp_len) as u16;
+
+            let start = buf.len();
+            buf.extend_from_slice(&[0x45, 0]);
+            buf.extend_from_slice(&total_len.to_be_bytes());
+            buf.extend_from_slice(&[0, 0, 0x40, 0, 64, IPPROTO_UDP, 0, 0]);
+            buf.extend_from_slice(&src.octets());
+            buf.extend_from_slice(&dst.octets());
+
+            let checksum = ipv4_checksum(&buf[start..]);
+            buf[start + 10..start + 12].copy_from_slice(&checksum.to_be_bytes());
+        }
+        (src, dst) => {
+            // Mixed families can't happen on the wire, map any IPv4 into IPv6.
+            let src = to_ipv6(src);
+            let dst = to_ipv6(dst);
+
+            buf.extend_from_slice(&[0x60, 0, 0, 0]);
+            buf.extend_from_slice(&(udp_len as u16).to_be_bytes());
+            buf.extend_from_slice(&[IPPROTO_UDP, 64]);
+            buf.extend_from_slice(&src.octets());
+            buf.extend_from_slice(&dst.octets());
+        }
+    }
+
+    buf.extend_from_slice(&source.port().to_be_bytes());
+    buf.extend_from_slice(&destination.port().to_be_bytes());
+    buf.extend_from_slice(&(udp_len as u16).to_be_bytes());
+    // Checksum 0 means no checksum.
+    buf.extend_from_slice(&[0, 0]);
+    buf.extend_from_slice(data);
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(v) => v.to_ipv6_mapped(),
+        IpAddr::V6(v) => v,
+    }
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]) as u32)
+        .sum();
+
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ipv4_udp() {
+        let source = "10.0.0.1:5000".parse().unwrap();
+        let destination = "10.0.0.2:6000".parse().unwrap();
+
+        let mut buf = vec![];
+        write_ip_udp(&mut buf, source, destination, &[1, 2, 3]);
+
+        assert_eq!(buf.len(), 20 + 8 + 3);
+        assert_eq!(&buf[2..4], &31_u16.to_be_bytes());
+        assert_eq!(&buf[12..16], &[10, 0, 0, 1]);
+        assert_eq!(&buf[16..20], &[10, 0, 0, 2]);
+
+        // A correct header sums to 0xffff including the checksum.
+        assert_eq!(ipv4_checksum(&buf[..20]), 0);
+
+        assert_eq!(&buf[20..22], &5000_u16.to_be_bytes());
+        assert_eq!(&buf[22..24], &6000_u16.to_be_bytes());
+        assert_eq!(&buf[24..26], &11_u16.to_be_bytes());
+        assert_eq!(&buf[28..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn ipv6_udp() {
+        let source = "[::1]:5000".parse().unwrap();
+        let destination = "10.0.0.2:6000".parse().unwrap();
+
+        let mut buf = vec![];
+        write_ip_udp(&mut buf, source, destination, &[1, 2, 3]);
+
+        assert_eq!(buf.len(), 40 + 8 + 3);
+        assert_eq!(buf[0], 0x60);
+        assert_eq!(&buf[4..6], &11_u16.to_be_bytes());
+        assert_eq!(buf[6], IPPROTO_UDP);
+        assert_eq!(
+            &buf[24..40],
+            &"::ffff:10.0.0.2".parse::<Ipv6Addr>().unwrap().octets()
+        );
+    }
+
+    #[test]
+    fn header_once() {
+        #[derive(Clone, Default)]
+        struct Shared(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let shared = Shared::default();
+        let writer = PcapWriter::new(shared.clone());
+
+        let addr = "10.0.0.1:5000".parse().unwrap();
+        let now = Instant::now();
+        writer.write(now, addr, addr, &[1]);
+        writer.clone().write(now, addr, addr, &[2, 3]);
+
+        let out = shared.0.lock().unwrap();
+        let record = 16 + 28;
+        assert_eq!(out.len(), 24 + record + 1 + record + 2);
+        assert_eq!(&out[..4], &[0xd4, 0xc3, 0xb2, 0xa1]);
+        assert_eq!(&out[20..24], &LINKTYPE_RAW.to_le_bytes());
+    }
+}
@@ -17,7 +17,7 @@ use crate::util::InstantExt;
 use crate::rtp_::Frequency;
 
 use super::mtime::MediaTime;
-use super::{Mid, Rid};
+use super::{Mid, Rid, Ssrc, MAX_CSRC};
 
 /// RTP header extensions.
 #[derive(Debug, Clone)]
@@ -27,6 +27,11 @@ pub enum Extension {
     AbsoluteSendTime,
     /// <urn:ietf:params:rtp-hdrext:ssrc-audio-level>
     AudioLevel,
+    /// <urn:ietf:params:rtp-hdrext:csrc-audio-level>
+    ///
+    /// Mixer-to-client audio levels (RFC 6465) of the contributing sources in a mixed
+    /// stream, see [`ExtensionValues::csrc_audio_levels`].
+    CsrcAudioLevel,
     /// <urn:ietf:params:rtp-hdrext:toffset>
     ///
     /// Use when a RTP packet is delayed by a send queue to indicate an offset in the "transmitter".
@@ -35,6 +40,13 @@ pub enum Extension {
     TransmissionTimeOffset,
     /// <urn:3gpp:video-orientation>
     VideoOrientation,
+    /// <urn:3gpp:video-orientation:6>
+    ///
+    /// The higher granularity CVO variant used by some older Android devices. Only the
+    /// 90 degree rotation is read into [`ExtensionValues::video_orientation`], same as for
+    /// [`Extension::VideoOrientation`]. Negotiated in place of `VideoOrientation` when the
+    /// remote peer only offers this variant.
+    VideoOrientationLegacy,
     /// <http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01>
     TransportSequenceNumber,
     /// <http://www.webrtc.org/experiments/rtp-hdrext/playout-delay>
@@ -109,6 +121,59 @@ impl ExtensionsForm {
             None
         }
     }
+
+    /// Iterate over the `(id, value)` of each extension element in a header extension block.
+    pub(crate) fn iter(self, mut buf: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+        std::iter::from_fn(move || loop {
+            if buf.is_empty() {
+                return None;
+            }
+
+            if buf[0] == 0 {
+                // padding
+                buf = &buf[1..];
+                continue;
+            }
+
+            let (id, len) = match self {
+                ExtensionsForm::OneByte => {
+                    let id = buf[0] >> 4;
+                    let len = (buf[0] & 0xf) as usize + 1;
+                    buf = &buf[1..];
+
+                    if id == 15 {
+                        // If the ID value 15 is
+                        // encountered, its length field should be ignored, processing of the
+                        // entire extension should terminate at that point, and only the
+                        // extension elements present prior to the element with ID 15
+                        // considered.
+                        return None;
+                    }
+                    (id, len)
+                }
+                ExtensionsForm::TwoByte => {
+                    if buf.len() < 2 {
+                        trace!("Not enough ext header len: {} < {}", buf.len(), 2);
+                        return None;
+                    }
+                    let id = buf[0];
+                    let len = buf[1] as usize;
+                    buf = &buf[2..];
+                    (id, len)
+                }
+            };
+
+            if buf.len() < len {
+                trace!("Not enough type ext len: {} < {}", buf.len(), len);
+                return None;
+            }
+
+            let (value, rest) = buf.split_at(len);
+            buf = rest;
+
+            return Some((id, value));
+        })
+    }
 }
 
 // TODO: think this through. Is it unwind safe?
@@ -141,6 +206,12 @@ impl Extension {
     fn requires_two_byte_form(&self, ev: &ExtensionValues) -> bool {
         match self {
             Extension::UnknownUri(_, serializer) => serializer.requires_two_byte_form(ev),
+            // With HDR metadata the value is 28 bytes.
+            Extension::ColorSpace => ev
+                .color_space
+                .as_ref()
+                .map(|v| v.hdr_metadata.is_some())
+                .unwrap_or(false),
             _ => false,
         }
     }
@@ -167,6 +238,26 @@ impl ExtensionSerializer for SdpUnknownUri {
     }
 }
 
+/// Serializer for [`Extension::with_raw_values()`]. The values are in
+/// [`ExtensionValues::raw_values`], so there is nothing to parse or write.
+#[derive(Debug)]
+struct RawSerializer;
+
+impl ExtensionSerializer for RawSerializer {
+    fn write_to(&self, _buf: &mut [u8], _ev: &ExtensionValues) -> usize {
+        0
+    }
+    fn parse_value(&self, _buf: &[u8], _ev: &mut ExtensionValues) -> bool {
+        true
+    }
+    fn is_video(&self) -> bool {
+        true
+    }
+    fn is_audio(&self) -> bool {
+        true
+    }
+}
+
 /// Mapping of extension URI to our enum
 const EXT_URI: &[(Extension, &str)] = &[
     (
@@ -177,6 +268,10 @@ const EXT_URI: &[(Extension, &str)] = &[
         Extension::AudioLevel,
         "urn:ietf:params:rtp-hdrext:ssrc-audio-level",
     ),
+    (
+        Extension::CsrcAudioLevel,
+        "urn:ietf:params:rtp-hdrext:csrc-audio-level",
+    ),
     (
         Extension::TransmissionTimeOffset,
         "urn:ietf:params:rtp-hdrext:toffset",
@@ -185,6 +280,10 @@ const EXT_URI: &[(Extension, &str)] = &[
         Extension::VideoOrientation, //
         "urn:3gpp:video-orientation",
     ),
+    (
+        Extension::VideoOrientationLegacy,
+        "urn:3gpp:video-orientation:6",
+    ),
     (
         Extension::TransportSequenceNumber,
         "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01",
@@ -242,6 +341,20 @@ impl Extension {
         Extension::UnknownUri(uri.to_string(), Arc::new(s))
     }
 
+    /// Extension for a uri not handled by str0m, where the values are read and written
+    /// as bytes via [`ExtensionValues::raw_values`].
+    ///
+    /// ```
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::Extension;
+    /// let ext = Extension::with_raw_values("http://my-special-extension");
+    ///
+    /// let rtc = Rtc::builder().set_extension(12, ext).build();
+    /// ```
+    pub fn with_raw_values(uri: &str) -> Self {
+        Extension::with_serializer(uri, RawSerializer)
+    }
+
     /// Represents the extension as an URI.
     pub fn as_uri(&self) -> &str {
         for (t, spec) in EXT_URI.iter() {
@@ -257,6 +370,11 @@ impl Extension {
         "unknown"
     }
 
+    /// Whether this is an extension str0m doesn't parse or write itself.
+    pub(crate) fn is_unknown_uri(&self) -> bool {
+        matches!(self, Extension::UnknownUri(..))
+    }
+
     pub(crate) fn is_serialized(&self) -> bool {
         if let Self::UnknownUri(_, s) = self {
             // Check if this Arc contains the SdpUnknownUri.
@@ -289,6 +407,7 @@ impl Extension {
                 | RtpMid
                 | AbsoluteSendTime
                 | AudioLevel
+                | CsrcAudioLevel
                 | TransportSequenceNumber
                 | TransmissionTimeOffset
                 | PlayoutDelay
@@ -309,6 +428,7 @@ impl Extension {
                 | RtpMid
                 | AbsoluteSendTime
                 | VideoOrientation
+                | VideoOrientationLegacy
                 | TransportSequenceNumber
                 | TransmissionTimeOffset
                 | PlayoutDelay
@@ -457,76 +577,64 @@ impl ExtensionMap {
     }
 
     // https://tools.ietf.org/html/rfc5285
-    pub(crate) fn parse(
-        &self,
-        mut buf: &[u8],
-        form: ExtensionsForm,
-        ext_vals: &mut ExtensionValues,
-    ) {
-        loop {
-            if buf.is_empty() {
-                return;
-            }
-
-            if buf[0] == 0 {
-                // padding
-                buf = &buf[1..];
+    pub(crate) fn parse(&self, buf: &[u8], form: ExtensionsForm, ext_vals: &mut ExtensionValues) {
+        for (id, ext_buf) in ExtensionsForm::iter(form, buf) {
+            let Some(ext) = self.lookup(id) else {
                 continue;
-            }
-
-            let (id, len) = match form {
-                ExtensionsForm::OneByte => {
-                    let id = buf[0] >> 4;
-                    let len = (buf[0] & 0xf) as usize + 1;
-                    buf = &buf[1..];
-
-                    if id == 15 {
-                        // If the ID value 15 is
-                        // encountered, its length field should be ignored, processing of the
-                        // entire extension should terminate at that point, and only the
-                        // extension elements present prior to the element with ID 15
-                        // considered.
-                        return;
-                    }
-                    (id, len)
-                }
-                ExtensionsForm::TwoByte => {
-                    if buf.len() < 2 {
-                        trace!("Not enough ext header len: {} < {}", buf.len(), 2);
-                        return;
-                    }
-                    let id = buf[0];
-                    let len = buf[1] as usize;
-                    buf = &buf[2..];
-                    (id, len)
-                }
             };
 
-            if buf.len() < len {
-                trace!("Not enough type ext len: {} < {}", buf.len(), len);
-                return;
-            }
+            ext.parse_value(ext_buf, ext_vals);
 
-            let ext_buf = &buf[..len];
-            if let Some(ext) = self.lookup(id) {
-                ext.parse_value(ext_buf, ext_vals);
+            // Extensions str0m doesn't parse itself are also kept raw.
+            if ext.is_unknown_uri() {
+                ext_vals.raw_values.set(id, ext_buf);
             }
-
-            buf = &buf[len..];
         }
     }
 
     pub(crate) fn form(&self, ev: &ExtensionValues) -> ExtensionsForm {
-        if self
-            .iter()
-            .any(|(id, ext)| id > MAX_ID_ONE_BYTE_FORM || ext.requires_two_byte_form(ev))
-        {
+        if self.iter().any(|(id, ext)| {
+            id > MAX_ID_ONE_BYTE_FORM
+                || ext.requires_two_byte_form(ev)
+                || Self::raw_value(id, ext, ev)
+                    .map(|v| v.is_empty() || v.len() > 16)
+                    .unwrap_or(false)
+        }) {
             ExtensionsForm::TwoByte
         } else {
             ExtensionsForm::OneByte
         }
     }
 
+    /// The length [`ExtensionMap::write_to()`] would write, padded to 32 bits.
+    ///
+    /// `sent` gives the length of values str0m sets when sending, in place of those in `ev`.
+    pub(crate) fn written_len(
+        &self,
+        ev: &ExtensionValues,
+        sent: impl Fn(&Extension) -> Option<usize>,
+    ) -> usize {
+        let mut two_byte = self.form(ev) == ExtensionsForm::TwoByte;
+        let mut len = 0;
+        let mut count = 0;
+
+        for (id, ext) in self.iter() {
+            let n = sent(ext)
+                .or_else(|| ext.value_len(ev))
+                .or_else(|| Self::raw_value(id, ext, ev).map(|v| v.len()));
+
+            if let Some(n) = n {
+                two_byte |= n > 16;
+                len += n;
+                count += 1;
+            }
+        }
+
+        len += count * if two_byte { 2 } else { 1 };
+
+        len + (4 - len % 4) % 4
+    }
+
     pub(crate) fn write_to(
         &self,
         ext_buf: &mut [u8],
@@ -538,18 +646,19 @@ impl ExtensionMap {
 
         for (idx, x) in self.0.iter().enumerate() {
             if let Some(v) = x {
+                let id = idx as u8 + 1;
                 match form {
                     ExtensionsForm::OneByte => {
-                        if let Some(n) = v.ext.write_to(&mut b[1..], ev) {
+                        if let Some(n) = Self::write_value(id, &v.ext, &mut b[1..], ev) {
                             assert!(n <= 16);
                             assert!(n > 0);
-                            b[0] = (idx as u8 + 1) << 4 | (n as u8 - 1);
+                            b[0] = id << 4 | (n as u8 - 1);
                             b = &mut b[1 + n..];
                         }
                     }
                     ExtensionsForm::TwoByte => {
-                        if let Some(n) = v.ext.write_to(&mut b[2..], ev) {
-                            b[0] = (idx + 1) as u8;
+                        if let Some(n) = Self::write_value(id, &v.ext, &mut b[2..], ev) {
+                            b[0] = id;
                             b[1] = n as u8;
                             b = &mut b[2 + n..];
                         }
@@ -561,21 +670,69 @@ impl ExtensionMap {
         orig_len - b.len()
     }
 
-    pub(crate) fn remap(&mut self, remote_exts: &[(u8, &Extension)]) {
+    /// Write the value for a mapped extension. Extensions str0m doesn't serialize itself
+    /// fall back on the raw value when the serializer writes nothing.
+    fn write_value(id: u8, ext: &Extension, buf: &mut [u8], ev: &ExtensionValues) -> Option<usize> {
+        if let Some(n) = ext.write_to(buf, ev) {
+            return Some(n);
+        }
+
+        let value = Self::raw_value(id, ext, ev)?;
+        buf[..value.len()].copy_from_slice(value);
+
+        Some(value.len())
+    }
+
+    /// Raw values are only used for negotiated extensions that str0m doesn't know about.
+    fn raw_value<'a>(id: u8, ext: &Extension, ev: &'a ExtensionValues) -> Option<&'a [u8]> {
+        if !ext.is_unknown_uri() {
+            return None;
+        }
+        ev.raw_values.get(id)
+    }
+
+    /// Returns the extensions that could not be mapped to the remote id. Those are removed,
+    /// since the remote would not read them at our id.
+    pub(crate) fn remap(&mut self, remote_exts: &[(u8, &Extension)]) -> Vec<Extension> {
+        let mut not_mapped = vec![];
+
+        self.maybe_use_legacy_cvo(remote_exts);
+
         // Match remote numbers and lock down those we see for the first time.
         for (id, ext) in remote_exts {
-            self.swap(*id, ext);
+            if !self.swap(*id, ext) {
+                not_mapped.push((*ext).clone());
+            }
         }
+
+        not_mapped
     }
 
-    fn swap(&mut self, id: u8, ext: &Extension) {
-        if id < 1 || id > MAX_ID {
+    // Use the legacy CVO variant if that is the only one the remote offers. When both
+    // are offered, we keep the standard one.
+    fn maybe_use_legacy_cvo(&mut self, remote_exts: &[(u8, &Extension)]) {
+        let remote_has = |e: &Extension| remote_exts.iter().any(|(_, x)| *x == e);
+
+        if !remote_has(&Extension::VideoOrientationLegacy)
+            || remote_has(&Extension::VideoOrientation)
+        {
             return;
         }
 
-        // Mapping goes from 0 to 13.
-        let new_index = id as usize - 1;
+        let entry = self
+            .0
+            .iter_mut()
+            .flatten()
+            .find(|m| m.ext == Extension::VideoOrientation && !m.locked);
 
+        if let Some(m) = entry {
+            m.ext = Extension::VideoOrientationLegacy;
+        }
+    }
+
+    // Returns false, and removes the extension, if it is enabled locally, but can't be given
+    // the remote id.
+    fn swap(&mut self, id: u8, ext: &Extension) -> bool {
         let Some(old_index) = self
             .0
             .iter()
@@ -583,35 +740,91 @@ impl ExtensionMap {
             .find(|(_, m)| m.as_ref().map(|m| &m.ext) == Some(ext))
             .map(|(i, _)| i)
         else {
-            return;
+            // Not enabled locally, nothing to negotiate.
+            return true;
         };
 
-        // Unwrap OK because index is checking just above.
-        let old = self.0[old_index].as_mut().unwrap();
+        if id < 1 || id > MAX_ID {
+            warn!("Extmap id out of range 1-{}: {} {:?}", MAX_ID, id, ext);
+            self.0[old_index] = None;
+            return false;
+        }
+
+        // Mapping goes from 0 to 15.
+        let new_index = id as usize - 1;
 
         let is_change = new_index != old_index;
 
         // If either audio or video is locked, we got a previous extmap negotiation.
-        if is_change && old.locked {
+        if is_change && self.0[old_index].as_ref().map(|m| m.locked) == Some(true) {
             warn!(
                 "Extmap locked by previous negotiation. Ignore change: {} -> {}",
                 old_index, new_index
             );
-            return;
+            return true;
+        }
+
+        // Swapping would move an extension that is already negotiated.
+        if is_change && self.0[new_index].as_ref().map(|m| m.locked) == Some(true) {
+            warn!(
+                "Extmap id {} locked by previous negotiation. Can't map: {:?}",
+                id, ext
+            );
+            self.0[old_index] = None;
+            return false;
         }
 
+        // Unwrap OK because index is found just above.
+        let old = self.0[old_index].as_mut().unwrap();
+
         // Locking must be done regardless of whether there was an actual change.
         old.locked = true;
 
-        if !is_change {
-            return;
+        if is_change {
+            self.0.swap(old_index, new_index);
         }
 
-        self.0.swap(old_index, new_index);
+        true
     }
 }
 
 impl Extension {
+    /// The length of the value [`Extension::write_to()`] writes, without writing it.
+    fn value_len(&self, ev: &ExtensionValues) -> Option<usize> {
+        use Extension::*;
+        match self {
+            AbsoluteSendTime => ev.abs_send_time.map(|_| 3),
+            AudioLevel => ev.audio_level.and(ev.voice_activity).map(|_| 1),
+            CsrcAudioLevel => ev
+                .csrc_audio_levels
+                .as_ref()
+                .map(|l| l.len())
+                .filter(|n| (1..=MAX_CSRC).contains(n)),
+            TransmissionTimeOffset => ev.tx_time_offs.map(|_| 3),
+            VideoOrientation | VideoOrientationLegacy => ev.video_orientation.map(|_| 1),
+            TransportSequenceNumber => ev.transport_cc.map(|_| 2),
+            PlayoutDelay => ev.play_delay.as_ref().map(|_| 3),
+            VideoContentType => ev.video_content_type.map(|_| 1),
+            VideoTiming => ev.video_timing.map(|_| 13),
+            RtpStreamId => ev.rid.map(|v| v.len()),
+            RepairedRtpStreamId => ev.rid_repair.map(|v| v.len()),
+            RtpMid => ev.mid.map(|v| v.len()),
+            FrameMarking => ev
+                .frame_marking
+                .map(|v| if v.is_scalable() { 3 } else { 1 }),
+            ColorSpace => {
+                ev.color_space
+                    .as_ref()
+                    .map(|v| if v.hdr_metadata.is_some() { 28 } else { 4 })
+            }
+            UnknownUri(_, serializer) => {
+                // Only the serializer knows, by writing.
+                let n = serializer.write_to(&mut [0; 255], ev);
+                (n > 0).then_some(n)
+            }
+        }
+    }
+
     pub(crate) fn write_to(&self, buf: &mut [u8], ev: &ExtensionValues) -> Option<usize> {
         use Extension::*;
         match self {
@@ -639,12 +852,25 @@ impl Extension {
                 buf[0] = if v2 { 0x80 } else { 0 } | (-(0x7f & v1) as u8);
                 Some(1)
             }
+            CsrcAudioLevel => {
+                let levels = ev.csrc_audio_levels.as_ref()?;
+                if levels.is_empty() || levels.len() > MAX_CSRC {
+                    return None;
+                }
+                // One byte per CSRC, the first bit is reserved, 127 is silence.
+                for (i, (_, level)) in levels.iter().enumerate() {
+                    buf[i] = -level.clamp(&-127, &0) as u8;
+                }
+                Some(levels.len())
+            }
             TransmissionTimeOffset => {
-                let v = ev.tx_time_offs?;
-                buf[..4].copy_from_slice(&v.to_be_bytes());
-                Some(4)
+                // 24 bit signed.
+                let v = ev.tx_time_offs?.clamp(-0x80_0000, 0x7f_ffff);
+                buf[..3].copy_from_slice(&v.to_be_bytes()[1..]);
+                Some(3)
             }
-            VideoOrientation => {
+            VideoOrientation | VideoOrientationLegacy => {
+                // The legacy variant has the same 2 bit rotation in the lowest bits.
                 let v = ev.video_orientation?;
                 buf[0] = v as u8;
                 Some(1)
@@ -655,8 +881,9 @@ impl Extension {
                 Some(2)
             }
             PlayoutDelay => {
-                let v1 = ev.play_delay_min?.rebase(Frequency::HUNDREDTHS);
-                let v2 = ev.play_delay_max?.rebase(Frequency::HUNDREDTHS);
+                let (min, max) = **ev.play_delay.as_ref()?;
+                let v1 = min.rebase(Frequency::HUNDREDTHS);
+                let v2 = max.rebase(Frequency::HUNDREDTHS);
                 let min = (v1.numer() & 0xfff) as u32;
                 let max = (v2.numer() & 0xfff) as u32;
                 buf[0] = (min >> 4) as u8;
@@ -700,13 +927,12 @@ impl Extension {
                 Some(l)
             }
             FrameMarking => {
-                let v = ev.frame_mark?;
-                buf[..4].copy_from_slice(&v.to_be_bytes());
-                Some(4)
+                let v = ev.frame_marking?;
+                Some(v.write_to(buf))
             }
             ColorSpace => {
-                // TODO HDR color space
-                None
+                let v = ev.color_space.as_ref()?;
+                Some(v.write_to(buf))
             }
             UnknownUri(_, serializer) => {
                 let n = serializer.write_to(buf, ev);
@@ -753,15 +979,26 @@ impl Extension {
                 ev.audio_level = Some(-(0x7f & buf[0] as i8));
                 ev.voice_activity = Some(buf[0] & 0x80 > 0);
             }
+            // 1-15
+            CsrcAudioLevel => {
+                if buf.is_empty() || buf.len() > MAX_CSRC {
+                    return None;
+                }
+                // The SSRCs are filled in from the CSRC list when parsing the RTP header.
+                let levels = buf.iter().map(|b| (0.into(), -((b & 0x7f) as i8)));
+                ev.csrc_audio_levels = Some(levels.collect());
+            }
             // 3
             TransmissionTimeOffset => {
-                if buf.len() < 4 {
+                if buf.len() < 3 {
                     return None;
                 }
-                ev.tx_time_offs = Some(u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]));
+                // Sign extend the 24 bit value.
+                let v = i32::from_be_bytes([buf[0], buf[1], buf[2], 0]) >> 8;
+                ev.tx_time_offs = Some(v);
             }
             // 1
-            VideoOrientation => {
+            VideoOrientation | VideoOrientationLegacy => {
                 if buf.is_empty() {
                     return None;
                 }
@@ -781,8 +1018,10 @@ impl Extension {
                 }
                 let min = (buf[0] as u32) << 4 | (buf[1] as u32) >> 4;
                 let max = ((buf[1] & 0xf) as u32) << 8 | buf[2] as u32;
-                ev.play_delay_min = Some(MediaTime::from_hundredths(min as u64));
-                ev.play_delay_max = Some(MediaTime::from_hundredths(max as u64));
+                ev.play_delay = Some(Box::new((
+                    MediaTime::from_hundredths(min as u64),
+                    MediaTime::from_hundredths(max as u64),
+                )));
             }
             // 1
             VideoContentType => {
@@ -818,14 +1057,13 @@ impl Extension {
                 let s = from_utf8(buf).ok()?;
                 ev.mid = Some(s.into());
             }
+            // 1 or 3
             FrameMarking => {
-                if buf.len() < 4 {
-                    return None;
-                }
-                ev.frame_mark = Some(u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]));
+                ev.frame_marking = Some(self::FrameMarking::parse(buf)?);
             }
+            // 4 or 28
             ColorSpace => {
-                // TODO HDR color space
+                ev.color_space = Some(Box::new(self::ColorSpace::parse(buf)?));
             }
             UnknownUri(_, serializer) => {
                 let success = serializer.parse_value(buf, ev);
@@ -850,25 +1088,43 @@ pub struct ExtensionValues {
     /// Indication that there is sound from a voice.
     pub voice_activity: Option<bool>,
 
+    /// Audio levels of the contributing sources in a mixed stream, in negative decibel.
+    ///
+    /// For outgoing packets, the SSRCs also make up the CSRC list of the RTP header, at
+    /// most 15. For incoming packets, they are taken from the CSRC list. Boxed since they
+    /// are rarely used.
+    pub csrc_audio_levels: Option<Box<[(Ssrc, i8)]>>,
+
     /// Tell a receiver what rotation a video need to replay correctly.
     pub video_orientation: Option<VideoOrientation>,
 
+    /// Frame boundaries and layer information for forwarding without parsing the codec.
+    pub frame_marking: Option<FrameMarking>,
+
+    /// Color space and optional HDR metadata of the video.
+    ///
+    /// Boxed since it is rarely used.
+    pub color_space: Option<Box<ColorSpace>>,
+
     // The values below are considered internal until we have a reason to expose them.
     // Generally we want to avoid expose experimental features unless there are strong
     // reasons to do so.
     #[doc(hidden)]
     pub video_content_type: Option<u8>, // 0 = unspecified, 1 = screenshare
-    #[doc(hidden)]
-    pub tx_time_offs: Option<u32>,
+    /// Transmission time offset (RFC 5450) in the clock rate of the codec.
+    ///
+    /// The time from the sampling instant given by the RTP timestamp until the packet was
+    /// sent. For outgoing packets, str0m sets this to the time spent in the send queue, or
+    /// for resends, the time since the packet was first sent.
+    pub tx_time_offs: Option<i32>,
     #[doc(hidden)]
     pub abs_send_time: Option<Instant>,
     #[doc(hidden)]
     pub transport_cc: Option<u16>, // (buf[0] << 8) | buf[1];
     #[doc(hidden)]
     // https://webrtc.googlesource.com/src/+/refs/heads/master/docs/native-code/rtp-hdrext/playout-delay
-    pub play_delay_min: Option<MediaTime>,
-    #[doc(hidden)]
-    pub play_delay_max: Option<MediaTime>,
+    // Min and max delay, boxed since it's rarely used.
+    pub play_delay: Option<Box<(MediaTime, MediaTime)>>,
     #[doc(hidden)]
     pub video_timing: Option<VideoTiming>,
     #[doc(hidden)]
@@ -877,11 +1133,12 @@ pub struct ExtensionValues {
     pub rid_repair: Option<Rid>,
     #[doc(hidden)]
     pub mid: Option<Mid>,
-    #[doc(hidden)]
-    pub frame_mark: Option<u32>,
 
     /// User values for [`ExtensionSerializer`] to parse into and write from.
     pub user_values: UserExtensionValues,
+
+    /// Raw bytes by id of negotiated extensions str0m doesn't know about.
+    pub raw_values: RawExtensionValues,
 }
 impl ExtensionValues {
     pub(crate) fn update_absolute_send_time(&mut self, now: Instant) {
@@ -914,7 +1171,9 @@ impl ExtensionValues {
 /// Space for storing user extension values via [`ExtensionSerializer`].
 #[derive(Clone, Default)]
 pub struct UserExtensionValues {
-    map: Option<AnyMap>,
+    // Boxed to keep the size of ExtensionValues down.
+    #[allow(clippy::box_collection)]
+    map: Option<Box<AnyMap>>,
 }
 
 // The "AnyMap" idea is borrowed from the http crate but replacing Box for Any.
@@ -984,7 +1243,7 @@ impl UserExtensionValues {
     pub fn set<T: Send + Sync + 'static>(&mut self, val: T) {
         // TODO: Consider simplifying to "self.set_arc(Arc::new(val))";
         self.map
-            .get_or_insert_with(HashMap::default)
+            .get_or_insert_with(Box::default)
             .insert(TypeId::of::<T>(), Arc::new(val));
     }
 
@@ -1014,7 +1273,7 @@ impl UserExtensionValues {
     /// large extension values.
     pub fn set_arc<T: Send + Sync + 'static>(&mut self, val: Arc<T>) {
         self.map
-            .get_or_insert_with(HashMap::default)
+            .get_or_insert_with(Box::default)
             .insert(TypeId::of::<T>(), val);
     }
 
@@ -1032,6 +1291,115 @@ impl UserExtensionValues {
 
 impl UnwindSafe for UserExtensionValues {}
 
+/// Raw bytes of RTP header extensions, keyed by extension id.
+///
+/// Only used for negotiated extensions that str0m doesn't parse itself, such as the ones
+/// added with [`Extension::with_raw_values()`]. Extensions str0m knows about are in the
+/// typed fields of [`ExtensionValues`]. For incoming packets, this holds the values of such
+/// extensions present in the header. For outgoing packets, the values are written as is,
+/// unless the extension's serializer writes a value. Values for ids that aren't negotiated
+/// are not sent.
+#[derive(Clone, Default)]
+pub struct RawExtensionValues {
+    // Entries encoded back-to-back as [id, len, value...] to avoid an
+    // allocation per extension. Boxed to keep the size of ExtensionValues down.
+    #[allow(clippy::box_collection)]
+    buf: Option<Box<Vec<u8>>>,
+}
+
+impl RawExtensionValues {
+    /// Set the raw value for an extension id.
+    ///
+    /// Replaces any previous value for the same id.
+    ///
+    /// panics if the id is 0 (padding), or if the value is longer than 255 bytes.
+    ///
+    /// ```
+    /// # use str0m::rtp::ExtensionValues;
+    /// let mut exts = ExtensionValues::default();
+    ///
+    /// exts.raw_values.set(7, &[1, 2, 3]);
+    ///
+    /// assert_eq!(exts.raw_values.get(7), Some(&[1, 2, 3][..]));
+    /// ```
+    pub fn set(&mut self, id: u8, value: &[u8]) {
+        assert!(id != 0, "RTP extension id 0 is padding");
+        assert!(value.len() <= 255);
+
+        self.remove(id);
+
+        let buf = self.buf.get_or_insert_with(Default::default);
+        buf.push(id);
+        buf.push(value.len() as u8);
+        buf.extend_from_slice(value);
+    }
+
+    /// Get the raw value for an extension id.
+    pub fn get(&self, id: u8) -> Option<&[u8]> {
+        self.iter().find(|(i, _)| *i == id).map(|(_, v)| v)
+    }
+
+    /// Remove the raw value for an extension id.
+    pub fn remove(&mut self, id: u8) {
+        let Some(start) = self.offset_of(id) else {
+            return;
+        };
+        let buf = self.buf.as_mut().expect("buf when offset is found");
+        let len = 2 + buf[start + 1] as usize;
+        buf.drain(start..start + len);
+    }
+
+    /// Iterate over all `(id, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &[u8])> + '_ {
+        let mut buf = self.as_slice();
+        std::iter::from_fn(move || {
+            if buf.is_empty() {
+                return None;
+            }
+            let id = buf[0];
+            let len = buf[1] as usize;
+            let value = &buf[2..2 + len];
+            buf = &buf[2 + len..];
+            Some((id, value))
+        })
+    }
+
+    /// Whether there are no raw values.
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self.buf.as_deref().map(|b| &b[..]).unwrap_or_default()
+    }
+
+    fn offset_of(&self, id: u8) -> Option<usize> {
+        let buf = self.as_slice();
+        let mut offset = 0;
+        while offset < buf.len() {
+            if buf[offset] == id {
+                return Some(offset);
+            }
+            offset += 2 + buf[offset + 1] as usize;
+        }
+        None
+    }
+}
+
+impl PartialEq for RawExtensionValues {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for RawExtensionValues {}
+
+impl fmt::Debug for RawExtensionValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
 impl fmt::Debug for ExtensionValues {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "ExtensionValues {{")?;
@@ -1054,6 +1422,9 @@ impl fmt::Debug for ExtensionValues {
         if let Some(t) = self.audio_level {
             write!(f, " audio_level: {t}")?;
         }
+        if let Some(t) = &self.csrc_audio_levels {
+            write!(f, " csrc_audio_levels: {t:?}")?;
+        }
         if let Some(t) = self.tx_time_offs {
             write!(f, " tx_time_offs: {t}")?;
         }
@@ -1063,11 +1434,9 @@ impl fmt::Debug for ExtensionValues {
         if let Some(t) = self.transport_cc {
             write!(f, " transport_cc: {t}")?;
         }
-        if let Some(t) = self.play_delay_min {
-            write!(f, " play_delay_min: {}", t.as_seconds())?;
-        }
-        if let Some(t) = self.play_delay_max {
-            write!(f, " play_delay_max: {}", t.as_seconds())?;
+        if let Some((min, max)) = self.play_delay.as_deref() {
+            write!(f, " play_delay_min: {}", min.as_seconds())?;
+            write!(f, " play_delay_max: {}", max.as_seconds())?;
         }
         if let Some(t) = self.video_content_type {
             write!(f, " video_content_type: {t}")?;
@@ -1075,8 +1444,14 @@ impl fmt::Debug for ExtensionValues {
         if let Some(t) = &self.video_timing {
             write!(f, " video_timing: {t:?}")?;
         }
-        if let Some(t) = &self.frame_mark {
-            write!(f, " frame_mark: {t}")?;
+        if let Some(t) = &self.frame_marking {
+            write!(f, " frame_marking: {t:?}")?;
+        }
+        if let Some(t) = &self.color_space {
+            write!(f, " color_space: {t:?}")?;
+        }
+        if !self.raw_values.is_empty() {
+            write!(f, " raw_values: {:?}", self.raw_values)?;
         }
 
         write!(f, " }}")?;
@@ -1104,8 +1479,10 @@ impl fmt::Display for Extension {
             match self {
                 AbsoluteSendTime => "abs-send-time",
                 AudioLevel => "ssrc-audio-level",
+                CsrcAudioLevel => "csrc-audio-level",
                 TransmissionTimeOffset => "toffset",
                 VideoOrientation => "video-orientation",
+                VideoOrientationLegacy => "video-orientation-6",
                 TransportSequenceNumber => "transport-wide-cc",
                 PlayoutDelay => "playout-delay",
                 VideoContentType => "video-content-type",
@@ -1162,13 +1539,202 @@ impl From<u8> for VideoOrientation {
     }
 }
 
+/// Value of the frame marking RTP header extension.
+///
+/// <https://datatracker.ietf.org/doc/html/draft-ietf-avtext-framemarking-07>
+///
+/// The non-scalable (1 byte) form is written when there is no layer information,
+/// i.e. `base_layer_sync`, `temporal_id`, `layer_id` and `tl0_pic_idx` are all zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameMarking {
+    /// This packet starts a frame.
+    pub start_of_frame: bool,
+    /// This packet ends a frame.
+    pub end_of_frame: bool,
+    /// The frame can be decoded without any previous frames.
+    pub independent: bool,
+    /// The frame can be dropped without affecting the decoding of other frames.
+    pub discardable: bool,
+    /// The frame only depends on the base temporal layer.
+    pub base_layer_sync: bool,
+    /// Temporal layer id, 3 bits.
+    pub temporal_id: u8,
+    /// Spatial/quality layer id. The meaning is codec specific.
+    pub layer_id: u8,
+    /// Running index of the base temporal layer frames.
+    pub tl0_pic_idx: u8,
+}
+
+impl FrameMarking {
+    fn is_scalable(&self) -> bool {
+        self.base_layer_sync || self.temporal_id > 0 || self.layer_id > 0 || self.tl0_pic_idx > 0
+    }
+
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        buf[0] = (self.start_of_frame as u8) << 7
+            | (self.end_of_frame as u8) << 6
+            | (self.independent as u8) << 5
+            | (self.discardable as u8) << 4;
+
+        if !self.is_scalable() {
+            return 1;
+        }
+
+        buf[0] |= (self.base_layer_sync as u8) << 3 | (self.temporal_id & 0x7);
+        buf[1] = self.layer_id;
+        buf[2] = self.tl0_pic_idx;
+        3
+    }
+
+    fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.is_empty() {
+            return None;
+        }
+
+        let b = buf[0];
+
+        let mut v = FrameMarking {
+            start_of_frame: b & 0x80 > 0,
+            end_of_frame: b & 0x40 > 0,
+            independent: b & 0x20 > 0,
+            discardable: b & 0x10 > 0,
+            ..Default::default()
+        };
+
+        // Layer fields are only valid in the scalable form.
+        if buf.len() >= 3 {
+            v.base_layer_sync = b & 0x08 > 0;
+            v.temporal_id = b & 0x07;
+            v.layer_id = buf[1];
+            v.tl0_pic_idx = buf[2];
+        }
+
+        Some(v)
+    }
+}
+
+/// Value of the color space RTP header extension.
+///
+/// <http://www.webrtc.org/experiments/rtp-hdrext/color-space>
+///
+/// The `primaries`, `transfer` and `matrix` values are the code points of ITU-T H.273.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColorSpace {
+    /// Color primaries, e.g. 1 for BT.709 and 9 for BT.2020.
+    pub primaries: u8,
+    /// Transfer characteristics, e.g. 1 for BT.709, 16 for PQ and 18 for HLG.
+    pub transfer: u8,
+    /// Matrix coefficients, e.g. 1 for BT.709 and 9 for BT.2020 non-constant luminance.
+    pub matrix: u8,
+    /// Range, 2 bits. 0 is invalid, 1 limited, 2 full and 3 derived from the other values.
+    pub range: u8,
+    /// Horizontal chroma siting, 2 bits. 0 is unspecified, 1 collocated with luma and 2 half.
+    pub chroma_siting_horizontal: u8,
+    /// Vertical chroma siting, 2 bits. 0 is unspecified, 1 collocated with luma and 2 half.
+    pub chroma_siting_vertical: u8,
+    /// HDR metadata, if any.
+    pub hdr_metadata: Option<HdrMetadata>,
+}
+
+/// HDR mastering display and content light level metadata (SMPTE ST 2086, CTA-861.3).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HdrMetadata {
+    /// Chromaticity (x, y) of the red primary, in units of 0.00002.
+    pub primary_r: (u16, u16),
+    /// Chromaticity (x, y) of the green primary, in units of 0.00002.
+    pub primary_g: (u16, u16),
+    /// Chromaticity (x, y) of the blue primary, in units of 0.00002.
+    pub primary_b: (u16, u16),
+    /// Chromaticity (x, y) of the white point, in units of 0.00002.
+    pub white_point: (u16, u16),
+    /// Max luminance of the mastering display, in cd/m².
+    pub luminance_max: u16,
+    /// Min luminance of the mastering display, in units of 0.0001 cd/m².
+    pub luminance_min: u16,
+    /// Max content light level, in cd/m².
+    pub max_content_light_level: u16,
+    /// Max frame average light level, in cd/m².
+    pub max_frame_average_light_level: u16,
+}
+
+impl ColorSpace {
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        buf[0] = self.primaries;
+        buf[1] = self.transfer;
+        buf[2] = self.matrix;
+        buf[3] = (self.range & 0x3) << 4
+            | (self.chroma_siting_horizontal & 0x3) << 2
+            | (self.chroma_siting_vertical & 0x3);
+
+        let Some(hdr) = &self.hdr_metadata else {
+            return 4;
+        };
+
+        let values = [
+            hdr.luminance_max,
+            hdr.luminance_min,
+            hdr.primary_r.0,
+            hdr.primary_r.1,
+            hdr.primary_g.0,
+            hdr.primary_g.1,
+            hdr.primary_b.0,
+            hdr.primary_b.1,
+            hdr.white_point.0,
+            hdr.white_point.1,
+            hdr.max_content_light_level,
+            hdr.max_frame_average_light_level,
+        ];
+
+        for (i, v) in values.iter().enumerate() {
+            buf[4 + i * 2..6 + i * 2].copy_from_slice(&v.to_be_bytes());
+        }
+
+        28
+    }
+
+    fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() < 4 {
+            return None;
+        }
+
+        let mut v = ColorSpace {
+            primaries: buf[0],
+            transfer: buf[1],
+            matrix: buf[2],
+            range: (buf[3] >> 4) & 0x3,
+            chroma_siting_horizontal: (buf[3] >> 2) & 0x3,
+            chroma_siting_vertical: buf[3] & 0x3,
+            hdr_metadata: None,
+        };
+
+        if buf.len() >= 28 {
+            let u = |i: usize| u16::from_be_bytes([buf[4 + i * 2], buf[5 + i * 2]]);
+
+            v.hdr_metadata = Some(HdrMetadata {
+                luminance_max: u(0),
+                luminance_min: u(1),
+                primary_r: (u(2), u(3)),
+                primary_g: (u(4), u(5)),
+                primary_b: (u(6), u(7)),
+                white_point: (u(8), u(9)),
+                max_content_light_level: u(10),
+                max_frame_average_light_level: u(11),
+            });
+        }
+
+        Some(v)
+    }
+}
+
 impl PartialEq for Extension {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Extension::AbsoluteSendTime, Extension::AbsoluteSendTime) => true,
             (Extension::AudioLevel, Extension::AudioLevel) => true,
+            (Extension::CsrcAudioLevel, Extension::CsrcAudioLevel) => true,
             (Extension::TransmissionTimeOffset, Extension::TransmissionTimeOffset) => true,
             (Extension::VideoOrientation, Extension::VideoOrientation) => true,
+            (Extension::VideoOrientationLegacy, Extension::VideoOrientationLegacy) => true,
             (Extension::TransportSequenceNumber, Extension::TransportSequenceNumber) => true,
             (Extension::PlayoutDelay, Extension::PlayoutDelay) => true,
             (Extension::VideoContentType, Extension::VideoContentType) => true,
@@ -1245,13 +1811,202 @@ mod test {
         assert!(abs < Duration::from_millis(1));
     }
 
+    #[test]
+    fn transport_cc_two_byte_form() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(15, Extension::TransportSequenceNumber);
+        let ev = ExtensionValues {
+            transport_cc: Some(0xabcd),
+            ..Default::default()
+        };
+
+        let mut buf = vec![0_u8; 4];
+        assert_eq!(ExtensionsForm::TwoByte, exts.form(&ev));
+        let n = exts.write_to(&mut buf[..], &ev, ExtensionsForm::TwoByte);
+        assert_eq!(&buf[..n], &[15, 2, 0xab, 0xcd]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::TwoByte, &mut ev2);
+
+        assert_eq!(ev2.transport_cc, Some(0xabcd));
+    }
+
+    #[test]
+    fn written_len_matches_write_to() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(1, Extension::AudioLevel);
+        exts.set(3, Extension::FrameMarking);
+        exts.set(4, Extension::AbsoluteSendTime);
+        exts.set(5, Extension::TransportSequenceNumber);
+        exts.set(10, Extension::RtpStreamId);
+        exts.set(12, Extension::RtpMid);
+
+        let one_byte = ExtensionValues {
+            audio_level: Some(-42),
+            voice_activity: Some(true),
+            frame_marking: Some(FrameMarking {
+                temporal_id: 1,
+                ..Default::default()
+            }),
+            abs_send_time: Some(Instant::now()),
+            mid: Some("video0".into()),
+            ..Default::default()
+        };
+
+        let mut two_byte = exts.clone();
+        two_byte.set(16, Extension::VideoOrientation);
+        let mut two_byte_ev = one_byte.clone();
+        two_byte_ev.transport_cc = Some(1);
+        two_byte_ev.rid = Some("a".into());
+        two_byte_ev.video_orientation = Some(VideoOrientation::Deg90);
+
+        for (exts, ev) in [(&exts, one_byte), (&two_byte, two_byte_ev)] {
+            let mut buf = [0_u8; 100];
+            let n = exts.write_to(&mut buf[..], &ev, exts.form(&ev));
+            assert_eq!(exts.written_len(&ev, |_| None), n + (4 - n % 4) % 4);
+        }
+
+        // Values str0m sets itself count even if missing in the values.
+        let ev = ExtensionValues::default();
+        assert_eq!(
+            exts.written_len(&ev, |e| (*e == Extension::TransportSequenceNumber)
+                .then_some(2)),
+            4
+        );
+    }
+
+    #[test]
+    fn frame_marking_non_scalable() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(3, Extension::FrameMarking);
+        let v = FrameMarking {
+            start_of_frame: true,
+            independent: true,
+            ..Default::default()
+        };
+        let ev = ExtensionValues {
+            frame_marking: Some(v),
+            ..Default::default()
+        };
+
+        let mut buf = vec![0_u8; 8];
+        let n = exts.write_to(&mut buf[..], &ev, ExtensionsForm::OneByte);
+        assert_eq!(&buf[..n], &[0x30, 0xa0]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::OneByte, &mut ev2);
+
+        assert_eq!(ev2.frame_marking, Some(v));
+    }
+
+    #[test]
+    fn frame_marking_scalable() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(3, Extension::FrameMarking);
+        let v = FrameMarking {
+            end_of_frame: true,
+            discardable: true,
+            base_layer_sync: true,
+            temporal_id: 2,
+            layer_id: 1,
+            tl0_pic_idx: 42,
+            ..Default::default()
+        };
+        let ev = ExtensionValues {
+            frame_marking: Some(v),
+            ..Default::default()
+        };
+
+        let mut buf = vec![0_u8; 8];
+        let n = exts.write_to(&mut buf[..], &ev, ExtensionsForm::OneByte);
+        assert_eq!(&buf[..n], &[0x32, 0x5a, 1, 42]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::OneByte, &mut ev2);
+
+        assert_eq!(ev2.frame_marking, Some(v));
+    }
+
+    #[test]
+    fn color_space() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(8, Extension::ColorSpace);
+        let v = ColorSpace {
+            primaries: 1,
+            transfer: 1,
+            matrix: 1,
+            range: 1,
+            chroma_siting_horizontal: 2,
+            chroma_siting_vertical: 1,
+            hdr_metadata: None,
+        };
+        let ev = ExtensionValues {
+            color_space: Some(Box::new(v.clone())),
+            ..Default::default()
+        };
+
+        assert_eq!(exts.form(&ev), ExtensionsForm::OneByte);
+
+        let mut buf = vec![0_u8; 8];
+        let n = exts.write_to(&mut buf[..], &ev, ExtensionsForm::OneByte);
+        assert_eq!(&buf[..n], &[0x83, 1, 1, 1, 0x19]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::OneByte, &mut ev2);
+
+        assert_eq!(ev2.color_space.as_deref(), Some(&v));
+    }
+
+    #[test]
+    fn color_space_hdr() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(8, Extension::ColorSpace);
+        let v = ColorSpace {
+            primaries: 9,
+            transfer: 16,
+            matrix: 9,
+            range: 1,
+            hdr_metadata: Some(HdrMetadata {
+                primary_r: (35400, 14600),
+                primary_g: (8500, 39850),
+                primary_b: (6550, 2300),
+                white_point: (15635, 16450),
+                luminance_max: 1000,
+                luminance_min: 50,
+                max_content_light_level: 1000,
+                max_frame_average_light_level: 400,
+            }),
+            ..Default::default()
+        };
+        let ev = ExtensionValues {
+            color_space: Some(Box::new(v.clone())),
+            ..Default::default()
+        };
+
+        // 28 bytes doesn't fit the one byte form.
+        let form = exts.form(&ev);
+        assert_eq!(form, ExtensionsForm::TwoByte);
+
+        let mut buf = [0_u8; 40];
+        let n = exts.write_to(&mut buf[..], &ev, form);
+        assert_eq!(n, 30);
+        assert_eq!(&buf[..8], &[8, 28, 9, 16, 9, 0x10, 0x03, 0xe8]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf[..n], form, &mut ev2);
+
+        assert_eq!(ev2.color_space.as_deref(), Some(&v));
+    }
+
     #[test]
     fn playout_delay() {
         let mut exts = ExtensionMap::empty();
         exts.set(2, Extension::PlayoutDelay);
         let ev = ExtensionValues {
-            play_delay_min: Some(MediaTime::from_hundredths(100)),
-            play_delay_max: Some(MediaTime::from_hundredths(200)),
+            play_delay: Some(Box::new((
+                MediaTime::from_hundredths(100),
+                MediaTime::from_hundredths(200),
+            ))),
             ..Default::default()
         };
 
@@ -1261,8 +2016,126 @@ mod test {
         let mut ev2 = ExtensionValues::default();
         exts.parse(&buf, ExtensionsForm::OneByte, &mut ev2);
 
-        assert_eq!(ev.play_delay_min, ev2.play_delay_min);
-        assert_eq!(ev.play_delay_max, ev2.play_delay_max);
+        assert_eq!(ev.play_delay, ev2.play_delay);
+    }
+
+    #[test]
+    fn transmission_time_offset() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(2, Extension::TransmissionTimeOffset);
+
+        for v in [0, 1234, -1234, 0x7f_ffff, -0x80_0000] {
+            let ev = ExtensionValues {
+                tx_time_offs: Some(v),
+                ..Default::default()
+            };
+
+            let mut buf = vec![0_u8; 8];
+            exts.write_to(&mut buf[..], &ev, ExtensionsForm::OneByte);
+
+            // One byte header with len 2, i.e. 3 bytes of data.
+            assert_eq!(buf[0], 0x22);
+
+            let mut ev2 = ExtensionValues::default();
+            exts.parse(&buf, ExtensionsForm::OneByte, &mut ev2);
+
+            assert_eq!(ev2.tx_time_offs, Some(v));
+        }
+    }
+
+    #[test]
+    fn video_orientation_legacy() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(3, Extension::VideoOrientationLegacy);
+
+        // Higher granularity bits are set, but only the 90 degree rotation is read.
+        let buf = [0x30, 0b1010_0011, 0, 0];
+
+        let mut ev = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::OneByte, &mut ev);
+
+        assert_eq!(ev.video_orientation, Some(VideoOrientation::Deg90));
+    }
+
+    #[test]
+    fn raw_values_parse() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(2, Extension::TransportSequenceNumber);
+        exts.set(5, Extension::with_raw_values("http://my-special-extension"));
+
+        // id 2 is parsed by str0m, id 5 is raw, id 7 is not negotiated.
+        let buf = [0x21, 0x01, 0x02, 0x51, 0x03, 0x04, 0x70, 0x05];
+
+        let mut ev = ExtensionValues::default();
+        exts.parse(&buf, ExtensionsForm::OneByte, &mut ev);
+
+        assert_eq!(ev.transport_cc, Some(0x0102));
+        assert_eq!(
+            ev.raw_values.iter().collect::<Vec<_>>(),
+            vec![(5, &[3, 4][..])]
+        );
+    }
+
+    #[test]
+    fn raw_values_write() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(2, Extension::TransportSequenceNumber);
+        exts.set(5, Extension::with_raw_values("http://my-special-extension"));
+
+        let mut ev = ExtensionValues {
+            transport_cc: Some(0x0102),
+            ..Default::default()
+        };
+        // Known to str0m, so not written raw.
+        ev.raw_values.set(2, &[9, 9]);
+        ev.raw_values.set(5, &[3, 4]);
+        // Not negotiated.
+        ev.raw_values.set(7, &[5]);
+
+        let form = exts.form(&ev);
+        assert_eq!(form, ExtensionsForm::OneByte);
+
+        let mut buf = [0_u8; 8];
+        let n = exts.write_to(&mut buf[..], &ev, form);
+
+        assert_eq!(&buf[..n], &[0x21, 0x01, 0x02, 0x51, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn raw_values_two_byte_form() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(5, Extension::with_raw_values("http://my-special-extension"));
+
+        let mut ev = ExtensionValues::default();
+        ev.raw_values.set(5, &[]);
+
+        let form = exts.form(&ev);
+        assert_eq!(form, ExtensionsForm::TwoByte);
+
+        let mut buf = [0_u8; 8];
+        let n = exts.write_to(&mut buf[..], &ev, form);
+        assert_eq!(&buf[..n], &[5, 0]);
+
+        let mut ev2 = ExtensionValues::default();
+        exts.parse(&buf[..n], form, &mut ev2);
+        assert_eq!(ev.raw_values, ev2.raw_values);
+    }
+
+    #[test]
+    fn raw_values_replace() {
+        let mut raw = RawExtensionValues::default();
+        raw.set(1, &[1]);
+        raw.set(2, &[2, 2]);
+        raw.set(1, &[3, 3, 3]);
+
+        assert_eq!(
+            raw.iter().collect::<Vec<_>>(),
+            vec![(2, &[2, 2][..]), (1, &[3, 3, 3][..])]
+        );
+
+        raw.remove(2);
+        assert_eq!(raw.get(2), None);
+        assert_eq!(raw.get(1), Some(&[3, 3, 3][..]));
     }
 
     #[test]
@@ -1349,6 +2222,65 @@ mod test {
         );
     }
 
+    #[test]
+    fn remap_exts_out_of_range() {
+        use Extension::*;
+
+        let mut e1 = ExtensionMap::standard();
+
+        let not_mapped = e1.remap(&[(4, &RtpMid), (20, &AudioLevel), (21, &FrameMarking)]);
+
+        // FrameMarking is not enabled locally, so that's not a failure.
+        assert_eq!(not_mapped, vec![AudioLevel]);
+
+        // AudioLevel is not sent at our id.
+        assert_eq!(e1.id_of(AudioLevel), None);
+        assert_eq!(e1.lookup(1), None);
+    }
+
+    #[test]
+    fn remap_exts_locked_target() {
+        use Extension::*;
+
+        let mut e1 = ExtensionMap::empty();
+        e1.set(1, AudioLevel);
+        e1.set(12, VideoOrientation);
+
+        e1.remap(&[(12, &VideoOrientation)]);
+
+        // AudioLevel would displace the negotiated VideoOrientation.
+        let not_mapped = e1.remap(&[(12, &AudioLevel)]);
+
+        assert_eq!(not_mapped, vec![AudioLevel]);
+        assert_eq!(e1.lookup(1), None);
+        assert_eq!(e1.lookup(12), Some(&VideoOrientation));
+    }
+
+    #[test]
+    fn remap_exts_legacy_cvo() {
+        use Extension::*;
+
+        let mut e1 = ExtensionMap::standard();
+
+        let not_mapped = e1.remap(&[(7, &VideoOrientationLegacy)]);
+
+        assert!(not_mapped.is_empty());
+        assert_eq!(e1.id_of(VideoOrientationLegacy), Some(7));
+        assert_eq!(e1.id_of(VideoOrientation), None);
+    }
+
+    #[test]
+    fn remap_exts_both_cvo() {
+        use Extension::*;
+
+        let mut e1 = ExtensionMap::standard();
+
+        e1.remap(&[(7, &VideoOrientationLegacy), (12, &VideoOrientation)]);
+
+        assert_eq!(e1.id_of(VideoOrientation), Some(12));
+        assert_eq!(e1.id_of(VideoOrientationLegacy), None);
+    }
+
     #[test]
     fn remap_exts_illegal() {
         use Extension::*;
@@ -1,7 +1,10 @@
 #![allow(clippy::unusual_byte_groupings)]
 
+use std::fmt;
+use std::ops::Deref;
+
 use super::ext::{ExtensionMap, ExtensionValues, ExtensionsForm};
-use super::{Pt, SeqNo, Ssrc, MAX_BLANK_PADDING_PAYLOAD_SIZE};
+use super::{Pt, SeqNo, Ssrc, MAX_BLANK_PADDING_PAYLOAD_SIZE, MAX_CSRC};
 
 /// Parsed header from an RTP packet.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,7 +15,6 @@ pub struct RtpHeader {
     pub has_padding: bool,
     /// RTP packet has "RTP header extensions".
     pub has_extension: bool,
-    // pub csrc_count: usize, // "contributing source" (other ssrc)
     /// A marker indicates the end of a series of packets belonging together such
     /// as for a single video frame.
     pub marker: bool,
@@ -25,18 +27,89 @@ pub struct RtpHeader {
     pub timestamp: u32,
     /// Sender source identifier.
     pub ssrc: Ssrc,
-    // pub csrc: [u32; 15],
+    /// Contributing sources, such as the streams mixed into this one.
+    pub csrc: CsrcList,
     /// The extension values parsed using the mapping via SDP.
     pub ext_vals: ExtensionValues,
     /// Length of header.
     pub header_len: usize,
 }
 
+/// List of contributing sources (CSRC) in an RTP header, at most 15.
+#[derive(Clone, Copy)]
+pub struct CsrcList {
+    ssrcs: [Ssrc; MAX_CSRC],
+    len: u8,
+}
+
+impl CsrcList {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        CsrcList {
+            ssrcs: [0.into(); MAX_CSRC],
+            len: 0,
+        }
+    }
+
+    /// Add a contributing source. Returns `false` if the list already holds 15.
+    pub fn push(&mut self, ssrc: Ssrc) -> bool {
+        let len = self.len as usize;
+        if len == MAX_CSRC {
+            return false;
+        }
+        self.ssrcs[len] = ssrc;
+        self.len += 1;
+        true
+    }
+}
+
+impl Default for CsrcList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for CsrcList {
+    type Target = [Ssrc];
+
+    fn deref(&self) -> &Self::Target {
+        &self.ssrcs[..self.len as usize]
+    }
+}
+
+impl PartialEq for CsrcList {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for CsrcList {}
+
+impl fmt::Debug for CsrcList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Collects at most 15, the rest are ignored.
+impl FromIterator<Ssrc> for CsrcList {
+    fn from_iter<T: IntoIterator<Item = Ssrc>>(iter: T) -> Self {
+        let mut list = CsrcList::new();
+        for ssrc in iter.into_iter().take(MAX_CSRC) {
+            list.push(ssrc);
+        }
+        list
+    }
+}
+
 impl RtpHeader {
     pub(crate) fn write_to(&self, buf: &mut [u8], exts: &ExtensionMap) -> usize {
+        let csrc = &self.csrc;
+
         buf[0] = 0b10_0_0_0000
             | if self.has_padding { 1 << 5 } else { 0 }
-            | if self.has_extension { 1 << 4 } else { 0 };
+            | if self.has_extension { 1 << 4 } else { 0 }
+            | csrc.len() as u8;
 
         assert!(*self.payload_type <= 127);
         buf[1] = *self.payload_type & 0b0111_1111 | if self.marker { 1 << 7 } else { 0 };
@@ -45,10 +118,16 @@ impl RtpHeader {
         buf[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
         buf[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
 
+        for (i, c) in csrc.iter().enumerate() {
+            buf[12 + i * 4..16 + i * 4].copy_from_slice(&c.to_be_bytes());
+        }
+
+        let buf = &mut buf[12 + csrc.len() * 4..];
+
         let exts_form = exts.form(&self.ext_vals);
-        buf[12..14].copy_from_slice(&exts_form.serialize());
+        buf[0..2].copy_from_slice(&exts_form.serialize());
 
-        let ext_buf = &mut buf[16..];
+        let ext_buf = &mut buf[4..];
         let mut ext_len = exts.write_to(ext_buf, &self.ext_vals, exts_form);
 
         let pad = 4 - ext_len % 4;
@@ -60,9 +139,9 @@ impl RtpHeader {
         }
 
         let bede_len = (ext_len / 4) as u16;
-        buf[14..16].copy_from_slice(&bede_len.to_be_bytes());
+        buf[2..4].copy_from_slice(&bede_len.to_be_bytes());
 
-        16 + ext_len
+        16 + csrc.len() * 4 + ext_len
     }
 
     fn do_pad(buf: &mut [u8], from: usize, pad: usize) {
@@ -130,6 +209,22 @@ impl RtpHeader {
         true
     }
 
+    /// Ids of the header extensions present in the packet this header was parsed from.
+    pub(crate) fn extension_ids<'a>(&self, buf: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        let block = if self.has_extension {
+            let start = 12 + self.csrc.len() * 4;
+            buf.get(start..start + 2)
+                .and_then(|b| ExtensionsForm::parse([b[0], b[1]]))
+                .zip(buf.get(start + 4..self.header_len))
+        } else {
+            None
+        };
+
+        block
+            .into_iter()
+            .flat_map(|(form, b)| form.iter(b).map(|(id, _)| id))
+    }
+
     pub(crate) fn parse(buf: &[u8], exts: &ExtensionMap) -> Option<RtpHeader> {
         let orig_len = buf.len();
         if buf.len() < 12 {
@@ -161,11 +256,10 @@ impl RtpHeader {
             return None;
         }
 
-        let mut csrc = [0_u32; 15];
-        for i in 0..csrc_count {
-            let n = u32::from_be_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
-            csrc[i] = n;
-        }
+        let csrc: CsrcList = buf[..csrc_len]
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]).into())
+            .collect();
 
         let buf: &[u8] = &buf[csrc_len..];
 
@@ -202,19 +296,30 @@ impl RtpHeader {
             &buf[ext_len..]
         };
 
+        // The audio levels are in the order of the CSRC list.
+        if let Some(levels) = &mut ext.csrc_audio_levels {
+            if levels.len() == csrc.len() {
+                for ((ssrc, _), c) in levels.iter_mut().zip(csrc.iter()) {
+                    *ssrc = *c;
+                }
+            } else {
+                trace!("CSRC audio levels don't match CSRC count");
+                ext.csrc_audio_levels = None;
+            }
+        }
+
         let header_len = orig_len - rest.len();
 
         let ret = RtpHeader {
             version,
             has_padding,
             has_extension,
-            // csrc_count,
             marker,
             payload_type,
             sequence_number,
             timestamp,
             ssrc: ssrc.into(),
-            // csrc,
+            csrc,
             ext_vals: ext,
             header_len,
         };
@@ -320,6 +425,7 @@ impl Default for RtpHeader {
             sequence_number: 0,
             timestamp: 0,
             ssrc: 0.into(),
+            csrc: CsrcList::new(),
             ext_vals: ExtensionValues::default(),
             header_len: 16,
         }
@@ -417,6 +523,87 @@ mod test {
         assert_eq!(extend_u15(Some(0), seq as u16), expected);
     }
 
+    #[test]
+    fn csrc_audio_levels() {
+        let mut exts = ExtensionMap::empty();
+        exts.set(3, Extension::CsrcAudioLevel);
+
+        let levels = vec![(11.into(), -10), (22.into(), -127), (33.into(), 0)];
+        let header = RtpHeader {
+            payload_type: 111.into(),
+            ssrc: 44.into(),
+            csrc: [11.into(), 22.into(), 33.into()].into_iter().collect(),
+            ext_vals: ExtensionValues {
+                csrc_audio_levels: Some(levels.clone().into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut buf = vec![0; DATAGRAM_MAX_PACKET_SIZE];
+        let header_len = header.write_to(&mut buf[..], &exts);
+        assert_eq!(buf[0] & 0b0000_1111, 3);
+        assert_eq!(header_len, 12 + 3 * 4 + 4 + 4);
+
+        let parsed = RtpHeader::parse(&buf[..header_len], &exts).unwrap();
+        assert_eq!(parsed.header_len, header_len);
+        assert_eq!(parsed.csrc, header.csrc);
+        assert_eq!(
+            parsed.ext_vals.csrc_audio_levels.as_deref(),
+            Some(&levels[..])
+        );
+
+        // Levels not matching the CSRC list are dropped.
+        let header = RtpHeader {
+            csrc: [11.into(), 22.into()].into_iter().collect(),
+            ..header
+        };
+        let header_len = header.write_to(&mut buf[..], &exts);
+        let parsed = RtpHeader::parse(&buf[..header_len], &exts).unwrap();
+        assert_eq!(parsed.csrc, header.csrc);
+        assert_eq!(parsed.ext_vals.csrc_audio_levels, None);
+    }
+
+    #[test]
+    fn csrc_list_max_15() {
+        let mut list: CsrcList = (0..20).map(Ssrc::from).collect();
+        assert_eq!(list.len(), 15);
+        assert_eq!(list[14], 14.into());
+        assert!(!list.push(20.into()));
+    }
+
+    #[test]
+    fn parse_padded_packet() {
+        let exts = ExtensionMap::empty();
+        let header = RtpHeader {
+            payload_type: 33.into(),
+            sequence_number: 1,
+            ssrc: 44.into(),
+            ..Default::default()
+        };
+
+        let mut buf = vec![0; DATAGRAM_MAX_PACKET_SIZE];
+        let header_len = header.write_to(&mut buf[..], &exts);
+        buf[header_len..header_len + 3].copy_from_slice(&[1, 2, 3]);
+        let pad_len = RtpHeader::pad_packet(&mut buf, header_len, 3, 16);
+        buf.truncate(header_len + 3 + pad_len);
+
+        let parsed = RtpHeader::parse(&buf, &exts).unwrap();
+        assert!(parsed.has_padding);
+        assert_eq!(parsed.header_len, header_len);
+
+        // The payload excludes the padding.
+        let mut payload = buf[header_len..].to_vec();
+        assert_eq!(payload.len(), 16);
+        assert!(RtpHeader::unpad_payload(&mut payload));
+        assert_eq!(payload, [1, 2, 3]);
+
+        // Padding longer than the payload is rejected.
+        let mut payload = buf[header_len..].to_vec();
+        *payload.last_mut().unwrap() = 17;
+        assert!(!RtpHeader::unpad_payload(&mut payload));
+    }
+
     #[test]
     fn test_generate_one_length_padding_packet() {
         let mut buf = vec![6; 255];
@@ -564,6 +751,7 @@ mod test {
                 sequence_number: 47000,
                 timestamp: 10000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs1),
@@ -590,6 +778,7 @@ mod test {
                 sequence_number: 47001,
                 timestamp: 12000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs2),
@@ -616,6 +805,7 @@ mod test {
                 sequence_number: 47002,
                 timestamp: 14000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs3),
@@ -664,6 +854,7 @@ mod test {
                 sequence_number: 47000,
                 timestamp: 10000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs1),
@@ -690,6 +881,7 @@ mod test {
                 sequence_number: 47001,
                 timestamp: 12000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs2),
@@ -716,6 +908,7 @@ mod test {
                 sequence_number: 47002,
                 timestamp: 14000,
                 ssrc: 777459193.into(),
+                csrc: CsrcList::new(),
                 ext_vals: ExtensionValues {
                     mid: Some("xYj".into()),
                     abs_send_time: Some(abs3),
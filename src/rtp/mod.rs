@@ -6,8 +6,9 @@ mod id;
 pub use id::{Mid, Pt, Rid, SeqNo, SessionId, Ssrc};
 
 mod ext;
+pub use ext::{ColorSpace, FrameMarking, HdrMetadata};
 pub use ext::{Extension, ExtensionMap, ExtensionSerializer, ExtensionValues};
-pub use ext::{UserExtensionValues, VideoOrientation};
+pub use ext::{RawExtensionValues, UserExtensionValues, VideoOrientation};
 
 mod dir;
 pub use dir::Direction;
@@ -17,11 +18,12 @@ pub use mtime::Frequency;
 pub use mtime::MediaTime;
 
 mod header;
-pub use header::RtpHeader;
 pub(crate) use header::{extend_u15, extend_u16, extend_u32, extend_u7, extend_u8};
+pub use header::{CsrcList, RtpHeader};
 
 mod srtp;
 pub(crate) use srtp::SrtpContext;
+pub(crate) use srtp::SRTP_MAX_PACKETS;
 pub(crate) use srtp::{SRTCP_OVERHEAD, SRTP_BLOCK_SIZE, SRTP_OVERHEAD};
 
 mod rtcp;
@@ -35,6 +37,9 @@ use crate::crypto::CryptoError;
 // Max in the RFC 3550 is 255 bytes, we limit it to be modulus 16 for SRTP and to match libWebRTC
 pub const MAX_BLANK_PADDING_PAYLOAD_SIZE: usize = 240;
 
+// The CSRC count in the RTP header is 4 bits.
+pub const MAX_CSRC: usize = 15;
+
 /// Errors that can arise in RTP.
 #[derive(Debug, Error)]
 pub enum RtpError {
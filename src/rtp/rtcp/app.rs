@@ -0,0 +1,130 @@
+use super::{pad_bytes_to_word, FeedbackMessageType, RtcpHeader, RtcpPacket};
+use super::{RtcpType, Ssrc};
+
+/*
+    0                   1                   2                   3
+    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+   |V=2|P| subtype |   PT=APP=204  |             length            |
+   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+   |                           SSRC/CSRC                           |
+   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+   |                          name (ASCII)                         |
+   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+   |                   application-dependent data                ...
+   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+*/
+
+/// Application-defined RTCP packet, also known as APP.
+///
+/// Carries data that is only meaningful to the application. str0m does not
+/// interpret it.
+///
+/// Definition: <https://www.rfc-editor.org/rfc/rfc3550#section-6.7>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct App {
+    /// Application specific subtype. At most 31.
+    pub subtype: u8,
+    /// Sender of this packet.
+    pub ssrc: Ssrc,
+    /// Name of the application, four ASCII characters.
+    pub name: [u8; 4],
+    /// Application-dependent data.
+    ///
+    /// When sending, this is padded with zeros to a multiple of 4 bytes.
+    pub data: Vec<u8>,
+}
+
+impl RtcpPacket for App {
+    fn header(&self) -> RtcpHeader {
+        // The length field is 16 bits. Sending checks against the MTU, and received
+        // packets can't be longer than their length field.
+        debug_assert!(self.length_words() <= u16::MAX as usize + 1);
+        RtcpHeader {
+            rtcp_type: RtcpType::ApplicationDefined,
+            feedback_message_type: FeedbackMessageType::Subtype(self.subtype & 0b1_1111),
+            words_less_one: (self.length_words() - 1) as u16,
+        }
+    }
+
+    fn length_words(&self) -> usize {
+        // header
+        // SSRC
+        // name
+        // data
+        3 + pad_bytes_to_word(self.data.len()) / 4
+    }
+
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        let len = self.length_words() * 4;
+
+        self.header().write_to(&mut buf[..4]);
+        buf[4..8].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.name);
+
+        let end = 12 + self.data.len();
+        buf[12..end].copy_from_slice(&self.data);
+        buf[end..len].fill(0);
+
+        len
+    }
+}
+
+impl<'a> TryFrom<(u8, &'a [u8])> for App {
+    type Error = &'static str;
+
+    fn try_from((subtype, buf): (u8, &'a [u8])) -> Result<Self, Self::Error> {
+        if buf.len() < 8 {
+            return Err("App less than 8 bytes");
+        }
+
+        let ssrc = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]).into();
+        let name = [buf[4], buf[5], buf[6], buf[7]];
+
+        Ok(App {
+            subtype,
+            ssrc,
+            name,
+            data: buf[8..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn app_roundtrip() {
+        let app = App {
+            subtype: 3,
+            ssrc: 1.into(),
+            name: *b"STRM",
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+
+        let mut buf = vec![0; 20];
+        assert_eq!(app.write_to(&mut buf), 20);
+
+        assert_eq!(&buf[..4], &[0x83, 204, 0, 4]);
+        assert_eq!(&buf[8..12], b"STRM");
+
+        let parsed: App = (3, &buf[4..]).try_into().unwrap();
+        assert_eq!(parsed, app);
+    }
+
+    #[test]
+    fn app_pads_data() {
+        let app = App {
+            subtype: 0,
+            ssrc: 1.into(),
+            name: *b"STRM",
+            data: vec![1, 2, 3, 4, 5],
+        };
+
+        let mut buf = vec![0xff; 20];
+        assert_eq!(app.length_words(), 5);
+        assert_eq!(app.write_to(&mut buf), 20);
+        assert_eq!(&buf[12..], &[1, 2, 3, 4, 5, 0, 0, 0]);
+    }
+}
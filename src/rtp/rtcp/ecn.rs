@@ -0,0 +1,123 @@
+use super::{FeedbackMessageType, RtcpHeader, RtcpPacket};
+use super::{RtcpType, Ssrc, TransportType};
+
+/// RTP/AVPF ECN feedback.
+///
+/// Cumulative counts of the ECN marks on received RTP packets.
+///
+/// Definition: <https://www.rfc-editor.org/rfc/rfc6679#section-5.1>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcnFeedback {
+    /// Sender of this feedback. Mostly irrelevant, but part of RTCP packets.
+    pub sender_ssrc: Ssrc,
+    /// The SSRC this feedback is for.
+    pub ssrc: Ssrc,
+    /// The extended highest sequence number received.
+    pub extended_highest_seq: u32,
+    /// Number of packets received marked ECT(0).
+    pub ect0: u32,
+    /// Number of packets received marked ECT(1).
+    pub ect1: u32,
+    /// Number of packets received marked ECN-CE (congestion experienced).
+    pub ce: u16,
+    /// Number of packets received marked not-ECT.
+    pub not_ect: u16,
+    /// Number of packets lost.
+    pub lost: u16,
+    /// Number of duplicate packets received.
+    pub duplicates: u16,
+}
+
+impl RtcpPacket for EcnFeedback {
+    fn header(&self) -> RtcpHeader {
+        RtcpHeader {
+            rtcp_type: RtcpType::TransportLayerFeedback,
+            feedback_message_type: FeedbackMessageType::TransportFeedback(TransportType::Ecn),
+            words_less_one: (self.length_words() - 1) as u16,
+        }
+    }
+
+    fn length_words(&self) -> usize {
+        // header
+        // sender SSRC
+        // media SSRC
+        // extended highest sequence number
+        // ECT(0)
+        // ECT(1)
+        // ECN-CE, not-ECT
+        // lost, duplicates
+        8
+    }
+
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        self.header().write_to(&mut buf[..4]);
+        buf[4..8].copy_from_slice(&self.sender_ssrc.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[12..16].copy_from_slice(&self.extended_highest_seq.to_be_bytes());
+        buf[16..20].copy_from_slice(&self.ect0.to_be_bytes());
+        buf[20..24].copy_from_slice(&self.ect1.to_be_bytes());
+        buf[24..26].copy_from_slice(&self.ce.to_be_bytes());
+        buf[26..28].copy_from_slice(&self.not_ect.to_be_bytes());
+        buf[28..30].copy_from_slice(&self.lost.to_be_bytes());
+        buf[30..32].copy_from_slice(&self.duplicates.to_be_bytes());
+        32
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for EcnFeedback {
+    type Error = &'static str;
+
+    fn try_from(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        if buf.len() < 28 {
+            return Err("EcnFeedback less than 28 bytes");
+        }
+
+        let u32_at = |i: usize| u32::from_be_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let u16_at = |i: usize| u16::from_be_bytes([buf[i], buf[i + 1]]);
+
+        Ok(EcnFeedback {
+            sender_ssrc: u32_at(0).into(),
+            ssrc: u32_at(4).into(),
+            extended_highest_seq: u32_at(8),
+            ect0: u32_at(12),
+            ect1: u32_at(16),
+            ce: u16_at(20),
+            not_ect: u16_at(22),
+            lost: u16_at(24),
+            duplicates: u16_at(26),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ecn_feedback_roundtrip() {
+        let ecn = EcnFeedback {
+            sender_ssrc: 1.into(),
+            ssrc: 2.into(),
+            extended_highest_seq: 65_546,
+            ect0: 100,
+            ect1: 0,
+            ce: 3,
+            not_ect: 1,
+            lost: 2,
+            duplicates: 0,
+        };
+
+        let mut buf = vec![0; 32];
+        assert_eq!(ecn.write_to(&mut buf), 32);
+
+        let header: RtcpHeader = buf.as_slice().try_into().unwrap();
+        assert_eq!(header.length_words(), 8);
+        assert_eq!(
+            header.feedback_message_type(),
+            FeedbackMessageType::TransportFeedback(TransportType::Ecn)
+        );
+
+        let parsed: EcnFeedback = buf[4..].try_into().unwrap();
+        assert_eq!(parsed, ecn);
+    }
+}
@@ -61,6 +61,11 @@ pub enum TransportType {
     /// Definition: <https://www.rfc-editor.org/rfc/rfc4585#section-6.2.1>
     Nack = 1,
 
+    /// ECN feedback packet.
+    ///
+    /// Definition: <https://www.rfc-editor.org/rfc/rfc6679#section-5.1>
+    Ecn = 8,
+
     /// Transportwide congestion control packet.
     ///
     /// Definition: <https://tools.ietf.org/html/draft-holmer-rmcat-transport-wide-cc-extensions-01>
@@ -74,6 +79,7 @@ impl TryFrom<u8> for TransportType {
         use TransportType::*;
         match v {
             1 => Ok(Nack),
+            8 => Ok(Ecn),
             15 => Ok(TransportWide),
             _ => {
                 trace!("Uknown TransportType: {}", v);
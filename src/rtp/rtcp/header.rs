@@ -48,6 +48,7 @@ impl RtcpHeader {
                         // each fci is one word: [pid, blp]
                         fci_length / 4
                     }
+                    TransportType::Ecn => 1,
                     TransportType::TransportWide => {
                         // TODO
                         0
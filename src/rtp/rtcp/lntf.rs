@@ -0,0 +1,140 @@
+use super::{FeedbackMessageType, PayloadType, RtcpHeader, RtcpPacket};
+use super::{RtcpType, Ssrc};
+
+const UNIQUE_IDENTIFIER: [u8; 4] = [b'L', b'N', b'T', b'F'];
+
+/// Max difference between last received and last decoded sequence number.
+pub(crate) const MAX_DELTA: u16 = 0x7fff;
+
+/*
+    0                   1                   2                   3
+    0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    |V=2|P| FMT=15  |   PT=206      |             length            |
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    |                  SSRC of packet sender                        |
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    |                  SSRC of media source                         |
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    |  Unique identifier 'L' 'N' 'T' 'F'                            |
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    | Last Decoded Sequence Number  | Last Received SeqNum Delta  |D|
+    +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+*/
+
+/// Loss notification, `goog-lntf`.
+///
+/// Tells the sender that packets were lost, and whether the frames received since
+/// can be decoded. This lets an encoder recover without a keyframe.
+///
+/// Definition: <https://datatracker.ietf.org/doc/html/draft-ietf-avtcore-rtp-loss-notification>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lntf {
+    /// Sender of this feedback. Mostly irrelevant, but part of RTCP packets.
+    pub sender_ssrc: Ssrc,
+    /// The SSRC this loss notification is for.
+    pub ssrc: Ssrc,
+    /// Sequence number of the last packet of the last decoded frame.
+    pub last_decoded: u16,
+    /// Sequence number of the last received packet.
+    ///
+    /// At most 0x7fff after `last_decoded`.
+    pub last_received: u16,
+    /// Whether the frame of the last received packet is decodable.
+    pub decodable: bool,
+}
+
+impl RtcpPacket for Lntf {
+    fn header(&self) -> RtcpHeader {
+        RtcpHeader {
+            rtcp_type: RtcpType::PayloadSpecificFeedback,
+            feedback_message_type: FeedbackMessageType::PayloadFeedback(
+                PayloadType::ApplicationLayer,
+            ),
+            words_less_one: (self.length_words() - 1) as u16,
+        }
+    }
+
+    fn length_words(&self) -> usize {
+        // header
+        // sender SSRC
+        // media SSRC
+        // unique identifier
+        // sequence numbers
+        5
+    }
+
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        let delta = self
+            .last_received
+            .wrapping_sub(self.last_decoded)
+            .min(MAX_DELTA);
+        let delta_and_flag = delta << 1 | self.decodable as u16;
+
+        self.header().write_to(&mut buf[..4]);
+        buf[4..8].copy_from_slice(&self.sender_ssrc.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[12..16].copy_from_slice(&UNIQUE_IDENTIFIER);
+        buf[16..18].copy_from_slice(&self.last_decoded.to_be_bytes());
+        buf[18..20].copy_from_slice(&delta_and_flag.to_be_bytes());
+        20
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Lntf {
+    type Error = &'static str;
+
+    fn try_from(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        if buf.len() < 16 {
+            return Err("Lntf less than 16 bytes");
+        }
+
+        if buf[8..12] != UNIQUE_IDENTIFIER {
+            return Err("Missing lntf identifier");
+        }
+
+        let sender_ssrc = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]).into();
+        let ssrc = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]).into();
+        let last_decoded = u16::from_be_bytes([buf[12], buf[13]]);
+        let delta_and_flag = u16::from_be_bytes([buf[14], buf[15]]);
+
+        Ok(Lntf {
+            sender_ssrc,
+            ssrc,
+            last_decoded,
+            last_received: last_decoded.wrapping_add(delta_and_flag >> 1),
+            decodable: delta_and_flag & 1 == 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lntf_roundtrip() {
+        let lntf = Lntf {
+            sender_ssrc: 1.into(),
+            ssrc: 2.into(),
+            last_decoded: 65_530,
+            last_received: 10,
+            decodable: true,
+        };
+
+        let mut buf = vec![0; 20];
+        assert_eq!(lntf.write_to(&mut buf), 20);
+
+        assert_eq!(&buf[..4], &[0x8f, 206, 0, 4]);
+        assert_eq!(&buf[16..], &[0xff, 0xfa, 0, 33]);
+
+        let parsed: Lntf = buf[4..].try_into().unwrap();
+        assert_eq!(parsed, lntf);
+    }
+
+    #[test]
+    fn lntf_not_remb() {
+        let buf = [0, 0, 0, 1, 0, 0, 0, 0, b'R', b'E', b'M', b'B', 0, 0, 0, 0];
+        assert!(Lntf::try_from(&buf[..]).is_err());
+    }
+}
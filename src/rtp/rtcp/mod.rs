@@ -2,6 +2,8 @@
 
 mod header;
 use std::collections::VecDeque;
+use std::panic::RefUnwindSafe;
+use std::time::Instant;
 
 pub use header::{RtcpHeader, RtcpType};
 
@@ -19,7 +21,8 @@ mod rr;
 pub use rr::{ReceiverReport, ReceptionReport};
 
 mod xr;
-pub use xr::{Dlrr, DlrrItem, ExtendedReport, ReportBlock, Rrtr};
+pub use xr::{Dlrr, DlrrItem, ExtendedReport, JitterBufferMetrics, MetricInterval};
+pub use xr::{ReportBlock, Rrtr};
 
 mod sdes;
 pub use sdes::{Descriptions, Sdes, SdesType};
@@ -45,6 +48,16 @@ pub use rtcpfb::RtcpFb;
 mod remb;
 pub use remb::Remb;
 
+mod ecn;
+pub use ecn::EcnFeedback;
+
+mod lntf;
+pub use lntf::Lntf;
+pub(crate) use lntf::MAX_DELTA as LNTF_MAX_DELTA;
+
+mod app;
+pub use app::App;
+
 use super::extend_u16;
 use super::SeqNo;
 use super::Ssrc;
@@ -62,9 +75,21 @@ pub trait RtcpPacket {
     fn write_to(&self, buf: &mut [u8]) -> usize;
 }
 
+/// Observer of incoming RTCP.
+///
+/// Set using [`RtcConfig::set_rtcp_observer()`][crate::RtcConfig::set_rtcp_observer].
+pub trait RtcpObserver: std::fmt::Debug + Send + Sync + RefUnwindSafe + 'static {
+    /// Called with the decrypted and parsed packets of each incoming compound RTCP packet,
+    /// before str0m handles them.
+    ///
+    /// The packets are borrowed, str0m handles them the same regardless of the observer.
+    fn on_rtcp_rx(&self, now: Instant, packets: &[Rtcp]);
+}
+
 /// RTCP reports handled by str0m.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Rtcp {
     /// Sender report. Also known as SR.
     SenderReport(SenderReport),
@@ -88,6 +113,12 @@ pub enum Rtcp {
     Twcc(Twcc),
     /// Receiver Estimated Maximum Bitrate. Feedback to the sender about the maximum bitrate.
     Remb(Remb),
+    /// ECN feedback. Counts of ECN marks on received packets.
+    EcnFeedback(EcnFeedback),
+    /// Loss notification. Lost packets and whether later frames are decodable.
+    Lntf(Lntf),
+    /// Application-defined. Not interpreted by str0m.
+    App(App),
 }
 
 impl Rtcp {
@@ -136,6 +167,7 @@ impl Rtcp {
     pub(crate) fn write_packet(
         feedback: &mut VecDeque<Rtcp>,
         buf: &mut [u8],
+        compound: bool,
         mut output: impl FnMut(Rtcp),
     ) -> usize {
         if feedback.is_empty() {
@@ -156,6 +188,13 @@ impl Rtcp {
             // Length of next item.
             let item_len = fb.length_words() * 4;
 
+            // Items larger than the entire buffer would block the queue forever.
+            if item_len > total_len {
+                warn!("Drop RTCP item larger than buffer: {}", item_len);
+                feedback.pop_front();
+                continue;
+            }
+
             // Capacity left in the buffer.
             let capacity = total_len - offset;
             if capacity < item_len {
@@ -176,6 +215,11 @@ impl Rtcp {
 
             // Move offsets for the amount written.
             offset += item_len;
+
+            // Reduced-size RTCP (RFC 5506) is one packet per datagram.
+            if !compound {
+                break;
+            }
         }
 
         offset
@@ -236,6 +280,9 @@ impl Rtcp {
             Rtcp::Fir(v) => v.reports.is_full(),
             Rtcp::Twcc(_) => true,
             Rtcp::Remb(_) => true,
+            Rtcp::EcnFeedback(_) => true,
+            Rtcp::Lntf(_) => true,
+            Rtcp::App(_) => true,
         }
     }
 
@@ -263,6 +310,12 @@ impl Rtcp {
             Rtcp::Twcc(_) => false,
             // A REMB report is never empty.
             Rtcp::Remb(_) => false,
+            // An ECN feedback is never empty.
+            Rtcp::EcnFeedback(_) => false,
+            // A loss notification is never empty.
+            Rtcp::Lntf(_) => false,
+            // An application-defined packet is never empty.
+            Rtcp::App(_) => false,
         }
     }
 
@@ -317,7 +370,10 @@ impl Rtcp {
             }
 
             if !any_change {
-                word_capacity -= fb_a.length_words();
+                // Emptied items are pruned below and take no space.
+                if !fb_a.is_empty() {
+                    word_capacity -= fb_a.length_words();
+                }
                 i += 1;
             }
         }
@@ -340,10 +396,13 @@ impl Rtcp {
             Fir(_) => 5,
             Twcc(_) => 6,
             Remb(_) => 7,
-            ExtendedReport(_) => 10,
+            EcnFeedback(_) => 8,
+            Lntf(_) => 9,
+            App(_) => 10,
+            ExtendedReport(_) => 11,
 
             // Goodbye last since they remove stuff.
-            Goodbye(_) => 11,
+            Goodbye(_) => 12,
         }
     }
 }
@@ -361,6 +420,9 @@ impl RtcpPacket for Rtcp {
             Rtcp::Fir(v) => v.header(),
             Rtcp::Twcc(v) => v.header(),
             Rtcp::Remb(v) => v.header(),
+            Rtcp::EcnFeedback(v) => v.header(),
+            Rtcp::Lntf(v) => v.header(),
+            Rtcp::App(v) => v.header(),
         }
     }
 
@@ -376,6 +438,9 @@ impl RtcpPacket for Rtcp {
             Rtcp::Fir(v) => v.length_words(),
             Rtcp::Twcc(v) => v.length_words(),
             Rtcp::Remb(v) => v.length_words(),
+            Rtcp::EcnFeedback(v) => v.length_words(),
+            Rtcp::Lntf(v) => v.length_words(),
+            Rtcp::App(v) => v.length_words(),
         }
     }
 
@@ -391,6 +456,9 @@ impl RtcpPacket for Rtcp {
             Rtcp::Fir(v) => v.write_to(buf),
             Rtcp::Twcc(v) => v.write_to(buf),
             Rtcp::Remb(v) => v.write_to(buf),
+            Rtcp::EcnFeedback(v) => v.write_to(buf),
+            Rtcp::Lntf(v) => v.write_to(buf),
+            Rtcp::App(v) => v.write_to(buf),
         }
     }
 }
@@ -411,7 +479,12 @@ impl<'a> TryFrom<&'a [u8]> for Rtcp {
             RtcpType::ReceiverReport => Rtcp::ReceiverReport(buf.try_into()?),
             RtcpType::SourceDescription => Rtcp::SourceDescription(buf.try_into()?),
             RtcpType::Goodbye => Rtcp::Goodbye((header.count(), buf).try_into()?),
-            RtcpType::ApplicationDefined => return Err("Ignore RTCP type: ApplicationDefined"),
+            RtcpType::ApplicationDefined => {
+                let FeedbackMessageType::Subtype(subtype) = header.feedback_message_type() else {
+                    return Err("Expected Subtype in FeedbackMessageType");
+                };
+                Rtcp::App((subtype, buf).try_into()?)
+            }
             RtcpType::TransportLayerFeedback => {
                 let tlfb = match header.feedback_message_type() {
                     FeedbackMessageType::TransportFeedback(v) => v,
@@ -420,6 +493,7 @@ impl<'a> TryFrom<&'a [u8]> for Rtcp {
 
                 match tlfb {
                     TransportType::Nack => Rtcp::Nack(buf.try_into()?),
+                    TransportType::Ecn => Rtcp::EcnFeedback(buf.try_into()?),
                     TransportType::TransportWide => Rtcp::Twcc(buf.try_into()?),
                 }
             }
@@ -441,6 +515,9 @@ impl<'a> TryFrom<&'a [u8]> for Rtcp {
                             if let Ok(remb) = Remb::try_from(buf) {
                                 return Ok(Rtcp::Remb(remb));
                             }
+                            if let Ok(lntf) = Lntf::try_from(buf) {
+                                return Ok(Rtcp::Lntf(lntf));
+                            }
                         }
                         return Err("Ignore PayloadType: ApplicationLayer");
                     }
@@ -496,7 +573,7 @@ mod test {
         twcc.delta.push_back(Delta::Small(0x84));
         queue.push_back(Rtcp::Twcc(twcc));
         let mut buf = vec![0; 1500];
-        let n = Rtcp::write_packet(&mut queue, &mut buf, |_| {});
+        let n = Rtcp::write_packet(&mut queue, &mut buf, true, |_| {});
         buf.truncate(n);
         println!("{buf:02x?}");
         assert_eq!(
@@ -568,6 +645,60 @@ mod test {
         assert_eq!(iter.next().unwrap(), &report(4));
     }
 
+    #[test]
+    fn pack_40_rr() {
+        let mut queue = VecDeque::new();
+        for i in 1..=40 {
+            queue.push_back(rr(i));
+        }
+
+        // Room for 31 + 9 blocks, but not for the emptied RRs on top.
+        Rtcp::pack(&mut queue, 250);
+
+        assert_eq!(queue.len(), 2);
+
+        let sizes: Vec<_> = queue
+            .iter()
+            .map(|fb| match fb {
+                Rtcp::ReceiverReport(v) => v.reports.len(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(sizes, [31, 9]);
+
+        let ssrcs: Vec<_> = queue
+            .iter()
+            .flat_map(|fb| match fb {
+                Rtcp::ReceiverReport(v) => v.reports.iter().map(|r| *r.ssrc).collect::<Vec<_>>(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(ssrcs, (1..=40).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn write_packet_drops_oversized() {
+        let mut feedback = VecDeque::new();
+        feedback.push_back(Rtcp::App(App {
+            subtype: 0,
+            ssrc: 1.into(),
+            name: *b"STRM",
+            data: vec![0; 2000],
+        }));
+        feedback.push_back(rr(3));
+
+        let mut buf = vec![0_u8; 1360];
+        let n = Rtcp::write_packet(&mut feedback, &mut buf, true, |_| {});
+        buf.truncate(n);
+
+        let mut parsed = VecDeque::new();
+        Rtcp::read_packet(&buf, &mut parsed);
+
+        assert!(feedback.is_empty());
+        assert_eq!(parsed.len(), 1);
+        assert!(matches!(parsed[0], Rtcp::ReceiverReport(_)));
+    }
+
     #[test]
     fn roundtrip_sr_rr() {
         let now = Instant::now();
@@ -578,7 +709,7 @@ mod test {
         feedback.push_back(rr(5));
 
         let mut buf = vec![0_u8; 1360];
-        let n = Rtcp::write_packet(&mut feedback, &mut buf, |_| {});
+        let n = Rtcp::write_packet(&mut feedback, &mut buf, true, |_| {});
         buf.truncate(n);
 
         let mut parsed = VecDeque::new();
@@ -1,4 +1,7 @@
-use super::{DlrrItem, FirEntry, NackEntry, ReceptionReport, Remb, ReportBlock, ReportList};
+use super::{
+    App, DlrrItem, EcnFeedback, FirEntry, JitterBufferMetrics, Lntf, NackEntry, ReceptionReport,
+    Remb, ReportBlock, ReportList,
+};
 use super::{Rrtr, Rtcp, Sdes, SenderInfo, Ssrc, Twcc};
 
 /// Normalization of [`Rtcp`] so we can deal with one SSRC at a time.
@@ -9,6 +12,7 @@ pub enum RtcpFb {
     ReceptionReport(ReceptionReport),  // rx -> tx
     DlrrItem(DlrrItem),                // rx <- tx
     Rrtr((Rrtr, Ssrc)),                // rx -> tx
+    JitterBuffer(JitterBufferMetrics), // rx -> tx
     SourceDescription(Sdes),           // tx -> rx
     Goodbye(Ssrc),                     // tx -> rx
     Nack(Ssrc, ReportList<NackEntry>), // rx -> tx
@@ -16,6 +20,9 @@ pub enum RtcpFb {
     Fir(FirEntry),                     // rx -> tx
     Twcc(Twcc),                        // rx -> tx
     Remb(Remb),                        // rx -> tx
+    Ecn(EcnFeedback),                  // rx -> tx
+    Lntf(Lntf),                        // rx -> tx
+    App(App),                          // app
 }
 
 impl RtcpFb {
@@ -48,6 +55,7 @@ impl RtcpFb {
                             ReportBlock::Dlrr(v) => {
                                 q.extend(v.items.iter().map(|i| RtcpFb::DlrrItem(*i)))
                             }
+                            ReportBlock::JitterBuffer(b) => q.push(RtcpFb::JitterBuffer(b)),
                         }
                     }
                 }
@@ -72,6 +80,15 @@ impl RtcpFb {
                 Rtcp::Remb(v) => {
                     q.push(RtcpFb::Remb(v));
                 }
+                Rtcp::EcnFeedback(v) => {
+                    q.push(RtcpFb::Ecn(v));
+                }
+                Rtcp::Lntf(v) => {
+                    q.push(RtcpFb::Lntf(v));
+                }
+                Rtcp::App(v) => {
+                    q.push(RtcpFb::App(v));
+                }
             }
         }
         q.into_iter()
@@ -83,12 +100,16 @@ impl RtcpFb {
             RtcpFb::ReceptionReport(v) => v.ssrc,
             RtcpFb::DlrrItem(v) => v.ssrc,
             RtcpFb::Rrtr((_, ssrc)) => *ssrc,
+            RtcpFb::JitterBuffer(v) => v.ssrc,
             RtcpFb::SourceDescription(v) => v.ssrc,
             RtcpFb::Goodbye(v) => *v,
             RtcpFb::Nack(v, _) => *v,
             RtcpFb::Pli(v) => *v,
             RtcpFb::Fir(v) => v.ssrc,
             RtcpFb::Twcc(v) => v.ssrc,
+            RtcpFb::Ecn(v) => v.ssrc,
+            RtcpFb::Lntf(v) => v.ssrc,
+            RtcpFb::App(v) => v.ssrc,
             RtcpFb::Remb(v) => v.ssrcs.first().map(|ssrc| (*ssrc).into()).unwrap_or(v.ssrc),
         }
     }
@@ -127,7 +127,9 @@ impl WordSized for Sdes {
             .iter()
             // 2 here for 2 byte encoding of type + length
             .map(|(_, s)| 2 + s.as_bytes().len())
-            .sum::<usize>();
+            .sum::<usize>()
+            // 1 for the END item, which is always present.
+            + 1;
 
         let padded = pad_bytes_to_word(byte_size);
 
@@ -274,4 +276,44 @@ mod test {
 
         assert_eq!(s1, s2);
     }
+
+    #[test]
+    fn cname_on_word_boundary() {
+        // 4 (ssrc) + 2 (type/len) + 6 (value) = 12, the END item needs another word.
+        let mut s1 = Sdes {
+            ssrc: 1.into(),
+            values: ReportList::new(),
+        };
+        s1.values.push((SdesType::CNAME, "abcdef".into()));
+
+        let mut buf = vec![0; 50];
+        let n = s1.write_to(&mut buf);
+
+        assert_eq!(n, 16);
+        assert_eq!(s1.word_size(), 4);
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let mut d1 = Descriptions {
+            reports: Box::new(ReportList::new()),
+        };
+
+        for (ssrc, cname) in [(1, "abcdef"), (2, "abc"), (3, "abcdefgh")] {
+            let mut s = Sdes {
+                ssrc: ssrc.into(),
+                values: ReportList::new(),
+            };
+            s.values.push((SdesType::CNAME, cname.into()));
+            d1.reports.push(s);
+        }
+
+        let mut buf = vec![0; 100];
+        let n = d1.write_to(&mut buf);
+        assert_eq!(n, d1.length_words() * 4);
+
+        let d2: Descriptions = buf[4..n].try_into().unwrap();
+
+        assert_eq!(d1, d2);
+    }
 }
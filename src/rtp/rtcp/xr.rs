@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::util::InstantExt;
 
@@ -32,6 +32,7 @@ pub struct ExtendedReport {
 pub enum ReportBlock {
     Rrtr(Rrtr),
     Dlrr(Dlrr),
+    JitterBuffer(JitterBufferMetrics),
 }
 
 //   0                   1                   2                   3
@@ -85,6 +86,55 @@ pub struct DlrrItem {
     pub last_rr_delay: u32,
 }
 
+//   0                   1                   2                   3
+//   0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+//   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//   |     BT=23     | I |C|  resv.  |      Block Length=3           |
+//   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//   |                        SSRC of Source                         |
+//   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//   |          JB nominal           |        JB maximum             |
+//   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//   |         JB high-water mark    |        JB low-water mark      |
+//   +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+
+/// De-Jitter Buffer Metrics Block.
+///
+/// Reports the playout delay of the receiver's jitter buffer back to the sender.
+/// The delays are `None` when the receiver doesn't know them.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc7005#section-3>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JitterBufferMetrics {
+    /// The SSRC of the media source the jitter buffer is for.
+    pub ssrc: Ssrc,
+    /// Which period the high- and low-water marks cover.
+    pub interval: MetricInterval,
+    /// Whether the jitter buffer is adaptive, as opposed to fixed.
+    pub adaptive: bool,
+    /// The current nominal delay of the jitter buffer.
+    pub nominal: Option<Duration>,
+    /// The current maximum delay, after which packets arriving are discarded.
+    pub maximum: Option<Duration>,
+    /// The highest nominal delay during the interval.
+    pub high_water: Option<Duration>,
+    /// The lowest nominal delay during the interval.
+    pub low_water: Option<Duration>,
+}
+
+/// Interval the metrics of a [`JitterBufferMetrics`] are for.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc6792#section-5.1>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricInterval {
+    /// Since the previous report.
+    Interval,
+    /// Since the start of the stream.
+    Cumulative,
+    /// A value sampled at the time of the report.
+    Sampled,
+}
+
 impl RtcpPacket for ExtendedReport {
     fn header(&self) -> RtcpHeader {
         RtcpHeader {
@@ -111,6 +161,7 @@ impl RtcpPacket for ExtendedReport {
             len += match block {
                 ReportBlock::Rrtr(b) => b.write_to(&mut buf[len..]),
                 ReportBlock::Dlrr(b) => b.write_to(&mut buf[len..]),
+                ReportBlock::JitterBuffer(b) => b.write_to(&mut buf[len..]),
             };
         }
 
@@ -123,6 +174,7 @@ impl ReportBlock {
         match self {
             Self::Rrtr(_) => Rrtr::len(),
             Self::Dlrr(v) => v.len(),
+            Self::JitterBuffer(_) => JitterBufferMetrics::len(),
         }
     }
 }
@@ -175,6 +227,46 @@ impl Dlrr {
     }
 }
 
+impl JitterBufferMetrics {
+    fn write_to(&self, buf: &mut [u8]) -> usize {
+        // block type
+        buf[0] = 23_u8;
+        // interval metric flag, jitter buffer configuration and reserved.
+        let interval: u8 = match self.interval {
+            MetricInterval::Interval => 0b11,
+            MetricInterval::Cumulative => 0b10,
+            MetricInterval::Sampled => 0b01,
+        };
+        buf[1] = (interval << 6) | ((self.adaptive as u8) << 5);
+        // block length
+        buf[2..4].copy_from_slice(&3_u16.to_be_bytes());
+
+        buf[4..8].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[8..10].copy_from_slice(&delay_to_ms(self.nominal).to_be_bytes());
+        buf[10..12].copy_from_slice(&delay_to_ms(self.maximum).to_be_bytes());
+        buf[12..14].copy_from_slice(&delay_to_ms(self.high_water).to_be_bytes());
+        buf[14..16].copy_from_slice(&delay_to_ms(self.low_water).to_be_bytes());
+
+        16
+    }
+
+    fn len() -> usize {
+        16
+    }
+}
+
+/// 0xFFFF means unavailable, 0xFFFE means 65534 or more.
+fn delay_to_ms(delay: Option<Duration>) -> u16 {
+    match delay {
+        Some(d) => d.as_millis().min(0xfffe) as u16,
+        None => 0xffff,
+    }
+}
+
+fn ms_to_delay(ms: u16) -> Option<Duration> {
+    (ms != 0xffff).then(|| Duration::from_millis(ms as u64))
+}
+
 impl<'a> TryFrom<&'a [u8]> for ExtendedReport {
     type Error = &'static str;
 
@@ -217,6 +309,10 @@ impl<'a> TryFrom<&'a [u8]> for ReportBlock {
                 let block = Dlrr::try_from(buf)?;
                 Ok(Self::Dlrr(block))
             }
+            23 => {
+                let block = JitterBufferMetrics::try_from(buf)?;
+                Ok(Self::JitterBuffer(block))
+            }
             _ => Err("unknown block type"),
         }
     }
@@ -260,3 +356,80 @@ impl<'a> TryFrom<&'a [u8]> for Dlrr {
         Ok(Dlrr { items })
     }
 }
+
+impl<'a> TryFrom<&'a [u8]> for JitterBufferMetrics {
+    type Error = &'static str;
+
+    fn try_from(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        if buf.len() < Self::len() {
+            return Err("Less than 16 bytes for JitterBufferMetrics");
+        }
+
+        let interval = match buf[1] >> 6 {
+            0b11 => MetricInterval::Interval,
+            0b10 => MetricInterval::Cumulative,
+            0b01 => MetricInterval::Sampled,
+            _ => return Err("Reserved interval metric flag"),
+        };
+        let adaptive = buf[1] & 0b0010_0000 > 0;
+
+        let ssrc = u32::from_be_bytes(buf[4..8].try_into().unwrap()).into();
+        let ms = |i: usize| ms_to_delay(u16::from_be_bytes([buf[i], buf[i + 1]]));
+
+        Ok(JitterBufferMetrics {
+            ssrc,
+            interval,
+            adaptive,
+            nominal: ms(8),
+            maximum: ms(10),
+            high_water: ms(12),
+            low_water: ms(14),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn jitter_buffer_metrics_roundtrip() {
+        let xr = ExtendedReport {
+            ssrc: 1.into(),
+            blocks: vec![
+                ReportBlock::Rrtr(Rrtr {
+                    ntp_time: Instant::from_ntp_64(0x1234_5678_0000_0000),
+                }),
+                ReportBlock::JitterBuffer(JitterBufferMetrics {
+                    ssrc: 2.into(),
+                    interval: MetricInterval::Interval,
+                    adaptive: true,
+                    nominal: Some(Duration::from_millis(60)),
+                    maximum: Some(Duration::from_secs(100)),
+                    high_water: Some(Duration::from_millis(80)),
+                    low_water: None,
+                }),
+            ],
+        };
+
+        let mut buf = vec![0; 1500];
+        let n = xr.write_to(&mut buf);
+        assert_eq!(n, xr.length_words() * 4);
+
+        assert_eq!(&buf[20..24], &[23, 0b1110_0000, 0, 3]);
+
+        let parsed = ExtendedReport::try_from(&buf[4..n]).unwrap();
+        let ReportBlock::JitterBuffer(jb) = parsed.blocks[1] else {
+            panic!("Expected JitterBuffer block");
+        };
+
+        assert_eq!(jb.ssrc, 2.into());
+        assert_eq!(jb.interval, MetricInterval::Interval);
+        assert!(jb.adaptive);
+        assert_eq!(jb.nominal, Some(Duration::from_millis(60)));
+        // Over-range is capped at 65534ms.
+        assert_eq!(jb.maximum, Some(Duration::from_millis(65534)));
+        assert_eq!(jb.high_water, Some(Duration::from_millis(80)));
+        assert_eq!(jb.low_water, None);
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use crate::crypto::{self, new_aead_aes_128_gcm, new_aes_128_cm_sha1_80, KeyingMaterial};
-use crate::crypto::{aead_aes_128_gcm, aes_128_cm_sha1_80, SrtpProfile};
+use crate::crypto::{aead_aes_128_gcm, aes_128_cm_sha1_80, Sha1HmacKey, SrtpProfile};
 
 use super::header::RtpHeader;
 
@@ -28,6 +28,11 @@ const MAX_TAG_LEN: usize = aead_aes_128_gcm::TAG_LEN;
 pub const SRTCP_OVERHEAD: usize = MAX_TAG_LEN + SRTCP_INDEX_LEN;
 pub const SRTP_OVERHEAD: usize = MAX_TAG_LEN;
 
+/// Max number of SRTP packets per SSRC protected with the same key (RFC3711 Section 9.2).
+pub const SRTP_MAX_PACKETS: u64 = 1 << 48;
+/// Max number of SRTCP packets protected with the same key, the SRTCP index is 31 bits.
+pub const SRTCP_MAX_PACKETS: u64 = 1 << 31;
+
 impl SrtpContext {
     /// Create an SRTP context for the relevant profile using the provided keying material.
     pub fn new(profile: SrtpProfile, mat: &KeyingMaterial, left: bool) -> Self {
@@ -38,12 +43,19 @@ impl SrtpContext {
                 rtcp: Derived::PassThrough,
                 srtcp_index: 0,
             },
-            SrtpProfile::Aes128CmSha1_80 => {
-                use aes_128_cm_sha1_80::{KEY_LEN, SALT_LEN};
+            SrtpProfile::Aes128CmSha1_80 | SrtpProfile::Aes128CmSha1_32 => {
+                use aes_128_cm_sha1_80::{HMAC_TAG_LEN, HMAC_TAG_LEN_32, KEY_LEN, SALT_LEN};
 
                 let key = SrtpKey::<KEY_LEN, SALT_LEN>::new(mat, left);
 
-                let (rtp, rtcp) = Derived::aes_128_cm_sha1_80(&key);
+                // The profiles only differ in the SRTP tag length.
+                let rtp_tag_len = if profile == SrtpProfile::Aes128CmSha1_32 {
+                    HMAC_TAG_LEN_32
+                } else {
+                    HMAC_TAG_LEN
+                };
+
+                let (rtp, rtcp) = Derived::aes_128_cm_sha1_80(&key, rtp_tag_len);
 
                 SrtpContext {
                     rtp,
@@ -102,6 +114,16 @@ pub struct SrtpContext {
 }
 
 impl SrtpContext {
+    /// The profile this context was created for.
+    pub fn profile(&self) -> SrtpProfile {
+        self.rtp.profile()
+    }
+
+    /// Number of SRTCP packets that can be protected before the SRTCP index wraps.
+    pub fn srtcp_packets_left(&self) -> u64 {
+        SRTCP_MAX_PACKETS - self.srtcp_index as u64
+    }
+
     pub fn protect_rtp(
         &mut self,
         buf: &[u8],
@@ -141,16 +163,20 @@ impl SrtpContext {
         match &mut self.rtp {
             #[cfg(feature = "_internal_test_exports")]
             Derived::PassThrough => input.to_vec(),
-            Derived::Aes128CmSha1_80 { key, salt, enc, .. } => {
+            Derived::Aes128CmSha1_80 {
+                key,
+                salt,
+                enc,
+                tag_len,
+                ..
+            } => {
                 assert!(
                     input.len() % SRTP_BLOCK_SIZE == 0,
                     "RTP body should be padded to 16 byte block size, {header:?} with body length {} was not", input.len()
                 );
-                use aes_128_cm_sha1_80::HMAC_TAG_LEN;
-
                 let iv = aes_128_cm_sha1_80::rtp_iv(*salt, *header.ssrc, srtp_index);
 
-                let mut output = vec![0_u8; buf.len() + HMAC_TAG_LEN];
+                let mut output = vec![0_u8; buf.len() + *tag_len];
                 enc.encrypt(&iv, input, &mut output[hlen..])
                     .expect("rtp encrypt");
 
@@ -189,14 +215,18 @@ impl SrtpContext {
         match &mut self.rtp {
             #[cfg(feature = "_internal_test_exports")]
             Derived::PassThrough => Some(buf.to_vec()),
-            Derived::Aes128CmSha1_80 { key, salt, dec, .. } => {
-                use aes_128_cm_sha1_80::HMAC_TAG_LEN;
-
-                if buf.len() < HMAC_TAG_LEN {
+            Derived::Aes128CmSha1_80 {
+                key,
+                salt,
+                dec,
+                tag_len,
+                ..
+            } => {
+                if buf.len() < header.header_len + *tag_len {
                     return None;
                 }
 
-                let hmac_start = buf.len() - HMAC_TAG_LEN;
+                let hmac_start = buf.len() - *tag_len;
 
                 if !aes_128_cm_sha1_80::rtp_verify(
                     key,
@@ -533,10 +563,12 @@ enum Derived {
     #[cfg(feature = "_internal_test_exports")]
     PassThrough,
     Aes128CmSha1_80 {
-        key: [u8; 20],
+        key: Sha1HmacKey,
         salt: aes_128_cm_sha1_80::RtpSalt,
         enc: Box<dyn aes_128_cm_sha1_80::CipherCtx>,
         dec: Box<dyn aes_128_cm_sha1_80::CipherCtx>,
+        /// Length of the authentication tag. Shorter for SRTP with the _32 profile.
+        tag_len: usize,
     },
     AeadAes128Gcm {
         salt: aead_aes_128_gcm::RtpSalt,
@@ -548,6 +580,7 @@ enum Derived {
 impl Derived {
     fn aes_128_cm_sha1_80(
         srtp_key: &SrtpKey<{ aes_128_cm_sha1_80::KEY_LEN }, { aes_128_cm_sha1_80::SALT_LEN }>,
+        rtp_tag_len: usize,
     ) -> (Self, Self) {
         use aes_128_cm_sha1_80::*;
 
@@ -582,17 +615,19 @@ impl Derived {
         srtp_key.derive(LABEL_RTCP_SALT, &mut rtcp_salt[..]);
 
         let rtp = Derived::Aes128CmSha1_80 {
-            key: rtp_hmac,
+            key: Sha1HmacKey::new(&rtp_hmac),
             salt: rtp_salt,
             enc: new_aes_128_cm_sha1_80(rtp_aes, true),
             dec: new_aes_128_cm_sha1_80(rtp_aes, false),
+            tag_len: rtp_tag_len,
         };
 
         let rtcp = Derived::Aes128CmSha1_80 {
-            key: rtcp_hmac,
+            key: Sha1HmacKey::new(&rtcp_hmac),
             salt: rtcp_salt,
             enc: new_aes_128_cm_sha1_80(rtcp_aes, true),
             dec: new_aes_128_cm_sha1_80(rtcp_aes, false),
+            tag_len: HMAC_TAG_LEN,
         };
 
         (rtp, rtcp)
@@ -638,7 +673,13 @@ impl Derived {
         match self {
             #[cfg(feature = "_internal_test_exports")]
             Derived::PassThrough => SrtpProfile::PassThrough,
-            Derived::Aes128CmSha1_80 { .. } => SrtpProfile::Aes128CmSha1_80,
+            Derived::Aes128CmSha1_80 { tag_len, .. } => {
+                if *tag_len == aes_128_cm_sha1_80::HMAC_TAG_LEN_32 {
+                    SrtpProfile::Aes128CmSha1_32
+                } else {
+                    SrtpProfile::Aes128CmSha1_80
+                }
+            }
             Derived::AeadAes128Gcm { .. } => SrtpProfile::AeadAes128Gcm,
         }
     }
@@ -711,6 +752,8 @@ mod test {
     }
 
     mod test_aes128_cm_sha1_80 {
+        use crate::rtp_::ExtensionMap;
+
         use super::aes_128_cm_sha1_80::*;
         use super::*;
 
@@ -759,6 +802,80 @@ mod test {
             let encrypted = ctx_rx.protect_rtcp(&decrypted);
             assert_eq!(encrypted, SRTCP);
         }
+
+        mod libsrtp {
+            // Test vectors from libsrtp test/srtp_driver.c
+
+            // Master key followed by master salt.
+            pub(super) const KEY: [u8; 30] = [
+                0xe1, 0xf9, 0x7a, 0x0d, 0x3e, 0x01, 0x8b, 0xe0, 0xd6, 0x4f, 0xa3, 0x2c, 0x06, 0xde,
+                0x41, 0x39, 0x0e, 0xc6, 0x75, 0xad, 0x49, 0x8a, 0xfe, 0xeb, 0xb6, 0x96, 0x0b, 0x3a,
+                0xab, 0xe6,
+            ];
+
+            pub(super) const PLAINTEXT_RTP_PACKET: &[u8] = &[
+                0x80, 0x0f, 0x12, 0x34, 0xde, 0xca, 0xfb, 0xad, 0xca, 0xfe, 0xba, 0xbe, 0xab, 0xab,
+                0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab,
+            ];
+
+            // The 32 bit tag is the first 4 bytes of the 80 bit tag.
+            pub(super) const PROTECTED_RTP_PACKET: &[u8] = &[
+                0x80, 0x0f, 0x12, 0x34, 0xde, 0xca, 0xfb, 0xad, 0xca, 0xfe, 0xba, 0xbe, 0x4e, 0x55,
+                0xdc, 0x4c, 0xe7, 0x99, 0x78, 0xd8, 0x8c, 0xa4, 0xd2, 0x15, 0x94, 0x9d, 0x24, 0x02,
+                // Tag
+                0xb7, 0x8d, 0x6a, 0xcc, 0x99, 0xea, 0x17, 0x9b, 0x8d, 0xbb,
+            ];
+        }
+
+        fn make_context(profile: SrtpProfile) -> SrtpContext {
+            // Layout is [key_left, key_right, salt_left, salt_right]
+            let (key, salt) = libsrtp::KEY.split_at(KEY_LEN);
+            let mat = [key, key, salt, salt].concat();
+            SrtpContext::new(profile, &KeyingMaterial::new(mat), true)
+        }
+
+        fn protect_unprotect(profile: SrtpProfile, tag_len: usize) {
+            let mut ctx = make_context(profile);
+
+            let plain = libsrtp::PLAINTEXT_RTP_PACKET;
+            let header = RtpHeader::parse(&plain[..12], &ExtensionMap::empty()).unwrap();
+            let expected = &libsrtp::PROTECTED_RTP_PACKET[..plain.len() + tag_len];
+
+            let out = ctx.protect_rtp(plain, &header, 0x1234);
+            assert_eq!(out, expected);
+
+            let decrypted = ctx.unprotect_rtp(expected, &header, 0x1234).unwrap();
+            assert_eq!(decrypted, plain[12..]);
+
+            // A broken tag fails.
+            let mut broken = expected.to_vec();
+            *broken.last_mut().unwrap() ^= 0xff;
+            assert!(ctx.unprotect_rtp(&broken, &header, 0x1234).is_none());
+        }
+
+        #[test]
+        fn protect_unprotect_rtp_80() {
+            protect_unprotect(SrtpProfile::Aes128CmSha1_80, HMAC_TAG_LEN);
+        }
+
+        #[test]
+        fn protect_unprotect_rtp_32() {
+            protect_unprotect(SrtpProfile::Aes128CmSha1_32, HMAC_TAG_LEN_32);
+        }
+
+        #[test]
+        fn rtcp_tag_is_80_for_32_profile() {
+            let mut ctx = make_context(SrtpProfile::Aes128CmSha1_32);
+
+            let header_and_payload = SRTCP.len() - SRTCP_INDEX_LEN - HMAC_TAG_LEN;
+            let protected = ctx.protect_rtcp(&SRTCP[..header_and_payload]);
+            assert_eq!(
+                protected.len(),
+                header_and_payload + SRTCP_INDEX_LEN + HMAC_TAG_LEN
+            );
+
+            assert!(ctx.unprotect_rtcp(&protected).is_some());
+        }
     }
 
     mod test_aead_aes_128_gcm {
@@ -959,6 +1076,15 @@ mod test {
             assert_eq!(out, rfc7714::PLAINTEXT_RTCP_PACKET);
         }
 
+        #[test]
+        fn srtcp_packets_left() {
+            let mut context = make_rtcp_context();
+            assert_eq!(context.srtcp_packets_left(), SRTCP_MAX_PACKETS - 0x5d4);
+
+            context.protect_rtcp(rfc7714::PLAINTEXT_RTCP_PACKET);
+            assert_eq!(context.srtcp_packets_left(), SRTCP_MAX_PACKETS - 0x5d5);
+        }
+
         fn make_rtp_context() -> SrtpContext {
             SrtpContext::new_aead_aes_128_gcm(
                 rfc7714::KEY,
@@ -18,6 +18,9 @@ use sctp_proto::ReliabilityType;
 mod dcep;
 use dcep::DcepOpen;
 
+mod reliability;
+use reliability::ReliabilityTracker;
+
 use dcep::DcepAck;
 
 /// Errors from the SCTP subsystem.
@@ -52,6 +55,7 @@ pub(crate) struct RtcSctp {
     pushed_back_transmit: Option<VecDeque<Vec<u8>>>,
     last_now: Instant,
     client: bool,
+    reliability: ReliabilityTracker,
 }
 
 /// This is okay because there is no way for a user of Rtc to interact with the Sctp subsystem
@@ -86,6 +90,10 @@ struct StreamEntry {
     id: u16,
     /// If we are to close this entry.
     do_close: bool,
+    /// Total bytes of user data written to the stream.
+    written: u64,
+    /// Total bytes of DCEP messages written to the stream.
+    written_dcep: u64,
 }
 
 pub(crate) enum SctpEvent {
@@ -146,6 +154,33 @@ pub struct ChannelConfig {
     pub protocol: String,
 }
 
+/// Delivery progress of the data written to a channel.
+///
+/// Obtained via [`Channel::delivery_progress()`][crate::channel::Channel::delivery_progress()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryProgress {
+    /// Total number of bytes written to the channel.
+    pub written: u64,
+    /// Number of the written bytes the remote peer has acknowledged.
+    ///
+    /// The difference to `written` is data that is queued or in flight.
+    pub acked: u64,
+}
+
+/// Delivered and abandoned messages of a channel.
+///
+/// Obtained via [`Channel::reliability_stats()`][crate::channel::Channel::reliability_stats()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReliabilityStats {
+    /// Number of messages the remote peer has acknowledged.
+    pub delivered: u64,
+    /// Number of messages abandoned before the remote peer acknowledged them.
+    ///
+    /// Only partially reliable channels abandon messages, when they exceed the max
+    /// retransmits or max packet lifetime.
+    pub abandoned: u64,
+}
+
 /// Reliability setting of a data channel.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Reliability {
@@ -218,6 +253,7 @@ impl RtcSctp {
             pushed_back_transmit: None,
             last_now: Instant::now(), // placeholder until init()
             client: false,
+            reliability: ReliabilityTracker::default(),
         }
     }
 
@@ -332,7 +368,7 @@ impl RtcSctp {
 
         let rec = self
             .entries
-            .iter()
+            .iter_mut()
             .find(|e| e.id == id)
             .expect("stream entry for write");
 
@@ -354,12 +390,48 @@ impl RtcSctp {
             PayloadProtocolIdentifier::String
         };
 
-        Ok(stream.write_with_ppi(buf, ppi)?)
+        let n = stream.write_with_ppi(buf, ppi)?;
+        rec.written += n as u64;
+
+        Ok(n)
+    }
+
+    /// Delivery progress for an open stream.
+    pub fn delivery_progress(&mut self, id: u16) -> Option<DeliveryProgress> {
+        if !self.is_open(id) {
+            return None;
+        }
+
+        let rec = self.entries.iter().find(|e| e.id == id)?;
+        let stream = self.assoc.as_mut()?.stream(id).ok()?;
+        let buffered = stream.buffered_amount().ok()? as u64;
+
+        // The buffered amount covers everything not yet acked, including DCEP messages.
+        // Those are written before any user data, and are thus acked first.
+        let acked = (rec.written + rec.written_dcep)
+            .saturating_sub(buffered)
+            .saturating_sub(rec.written_dcep);
+
+        Some(DeliveryProgress {
+            written: rec.written,
+            acked,
+        })
+    }
+
+    /// Delivered and abandoned messages for an open stream.
+    pub fn reliability_stats(&self, id: u16) -> Option<ReliabilityStats> {
+        if !self.is_open(id) {
+            return None;
+        }
+
+        Some(self.reliability.stats(id))
     }
 
     pub fn handle_input(&mut self, now: Instant, data: &[u8]) {
         trace!("Handle input: {}", data.len());
 
+        self.reliability.handle_incoming(data);
+
         // TODO, remove Bytes in sctp and just use &[u8].
         let data = data.to_vec().into();
         let r = self.endpoint.handle(now, self.fake_addr, None, None, data);
@@ -396,6 +468,11 @@ impl RtcSctp {
         self.last_now = now;
 
         // Remove closed entries.
+        for e in &self.entries {
+            if e.state == StreamEntryState::Closed {
+                self.reliability.remove_stream(e.id);
+            }
+        }
         self.entries.retain(|e| e.state != StreamEntryState::Closed);
 
         let Some(assoc) = &mut self.assoc else {
@@ -413,7 +490,7 @@ impl RtcSctp {
     }
 
     pub fn poll(&mut self) -> Option<SctpEvent> {
-        let r = self.do_poll();
+        let r = self.do_poll(true);
 
         if let Some(r) = &r {
             trace!("Poll {:?}", r);
@@ -422,22 +499,41 @@ impl RtcSctp {
         r
     }
 
-    pub fn do_poll(&mut self) -> Option<SctpEvent> {
+    /// Like [`Sctp::poll()`], but without any [`SctpEvent::Transmit`].
+    ///
+    /// Used when the transmits can't be handled right now, since they are blocked.
+    pub fn poll_event(&mut self) -> Option<SctpEvent> {
+        let r = self.do_poll(false);
+
+        if let Some(r) = &r {
+            trace!("Poll event {:?}", r);
+        }
+
+        r
+    }
+
+    fn do_poll(&mut self, transmit: bool) -> Option<SctpEvent> {
         if self.state == RtcSctpState::Uninited {
             // Need to call `init()` before any polling starts.
             return None;
         }
 
-        if let Some(t) = self.pushed_back_transmit.take() {
-            return Some(SctpEvent::Transmit { packets: t });
-        }
+        if transmit {
+            if let Some(t) = self.pushed_back_transmit.take() {
+                return Some(SctpEvent::Transmit { packets: t });
+            }
 
-        while let Some(t) = self.poll_transmit() {
-            let Some(buf) = transmit_to_vec(t) else {
-                continue;
-            };
+            while let Some(t) = self.poll_transmit() {
+                let Some(buf) = transmit_to_vec(t) else {
+                    continue;
+                };
+
+                for p in &buf {
+                    self.reliability.handle_outgoing(p);
+                }
 
-            return Some(SctpEvent::Transmit { packets: buf });
+                return Some(SctpEvent::Transmit { packets: buf });
+            }
         }
 
         // Don't progress to move data between association and endpoint until we have an
@@ -451,7 +547,7 @@ impl RtcSctp {
         while let Some(e) = assoc.poll() {
             if let Event::Connected = e {
                 set_state(&mut self.state, RtcSctpState::Established);
-                return self.poll();
+                return self.do_poll(transmit);
             }
 
             // TODO: Do we need to handle AssociationLost?
@@ -510,12 +606,13 @@ impl RtcSctp {
                                 .write_with_ppi(&buf, PayloadProtocolIdentifier::Dcep)
                                 .expect("writing dcep open");
                             assert!(n == l);
+                            entry.written_dcep += l as u64;
 
                             entry.set_state(StreamEntryState::AwaitDcepAck);
 
                             // Start over with polling, since we might have caused some network traffic by
                             // writing the DcepOpen.
-                            return self.do_poll();
+                            return self.do_poll(transmit);
                         }
 
                         // Continuing means we are opening the stream out-of-band.
@@ -616,6 +713,7 @@ impl RtcSctp {
                                 .write_with_ppi(&obuf, PayloadProtocolIdentifier::Dcep)
                                 .expect("writing dcep open");
                             assert!(obuf.len() == l);
+                            entry.written_dcep += l as u64;
 
                             entry.set_state(StreamEntryState::Open);
 
@@ -713,6 +811,8 @@ fn stream_entry<'a>(
             state: initial_state,
             id,
             do_close: false,
+            written: 0,
+            written_dcep: 0,
         };
         entries.push(e);
         entries.last_mut().unwrap()
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::ReliabilityStats;
+
+const CHUNK_DATA: u8 = 0;
+const CHUNK_SACK: u8 = 3;
+const CHUNK_FORWARD_TSN: u8 = 192;
+
+/// PPID of the data channel establishment protocol (DCEP).
+const PPID_DCEP: u32 = 50;
+
+/// Size of the SCTP common header.
+const COMMON_HEADER_LEN: usize = 12;
+
+/// Counts delivered and abandoned messages per stream.
+///
+/// The SCTP stack doesn't report abandoned messages, so this follows the SCTP packets
+/// instead. Outgoing DATA chunks are remembered until the remote peer acknowledges them
+/// (SACK), or we skip them with a FORWARD TSN (RFC 3758), which is how the SCTP stack
+/// abandons data of partially reliable streams.
+#[derive(Debug, Default)]
+pub(crate) struct ReliabilityTracker {
+    /// Outgoing DATA chunks not yet acked or skipped, in TSN order.
+    chunks: VecDeque<ChunkEntry>,
+
+    /// Highest TSN sent. Chunks up to this are retransmits.
+    last_tsn: Option<u32>,
+
+    /// Streams where a chunk of the current message has been abandoned.
+    abandoned_fragment: HashSet<u16>,
+
+    stats: HashMap<u16, ReliabilityStats>,
+}
+
+#[derive(Debug)]
+struct ChunkEntry {
+    tsn: u32,
+    stream: u16,
+    /// Whether this is the last fragment of a message.
+    end: bool,
+    /// Whether this is a DCEP message, which is not counted.
+    dcep: bool,
+    /// Whether the chunk has been acked in a gap ack block.
+    acked: bool,
+}
+
+impl ReliabilityTracker {
+    pub fn stats(&self, stream: u16) -> ReliabilityStats {
+        self.stats.get(&stream).copied().unwrap_or_default()
+    }
+
+    pub fn remove_stream(&mut self, stream: u16) {
+        self.stats.remove(&stream);
+        self.abandoned_fragment.remove(&stream);
+    }
+
+    /// Observe an SCTP packet we send.
+    pub fn handle_outgoing(&mut self, packet: &[u8]) {
+        for (kind, flags, value) in chunks(packet) {
+            match kind {
+                CHUNK_DATA => self.handle_data(flags, value),
+                // New cumulative TSN, followed by stream/SSN pairs.
+                CHUNK_FORWARD_TSN if value.len() >= 4 => {
+                    self.resolve(read_u32(value), true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Observe an SCTP packet from the remote peer.
+    pub fn handle_incoming(&mut self, packet: &[u8]) {
+        for (kind, _, value) in chunks(packet) {
+            if kind == CHUNK_SACK {
+                self.handle_sack(value);
+            }
+        }
+    }
+
+    fn handle_data(&mut self, flags: u8, value: &[u8]) {
+        // TSN, stream id, stream sequence number, PPID.
+        if value.len() < 12 {
+            return;
+        }
+
+        let tsn = read_u32(value);
+
+        if let Some(last) = self.last_tsn {
+            if !tsn_gt(tsn, last) {
+                // Retransmit
+                return;
+            }
+        }
+        self.last_tsn = Some(tsn);
+
+        self.chunks.push_back(ChunkEntry {
+            tsn,
+            stream: u16::from_be_bytes([value[4], value[5]]),
+            end: flags & 0x01 > 0,
+            dcep: read_u32(&value[8..]) == PPID_DCEP,
+            acked: false,
+        });
+    }
+
+    fn handle_sack(&mut self, value: &[u8]) {
+        // Cumulative TSN ack, a_rwnd, number of gap ack blocks, number of duplicate TSNs.
+        if value.len() < 12 {
+            return;
+        }
+
+        let cumulative = read_u32(value);
+        let gap_count = u16::from_be_bytes([value[8], value[9]]) as usize;
+
+        self.resolve(cumulative, false);
+
+        for block in value[12..].chunks_exact(4).take(gap_count) {
+            // Offsets relative to the cumulative TSN ack.
+            let start = cumulative.wrapping_add(u16::from_be_bytes([block[0], block[1]]) as u32);
+            let end = cumulative.wrapping_add(u16::from_be_bytes([block[2], block[3]]) as u32);
+
+            for c in &mut self.chunks {
+                if !tsn_gt(start, c.tsn) && !tsn_gt(c.tsn, end) {
+                    c.acked = true;
+                }
+            }
+        }
+    }
+
+    /// Resolve all chunks up to and including `tsn`.
+    ///
+    /// With `skipped`, the chunks not acked are abandoned.
+    fn resolve(&mut self, tsn: u32, skipped: bool) {
+        while let Some(c) = self.chunks.front() {
+            if tsn_gt(c.tsn, tsn) {
+                break;
+            }
+            let c = self.chunks.pop_front().unwrap();
+
+            if c.dcep {
+                continue;
+            }
+
+            if skipped && !c.acked {
+                self.abandoned_fragment.insert(c.stream);
+            }
+
+            if !c.end {
+                continue;
+            }
+
+            let stats = self.stats.entry(c.stream).or_default();
+
+            if self.abandoned_fragment.remove(&c.stream) {
+                stats.abandoned += 1;
+            } else {
+                stats.delivered += 1;
+            }
+        }
+    }
+}
+
+/// Iterate the chunks of an SCTP packet as (type, flags, value).
+fn chunks(packet: &[u8]) -> impl Iterator<Item = (u8, u8, &[u8])> {
+    let mut rest = packet.get(COMMON_HEADER_LEN..).unwrap_or_default();
+
+    std::iter::from_fn(move || {
+        if rest.len() < 4 {
+            return None;
+        }
+
+        let kind = rest[0];
+        let flags = rest[1];
+        let len = u16::from_be_bytes([rest[2], rest[3]]) as usize;
+
+        if len < 4 || len > rest.len() {
+            return None;
+        }
+
+        let value = &rest[4..len];
+
+        // Chunks are padded to 4 bytes.
+        let padded = (len + 3) & !3;
+        rest = rest.get(padded..).unwrap_or_default();
+
+        Some((kind, flags, value))
+    })
+}
+
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+/// Serial number comparison of TSN (RFC 1982).
+fn tsn_gt(a: u32, b: u32) -> bool {
+    a != b && a.wrapping_sub(b) < (1 << 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn packet(chunks: &[(u8, u8, Vec<u8>)]) -> Vec<u8> {
+        let mut p = vec![0; COMMON_HEADER_LEN];
+        for (kind, flags, value) in chunks {
+            p.push(*kind);
+            p.push(*flags);
+            p.extend_from_slice(&(value.len() as u16 + 4).to_be_bytes());
+            p.extend_from_slice(value);
+            p.resize((p.len() + 3) & !3, 0);
+        }
+        p
+    }
+
+    fn data(tsn: u32, stream: u16, end: bool) -> (u8, u8, Vec<u8>) {
+        let mut v = tsn.to_be_bytes().to_vec();
+        v.extend_from_slice(&stream.to_be_bytes());
+        v.extend_from_slice(&[0, 0]);
+        v.extend_from_slice(&53_u32.to_be_bytes());
+        v.push(42);
+        (CHUNK_DATA, if end { 0x03 } else { 0x02 }, v)
+    }
+
+    fn sack(cumulative: u32, gaps: &[(u16, u16)]) -> (u8, u8, Vec<u8>) {
+        let mut v = cumulative.to_be_bytes().to_vec();
+        v.extend_from_slice(&1024_u32.to_be_bytes());
+        v.extend_from_slice(&(gaps.len() as u16).to_be_bytes());
+        v.extend_from_slice(&[0, 0]);
+        for (start, end) in gaps {
+            v.extend_from_slice(&start.to_be_bytes());
+            v.extend_from_slice(&end.to_be_bytes());
+        }
+        (CHUNK_SACK, 0, v)
+    }
+
+    fn forward_tsn(tsn: u32) -> (u8, u8, Vec<u8>) {
+        (CHUNK_FORWARD_TSN, 0, tsn.to_be_bytes().to_vec())
+    }
+
+    #[test]
+    fn delivered_and_abandoned() {
+        let mut t = ReliabilityTracker::default();
+
+        // Crosses the TSN wrap around.
+        let first = u32::MAX - 1;
+        for i in 0..4 {
+            t.handle_outgoing(&packet(&[data(first.wrapping_add(i), 1, true)]));
+        }
+
+        // The first is acked, the third in a gap block.
+        t.handle_incoming(&packet(&[sack(first, &[(2, 2)])]));
+        assert_eq!(t.stats(1).delivered, 1);
+
+        // A retransmit of the second does not count.
+        t.handle_outgoing(&packet(&[data(first.wrapping_add(1), 1, true)]));
+
+        // The second and fourth are abandoned.
+        t.handle_outgoing(&packet(&[forward_tsn(first.wrapping_add(3))]));
+
+        let stats = t.stats(1);
+        assert_eq!(stats.delivered, 2);
+        assert_eq!(stats.abandoned, 2);
+    }
+
+    #[test]
+    fn abandoned_fragment_abandons_message() {
+        let mut t = ReliabilityTracker::default();
+
+        t.handle_outgoing(&packet(&[data(10, 1, false), data(11, 1, true)]));
+
+        // The last fragment arrived, the first did not.
+        t.handle_incoming(&packet(&[sack(9, &[(2, 2)])]));
+        t.handle_outgoing(&packet(&[forward_tsn(11)]));
+
+        let stats = t.stats(1);
+        assert_eq!(stats.delivered, 0);
+        assert_eq!(stats.abandoned, 1);
+    }
+}
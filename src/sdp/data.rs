@@ -3,6 +3,7 @@
 use combine::EasyParser;
 use std::collections::HashSet;
 use std::fmt::{self};
+use std::net::IpAddr;
 use std::num::ParseFloatError;
 use std::ops::Deref;
 use std::str::FromStr;
@@ -11,8 +12,9 @@ use crate::crypto::Fingerprint;
 use crate::format::Codec;
 use crate::format::CodecSpec;
 use crate::format::FormatParams;
+use crate::format::PacketTime;
 use crate::format::PayloadParams;
-use crate::rtp_::{Direction, Extension, Frequency, Mid, Pt, Rid, SessionId, Ssrc};
+use crate::rtp_::{Bitrate, Direction, Extension, Frequency, Mid, Pt, Rid, SessionId, Ssrc};
 use crate::{Candidate, IceCreds, VERSION};
 
 use super::parser::sdp_parser;
@@ -29,7 +31,22 @@ impl Sdp {
         sdp_parser()
             .easy_parse(input)
             .map(|(sdp, _)| sdp)
-            .map_err(|e| SdpError::ParseError(e.to_string()))
+            .map_err(|e| {
+                let offset = e.position.translate_position(input);
+                let before = &input[..offset];
+                let line = before.matches('\n').count() + 1;
+                let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+
+                // The first line is "Parse error at <position>", which we replace with line/column.
+                let e = e.to_string();
+                let message = e.split_once('\n').map(|(_, m)| m).unwrap_or(&e);
+
+                SdpError::Malformed {
+                    line,
+                    column,
+                    message: message.trim_end().replace('\n', ", "),
+                }
+            })
     }
 
     pub(crate) fn assert_consistency(&self) -> Result<(), SdpError> {
@@ -51,6 +68,11 @@ impl Sdp {
             .or_else(|| self.media_lines.iter().find_map(|m| m.ice_creds()))
     }
 
+    /// Any end-of-candidates on session level or in any m-line.
+    pub(crate) fn end_of_candidates(&self) -> bool {
+        self.session.end_of_candidates() || self.media_lines.iter().any(|m| m.end_of_candidates())
+    }
+
     pub(crate) fn ice_candidates(&self) -> impl Iterator<Item = &Candidate> {
         let mut candidates: HashSet<&Candidate> = HashSet::new();
 
@@ -91,7 +113,9 @@ impl Sdp {
                     self.media_lines.len()
                 ));
             }
-            for (media, mid) in self.media_lines.iter().zip(mids.iter()) {
+            // Disabled m-lines (port 0) are not in the BUNDLE group.
+            let bundled = self.media_lines.iter().filter(|m| !m.disabled);
+            for (media, mid) in bundled.zip(mids.iter()) {
                 media.check_consistent()?;
                 let m = media.mid();
                 if m != *mid {
@@ -243,6 +267,41 @@ impl MediaLine {
             .expect("missing a=mid")
     }
 
+    /// The application specific maximum bandwidth from a `b=AS:<kbps>` line, if any.
+    pub fn bandwidth_as(&self) -> Option<Bitrate> {
+        let bw = self.bw.as_ref()?;
+        if bw.typ != "AS" {
+            return None;
+        }
+        let kbps: u64 = bw.val.trim().parse().ok()?;
+        Some(Bitrate::kbps(kbps))
+    }
+
+    pub fn rtcp_addr(&self) -> Option<RtcpAddr> {
+        self.attrs.iter().find_map(|a| match a {
+            MediaAttribute::Rtcp(v) => Some(v.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn rtcp_rsize(&self) -> bool {
+        self.attrs.contains(&MediaAttribute::RtcpRsize)
+    }
+
+    pub fn ecn_capable_rtp(&self) -> bool {
+        self.attrs.contains(&MediaAttribute::EcnCapableRtp)
+    }
+
+    pub fn bundle_only(&self) -> bool {
+        self.attrs.contains(&MediaAttribute::BundleOnly)
+    }
+
+    pub fn rtcp_mux(&self) -> bool {
+        self.attrs
+            .iter()
+            .any(|a| matches!(a, MediaAttribute::RtcpMux | MediaAttribute::RtcpMuxOnly))
+    }
+
     pub fn direction(&self) -> Direction {
         for a in &self.attrs {
             match a {
@@ -303,10 +362,33 @@ impl MediaLine {
             })
             .collect();
 
+        // ptime/maxptime are for the entire m-line.
+        let p_time = self.attrs.iter().find_map(|a| match a {
+            MediaAttribute::PTime(v) => Some(*v),
+            _ => None,
+        });
+        let max_p_time = self.attrs.iter().find_map(|a| match a {
+            MediaAttribute::MaxPTime(v) => Some(*v),
+            _ => None,
+        });
+        let packet_time = (p_time.is_some() || max_p_time.is_some())
+            .then(|| Box::new(PacketTime { p_time, max_p_time }));
+
         let mut params: Vec<_> = rtp_maps
             .iter()
             .filter(|(_, c)| c.codec.is_audio() | c.codec.is_video())
-            .map(|(pt, c)| PayloadParams::new(*pt, None, (*c).into()))
+            .map(|(pt, c)| {
+                let mut p = PayloadParams::new(*pt, None, (*c).into());
+                p.spec.format.packet_time = packet_time.clone();
+                // Only the feedback mechanisms in a=rtcp-fb lines are enabled.
+                p.fb_transport_cc = false;
+                p.fb_nack = false;
+                p.fb_pli = false;
+                p.fb_fir = false;
+                p.fb_remb = false;
+                p.fb_lntf = false;
+                p
+            })
             .collect();
 
         for p in &mut params {
@@ -353,6 +435,9 @@ impl MediaLine {
                         "nack pli" => {
                             p.fb_pli = true;
                         }
+                        "goog-lntf" => {
+                            p.fb_lntf = true;
+                        }
                         _ => {
                             //
                         }
@@ -428,9 +513,60 @@ impl MediaLine {
                 ));
             }
         }
+
+        // Every RTX must point out the PT it is repairing using a=fmtp:<pt> apt=<pt>.
+        for a in &self.attrs {
+            let MediaAttribute::RtpMap { pt, value } = a else {
+                continue;
+            };
+            if value.codec != Codec::Rtx {
+                continue;
+            }
+
+            let Some(apt) = self.apt(*pt) else {
+                return Some(format!(
+                    "Missing a=fmtp:{} apt= for RTX in mid: {}",
+                    pt,
+                    self.mid()
+                ));
+            };
+
+            if !self.pts.contains(&apt) || self.apt(apt).is_some() {
+                return Some(format!(
+                    "RTX a=fmtp:{} apt={} does not point to a PT in mid: {}",
+                    pt,
+                    apt,
+                    self.mid()
+                ));
+            }
+        }
+
         None
     }
 
+    /// The associated payload type (apt) declared for a PT, if any.
+    fn apt(&self, pt: Pt) -> Option<Pt> {
+        self.attrs.iter().find_map(|a| {
+            let MediaAttribute::Fmtp {
+                pt: fmtp_pt,
+                values,
+            } = a
+            else {
+                return None;
+            };
+            if *fmtp_pt != pt {
+                return None;
+            }
+            values.iter().find_map(|v| {
+                if let FormatParam::Apt(apt) = v {
+                    Some(*apt)
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
     pub fn setup(&self) -> Option<Setup> {
         let setup = self.attrs.iter().find_map(|m| {
             if let MediaAttribute::Setup(v) = m {
@@ -599,6 +735,8 @@ impl MediaLine {
         for a in &self.attrs {
             match a {
                 MediaAttribute::SsrcGroup { semantics, ssrcs } => {
+                    // a=ssrc-group:SIM is legacy simulcast by SDP munging, which we don't
+                    // support. Simulcast goes via a=simulcast and a=rid.
                     if semantics.to_lowercase() != "fid" {
                         continue;
                     }
@@ -795,7 +933,7 @@ impl Setup {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MediaAttribute {
     // The "a=rtcp" line MUST NOT be added if the most recent answer included an "a=rtcp-mux" line.
-    Rtcp(String),
+    Rtcp(RtcpAddr),
     IceUfrag(String),
     IcePwd(String),
     IceOptions(String),
@@ -819,8 +957,13 @@ pub enum MediaAttribute {
     Msid(Msid),
     RtcpMux,     //
     RtcpMuxOnly, // only in offer, answer with a=rtcp-mux
+    // a=bundle-only, with port 0 in an offer for an m-line that can only be bundled (RFC 8843).
+    BundleOnly,
     // reduced size rtcp. remove this if not supported.
     RtcpRsize,
+    // a=ecn-capable-rtp:leap ect=0
+    // https://www.rfc-editor.org/rfc/rfc6679#section-6.1
+    EcnCapableRtp,
     Candidate(Candidate),
     EndOfCandidates,
     RtpMap {
@@ -837,6 +980,10 @@ pub enum MediaAttribute {
         pt: Pt,                   // 111
         values: Vec<FormatParam>, // minptime=10;useinbandfec=1
     },
+    // a=ptime:20
+    PTime(u16),
+    // a=maxptime:60
+    MaxPTime(u16),
     // a=rid:<rid-id> <direction> [pt=<fmt-list>;]<restriction>=<value>
     // a=rid:hi send pt=111,112;max-br=64000;max-height=360
     // https://tools.ietf.org/html/draft-ietf-mmusic-rid-15
@@ -906,6 +1053,15 @@ pub enum FormatParam {
     /// VP9 profile id
     ProfileId(u32),
 
+    /// Maximum frame size in macroblocks the decoder can handle.
+    MaxFs(u32),
+
+    /// Maximum macroblock processing rate per second the decoder can handle.
+    MaxMbps(u32),
+
+    /// Maximum frame rate the decoder can handle.
+    MaxFr(u32),
+
     /// RTX (resend) codecs, which PT it concerns.
     Apt(Pt),
 
@@ -952,6 +1108,18 @@ impl FormatParam {
                     Unknown
                 }
             }
+            "max-fs" | "max-mbps" | "max-fr" => {
+                if let Ok(n) = v.parse() {
+                    match k {
+                        "max-fs" => MaxFs(n),
+                        "max-mbps" => MaxMbps(n),
+                        _ => MaxFr(n),
+                    }
+                } else {
+                    trace!("Failed to parse: {}", k);
+                    Unknown
+                }
+            }
             "apt" => {
                 if let Ok(v) = v.parse::<u8>() {
                     Apt(v.into())
@@ -978,6 +1146,9 @@ impl fmt::Display for FormatParam {
             PacketizationMode(v) => write!(f, "packetization-mode={}", *v),
             ProfileLevelId(v) => write!(f, "profile-level-id={:06x}", *v),
             ProfileId(v) => write!(f, "profile-id={}", *v),
+            MaxFs(v) => write!(f, "max-fs={v}"),
+            MaxMbps(v) => write!(f, "max-mbps={v}"),
+            MaxFr(v) => write!(f, "max-fr={v}"),
             Apt(v) => write!(f, "apt={v}"),
             Unknown => Ok(()),
         }
@@ -988,7 +1159,7 @@ impl PayloadParams {
     pub(crate) fn as_media_attrs(&self, attrs: &mut Vec<MediaAttribute>) {
         attrs.push(MediaAttribute::RtpMap {
             pt: self.pt,
-            value: self.spec.into(),
+            value: self.spec.clone().into(),
         });
 
         if self.fb_transport_cc {
@@ -1021,6 +1192,12 @@ impl PayloadParams {
                 value: "nack pli".into(),
             });
         }
+        if self.fb_lntf {
+            attrs.push(MediaAttribute::RtcpFb {
+                pt: self.pt,
+                value: "goog-lntf".into(),
+            });
+        }
 
         let fmtps = self.spec.format.to_format_param();
         if !fmtps.is_empty() {
@@ -1111,6 +1288,35 @@ impl Deref for SimulcastGroups {
     }
 }
 
+/// Port, and optionally address, for RTCP when not multiplexed with RTP (RFC 3605).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtcpAddr {
+    pub port: u16,
+    pub addr: Option<RtcpHost>,
+}
+
+/// The address of a=rtcp, which is either an IP or a fully qualified domain name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RtcpHost {
+    Ip(IpAddr),
+    Fqdn { ipv6: bool, name: String },
+}
+
+impl fmt::Display for RtcpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.port)?;
+        match &self.addr {
+            Some(RtcpHost::Ip(IpAddr::V4(v))) => write!(f, " IN IP4 {v}"),
+            Some(RtcpHost::Ip(IpAddr::V6(v))) => write!(f, " IN IP6 {v}"),
+            Some(RtcpHost::Fqdn { ipv6, name }) => {
+                let typ = if *ipv6 { "IP6" } else { "IP4" };
+                write!(f, " IN {typ} {name}")
+            }
+            None => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Msid {
     pub stream_id: String,
@@ -1198,7 +1404,12 @@ impl fmt::Display for SessionAttribute {
 
 impl fmt::Display for MediaLine {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "m={} 9 {} ", self.typ, self.proto,)?;
+        let port = if self.disabled || self.bundle_only() {
+            0
+        } else {
+            9
+        };
+        write!(f, "m={} {} {} ", self.typ, port, self.proto,)?;
         let len = self.pts.len();
         if self.typ.is_channel() {
             write!(f, "webrtc-datachannel\r\n")?;
@@ -1283,7 +1494,9 @@ impl fmt::Display for MediaAttribute {
             Msid(v) => write!(f, "a=msid:{} {}\r\n", v.stream_id, v.track_id)?,
             RtcpMux => write!(f, "a=rtcp-mux\r\n")?,
             RtcpMuxOnly => write!(f, "a=rtcp-mux-only\r\n")?,
+            BundleOnly => write!(f, "a=bundle-only\r\n")?,
             RtcpRsize => write!(f, "a=rtcp-rsize\r\n")?,
+            EcnCapableRtp => write!(f, "a=ecn-capable-rtp:leap ect=0\r\n")?,
             Candidate(c) => write!(f, "a={}\r\n", c.to_sdp_string())?,
             EndOfCandidates => write!(f, "a=end-of-candidates\r\n")?,
             RtpMap { pt, value: c } => {
@@ -1304,6 +1517,8 @@ impl fmt::Display for MediaAttribute {
                     }
                 }
             }
+            PTime(v) => write!(f, "a=ptime:{v}\r\n")?,
+            MaxPTime(v) => write!(f, "a=maxptime:{v}\r\n")?,
             // a=rid:hi send pt=111,112;max-br=64000;max-height=360
             Rid {
                 id,
@@ -1417,6 +1632,69 @@ mod test {
         assert_eq!(f.to_string(), "minptime=10;useinbandfec=1");
     }
 
+    #[test]
+    fn fmtp_param_max_fs() {
+        let f = FormatParams::parse_line("max-fs=3600;max-fr=30;max-mbps=108000");
+        let l = f.decoder_limits.as_deref().unwrap();
+        assert_eq!(l.max_fs, Some(3600));
+        assert_eq!(l.max_mbps, Some(108000));
+        assert_eq!(l.max_fr, Some(30));
+        assert_eq!(f.to_string(), "max-fs=3600;max-mbps=108000;max-fr=30");
+    }
+
+    #[test]
+    fn bandwidth_as() {
+        let mut line = MediaLine::default();
+        assert_eq!(line.bandwidth_as(), None);
+
+        line.bw = Some(Bandwidth {
+            typ: "AS".into(),
+            val: "300".into(),
+        });
+        assert_eq!(line.bandwidth_as(), Some(Bitrate::kbps(300)));
+
+        line.bw = Some(Bandwidth {
+            typ: "TIAS".into(),
+            val: "300000".into(),
+        });
+        assert_eq!(line.bandwidth_as(), None);
+    }
+
+    #[test]
+    fn ssrc_info_fid_ignores_sim() {
+        let line = MediaLine {
+            attrs: vec![
+                MediaAttribute::SsrcGroup {
+                    semantics: "FID".into(),
+                    ssrcs: vec![1.into(), 11.into()],
+                },
+                MediaAttribute::SsrcGroup {
+                    semantics: "FID".into(),
+                    ssrcs: vec![2.into(), 12.into()],
+                },
+                MediaAttribute::SsrcGroup {
+                    semantics: "SIM".into(),
+                    ssrcs: vec![2.into(), 1.into()],
+                },
+                MediaAttribute::Ssrc {
+                    ssrc: 1.into(),
+                    attr: "cname".into(),
+                    value: "foo".into(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let info = line.ssrc_info();
+        let by_ssrc = |ssrc: u32| info.iter().find(|i| *i.ssrc == ssrc).unwrap();
+
+        // The SIM group adds nothing.
+        assert_eq!(info.len(), 3);
+        assert_eq!(by_ssrc(1).cname.as_deref(), Some("foo"));
+        assert_eq!(by_ssrc(11).repairs, Some(1.into()));
+        assert_eq!(by_ssrc(12).repairs, Some(2.into()));
+    }
+
     #[test]
     fn parse_error() {
         let input = "v=0\r\n\
@@ -1437,14 +1715,85 @@ mod test {
         let sdp = Sdp::parse(input);
 
         match sdp {
-            Err(SdpError::ParseError(out)) => {
-                assert!(out.starts_with(&"Parse error at ".to_string()));
+            Err(SdpError::Malformed { message: out, .. }) => {
                 assert!(out.contains(&"Expected exactly one of a=sendrecv, a=sendonly, a=recvonly, a=inactive for mid: 1".to_string()));
             }
             _ => panic!(),
         }
     }
 
+    #[test]
+    fn parse_error_location() {
+        let input = "v=0\r\n\
+        o=- 7710052215259647220 2 IN IP4 0.0.0.0\r\n\
+        s=-\r\n\
+        t=0 0\r\n\
+        a=group:BUNDLE 0\r\n\
+        m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=mid:0\r\n\
+        a=sendrecv\r\n\
+        a=rtpmap:111 opus/nope\r\n\
+        ";
+
+        match Sdp::parse(input) {
+            Err(SdpError::Malformed {
+                line,
+                column,
+                message,
+            }) => {
+                // Errors in an m-line are reported at the start of it.
+                assert_eq!((line, column), (6, 1));
+                assert!(
+                    message.contains("Missing a=rtp_map:111 for mid: 0"),
+                    "{message}"
+                );
+            }
+            r => panic!("Expected malformed SDP: {r:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_error_rtx_apt() {
+        let sdp = |apt: &str| {
+            format!(
+                "v=0\r\n\
+                o=- 7710052215259647220 2 IN IP4 0.0.0.0\r\n\
+                s=-\r\n\
+                t=0 0\r\n\
+                a=group:BUNDLE 0\r\n\
+                m=video 9 UDP/TLS/RTP/SAVPF 96 97\r\n\
+                c=IN IP4 0.0.0.0\r\n\
+                a=mid:0\r\n\
+                a=sendrecv\r\n\
+                a=rtpmap:96 VP8/90000\r\n\
+                a=rtpmap:97 rtx/90000\r\n\
+                {apt}"
+            )
+        };
+
+        assert!(Sdp::parse(&sdp("a=fmtp:97 apt=96\r\n")).is_ok());
+
+        for (apt, err) in [
+            ("", "Missing a=fmtp:97 apt= for RTX in mid: 0"),
+            (
+                "a=fmtp:97 apt=98\r\n",
+                "RTX a=fmtp:97 apt=98 does not point to a PT in mid: 0",
+            ),
+            (
+                "a=fmtp:97 apt=97\r\n",
+                "RTX a=fmtp:97 apt=97 does not point to a PT in mid: 0",
+            ),
+        ] {
+            match Sdp::parse(&sdp(apt)) {
+                Err(SdpError::Malformed { message: out, .. }) => {
+                    assert!(out.contains(err), "{out}")
+                }
+                r => panic!("Expected parse error for {apt:?}: {r:?}"),
+            }
+        }
+    }
+
     #[test]
     fn write_sdp() {
         let sdp = Sdp {
@@ -1482,7 +1831,7 @@ mod test {
                 ],
                 bw: None,
                 attrs: vec![
-                        MediaAttribute::Rtcp("9 IN IP4 0.0.0.0".into()),
+                        MediaAttribute::Rtcp(RtcpAddr { port: 9, addr: Some(RtcpHost::Ip("0.0.0.0".parse().unwrap())) }),
                         MediaAttribute::IceUfrag("S5hk".into()),
                         MediaAttribute::IcePwd("0zV/Yu3y8aDzbHgqWhnVQhqP".into()),
                         MediaAttribute::IceOptions("trickle".into()),
@@ -5,6 +5,8 @@ use serde::ser::SerializeStruct;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
+use crate::rtp_::Mid;
+
 mod data;
 pub(crate) use data::{FormatParam, Sdp, Session, SessionAttribute, Setup};
 pub(crate) use data::{MediaAttribute, MediaLine, MediaType, Msid, Proto};
@@ -16,18 +18,82 @@ pub(crate) use data::RtpMap;
 
 mod parser;
 
-/// Errors from parsing and serializing SDP.
+/// Errors from parsing SDP, and from negotiating an SDP OFFER/ANSWER.
 #[derive(Debug, Error)]
-#[allow(missing_docs)]
+#[non_exhaustive]
 pub enum SdpError {
+    /// A single SDP line, such as an ICE candidate, could not be parsed.
     #[error("SDP parse: {0}")]
     ParseError(String),
 
+    /// The SDP could not be parsed.
+    ///
+    /// The line and column (both 1-based) is where the parser gave up.
+    #[error("SDP malformed at line {line}, column {column}: {message}")]
+    Malformed {
+        /// Line of the error.
+        line: usize,
+        /// Column of the error.
+        column: usize,
+        /// Description of what went wrong.
+        message: String,
+    },
+
+    /// The SDP parsed, but contradicts itself.
     #[error("SDP inconsistent: {0}")]
     Inconsistent(String),
+
+    /// The OFFER has no m-lines.
+    #[error("No m-lines in offer")]
+    NoMedia,
+
+    /// Both we and the remote peer are ICE-Lite.
+    #[error("Both peers being ICE-Lite not supported")]
+    BothIceLite,
+
+    /// The SDP is missing `a=ice-ufrag` or `a=ice-pwd`.
+    #[error("Missing a=ice-ufrag/pwd")]
+    MissingIceCredentials,
+
+    /// The ANSWER restarts ICE, but our OFFER did not.
+    #[error("Ice restart in answer without one in the preceeding offer")]
+    UnexpectedIceRestart,
+
+    /// The SDP is missing `a=fingerprint`.
+    #[error("Missing a=fingerprint")]
+    MissingFingerprint,
+
+    /// The remote DTLS certificate does not match the `a=fingerprint` in the SDP.
+    #[error("Remote fingerprint no match")]
+    FingerprintMismatch,
+
+    /// An m-line is moved to a different position in the SDP than it was negotiated.
+    #[error("Changed order for m-line with mid: {0}")]
+    ChangedOrder(Mid),
+
+    /// An m-line is neither audio/video nor a data channel.
+    #[error("New m-line is neither media nor channel: {0}")]
+    UnsupportedMediaType(Mid),
+
+    /// The ANSWER does not have the same number of new m-lines as our OFFER.
+    #[error("Differing m-line count in offer vs answer: {offer} != {answer}")]
+    AnswerLineCount {
+        /// Number of new m-lines in our OFFER.
+        offer: usize,
+        /// Number of new m-lines in the ANSWER.
+        answer: usize,
+    },
+
+    /// The ANSWER m-line has a different type (audio/video or data channel) than in our OFFER.
+    #[error("Answer m-line for mid ({0}) is not of the offered type")]
+    AnswerTypeMismatch(Mid),
+
+    /// The ANSWER has an m-line that was never in our OFFER.
+    #[error("Mid in answer is not in offer: {0}")]
+    AnswerUnknownMid(Mid),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// SDP offer. Offers can be serialized via serde.
 pub struct SdpOffer(Sdp);
 
@@ -44,7 +110,7 @@ impl SdpOffer {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// SDP answer. Answers can be serialized via serde.
 pub struct SdpAnswer(Sdp);
 
@@ -61,6 +127,30 @@ impl SdpAnswer {
     }
 }
 
+/// A negotiated session description, either an offer or an answer.
+///
+/// Obtained via [`Rtc::local_description()`][crate::Rtc::local_description] and
+/// [`Rtc::remote_description()`][crate::Rtc::remote_description]. Serializes via serde the
+/// same way as the [`SdpOffer`] or [`SdpAnswer`] it holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SdpDescription {
+    /// The description was an offer.
+    Offer(SdpOffer),
+    /// The description was an answer.
+    Answer(SdpAnswer),
+}
+
+impl SdpDescription {
+    /// Turns this description into an SDP string, without any JSON wrapping.
+    pub fn to_sdp_string(&self) -> String {
+        match self {
+            SdpDescription::Offer(v) => v.to_sdp_string(),
+            SdpDescription::Answer(v) => v.to_sdp_string(),
+        }
+    }
+}
+
 impl Deref for SdpOffer {
     type Target = Sdp;
 
@@ -272,4 +362,19 @@ mod test {
 
         assert_eq!(answer, answer2);
     }
+
+    #[test]
+    fn serialize_deserialize_description() {
+        let offer = SdpDescription::Offer(SdpOffer(sdp()));
+        let json = serde_json::to_string(&offer).unwrap();
+        assert!(json.starts_with("{\"type\":\"offer\""));
+        let offer2: SdpDescription = serde_json::from_str(&json).unwrap();
+        assert_eq!(offer, offer2);
+
+        let answer = SdpDescription::Answer(SdpAnswer(sdp()));
+        let json = serde_json::to_string(&answer).unwrap();
+        assert!(json.starts_with("{\"type\":\"answer\""));
+        let answer2: SdpDescription = serde_json::from_str(&json).unwrap();
+        assert_eq!(answer, answer2);
+    }
 }
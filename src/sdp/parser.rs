@@ -274,10 +274,11 @@ where
             string(" rport "),
             port(),
         )),
-        optional((attempt(string(" generation ")), not_sp())),
-        optional((attempt(string(" network-id ")), not_sp())),
-        optional((attempt(string(" ufrag ")), not_sp())),
-        optional((attempt(string(" network-cost ")), not_sp())),
+        // Extension attributes, such as generation, ufrag, network-id and network-cost,
+        // in any order. Unknown ones are ignored.
+        many::<Vec<_>, _, _>(attempt(
+            (token(' '), not_sp(), token(' '), not_sp()).map(|(_, k, _, v)| (k, v)),
+        )),
     )
         .map(
             |(
@@ -297,12 +298,11 @@ where
                 kind,
                 _,     // (" tcptype ", tcptype)
                 raddr, // (" raddr ", addr, " rport ", port)
-                _,     // (" generation ", generation)
-                _,     // (" network-id ", network_id)
-                ufrag, // (" ufrag ", ufrag)
-                _,     // ("network-cost", network_cost)
+                attrs, // (name, value)
             )| {
-                Candidate::parsed(
+                let attr = |name: &str| attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v);
+
+                let mut c = Candidate::parsed(
                     found,
                     comp_id,
                     proto,
@@ -310,8 +310,15 @@ where
                     SocketAddr::from((addr, port)),
                     kind,
                     raddr.map(|(_, addr, _, port)| SocketAddr::from((addr, port))),
-                    ufrag.map(|(_, u)| u),
-                )
+                    attr("ufrag").cloned(),
+                );
+
+                // An invalid network-cost is ignored like an unknown attribute.
+                if let Some(cost) = attr("network-cost").and_then(|v| v.parse().ok()) {
+                    c.set_network_cost(cost);
+                }
+
+                c
             },
         )
 }
@@ -368,9 +375,11 @@ where
         many::<Vec<_>, _, _>(media_attribute_line()),
     )
         .and_then(|((typ, port, proto, pts), _, bw, attrs)| {
+            // RFC 8843: port 0 with a=bundle-only is an m-line that can only be bundled.
+            let bundle_only = attrs.contains(&MediaAttribute::BundleOnly);
             let m = MediaLine {
                 typ,
-                disabled: port == "0",
+                disabled: port == "0" && !bundle_only,
                 proto,
                 pts,
                 bw,
@@ -448,7 +457,32 @@ where
     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
     // a=rtcp:9 IN IP4 0.0.0.0
-    let rtcp = attribute_line("rtcp", any_value()).map(MediaAttribute::Rtcp);
+    // a=rtcp:53020
+    let rtcp = attribute_line(
+        "rtcp",
+        (
+            not_sp().and_then(|s| {
+                s.parse::<u16>()
+                    .map_err(StreamErrorFor::<Input>::message_format)
+            }),
+            optional((
+                string(" IN "),
+                choice((attempt(string("IP4")), string("IP6"))),
+                token(' '),
+                not_sp(),
+            )),
+        ),
+    )
+    .map(|(port, addr)| {
+        let addr = addr.map(|(_, typ, _, name)| match name.parse::<IpAddr>() {
+            Ok(ip) => RtcpHost::Ip(ip),
+            Err(_) => RtcpHost::Fqdn {
+                ipv6: typ == "IP6",
+                name,
+            },
+        });
+        MediaAttribute::Rtcp(RtcpAddr { port, addr })
+    });
 
     // a=ice-ufrag:IdNYTNL1fjvjyEzL
     let ice_ufrag = attribute_line("ice-ufrag", any_value()).map(MediaAttribute::IceUfrag);
@@ -504,6 +538,26 @@ where
     )
     .map(MediaAttribute::MaxMessageSize);
 
+    // a=ptime:20
+    let ptime = attribute_line(
+        "ptime",
+        not_sp::<Input>().and_then(|s| {
+            s.parse::<u16>()
+                .map_err(StreamErrorFor::<Input>::message_format)
+        }),
+    )
+    .map(MediaAttribute::PTime);
+
+    // a=maxptime:60
+    let maxptime = attribute_line(
+        "maxptime",
+        not_sp::<Input>().and_then(|s| {
+            s.parse::<u16>()
+                .map_err(StreamErrorFor::<Input>::message_format)
+        }),
+    )
+    .map(MediaAttribute::MaxPTime);
+
     // a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level
     // a=extmap:<value>["/"<direction>] <URI> <extensionattributes>
     let extmap = attribute_line(
@@ -541,7 +595,15 @@ where
 
     let rtcpmux = attribute_line_flag("rtcp-mux").map(|_| MediaAttribute::RtcpMux);
     let rtcpmuxonly = attribute_line_flag("rtcp-mux-only").map(|_| MediaAttribute::RtcpMuxOnly);
-    let rtcprsize = attribute_line_flag("rtcp-rsize").map(|_| MediaAttribute::RtcpRsize);
+    let bundle_only = attribute_line_flag("bundle-only").map(|_| MediaAttribute::BundleOnly);
+    // a=ecn-capable-rtp:leap ect=0
+    // We only send ECT(0) without initiation, so the init methods don't matter.
+    let ecn = attribute_line("ecn-capable-rtp", any_value()).map(|_| MediaAttribute::EcnCapableRtp);
+
+    let rtcprsize_or_ecn = choice((
+        attempt(attribute_line_flag("rtcp-rsize").map(|_| MediaAttribute::RtcpRsize)),
+        attempt(ecn),
+    ));
 
     // a=candidate
     let cand = candidate_attribute().map(MediaAttribute::Candidate);
@@ -767,13 +829,14 @@ where
         attempt(msid),
         attempt(rtcp),
         attempt(rtcpmux),
-        attempt(rtcpmuxonly),
-        attempt(rtcprsize),
+        attempt(choice((attempt(rtcpmuxonly), bundle_only))),
+        attempt(rtcprsize_or_ecn),
         attempt(cand),
         attempt(endof),
         attempt(rtpmap),
         attempt(rtcp_fb),
-        attempt(fmtp),
+        // Grouped since choice() is limited in number of alternatives.
+        attempt(choice((attempt(fmtp), attempt(ptime), maxptime))),
         attempt(rid),
         attempt(simulcast),
         attempt(ssrc_group),
@@ -923,6 +986,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn media_attribute_line_ptime() {
+        let x = media_attribute_line().parse("a=ptime:20").unwrap();
+        assert_eq!(x.0, MediaAttribute::PTime(20));
+        assert_eq!("a=ptime:20\r\n", x.0.to_string());
+
+        let x = media_attribute_line().parse("a=maxptime:120").unwrap();
+        assert_eq!(x.0, MediaAttribute::MaxPTime(120));
+        assert_eq!("a=maxptime:120\r\n", x.0.to_string());
+
+        // Longer than 255ms, allowed for some codecs.
+        let x = media_attribute_line().parse("a=maxptime:480").unwrap();
+        assert_eq!(x.0, MediaAttribute::MaxPTime(480));
+    }
+
     #[test]
     fn media_attribute_line_rid_simple() {
         let x = media_attribute_line().parse("a=rid:lo send").unwrap();
@@ -1234,6 +1312,78 @@ mod test {
         assert_eq!(c.addr(), "113.185.55.72:41775".parse().unwrap());
     }
 
+    #[test]
+    fn parse_candidate_extension_attributes() {
+        // Chrome order, ufrag before network-id.
+        let a = "a=candidate:3684617590 1 udp 2122260223 10.217.229.219 50028 typ host generation 0 ufrag abc network-id 1 network-cost 10\r\n";
+        let (c, _) = candidate_attribute().parse(a).unwrap();
+        assert_eq!(c.ufrag(), Some("abc"));
+        assert_eq!(c.network_cost(), Some(10));
+
+        // Unknown attributes and invalid values are ignored.
+        let a = "a=candidate:3684617590 1 udp 2122260223 10.217.229.219 50028 typ host foo bar network-cost x\r\n";
+        let (c, _) = candidate_attribute().parse(a).unwrap();
+        assert_eq!(c.addr(), "10.217.229.219:50028".parse().unwrap());
+        assert_eq!(c.network_cost(), None);
+    }
+
+    #[test]
+    fn parse_ecn_capable_rtp() {
+        let parse = |a: &str| media_attribute_line().parse(a).unwrap().0;
+
+        let a = "a=ecn-capable-rtp:leap ect=0\r\n";
+        assert_eq!(parse(a), MediaAttribute::EcnCapableRtp);
+        assert_eq!(parse(a).to_string(), a);
+
+        let a = "a=ecn-capable-rtp: ice;rtp ect=1\r\n";
+        assert_eq!(parse(a), MediaAttribute::EcnCapableRtp);
+    }
+
+    #[test]
+    fn parse_rtcp_attribute() {
+        let parse = |a: &str| media_attribute_line().parse(a).unwrap().0;
+
+        let a = "a=rtcp:53020\r\n";
+        let rtcp = RtcpAddr {
+            port: 53020,
+            addr: None,
+        };
+        assert_eq!(parse(a), MediaAttribute::Rtcp(rtcp));
+        assert_eq!(parse(a).to_string(), a);
+
+        let a = "a=rtcp:53020 IN IP4 126.16.64.4\r\n";
+        let rtcp = RtcpAddr {
+            port: 53020,
+            addr: Some(RtcpHost::Ip("126.16.64.4".parse().unwrap())),
+        };
+        assert_eq!(parse(a), MediaAttribute::Rtcp(rtcp));
+        assert_eq!(parse(a).to_string(), a);
+
+        let a = "a=rtcp:53020 IN IP6 2001:2345:6789:ABCD:EF01:2345:6789:ABCD\r\n";
+        let rtcp = RtcpAddr {
+            port: 53020,
+            addr: Some(RtcpHost::Ip(
+                "2001:2345:6789:abcd:ef01:2345:6789:abcd".parse().unwrap(),
+            )),
+        };
+        assert_eq!(parse(a), MediaAttribute::Rtcp(rtcp));
+
+        let a = "a=rtcp:53020 IN IP4 rtcp.example.com\r\n";
+        let rtcp = RtcpAddr {
+            port: 53020,
+            addr: Some(RtcpHost::Fqdn {
+                ipv6: false,
+                name: "rtcp.example.com".into(),
+            }),
+        };
+        assert_eq!(parse(a), MediaAttribute::Rtcp(rtcp));
+        assert_eq!(parse(a).to_string(), a);
+
+        // Invalid values are kept as unused lines.
+        let a = "a=rtcp:foo\r\n";
+        assert!(matches!(parse(a), MediaAttribute::Unused(_)));
+    }
+
     #[test]
     fn parse_firefox_missing_setup_on_mid1() {
         let sdp = "v=0\r\n\
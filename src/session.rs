@@ -1,16 +1,20 @@
 use std::collections::VecDeque;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::bwe::BweKind;
+use crate::bwe::{
+    BandwidthEstimator, BweKind, BweThresholdCrossed, CrossingDirection, GoogCc, PacketFeedback,
+};
+use crate::change::BundlePolicy;
 use crate::crypto::KeyingMaterial;
 use crate::crypto::SrtpProfile;
 use crate::format::CodecConfig;
 use crate::format::PayloadParams;
-use crate::io::{DatagramSend, DATAGRAM_MTU, DATAGRAM_MTU_WARN};
+use crate::io::{DatagramSend, Ecn, Id, Marking};
+use crate::io::{DATAGRAM_MTU, DATAGRAM_MTU_WARN};
 use crate::media::KeyframeRequestKind;
-use crate::media::Media;
-use crate::media::{MediaAdded, MediaChanged};
-use crate::packet::SendSideBandwithEstimator;
+use crate::media::{DepayloadLimits, Media};
+use crate::media::{MediaAdded, MediaChanged, MediaKind};
 use crate::packet::{LeakyBucketPacer, NullPacer, Pacer, PacerImpl};
 use crate::rtp::RawPacket;
 use crate::rtp_::Direction;
@@ -18,10 +22,14 @@ use crate::rtp_::Pt;
 use crate::rtp_::SeqNo;
 use crate::rtp_::SRTCP_OVERHEAD;
 use crate::rtp_::{extend_u16, RtpHeader, SessionId, TwccRecvRegister, TwccSendRegister};
-use crate::rtp_::{Bitrate, ExtensionMap, Mid, Rtcp, RtcpFb};
-use crate::rtp_::{SrtpContext, Ssrc};
+use crate::rtp_::{App, Goodbye, ReportList};
+use crate::rtp_::{Bitrate, Extension, ExtensionMap, Mid, Rtcp, RtcpFb, RtcpObserver, RtcpPacket};
+use crate::rtp_::{ExtensionValues, SrtpContext, Ssrc};
+use crate::sdp::SdpError;
 use crate::stats::StatsSnapshot;
-use crate::streams::{RtpPacket, Streams};
+use crate::streams::{BufferedPacket, DemuxBuffer, DemuxBy, DemuxPolicy, RtpPacket, Streams};
+use crate::streams::{UnknownPt, UnknownPtPolicy};
+use crate::util::value_history::ValueHistory;
 use crate::util::{already_happened, not_happening, Soonest};
 use crate::Event;
 use crate::{net, Reason};
@@ -42,6 +50,21 @@ const PACING_FACTOR: f64 = 1.1;
 /// the total number BWE events to only fire when there is a substantial change.
 const ESTIMATE_TOLERANCE: f64 = 0.05;
 
+/// Default number of packets left for the SRTP key at which to warn.
+pub(crate) const DEFAULT_SRTP_LIMIT_MARGIN: u64 = 1 << 24;
+
+/// Min time between SrtpAuthFailure events.
+const SRTP_AUTH_FAILURE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Max number of SSRC/PT combinations remembered to emit UnknownPt once.
+const MAX_UNKNOWN_PT_SEEN: usize = 100;
+
+/// Max number of received RTCP APP packets waiting to be polled as events.
+const MAX_PENDING_RTCP_APP: usize = 100;
+
+/// Space for RTCP in a datagram, rounded to nearest multiple of 4 bytes.
+const ENCRYPTABLE_MTU: usize = (DATAGRAM_MTU - SRTCP_OVERHEAD) & !3;
+
 pub(crate) struct Session {
     id: SessionId,
 
@@ -56,11 +79,13 @@ pub(crate) struct Session {
     /// The app m-line. Spliced into medias above.
     app: Option<(Mid, usize)>,
 
-    reordering_size_audio: usize,
-    reordering_size_video: usize,
+    depayload_limits: DepayloadLimits,
     pub send_buffer_audio: usize,
     pub send_buffer_video: usize,
 
+    /// CNAME shared by all local media, sent in RTCP SDES.
+    pub cname: String,
+
     /// Extension mappings are _per BUNDLE_, but we can only have one a=group BUNDLE
     /// in WebRTC (one ice connection), so they are effectively per session.
     pub exts: ExtensionMap,
@@ -83,13 +108,27 @@ pub(crate) struct Session {
     /// A pacer for sending RTP at specific rate.
     pacer: PacerImpl,
 
+    /// Pacing priority of audio and video.
+    pacing_priority: (u8, u8),
+
     // temporary buffer when getting the next (unencrypted) RTP packet from Media line.
     poll_packet_buf: Vec<u8>,
 
     // Next packet for RtpPacket event.
-    pending_packet: Option<RtpPacket>,
-
-    pub ice_lite: bool,
+    pending_packets: VecDeque<RtpPacket>,
+    demux: DemuxPolicy,
+    demux_buffer: DemuxBuffer,
+
+    unknown_pt: UnknownPtPolicy,
+    unknown_pt_buffer: DemuxBuffer,
+    unknown_pt_count: u64,
+    // SSRC and PT already reported in UnknownPt events.
+    unknown_pt_seen: VecDeque<(Ssrc, Pt)>,
+    pending_unknown_pt: VecDeque<UnknownPt>,
+    // Incomplete frames dropped by the depayloaders.
+    frames_dropped_count: u64,
+    // Incoming RTCP APP packets.
+    pending_rtcp_app: VecDeque<App>,
 
     /// Whether we are running in RTP-mode.
     pub rtp_mode: bool,
@@ -97,7 +136,67 @@ pub(crate) struct Session {
     feedback_tx: VecDeque<Rtcp>,
     feedback_rx: VecDeque<Rtcp>,
 
+    /// Whether feedback_tx is sent as compound RTCP, or one packet per datagram.
+    pub rtcp_compound: bool,
+
+    /// Whether the remote peer has a=rtcp-rsize, i.e. accepts reduced-size RTCP.
+    pub remote_rtcp_rsize: bool,
+
+    /// Whether m-lines without a=rtcp-mux are rejected.
+    pub rtcp_mux_only: bool,
+
+    /// Which new m-lines in offers are bundle-only.
+    pub bundle_policy: BundlePolicy,
+
+    /// Whether ECN is enabled locally, which is offered with a=ecn-capable-rtp.
+    pub ecn: bool,
+
+    /// Whether the remote peer has a=ecn-capable-rtp.
+    pub remote_ecn: bool,
+
     raw_packets: Option<VecDeque<Box<RawPacket>>>,
+
+    // Decrypted RTP/RTCP to be written to the pcap capture.
+    #[cfg(feature = "pcap")]
+    pcap_packets: Option<VecDeque<Vec<u8>>>,
+
+    rtcp_observer: Option<Arc<dyn RtcpObserver>>,
+
+    // Factors of the needed bitrate for stopping and resuming simulcast layers.
+    layer_thresholds: (f64, f64),
+
+    // Buffer of simulcast layer bitrates and SSRCs for update_layers().
+    layer_buf: Vec<(Bitrate, Ssrc)>,
+
+    // Max age of media written before the SRTP keys are ready.
+    early_media_buffer: Option<Duration>,
+
+    // Packets left for the SRTP key at which to warn.
+    srtp_limit_margin: u64,
+
+    // Whether the SRTP limit margin has been reached. Only warned once.
+    srtp_limit_warned: bool,
+
+    // Packets left when the margin was reached, until polled.
+    pending_srtp_limit: Option<u64>,
+
+    // Incoming SRTP/SRTCP packets that failed to unprotect.
+    srtp_auth_failures: u64,
+
+    // When SrtpAuthFailure was last emitted, to rate limit it.
+    last_srtp_auth_failure: Option<Instant>,
+
+    // Failures to emit in SrtpAuthFailure, until polled.
+    pending_srtp_auth_failure: Option<u64>,
+
+    // Bytes of outgoing SRTP packets over the last second, for the send rate.
+    bytes_sent: ValueHistory<u64>,
+
+    // Max size of outgoing SRTP packets, if set.
+    pub rtp_mtu: Option<usize>,
+
+    // Extensions that could not be given the id the remote peer asked for.
+    exts_not_negotiated: VecDeque<(Mid, Extension)>,
 }
 
 impl Session {
@@ -111,13 +210,13 @@ impl Session {
         let (pacer, bwe) = if let Some(rate) = config.bwe_initial_bitrate {
             let pacer = PacerImpl::LeakyBucket(LeakyBucketPacer::new(rate * PACING_FACTOR * 2.0));
 
-            let send_side_bwe = SendSideBandwithEstimator::new(rate);
             let bwe = Bwe {
-                bwe: send_side_bwe,
+                bwe: Box::new(GoogCc::new(rate)),
                 desired_bitrate: Bitrate::ZERO,
                 current_bitrate: rate,
 
                 last_emitted_estimate: Bitrate::ZERO,
+                thresholds: vec![],
             };
 
             (pacer, Some(bwe))
@@ -125,15 +224,26 @@ impl Session {
             (PacerImpl::Null(NullPacer::default()), None)
         };
 
+        let mut streams = Streams::default();
+        streams.initial_seq_range = config.initial_seq_range.clone();
+
         Session {
             id,
             medias: vec![],
-            streams: Streams::default(),
+            streams,
             app: None,
-            reordering_size_audio: config.reordering_size_audio,
-            reordering_size_video: config.reordering_size_video,
+            depayload_limits: DepayloadLimits {
+                reordering_size_audio: config.reordering_size_audio,
+                reordering_size_video: config.reordering_size_video,
+                max_frame_size: config.max_frame_size,
+                frame_timeout: config.incomplete_frame_timeout,
+            },
             send_buffer_audio: config.send_buffer_audio,
             send_buffer_video: config.send_buffer_video,
+            cname: config
+                .cname
+                .clone()
+                .unwrap_or_else(|| Id::<20>::random().to_string()),
             exts: config.exts.clone(),
 
             // Both sending and receiving starts from the configured codecs.
@@ -150,17 +260,53 @@ impl Session {
             bwe,
             enable_twcc_feedback: false,
             pacer,
+            pacing_priority: (config.pacing_priority_audio, config.pacing_priority_video),
             poll_packet_buf: vec![0; 2000],
-            pending_packet: None,
-            ice_lite: config.ice_lite,
+            pending_packets: VecDeque::new(),
+            demux: config.demux_policy.clone(),
+            demux_buffer: DemuxBuffer::new(
+                config.demux_policy.buffer_packets,
+                config.demux_policy.buffer_time,
+            ),
+            unknown_pt: config.unknown_pt_policy,
+            unknown_pt_buffer: match config.unknown_pt_policy {
+                UnknownPtPolicy::Buffer { packets, time } => DemuxBuffer::new(packets, time),
+                _ => DemuxBuffer::new(0, Duration::ZERO),
+            },
+            unknown_pt_count: 0,
+            frames_dropped_count: 0,
+            unknown_pt_seen: VecDeque::new(),
+            pending_unknown_pt: VecDeque::new(),
+            pending_rtcp_app: VecDeque::new(),
             rtp_mode: config.rtp_mode,
             feedback_tx: VecDeque::new(),
             feedback_rx: VecDeque::new(),
+            rtcp_compound: config.rtcp_compound,
+            remote_rtcp_rsize: false,
+            rtcp_mux_only: config.rtcp_mux_only,
+            bundle_policy: config.bundle_policy,
+            ecn: config.ecn,
+            remote_ecn: false,
             raw_packets: if config.enable_raw_packets {
                 Some(VecDeque::new())
             } else {
                 None
             },
+            #[cfg(feature = "pcap")]
+            pcap_packets: config.pcap.as_ref().map(|_| VecDeque::new()),
+            rtcp_observer: config.rtcp_observer.clone(),
+            layer_thresholds: config.layer_thresholds,
+            layer_buf: vec![],
+            early_media_buffer: config.early_media_buffer,
+            srtp_limit_margin: config.srtp_limit_margin,
+            srtp_limit_warned: false,
+            pending_srtp_limit: None,
+            srtp_auth_failures: 0,
+            last_srtp_auth_failure: None,
+            pending_srtp_auth_failure: None,
+            bytes_sent: ValueHistory::default(),
+            rtp_mtu: None,
+            exts_not_negotiated: VecDeque::new(),
         }
     }
 
@@ -168,13 +314,10 @@ impl Session {
         self.id
     }
 
-    pub fn set_app(&mut self, mid: Mid, index: usize) -> Result<(), String> {
+    pub fn set_app(&mut self, mid: Mid, index: usize) -> Result<(), SdpError> {
         if let Some((mid_existing, index_existing)) = self.app {
-            if mid_existing != mid {
-                return Err(format!("App mid changed {} != {}", mid, mid_existing,));
-            }
-            if index_existing != index {
-                return Err(format!("App index changed {} != {}", index, index_existing,));
+            if mid_existing != mid || index_existing != index {
+                return Err(SdpError::ChangedOrder(mid));
             }
         } else {
             self.app = Some((mid, index));
@@ -201,6 +344,18 @@ impl Session {
         self.srtp_tx = Some(SrtpContext::new(srtp_profile, &mat, left));
     }
 
+    pub fn srtp_profile(&self) -> Option<SrtpProfile> {
+        self.srtp_tx.as_ref().map(|s| s.profile())
+    }
+
+    pub fn srtp_packets_left(&self) -> Option<u64> {
+        let srtp = self.srtp_tx.as_ref()?;
+        Some(
+            srtp.srtcp_packets_left()
+                .min(self.streams.srtp_packets_left()),
+        )
+    }
+
     pub fn handle_timeout(&mut self, now: Instant) -> Result<(), RtcError> {
         // Payload any waiting samples
         self.do_payload(now)?;
@@ -222,6 +377,22 @@ impl Session {
             self.last_nack = now;
         }
 
+        if !self.srtp_limit_warned {
+            if let Some(left) = self.srtp_packets_left() {
+                if left <= self.srtp_limit_margin {
+                    warn!("SRTP key has {} packets left", left);
+                    self.srtp_limit_warned = true;
+                    self.pending_srtp_limit = Some(left);
+                }
+            }
+        }
+
+        if self.srtp_tx.is_none() {
+            if let Some(max_age) = self.early_media_buffer {
+                self.streams.drop_early_media(now, max_age);
+            }
+        }
+
         self.update_queue_state(now);
 
         if let Some(twcc_at) = self.twcc_at() {
@@ -231,14 +402,92 @@ impl Session {
         }
 
         if let Some(bwe) = self.bwe.as_mut() {
+            let probe_before = bwe.probe_target();
             bwe.handle_timeout(now);
+
+            // The pacer pads up to the probe target while the probe cluster is sent.
+            if bwe.probe_target() != probe_before {
+                self.configure_pacer();
+            }
         }
 
+        self.update_layers();
+
         Ok(())
     }
 
+    /// Stop or resume simulcast layers given the available bitrate.
+    ///
+    /// The BWE estimate is shared between the media in order, each limited by its b=AS.
+    fn update_layers(&mut self) {
+        let (stop, resume) = self.layer_thresholds;
+        let mut remaining = self.bwe.as_ref().and_then(|b| b.last_estimate());
+
+        for media in &self.medias {
+            let available = match (remaining, media.remote_bitrate()) {
+                (Some(r), Some(b)) => Some(r.min(b)),
+                (r, b) => r.or(b),
+            };
+            let Some(available) = available else {
+                continue;
+            };
+
+            // Reused between calls to not allocate on every timeout.
+            let layers = &mut self.layer_buf;
+            layers.clear();
+            layers.extend(
+                self.streams
+                    .streams_tx_by_mid(media.mid())
+                    .filter_map(|s| Some((s.layer_bitrate()?, s.ssrc()))),
+            );
+
+            if layers.is_empty() {
+                continue;
+            }
+
+            layers.sort_by(|a, b| a.0.as_f64().total_cmp(&b.0.as_f64()));
+
+            let mut needed = Bitrate::ZERO;
+            let mut used = Bitrate::ZERO;
+            let mut stopped = false;
+
+            for (i, (bitrate, ssrc)) in layers.iter().enumerate() {
+                let Some(layer) = self.streams.stream_tx(ssrc) else {
+                    continue;
+                };
+
+                needed = needed + *bitrate;
+
+                let factor = if layer.is_layer_active() {
+                    stop
+                } else {
+                    resume
+                };
+                let enough = available.as_f64() >= needed.as_f64() * factor;
+
+                // The lowest layer is never stopped.
+                let active = i == 0 || (!stopped && enough);
+                stopped |= !active;
+
+                layer.set_layer_active(active);
+
+                if active {
+                    used = needed;
+                }
+            }
+
+            remaining = remaining.map(|r| if r > used { r - used } else { Bitrate::ZERO });
+        }
+    }
+
     fn update_queue_state(&mut self, now: Instant) {
-        let iter = self.streams.streams_tx().map(|m| m.queue_state(now));
+        let (audio, video) = self.pacing_priority;
+        let priority = move |kind: MediaKind| if kind.is_audio() { audio } else { video };
+
+        let iter = self
+            .streams
+            .streams_tx()
+            .map(|m| m.queue_state(now, priority));
 
         let Some(padding_request) = self.pacer.handle_timeout(now, iter) else {
             return;
@@ -266,13 +515,13 @@ impl Session {
         Some(())
     }
 
-    pub fn handle_rtp_receive(&mut self, now: Instant, message: &[u8]) {
+    pub fn handle_rtp_receive(&mut self, now: Instant, message: &[u8], ecn: Option<Ecn>) {
         let Some(header) = RtpHeader::parse(message, &self.exts) else {
             trace!("Failed to parse RTP header");
             return;
         };
 
-        self.handle_rtp(now, header, message);
+        self.handle_rtp(now, header, message, ecn);
     }
 
     pub fn handle_rtcp_receive(&mut self, now: Instant, message: &[u8]) {
@@ -289,32 +538,28 @@ impl Session {
             return Some(r);
         }
 
-        // Attempt to dynamically map this header to some Media/ReceiveStream.
-        self.map_dynamic(header);
+        // Attempt to dynamically map this header to some Media/ReceiveStream, trying
+        // the strategies in the order of the demux policy.
+        for i in 0..self.demux.order.len() {
+            self.map_dynamic(self.demux.order[i], header);
 
-        // The dynamic mapping might have added an entry by now.
-        self.streams.mid_ssrc_rx_by_ssrc_or_rtx(now, ssrc_header)
+            // The dynamic mapping might have added an entry by now.
+            if let Some(r) = self.streams.mid_ssrc_rx_by_ssrc_or_rtx(now, ssrc_header) {
+                return Some(r);
+            }
+        }
+
+        None
     }
 
-    fn map_dynamic(&mut self, header: &RtpHeader) {
-        // There are two strategies for dynamically mapping SSRC. Both use the RTP "mid"
-        // header extension.
+    fn map_dynamic(&mut self, by: DemuxBy, header: &RtpHeader) {
+        // There are three strategies for dynamically mapping SSRC.
         // A) Mid+Rid - used when doing simulcast. Rid points out which
         //              simulcast layer is in use. There is a separate header
         //              to indicate repair (RTX) stream.
         // B) Mid+PT - when not doing simulcast, the PT identifies whether
         //             this is a repair stream.
-
-        let Some(mid) = header.ext_vals.mid else {
-            return;
-        };
-        let rid = header.ext_vals.rid.or(header.ext_vals.rid_repair);
-
-        // The media the mid points out. Bail if the mid points to something
-        // we don't know about.
-        let Some(media) = self.medias.iter_mut().find(|m| m.mid() == mid) else {
-            return;
-        };
+        // C) PT - no mid, the PT alone must point out a single media.
 
         // Figure out which payload the PT maps to. Either main or RTX.
         let maybe_payload = self
@@ -327,33 +572,166 @@ impl Session {
             return;
         };
 
-        if let Some(rid) = rid {
-            // Case A - use the rid_repair header to identify RTX.
-            let is_main = header.ext_vals.rid.is_some();
+        let mid = match by {
+            DemuxBy::MidRid | DemuxBy::MidPt => header.ext_vals.mid,
+            DemuxBy::Pt => {
+                let config = &self.codec_config;
+
+                // Media without negotiated PTs, i.e. from the direct API, use all PTs
+                // of its kind.
+                let mut using_pt = self.medias.iter().filter(|m| {
+                    if m.remote_pts().is_empty() {
+                        config
+                            .all_for_kind(m.kind())
+                            .any(|p| p.pt() == payload.pt())
+                    } else {
+                        m.remote_pts().contains(&payload.pt())
+                    }
+                });
 
-            self.streams
-                .map_dynamic_by_rid(header.ssrc, mid, rid, media, *payload, is_main);
-        } else {
-            // Case B - the payload type identifies RTX.
-            let is_main = payload.pt() == header.payload_type;
+                match (using_pt.next(), using_pt.next()) {
+                    (Some(m), None) => Some(m.mid()),
+                    _ => None,
+                }
+            }
+        };
+
+        let Some(mid) = mid else {
+            return;
+        };
+
+        // The media the mid points out. Bail if the mid points to something
+        // we don't know about.
+        let Some(media) = self.medias.iter_mut().find(|m| m.mid() == mid) else {
+            return;
+        };
+
+        match by {
+            DemuxBy::MidRid => {
+                let Some(rid) = header.ext_vals.rid.or(header.ext_vals.rid_repair) else {
+                    return;
+                };
+
+                // Case A - use the rid_repair header to identify RTX.
+                let is_main = header.ext_vals.rid.is_some();
+
+                self.streams.map_dynamic_by_rid(
+                    header.ssrc,
+                    mid,
+                    rid,
+                    media,
+                    payload.clone(),
+                    is_main,
+                );
+            }
+            DemuxBy::MidPt | DemuxBy::Pt => {
+                // Case B and C - the payload type identifies RTX.
+                let is_main = payload.pt() == header.payload_type;
 
-            self.streams
-                .map_dynamic_by_pt(header.ssrc, mid, media, *payload, is_main);
+                self.streams
+                    .map_dynamic_by_pt(header.ssrc, mid, media, payload.clone(), is_main);
+            }
         }
     }
 
-    pub(crate) fn handle_rtp(&mut self, now: Instant, mut header: RtpHeader, buf: &[u8]) {
-        // Rewrite absolute-send-time (if present) to be relative to now.
-        header.ext_vals.update_absolute_send_time(now);
+    fn handle_unknown_pt(&mut self, now: Instant, header: RtpHeader, buf: &[u8], ecn: Option<Ecn>) {
+        self.unknown_pt_count += 1;
+
+        match self.unknown_pt {
+            UnknownPtPolicy::Drop => {
+                trace!("Drop packet with unknown PT: {:?}", header);
+            }
+            UnknownPtPolicy::Buffer { .. } => {
+                trace!("Buffer packet with unknown PT: {:?}", header);
+                let packet = BufferedPacket {
+                    received: now,
+                    header,
+                    buf: buf.to_vec(),
+                    ecn,
+                };
+                self.unknown_pt_buffer.push(packet);
+            }
+            UnknownPtPolicy::Event => {
+                let key = (header.ssrc, header.payload_type);
+                if self.unknown_pt_seen.contains(&key) {
+                    return;
+                }
+
+                debug!(
+                    "Unknown PT {} for SSRC {}",
+                    header.payload_type, header.ssrc
+                );
+
+                // Bound the memory for peers spraying SSRCs.
+                if self.unknown_pt_seen.len() >= MAX_UNKNOWN_PT_SEEN {
+                    self.unknown_pt_seen.pop_front();
+                }
+                self.unknown_pt_seen.push_back(key);
+
+                self.pending_unknown_pt.push_back(UnknownPt {
+                    ssrc: header.ssrc,
+                    pt: header.payload_type,
+                    mid: header.ext_vals.mid,
+                });
+            }
+        }
+    }
 
+    pub(crate) fn handle_rtp(
+        &mut self,
+        now: Instant,
+        mut header: RtpHeader,
+        buf: &[u8],
+        ecn: Option<Ecn>,
+    ) {
         trace!("Handle RTP: {:?}", header);
 
+        if main_payload_params(&self.codec_config, header.payload_type).is_none() {
+            // Dropping doesn't need the (costly) SRTP authentication.
+            let keep = !matches!(self.unknown_pt, UnknownPtPolicy::Drop);
+            if !keep || self.is_authentic_rtp(now, &header, buf) {
+                self.handle_unknown_pt(now, header, buf, ecn);
+            }
+            return;
+        }
+
+        // Packets that arrived before their PT was configured are handled first.
+        let config = &self.codec_config;
+        let configured = self.unknown_pt_buffer.take_where(now, |h| {
+            main_payload_params(config, h.payload_type).is_some()
+        });
+        for p in configured {
+            self.handle_rtp(p.received, p.header, &p.buf, p.ecn);
+        }
+
         // The ssrc is the _main_ ssrc (no the rtx, that might be in the header).
         let Some((mid, ssrc)) = self.mid_and_ssrc_for_header(now, &header) else {
-            debug!("No mid/SSRC for header: {:?}", header);
+            if header.ext_vals.mid.is_none() && self.demux.buffer_packets > 0 {
+                if !self.is_authentic_rtp(now, &header, buf) {
+                    return;
+                }
+                trace!("Buffer packet without mid: {:?}", header);
+                let packet = BufferedPacket {
+                    received: now,
+                    header,
+                    buf: buf.to_vec(),
+                    ecn,
+                };
+                self.demux_buffer.push(packet);
+            } else {
+                debug!("No mid/SSRC for header: {:?}", header);
+            }
             return;
         };
 
+        // Packets that arrived before the SSRC could be mapped are handled first.
+        for p in self.demux_buffer.take(header.ssrc, now) {
+            self.handle_rtp(p.received, p.header, &p.buf, p.ecn);
+        }
+
+        // Rewrite absolute-send-time (if present) to be relative to now.
+        header.ext_vals.update_absolute_send_time(now);
+
         let srtp = match self.srtp_rx.as_mut() {
             Some(v) => v,
             None => {
@@ -388,10 +766,16 @@ impl Session {
             Some(v) => v,
             None => {
                 trace!("Failed to unprotect SRTP");
+                self.srtp_auth_failure(now);
                 return;
             }
         };
 
+        #[cfg(feature = "pcap")]
+        if let Some(pcap_packets) = &mut self.pcap_packets {
+            pcap_packets.push_back([&buf[..header.header_len], &data].concat());
+        }
+
         if header.has_padding && !RtpHeader::unpad_payload(&mut data) {
             // Unpadding failed. Broken data?
             trace!("unpadding of unprotected payload failed");
@@ -409,8 +793,16 @@ impl Session {
             self.twcc_rx_register.update_seq(extended.into(), now);
         }
 
+        stream.update_extension_stats(&self.exts, header.extension_ids(buf));
+
+        // ECN feedback only when both sides have ECN.
+        if let Some(ecn) = ecn.filter(|_| self.ecn && self.remote_ecn) {
+            stream.register_ecn(ecn);
+        }
+
         // Register reception in nack registers.
-        let receipt_outer = stream.update_register(now, &header, clock_rate, is_repair, seq_no);
+        let receipt_outer =
+            stream.update_register(now, &header, clock_rate, is_repair, false, seq_no);
 
         // RTX packets must be rewritten to be a normal packet. This only changes the
         // the seq_no, however MediaTime might be different when interpreted against the
@@ -432,7 +824,19 @@ impl Session {
             seq_no = stream.extend_seq(&header, false);
 
             // Now update the "main" register with the repaired packet info.
-            stream.update_register(now, &header, clock_rate, false, seq_no)
+            let receipt = stream.update_register(now, &header, clock_rate, false, true, seq_no);
+
+            // An RTX for a packet we already got via the main SSRC (or an earlier RTX).
+            // This happens for spurious resends, such as BWE probing, and must not result
+            // in the packet being delivered twice.
+            if !receipt.is_new_packet {
+                trace!("Drop RTX for already received seq_no: {}", seq_no);
+                return;
+            }
+
+            stream.register_rtx_recovered();
+
+            receipt
         } else {
             // This is not RTX, the outer seq and time is what we use. The first
             // stream.update will have updated the main register.
@@ -445,27 +849,80 @@ impl Session {
             // In RTP mode, we store the packet temporarily here for the next poll_output().
             // However only if this is a packet not seen before. This filters out spurious resends for padding.
             if receipt.is_new_packet {
-                self.pending_packet = Some(packet);
+                self.pending_packets.extend(stream.buffer_packet(packet));
             }
         } else {
             // In non-RTP mode, we let the Media use a Depayloader.
-            media.depayload(
+            let dropped = media.depayload(
                 stream.rid(),
                 packet,
-                self.reordering_size_audio,
-                self.reordering_size_video,
+                &self.depayload_limits,
                 &self.codec_config,
             );
+            self.frames_dropped_count += dropped as u64;
+        }
+    }
+
+    /// Check the SRTP authentication of a packet we can't handle yet, before it is buffered
+    /// or reported. The packet is unprotected again once it can be handled.
+    fn is_authentic_rtp(&mut self, now: Instant, header: &RtpHeader, buf: &[u8]) -> bool {
+        // Without a stream, the index is what a new stream would start at.
+        let seq_no = match self.streams.stream_rx(&header.ssrc) {
+            Some(stream) => stream.peek_seq(header),
+            None => header.sequence_number(None),
+        };
+
+        let Some(srtp) = self.srtp_rx.as_mut() else {
+            trace!("Rejecting SRTP while missing SrtpContext");
+            return false;
+        };
+
+        if srtp.unprotect_rtp(buf, header, *seq_no).is_none() {
+            trace!("Failed to unprotect SRTP");
+            self.srtp_auth_failure(now);
+            return false;
+        }
+
+        true
+    }
+
+    fn srtp_auth_failure(&mut self, now: Instant) {
+        self.srtp_auth_failures += 1;
+
+        if let Some(last) = self.last_srtp_auth_failure {
+            if now < last + SRTP_AUTH_FAILURE_INTERVAL {
+                return;
+            }
         }
+
+        self.last_srtp_auth_failure = Some(now);
+        self.pending_srtp_auth_failure = Some(self.srtp_auth_failures);
+    }
+
+    pub fn srtp_auth_failures(&self) -> u64 {
+        self.srtp_auth_failures
     }
 
     fn handle_rtcp(&mut self, now: Instant, buf: &[u8]) -> Option<()> {
         let srtp: &mut SrtpContext = self.srtp_rx.as_mut()?;
-        let unprotected = srtp.unprotect_rtcp(buf)?;
+        let Some(unprotected) = srtp.unprotect_rtcp(buf) else {
+            trace!("Failed to unprotect SRTCP");
+            self.srtp_auth_failure(now);
+            return None;
+        };
+
+        #[cfg(feature = "pcap")]
+        if let Some(pcap_packets) = &mut self.pcap_packets {
+            pcap_packets.push_back(unprotected.clone());
+        }
 
         Rtcp::read_packet(&unprotected, &mut self.feedback_rx);
         let mut need_configure_pacer = false;
 
+        if let Some(observer) = &self.rtcp_observer {
+            observer.on_rtcp_rx(now, self.feedback_rx.make_contiguous());
+        }
+
         if let Some(raw_packets) = &mut self.raw_packets {
             for fb in &self.feedback_rx {
                 raw_packets.push_back(Box::new(RawPacket::RtcpRx(fb.clone())));
@@ -481,7 +938,8 @@ impl Session {
                     let records = range.and_then(|range| self.twcc_tx_register.send_records(range));
 
                     if let Some(records) = records {
-                        bwe.update(records, now);
+                        let feedback: Vec<PacketFeedback> = records.map(Into::into).collect();
+                        bwe.update(&feedback, now);
                     }
                 }
                 need_configure_pacer = true;
@@ -492,6 +950,29 @@ impl Session {
                 continue;
             }
 
+            if let RtcpFb::Ecn(ecn) = fb {
+                let Some(stream) = self.streams.stream_tx(&ecn.ssrc) else {
+                    continue;
+                };
+                let (ce, total) = stream.ecn_delta(ecn);
+
+                if let Some(bwe) = &mut self.bwe {
+                    bwe.handle_ecn(ce, total, now);
+                    need_configure_pacer = true;
+                }
+                continue;
+            }
+
+            if let RtcpFb::App(app) = fb {
+                // Not tied to any stream, handed to the application as is.
+                if self.pending_rtcp_app.len() >= MAX_PENDING_RTCP_APP {
+                    debug!("Drop RTCP APP, too many pending");
+                    self.pending_rtcp_app.pop_front();
+                }
+                self.pending_rtcp_app.push_back(app);
+                continue;
+            }
+
             if fb.is_for_rx() {
                 let Some(stream) = self.streams.stream_rx(&fb.ssrc()) else {
                     continue;
@@ -521,6 +1002,14 @@ impl Session {
             )));
         }
 
+        if let Some(crossed) = self
+            .bwe
+            .as_mut()
+            .and_then(|bwe| bwe.poll_threshold_crossed())
+        {
+            return Some(Event::BweThresholdCrossed(crossed));
+        }
+
         // If we're not ready to flow media, don't send any events.
         if !self.ready_for_srtp() {
             return None;
@@ -532,26 +1021,103 @@ impl Session {
             }
         }
 
-        // This must be before pending_packet.take() since we need to emit the unpaused event
+        // This must be before pending_packets.pop_front() since we need to emit the unpaused event
         // before the first packet causing the unpause.
         if let Some(paused) = self.streams.poll_stream_paused() {
             return Some(Event::StreamPaused(paused));
         }
 
+        // Like the paused event, before the packet that made the stream active.
+        if let Some((active, activity)) = self.streams.poll_stream_activity() {
+            return Some(if active {
+                Event::StreamActive(activity)
+            } else {
+                Event::StreamInactive(activity)
+            });
+        }
+
+        // Before the packet that jumped.
+        if let Some(jump) = self.streams.poll_time_jump() {
+            return Some(Event::RtpTimeJump(jump));
+        }
+
+        if let Some(layer) = self.streams.poll_layer_active() {
+            return Some(Event::LayerActive(layer));
+        }
+
+        if let Some(dropped) = self.streams.poll_packets_dropped() {
+            return Some(Event::PacketsDropped(dropped));
+        }
+
+        if let Some(left) = self.pending_srtp_limit.take() {
+            return Some(Event::SrtpLimitApproaching(left));
+        }
+
+        if let Some(failures) = self.pending_srtp_auth_failure.take() {
+            return Some(Event::SrtpAuthFailure(failures));
+        }
+
+        if let Some(rejected) = self.streams.poll_stream_rejected() {
+            return Some(Event::StreamRejected(rejected));
+        }
+
+        if let Some(changed) = self.streams.poll_ssrc_changed() {
+            return Some(Event::StreamSsrcChanged(changed));
+        }
+
+        if let Some(unknown) = self.pending_unknown_pt.pop_front() {
+            return Some(Event::UnknownPt(unknown));
+        }
+
+        if let Some(app) = self.pending_rtcp_app.pop_front() {
+            return Some(Event::RtcpApp(app));
+        }
+
+        // Before the non-contiguous MediaData following the dropped frame.
+        for media in &mut self.medias {
+            if let Some(dropped) = media.poll_frame_dropped() {
+                return Some(Event::FrameDropped(dropped));
+            }
+        }
+
+        // Before pending_packets.pop_front() for the boundary to precede the packet.
+        if let Some(boundary) = self.streams.poll_frame_boundary() {
+            return Some(Event::FrameBoundary(boundary));
+        }
+
+        // Before the packets declared lost by an underrun.
+        if let Some(event) = self.streams.poll_jitter_buffer_event() {
+            return Some(Event::JitterBuffer(event));
+        }
+
         if self.rtp_mode {
-            if let Some(packet) = self.pending_packet.take() {
+            if let Some(packet) = self.pending_packets.pop_front() {
                 return Some(Event::RtpPacket(packet));
             }
+
+            match self.streams.poll_jitter_buffer() {
+                Some(Ok(packet)) => return Some(Event::RtpPacket(packet)),
+                Some(Err(lost)) => return Some(Event::RtpPacketsLost(lost)),
+                None => {}
+            }
         }
 
         if let Some(req) = self.streams.poll_keyframe_request() {
             return Some(Event::KeyframeRequest(req));
         }
 
+        if let Some(l) = self.streams.poll_loss_notification() {
+            return Some(Event::LossNotification(l));
+        }
+
         if let Some((mid, bitrate)) = self.streams.poll_remb_request() {
             return Some(Event::EgressBitrateEstimate(BweKind::Remb(mid, bitrate)));
         }
 
+        if let Some((mid, ext)) = self.exts_not_negotiated.pop_front() {
+            return Some(Event::ExtensionNotNegotiated(mid, ext));
+        }
+
         for media in &mut self.medias {
             if media.need_open_event {
                 media.need_open_event = false;
@@ -591,21 +1157,28 @@ impl Session {
         Ok(None)
     }
 
+    /// Decrypted RTP/RTCP handled since the last call, for the pcap capture.
+    #[cfg(feature = "pcap")]
+    pub fn poll_pcap_packet(&mut self) -> Option<Vec<u8>> {
+        self.pcap_packets.as_mut()?.pop_front()
+    }
+
     fn ready_for_srtp(&self) -> bool {
         self.srtp_rx.is_some() && self.srtp_tx.is_some()
     }
 
-    pub fn poll_datagram(&mut self, now: Instant) -> Option<net::DatagramSend> {
+    /// The next datagram to send, with the marking hints of the stream it belongs to.
+    pub fn poll_datagram(&mut self, now: Instant) -> Option<(net::DatagramSend, Marking)> {
         // Time must have progressed forward from start value.
         if now == already_happened() {
             return None;
         }
 
         let x = None
-            .or_else(|| self.poll_feedback())
+            .or_else(|| self.poll_feedback().map(|d| (d, Marking::default())))
             .or_else(|| self.poll_packet(now));
 
-        if let Some(x) = &x {
+        if let Some((x, _)) = &x {
             // In RTP mode we trust the API user feeds the RTP packet sizes they
             // need for the MTU they are targeting. This warning is only for when
             // str0m does the RTP packetization.
@@ -622,8 +1195,6 @@ impl Session {
             return None;
         }
 
-        // Round to nearest multiple of 4 bytes.
-        const ENCRYPTABLE_MTU: usize = (DATAGRAM_MTU - SRTCP_OVERHEAD) & !3;
         assert!(ENCRYPTABLE_MTU % 4 == 0);
 
         let mut data = vec![0_u8; ENCRYPTABLE_MTU];
@@ -635,7 +1206,10 @@ impl Session {
             }
         };
 
-        let len = Rtcp::write_packet(&mut self.feedback_tx, &mut data, output);
+        // Reduced-size RTCP only when both sides negotiated a=rtcp-rsize.
+        let compound = self.rtcp_compound || !self.remote_rtcp_rsize;
+
+        let len = Rtcp::write_packet(&mut self.feedback_tx, &mut data, compound, output);
 
         if len == 0 {
             return None;
@@ -646,6 +1220,11 @@ impl Session {
         let srtp = self.srtp_tx.as_mut()?;
         let protected = srtp.protect_rtcp(&data);
 
+        #[cfg(feature = "pcap")]
+        if let Some(pcap_packets) = &mut self.pcap_packets {
+            pcap_packets.push_back(data);
+        }
+
         assert!(
             protected.len() < DATAGRAM_MTU,
             "Encrypted SRTCP should be less than MTU"
@@ -654,7 +1233,7 @@ impl Session {
         Some(protected.into())
     }
 
-    fn poll_packet(&mut self, now: Instant) -> Option<DatagramSend> {
+    fn poll_packet(&mut self, now: Instant) -> Option<(DatagramSend, Marking)> {
         let srtp_tx = self.srtp_tx.as_mut()?;
 
         // Figure out which, if any, queue to poll
@@ -670,10 +1249,14 @@ impl Session {
 
         // TODO: allow for sending simulcast
         let stream = self.streams.stream_tx_by_mid_rid(media.mid(), None)?;
+        let marking = Marking {
+            dscp: stream.dscp(),
+            ecn: (self.ecn && self.remote_ecn).then_some(Ecn::Ect0),
+        };
 
         let params = &self.codec_config;
         let exts = media.remote_extmap();
-        let receipt = stream.poll_packet(now, exts, &mut self.twcc, params, buf)?;
+        let receipt = stream.poll_packet(now, exts, &mut self.twcc, params, self.rtp_mtu, buf)?;
 
         let PacketReceipt {
             header,
@@ -698,6 +1281,12 @@ impl Session {
         }
 
         let protected = srtp_tx.protect_rtp(buf, &header, *seq_no);
+        self.bytes_sent.push(now, protected.len() as u64);
+
+        #[cfg(feature = "pcap")]
+        if let Some(pcap_packets) = &mut self.pcap_packets {
+            pcap_packets.push_back(buf.clone());
+        }
 
         self.twcc_tx_register
             .register_seq(twcc_seq.into(), now, payload_size);
@@ -706,7 +1295,7 @@ impl Session {
         // avoiding an extra poll_timeout.
         self.update_queue_state(now);
 
-        Some(protected.into())
+        Some((protected.into(), marking))
     }
 
     pub fn poll_timeout(&mut self) -> (Option<Instant>, Reason) {
@@ -715,8 +1304,10 @@ impl Session {
         let twcc_at = self.twcc_at();
         let pacing_at = self.pacer.poll_timeout();
         let packetize_at = self.medias.iter().flat_map(|m| m.poll_timeout()).next();
-        let bwe_at = self.bwe.as_ref().map(|bwe| bwe.poll_timeout());
+        let bwe_at = self.bwe.as_ref().and_then(|bwe| bwe.poll_timeout());
         let paused_at = self.paused_at();
+        let inactive_at = self.streams.inactive_at();
+        let jitter_buffer_at = self.streams.jitter_buffer_at();
         let send_stream_at = self.streams.send_stream();
 
         (feedback_at, Reason::Feedback)
@@ -726,6 +1317,8 @@ impl Session {
             .soonest((packetize_at, Reason::Packetize))
             .soonest((bwe_at, Reason::Bwe))
             .soonest((paused_at, Reason::PauseCheck))
+            .soonest((inactive_at, Reason::ActivityCheck))
+            .soonest((jitter_buffer_at, Reason::JitterBuffer))
             .soonest((send_stream_at, Reason::SendStream))
     }
 
@@ -780,6 +1373,8 @@ impl Session {
 
         snapshot.egress_loss_fraction = self.twcc_tx_register.loss(Duration::from_secs(1), now);
         snapshot.ingress_loss_fraction = self.twcc_rx_register.loss();
+        snapshot.unknown_pt_packets = self.unknown_pt_count;
+        snapshot.frames_dropped = self.frames_dropped_count;
     }
 
     pub fn set_bwe_current_bitrate(&mut self, current_bitrate: Bitrate) {
@@ -802,6 +1397,44 @@ impl Session {
         }
     }
 
+    pub fn set_bwe_estimator(&mut self, estimator: Box<dyn BandwidthEstimator>) {
+        if let Some(bwe) = self.bwe.as_mut() {
+            bwe.bwe = estimator;
+        }
+        self.configure_pacer();
+    }
+
+    pub fn add_bwe_threshold(&mut self, threshold: Bitrate, hysteresis: f64) {
+        if let Some(bwe) = self.bwe.as_mut() {
+            bwe.thresholds.push(BweThreshold {
+                bitrate: threshold,
+                hysteresis,
+                above: None,
+            });
+        }
+    }
+
+    pub fn clear_bwe_thresholds(&mut self) {
+        if let Some(bwe) = self.bwe.as_mut() {
+            bwe.thresholds.clear();
+        }
+    }
+
+    pub fn send_headroom(&self, now: Instant) -> Option<Bitrate> {
+        let estimate = self.bwe.as_ref()?.last_estimate()?;
+
+        // The history is one second long, which makes the bytes the rate.
+        let send_rate = self.bytes_sent.sum_at(now) as f64 * 8.0;
+
+        Some((estimate.as_f64() - send_rate).into())
+    }
+
+    pub fn start_bwe_probe(&mut self, target_bitrate: Bitrate, duration: Duration) {
+        if let Some(bwe) = self.bwe.as_mut() {
+            bwe.start_probe(target_bitrate, duration);
+        }
+    }
+
     pub fn line_count(&self) -> usize {
         self.medias.len() + if self.app.is_some() { 1 } else { 0 }
     }
@@ -819,16 +1452,71 @@ impl Session {
         self.streams.remove_streams_by_mid(mid);
     }
 
+    /// Permanently retire a media whose m-line is removed (port 0).
+    ///
+    /// The media is kept to retain the m-line order, but all its streams are torn down,
+    /// and an RTCP BYE is queued for the outgoing SSRCs.
+    pub fn retire_media(&mut self, mid: Mid) {
+        let Some(media) = self.media_by_mid_mut(mid) else {
+            return;
+        };
+
+        info!("Retire media: {}", mid);
+        media.set_rejected(true);
+        media.set_direction(Direction::Inactive);
+
+        let ssrcs: Vec<Ssrc> = self
+            .streams
+            .streams_tx_by_mid(mid)
+            .flat_map(|s| [Some(s.ssrc()), s.rtx()])
+            .flatten()
+            .collect();
+
+        for reports in ReportList::lists_from_iter(ssrcs) {
+            self.feedback_tx
+                .push_back(Rtcp::Goodbye(Goodbye { reports }));
+        }
+
+        self.streams.remove_streams_by_mid(mid);
+    }
+
+    /// Queue an RTCP APP packet to be sent with the next outgoing RTCP.
+    pub fn send_rtcp_app(&mut self, app: App) -> Result<(), RtcError> {
+        if app.subtype > 31 {
+            return Err(RtcError::InvalidRtcpApp("subtype above 31"));
+        }
+        if app.length_words() * 4 > ENCRYPTABLE_MTU {
+            return Err(RtcError::InvalidRtcpApp("larger than MTU"));
+        }
+        self.feedback_tx.push_back(Rtcp::App(app));
+        Ok(())
+    }
+
+    /// Remap the session extensions to those of a new m-line.
+    pub fn remap_exts(&mut self, mid: Mid, remote_exts: &[(u8, &Extension)]) {
+        for ext in self.exts.remap(remote_exts) {
+            let entry = (mid, ext);
+            if !self.exts_not_negotiated.contains(&entry) {
+                self.exts_not_negotiated.push_back(entry);
+            }
+        }
+    }
+
     fn configure_pacer(&mut self) {
         let Some(bwe) = self.bwe.as_ref() else {
             return;
         };
 
-        let padding_rate = bwe
+        let mut padding_rate = bwe
             .last_estimate()
             .map(|estimate| estimate.min(bwe.desired_bitrate))
             .unwrap_or(Bitrate::ZERO);
 
+        // A probe cluster pads up to the target regardless of estimate and desired bitrate.
+        if let Some(target) = bwe.probe_target() {
+            padding_rate = padding_rate.max(target);
+        }
+
         self.pacer.set_padding_rate(padding_rate);
 
         // We pad up to the pacing rate, therefore we need to increase pacing if the estimate, and
@@ -851,12 +1539,20 @@ impl Session {
 
     fn do_payload(&mut self, now: Instant) -> Result<(), RtcError> {
         for m in &mut self.medias {
-            m.do_payload(now, &mut self.streams, &self.codec_config)?;
+            m.do_payload(now, &mut self.streams, &self.codec_config, self.rtp_mtu)?;
         }
 
         Ok(())
     }
 
+    pub fn max_payload_size(&mut self, ssrc: Ssrc) -> Option<usize> {
+        let stream = self.streams.stream_tx(&ssrc)?;
+        let media = self.medias.iter().find(|m| m.mid() == stream.mid())?;
+        let exts = media.remote_extmap();
+
+        Some(stream.max_payload_size(self.rtp_mtu, exts, &ExtensionValues::default()))
+    }
+
     pub fn set_direction(&mut self, mid: Mid, direction: Direction) -> bool {
         let Some(media) = self.media_by_mid_mut(mid) else {
             return false;
@@ -879,24 +1575,42 @@ impl Session {
         true
     }
 
-    pub fn is_request_keyframe_possible(&self, kind: KeyframeRequestKind) -> bool {
+    pub fn is_request_keyframe_possible(&self, mid: Mid, kind: KeyframeRequestKind) -> bool {
+        // Only the PTs negotiated for this m-line are relevant. Without SDP (direct API)
+        // there are no remote PTs and we check the entire codec config.
+        let pts = self
+            .media_by_mid(mid)
+            .map(|m| m.remote_pts())
+            .unwrap_or(&[]);
+
         // TODO: It's possible to have different set of feedback enabled for different
         // payload types. I.e. we could have FIR enabled for H264, but not for VP8.
         // We might want to make this check more fine grained by testing which PT is
         // in "active use" right now.
-        self.codec_config.iter().any(|r| match kind {
-            KeyframeRequestKind::Pli => r.fb_pli,
-            KeyframeRequestKind::Fir => r.fb_fir,
-        })
+        self.codec_config
+            .iter()
+            .filter(|r| pts.is_empty() || pts.contains(&r.pt))
+            .any(|r| match kind {
+                KeyframeRequestKind::Pli => r.fb_pli,
+                KeyframeRequestKind::Fir => r.fb_fir,
+            })
     }
 }
 
 struct Bwe {
-    bwe: SendSideBandwithEstimator,
+    bwe: Box<dyn BandwidthEstimator>,
     desired_bitrate: Bitrate,
     current_bitrate: Bitrate,
 
     last_emitted_estimate: Bitrate,
+    thresholds: Vec<BweThreshold>,
+}
+
+struct BweThreshold {
+    bitrate: Bitrate,
+    hysteresis: f64,
+    /// Which side of the threshold the estimate is on, None until the first estimate.
+    above: Option<bool>,
 }
 
 impl Bwe {
@@ -905,19 +1619,15 @@ impl Bwe {
     }
 
     pub fn reset(&mut self, init_bitrate: Bitrate) {
-        self.bwe = SendSideBandwithEstimator::new(init_bitrate);
+        self.bwe.reset(init_bitrate);
     }
 
-    pub fn update<'t>(
-        &mut self,
-        records: impl Iterator<Item = &'t crate::rtp_::TwccSendRecord>,
-        now: Instant,
-    ) {
-        self.bwe.update(records, now);
+    pub fn update(&mut self, feedback: &[PacketFeedback], now: Instant) {
+        self.bwe.handle_feedback(feedback, now);
     }
 
     fn poll_estimate(&mut self) -> Option<Bitrate> {
-        let estimate = self.bwe.last_estimate()?;
+        let estimate = self.bwe.estimate()?;
 
         let min = self.last_emitted_estimate * (1.0 - ESTIMATE_TOLERANCE);
         let max = self.last_emitted_estimate * (1.0 + ESTIMATE_TOLERANCE);
@@ -931,12 +1641,53 @@ impl Bwe {
         }
     }
 
-    fn poll_timeout(&self) -> Instant {
+    fn poll_threshold_crossed(&mut self) -> Option<BweThresholdCrossed> {
+        let estimate = self.bwe.estimate()?;
+
+        for t in &mut self.thresholds {
+            let upper = t.bitrate * (1.0 + t.hysteresis);
+            let lower = t.bitrate * (1.0 - t.hysteresis);
+
+            let direction = match t.above {
+                None => {
+                    t.above = Some(estimate >= t.bitrate);
+                    continue;
+                }
+                Some(false) if estimate > upper => CrossingDirection::Up,
+                Some(true) if estimate < lower => CrossingDirection::Down,
+                _ => continue,
+            };
+
+            t.above = Some(direction == CrossingDirection::Up);
+
+            return Some(BweThresholdCrossed {
+                threshold: t.bitrate,
+                direction,
+                estimate,
+            });
+        }
+
+        None
+    }
+
+    fn poll_timeout(&self) -> Option<Instant> {
         self.bwe.poll_timeout()
     }
 
     fn last_estimate(&self) -> Option<Bitrate> {
-        self.bwe.last_estimate()
+        self.bwe.estimate()
+    }
+
+    fn handle_ecn(&mut self, ce: u64, total: u64, now: Instant) {
+        self.bwe.handle_ecn(ce, total, now);
+    }
+
+    fn start_probe(&mut self, target_bitrate: Bitrate, duration: Duration) {
+        self.bwe.start_probe(target_bitrate, duration);
+    }
+
+    fn probe_target(&self) -> Option<Bitrate> {
+        self.bwe.probe_target()
     }
 }
 
@@ -2,10 +2,13 @@
 
 use std::{
     collections::{HashMap, VecDeque},
+    net::SocketAddr,
     time::{Duration, Instant},
 };
 
-use crate::rtp_::{Mid, Rid};
+use crate::io::{DatagramRecvInner, MultiplexKind};
+use crate::net::Protocol;
+use crate::rtp_::{JitterBufferMetrics, Mid, Rid};
 use crate::Bitrate;
 
 pub(crate) struct Stats {
@@ -21,6 +24,8 @@ pub(crate) struct StatsSnapshot {
     pub rx: u64,
     pub egress_loss_fraction: Option<f32>,
     pub ingress_loss_fraction: Option<f32>,
+    pub unknown_pt_packets: u64,
+    pub frames_dropped: u64,
     pub ingress: HashMap<(Mid, Option<Rid>), MediaIngressStats>,
     pub egress: HashMap<(Mid, Option<Rid>), MediaEgressStats>,
     pub bwe_tx: Option<Bitrate>,
@@ -36,6 +41,8 @@ impl StatsSnapshot {
             rx: 0,
             egress_loss_fraction: None,
             ingress_loss_fraction: None,
+            unknown_pt_packets: 0,
+            frames_dropped: 0,
             ingress: HashMap::new(),
             egress: HashMap::new(),
             bwe_tx: None,
@@ -57,6 +64,7 @@ pub(crate) enum StatsEvent {
 ///
 /// This event is generated roughly every second
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PeerStats {
     /// Total bytes transmitted.
     pub peer_bytes_rx: u64,
@@ -74,6 +82,14 @@ pub struct PeerStats {
     pub egress_loss_fraction: Option<f32>,
     /// The ingress loss since the last stats event.
     pub ingress_loss_fraction: Option<f32>,
+    /// Total RTP packets received with a payload type that is not configured.
+    ///
+    /// See [`UnknownPtPolicy`][crate::rtp::UnknownPtPolicy].
+    pub unknown_pt_packets: u64,
+    /// Total incoming frames dropped before they were complete.
+    ///
+    /// See [`Event::FrameDropped`][crate::Event::FrameDropped].
+    pub frames_dropped: u64,
 }
 
 /// Outgoing media statistics in [`Event::MediaEgressStats`][crate::Event::MediaEgressStats].
@@ -103,11 +119,17 @@ pub struct MediaEgressStats {
     pub plis: u64,
     /// Number of nacks received.
     pub nacks: u64,
+    /// Number of resends requested by NACK that were dropped, due to the cap set
+    /// with [`StreamTx::set_max_rtx_ratio()`][crate::rtp::StreamTx::set_max_rtx_ratio].
+    pub resends_dropped: u64,
     /// Round-trip-time (ms) extracted from the last RTCP receiver report.
     pub rtt: Option<f32>,
     /// Fraction of packets lost averaged from the RTCP receiver reports received.
     /// `None` if no reports have been received since the last event
     pub loss: Option<f32>,
+    /// Jitter buffer delay from the last RTCP extended report (RFC 7005) of the remote peer.
+    /// `None` if the remote peer hasn't reported it.
+    pub remote_jitter_buffer: Option<JitterBufferMetrics>,
     /// Timestamp when this event was generated
     pub timestamp: Instant,
     // TODO
@@ -140,6 +162,22 @@ pub struct MediaIngressStats {
     pub plis: u64,
     /// Number of nacks sent.
     pub nacks: u64,
+    /// Number of keyframe requests coalesced with another request instead of being sent.
+    ///
+    /// See [`StreamRx::set_keyframe_request_interval()`][crate::rtp::StreamRx::set_keyframe_request_interval].
+    pub keyframe_requests_suppressed: u64,
+    /// Number of packets recovered via RTX (resends).
+    ///
+    /// Resends of packets that were already received are not counted.
+    pub rtx_recovered: u64,
+    /// Number of packets missing at playout time in the jitter buffer (underrun).
+    ///
+    /// See [`StreamRx::set_jitter_buffer_events()`][crate::rtp::StreamRx::set_jitter_buffer_events].
+    pub jitter_buffer_underruns: u64,
+    /// Number of packets dropped from a full jitter buffer (overrun).
+    ///
+    /// See [`StreamRx::set_max_held_packets()`][crate::rtp::StreamRx::set_max_held_packets].
+    pub jitter_buffer_overruns: u64,
     /// Round-trip-time (ms) extracted from the last RTCP XR DLRR report block.
     pub rtt: Option<f32>,
     /// Fraction of packets lost extracted from the last RTCP receiver report.
@@ -177,6 +215,11 @@ impl MediaIngressStats {
             firs: self.firs + other.firs,
             plis: self.plis + other.plis,
             nacks: self.nacks + other.nacks,
+            keyframe_requests_suppressed: self.keyframe_requests_suppressed
+                + other.keyframe_requests_suppressed,
+            rtx_recovered: self.rtx_recovered + other.rtx_recovered,
+            jitter_buffer_underruns: self.jitter_buffer_underruns + other.jitter_buffer_underruns,
+            jitter_buffer_overruns: self.jitter_buffer_overruns + other.jitter_buffer_overruns,
             rtt,
             loss,
             timestamp: self.timestamp.max(other.timestamp),
@@ -232,6 +275,8 @@ impl Stats {
             bwe_tx: snapshot.bwe_tx,
             egress_loss_fraction: snapshot.egress_loss_fraction,
             ingress_loss_fraction: snapshot.ingress_loss_fraction,
+            unknown_pt_packets: snapshot.unknown_pt_packets,
+            frames_dropped: snapshot.frames_dropped,
         };
 
         self.events.push_back(StatsEvent::Peer(event));
@@ -260,3 +305,165 @@ impl Stats {
         self.events.pop_front()
     }
 }
+
+/// Transport level statistics from [`Rtc::transport_stats()`][crate::Rtc::transport_stats].
+///
+/// These count every datagram on the wire since the `Rtc` instance was created,
+/// as opposed to [`PeerStats`] and the media stats that are periodic events.
+#[derive(Debug, Clone, Default)]
+pub struct TransportStats {
+    /// All UDP/TCP datagrams, regardless of kind.
+    pub total: TransportCounters,
+    /// STUN (ICE connectivity checks).
+    pub stun: TransportCounters,
+    /// DTLS, including the SCTP traffic of data channels.
+    pub dtls: TransportCounters,
+    /// SRTP protected media.
+    pub srtp: TransportCounters,
+    /// SRTCP protected feedback and reports.
+    pub srtcp: TransportCounters,
+    /// Incoming SRTP and SRTCP packets that failed to authenticate or decrypt, and were dropped.
+    ///
+    /// See [`Event::SrtpAuthFailure`][crate::Event::SrtpAuthFailure].
+    pub srtp_auth_failures: u64,
+    /// The currently selected ICE candidate pair, if connected.
+    pub selected_pair: Option<SelectedPair>,
+}
+
+/// Send queue statistics from [`Rtc::pacer_stats()`][crate::Rtc::pacer_stats].
+///
+/// The queue is that of packets written to outgoing streams waiting to be paced out,
+/// summed over all streams. Resends and padding are not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PacerStats {
+    /// Packets waiting to be sent.
+    pub queue_packets: usize,
+    /// Payload bytes waiting to be sent.
+    pub queue_bytes: usize,
+    /// How long the oldest packet has waited. None if the queue is empty.
+    pub max_queue_time: Option<Duration>,
+    /// Total packets dropped for exceeding
+    /// [`StreamTx::set_max_queue_age()`][crate::rtp::StreamTx::set_max_queue_age].
+    pub dropped_packets: u64,
+    /// Total payload bytes dropped.
+    pub dropped_bytes: u64,
+}
+
+/// Sending overhead from [`Rtc::overhead_stats()`][crate::Rtc::overhead_stats].
+///
+/// Payload bytes sent over all outgoing streams, split by what they were sent for. The
+/// counts are those of the streams' stats, which means they restart from 0 with
+/// [`StreamTx::reset_stats()`][crate::rtp::StreamTx::reset_stats].
+///
+/// str0m doesn't send FEC packets. In-band FEC, such as that of Opus, is part of the media.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OverheadStats {
+    /// Payload bytes of regular packets.
+    pub media_bytes: u64,
+    /// Number of regular packets.
+    pub media_packets: u64,
+    /// Payload bytes resent (RTX) in response to NACKs.
+    pub rtx_bytes: u64,
+    /// Number of resent packets.
+    pub rtx_packets: u64,
+    /// Payload bytes of padding for bandwidth estimation, including spurious resends.
+    pub padding_bytes: u64,
+    /// Number of padding packets.
+    pub padding_packets: u64,
+}
+
+impl OverheadStats {
+    /// RTX bytes as a percentage of media bytes. None if no media has been sent.
+    pub fn rtx_percent(&self) -> Option<f32> {
+        percent(self.rtx_bytes, self.media_bytes)
+    }
+
+    /// Padding bytes as a percentage of media bytes. None if no media has been sent.
+    pub fn padding_percent(&self) -> Option<f32> {
+        percent(self.padding_bytes, self.media_bytes)
+    }
+}
+
+fn percent(part: u64, whole: u64) -> Option<f32> {
+    if whole == 0 {
+        return None;
+    }
+    Some((part as f64 / whole as f64 * 100.0) as f32)
+}
+
+/// Byte and packet counters in each direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportCounters {
+    /// Total bytes sent.
+    pub bytes_tx: u64,
+    /// Total datagrams sent.
+    pub packets_tx: u64,
+    /// Total bytes received.
+    pub bytes_rx: u64,
+    /// Total datagrams received.
+    pub packets_rx: u64,
+}
+
+/// The addresses of the selected ICE candidate pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedPair {
+    /// The protocol used for the pair.
+    pub proto: Protocol,
+    /// Local address we send from.
+    pub local: SocketAddr,
+    /// Remote address we send to.
+    pub remote: SocketAddr,
+}
+
+impl TransportCounters {
+    fn tx(&mut self, len: usize) {
+        self.bytes_tx += len as u64;
+        self.packets_tx += 1;
+    }
+
+    fn rx(&mut self, len: usize) {
+        self.bytes_rx += len as u64;
+        self.packets_rx += 1;
+    }
+}
+
+impl TransportStats {
+    pub(crate) fn count_tx(&mut self, buf: &[u8]) {
+        let len = buf.len();
+        self.total.tx(len);
+
+        let Ok(kind) = MultiplexKind::try_from(buf) else {
+            return;
+        };
+
+        match kind {
+            MultiplexKind::Stun => self.stun.tx(len),
+            MultiplexKind::Dtls => self.dtls.tx(len),
+            MultiplexKind::Rtp => self.srtp.tx(len),
+            MultiplexKind::Rtcp => self.srtcp.tx(len),
+        }
+    }
+
+    pub(crate) fn count_rx(&mut self, inner: &DatagramRecvInner) {
+        use DatagramRecvInner::*;
+
+        match inner {
+            Stun(v) => {
+                self.total.rx(v.wire_len());
+                self.stun.rx(v.wire_len());
+            }
+            Dtls(v) => {
+                self.total.rx(v.len());
+                self.dtls.rx(v.len());
+            }
+            Rtp(v) => {
+                self.total.rx(v.len());
+                self.srtp.rx(v.len());
+            }
+            Rtcp(v) => {
+                self.total.rx(v.len());
+                self.srtcp.rx(v.len());
+            }
+        }
+    }
+}
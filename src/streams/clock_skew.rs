@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::rtp_::{Frequency, MediaTime};
+
+/// Number of SR needed before estimating the skew.
+const MIN_SAMPLES: usize = 4;
+
+/// Number of SR to keep for the regression.
+const MAX_SAMPLES: usize = 32;
+
+/// Estimates the drift of the sender RTP clock against the sender wall clock.
+///
+/// Each SR carries a NTP/RTP timestamp pair. A linear regression of the RTP time over the
+/// NTP time gives the actual rate of the RTP clock, which differs from the nominal clock
+/// rate by the skew.
+#[derive(Debug, Default)]
+pub(crate) struct ClockSkew {
+    samples: VecDeque<(Instant, u64)>,
+    frequency: Option<Frequency>,
+}
+
+impl ClockSkew {
+    pub fn push(&mut self, ntp_time: Instant, rtp_time: MediaTime) {
+        let frequency = rtp_time.frequency();
+
+        let restart = self.frequency != Some(frequency)
+            || self
+                .samples
+                .back()
+                .map(|(t, _)| ntp_time <= *t)
+                .unwrap_or(false);
+
+        if restart {
+            // Either a new clock rate, or the sender wall clock jumped back.
+            self.samples.clear();
+            self.frequency = Some(frequency);
+        }
+
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back((ntp_time, rtp_time.numer()));
+    }
+
+    /// Estimated skew in parts per million. Positive when the RTP clock runs fast.
+    pub fn ppm(&self) -> Option<f64> {
+        if self.samples.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        let rate = self.frequency?.get() as f64;
+        let (t0, r0) = *self.samples.front()?;
+
+        // Relative to the first sample to not lose precision.
+        let points = self.samples.iter().map(|(t, r)| {
+            let x = (*t - t0).as_secs_f64();
+            let y = (*r as f64 - r0 as f64) / rate;
+            (x, y)
+        });
+
+        let n = self.samples.len() as f64;
+        let (sx, sy) = points
+            .clone()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        let (mx, my) = (sx / n, sy / n);
+
+        let (cov, var) = points.fold((0.0, 0.0), |(cov, var), (x, y)| {
+            (cov + (x - mx) * (y - my), var + (x - mx) * (x - mx))
+        });
+
+        if var <= 0.0 {
+            return None;
+        }
+
+        let slope = cov / var;
+
+        Some((slope - 1.0) * 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn sample(start: Instant, secs: u64, ppm: f64) -> (Instant, MediaTime) {
+        let rtp = (secs as f64 * 90_000.0 * (1.0 + ppm / 1_000_000.0)) as u64;
+        (
+            start + Duration::from_secs(secs),
+            MediaTime::new(rtp, Frequency::NINETY_KHZ),
+        )
+    }
+
+    #[test]
+    fn none_until_enough_samples() {
+        let start = Instant::now();
+        let mut skew = ClockSkew::default();
+
+        for i in 0..MIN_SAMPLES as u64 {
+            assert_eq!(skew.ppm(), None);
+            let (t, r) = sample(start, i * 5, 0.0);
+            skew.push(t, r);
+        }
+
+        assert!(skew.ppm().unwrap().abs() < 1.0);
+    }
+
+    #[test]
+    fn fast_clock() {
+        let start = Instant::now();
+        let mut skew = ClockSkew::default();
+
+        for i in 0..10 {
+            let (t, r) = sample(start, i * 5, 100.0);
+            skew.push(t, r);
+        }
+
+        let ppm = skew.ppm().unwrap();
+        assert!((ppm - 100.0).abs() < 1.0, "{ppm}");
+    }
+
+    #[test]
+    fn restart_on_backwards_ntp() {
+        let start = Instant::now() + Duration::from_secs(100);
+        let mut skew = ClockSkew::default();
+
+        for i in 0..10 {
+            let (t, r) = sample(start, i * 5, -50.0);
+            skew.push(t, r);
+        }
+        assert!(skew.ppm().is_some());
+
+        let (_, r) = sample(start, 50, -50.0);
+        skew.push(start - Duration::from_secs(10), r);
+        assert_eq!(skew.ppm(), None);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::io::Ecn;
+use crate::rtp_::{Mid, Pt, RtpHeader, Ssrc};
+
+/// How incoming RTP packets of an SSRC not yet known are mapped to a media and stream.
+///
+/// SSRCs signaled in the SDP, or mapped by an earlier packet, are always used first. For
+/// other SSRCs, the strategies in `order` are tried one by one until one maps the packet.
+///
+/// Some peers send the first packets of a stream without the mid header extension. Such
+/// packets can be buffered until a packet of the same SSRC with the mid arrives, at which
+/// point they are handled in the order they were received. The buffer is bounded both in
+/// number of packets and in time, which bounds the memory to `buffer_packets` datagrams.
+///
+/// ```
+/// # use str0m::Rtc;
+/// # use str0m::rtp::{DemuxBy, DemuxPolicy};
+/// # use std::time::Duration;
+/// let policy = DemuxPolicy {
+///     order: vec![DemuxBy::MidRid, DemuxBy::MidPt, DemuxBy::Pt],
+///     buffer_packets: 50,
+///     buffer_time: Duration::from_millis(500),
+/// };
+///
+/// let rtc = Rtc::builder().set_demux_policy(policy).build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemuxPolicy {
+    /// The strategies to try, in order.
+    pub order: Vec<DemuxBy>,
+    /// Max number of packets without mid to buffer while waiting for a packet with mid.
+    ///
+    /// 0 means such packets are dropped.
+    pub buffer_packets: usize,
+    /// Max time a packet is kept in the buffer.
+    pub buffer_time: Duration,
+}
+
+/// A strategy for mapping an incoming RTP packet to a media and stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemuxBy {
+    /// The mid and rid header extensions, used for simulcast.
+    ///
+    /// The repaired rid header extension identifies RTX.
+    MidRid,
+    /// The mid header extension, any rid is ignored.
+    ///
+    /// The payload type identifies RTX. Does not map media that expects rids.
+    MidPt,
+    /// The payload type alone, ignoring the mid.
+    ///
+    /// Only maps the packet if exactly one media uses the payload type. Media declared via
+    /// the direct API uses all payload types of its kind. This corresponds to
+    /// the last step of demuxing in BUNDLE, see
+    /// <https://www.rfc-editor.org/rfc/rfc8843#section-9.2>.
+    Pt,
+}
+
+impl Default for DemuxPolicy {
+    fn default() -> Self {
+        Self {
+            order: vec![DemuxBy::MidRid, DemuxBy::MidPt],
+            buffer_packets: 0,
+            buffer_time: Duration::ZERO,
+        }
+    }
+}
+
+/// What happens to incoming RTP packets with a payload type (PT) that is not configured.
+///
+/// Such packets can't be handled, since the PT doesn't say what codec the payload is, nor
+/// whether the packet is a resend. They are typically caused by misnegotiation, or by a peer
+/// that starts sending before it has received the SDP answer.
+///
+/// Regardless of policy, the packets are counted in
+/// [`PeerStats::unknown_pt_packets`][crate::stats::PeerStats::unknown_pt_packets].
+///
+/// ```
+/// # use str0m::Rtc;
+/// # use str0m::rtp::UnknownPtPolicy;
+/// # use std::time::Duration;
+/// let policy = UnknownPtPolicy::Buffer {
+///     packets: 50,
+///     time: Duration::from_millis(500),
+/// };
+///
+/// let rtc = Rtc::builder().set_unknown_pt_policy(policy).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownPtPolicy {
+    /// Drop the packets.
+    #[default]
+    Drop,
+    /// Buffer the packets while waiting for the PT to be configured by SDP negotiation.
+    ///
+    /// When a packet with a configured PT arrives, the buffered packets that now have a
+    /// configured PT are handled first, in the order they were received.
+    Buffer {
+        /// Max number of packets to buffer.
+        packets: usize,
+        /// Max time a packet is kept in the buffer.
+        time: Duration,
+    },
+    /// Drop the packets, and emit [`Event::UnknownPt`][crate::Event::UnknownPt].
+    ///
+    /// The event is emitted once per SSRC and PT.
+    Event,
+}
+
+/// Incoming RTP with a payload type that is not configured.
+///
+/// Only emitted with [`UnknownPtPolicy::Event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownPt {
+    /// The SSRC of the packets.
+    pub ssrc: Ssrc,
+    /// The payload type that is not configured.
+    pub pt: Pt,
+    /// The mid header extension of the first packet, if present.
+    pub mid: Option<Mid>,
+}
+
+/// Packets waiting for something that lets them be handled, such as a mid.
+#[derive(Debug)]
+pub(crate) struct DemuxBuffer {
+    packets: VecDeque<BufferedPacket>,
+    max_packets: usize,
+    max_time: Duration,
+}
+
+#[derive(Debug)]
+pub(crate) struct BufferedPacket {
+    pub received: Instant,
+    pub header: RtpHeader,
+    pub buf: Vec<u8>,
+    pub ecn: Option<Ecn>,
+}
+
+impl DemuxBuffer {
+    pub fn new(max_packets: usize, max_time: Duration) -> Self {
+        DemuxBuffer {
+            packets: VecDeque::new(),
+            max_packets,
+            max_time,
+        }
+    }
+
+    pub fn push(&mut self, packet: BufferedPacket) {
+        if self.max_packets == 0 {
+            return;
+        }
+
+        self.expire(packet.received);
+
+        while self.packets.len() >= self.max_packets {
+            self.packets.pop_front();
+        }
+
+        self.packets.push_back(packet);
+    }
+
+    /// Take the packets of the SSRC, in the order they were received.
+    pub fn take(&mut self, ssrc: Ssrc, now: Instant) -> Vec<BufferedPacket> {
+        self.take_where(now, |h| h.ssrc == ssrc)
+    }
+
+    /// Take the packets matching the predicate, in the order they were received.
+    pub fn take_where(
+        &mut self,
+        now: Instant,
+        mut f: impl FnMut(&RtpHeader) -> bool,
+    ) -> Vec<BufferedPacket> {
+        if self.packets.is_empty() {
+            return vec![];
+        }
+
+        self.expire(now);
+
+        if !self.packets.iter().any(|p| f(&p.header)) {
+            return vec![];
+        }
+
+        let (taken, kept) = self
+            .packets
+            .drain(..)
+            .partition::<VecDeque<_>, _>(|p| f(&p.header));
+        self.packets = kept;
+
+        taken.into()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(p) = self.packets.front() {
+            if now.saturating_duration_since(p.received) <= self.max_time {
+                break;
+            }
+            self.packets.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn packet(ssrc: u32, received: Instant) -> BufferedPacket {
+        BufferedPacket {
+            received,
+            header: RtpHeader {
+                ssrc: ssrc.into(),
+                ..Default::default()
+            },
+            buf: vec![],
+            ecn: None,
+        }
+    }
+
+    #[test]
+    fn buffer_bounded() {
+        let mut buffer = DemuxBuffer::new(2, Duration::from_millis(100));
+        let now = Instant::now();
+
+        buffer.push(packet(1, now));
+        buffer.push(packet(2, now));
+        buffer.push(packet(1, now + Duration::from_millis(10)));
+
+        // The oldest packet is dropped to make room.
+        let taken = buffer.take(1.into(), now + Duration::from_millis(20));
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].received, now + Duration::from_millis(10));
+
+        // Too old.
+        let taken = buffer.take(2.into(), now + Duration::from_millis(200));
+        assert!(taken.is_empty());
+    }
+
+    #[test]
+    fn buffer_take_where() {
+        let mut buffer = DemuxBuffer::new(10, Duration::from_millis(100));
+        let now = Instant::now();
+
+        buffer.push(packet(1, now));
+        buffer.push(packet(2, now + Duration::from_millis(10)));
+        buffer.push(packet(1, now + Duration::from_millis(20)));
+
+        let taken = buffer.take_where(now, |h| *h.ssrc == 1);
+        assert_eq!(taken.len(), 2);
+        assert!(taken[0].received < taken[1].received);
+
+        // The rest is kept.
+        assert_eq!(buffer.take(2.into(), now).len(), 1);
+    }
+
+    #[test]
+    fn buffer_disabled() {
+        let policy = DemuxPolicy::default();
+        let mut buffer = DemuxBuffer::new(policy.buffer_packets, policy.buffer_time);
+        let now = Instant::now();
+
+        buffer.push(packet(1, now));
+        assert!(buffer.take(1.into(), now).is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::rtp_::SeqNo;
+
+use super::RtpPacket;
+
+/// Multiple of the measured jitter used as delay when adaptive.
+const JITTER_MULTIPLIER: f64 = 3.0;
+
+/// Output of the jitter buffer, in sequence number order.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)] // We purposely don't want to allocate.
+pub(crate) enum Released {
+    Packet(RtpPacket),
+    Lost(RangeInclusive<SeqNo>),
+}
+
+/// Reorders incoming RTP packets and releases them after a playout delay.
+///
+/// Every packet is held for the delay counted from its arrival. Packets are released
+/// in sequence number order, and a gap is declared lost once the packet after the gap
+/// is due. Packets arriving after their sequence number was released (or declared
+/// lost) are dropped. When the buffer holds more than the max number of packets, the
+/// oldest packets are dropped and declared lost.
+#[derive(Debug, Default)]
+pub(crate) struct JitterBuffer {
+    /// The delay, or the minimum delay when adaptive. Zero means disabled.
+    target_delay: Duration,
+
+    /// Whether the delay follows the measured jitter.
+    adaptive: bool,
+
+    /// Arbitrary fixed point in time the jitter calculation is relative to.
+    epoch: Option<Instant>,
+
+    /// Relative transit time of the previous packet, in seconds.
+    last_transit: Option<f64>,
+
+    /// Interarrival jitter estimate in seconds.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc3550#appendix-A.8>
+    jitter: f64,
+
+    /// Max number of held packets. None means no limit.
+    max_packets: Option<usize>,
+
+    /// Held packets, ordered by sequence number.
+    queue: VecDeque<RtpPacket>,
+
+    /// Next sequence number to release. None before the first release.
+    next: Option<SeqNo>,
+
+    /// Released output waiting to be polled.
+    released: VecDeque<Released>,
+
+    /// Lowest and highest delay since the range was last taken.
+    delay_range: Option<(Duration, Duration)>,
+}
+
+impl JitterBuffer {
+    pub fn set_target_delay(&mut self, delay: Duration) {
+        self.target_delay = delay;
+    }
+
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.adaptive = adaptive;
+    }
+
+    pub fn set_max_packets(&mut self, max: Option<usize>) {
+        self.max_packets = max;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.target_delay.is_zero()
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    pub fn delay(&self) -> Duration {
+        if !self.adaptive {
+            return self.target_delay;
+        }
+        let jitter = Duration::from_secs_f64(self.jitter * JITTER_MULTIPLIER);
+        self.target_delay.max(jitter)
+    }
+
+    /// Hold a packet. Returns the number of packets dropped to stay within the max (overrun).
+    pub fn push(&mut self, packet: RtpPacket) -> u64 {
+        if let Some(next) = self.next {
+            if packet.seq_no < next {
+                trace!("Drop packet arriving too late: {}", packet.seq_no);
+                return 0;
+            }
+        }
+
+        self.update_jitter(&packet);
+
+        match self
+            .queue
+            .binary_search_by_key(&packet.seq_no, |p| p.seq_no)
+        {
+            Ok(_) => trace!("Drop duplicate packet: {}", packet.seq_no),
+            Err(i) => self.queue.insert(i, packet),
+        }
+
+        let max = self.max_packets.unwrap_or(usize::MAX);
+        let mut dropped = 0;
+
+        while self.queue.len() > max {
+            let packet = self.queue.pop_front().expect("front packet");
+            let seq_no = packet.seq_no;
+            let next = self.next.unwrap_or(seq_no);
+
+            debug!("Jitter buffer overrun, drop packet: {}", seq_no);
+            self.released.push_back(Released::Lost(next..=seq_no));
+            self.next = Some((*seq_no + 1).into());
+            dropped += 1;
+        }
+
+        dropped
+    }
+
+    fn update_jitter(&mut self, packet: &RtpPacket) {
+        let epoch = *self.epoch.get_or_insert(packet.timestamp);
+        let arrival = packet
+            .timestamp
+            .saturating_duration_since(epoch)
+            .as_secs_f64();
+        let transit = arrival - packet.time.as_seconds();
+
+        if let Some(last) = self.last_transit {
+            let d = (transit - last).abs();
+            self.jitter += (d - self.jitter) / 16.0;
+        }
+
+        self.last_transit = Some(transit);
+    }
+
+    /// Lowest and highest delay since the last call, including the current delay.
+    pub fn take_delay_range(&mut self) -> (Duration, Duration) {
+        let delay = self.delay();
+        let (low, high) = self.delay_range.take().unwrap_or((delay, delay));
+        (low.min(delay), high.max(delay))
+    }
+
+    /// Release the packets that are due. Returns the number of packets that were missing at
+    /// their playout time (underrun).
+    pub fn handle_timeout(&mut self, now: Instant) -> u64 {
+        let delay = self.delay();
+
+        self.delay_range = Some(match self.delay_range {
+            Some((low, high)) => (low.min(delay), high.max(delay)),
+            None => (delay, delay),
+        });
+
+        let mut missing = 0;
+
+        while let Some(packet) = self.queue.front() {
+            if packet.timestamp + delay > now {
+                break;
+            }
+
+            let packet = self.queue.pop_front().expect("front packet");
+            let seq_no = packet.seq_no;
+            let next = self.next.unwrap_or(seq_no);
+
+            if seq_no > next {
+                let last_lost = (*seq_no - 1).into();
+                self.released.push_back(Released::Lost(next..=last_lost));
+                missing += *seq_no - *next;
+            }
+
+            self.next = Some((*seq_no + 1).into());
+            self.released.push_back(Released::Packet(packet));
+        }
+
+        missing
+    }
+
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        let packet = self.queue.front()?;
+        Some(packet.timestamp + self.delay())
+    }
+
+    pub fn poll_released(&mut self) -> Option<Released> {
+        self.released.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rtp_::{MediaTime, RtpHeader};
+
+    fn packet(start: Instant, seq_no: u64, arrival_ms: u64) -> RtpPacket {
+        RtpPacket {
+            seq_no: seq_no.into(),
+            time: MediaTime::from_seconds(seq_no as f64 * 0.02),
+            header: RtpHeader::default(),
+            payload: vec![],
+            timestamp: start + Duration::from_millis(arrival_ms),
+            last_sender_info: None,
+            nackable: false,
+        }
+    }
+
+    fn drain(jb: &mut JitterBuffer) -> Vec<String> {
+        let mut out = vec![];
+        while let Some(r) = jb.poll_released() {
+            out.push(match r {
+                Released::Packet(p) => format!("{}", p.seq_no),
+                Released::Lost(r) => format!("lost {}-{}", r.start(), r.end()),
+            });
+        }
+        out
+    }
+
+    #[test]
+    fn reorders() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(50));
+
+        jb.push(packet(start, 1, 0));
+        jb.push(packet(start, 3, 20));
+        jb.push(packet(start, 2, 30));
+
+        jb.handle_timeout(start + Duration::from_millis(49));
+        assert!(drain(&mut jb).is_empty());
+        assert_eq!(jb.poll_timeout(), Some(start + Duration::from_millis(50)));
+
+        jb.handle_timeout(start + Duration::from_millis(80));
+        assert_eq!(drain(&mut jb), ["1", "2", "3"]);
+        assert_eq!(jb.poll_timeout(), None);
+    }
+
+    #[test]
+    fn declares_lost() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(50));
+
+        jb.push(packet(start, 1, 0));
+        jb.push(packet(start, 4, 20));
+
+        jb.handle_timeout(start + Duration::from_millis(50));
+        assert_eq!(drain(&mut jb), ["1"]);
+
+        jb.handle_timeout(start + Duration::from_millis(70));
+        assert_eq!(drain(&mut jb), ["lost 2-3", "4"]);
+
+        // Too late, already declared lost.
+        jb.push(packet(start, 3, 80));
+        jb.handle_timeout(start + Duration::from_millis(200));
+        assert!(drain(&mut jb).is_empty());
+    }
+
+    #[test]
+    fn underrun() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(50));
+
+        jb.push(packet(start, 1, 0));
+        jb.push(packet(start, 5, 20));
+
+        assert_eq!(jb.handle_timeout(start + Duration::from_millis(50)), 0);
+        assert_eq!(jb.handle_timeout(start + Duration::from_millis(70)), 3);
+        assert_eq!(drain(&mut jb), ["1", "lost 2-4", "5"]);
+    }
+
+    #[test]
+    fn overrun() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(50));
+        jb.set_max_packets(Some(2));
+
+        assert_eq!(jb.push(packet(start, 1, 0)), 0);
+        assert_eq!(jb.push(packet(start, 3, 1)), 0);
+        assert_eq!(jb.push(packet(start, 4, 2)), 1);
+        assert_eq!(drain(&mut jb), ["lost 1-1"]);
+
+        // The dropped packet is not part of the underrun.
+        assert_eq!(jb.handle_timeout(start + Duration::from_millis(60)), 1);
+        assert_eq!(drain(&mut jb), ["lost 2-2", "3", "4"]);
+    }
+
+    #[test]
+    fn adaptive() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(10));
+        jb.set_adaptive(true);
+
+        // Packets every 20ms, arriving with +-40ms jitter.
+        for i in 0..100 {
+            let arrival = i * 20 + if i % 2 == 0 { 40 } else { 0 };
+            jb.push(packet(start, i, arrival));
+        }
+
+        assert!(jb.delay() > Duration::from_millis(50));
+
+        jb.set_adaptive(false);
+        assert_eq!(jb.delay(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn delay_range() {
+        let start = Instant::now();
+        let mut jb = JitterBuffer::default();
+        jb.set_target_delay(Duration::from_millis(10));
+        jb.set_adaptive(true);
+
+        jb.handle_timeout(start);
+
+        for i in 0..100 {
+            let arrival = i * 20 + if i % 2 == 0 { 40 } else { 0 };
+            jb.push(packet(start, i, arrival));
+        }
+        jb.handle_timeout(start + Duration::from_millis(10));
+
+        let (low, high) = jb.take_delay_range();
+        assert_eq!(low, Duration::from_millis(10));
+        assert_eq!(high, jb.delay());
+
+        // Taking resets to the current delay.
+        jb.set_adaptive(false);
+        let (low, high) = jb.take_delay_range();
+        assert_eq!(low, Duration::from_millis(10));
+        assert_eq!(high, Duration::from_millis(10));
+    }
+}
@@ -1,21 +1,31 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{self};
+use std::ops::RangeInclusive;
 use std::time::Duration;
 use std::time::Instant;
 
 use crate::format::CodecConfig;
 use crate::format::PayloadParams;
-use crate::media::{KeyframeRequest, Media};
-use crate::rtp_::Ssrc;
+use crate::media::{KeyframeRequest, LossNotification, Media};
 use crate::rtp_::{Bitrate, Pt};
+use crate::rtp_::{Extension, ExtensionMap};
 use crate::rtp_::{MediaTime, SenderInfo};
 use crate::rtp_::{Mid, Rid, SeqNo};
 use crate::rtp_::{Rtcp, RtpHeader};
+use crate::rtp_::{Ssrc, SRTP_MAX_PACKETS};
+use crate::stats::{OverheadStats, PacerStats};
 use crate::util::{already_happened, NonCryptographicRng};
 
+pub use self::demux::{DemuxBy, DemuxPolicy, UnknownPt, UnknownPtPolicy};
+pub use self::quality::{audio_mos, estimate_quality, video_mos};
+pub use self::quality::{QualityEstimator, QualityInput, QualityScore};
 pub use self::receive::StreamRx;
 pub use self::send::StreamTx;
 
+mod clock_skew;
+mod demux;
+mod jitter_buffer;
+mod quality;
 mod receive;
 pub(crate) mod register;
 pub(crate) mod register_nack;
@@ -24,12 +34,19 @@ pub(crate) mod rtx_cache_buf;
 mod send;
 mod send_queue;
 
+pub(crate) use demux::{BufferedPacket, DemuxBuffer};
 pub(crate) use send::DEFAULT_RTX_CACHE_DURATION;
 
 // Time between regular receiver reports.
 // https://www.rfc-editor.org/rfc/rfc8829#section-5.1.2
 // Should technically be 4 seconds according to spec, but libWebRTC
 // expects video to be every second, and audio every 5 seconds.
+/// Default max number of incoming encoded streams.
+pub(crate) const DEFAULT_MAX_STREAMS_RX: usize = 1024;
+
+/// Default max number of outgoing encoded streams.
+pub(crate) const DEFAULT_MAX_STREAMS_TX: usize = 1024;
+
 const RR_INTERVAL_VIDEO: Duration = Duration::from_millis(1000);
 const RR_INTERVAL_AUDIO: Duration = Duration::from_millis(5000);
 
@@ -82,7 +99,11 @@ pub struct RtpPacket {
 
 /// Event when an encoded stream is considered paused/unpaused.
 ///
-/// This means the stream has not received any data for some time (default 1.5 seconds).
+/// This means the stream has not received any data for some time (default 1.5 seconds),
+/// configurable with [`StreamRx::set_pause_threshold()`].
+///
+/// Streams start out paused. The unpaused event is emitted on the first packet, and again
+/// every time packets resume after a pause.
 #[derive(Debug)]
 pub struct StreamPaused {
     /// The main SSRC of the encoded stream that paused.
@@ -98,6 +119,372 @@ pub struct StreamPaused {
     pub paused: bool,
 }
 
+/// Event when an incoming encoded stream starts or stops receiving packets.
+///
+/// Only emitted for streams with activity tracking enabled via
+/// [`StreamRx::set_inactive_timeout()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamActivity {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+}
+
+/// Event when the RTP timestamps of an incoming encoded stream jump implausibly.
+///
+/// Rather than following the jump, the media time is resynced to the receive time.
+/// Configurable with [`StreamRx::set_max_time_jump()`].
+#[derive(Debug)]
+pub struct RtpTimeJump {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// Media time of the packet before the jump.
+    pub previous: MediaTime,
+
+    /// RTP timestamp of the packet that jumped, as sent by the remote peer.
+    pub timestamp: u32,
+
+    /// The resynced media time used instead.
+    pub time: MediaTime,
+}
+
+/// Event when stale packets are dropped from the send queue of an outgoing encoded stream.
+///
+/// Enable using [`StreamTx::set_max_queue_age()`].
+#[derive(Debug)]
+pub struct PacketsDropped {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// Number of packets dropped.
+    pub packets: usize,
+
+    /// Payload bytes dropped.
+    pub bytes: usize,
+}
+
+/// Event when an incoming encoded stream is not added due to the stream limit.
+///
+/// See [`Rtc::set_max_streams()`][crate::Rtc::set_max_streams]. Emitted once per SSRC,
+/// however an SSRC the remote peer keeps sending is reported again every 10 seconds.
+#[derive(Debug)]
+pub struct StreamRejected {
+    /// The SSRC of the rejected stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream would belong to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+}
+
+/// Event when the remote changes the main SSRC of an incoming encoded stream.
+///
+/// This typically happens when the sender restarts its encoder. The [`StreamRx`] now
+/// uses the new SSRC, and the old SSRC is forgotten. The sequence numbers and RTP time
+/// start over, so the application should reset its decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSsrcChanged {
+    /// The mid of the encoded stream.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// The previous main SSRC.
+    pub from: Ssrc,
+
+    /// The new main SSRC.
+    pub to: Ssrc,
+}
+
+/// Timestamps of the last sender report (SR) received for an incoming encoded stream.
+///
+/// The NTP and RTP timestamps refer to the same point in time at the sender, which maps
+/// the media clock to the sender's wallclock. See [`StreamRx::last_sender_report()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SrInfo {
+    /// When the SR was received.
+    pub received: Instant,
+
+    /// The sender's NTP timestamp converted to an [`Instant`].
+    pub ntp_time: Instant,
+
+    /// The RTP timestamp corresponding to the NTP timestamp.
+    ///
+    /// This is extended to 64 bits and in the clock rate of the last received packet.
+    pub rtp_time: MediaTime,
+}
+
+/// Reordering of the packets received for an incoming encoded stream.
+///
+/// Resent packets are not counted, since they are expected to arrive out of order.
+/// See [`StreamRx::reorder_stats()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReorderStats {
+    /// Number of packets that arrived after a packet with a higher sequence number.
+    pub reordered: u64,
+
+    /// The largest number of sequence numbers a reordered packet arrived late by.
+    pub max_distance: u64,
+
+    /// Number of packets received more than once.
+    ///
+    /// Packets arriving so late that they are outside the NACK window are also counted
+    /// here, since they can't be told apart from duplicates.
+    pub duplicates: u64,
+}
+
+impl ReorderStats {
+    pub(crate) fn update(&mut self, seq: SeqNo, max_seq: Option<SeqNo>, is_new: bool) {
+        let Some(max_seq) = max_seq else {
+            // The first packet.
+            return;
+        };
+
+        if !is_new {
+            self.duplicates += 1;
+        } else if seq < max_seq {
+            self.reordered += 1;
+            self.max_distance = self.max_distance.max(*max_seq - *seq);
+        }
+    }
+}
+
+/// Presence of RTP header extensions in the packets received for an incoming encoded stream.
+///
+/// A peer can negotiate an extension and still never send it, which for example breaks the
+/// bandwidth estimation when abs-send-time or transport-wide-cc is missing. See
+/// [`StreamRx::extension_stats()`].
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionStats {
+    /// Number of packets received, including retransmissions.
+    pub packets: u64,
+
+    counts: Vec<(Extension, u64)>,
+}
+
+impl ExtensionStats {
+    /// Number of received packets the extension was present in.
+    pub fn count(&self, ext: &Extension) -> u64 {
+        self.counts
+            .iter()
+            .find(|(e, _)| e == ext)
+            .map(|(_, n)| *n)
+            .unwrap_or(0)
+    }
+
+    /// Iterate over the extensions seen in at least one packet, with their counts.
+    pub fn iter(&self) -> impl Iterator<Item = (&Extension, u64)> + '_ {
+        self.counts.iter().map(|(e, n)| (e, *n))
+    }
+
+    pub(crate) fn update(&mut self, exts: &ExtensionMap, ids: impl Iterator<Item = u8>) {
+        self.packets += 1;
+
+        for id in ids {
+            let Some(ext) = exts.lookup(id) else {
+                continue;
+            };
+
+            match self.counts.iter_mut().find(|(e, _)| e == ext) {
+                Some((_, n)) => *n += 1,
+                None => self.counts.push((ext.clone(), 1)),
+            }
+        }
+    }
+}
+
+/// Packets written to an outgoing encoded stream, but not yet sent.
+///
+/// A growing queue means the pacer can't send as fast as the application writes, typically
+/// because of a low bandwidth estimate. See [`StreamTx::pending()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingStats {
+    /// Number of packets waiting to be sent.
+    pub packets: usize,
+    /// Payload bytes waiting to be sent.
+    pub bytes: usize,
+    /// How long the oldest packet has waited. None if the queue is empty.
+    pub oldest: Option<Duration>,
+    /// How long the newest packet has waited. None if the queue is empty.
+    pub newest: Option<Duration>,
+}
+
+/// Event when a simulcast layer is stopped or resumed due to the available bitrate.
+///
+/// See [`StreamTx::set_layer_bitrate()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerActive {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// Whether the layer is sent or not.
+    pub active: bool,
+}
+
+/// Event when a frame starts or ends in an incoming encoded stream.
+///
+/// Derived from the RTP headers only, without depacketizing. A frame starts when the RTP
+/// timestamp changes between consecutive packets, and ends at a packet with the marker bit.
+/// Packets arriving out of order don't cause any events.
+///
+/// Enable using [`StreamRx::set_frame_boundary_events()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameBoundary {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// Whether the frame starts or ends.
+    pub kind: FrameBoundaryKind,
+
+    /// The (extended) RTP timestamp of the frame.
+    pub time: MediaTime,
+
+    /// The (extended) sequence number of the packet at the boundary.
+    pub seq_no: SeqNo,
+
+    /// Whether packets are missing between the previous packet and this one.
+    ///
+    /// For a start, this means the end of the previous frame, or the start of this one,
+    /// might be lost.
+    pub gap: bool,
+}
+
+/// Kind of [`FrameBoundary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameBoundaryKind {
+    /// The first packet with a new RTP timestamp.
+    Start,
+
+    /// A packet with the marker bit set. For video this is the last packet of the frame.
+    End,
+}
+
+/// Event when the jitter buffer of an encoded stream gave up on missing packets.
+///
+/// See [`StreamRx::set_target_delay()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtpPacketsLost {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// The (extended) sequence numbers of the lost packets.
+    pub seq_range: RangeInclusive<SeqNo>,
+}
+
+/// Event when the jitter buffer of an encoded stream underruns or overruns.
+///
+/// Enable using [`StreamRx::set_jitter_buffer_events()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JitterBufferEvent {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// Whether the jitter buffer underran or overran.
+    pub kind: JitterBufferEventKind,
+
+    /// Number of packets missing at playout time, or dropped.
+    pub packets: u64,
+
+    /// The playout delay of the jitter buffer when it happened.
+    pub delay: Duration,
+}
+
+/// Kind of [`JitterBufferEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JitterBufferEventKind {
+    /// Packets were missing at their playout time, and are declared lost.
+    ///
+    /// The application must conceal them. Frequent underruns mean the delay is too
+    /// short for the network.
+    Underrun,
+
+    /// The jitter buffer held more than the max number of packets, and the oldest were
+    /// dropped.
+    ///
+    /// See [`StreamRx::set_max_held_packets()`].
+    Overrun,
+}
+
+/// Incoming encoded streams originating from the same source.
+///
+/// Streams are grouped by the CNAME the remote peer sends in RTCP SDES. Members
+/// of a group, such as audio and video from the same camera/microphone, can be
+/// synchronized (lip-sync) using the NTP/RTP time mapping of their sender reports.
+///
+/// Obtained via [`Rtc::sync_groups()`][crate::Rtc::sync_groups].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncGroup {
+    /// The CNAME shared by all members.
+    pub cname: String,
+
+    /// The encoded streams in the group, ordered by SSRC.
+    pub members: Vec<SyncMember>,
+}
+
+/// An encoded stream in a [`SyncGroup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncMember {
+    /// The main SSRC of the encoded stream.
+    pub ssrc: Ssrc,
+
+    /// The mid the encoded stream belongs to.
+    pub mid: Mid,
+
+    /// The rid, if the encoded stream has a rid.
+    pub rid: Option<Rid>,
+
+    /// The last received sender report information.
+    ///
+    /// This maps the RTP time of the stream to the remote NTP time. It is None until
+    /// the first sender report is received, until then the stream can't be synchronized.
+    pub sender_info: Option<SenderInfo>,
+}
+
 /// 255 is out of range for a real PT, which is 7 bit.
 const BLANK_PACKET_DEFAULT_PT: Pt = Pt::new_with_value(255);
 
@@ -148,6 +535,34 @@ pub(crate) struct Streams {
     /// Whether nack reports are enabled. This is an optimization to avoid too frequent
     /// Session::nack_at() when we don't need to send nacks.
     any_nack_active: Option<bool>,
+
+    /// Max number of entries in streams_rx.
+    pub max_streams_rx: usize,
+
+    /// Max number of entries in streams_tx.
+    pub max_streams_tx: usize,
+
+    /// SSRCs rejected due to max_streams_rx. Cleared together with rx_lookup, to not
+    /// report the same SSRC for every packet.
+    rejected_ssrcs_rx: HashSet<Ssrc>,
+
+    /// Rejected streams waiting to be polled.
+    streams_rejected: VecDeque<StreamRejected>,
+
+    /// Changes of main SSRC in incoming streams waiting to be polled.
+    ssrcs_changed: VecDeque<StreamSsrcChanged>,
+
+    /// Max number of keyframe requests sent for all StreamRx within keyframe_request_window.
+    pub keyframe_request_max: usize,
+
+    /// Window of the session wide keyframe request limit.
+    pub keyframe_request_window: Duration,
+
+    /// When the keyframe requests within the window were sent. Only kept when limited.
+    keyframe_requests_sent: VecDeque<Instant>,
+
+    /// Range of the random initial sequence numbers of new StreamTx.
+    pub initial_seq_range: RangeInclusive<u16>,
 }
 
 /// Delay between cleaning up the RxLookup.
@@ -173,6 +588,15 @@ impl Default for Streams {
             default_ssrc_tx: 0.into(), // this will be changed
             mids_to_report: Vec::with_capacity(10),
             any_nack_active: None,
+            max_streams_rx: DEFAULT_MAX_STREAMS_RX,
+            max_streams_tx: DEFAULT_MAX_STREAMS_TX,
+            rejected_ssrcs_rx: HashSet::new(),
+            streams_rejected: VecDeque::new(),
+            ssrcs_changed: VecDeque::new(),
+            keyframe_request_max: usize::MAX,
+            keyframe_request_window: Duration::from_secs(1),
+            keyframe_requests_sent: VecDeque::new(),
+            initial_seq_range: 0..=u16::MAX,
         }
     }
 }
@@ -262,12 +686,22 @@ impl Streams {
             // Handle changes in SSRC.
             if ssrc_from != ssrc_main {
                 // We got a change in main SSRC for this stream.
-                self.change_stream_rx_ssrc(ssrc_from, ssrc_main);
+                if !self.change_stream_rx_ssrc(ssrc_from, ssrc_main) {
+                    // A stray packet from the previous SSRC. Don't recreate a stream for it.
+                    return;
+                }
 
                 // When the SSRCs changes the sequence number typically also does, the
                 // depayloader(if in use) relies on sequence numbers and will not handle a
                 // large jump corretly, reset it.
                 media.reset_depayloader(payload.pt(), rid);
+
+                self.ssrcs_changed.push_back(StreamSsrcChanged {
+                    mid,
+                    rid,
+                    from: ssrc_from,
+                    to: ssrc_main,
+                });
             }
 
             // Handle changes in RTX
@@ -278,13 +712,43 @@ impl Streams {
             }
         }
 
-        // If we don't have an RTX PT configured, we don't want NACK.
-        let suppress_nack = payload.resend.is_none();
+        // If we don't have an RTX PT configured, or NACK isn't negotiated, we don't want NACK.
+        let suppress_nack = payload.resend.is_none() || !payload.fb_nack;
+
+        if self.reject_stream_rx(ssrc_main, mid, rid) {
+            return;
+        }
 
         // If stream already exists, this might only "fill in" the RTX.
         self.expect_stream_rx(ssrc_main, rtx, mid, rid, suppress_nack);
     }
 
+    /// Whether a new incoming stream, announced or sent by the remote peer, would be
+    /// above max_streams_rx. The rejected stream is reported once per SSRC.
+    pub fn reject_stream_rx(&mut self, ssrc: Ssrc, mid: Mid, rid: Option<Rid>) -> bool {
+        if self.streams_rx.contains_key(&ssrc) || self.streams_rx.len() < self.max_streams_rx {
+            return false;
+        }
+
+        // Bounded to not grow the set for as many SSRCs as the remote sends.
+        let is_new = self.rejected_ssrcs_rx.len() < self.max_streams_rx
+            && self.rejected_ssrcs_rx.insert(ssrc);
+
+        // This happens for every packet of the SSRC, only log the first.
+        if is_new {
+            warn!(
+                "Reject incoming stream, max {} reached: {} {} {:?}",
+                self.max_streams_rx, ssrc, mid, rid
+            );
+            self.streams_rejected
+                .push_back(StreamRejected { ssrc, mid, rid });
+        } else {
+            trace!("Reject incoming stream: {}", ssrc);
+        }
+
+        true
+    }
+
     pub fn expect_stream_rx(
         &mut self,
         ssrc: Ssrc,
@@ -317,6 +781,20 @@ impl Streams {
         existed
     }
 
+    /// Whether a new outgoing stream from SDP negotiation would be above max_streams_tx.
+    pub fn reject_stream_tx(&self, ssrc: Ssrc, mid: Mid, rid: Option<Rid>) -> bool {
+        if self.streams_tx.contains_key(&ssrc) || self.streams_tx.len() < self.max_streams_tx {
+            return false;
+        }
+
+        warn!(
+            "Reject outgoing stream, max {} reached: {} {} {:?}",
+            self.max_streams_tx, ssrc, mid, rid
+        );
+
+        true
+    }
+
     pub fn declare_stream_tx(
         &mut self,
         ssrc: Ssrc,
@@ -324,9 +802,10 @@ impl Streams {
         mid: Mid,
         rid: Option<Rid>,
     ) -> &mut StreamTx {
+        let seq_range = &self.initial_seq_range;
         self.streams_tx
             .entry(ssrc)
-            .or_insert_with(|| StreamTx::new(ssrc, rtx, mid, rid))
+            .or_insert_with(|| StreamTx::new(ssrc, rtx, mid, rid, seq_range.clone()))
     }
 
     pub fn remove_stream_tx(&mut self, ssrc: Ssrc) -> bool {
@@ -377,13 +856,134 @@ impl Streams {
     pub(crate) fn regular_feedback_at(&self) -> Option<Instant> {
         let r = self.streams_rx.values().map(|s| s.receiver_report_at());
         let s = self.streams_tx.values().map(|s| s.sender_report_at());
-        r.chain(s).min()
+        let k = self
+            .streams_rx
+            .values()
+            .filter_map(|s| s.keyframe_request_at())
+            .min()
+            .map(|t| t.max(self.keyframe_request_slot_at()));
+        let l = self
+            .streams_rx
+            .values()
+            .filter_map(|s| s.loss_notification_at());
+        r.chain(s).chain(k).chain(l).min()
+    }
+
+    /// When the session wide limit allows another keyframe request.
+    fn keyframe_request_slot_at(&self) -> Instant {
+        if self.keyframe_requests_sent.len() < self.keyframe_request_max {
+            return already_happened();
+        }
+
+        self.keyframe_requests_sent
+            .front()
+            .map(|t| *t + self.keyframe_request_window)
+            .unwrap_or_else(already_happened)
+    }
+
+    fn maybe_create_keyframe_requests(
+        &mut self,
+        now: Instant,
+        sender_ssrc: Ssrc,
+        feedback: &mut VecDeque<Rtcp>,
+    ) {
+        let limited = self.keyframe_request_max < usize::MAX;
+
+        while let Some(t) = self.keyframe_requests_sent.front() {
+            if !limited || *t + self.keyframe_request_window <= now {
+                self.keyframe_requests_sent.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let budget = self
+            .keyframe_request_max
+            .saturating_sub(self.keyframe_requests_sent.len());
+
+        // Stamp new requests also when out of budget, to know how long they waited.
+        let mut ready: Vec<_> = self
+            .streams_rx
+            .iter_mut()
+            .filter_map(|(ssrc, s)| {
+                let since = s.keyframe_request_since(now)?;
+                let at = s.keyframe_request_at()?;
+                Some((since, at, *ssrc))
+            })
+            .filter(|(_, at, _)| *at <= now)
+            .map(|(since, _, ssrc)| (since, ssrc))
+            .collect();
+
+        if budget == 0 {
+            return;
+        }
+
+        // The streams that waited the longest go first, held back requests stay pending
+        // in their StreamRx and are coalesced with later ones.
+
+        if ready.is_empty() {
+            return;
+        }
+
+        ready.sort();
+
+        for (_, ssrc) in ready.into_iter().take(budget) {
+            let Some(stream) = self.streams_rx.get_mut(&ssrc) else {
+                continue;
+            };
+            stream.maybe_create_keyframe_request(now, sender_ssrc, feedback);
+
+            if limited {
+                self.keyframe_requests_sent.push_back(now);
+            }
+        }
+    }
+
+    /// Makes all streams send SR/RR on the next handle_timeout. After that the
+    /// regular interval resumes.
+    pub(crate) fn schedule_immediate_feedback(&mut self) {
+        for s in self.streams_rx.values_mut() {
+            s.schedule_immediate_rr();
+        }
+        for s in self.streams_tx.values_mut() {
+            s.schedule_immediate_sr();
+        }
+    }
+
+    pub(crate) fn jitter_buffer_at(&self) -> Option<Instant> {
+        self.streams_rx
+            .values()
+            .filter_map(|s| s.jitter_buffer_at())
+            .min()
+    }
+
+    /// The least number of SRTP packets left for any outgoing SSRC.
+    pub(crate) fn srtp_packets_left(&self) -> u64 {
+        self.streams_tx
+            .values()
+            .map(|s| s.srtp_packets_left())
+            .min()
+            .unwrap_or(SRTP_MAX_PACKETS)
+    }
+
+    /// Drop media written before the connection is ready that is older than max_age.
+    pub(crate) fn drop_early_media(&mut self, now: Instant, max_age: Duration) {
+        for stream in self.streams_tx.values_mut() {
+            stream.drop_early_media(now, max_age);
+        }
     }
 
     pub(crate) fn paused_at(&self) -> Option<Instant> {
         self.streams_rx.values().find_map(|s| s.paused_at())
     }
 
+    pub(crate) fn inactive_at(&self) -> Option<Instant> {
+        self.streams_rx
+            .values()
+            .filter_map(|s| s.inactive_at())
+            .min()
+    }
+
     pub(crate) fn send_stream(&self) -> Option<Instant> {
         if self.streams_tx.values().any(|s| s.need_timeout()) {
             Some(already_happened())
@@ -412,10 +1012,14 @@ impl Streams {
             }
         }
 
+        self.maybe_create_keyframe_requests(now, sender_ssrc, feedback);
+
         for stream in self.streams_rx.values_mut() {
-            stream.maybe_create_keyframe_request(sender_ssrc, feedback);
             stream.maybe_create_remb_request(sender_ssrc, feedback);
 
+            let lntf = is_lntf_negotiated(stream.mid(), medias, config);
+            stream.maybe_create_loss_notification(sender_ssrc, lntf, feedback);
+
             // All StreamRx belonging to the same Mid are reported together.
             if self.mids_to_report.contains(&stream.mid()) {
                 stream.create_rr_and_update(now, sender_ssrc, feedback);
@@ -439,7 +1043,7 @@ impl Streams {
             let mid = stream.mid();
 
             // All StreamTx belonging to the same Mid are reported together.
-            if self.mids_to_report.contains(&mid) {
+            if self.mids_to_report.contains(&mid) && stream.sender_reports_enabled() {
                 stream.create_sr_and_update(now, feedback);
             }
 
@@ -454,6 +1058,7 @@ impl Streams {
         if now > self.rx_lookup_at() {
             self.rx_lookup
                 .retain(|_, l| now - l.last_used <= RX_LOOKUP_EXPIRY);
+            self.rejected_ssrcs_rx.clear();
         }
     }
 
@@ -468,6 +1073,19 @@ impl Streams {
         })
     }
 
+    pub(crate) fn poll_loss_notification(&mut self) -> Option<LossNotification> {
+        self.streams_tx.values_mut().find_map(|s| {
+            let (last_decoded, last_received, decodable) = s.poll_loss_notification()?;
+            Some(LossNotification {
+                mid: s.mid(),
+                rid: s.rid(),
+                last_decoded,
+                last_received,
+                decodable,
+            })
+        })
+    }
+
     pub(crate) fn poll_remb_request(&mut self) -> Option<(Mid, Bitrate)> {
         self.streams_tx
             .values_mut()
@@ -478,6 +1096,70 @@ impl Streams {
         self.streams_rx.values_mut().find_map(|s| s.poll_paused())
     }
 
+    pub(crate) fn poll_stream_activity(&mut self) -> Option<(bool, StreamActivity)> {
+        self.streams_rx.values_mut().find_map(|s| s.poll_activity())
+    }
+
+    pub(crate) fn poll_time_jump(&mut self) -> Option<RtpTimeJump> {
+        self.streams_rx
+            .values_mut()
+            .find_map(|s| s.poll_time_jump())
+    }
+
+    pub(crate) fn poll_packets_dropped(&mut self) -> Option<PacketsDropped> {
+        self.streams_tx
+            .values_mut()
+            .find_map(|s| s.poll_packets_dropped())
+    }
+
+    pub(crate) fn pacer_stats(&self, now: Instant) -> PacerStats {
+        let mut stats = PacerStats::default();
+        for s in self.streams_tx.values() {
+            s.visit_pacer_stats(&mut stats, now);
+        }
+        stats
+    }
+
+    pub(crate) fn overhead_stats(&self) -> OverheadStats {
+        let mut stats = OverheadStats::default();
+        for s in self.streams_tx.values() {
+            s.visit_overhead_stats(&mut stats);
+        }
+        stats
+    }
+
+    pub(crate) fn poll_stream_rejected(&mut self) -> Option<StreamRejected> {
+        self.streams_rejected.pop_front()
+    }
+
+    pub(crate) fn poll_ssrc_changed(&mut self) -> Option<StreamSsrcChanged> {
+        self.ssrcs_changed.pop_front()
+    }
+
+    pub(crate) fn poll_frame_boundary(&mut self) -> Option<FrameBoundary> {
+        self.streams_rx
+            .values_mut()
+            .find_map(|s| s.poll_frame_boundary())
+    }
+
+    pub(crate) fn poll_layer_active(&mut self) -> Option<LayerActive> {
+        self.streams_tx
+            .values_mut()
+            .find_map(|s| s.poll_layer_active())
+    }
+
+    pub(crate) fn poll_jitter_buffer_event(&mut self) -> Option<JitterBufferEvent> {
+        self.streams_rx
+            .values_mut()
+            .find_map(|s| s.poll_jitter_buffer_event())
+    }
+
+    pub(crate) fn poll_jitter_buffer(&mut self) -> Option<Result<RtpPacket, RtpPacketsLost>> {
+        self.streams_rx
+            .values_mut()
+            .find_map(|s| s.poll_jitter_buffer())
+    }
+
     pub(crate) fn has_stream_rx(&self, ssrc: Ssrc) -> bool {
         self.streams_rx.contains_key(&ssrc)
     }
@@ -490,6 +1172,39 @@ impl Streams {
         self.streams_rx.values_mut()
     }
 
+    pub(crate) fn sync_groups(&self) -> Vec<SyncGroup> {
+        let mut groups: Vec<SyncGroup> = vec![];
+
+        for s in self.streams_rx.values() {
+            let Some(cname) = s.cname() else {
+                continue;
+            };
+
+            let member = SyncMember {
+                ssrc: s.ssrc(),
+                mid: s.mid(),
+                rid: s.rid(),
+                sender_info: s.sender_info(),
+            };
+
+            if let Some(g) = groups.iter_mut().find(|g| g.cname == cname) {
+                g.members.push(member);
+            } else {
+                groups.push(SyncGroup {
+                    cname: cname.to_string(),
+                    members: vec![member],
+                });
+            }
+        }
+
+        for g in &mut groups {
+            g.members.sort_by_key(|m| m.ssrc);
+        }
+        groups.sort_by(|a, b| a.cname.cmp(&b.cname));
+
+        groups
+    }
+
     pub(crate) fn streams_tx(&mut self) -> impl Iterator<Item = &mut StreamTx> {
         self.streams_tx.values_mut()
     }
@@ -612,7 +1327,7 @@ impl Streams {
         }
     }
 
-    pub(crate) fn change_stream_rx_ssrc(&mut self, ssrc_from: Ssrc, ssrc_to: Ssrc) {
+    pub(crate) fn change_stream_rx_ssrc(&mut self, ssrc_from: Ssrc, ssrc_to: Ssrc) -> bool {
         // This unwrap is OK, because we can't call change_stream_rx_ssrc without first
         // knowing there is such a StreamRx.
         let maybe_change = self.streams_rx.get_mut(&ssrc_from).unwrap();
@@ -633,6 +1348,8 @@ impl Streams {
             self.rx_lookup
                 .retain(|k, l| *k != ssrc_from && l.main != ssrc_from);
         }
+
+        did_change
     }
 
     fn change_stream_rx_rtx(&mut self, rtx_from: Ssrc, rtx_to: Ssrc) {
@@ -684,3 +1401,18 @@ impl fmt::Debug for RtpPacket {
             .finish()
     }
 }
+
+/// Whether `goog-lntf` is negotiated for the PTs of the m-line. Without SDP (direct API)
+/// there are no remote PTs and the entire codec config is checked.
+fn is_lntf_negotiated(mid: Mid, medias: &[Media], config: &CodecConfig) -> bool {
+    let pts = medias
+        .iter()
+        .find(|m| m.mid() == mid)
+        .map(|m| m.remote_pts())
+        .unwrap_or(&[]);
+
+    config
+        .iter()
+        .filter(|p| pts.is_empty() || pts.contains(&p.pt()))
+        .any(|p| p.fb_lntf())
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use crate::media::MediaKind;
+
+/// Function estimating a MOS-like score from the measured network conditions.
+///
+/// The returned value is clamped to `1.0..=5.0`. See [`estimate_quality()`] for the default.
+pub type QualityEstimator = fn(MediaKind, &QualityInput) -> f32;
+
+/// Network conditions of a received stream used to estimate the quality.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityInput {
+    /// Fraction of packets lost, `0.0..=1.0`, as of the last sent receiver report.
+    pub loss: f32,
+
+    /// Estimated interarrival jitter.
+    pub jitter: Duration,
+
+    /// Round trip time, if known. This requires the sender to respond to extended reports.
+    pub rtt: Option<Duration>,
+}
+
+/// Estimated quality of a received stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityScore {
+    /// MOS-like score between 1.0 (bad) and 5.0 (excellent).
+    pub mos: f32,
+
+    /// The network conditions the score is estimated from.
+    pub input: QualityInput,
+}
+
+/// The default [`QualityEstimator`].
+///
+/// Uses [`audio_mos()`] for audio and [`video_mos()`] for video.
+pub fn estimate_quality(kind: MediaKind, input: &QualityInput) -> f32 {
+    match kind {
+        MediaKind::Audio => audio_mos(input),
+        MediaKind::Video => video_mos(input),
+    }
+}
+
+/// MOS for audio using a simplified ITU-T G.107 E-model.
+///
+/// ```text
+/// d  = rtt / 2 + 2 * jitter + 10ms
+/// Id = 0.024 * d + 0.11 * (d - 177.3) * H(d - 177.3)
+/// Ie = 95 * Ppl / (Ppl + 10)
+/// R  = 93.2 - Id - Ie
+/// MOS = 1 + 0.035 * R + 7e-6 * R * (R - 60) * (100 - R)
+/// ```
+///
+/// Where `d` is the one-way mouth-to-ear delay in milliseconds, `H` is the step function,
+/// and `Ppl` is the packet loss in percent. The codec is assumed to have no impairment
+/// of its own and a packet loss robustness of 10.
+///
+/// Without a known RTT, the delay impairment only accounts for the jitter.
+pub fn audio_mos(input: &QualityInput) -> f32 {
+    let rtt = input.rtt.unwrap_or(Duration::ZERO).as_secs_f32() * 1000.0;
+    let jitter = input.jitter.as_secs_f32() * 1000.0;
+
+    let d = rtt / 2.0 + 2.0 * jitter + 10.0;
+    let id = 0.024 * d + if d > 177.3 { 0.11 * (d - 177.3) } else { 0.0 };
+
+    let ppl = input.loss.clamp(0.0, 1.0) * 100.0;
+    let ie = 95.0 * ppl / (ppl + 10.0);
+
+    let r = 93.2 - id - ie;
+
+    let mos = if r <= 0.0 {
+        1.0
+    } else if r >= 100.0 {
+        4.5
+    } else {
+        1.0 + 0.035 * r + 7e-6 * r * (r - 60.0) * (100.0 - r)
+    };
+
+    mos.clamp(1.0, 5.0)
+}
+
+/// MOS-like score for video using a heuristic.
+///
+/// ```text
+/// MOS = 5 - 40 * loss - (jitter - 30ms) / 100ms - (rtt - 150ms) / 200ms
+/// ```
+///
+/// Where the jitter and RTT terms only apply above 30ms and 150ms respectively.
+/// Video degrades fast with loss, 10% loss alone gives a score of 1.
+pub fn video_mos(input: &QualityInput) -> f32 {
+    let rtt = input.rtt.unwrap_or(Duration::ZERO).as_secs_f32() * 1000.0;
+    let jitter = input.jitter.as_secs_f32() * 1000.0;
+
+    let loss_penalty = 40.0 * input.loss.clamp(0.0, 1.0);
+    let jitter_penalty = (jitter - 30.0).max(0.0) / 100.0;
+    let rtt_penalty = (rtt - 150.0).max(0.0) / 200.0;
+
+    (5.0 - loss_penalty - jitter_penalty - rtt_penalty).clamp(1.0, 5.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input(loss: f32, jitter_ms: u64, rtt_ms: Option<u64>) -> QualityInput {
+        QualityInput {
+            loss,
+            jitter: Duration::from_millis(jitter_ms),
+            rtt: rtt_ms.map(Duration::from_millis),
+        }
+    }
+
+    #[test]
+    fn audio_perfect() {
+        let mos = audio_mos(&input(0.0, 0, Some(0)));
+        assert!(mos > 4.3 && mos < 4.5, "{}", mos);
+    }
+
+    #[test]
+    fn audio_degrades() {
+        let good = audio_mos(&input(0.0, 5, Some(50)));
+        let lossy = audio_mos(&input(0.05, 5, Some(50)));
+        let delayed = audio_mos(&input(0.0, 5, Some(600)));
+        let bad = audio_mos(&input(0.3, 100, Some(1000)));
+
+        assert!(good > 4.0, "{}", good);
+        assert!(lossy < good);
+        assert!(delayed < good);
+        assert_eq!(bad, 1.0);
+    }
+
+    #[test]
+    fn video_degrades() {
+        assert_eq!(video_mos(&input(0.0, 10, Some(100))), 5.0);
+
+        let lossy = video_mos(&input(0.05, 10, Some(100)));
+        assert!((lossy - 3.0).abs() < 0.001, "{}", lossy);
+
+        let jittery = video_mos(&input(0.0, 130, None));
+        assert!((jittery - 4.0).abs() < 0.001, "{}", jittery);
+
+        assert_eq!(video_mos(&input(0.2, 10, Some(100))), 1.0);
+    }
+
+    #[test]
+    fn dispatch_on_kind() {
+        let i = input(0.05, 10, Some(100));
+        assert_eq!(estimate_quality(MediaKind::Audio, &i), audio_mos(&i));
+        assert_eq!(estimate_quality(MediaKind::Video, &i), video_mos(&i));
+    }
+}
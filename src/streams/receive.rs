@@ -1,20 +1,31 @@
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
-use crate::media::KeyframeRequestKind;
+use crate::io::Ecn;
+use crate::media::{KeyframeRequestKind, MediaKind};
 use crate::rtp_::{
     extend_u32, Bitrate, DlrrItem, ExtendedReport, Fir, FirEntry, Frequency, MediaTime, Remb,
 };
+use crate::rtp_::{EcnFeedback, ExtensionMap, Lntf, SdesType, Ssrc, LNTF_MAX_DELTA};
+use crate::rtp_::{JitterBufferMetrics, MetricInterval};
 use crate::rtp_::{Mid, Pli, Pt, ReceiverReport};
 use crate::rtp_::{ReportBlock, ReportList, Rid, Rrtr, Rtcp, RtcpFb, RtpHeader, SenderInfo, SeqNo};
-use crate::rtp_::{SdesType, Ssrc};
 use crate::stats::{MediaIngressStats, StatsSnapshot};
 use crate::util::InstantExt;
-use crate::util::{already_happened, calculate_rtt_ms};
+use crate::util::{already_happened, calculate_rtt_ms, not_happening};
 
+use super::clock_skew::ClockSkew;
+use super::jitter_buffer::{JitterBuffer, Released};
+use super::quality::{estimate_quality, QualityEstimator, QualityInput, QualityScore};
 use super::register::ReceiverRegister;
-use super::StreamPaused;
+use super::register_nack::{MAX_MISORDER, MAX_NACK_WINDOW};
 use super::{rr_interval, RtpPacket};
+use super::{ExtensionStats, FrameBoundary, FrameBoundaryKind, ReorderStats};
+use super::{JitterBufferEvent, JitterBufferEventKind};
+use super::{RtpPacketsLost, RtpTimeJump, SrInfo, StreamActivity, StreamPaused};
+
+/// Default max deviation of RTP time from receive time between two packets.
+const DEFAULT_MAX_TIME_JUMP: Duration = Duration::from_secs(10);
 
 /// Incoming encoded stream.
 ///
@@ -52,6 +63,9 @@ pub struct StreamRx {
     /// Defaults to false.
     suppress_nack: bool,
 
+    /// Number of packets behind the highest received sequence number we NACK for.
+    nack_window: u64,
+
     /// Timestamp when we got some indication of remote using this stream.
     last_used: Instant,
 
@@ -61,6 +75,12 @@ pub struct StreamRx {
     /// Last received sender info.
     sender_info: Option<(Instant, SenderInfo)>,
 
+    /// Skew of the sender RTP clock, from the NTP/RTP pairs in SR.
+    clock_skew: ClockSkew,
+
+    /// One-way delay of the last received packet, from abs-send-time.
+    one_way_delay: Option<Duration>,
+
     /// ROC to reset with on next incoming packet.
     reset_roc: Option<u64>,
 
@@ -74,15 +94,39 @@ pub struct StreamRx {
     /// Set on first ever RTXpacket.
     register_rtx: Option<ReceiverRegister>,
 
-    /// Last observed media time in an RTP packet.
-    last_time: Option<MediaTime>,
+    /// Last observed media time in an RTP packet, and when the packet was received.
+    last_time: Option<(MediaTime, Instant)>,
+
+    /// Max deviation of the media time from the receive time between two packets.
+    max_time_jump: Option<Duration>,
+
+    /// RTX PT per main PT, as seen in incoming resends.
+    rtx_pt_map: Vec<(Pt, Pt)>,
+
+    /// Added to incoming RTP timestamps to resync after a time jump.
+    time_offset: u32,
+
+    /// Time jump to emit as event.
+    pending_time_jump: Option<RtpTimeJump>,
 
     /// If we have a pending keyframe request to send.
     pending_request_keyframe: Option<KeyframeRequestKind>,
 
+    /// When the pending keyframe request was first seen by a timeout.
+    keyframe_request_since: Option<Instant>,
+
+    /// Min interval between keyframe requests sent to the remote.
+    keyframe_request_interval: Duration,
+
+    /// When we last sent a keyframe request.
+    last_keyframe_request: Option<Instant>,
+
     /// If we have a pending REMB request to send.
     pending_request_remb: Option<Bitrate>,
 
+    /// Loss notification to send (last decoded, last received, decodable).
+    pending_loss_notification: Option<(SeqNo, SeqNo, bool)>,
+
     /// Sequence number of the next FIR.
     fir_seq_no: u8,
 
@@ -108,6 +152,45 @@ pub struct StreamRx {
 
     /// The configured threshold before considering the lack of packets as going into paused.
     pause_threshold: Duration,
+
+    /// When we need to evaluate the active state, if activity tracking is enabled.
+    ///
+    /// now + inactive_timeout
+    check_inactive_at: Option<Instant>,
+
+    /// Whether the stream is receiving packets.
+    active: bool,
+
+    /// Whether we need to emit an active/inactive event for the current state.
+    need_activity_event: bool,
+
+    /// Duration without packets after which the stream is inactive. `None` disables
+    /// activity tracking.
+    inactive_timeout: Option<Duration>,
+
+    /// Jitter buffer for RTP mode. Disabled unless a target delay is set.
+    jitter_buffer: JitterBuffer,
+
+    /// Whether to emit jitter buffer underrun/overrun events.
+    jitter_buffer_events: bool,
+
+    /// Jitter buffer events waiting to be polled.
+    pending_jitter_buffer_events: VecDeque<JitterBufferEvent>,
+
+    /// Estimator used by [`StreamRx::quality()`].
+    quality_estimator: QualityEstimator,
+
+    /// Whether to emit frame boundary events.
+    frame_boundary_events: bool,
+
+    /// Sequence number and RTP time of the last packet in sequence order.
+    last_frame_packet: Option<(SeqNo, u64)>,
+
+    /// Frame boundaries waiting to be polled.
+    frame_boundaries: VecDeque<FrameBoundary>,
+
+    /// Cumulative counts of the ECN codepoints of received packets. Sent with the RR.
+    ecn: Option<EcnFeedback>,
 }
 
 /// Holder of stats.
@@ -123,10 +206,20 @@ pub(crate) struct StreamRxStats {
     plis: u64,
     /// count of NACKs sent
     nacks: u64,
+    /// count of keyframe requests coalesced with another request
+    keyframe_requests_suppressed: u64,
+    /// count of packets recovered via RTX
+    rtx_recovered: u64,
+    /// count of packets missing at playout time in the jitter buffer
+    jitter_buffer_underruns: u64,
+    /// count of packets dropped due to the jitter buffer being full
+    jitter_buffer_overruns: u64,
     /// round trip time (ms) from the last DLRR, if any
     rtt: Option<f32>,
     /// fraction of packets lost from the last RR, if any
     loss: Option<f32>,
+    /// presence of header extensions in received packets
+    extensions: ExtensionStats,
 }
 
 impl StreamRx {
@@ -141,15 +234,26 @@ impl StreamRx {
             rid,
             cname: None,
             suppress_nack,
+            nack_window: MAX_MISORDER,
             last_used: already_happened(),
             last_clock_rate: None,
             sender_info: None,
+            clock_skew: ClockSkew::default(),
+            one_way_delay: None,
             reset_roc: None,
             register: None,
             register_rtx: None,
             last_time: None,
+            max_time_jump: Some(DEFAULT_MAX_TIME_JUMP),
+            rtx_pt_map: vec![],
+            time_offset: 0,
+            pending_time_jump: None,
             pending_request_keyframe: None,
+            keyframe_request_since: None,
+            keyframe_request_interval: Duration::ZERO,
+            last_keyframe_request: None,
             pending_request_remb: None,
+            pending_loss_notification: None,
             fir_seq_no: 0,
             last_receiver_report: already_happened(),
             stats: StreamRxStats::default(),
@@ -157,6 +261,18 @@ impl StreamRx {
             paused: true,
             need_paused_event: false,
             pause_threshold: Duration::from_millis(1500),
+            check_inactive_at: None,
+            active: false,
+            need_activity_event: false,
+            inactive_timeout: None,
+            jitter_buffer: JitterBuffer::default(),
+            jitter_buffer_events: false,
+            pending_jitter_buffer_events: VecDeque::new(),
+            quality_estimator: estimate_quality,
+            frame_boundary_events: false,
+            last_frame_packet: None,
+            frame_boundaries: VecDeque::new(),
+            ecn: None,
         }
     }
 
@@ -170,6 +286,16 @@ impl StreamRx {
         self.rtx
     }
 
+    /// The RTX PT seen in resends of packets with the main PT `pt`.
+    ///
+    /// This is `None` until a resend with that main PT has been received.
+    pub fn rtx_pt(&self, pt: Pt) -> Option<Pt> {
+        self.rtx_pt_map
+            .iter()
+            .find(|(p, _)| *p == pt)
+            .map(|(_, rtx)| *rtx)
+    }
+
     /// Mid for this stream.
     ///
     /// In SDP this corresponds to m-line and "Media".
@@ -191,21 +317,297 @@ impl StreamRx {
         self.cname.as_deref()
     }
 
+    pub(crate) fn sender_info(&self) -> Option<SenderInfo> {
+        self.sender_info.map(|(_, s)| s)
+    }
+
+    /// The NTP and RTP timestamps of the last received sender report (SR).
+    ///
+    /// None until a SR is received. This is the raw data for custom synchronization of
+    /// streams, or for debugging the mapping of the media clock.
+    pub fn last_sender_report(&self) -> Option<SrInfo> {
+        self.sender_info.map(|(received, s)| SrInfo {
+            received,
+            ntp_time: s.ntp_time,
+            rtp_time: s.rtp_time,
+        })
+    }
+
+    /// Estimated skew of the sender clock in parts per million.
+    ///
+    /// This is derived from the NTP/RTP timestamp pairs of the received sender reports (SR)
+    /// and tells how much faster (positive) or slower (negative) the RTP clock runs compared
+    /// to the sender wall clock. Recorders can use it to resample and avoid A/V drift over
+    /// long sessions.
+    ///
+    /// None until enough SRs have been received.
+    pub fn clock_skew_ppm(&self) -> Option<f64> {
+        self.clock_skew.ppm()
+    }
+
+    /// Estimated one-way delay from the sender to us.
+    ///
+    /// This is the time between the abs-send-time of the last received packet, and when
+    /// it was received. The send time is the sender's wall clock, which means the delay
+    /// is only correct when the clocks of both ends are synchronized (e.g. using NTP).
+    /// Any clock offset ends up in the delay. Unlike RTT/2, it reflects asymmetric paths.
+    /// If the sender's clock is ahead so that the packet appears sent after it was
+    /// received, the delay is zero.
+    ///
+    /// None if the last received packet didn't have the abs-send-time extension.
+    pub fn one_way_delay(&self) -> Option<Duration> {
+        self.one_way_delay
+    }
+
+    /// How often each RTP header extension was present in the received packets.
+    ///
+    /// Counts every packet received for the stream, including retransmissions, and is
+    /// cleared by [`StreamRx::reset_stats()`]. Useful to find out whether the remote peer
+    /// actually sends the negotiated extensions.
+    ///
+    /// ```no_run
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::{Extension, Ssrc};
+    /// # let mut rtc = Rtc::new();
+    /// # let ssrc: Ssrc = 42.into();
+    /// let mut api = rtc.direct_api();
+    /// let stats = api.stream_rx(&ssrc).unwrap().extension_stats();
+    ///
+    /// if stats.count(&Extension::AbsoluteSendTime) == 0 {
+    ///     println!("No abs-send-time in {} packets", stats.packets);
+    /// }
+    /// ```
+    pub fn extension_stats(&self) -> &ExtensionStats {
+        &self.stats.extensions
+    }
+
+    /// How many packets arrived out of order, how far, and how many were duplicates.
+    ///
+    /// Counts the packets received since the stream was created, or since
+    /// [`StreamRx::reset_stats()`]. Useful to tune the NACK window and the jitter buffer
+    /// to the network, see [`StreamRx::set_nack_window()`] and
+    /// [`StreamRx::set_target_delay()`].
+    pub fn reorder_stats(&self) -> ReorderStats {
+        self.register
+            .as_ref()
+            .map(|r| r.reorder_stats())
+            .unwrap_or_default()
+    }
+
+    /// Set the target playout delay of the jitter buffer.
+    ///
+    /// This is only relevant in RTP mode. Incoming packets are held for the delay, reordered,
+    /// and emitted in sequence number order as [`Event::RtpPacket`][crate::Event::RtpPacket].
+    /// Packets still missing when the delay has passed are declared lost using
+    /// [`Event::RtpPacketsLost`][crate::Event::RtpPacketsLost]. A longer delay gives more
+    /// time for reordered packets and resends to arrive, at the cost of latency.
+    ///
+    /// The default is zero, which disables the jitter buffer.
+    pub fn set_target_delay(&mut self, delay: Duration) {
+        self.jitter_buffer.set_target_delay(delay);
+    }
+
+    /// Set whether the jitter buffer adapts the delay to the measured jitter.
+    ///
+    /// When adaptive, the delay follows the measured network jitter, but never goes below
+    /// the target delay set with [`StreamRx::set_target_delay()`].
+    ///
+    /// The default is false.
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.jitter_buffer.set_adaptive(adaptive);
+    }
+
+    /// The current playout delay of the jitter buffer.
+    ///
+    /// This is the target delay, or the adapted delay if the jitter buffer is adaptive.
+    /// The delay is also reported to the remote sender in RTCP XR (RFC 7005).
+    ///
+    /// Returns `None` if the jitter buffer is disabled.
+    pub fn jitter_buffer_delay(&self) -> Option<Duration> {
+        self.jitter_buffer
+            .is_enabled()
+            .then(|| self.jitter_buffer.delay())
+    }
+
+    /// Set the max number of packets the jitter buffer holds.
+    ///
+    /// When a packet arrives to a full jitter buffer, the oldest packets are dropped and
+    /// declared lost using [`Event::RtpPacketsLost`][crate::Event::RtpPacketsLost]. This
+    /// bounds the memory use if the remote peer sends faster than the delay allows.
+    ///
+    /// The default is no limit.
+    ///
+    /// Panics if `max` is zero.
+    pub fn set_max_held_packets(&mut self, max: Option<usize>) {
+        assert!(max != Some(0), "max held packets must be above zero");
+        self.jitter_buffer.set_max_packets(max);
+    }
+
+    /// Set whether to emit [`Event::JitterBuffer`][crate::Event::JitterBuffer].
+    ///
+    /// The events tell when the jitter buffer underruns (packets missing at playout time)
+    /// or overruns (packets dropped due to [`StreamRx::set_max_held_packets()`]). This is
+    /// useful to adapt the target delay. The counts are also in the
+    /// [`MediaIngressStats`][crate::stats::MediaIngressStats].
+    ///
+    /// The default is false.
+    pub fn set_jitter_buffer_events(&mut self, enabled: bool) {
+        self.jitter_buffer_events = enabled;
+        self.pending_jitter_buffer_events.clear();
+    }
+
+    /// Set whether to emit [`Event::FrameBoundary`][crate::Event::FrameBoundary].
+    ///
+    /// This detects frames from the RTP timestamp and marker bit of incoming packets, for
+    /// apps that want to know the frame boundaries without depacketizing.
+    ///
+    /// The default is false.
+    pub fn set_frame_boundary_events(&mut self, enabled: bool) {
+        self.frame_boundary_events = enabled;
+        self.last_frame_packet = None;
+        self.frame_boundaries.clear();
+    }
+
+    /// Estimated quality of the stream as a MOS-like score.
+    ///
+    /// Combines the packet loss, jitter and RTT into a single number between 1.0 (bad)
+    /// and 5.0 (excellent), useful for UI indicators. The default estimator uses the
+    /// E-model for audio and a heuristic for video, see [`estimate_quality()`].
+    ///
+    /// The stream is considered video if the last received packet has a 90kHz clock rate.
+    ///
+    /// [`estimate_quality()`]: crate::rtp::estimate_quality
+    pub fn quality(&self) -> QualityScore {
+        let input = QualityInput {
+            loss: self.stats.loss.unwrap_or(0.0),
+            jitter: self
+                .register
+                .as_ref()
+                .map(|r| r.jitter())
+                .unwrap_or(Duration::ZERO),
+            rtt: self
+                .stats
+                .rtt
+                .map(|ms| Duration::from_secs_f32(ms.max(0.0) / 1000.0)),
+        };
+
+        let is_video = self.last_clock_rate.map(|(_, r)| r) == Some(Frequency::NINETY_KHZ);
+        let kind = if is_video {
+            MediaKind::Video
+        } else {
+            MediaKind::Audio
+        };
+
+        let mos = (self.quality_estimator)(kind, &input).clamp(1.0, 5.0);
+
+        QualityScore { mos, input }
+    }
+
+    /// Set the estimator used by [`StreamRx::quality()`].
+    ///
+    /// Defaults to [`estimate_quality()`].
+    ///
+    /// [`estimate_quality()`]: crate::rtp::estimate_quality
+    pub fn set_quality_estimator(&mut self, estimator: QualityEstimator) {
+        self.quality_estimator = estimator;
+    }
+
+    /// Reset the cumulative counters reported in [`MediaIngressStats`].
+    ///
+    /// This zeroes the bytes, packets, FIR, PLI, NACK, RTX recovered and reorder counters, which is
+    /// useful to compute per-interval values. The last measured RTT and loss are kept, since
+    /// they are not cumulative. Until more data is received, no stats are reported for
+    /// the stream.
+    ///
+    /// Protocol state is not affected. This includes the extended sequence numbers, the ROC,
+    /// the SRTP context, and the packet loss and jitter sent to the remote peer in receiver
+    /// reports.
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+        if let Some(r) = &mut self.register {
+            r.reset_reorder_stats();
+        }
+    }
+
     /// Set threshold duration for emitting the paused event.
     ///
-    /// This event is emitted when no packet have received for this duration.
+    /// This event is emitted when no packet have received for this duration. When packets
+    /// resume, the stream is unpaused straight away.
+    ///
+    /// Defaults to 1.5 seconds.
+    ///
+    /// See [`StreamPaused`][crate::rtp::StreamPaused].
     pub fn set_pause_threshold(&mut self, t: Duration) {
         self.pause_threshold = t;
     }
 
+    /// Enable activity tracking with the duration without packets after which the stream
+    /// is inactive.
+    ///
+    /// [`Event::StreamActive`][crate::Event::StreamActive] is emitted on the next packet,
+    /// and again every time packets resume after the stream went
+    /// [`Event::StreamInactive`][crate::Event::StreamInactive].
+    ///
+    /// Disabled by default.
+    pub fn set_inactive_timeout(&mut self, t: Duration) {
+        self.inactive_timeout = Some(t);
+
+        // Reschedule an ongoing check for the new timeout.
+        if self.active {
+            self.check_inactive_at = Some(self.last_used + t);
+        }
+    }
+
+    /// Set the max plausible jump of the RTP timestamps of incoming packets.
+    ///
+    /// Between two packets, the RTP timestamp is expected to move as much as the time
+    /// passed between receiving them. When it deviates more than this, the sender is
+    /// considered broken (or malicious). Instead of following the jump, the media time
+    /// is resynced to the receive time, and later packets continue from there. This is
+    /// signaled with [`Event::RtpTimeJump`][crate::Event::RtpTimeJump].
+    ///
+    /// `None` disables the check. Defaults to 10 seconds.
+    pub fn set_max_time_jump(&mut self, max: Option<Duration>) {
+        self.max_time_jump = max;
+    }
+
     /// Request a keyframe for an incoming encoded stream.
     ///
     /// * SSRC the identifier of the remote encoded stream to request a keyframe for.
     /// * kind PLI or FIR.
+    ///
+    /// Requests made before a pending request is sent are coalesced with it, see
+    /// [`StreamRx::set_keyframe_request_interval()`].
     pub fn request_keyframe(&mut self, kind: KeyframeRequestKind) {
+        let kind = match self.pending_request_keyframe {
+            Some(pending) => {
+                self.stats.keyframe_requests_suppressed += 1;
+
+                // A FIR is the stronger request.
+                if pending == KeyframeRequestKind::Fir {
+                    pending
+                } else {
+                    kind
+                }
+            }
+            None => kind,
+        };
+
         self.pending_request_keyframe = Some(kind);
     }
 
+    /// Set the min interval between keyframe requests sent to the remote.
+    ///
+    /// In an SFU, many viewers requesting keyframes at once would otherwise cause a storm of
+    /// PLI/FIR to the sender. Requests within the interval after the last sent request are
+    /// held back and coalesced into one request, which is sent when the interval has passed.
+    /// The number of coalesced requests is counted in [`MediaIngressStats`].
+    ///
+    /// The default is zero, which only coalesces requests made between two timeouts.
+    pub fn set_keyframe_request_interval(&mut self, interval: Duration) {
+        self.keyframe_request_interval = interval;
+    }
+
     /// Request max recv bitrate for an incoming encoded stream.
     ///
     /// * bitrate Bitrate.
@@ -213,6 +615,35 @@ impl StreamRx {
         self.pending_request_remb = Some(bitrate);
     }
 
+    /// Send a loss notification (`goog-lntf`) for an incoming encoded stream.
+    ///
+    /// * `last_decoded` sequence number of the last packet of the last decoded frame.
+    /// * `last_received` sequence number of the last received packet.
+    /// * `decodable` whether the frame of `last_received` can be decoded.
+    ///
+    /// This lets the remote encoder recover from the loss without a keyframe. The
+    /// notification is sent straight away, a later request replaces a pending one. It's
+    /// dropped unless `goog-lntf` is negotiated, see
+    /// [`PayloadParams::set_fb_lntf()`][crate::format::PayloadParams::set_fb_lntf].
+    /// `last_received` can be at most 32767 packets after `last_decoded`.
+    pub fn request_loss_notification(
+        &mut self,
+        last_decoded: SeqNo,
+        last_received: SeqNo,
+        decodable: bool,
+    ) {
+        if *last_received < *last_decoded || *last_received - *last_decoded > LNTF_MAX_DELTA as u64
+        {
+            warn!(
+                "Ignore loss notification, last received {} not within {} after last decoded {}",
+                last_received, LNTF_MAX_DELTA, last_decoded
+            );
+            return;
+        }
+
+        self.pending_loss_notification = Some((last_decoded, last_received, decodable));
+    }
+
     /// Suppress NACK sending.
     ///
     /// Normally NACK is disabled by not having an RTX SSRC set. In some situations it might be
@@ -221,6 +652,24 @@ impl StreamRx {
         self.suppress_nack = suppress;
     }
 
+    /// Set how many packets behind the highest received sequence number are NACKed.
+    ///
+    /// Gaps older than the window are considered permanently lost. They count as lost in
+    /// the stats, but are never NACKed. This bounds the memory used per stream and avoids
+    /// requesting ancient packets after a long outage.
+    ///
+    /// Defaults to 100. Values over 32768 (half the sequence number space) are clamped.
+    pub fn set_nack_window(&mut self, packets: usize) {
+        self.nack_window = (packets as u64).min(MAX_NACK_WINDOW);
+
+        if let Some(r) = &mut self.register {
+            r.set_nack_window(self.nack_window);
+        }
+        if let Some(r) = &mut self.register_rtx {
+            r.set_nack_window(self.nack_window);
+        }
+    }
+
     pub(crate) fn receiver_report_at(&self) -> Instant {
         let is_audio = self.rtx.is_none(); // this is maybe not correct, but it's all we got.
         self.last_receiver_report + rr_interval(is_audio)
@@ -266,7 +715,8 @@ impl StreamRx {
         // Extend the incoming time given our knowledge of last time.
         let extended = {
             let prev = self.sender_info.map(|(_, sr)| sr.rtp_time.numer());
-            let r_u32 = info.rtp_time.numer() as u32;
+            // Same offset as the RTP packets after a time jump.
+            let r_u32 = (info.rtp_time.numer() as u32).wrapping_add(self.time_offset);
             extend_u32(prev, r_u32)
         };
 
@@ -280,6 +730,11 @@ impl StreamRx {
         // Clock rate is that of the last received packet.
         info.rtp_time = MediaTime::new(extended, clock_rate);
 
+        // Without a known clock rate, the skew would be meaningless.
+        if self.last_clock_rate.is_some() {
+            self.clock_skew.push(info.ntp_time, info.rtp_time);
+        }
+
         self.sender_info = Some((now, info));
     }
 
@@ -293,7 +748,86 @@ impl StreamRx {
         self.check_paused_at
     }
 
+    pub(crate) fn inactive_at(&self) -> Option<Instant> {
+        self.check_inactive_at
+    }
+
+    pub(crate) fn jitter_buffer_at(&self) -> Option<Instant> {
+        self.jitter_buffer.poll_timeout()
+    }
+
+    /// Hold the packet in the jitter buffer, if enabled. Otherwise gives the packet back.
+    ///
+    /// Padding only packets, such as BWE probes, have an empty payload after unpadding. They
+    /// are not media, and are not released. The jitter buffer still gets them, since they
+    /// are part of the sequence number series.
+    pub(crate) fn buffer_packet(&mut self, packet: RtpPacket) -> Option<RtpPacket> {
+        if !self.jitter_buffer.is_enabled() {
+            return (!packet.payload.is_empty()).then_some(packet);
+        }
+        let dropped = self.jitter_buffer.push(packet);
+        self.jitter_buffer_event(JitterBufferEventKind::Overrun, dropped);
+        None
+    }
+
+    fn jitter_buffer_event(&mut self, kind: JitterBufferEventKind, packets: u64) {
+        if packets == 0 {
+            return;
+        }
+
+        match kind {
+            JitterBufferEventKind::Underrun => self.stats.jitter_buffer_underruns += packets,
+            JitterBufferEventKind::Overrun => self.stats.jitter_buffer_overruns += packets,
+        }
+
+        if self.jitter_buffer_events {
+            self.pending_jitter_buffer_events
+                .push_back(JitterBufferEvent {
+                    ssrc: self.ssrc,
+                    mid: self.mid,
+                    rid: self.rid,
+                    kind,
+                    packets,
+                    delay: self.jitter_buffer.delay(),
+                });
+        }
+    }
+
+    pub(crate) fn poll_jitter_buffer_event(&mut self) -> Option<JitterBufferEvent> {
+        self.pending_jitter_buffer_events.pop_front()
+    }
+
+    pub(crate) fn poll_jitter_buffer(&mut self) -> Option<Result<RtpPacket, RtpPacketsLost>> {
+        let released = loop {
+            match self.jitter_buffer.poll_released()? {
+                Released::Packet(p) if p.payload.is_empty() => {
+                    trace!("Skip padding only packet: {}", p.seq_no);
+                }
+                r => break r,
+            }
+        };
+
+        Some(match released {
+            Released::Packet(p) => Ok(p),
+            Released::Lost(seq_range) => Err(RtpPacketsLost {
+                ssrc: self.ssrc,
+                mid: self.mid,
+                rid: self.rid,
+                seq_range,
+            }),
+        })
+    }
+
     pub(crate) fn handle_timeout(&mut self, now: Instant) {
+        let missing = self.jitter_buffer.handle_timeout(now);
+        self.jitter_buffer_event(JitterBufferEventKind::Underrun, missing);
+
+        if self.check_inactive_at.map(|t| now >= t).unwrap_or(false) {
+            self.check_inactive_at = None;
+            self.active = false;
+            self.need_activity_event = true;
+        }
+
         // No scheduled paused check?
         if self.check_paused_at.is_none() {
             return;
@@ -321,7 +855,9 @@ impl StreamRx {
             &mut self.register
         };
 
-        let register = register_ref.get_or_insert_with(ReceiverRegister::new);
+        let nack_window = self.nack_window;
+        let register =
+            register_ref.get_or_insert_with(|| ReceiverRegister::with_nack_window(nack_window));
 
         // If the user has called `reset_seq_no`, this is the time to handle it, but only
         // if the incoming packet is for main (not repair).
@@ -340,12 +876,30 @@ impl StreamRx {
         }
     }
 
+    /// The extended sequence number [`StreamRx::extend_seq`] would give a main packet,
+    /// without registering it.
+    pub(crate) fn peek_seq(&self, header: &RtpHeader) -> SeqNo {
+        if let Some(reset_roc) = self.reset_roc {
+            return (reset_roc << 16 | header.sequence_number as u64).into();
+        }
+        header.sequence_number(self.register.as_ref().and_then(|r| r.max_seq()))
+    }
+
+    pub(crate) fn update_extension_stats(
+        &mut self,
+        exts: &ExtensionMap,
+        ids: impl Iterator<Item = u8>,
+    ) {
+        self.stats.extensions.update(exts, ids);
+    }
+
     pub(crate) fn update_register(
         &mut self,
         now: Instant,
         header: &RtpHeader,
         clock_rate: Frequency,
         is_repair: bool,
+        is_resend: bool,
         seq_no: SeqNo,
     ) -> RegisterUpdateReceipt {
         self.last_used = now;
@@ -356,6 +910,27 @@ impl StreamRx {
         }
         self.check_paused_at = Some(now + self.pause_threshold);
 
+        if let Some(timeout) = self.inactive_timeout {
+            if !self.active {
+                self.active = true;
+                self.need_activity_event = true;
+            }
+            self.check_inactive_at = Some(now + timeout);
+        }
+
+        let previous_time = self.last_time.map(|(t, _)| t.numer());
+        let timestamp = header.timestamp.wrapping_add(self.time_offset);
+        let mut time = MediaTime::new(extend_u32(previous_time, timestamp), clock_rate);
+
+        // Resends carry the media time of when they were first sent, which would look
+        // like a jump back, and then forward again with the next packet.
+        if !is_repair && !is_resend {
+            if let Some(resynced) = self.check_time_jump(now, time, header.timestamp) {
+                time = resynced;
+            }
+            self.last_time = Some((time, now));
+        }
+
         let register_ref = if is_repair {
             &mut self.register_rtx
         } else {
@@ -365,15 +940,13 @@ impl StreamRx {
         // Unwrap is OK because we always call extend_seq() for the same is_repair flag beforehand
         let register = register_ref.as_mut().unwrap();
 
-        let is_new_packet = register.update(seq_no, now, header.timestamp, clock_rate.get());
-
-        let previous_time = self.last_time.map(|t| t.numer());
-        let time_u32 = extend_u32(previous_time, header.timestamp);
-        let time = MediaTime::new(time_u32, clock_rate);
-
-        if !is_repair {
-            self.last_time = Some(time);
-        }
+        // The resynced time, to not have a time jump show up as jitter.
+        let rtp_time = time.numer() as u32;
+        let is_new_packet = if is_resend {
+            register.update_resend(seq_no, now, rtp_time, clock_rate.get())
+        } else {
+            register.update(seq_no, now, rtp_time, clock_rate.get())
+        };
 
         RegisterUpdateReceipt {
             time,
@@ -381,6 +954,53 @@ impl StreamRx {
         }
     }
 
+    /// Resynced media time, if the time jumped more than max_time_jump.
+    fn check_time_jump(
+        &mut self,
+        now: Instant,
+        time: MediaTime,
+        timestamp: u32,
+    ) -> Option<MediaTime> {
+        let max = self.max_time_jump?;
+        let (last, last_at) = self.last_time?;
+
+        // Times with different clock rates are not comparable.
+        if last.frequency() != time.frequency() {
+            return None;
+        }
+
+        let rate = time.frequency().get() as f64;
+        let media = (time.numer() as i128 - last.numer() as i128) as f64 / rate;
+        let elapsed = now.saturating_duration_since(last_at).as_secs_f64();
+
+        if (media - elapsed).abs() <= max.as_secs_f64() {
+            return None;
+        }
+
+        let resynced = MediaTime::new(last.numer() + (elapsed * rate) as u64, time.frequency());
+        self.time_offset = (resynced.numer() as u32).wrapping_sub(timestamp);
+
+        warn!(
+            "RTP time jump for SSRC {}: {:.1}s in {:.1}s, resync",
+            self.ssrc, media, elapsed
+        );
+
+        self.pending_time_jump = Some(RtpTimeJump {
+            ssrc: self.ssrc,
+            mid: self.mid,
+            rid: self.rid,
+            previous: last,
+            timestamp,
+            time: resynced,
+        });
+
+        Some(resynced)
+    }
+
+    pub(crate) fn poll_time_jump(&mut self) -> Option<RtpTimeJump> {
+        self.pending_time_jump.take()
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn handle_rtp(
         &mut self,
@@ -402,6 +1022,16 @@ impl StreamRx {
             }
         }
 
+        if self.frame_boundary_events {
+            self.update_frame_boundary(&header, seq_no, time);
+        }
+
+        // abs-send-time is already rebased to an Instant before now.
+        self.one_way_delay = header
+            .ext_vals
+            .abs_send_time
+            .map(|sent| one_way_delay(now, sent));
+
         let packet = RtpPacket {
             seq_no,
             time,
@@ -418,7 +1048,57 @@ impl StreamRx {
         packet
     }
 
-    pub(crate) fn un_rtx(&self, header: &mut RtpHeader, data: &mut Vec<u8>, pt: Pt) {
+    fn update_frame_boundary(&mut self, header: &RtpHeader, seq_no: SeqNo, time: MediaTime) {
+        let last = self.last_frame_packet;
+
+        // Out of order packets, including resends, are not frame boundaries.
+        if let Some((last_seq_no, _)) = last {
+            if seq_no <= last_seq_no {
+                return;
+            }
+        }
+
+        self.last_frame_packet = Some((seq_no, time.numer()));
+
+        let gap = last.map(|(s, _)| *seq_no > *s + 1).unwrap_or(false);
+        let is_start = last.map(|(_, t)| t != time.numer()).unwrap_or(true);
+
+        let mut push = |kind, gap| {
+            self.frame_boundaries.push_back(FrameBoundary {
+                ssrc: self.ssrc,
+                mid: self.mid,
+                rid: self.rid,
+                kind,
+                time,
+                seq_no,
+                gap,
+            });
+        };
+
+        if is_start {
+            push(FrameBoundaryKind::Start, gap);
+        }
+
+        if header.marker {
+            // A gap before the start is already reported.
+            push(FrameBoundaryKind::End, gap && !is_start);
+        }
+    }
+
+    pub(crate) fn poll_frame_boundary(&mut self) -> Option<FrameBoundary> {
+        self.frame_boundaries.pop_front()
+    }
+
+    pub(crate) fn register_rtx_recovered(&mut self) {
+        self.stats.rtx_recovered += 1;
+    }
+
+    pub(crate) fn un_rtx(&mut self, header: &mut RtpHeader, data: &mut Vec<u8>, pt: Pt) {
+        if self.rtx_pt(pt) != Some(header.payload_type) {
+            self.rtx_pt_map.retain(|(p, _)| *p != pt);
+            self.rtx_pt_map.push((pt, header.payload_type));
+        }
+
         let mut orig_seq_no_16 = 0;
 
         let n = RtpHeader::read_original_sequence_number(data, &mut orig_seq_no_16);
@@ -437,15 +1117,41 @@ impl StreamRx {
         header.ext_vals.rid = header.ext_vals.rid_repair.take();
     }
 
+    pub(crate) fn keyframe_request_at(&self) -> Option<Instant> {
+        self.pending_request_keyframe?;
+
+        let at = self
+            .last_keyframe_request
+            .map(|t| t + self.keyframe_request_interval)
+            .unwrap_or_else(already_happened);
+
+        Some(at)
+    }
+
+    /// When the pending keyframe request started waiting, `now` if seen for the first time.
+    pub(crate) fn keyframe_request_since(&mut self, now: Instant) -> Option<Instant> {
+        self.pending_request_keyframe?;
+
+        Some(*self.keyframe_request_since.get_or_insert(now))
+    }
+
     pub(crate) fn maybe_create_keyframe_request(
         &mut self,
+        now: Instant,
         sender_ssrc: Ssrc,
         feedback: &mut VecDeque<Rtcp>,
     ) {
+        if now < self.keyframe_request_at().unwrap_or(not_happening()) {
+            return;
+        }
+
         let Some(kind) = self.pending_request_keyframe.take() else {
             return;
         };
 
+        self.last_keyframe_request = Some(now);
+        self.keyframe_request_since = None;
+
         let ssrc = self.ssrc;
 
         match kind {
@@ -484,12 +1190,72 @@ impl StreamRx {
         }))
     }
 
+    pub(crate) fn loss_notification_at(&self) -> Option<Instant> {
+        self.pending_loss_notification?;
+        Some(already_happened())
+    }
+
+    pub(crate) fn maybe_create_loss_notification(
+        &mut self,
+        sender_ssrc: Ssrc,
+        negotiated: bool,
+        feedback: &mut VecDeque<Rtcp>,
+    ) {
+        let Some((last_decoded, last_received, decodable)) = self.pending_loss_notification.take()
+        else {
+            return;
+        };
+
+        if !negotiated {
+            debug!(
+                "Drop loss notification, goog-lntf is not negotiated for mid: {}",
+                self.mid
+            );
+            return;
+        }
+
+        feedback.push_back(Rtcp::Lntf(Lntf {
+            sender_ssrc,
+            ssrc: self.ssrc,
+            last_decoded: *last_decoded as u16,
+            last_received: *last_received as u16,
+            decodable,
+        }))
+    }
+
     fn next_fir_seq_no(&mut self) -> u8 {
         let x = self.fir_seq_no;
         self.fir_seq_no = self.fir_seq_no.wrapping_add(1);
         x
     }
 
+    /// Count the ECN codepoint of a received packet.
+    pub(crate) fn register_ecn(&mut self, ecn: Ecn) {
+        let counts = self.ecn.get_or_insert(EcnFeedback {
+            sender_ssrc: 0.into(),
+            ssrc: self.ssrc,
+            extended_highest_seq: 0,
+            ect0: 0,
+            ect1: 0,
+            ce: 0,
+            not_ect: 0,
+            lost: 0,
+            duplicates: 0,
+        });
+
+        // The counters wrap around as per RFC 6679.
+        match ecn {
+            Ecn::NotEct => counts.not_ect = counts.not_ect.wrapping_add(1),
+            Ecn::Ect1 => counts.ect1 = counts.ect1.wrapping_add(1),
+            Ecn::Ect0 => counts.ect0 = counts.ect0.wrapping_add(1),
+            Ecn::Ce => counts.ce = counts.ce.wrapping_add(1),
+        }
+    }
+
+    pub(crate) fn schedule_immediate_rr(&mut self) {
+        self.last_receiver_report = already_happened();
+    }
+
     pub(crate) fn need_rr(&self, now: Instant) -> bool {
         now >= self.receiver_report_at()
     }
@@ -510,6 +1276,17 @@ impl StreamRx {
 
         let xr = self.create_extended_receiver_report(now);
 
+        // ECN feedback goes with the RR, which has the same highest seq and lost count.
+        let ecn = self.ecn.as_ref().map(|e| {
+            let mut ecn = e.clone();
+            ecn.sender_ssrc = sender_ssrc;
+            if let Some(r) = rr.reports.iter().last() {
+                ecn.extended_highest_seq = r.max_seq;
+                ecn.lost = r.packets_lost as u16;
+            }
+            ecn
+        });
+
         trace!(
             "Created feedback RR/XR ({:?}/{:?}): {:?} {:?}",
             self.mid,
@@ -520,6 +1297,11 @@ impl StreamRx {
         feedback.push_back(Rtcp::ReceiverReport(rr));
         feedback.push_back(Rtcp::ExtendedReport(xr));
 
+        if let Some(ecn) = ecn {
+            trace!("Created feedback ECN: {:?}", ecn);
+            feedback.push_back(Rtcp::EcnFeedback(ecn));
+        }
+
         self.last_receiver_report = now;
     }
 
@@ -563,13 +1345,31 @@ impl StreamRx {
         }
     }
 
-    fn create_extended_receiver_report(&self, now: Instant) -> ExtendedReport {
+    fn create_extended_receiver_report(&mut self, now: Instant) -> ExtendedReport {
         // we only want to report our time to measure RTT,
         // the source will answer with Dlrr feedback, allowing us to calculate RTT
-        let block = ReportBlock::Rrtr(Rrtr { ntp_time: now });
+        let mut blocks = vec![ReportBlock::Rrtr(Rrtr { ntp_time: now })];
+
+        if self.jitter_buffer.is_enabled() {
+            // The water marks cover the time since the previous report.
+            let delay = self.jitter_buffer.delay();
+            let (low, high) = self.jitter_buffer.take_delay_range();
+
+            blocks.push(ReportBlock::JitterBuffer(JitterBufferMetrics {
+                ssrc: self.ssrc,
+                interval: MetricInterval::Interval,
+                adaptive: self.jitter_buffer.is_adaptive(),
+                nominal: Some(delay),
+                // Packets are released after the delay, later arrivals are discarded.
+                maximum: Some(delay),
+                high_water: Some(high),
+                low_water: Some(low),
+            }));
+        }
+
         ExtendedReport {
             ssrc: self.ssrc,
-            blocks: vec![block],
+            blocks,
         }
     }
 
@@ -629,6 +1429,30 @@ impl StreamRx {
         })
     }
 
+    pub(crate) fn poll_activity(&mut self) -> Option<(bool, StreamActivity)> {
+        if !self.need_activity_event {
+            return None;
+        }
+
+        self.need_activity_event = false;
+
+        info!(
+            "StreamRx {} with mid: {} rid: {:?} and SSRC: {}",
+            if self.active { "active" } else { "inactive" },
+            self.mid,
+            self.rid,
+            self.ssrc
+        );
+
+        let activity = StreamActivity {
+            ssrc: self.ssrc,
+            mid: self.mid,
+            rid: self.rid,
+        };
+
+        Some((self.active, activity))
+    }
+
     pub(crate) fn reset_buffers(&mut self) {
         if let Some(r) = &mut self.register {
             r.clear();
@@ -638,6 +1462,7 @@ impl StreamRx {
             r.clear();
         }
         self.pending_request_keyframe = None;
+        self.keyframe_request_since = None;
     }
 
     #[must_use]
@@ -700,6 +1525,19 @@ impl StreamRx {
 }
 
 impl StreamRxStats {
+    fn reset(&mut self) {
+        self.bytes = 0;
+        self.packets = 0;
+        self.firs = 0;
+        self.plis = 0;
+        self.nacks = 0;
+        self.keyframe_requests_suppressed = 0;
+        self.rtx_recovered = 0;
+        self.jitter_buffer_underruns = 0;
+        self.jitter_buffer_overruns = 0;
+        self.extensions = ExtensionStats::default();
+    }
+
     fn update_loss(&mut self, fraction_lost: u8) {
         self.loss = Some(fraction_lost as f32 / u8::MAX as f32)
     }
@@ -724,6 +1562,10 @@ impl StreamRxStats {
             firs: self.firs,
             plis: self.plis,
             nacks: self.nacks,
+            keyframe_requests_suppressed: self.keyframe_requests_suppressed,
+            rtx_recovered: self.rtx_recovered,
+            jitter_buffer_underruns: self.jitter_buffer_underruns,
+            jitter_buffer_overruns: self.jitter_buffer_overruns,
             rtt: self.rtt,
             loss: self.loss,
             timestamp: now,
@@ -746,3 +1588,59 @@ pub(crate) struct RegisterUpdateReceipt {
     pub time: MediaTime,
     pub is_new_packet: bool,
 }
+
+/// The delay between the abs-send-time `sent` and `now`.
+///
+/// abs-send-time wraps every 64 seconds and is placed in the 64 seconds up to `now`. A send
+/// time slightly ahead of `now` (clocks not in sync) thus looks like it's almost 64 seconds
+/// ago. Anything over half the wrap is taken to be such a negative delay.
+fn one_way_delay(now: Instant, sent: Instant) -> Duration {
+    const HALF_WRAP: Duration = Duration::from_secs(32);
+
+    let delay = now.saturating_duration_since(sent);
+
+    if delay > HALF_WRAP {
+        Duration::ZERO
+    } else {
+        delay
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn one_way_delay_wraps() {
+        let now = Instant::now() + Duration::from_secs(100);
+
+        let sent = now - Duration::from_millis(30);
+        assert_eq!(one_way_delay(now, sent), Duration::from_millis(30));
+
+        // Sender clock 10ms ahead, rebased to the previous 64 second window.
+        let sent = now - Duration::from_secs(64) + Duration::from_millis(10);
+        assert_eq!(one_way_delay(now, sent), Duration::ZERO);
+
+        let sent = now + Duration::from_millis(10);
+        assert_eq!(one_way_delay(now, sent), Duration::ZERO);
+    }
+
+    #[test]
+    fn loss_notification_is_due_straight_away() {
+        let mut rx = StreamRx::new(1.into(), Mid::from("v"), None, false);
+        assert_eq!(rx.loss_notification_at(), None);
+
+        rx.request_loss_notification(10.into(), 20.into(), true);
+        assert_eq!(rx.loss_notification_at(), Some(already_happened()));
+
+        // Dropped when goog-lntf is not negotiated.
+        let mut feedback = VecDeque::new();
+        rx.maybe_create_loss_notification(2.into(), false, &mut feedback);
+        assert!(feedback.is_empty());
+        assert_eq!(rx.loss_notification_at(), None);
+
+        rx.request_loss_notification(10.into(), 20.into(), true);
+        rx.maybe_create_loss_notification(2.into(), true, &mut feedback);
+        assert!(matches!(feedback.pop_front(), Some(Rtcp::Lntf(_))));
+    }
+}
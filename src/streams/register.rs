@@ -1,8 +1,9 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::rtp_::{Nack, ReceptionReport, SeqNo};
 
 use super::register_nack::NackRegister;
+use super::ReorderStats;
 
 #[derive(Debug)]
 pub struct ReceiverRegister {
@@ -26,6 +27,9 @@ pub struct ReceiverRegister {
     /// Estimated jitter. This is in the media time base, so divided by
     /// 90_000 or 48_000 to normalize.
     jitter: f32,
+
+    /// Reordering of the packets, not counting resends.
+    reorder: ReorderStats,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -61,19 +65,39 @@ impl TimePoint {
 }
 
 impl ReceiverRegister {
+    #[cfg(any(test, feature = "_internal_test_exports"))]
     pub fn new() -> Self {
+        Self::with_nack_window(super::register_nack::MAX_MISORDER)
+    }
+
+    pub fn with_nack_window(window: u64) -> Self {
         ReceiverRegister {
-            nack: NackRegister::new(),
+            nack: NackRegister::with_window(window),
             first: None,
             count: 0,
             time_point_prior: None,
             expected_prior: 0,
             received_prior: 0,
             jitter: 0.0,
+            reorder: ReorderStats::default(),
         }
     }
 
     pub fn update(&mut self, seq: SeqNo, arrival: Instant, rtp_time: u32, clock_rate: u32) -> bool {
+        let max_seq = self.max_seq();
+        let new = self.update_resend(seq, arrival, rtp_time, clock_rate);
+        self.reorder.update(seq, max_seq, new);
+        new
+    }
+
+    /// Like [`ReceiverRegister::update`], for a resent packet which doesn't count as reordered.
+    pub fn update_resend(
+        &mut self,
+        seq: SeqNo,
+        arrival: Instant,
+        rtp_time: u32,
+        clock_rate: u32,
+    ) -> bool {
         if self.first.is_none() {
             self.first = Some(seq);
         }
@@ -115,12 +139,29 @@ impl ReceiverRegister {
         })
     }
 
+    /// Estimated interarrival jitter.
+    pub fn jitter(&self) -> Duration {
+        Duration::from_micros(self.jitter as u64)
+    }
+
+    pub fn reorder_stats(&self) -> ReorderStats {
+        self.reorder
+    }
+
+    pub fn reset_reorder_stats(&mut self) {
+        self.reorder = ReorderStats::default();
+    }
+
     pub fn max_seq(&self) -> Option<SeqNo> {
         self.nack.max_seq()
     }
 
+    pub fn set_nack_window(&mut self, window: u64) {
+        self.nack.set_window(window);
+    }
+
     pub fn clear(&mut self) {
-        self.nack = NackRegister::new();
+        self.nack = NackRegister::with_window(self.nack.window());
         self.count = 0;
         self.first = None;
         self.time_point_prior = None;
@@ -340,4 +381,22 @@ mod test {
         assert_eq!(19, report.max_seq);
         assert_eq!(0, report.jitter);
     }
+
+    #[test]
+    fn reorder_stats() {
+        let mut r = ReceiverRegister::new();
+        let now = Instant::now();
+
+        for i in [10, 11, 14, 12, 15, 11, 13, 20, 16] {
+            r.update((i as u64).into(), now, 0, 90_000);
+        }
+
+        // A resend is expected to be late.
+        r.update_resend(17.into(), now, 0, 90_000);
+
+        let stats = r.reorder_stats();
+        assert_eq!(stats.reordered, 3);
+        assert_eq!(stats.max_distance, 4);
+        assert_eq!(stats.duplicates, 1);
+    }
 }
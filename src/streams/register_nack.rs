@@ -2,17 +2,18 @@ use std::ops::Range;
 
 use crate::rtp_::{Nack, NackEntry, ReportList, SeqNo};
 
-/// Number of out of order packets we keep track of for reports
-const MAX_MISORDER: u64 = 100;
+/// Default number of out of order packets we keep track of for reports
+pub(crate) const MAX_MISORDER: u64 = 100;
+
+/// Largest NACK window. Beyond half the sequence number space, we can't tell old
+/// packets from new ones.
+pub(crate) const MAX_NACK_WINDOW: u64 = 1 << 15;
 
 const U16_MAX: u64 = u16::MAX as u64 + 1_u64;
 
 /// The max number of NACKs we perform for a single packet
 const MAX_NACKS: u8 = 5;
 
-/// Circular buffer size
-const BUFFER_SIZE: u64 = MAX_MISORDER + 1;
-
 #[derive(Debug)]
 pub struct NackRegister {
     /// Status of packets indexed by wrapping SeqNo.
@@ -20,6 +21,9 @@ pub struct NackRegister {
 
     /// Range of seq numbers considered NACK reporting.
     active: Option<Range<SeqNo>>,
+
+    /// Number of packets behind the max seq number we NACK for.
+    window: u64,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -81,11 +85,40 @@ impl<'a> Iterator for NackIterator<'a> {
 }
 
 impl NackRegister {
-    pub fn new() -> Self {
+    pub fn with_window(window: u64) -> Self {
+        let window = window.min(MAX_NACK_WINDOW);
         NackRegister {
-            packets: vec![PacketStatus::default(); BUFFER_SIZE as usize],
+            // Circular buffer covering the window and the max seq number.
+            packets: vec![PacketStatus::default(); window as usize + 1],
             active: None,
+            window,
+        }
+    }
+
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// Change the window, keeping the state of the packets still within it.
+    ///
+    /// Gaps that fall outside a shrunk window are never NACKed.
+    pub fn set_window(&mut self, window: u64) {
+        let window = window.min(MAX_NACK_WINDOW);
+        let mut packets = vec![PacketStatus::default(); window as usize + 1];
+
+        if let Some(active) = self.active.clone() {
+            let start = (*active.start).max(active.end.saturating_sub(window));
+
+            for s in start..=*active.end {
+                let index = (s % packets.len() as u64) as usize;
+                packets[index] = *self.packet(s.into());
+            }
+
+            self.active = Some(start.into()..active.end);
         }
+
+        self.packets = packets;
+        self.window = window;
     }
 
     pub fn update(&mut self, seq: SeqNo) -> bool {
@@ -106,7 +139,7 @@ impl NackRegister {
         let end = active.end.max(seq);
 
         let start: SeqNo = {
-            let min = end.saturating_sub(MAX_MISORDER);
+            let min = end.saturating_sub(self.window);
             let mut start = (*active.start).max(min);
             while start < *end {
                 if !self.packet(start.into()).received && start != *seq {
@@ -182,7 +215,7 @@ impl NackRegister {
 mod test {
     use std::ops::Range;
 
-    use crate::streams::register_nack::MAX_MISORDER;
+    use crate::streams::register_nack::{MAX_MISORDER, MAX_NACK_WINDOW};
 
     use super::NackRegister;
 
@@ -235,7 +268,7 @@ mod test {
 
     #[test]
     fn active_window_sliding() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         assert_update(&mut reg, 10, true, true, 10..10);
 
@@ -268,9 +301,58 @@ mod test {
         assert_update(&mut reg, next, true, false, 15..(13 + MAX_MISORDER));
     }
 
+    #[test]
+    fn nack_window_after_outage() {
+        let mut reg = NackRegister::with_window(10);
+
+        reg.update(100.into());
+
+        // Long outage, only the last 10 packets are NACKed.
+        reg.update(200.into());
+
+        let report = reg.nack_reports().map(Vec::from_iter).expect("some report");
+        assert_eq!(report[0].reports.len(), 1);
+        assert_eq!(report[0].reports[0].pid, 190);
+        assert_eq!(report[0].reports[0].blp, 0b1_1111_1111);
+
+        // Packets before the window are not new.
+        assert!(!reg.update(150.into()));
+    }
+
+    #[test]
+    fn nack_window_clamped() {
+        let mut reg = NackRegister::with_window(u64::MAX);
+        assert_eq!(reg.window(), MAX_NACK_WINDOW);
+        assert_eq!(reg.packets.len() as u64, MAX_NACK_WINDOW + 1);
+
+        reg.set_window(u64::MAX);
+        assert_eq!(reg.window(), MAX_NACK_WINDOW);
+    }
+
+    #[test]
+    fn nack_set_window() {
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
+
+        reg.update(100.into());
+        reg.update(150.into());
+        reg.update(160.into());
+        assert_eq!(*reg.active.clone().unwrap().start, 101);
+
+        reg.set_window(20);
+        assert_eq!(*reg.active.clone().unwrap().start, 140);
+        assert_not_dirty(&reg);
+
+        // 150 is still known as received.
+        assert!(!reg.update(150.into()));
+
+        let report = reg.nack_reports().map(Vec::from_iter).expect("some report");
+        assert_eq!(report[0].reports[0].pid, 140);
+        assert_eq!(report[0].reports[0].blp, 0b1111_1101_1111_1111);
+    }
+
     #[test]
     fn nack_report_none() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         assert!(reg.nack_reports().is_none());
 
         reg.update(110.into());
@@ -282,7 +364,7 @@ mod test {
 
     #[test]
     fn nack_test_huge_seq_gap_no_hang() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         reg.update(0.into());
         reg.update(18446744073709551515.into());
@@ -290,7 +372,7 @@ mod test {
 
     #[test]
     fn nack_report_one() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         assert!(reg.nack_reports().is_none());
 
         reg.update(110.into());
@@ -306,7 +388,7 @@ mod test {
 
     #[test]
     fn nack_report_two() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         assert!(reg.nack_reports().is_none());
 
         reg.update(110.into());
@@ -322,7 +404,7 @@ mod test {
 
     #[test]
     fn nack_report_with_hole() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         for i in &[100, 101, 103, 105, 106, 107, 108, 109, 110] {
             reg.update((*i).into());
@@ -337,7 +419,7 @@ mod test {
 
     #[test]
     fn nack_report_stop_at_17() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         let seq = &[
             100, 101, 103, 104, 105, 106, 107, 108, 109, 110, //
@@ -358,7 +440,7 @@ mod test {
 
     #[test]
     fn nack_report_hole_at_17() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         let seq = &[
             100, 101, 103, 104, 105, 106, 107, 108, 109, 110, //
@@ -379,7 +461,7 @@ mod test {
 
     #[test]
     fn nack_report_no_stop_all_there() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         let seq = &[
             100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, //
@@ -396,7 +478,7 @@ mod test {
 
     #[test]
     fn nack_report_rtx() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         for i in &[
             100, 101, 102, 103, 104, 105, //
         ] {
@@ -430,7 +512,7 @@ mod test {
     fn nack_report_rollover_rtx() {
         // This test is checking that after rollover nacks are not skipped because of
         // packet position that would remain marked as received from before the rollover
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         for i in &[
             100, 101, 102, 103, 104, 105, 106, 108, 109, 110, 111, 112, 113, 114, 115,
         ] {
@@ -457,7 +539,7 @@ mod test {
 
     #[test]
     fn nack_report_rollover_rtx_with_seq_jump() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         // 2999 is missing
         for i in 0..2999 {
@@ -478,7 +560,7 @@ mod test {
 
     #[test]
     fn out_of_order_and_rollover() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
 
         reg.update(2998.into());
         reg.update(2999.into());
@@ -509,7 +591,7 @@ mod test {
 
         for (missing, expected) in missing.iter().zip(expected.iter()) {
             let mut seqs: Vec<_> = range.clone().collect();
-            let mut reg = NackRegister::new();
+            let mut reg = NackRegister::with_window(MAX_MISORDER);
 
             seqs.retain(|x| *x != *missing);
             for i in seqs.as_slice() {
@@ -524,7 +606,7 @@ mod test {
 
     #[test]
     fn nack_check_forward_at_boundary() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         for i in 2996..=3003 {
             reg.update(i.into());
         }
@@ -545,7 +627,7 @@ mod test {
 
     #[test]
     fn nack_check_forward_at_u16_boundary() {
-        let mut reg = NackRegister::new();
+        let mut reg = NackRegister::with_window(MAX_MISORDER);
         for i in 65500..=65534 {
             reg.update(i.into());
         }
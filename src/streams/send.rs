@@ -1,28 +1,31 @@
 use std::collections::VecDeque;
+use std::mem;
+use std::ops::RangeInclusive;
 use std::time::Duration;
 use std::time::Instant;
 
 use crate::format::CodecConfig;
 use crate::format::PayloadParams;
 use crate::io::DATAGRAM_MAX_PACKET_SIZE;
-use crate::io::DATAGRAM_MTU_WARN;
 use crate::io::MAX_RTP_OVERHEAD;
+use crate::io::{DATAGRAM_MTU, DATAGRAM_MTU_WARN};
 use crate::media::KeyframeRequestKind;
 use crate::media::Media;
 use crate::media::MediaKind;
 use crate::packet::QueuePriority;
 use crate::packet::QueueSnapshot;
 use crate::packet::QueueState;
-use crate::rtp_::Bitrate;
-use crate::rtp_::{extend_u16, Descriptions, ReportList, Rtcp};
-use crate::rtp_::{ExtensionMap, ReceptionReport, RtpHeader};
+use crate::rtp_::{extend_u16, Descriptions, EcnFeedback, ReportList, Rtcp};
+use crate::rtp_::{Bitrate, CsrcList};
+use crate::rtp_::{Extension, ExtensionMap, JitterBufferMetrics, ReceptionReport, RtpHeader};
 use crate::rtp_::{ExtensionValues, Frequency, MediaTime, Mid, NackEntry};
 use crate::rtp_::{Pt, Rid, RtcpFb, SenderInfo, SenderReport, Ssrc};
 use crate::rtp_::{Sdes, SdesType, MAX_BLANK_PADDING_PAYLOAD_SIZE};
-use crate::rtp_::{SeqNo, SRTP_BLOCK_SIZE};
+use crate::rtp_::{SeqNo, MAX_CSRC, SRTP_BLOCK_SIZE, SRTP_MAX_PACKETS, SRTP_OVERHEAD};
 use crate::session::PacketReceipt;
 use crate::stats::MediaEgressStats;
 use crate::stats::StatsSnapshot;
+use crate::stats::{OverheadStats, PacerStats};
 use crate::util::value_history::ValueHistory;
 use crate::util::{already_happened, calculate_rtt_ms, not_happening};
 use crate::util::{InstantExt, NonCryptographicRng};
@@ -30,7 +33,7 @@ use crate::RtcError;
 
 use super::rtx_cache::RtxCache;
 use super::send_queue::SendQueue;
-use super::{rr_interval, RtpPacket};
+use super::{rr_interval, LayerActive, PacketsDropped, PendingStats, RtpPacket};
 
 /// The smallest size of padding for which we attempt to use a spurious resend. For padding
 /// requests smaller than this we use blank packets instead.
@@ -38,6 +41,13 @@ const MIN_SPURIOUS_PADDING_SIZE: usize = 50;
 
 pub const DEFAULT_RTX_CACHE_DURATION: Duration = Duration::from_secs(3);
 
+const RTP_SIZE: usize = DATAGRAM_MTU - SRTP_OVERHEAD;
+// align to SRTP block size to minimize padding needs
+const DEFAULT_MAX_PAYLOAD_SIZE: usize = RTP_SIZE - RTP_SIZE % SRTP_BLOCK_SIZE;
+
+/// Default cap of the ratio of retransmitted bytes to all sent bytes.
+const DEFAULT_MAX_RTX_RATIO: f32 = 0.15;
+
 /// Outgoing encoded stream.
 ///
 /// A stream is a primary SSRC + optional RTX SSRC.
@@ -105,21 +115,77 @@ pub struct StreamTx {
     /// Last time we produced a SR.
     last_sender_report: Instant,
 
+    /// Whether we produce SR for this stream at all.
+    sender_reports_enabled: bool,
+
     /// If we have a pending incoming keyframe request.
     pending_request_keyframe: Option<KeyframeRequestKind>,
 
     /// If we have a pending incoming remb request.
     pending_request_remb: Option<Bitrate>,
 
+    /// If we have a pending incoming loss notification (last decoded, last received, decodable).
+    pending_loss_notification: Option<(SeqNo, SeqNo, bool)>,
+
+    /// Sequence number of the last sent media packet, to extend incoming 16 bit sequence numbers.
+    last_sent_seq_no: Option<SeqNo>,
+
     /// Statistics of outgoing data.
     stats: StreamTxStats,
 
+    /// Packets and payload bytes sent, for the sender report. Unlike stats, never reset.
+    sender_counts: (u64, u64),
+
     // downsampled rtx ratio (value, last calculation)
     rtx_ratio: (f32, Instant),
 
+    // Cap of the rtx ratio above which we don't do resends.
+    max_rtx_ratio: f32,
+
     // The _main_ PT to use for padding. This is main PT, since the poll_packet() loop
     // figures out the param.resend() RTX PT using main.
     pt_for_padding: Option<Pt>,
+
+    // Rewrite of PT from write_rtp() to the PT negotiated for this stream.
+    pt_map: Vec<(Pt, Pt)>,
+
+    // Explicit RTX PT per main PT, overriding the RTX PT of the media.
+    rtx_pt_map: Vec<(Pt, Pt)>,
+
+    /// Bitrate needed to send this stream as a simulcast layer. None if not managed.
+    layer_bitrate: Option<Bitrate>,
+
+    /// Whether the layer is sent. Only false if managed and the bitrate is insufficient.
+    layer_active: bool,
+
+    /// Whether we need to emit a layer active event for the current state.
+    need_layer_event: bool,
+
+    /// Number of packets dropped while the layer was stopped. Subtracted from the sequence
+    /// numbers of written packets to not leave gaps the receiver would NACK.
+    layer_seq_offset: u64,
+
+    /// Largest RTP header written with `max_header_exts`, including the RTX original
+    /// sequence number.
+    max_header_len: usize,
+
+    /// The extensions of the headers in `max_header_len`.
+    max_header_exts: Option<ExtensionMap>,
+
+    /// Max time a packet waits in the send queue before stale packets are dropped.
+    max_queue_age: Option<Duration>,
+
+    /// Dropped packets and bytes not yet reported in an event.
+    pending_dropped: Option<(usize, usize)>,
+
+    /// Packets and payload bytes dropped from the send queue. Never reset.
+    dropped_counts: (u64, u64),
+
+    /// DSCP hint for outgoing packets.
+    dscp: Option<u8>,
+
+    /// Last ECN feedback from the remote peer.
+    last_ecn: Option<EcnFeedback>,
 }
 
 /// Holder of stats.
@@ -141,22 +207,36 @@ pub(crate) struct StreamTxStats {
     plis: u64,
     /// count of NACKs received
     nacks: u64,
+    /// count of resends dropped due to the rtx ratio cap
+    resends_dropped: u64,
+    /// count of padding bytes, including spurious resends
+    bytes_padding: u64,
+    /// count of padding packets
+    packets_padding: u64,
     /// round trip time (ms)
     /// Can be null in case of missing or bad reports
     rtt: Option<f32>,
     /// losses collecter from RR (known packets, lost ratio)
     losses: Vec<(u64, f32)>,
+    /// last jitter buffer metrics reported in XR
+    remote_jitter_buffer: Option<JitterBufferMetrics>,
     bytes_transmitted: ValueHistory<u64>,
     bytes_retransmitted: ValueHistory<u64>,
 }
 
 impl StreamTx {
-    pub(crate) fn new(ssrc: Ssrc, rtx: Option<Ssrc>, mid: Mid, rid: Option<Rid>) -> Self {
+    pub(crate) fn new(
+        ssrc: Ssrc,
+        rtx: Option<Ssrc>,
+        mid: Mid,
+        rid: Option<Rid>,
+        seq_range: RangeInclusive<u16>,
+    ) -> Self {
         // https://www.rfc-editor.org/rfc/rfc3550#page-13
         // The initial value of the sequence number SHOULD be random (unpredictable)
         // to make known-plaintext attacks on encryption more difficult
-        let seq_no = (NonCryptographicRng::u16() as u64).into();
-        let seq_no_rtx = (NonCryptographicRng::u16() as u64).into();
+        let seq_no = (NonCryptographicRng::u16_in(seq_range.clone()) as u64).into();
+        let seq_no_rtx = (NonCryptographicRng::u16_in(seq_range) as u64).into();
 
         debug!("Create StreamTx for SSRC: {}", ssrc);
 
@@ -179,11 +259,29 @@ impl StreamTx {
             blank_packet: RtpPacket::blank(),
             rtx_cache: RtxCache::new(2000, DEFAULT_RTX_CACHE_DURATION),
             last_sender_report: already_happened(),
+            sender_reports_enabled: true,
             pending_request_keyframe: None,
             pending_request_remb: None,
+            pending_loss_notification: None,
+            last_sent_seq_no: None,
             stats: StreamTxStats::default(),
+            sender_counts: (0, 0),
             rtx_ratio: (0.0, already_happened()),
+            max_rtx_ratio: DEFAULT_MAX_RTX_RATIO,
             pt_for_padding: None,
+            pt_map: vec![],
+            rtx_pt_map: vec![],
+            layer_bitrate: None,
+            layer_active: true,
+            need_layer_event: false,
+            layer_seq_offset: 0,
+            max_header_len: 0,
+            max_header_exts: None,
+            max_queue_age: None,
+            pending_dropped: None,
+            dropped_counts: (0, 0),
+            dscp: None,
+            last_ecn: None,
         }
     }
 
@@ -221,6 +319,78 @@ impl StreamTx {
         self.rtx_cache = RtxCache::new(max_packets, max_age);
     }
 
+    /// Cap the bandwidth used for resends.
+    ///
+    /// The ratio is that of retransmitted bytes to all bytes sent over the last second. Resends
+    /// requested by NACK are dropped while the ratio is above the cap. This protects against a
+    /// peer that (maliciously or not) requests far more resends than it should. The number of
+    /// dropped resends is reported in [`MediaEgressStats::resends_dropped`].
+    ///
+    /// The default is 0.15.
+    pub fn set_max_rtx_ratio(&mut self, ratio: f32) {
+        self.max_rtx_ratio = ratio;
+    }
+
+    /// Set the max time packets wait in the send queue.
+    ///
+    /// When the pacer can't keep up, such as when the bandwidth estimate is lower than the
+    /// bitrate written, packets accumulate in the send queue. Once the oldest packet has waited
+    /// longer than this, the stale packets are dropped and reported in
+    /// [`Event::PacketsDropped`][crate::Event::PacketsDropped]. Packets marked discardable
+    /// using the [`FrameMarking`][crate::rtp::FrameMarking] extension value, typically the
+    /// non-referenced temporal layer, are dropped first. Stale packets are dropped together
+    /// with the rest of the frame.
+    ///
+    /// The remote peer sees the dropped packets as lost, and likely requests a keyframe.
+    ///
+    /// The default is None, which never drops packets.
+    pub fn set_max_queue_age(&mut self, max_age: Option<Duration>) {
+        self.max_queue_age = max_age;
+    }
+
+    /// Snapshot of the packets written to this stream that are not yet sent.
+    ///
+    /// Packets wait in the send queue until the pacer releases them. Comparing this with
+    /// the transport stats tells apart an application writing faster than the bandwidth
+    /// estimate allows from congestion in the network. Resends and padding are not included.
+    ///
+    /// ```no_run
+    /// # use str0m::Rtc;
+    /// # use str0m::rtp::Ssrc;
+    /// # use std::time::Instant;
+    /// # let mut rtc = Rtc::new();
+    /// # let ssrc: Ssrc = 42.into();
+    /// let mut api = rtc.direct_api();
+    /// let pending = api.stream_tx(&ssrc).unwrap().pending(Instant::now());
+    ///
+    /// println!("{} packets, {} bytes queued", pending.packets, pending.bytes);
+    /// ```
+    pub fn pending(&self, now: Instant) -> PendingStats {
+        self.send_queue.pending(now)
+    }
+
+    /// Set the DSCP value packets of this stream are intended to be marked with.
+    ///
+    /// str0m is sans-IO and never marks packets itself. The value is passed on in
+    /// [`Transmit::dscp`][crate::net::Transmit::dscp] for every RTP packet of this stream,
+    /// and the application sets it on the socket. This way audio can, for instance, be
+    /// marked EF (46) and video AF41 (34) so the network can prioritize audio.
+    ///
+    /// RTCP, DTLS and STUN are sent without a DSCP hint. The default is None.
+    ///
+    /// DSCP is a 6 bit value, anything larger than 63 is clamped to 63.
+    pub fn set_dscp(&mut self, dscp: Option<u8>) {
+        if let Some(v) = dscp.filter(|v| *v > 63) {
+            warn!("DSCP is a 6 bit value, clamp {} to 63", v);
+        }
+        self.dscp = dscp.map(|v| v.min(63));
+    }
+
+    /// The DSCP value set with [`StreamTx::set_dscp()`].
+    pub fn dscp(&self) -> Option<u8> {
+        self.dscp
+    }
+
     /// Set whether this stream is unpaced or not.
     ///
     /// This is only relevant when BWE (Bandwidth Estimation) is enabled. By default, audio is unpaced
@@ -231,11 +401,140 @@ impl StreamTx {
         self.unpaced = Some(unpaced);
     }
 
+    /// Enable or disable sending RTCP sender reports (SR) for this stream.
+    ///
+    /// Disabling SR reduces RTCP bandwidth, which can be useful in unidirectional scenarios.
+    /// The tradeoff is that the SR carries the NTP/RTP time mapping the remote peer needs to
+    /// synchronize this stream with others (lip-sync), and the SDES CNAME that groups them.
+    /// Without it, the remote peer can't synchronize the stream.
+    ///
+    /// The default is enabled.
+    pub fn set_sender_reports_enabled(&mut self, enabled: bool) {
+        self.sender_reports_enabled = enabled;
+    }
+
+    /// Set the bitrate needed to send this stream as a simulcast layer.
+    ///
+    /// Once set, str0m stops sending the layer when the available bitrate is not enough for it
+    /// and all lower layers of the same mid, and resumes it when the bitrate recovers. The
+    /// available bitrate is the BWE estimate, if enabled, limited by any `b=AS` the remote
+    /// peer signalled for the media, see [`Media::remote_bitrate()`][crate::media::Media::remote_bitrate].
+    ///
+    /// The lowest layer is never stopped. Changes are signalled with
+    /// [`Event::LayerActive`][crate::Event::LayerActive]. While stopped, written packets are
+    /// dropped, and the sequence numbers of later packets are lowered to not leave a gap.
+    /// A resumed video layer typically needs a keyframe.
+    ///
+    /// The thresholds are set with [`RtcConfig::set_layer_thresholds()`][crate::RtcConfig::set_layer_thresholds].
+    pub fn set_layer_bitrate(&mut self, bitrate: Bitrate) {
+        self.layer_bitrate = Some(bitrate);
+    }
+
+    /// Whether this stream is currently sent as a simulcast layer.
+    ///
+    /// Always true unless [`StreamTx::set_layer_bitrate()`] is used.
+    pub fn is_layer_active(&self) -> bool {
+        self.layer_active
+    }
+
+    pub(crate) fn layer_bitrate(&self) -> Option<Bitrate> {
+        self.layer_bitrate
+    }
+
+    pub(crate) fn set_layer_active(&mut self, active: bool) {
+        if self.layer_active == active {
+            return;
+        }
+        self.layer_active = active;
+        self.need_layer_event = true;
+    }
+
+    pub(crate) fn poll_layer_active(&mut self) -> Option<LayerActive> {
+        if !self.need_layer_event {
+            return None;
+        }
+
+        self.need_layer_event = false;
+
+        info!(
+            "{} layer StreamTx with mid: {} rid: {:?} and SSRC: {}",
+            if self.layer_active { "Resume" } else { "Stop" },
+            self.mid,
+            self.rid,
+            self.ssrc
+        );
+
+        Some(LayerActive {
+            ssrc: self.ssrc,
+            mid: self.mid,
+            rid: self.rid,
+            active: self.layer_active,
+        })
+    }
+
+    /// Reset the cumulative counters reported in [`MediaEgressStats`].
+    ///
+    /// This zeroes the bytes, packets, resent, FIR, PLI, NACK and dropped resends counters,
+    /// which is useful to compute per-interval values. The last measured RTT and loss are
+    /// kept, since they are not cumulative. Until more data is sent, no stats are reported
+    /// for the stream.
+    ///
+    /// Protocol state is not affected. This includes the sequence numbers, the ROC, the SRTP
+    /// context, the packet and octet counts sent to the remote peer in sender reports, and
+    /// the send history used to cap resends.
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Rewrite the payload type of packets written with [`StreamTx::write_rtp()`].
+    ///
+    /// In an SFU, the PT negotiated with the peer sending the media often differs from the PT
+    /// negotiated with the peer receiving it. Each `(from, to)` pair maps a written PT to the
+    /// PT to send. PTs not in the map are sent as is. Resends use the RTX PT associated with
+    /// the rewritten PT.
+    ///
+    /// The rewrite happens as the packet is written, so everything after that, such as
+    /// resends and SRTP, only sees the rewritten PT.
+    ///
+    /// The `to` PT can be found with [`CodecConfig::match_params()`][crate::format::CodecConfig::match_params]
+    /// on the receiving peer's [`Rtc::codec_config()`][crate::Rtc::codec_config].
+    pub fn set_pt_map(&mut self, map: impl IntoIterator<Item = (Pt, Pt)>) {
+        self.pt_map = map.into_iter().collect();
+    }
+
+    /// Set the RTX PT used for resends of packets sent with the main PT `pt`.
+    ///
+    /// By default, resends use the RTX PT associated (`apt`) with the main PT in the media,
+    /// see [`PayloadParams::resend()`][crate::format::PayloadParams::resend]. This overrides
+    /// it, for instance when forwarding to a peer that negotiated other RTX PTs. The remote
+    /// peer must know `rtx_pt` as the RTX PT for `pt`, or it can't use the resends.
+    ///
+    /// The `pt` is the PT sent, i.e. after any rewrite by [`StreamTx::set_pt_map()`]. The RTX PT
+    /// must be a dynamic PT (96-127) that differs from the main PT.
+    pub fn set_rtx_pt(&mut self, pt: Pt, rtx_pt: Pt) -> Result<(), RtcError> {
+        if rtx_pt == pt || !(96..=127).contains(&*rtx_pt) {
+            return Err(RtcError::InvalidRtxPt(rtx_pt));
+        }
+
+        self.rtx_pt_map.retain(|(p, _)| *p != pt);
+        self.rtx_pt_map.push((pt, rtx_pt));
+
+        Ok(())
+    }
+
+    /// The RTX PT set with [`StreamTx::set_rtx_pt()`] for the main PT `pt`.
+    pub fn rtx_pt(&self, pt: Pt) -> Option<Pt> {
+        self.rtx_pt_map
+            .iter()
+            .find(|(p, _)| *p == pt)
+            .map(|(_, rtx)| *rtx)
+    }
+
     /// Write RTP packet to a send stream.
     ///
     /// The `payload` argument is expected to be only the RTP payload, not the RTP packet header.
     ///
-    /// * `pt` Payload type. Declared in the Media this encoded stream belongs to.
+    /// * `pt` Payload type. Declared in the Media this encoded stream belongs to, or mapped using [`StreamTx::set_pt_map()`].
     /// * `seq_no` Sequence number to use for this packet.
     /// * `time` Time in whatever the clock rate is for the media in question (normally 90_000 for video
     ///          and 48_000 for audio).
@@ -265,8 +564,23 @@ impl StreamTx {
         nackable: bool,
         payload: Vec<u8>,
     ) -> Result<(), RtcError> {
+        if !self.layer_active {
+            trace!("Drop packet for stopped layer: {}", seq_no);
+            self.layer_seq_offset += 1;
+            return Ok(());
+        }
+
+        let seq_no: SeqNo = (*seq_no).saturating_sub(self.layer_seq_offset).into();
+
         let first_call = self.rtp_and_wallclock.is_none();
 
+        let pt = self
+            .pt_map
+            .iter()
+            .find(|(from, _)| *from == pt)
+            .map(|(_, to)| *to)
+            .unwrap_or(pt);
+
         if first_call && seq_no.roc() > 0 {
             // TODO: make it possible to supress this.
             warn!("First SeqNo has non-zero ROC ({}), which needs out-of-band signalling to remote peer", seq_no.roc());
@@ -282,6 +596,7 @@ impl StreamTx {
             payload_type: pt,
             timestamp: time,
             ssrc: self.ssrc,
+            csrc: csrc_of(&ext_vals),
             ext_vals,
             ..Default::default()
         };
@@ -314,29 +629,100 @@ impl StreamTx {
         self.rtx.is_some() && self.pt_for_padding.is_some()
     }
 
+    /// Max payload size for packets of this stream to fit the `rtp_mtu`.
+    ///
+    /// The header size is estimated from the extensions str0m sets and `ext_vals`, but is
+    /// never less than the largest header sent with the current extensions.
+    pub(crate) fn max_payload_size(
+        &self,
+        rtp_mtu: Option<usize>,
+        exts: &ExtensionMap,
+        ext_vals: &ExtensionValues,
+    ) -> usize {
+        let Some(rtp_mtu) = rtp_mtu else {
+            return DEFAULT_MAX_PAYLOAD_SIZE;
+        };
+
+        // Values set in poll_packet().
+        let sent = |ext: &Extension| match ext {
+            Extension::RtpMid => Some(self.mid.len()),
+            Extension::RtpStreamId => self.rid.map(|r| r.len()),
+            Extension::AbsoluteSendTime | Extension::TransmissionTimeOffset => Some(3),
+            Extension::TransportSequenceNumber => Some(2),
+            _ => None,
+        };
+
+        let csrc_len = ext_vals
+            .csrc_audio_levels
+            .as_ref()
+            .map(|l| l.len().min(MAX_CSRC))
+            .unwrap_or(0);
+        let header_len = 16 + csrc_len * 4 + exts.written_len(ext_vals, sent);
+
+        let max_header_len = if self.max_header_exts.as_ref() == Some(exts) {
+            self.max_header_len
+        } else {
+            0
+        };
+        let header_len = header_len.max(max_header_len);
+
+        // Resends have the original sequence number before the payload.
+        let original_seq_len = if self.rtx.is_some() { 2 } else { 0 };
+
+        // The payload is padded to the SRTP block size.
+        let available = rtp_mtu.saturating_sub(header_len + SRTP_OVERHEAD);
+        let available = available - available % SRTP_BLOCK_SIZE;
+
+        available.saturating_sub(original_seq_len)
+    }
+
     pub(crate) fn poll_packet(
         &mut self,
         now: Instant,
         exts: &ExtensionMap,
         twcc: &mut u64,
         params: &[PayloadParams],
+        rtp_mtu: Option<usize>,
         buf: &mut Vec<u8>,
+    ) -> Option<PacketReceipt> {
+        // Moved out while polling, since the next packet borrows self.
+        let rtx_pt_map = mem::take(&mut self.rtx_pt_map);
+
+        let receipt = self.do_poll_packet(now, exts, twcc, params, rtp_mtu, buf, &rtx_pt_map);
+
+        self.rtx_pt_map = rtx_pt_map;
+
+        receipt
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn do_poll_packet(
+        &mut self,
+        now: Instant,
+        exts: &ExtensionMap,
+        twcc: &mut u64,
+        params: &[PayloadParams],
+        rtp_mtu: Option<usize>,
+        buf: &mut Vec<u8>,
+        rtx_pt_map: &[(Pt, Pt)],
     ) -> Option<PacketReceipt> {
         let mid = self.mid;
         let rid = self.rid;
         let ssrc_rtx = self.rtx;
+        let rtp_and_wallclock = self.rtp_and_wallclock;
 
         let (next, is_padding) = if let Some(next) = self.poll_packet_resend(now) {
             (next, false)
         } else if let Some(next) = self.poll_packet_regular(now) {
             (next, false)
-        } else if let Some(next) = self.poll_packet_padding(now) {
+        } else if let Some(next) = self.poll_packet_padding(now, rtp_mtu) {
             (next, true)
         } else {
             return None;
         };
 
         let pop_send_queue = next.kind == NextPacketKind::Regular;
+        let is_blank = matches!(next.kind, NextPacketKind::Blank(_));
 
         // Need the header for the receipt and modifications
         // TODO: Can we remove this?
@@ -363,9 +749,15 @@ impl StreamTx {
         let mut set_pt_for_padding = None;
         let mut set_cr = None;
 
+        let pt_rtx = rtx_pt_map
+            .iter()
+            .find(|(p, _)| *p == pt_main)
+            .map(|(_, rtx)| *rtx)
+            .or(param.resend());
+
         let mut header = match next.kind {
             NextPacketKind::Regular => {
-                let rtx_possible = param.resend().is_some();
+                let rtx_possible = pt_rtx.is_some();
 
                 if rtx_possible {
                     // Remember PT We want to set these directly on `self` here, but can't
@@ -403,7 +795,7 @@ impl StreamTx {
                 //   got a "real" PTX RT, either via set_pt_for_padding above, or via
                 //   the on_first_timeout() further down.
                 // Either way, unwrapping this optional _should_ be correct.
-                let pt_rtx = param.resend().expect("PT for resend or blank");
+                let pt_rtx = pt_rtx.expect("PT for resend or blank");
 
                 // Clone header to not change the original (cached) header.
                 let mut header = header_ref.clone();
@@ -426,6 +818,13 @@ impl StreamTx {
         header.ext_vals.transport_cc = Some(*twcc as u16);
         *twcc += 1;
 
+        let tx_time_offs = if is_blank {
+            0
+        } else {
+            capture_offset(now, &header, rtp_and_wallclock, param.spec().clock_rate)
+        };
+        header.ext_vals.tx_time_offs = Some(tx_time_offs);
+
         buf.resize(DATAGRAM_MAX_PACKET_SIZE, 0);
 
         let header_len = header.write_to(buf, exts);
@@ -494,6 +893,17 @@ impl StreamTx {
         let seq_no = next.seq_no;
         self.last_used = now;
 
+        // Blank padding has no payload, and thus no bearing on the max payload size.
+        if !is_blank {
+            // Headers written with other extensions don't tell anything about the current.
+            if self.max_header_exts.as_ref() != Some(exts) {
+                self.max_header_exts = Some(exts.clone());
+                self.max_header_len = 0;
+            }
+            let header_len = header_len + original_seq_len;
+            self.max_header_len = self.max_header_len.max(header_len);
+        }
+
         // Padding comes in two forms, "spurious resends" of sent packets where
         // the remote side didn't ask for a resend. The other variant are blank
         // packets, containing nothing but zeroes. Such packets must be sent from
@@ -510,6 +920,8 @@ impl StreamTx {
         }
 
         if pop_send_queue {
+            self.last_sent_seq_no = Some(seq_no);
+
             // poll_packet_regular leaves the packet in the head of the send_queue
             let pkt = self
                 .send_queue
@@ -548,7 +960,8 @@ impl StreamTx {
         let ratio = self.rtx_ratio_downsampled(now);
 
         // If we hit the cap, stop doing resends by clearing those we have queued.
-        if ratio > 0.15_f32 {
+        if ratio > self.max_rtx_ratio {
+            self.stats.resends_dropped += self.resends.len() as u64;
             self.resends.clear();
             return None;
         }
@@ -576,6 +989,8 @@ impl StreamTx {
 
         let len = pkt.payload.len() as u64;
         self.stats.update_packet_counts(len, true);
+        self.sender_counts.0 += 1;
+        self.sender_counts.1 += len;
         self.stats.bytes_retransmitted.push(now, len);
 
         let seq_no = self.seq_no_rtx.inc();
@@ -599,6 +1014,8 @@ impl StreamTx {
 
         let len = pkt.payload.len() as u64;
         self.stats.update_packet_counts(len, false);
+        self.sender_counts.0 += 1;
+        self.sender_counts.1 += len;
         self.stats.bytes_transmitted.push(now, len);
 
         let seq_no = pkt.seq_no;
@@ -610,7 +1027,11 @@ impl StreamTx {
         })
     }
 
-    fn poll_packet_padding(&mut self, _now: Instant) -> Option<NextPacket> {
+    fn poll_packet_padding(
+        &mut self,
+        _now: Instant,
+        rtp_mtu: Option<usize>,
+    ) -> Option<NextPacket<'_>> {
         if !self.padding_enabled() {
             self.padding = 0;
             return None;
@@ -625,7 +1046,8 @@ impl StreamTx {
             if self.padding > MIN_SPURIOUS_PADDING_SIZE {
                 // Find a historic packet that is smaller than this max size. The max size
                 // is a headroom since we can accept slightly larger padding than asked for.
-                let max_size = (self.padding * 2).min(DATAGRAM_MTU_WARN - MAX_RTP_OVERHEAD);
+                let rtp_mtu = rtp_mtu.unwrap_or(DATAGRAM_MTU_WARN);
+                let max_size = (self.padding * 2).min(rtp_mtu.saturating_sub(MAX_RTP_OVERHEAD));
 
                 let Some(pkt) = self.rtx_cache.get_cached_packet_smaller_than(max_size) else {
                     // Couldn't find spurious packet, try a blank packet instead.
@@ -636,6 +1058,7 @@ impl StreamTx {
                 let seq_no = self.seq_no_rtx.inc();
 
                 self.padding = self.padding.saturating_sub(pkt.payload.len());
+                self.stats.update_padding_counts(pkt.payload.len() as u64);
 
                 return Some(NextPacket {
                     kind: NextPacketKind::Resend(orig_seq_no),
@@ -658,6 +1081,7 @@ impl StreamTx {
         assert!(len <= 255); // should fit in a byte
 
         self.padding = self.padding.saturating_sub(len);
+        self.stats.update_padding_counts(len as u64);
 
         Some(NextPacket {
             kind: NextPacketKind::Blank(len as u8),
@@ -667,6 +1091,9 @@ impl StreamTx {
     }
 
     pub(crate) fn sender_report_at(&self) -> Instant {
+        if !self.sender_reports_enabled {
+            return not_happening();
+        }
         let Some(kind) = self.kind else {
             // First handle_timeout sets the kind. No sender report until then.
             return not_happening();
@@ -682,6 +1109,10 @@ impl StreamTx {
         self.pending_request_remb.take()
     }
 
+    pub(crate) fn poll_loss_notification(&mut self) -> Option<(SeqNo, SeqNo, bool)> {
+        self.pending_loss_notification.take()
+    }
+
     pub(crate) fn handle_rtcp(&mut self, now: Instant, fb: RtcpFb) {
         use RtcpFb::*;
         match fb {
@@ -699,14 +1130,52 @@ impl StreamTx {
                 self.stats.increase_firs();
                 self.pending_request_keyframe = Some(KeyframeRequestKind::Fir);
             }
+            JitterBuffer(m) => self.stats.remote_jitter_buffer = Some(m),
             Remb(r) => {
                 self.pending_request_remb = Some(Bitrate::from(r.bitrate as f64));
             }
+            Lntf(l) => {
+                let Some(last) = self.last_sent_seq_no else {
+                    // Nothing sent, nothing to be lost.
+                    return;
+                };
+                let last_received: SeqNo = extend_u16(Some(*last), l.last_received).into();
+                let last_decoded: SeqNo = extend_u16(Some(*last_received), l.last_decoded).into();
+                self.pending_loss_notification = Some((last_decoded, last_received, l.decodable));
+            }
             Twcc(_) => unreachable!("TWCC should be handled on session level"),
             _ => {}
         }
     }
 
+    /// Packets marked CE and the total packets received since the previous ECN feedback.
+    pub(crate) fn ecn_delta(&mut self, ecn: EcnFeedback) -> (u64, u64) {
+        // The first feedback counts from the start of the stream.
+        let (ect0, ect1, ce, not_ect) = match &self.last_ecn {
+            Some(p) => (p.ect0, p.ect1, p.ce, p.not_ect),
+            None => (0, 0, 0, 0),
+        };
+
+        let deltas = (
+            ecn.ce.checked_sub(ce),
+            ecn.ect0.checked_sub(ect0),
+            ecn.ect1.checked_sub(ect1),
+            ecn.not_ect.checked_sub(not_ect),
+        );
+
+        self.last_ecn = Some(ecn);
+
+        // Counters going backwards is a wrap around, a reordered feedback or a remote that
+        // restarted counting. There is nothing to learn from it, but it's the new baseline.
+        let (Some(ce), Some(ect0), Some(ect1), Some(not_ect)) = deltas else {
+            debug!("Ignore ECN feedback with decreasing counters");
+            return (0, 0);
+        };
+
+        let ce = ce as u64;
+        (ce, ect0 as u64 + ect1 as u64 + not_ect as u64 + ce)
+    }
+
     pub(crate) fn handle_nack(
         &mut self,
         entries: impl Iterator<Item = NackEntry>,
@@ -735,10 +1204,18 @@ impl StreamTx {
         Some(())
     }
 
+    pub(crate) fn schedule_immediate_sr(&mut self) {
+        self.last_sender_report = already_happened();
+    }
+
     pub(crate) fn need_sr(&self, now: Instant) -> bool {
         now >= self.sender_report_at()
     }
 
+    pub(crate) fn sender_reports_enabled(&self) -> bool {
+        self.sender_reports_enabled
+    }
+
     pub(crate) fn create_sr_and_update(&mut self, now: Instant, feedback: &mut VecDeque<Rtcp>) {
         let sr = self.create_sender_report(now);
 
@@ -784,8 +1261,8 @@ impl StreamTx {
             ssrc: self.ssrc,
             ntp_time: now,
             rtp_time,
-            sender_packet_count: self.stats.packets as u32,
-            sender_octet_count: self.stats.bytes as u32,
+            sender_packet_count: self.sender_counts.0 as u32,
+            sender_octet_count: self.sender_counts.1 as u32,
         }
     }
 
@@ -812,6 +1289,14 @@ impl StreamTx {
         Some(rtp_time.rebase(clock_rate))
     }
 
+    /// Number of SRTP packets that can be sent with this SSRC, or its RTX, before the
+    /// SRTP index is exhausted.
+    pub(crate) fn srtp_packets_left(&self) -> u64 {
+        let main = self.last_sent_seq_no.map(|s| *s).unwrap_or(0);
+        let used = main.max(*self.seq_no_rtx);
+        SRTP_MAX_PACKETS.saturating_sub(used)
+    }
+
     pub(crate) fn next_seq_no(&mut self) -> SeqNo {
         self.seq_no.inc()
     }
@@ -828,7 +1313,11 @@ impl StreamTx {
         self.stats.fill(snapshot, self.mid, self.rid, now);
     }
 
-    pub(crate) fn queue_state(&mut self, now: Instant) -> QueueState {
+    pub(crate) fn queue_state(
+        &mut self,
+        now: Instant,
+        pacing_priority: impl Fn(MediaKind) -> u8,
+    ) -> QueueState {
         // The unpaced flag is set to a default value on first handle_timeout. The
         // default is to not pace audio. We unwrap default to "true" here to not
         // apply any pacing until we know what kind of content we are sending.
@@ -848,10 +1337,14 @@ impl StreamTx {
             snapshot.merge(&snapshot_padding);
         }
 
+        // Until we know the kind, there is nothing queued to prioritize.
+        let pacing_priority = self.kind.map(pacing_priority).unwrap_or(0);
+
         QueueState {
             mid: self.mid,
             unpaced,
             use_for_padding,
+            pacing_priority,
             snapshot,
         }
     }
@@ -928,6 +1421,65 @@ impl StreamTx {
         }
 
         self.send_queue.handle_timeout(now);
+
+        if let Some(max_age) = self.max_queue_age {
+            if let Some((packets, bytes)) = self.send_queue.drop_stale(now, max_age) {
+                debug!(
+                    "Drop {} stale packets ({} bytes) for StreamTx with SSRC: {}",
+                    packets, bytes, self.ssrc
+                );
+                self.add_dropped(packets, bytes);
+            }
+        }
+    }
+
+    pub(crate) fn drop_early_media(&mut self, now: Instant, max_age: Duration) {
+        if let Some((packets, bytes)) = self.send_queue.drop_head(now, max_age) {
+            debug!(
+                "Drop {} early packets ({} bytes) for StreamTx with SSRC: {}",
+                packets, bytes, self.ssrc
+            );
+            self.add_dropped(packets, bytes);
+        }
+    }
+
+    fn add_dropped(&mut self, packets: usize, bytes: usize) {
+        let pending = self.pending_dropped.get_or_insert((0, 0));
+        pending.0 += packets;
+        pending.1 += bytes;
+        self.dropped_counts.0 += packets as u64;
+        self.dropped_counts.1 += bytes as u64;
+    }
+
+    pub(crate) fn poll_packets_dropped(&mut self) -> Option<PacketsDropped> {
+        let (packets, bytes) = self.pending_dropped.take()?;
+
+        Some(PacketsDropped {
+            ssrc: self.ssrc,
+            mid: self.mid,
+            rid: self.rid,
+            packets,
+            bytes,
+        })
+    }
+
+    pub(crate) fn visit_pacer_stats(&self, stats: &mut PacerStats, now: Instant) {
+        let (packets, bytes, oldest) = self.send_queue.stats(now);
+
+        stats.queue_packets += packets;
+        stats.queue_bytes += bytes;
+        stats.max_queue_time = stats.max_queue_time.max(oldest);
+        stats.dropped_packets += self.dropped_counts.0;
+        stats.dropped_bytes += self.dropped_counts.1;
+    }
+
+    pub(crate) fn visit_overhead_stats(&self, stats: &mut OverheadStats) {
+        stats.media_bytes += self.stats.bytes - self.stats.bytes_resent;
+        stats.media_packets += self.stats.packets - self.stats.packets_resent;
+        stats.rtx_bytes += self.stats.bytes_resent;
+        stats.rtx_packets += self.stats.packets_resent;
+        stats.padding_bytes += self.stats.bytes_padding;
+        stats.padding_packets += self.stats.packets_padding;
     }
 
     fn on_first_timeout(&mut self, media: &Media, config: &CodecConfig) {
@@ -967,7 +1519,55 @@ impl StreamTx {
     }
 }
 
+/// The CSRC list of outgoing packets comes from the contributing sources with audio levels.
+fn csrc_of(ext_vals: &ExtensionValues) -> CsrcList {
+    ext_vals
+        .csrc_audio_levels
+        .iter()
+        .flatten()
+        .map(|(ssrc, _)| *ssrc)
+        .collect()
+}
+
+/// Transmission time offset (RFC 5450) of a packet, in RTP time since it was captured.
+///
+/// The capture time follows from the RTP time relative to the last `write_rtp()` wallclock,
+/// which means resends are offset by the whole time since capture, not since the first send.
+fn capture_offset(
+    now: Instant,
+    header: &RtpHeader,
+    rtp_and_wallclock: Option<(u32, Instant)>,
+    clock_rate: Frequency,
+) -> i32 {
+    let Some((t, wallclock)) = rtp_and_wallclock else {
+        return 0;
+    };
+
+    let since_wallclock = MediaTime::from(now.saturating_duration_since(wallclock))
+        .rebase(clock_rate)
+        .numer() as i64;
+
+    // RTP time of the packet before the last written, allowing for wrap around.
+    let before = t.wrapping_sub(header.timestamp) as i32 as i64;
+
+    // The extension is a 24 bit signed value.
+    (since_wallclock + before).clamp(0, 0x7f_ffff) as i32
+}
+
 impl StreamTxStats {
+    fn reset(&mut self) {
+        self.bytes = 0;
+        self.bytes_resent = 0;
+        self.packets = 0;
+        self.packets_resent = 0;
+        self.firs = 0;
+        self.plis = 0;
+        self.nacks = 0;
+        self.resends_dropped = 0;
+        self.bytes_padding = 0;
+        self.packets_padding = 0;
+    }
+
     fn update_packet_counts(&mut self, bytes: u64, is_resend: bool) {
         self.packets += 1;
         self.bytes += bytes;
@@ -977,6 +1577,11 @@ impl StreamTxStats {
         }
     }
 
+    fn update_padding_counts(&mut self, bytes: u64) {
+        self.packets_padding += 1;
+        self.bytes_padding += bytes;
+    }
+
     fn increase_nacks(&mut self) {
         self.nacks += 1;
     }
@@ -1048,8 +1653,10 @@ impl StreamTxStats {
                 firs: self.firs,
                 plis: self.plis,
                 nacks: self.nacks,
+                resends_dropped: self.resends_dropped,
                 rtt: self.rtt,
                 loss,
+                remote_jitter_buffer: self.remote_jitter_buffer,
                 timestamp: now,
             },
         );
@@ -1075,3 +1682,95 @@ struct Resend {
     queued_at: Instant,
     payload_size: usize,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stopped_layer_keeps_seq_continuity() {
+        let mut tx = StreamTx::new(1.into(), None, Mid::from("v"), None, 0..=u16::MAX);
+        tx.set_layer_bitrate(Bitrate::kbps(100));
+
+        let now = Instant::now();
+
+        for seq in 0..10_u64 {
+            // Stopped for the packets 3 to 6.
+            if seq == 3 || seq == 7 {
+                tx.set_layer_active(seq == 7);
+            }
+
+            tx.write_rtp(
+                96.into(),
+                (1000 + seq).into(),
+                0,
+                now,
+                false,
+                ExtensionValues::default(),
+                true,
+                vec![1, 2, 3],
+            )
+            .unwrap();
+        }
+
+        tx.send_queue.handle_timeout(now);
+
+        let mut seqs = vec![];
+        while let Some(p) = tx.send_queue.pop(now) {
+            seqs.push(*p.seq_no);
+        }
+
+        assert_eq!(seqs, [1000, 1001, 1002, 1003, 1004, 1005]);
+    }
+
+    #[test]
+    fn ecn_delta_ignores_decreasing_counters() {
+        let mut tx = StreamTx::new(1.into(), None, Mid::from("v"), None, 0..=u16::MAX);
+
+        let feedback = |ect0, ce| EcnFeedback {
+            sender_ssrc: 2.into(),
+            ssrc: 1.into(),
+            extended_highest_seq: 0,
+            ect0,
+            ect1: 0,
+            ce,
+            not_ect: 0,
+            lost: 0,
+            duplicates: 0,
+        };
+
+        assert_eq!(tx.ecn_delta(feedback(10, 2)), (2, 12));
+        assert_eq!(tx.ecn_delta(feedback(15, 3)), (1, 6));
+
+        // A reordered feedback is ignored, but becomes the new baseline.
+        assert_eq!(tx.ecn_delta(feedback(12, 3)), (0, 0));
+        assert_eq!(tx.ecn_delta(feedback(14, 3)), (0, 2));
+    }
+
+    #[test]
+    fn max_payload_size_follows_extensions() {
+        let mut tx = StreamTx::new(1.into(), None, Mid::from("v"), None, 0..=u16::MAX);
+        let ev = ExtensionValues::default();
+
+        assert_eq!(
+            tx.max_payload_size(None, &ExtensionMap::empty(), &ev),
+            DEFAULT_MAX_PAYLOAD_SIZE
+        );
+
+        let mut exts = ExtensionMap::empty();
+        exts.set(1, Extension::TransportSequenceNumber);
+        let small = tx.max_payload_size(Some(1000), &exts, &ev);
+
+        // A larger header seen for these extensions is kept.
+        tx.max_header_exts = Some(exts.clone());
+        tx.max_header_len = 100;
+        assert!(tx.max_payload_size(Some(1000), &exts, &ev) < small);
+
+        // But not once the extensions change.
+        let mut other = exts.clone();
+        other.set(2, Extension::AbsoluteSendTime);
+        let other_size = tx.max_payload_size(Some(1000), &other, &ev);
+        assert!(other_size > tx.max_payload_size(Some(1000), &exts, &ev));
+        assert!(other_size <= small);
+    }
+}
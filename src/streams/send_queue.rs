@@ -4,7 +4,7 @@ use std::time::{Duration, Instant};
 use crate::packet::{QueuePriority, QueueSnapshot};
 use crate::util::not_happening;
 
-use super::RtpPacket;
+use super::{PendingStats, RtpPacket};
 
 #[derive(Debug)]
 pub(crate) struct SendQueue {
@@ -103,6 +103,112 @@ impl SendQueue {
         }
     }
 
+    /// Drop stale packets once the oldest packet has waited longer than max_age.
+    ///
+    /// Discardable packets, as marked by the frame marking extension, are dropped first,
+    /// regardless of age, since sending them only delays the rest. Stale packets are dropped
+    /// with the rest of their frame, to not send partial frames.
+    ///
+    /// Returns the number of dropped packets and their payload bytes.
+    pub fn drop_stale(&mut self, now: Instant, max_age: Duration) -> Option<(usize, usize)> {
+        let head = self.peek()?;
+        if now - head.timestamp <= max_age {
+            return None;
+        }
+
+        let total = &mut self.total;
+        let mut stale_time = None;
+        let mut dropped = (0, 0);
+
+        self.queue.retain(|p| {
+            // Not timestamped yet means it was just written.
+            if p.timestamp == not_happening() {
+                return true;
+            }
+
+            let is_stale = now - p.timestamp > max_age;
+            if is_stale {
+                stale_time = Some(p.time);
+            }
+
+            let is_discardable = p
+                .header
+                .ext_vals
+                .frame_marking
+                .map(|f| f.discardable)
+                .unwrap_or(false);
+
+            if is_stale || is_discardable || stale_time == Some(p.time) {
+                total.decrease(now, p.payload.len(), now - p.timestamp);
+                dropped.0 += 1;
+                dropped.1 += p.payload.len();
+                false
+            } else {
+                true
+            }
+        });
+
+        Some(dropped)
+    }
+
+    /// Drop packets older than max_age from the head of the queue.
+    ///
+    /// Unlike drop_stale, this only drops from the head, and always the rest of the last
+    /// dropped frame, which means the packets left start a frame and have no gaps.
+    ///
+    /// Returns the number of dropped packets and their payload bytes.
+    pub fn drop_head(&mut self, now: Instant, max_age: Duration) -> Option<(usize, usize)> {
+        let mut dropped = (0, 0);
+        let mut stale_time = None;
+
+        while let Some(p) = self.peek() {
+            let is_stale = now - p.timestamp > max_age;
+            if !is_stale && stale_time != Some(p.time) {
+                break;
+            }
+
+            stale_time = Some(p.time);
+
+            // Unwrap is OK, because peek() above returned a value.
+            let p = self.queue.pop_front().unwrap();
+            self.total.decrease(now, p.payload.len(), now - p.timestamp);
+            dropped.0 += 1;
+            dropped.1 += p.payload.len();
+        }
+
+        (dropped.0 > 0).then_some(dropped)
+    }
+
+    /// Number of packets, payload bytes and the time the oldest packet has waited.
+    pub(crate) fn stats(&self, now: Instant) -> (usize, usize, Option<Duration>) {
+        let oldest = self
+            .queue
+            .iter()
+            .find(|p| p.timestamp != not_happening())
+            .map(|p| now.saturating_duration_since(p.timestamp));
+
+        (self.total.unsent_count, self.total.unsent_size, oldest)
+    }
+
+    /// Snapshot of all packets in the queue, including those not yet timestamped.
+    pub(crate) fn pending(&self, now: Instant) -> PendingStats {
+        let age = |p: &RtpPacket| {
+            if p.timestamp == not_happening() {
+                // Written since the last handle_timeout.
+                Duration::ZERO
+            } else {
+                now.saturating_duration_since(p.timestamp)
+            }
+        };
+
+        PendingStats {
+            packets: self.queue.len(),
+            bytes: self.queue.iter().map(|p| p.payload.len()).sum(),
+            oldest: self.queue.front().map(age),
+            newest: self.queue.back().map(age),
+        }
+    }
+
     pub(crate) fn clear(&mut self) {
         self.queue.clear();
         self.total.clear();
@@ -200,6 +306,7 @@ impl TotalQueue {
 mod test {
     use crate::rtp_::MediaTime;
     use crate::rtp_::RtpHeader;
+    use crate::rtp_::{ExtensionValues, FrameMarking};
 
     use super::*;
 
@@ -274,6 +381,113 @@ mod test {
         assert!(queue.pop(Instant::now()).is_some());
     }
 
+    fn packet(time: u64, size: usize, discardable: bool) -> RtpPacket {
+        let header = RtpHeader {
+            ext_vals: ExtensionValues {
+                frame_marking: Some(FrameMarking {
+                    discardable,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        RtpPacket {
+            seq_no: 0.into(),
+            time: MediaTime::from_90khz(time),
+            header,
+            payload: vec![0; size],
+            timestamp: Instant::now(),
+            last_sender_info: None,
+            nackable: true,
+        }
+    }
+
+    #[test]
+    fn drop_stale() {
+        let mut queue = SendQueue::new();
+        let start = Instant::now();
+        let max_age = Duration::from_millis(100);
+
+        // Frame 1 is split over the max age.
+        queue.push(packet(1, 10, false));
+        queue.handle_timeout(start);
+        queue.push(packet(1, 10, false));
+        queue.push(packet(2, 20, true));
+        queue.push(packet(3, 30, false));
+        queue.handle_timeout(start + Duration::from_millis(50));
+
+        assert_eq!(
+            queue.drop_stale(start + Duration::from_millis(100), max_age),
+            None
+        );
+
+        // Frame 1 and the discardable frame 2 are dropped.
+        let now = start + Duration::from_millis(120);
+        assert_eq!(queue.drop_stale(now, max_age), Some((3, 40)));
+
+        assert_eq!(queue.stats(now), (1, 30, Some(Duration::from_millis(70))));
+        assert_eq!(queue.pop(now).unwrap().time, MediaTime::from_90khz(3));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drop_head() {
+        let mut queue = SendQueue::new();
+        let start = Instant::now();
+        let max_age = Duration::from_millis(100);
+
+        // Frame 2 is split over the max age, the discardable frame 3 is kept.
+        queue.push(packet(1, 10, false));
+        queue.push(packet(2, 20, false));
+        queue.handle_timeout(start);
+        queue.push(packet(2, 20, false));
+        queue.push(packet(3, 30, true));
+        queue.handle_timeout(start + Duration::from_millis(50));
+
+        assert_eq!(
+            queue.drop_head(start + Duration::from_millis(100), max_age),
+            None
+        );
+
+        let now = start + Duration::from_millis(120);
+        assert_eq!(queue.drop_head(now, max_age), Some((3, 50)));
+
+        assert_eq!(queue.stats(now), (1, 30, Some(Duration::from_millis(70))));
+        assert_eq!(queue.pop(now).unwrap().time, MediaTime::from_90khz(3));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn pending() {
+        let mut queue = SendQueue::new();
+        let start = Instant::now();
+
+        assert_eq!(queue.pending(start), PendingStats::default());
+
+        queue.push(packet(1, 10, false));
+        queue.handle_timeout(start);
+        queue.push(packet(2, 20, false));
+        queue.handle_timeout(start + Duration::from_millis(30));
+        queue.push(packet(3, 30, false));
+
+        let now = start + Duration::from_millis(50);
+        assert_eq!(
+            queue.pending(now),
+            PendingStats {
+                packets: 3,
+                bytes: 60,
+                oldest: Some(Duration::from_millis(50)),
+                newest: Some(Duration::ZERO),
+            }
+        );
+
+        queue.pop(now);
+        assert_eq!(queue.pending(now).packets, 2);
+        assert_eq!(queue.pending(now).oldest, Some(Duration::from_millis(20)));
+    }
+
     #[test]
     fn total_queue() {
         let mut total_queue = TotalQueue::default();
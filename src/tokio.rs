@@ -0,0 +1,181 @@
+//! Async adapter for running an [`Rtc`] instance on a tokio runtime.
+//!
+//! str0m itself is Sans-IO. This module provides the boilerplate of driving an `Rtc`
+//! with a UDP socket and timers, for the common case of one socket per `Rtc` instance.
+//!
+//! Requires the `tokio` feature.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), str0m::RtcError> {
+//! use std::time::Instant;
+//! use str0m::{Candidate, Event, Rtc};
+//! use str0m::tokio::{run, Command};
+//! use tokio::net::UdpSocket;
+//! use tokio::sync::mpsc;
+//!
+//! // Bind to the address of a network interface. A host candidate can't
+//! // use an unspecified address such as 0.0.0.0.
+//! let socket = UdpSocket::bind("192.168.1.10:0").await?;
+//!
+//! let mut rtc = Rtc::new();
+//! let candidate = Candidate::host(socket.local_addr()?, "udp")?;
+//! rtc.add_local_candidate(candidate);
+//!
+//! let (event_tx, mut event_rx) = mpsc::channel::<Event>(100);
+//! let (command_tx, command_rx) = mpsc::channel::<Command>(100);
+//!
+//! tokio::spawn(run(rtc, socket, event_tx, command_rx));
+//!
+//! while let Some(event) = event_rx.recv().await {
+//!     if let Event::ChannelData(data) = event {
+//!         // Use a command to get at the Rtc instance.
+//!         let command: Command = Box::new(move |rtc: &mut Rtc| {
+//!             if let Some(mut channel) = rtc.channel(data.id) {
+//!                 let _ = channel.write(data.binary, &data.data);
+//!             }
+//!         });
+//!         if command_tx.send(command).await.is_err() {
+//!             break;
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use ::tokio::net::UdpSocket;
+use ::tokio::sync::mpsc;
+use ::tokio::time::sleep_until;
+
+use crate::net::{Protocol, Receive};
+use crate::{Event, Input, Output, Rtc, RtcError};
+
+/// A change to make to the [`Rtc`] instance owned by [`run()`].
+///
+/// Since `run` owns the instance, this is the way to do things like writing media,
+/// data channel data, or SDP negotiation.
+pub type Command = Box<dyn FnOnce(&mut Rtc) + Send>;
+
+/// Drive the [`Rtc`] instance using the UDP socket.
+///
+/// Events are sent to `events`, and `commands` are applied to the instance as they arrive.
+///
+/// This runs until the `Rtc` is disconnected (see [`Rtc::is_alive()`]), or either of the
+/// channels is closed, in which case it returns `Ok(())`. An error from the `Rtc` instance,
+/// or a socket error that isn't transient, ends the loop with that error. A failed send is
+/// treated like a lost packet.
+///
+/// The socket should be bound to the address of the local candidate. If it is bound to
+/// an unspecified address (such as `0.0.0.0`), the incoming datagrams are taken to be for
+/// the single local candidate with the socket's port.
+pub async fn run(
+    mut rtc: Rtc,
+    socket: UdpSocket,
+    events: mpsc::Sender<Event>,
+    mut commands: mpsc::Receiver<Command>,
+) -> Result<(), RtcError> {
+    let local_addr = socket.local_addr()?;
+
+    // Buffer for incoming datagrams.
+    let mut buf = vec![0; 2000];
+
+    loop {
+        let timeout = match rtc.poll_output()? {
+            Output::Timeout(v) => v,
+            Output::Transmit(v) => {
+                if let Err(e) = socket.send_to(&v.contents, v.destination).await {
+                    debug!("Failed to send to {}: {:?}", v.destination, e);
+                }
+                continue;
+            }
+            Output::Event(v) => {
+                if events.send(v).await.is_err() {
+                    return Ok(());
+                }
+                continue;
+            }
+        };
+
+        if !rtc.is_alive() {
+            return Ok(());
+        }
+
+        ::tokio::select! {
+            _ = sleep_until(timeout.into()) => {
+                rtc.handle_input(Input::Timeout(Instant::now()))?;
+            }
+            r = socket.recv_from(&mut buf) => {
+                match r {
+                    Ok((n, source)) => handle_receive(&mut rtc, source, local_addr, &buf[..n])?,
+                    Err(e) if is_transient(&e) => debug!("Transient receive error: {:?}", e),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            c = commands.recv() => {
+                let Some(command) = c else {
+                    return Ok(());
+                };
+                command(&mut rtc);
+            }
+        }
+    }
+}
+
+/// Receive errors that don't mean the socket is broken. For example, on some platforms an
+/// ICMP port unreachable for an earlier send shows up as a connection reset.
+fn is_transient(e: &io::Error) -> bool {
+    use io::ErrorKind::*;
+    matches!(
+        e.kind(),
+        WouldBlock
+            | Interrupted
+            | TimedOut
+            | ConnectionRefused
+            | ConnectionReset
+            | ConnectionAborted
+    )
+}
+
+fn handle_receive(
+    rtc: &mut Rtc,
+    source: SocketAddr,
+    local_addr: SocketAddr,
+    buf: &[u8],
+) -> Result<(), RtcError> {
+    let Some(destination) = local_destination(rtc, local_addr) else {
+        debug!("No single local candidate for: {}", local_addr);
+        return Ok(());
+    };
+
+    let Ok(receive) = Receive::new(Protocol::Udp, source, destination, buf) else {
+        // Not a datagram str0m knows about.
+        trace!("Ignore unknown datagram from: {}", source);
+        return Ok(());
+    };
+
+    rtc.handle_input(Input::Receive(Instant::now(), receive))
+}
+
+/// The address a datagram arrived at. A socket bound to an unspecified address doesn't tell,
+/// in which case it's the local candidate with the socket's port, as long as there is
+/// only one.
+fn local_destination(rtc: &Rtc, local_addr: SocketAddr) -> Option<SocketAddr> {
+    if !local_addr.ip().is_unspecified() {
+        return Some(local_addr);
+    }
+
+    let mut bases = rtc
+        .ice
+        .local_candidates()
+        .iter()
+        .filter(|c| c.proto() == Protocol::Udp && c.base().port() == local_addr.port())
+        .map(|c| c.base());
+
+    let first = bases.next()?;
+
+    bases.all(|b| b == first).then_some(first)
+}
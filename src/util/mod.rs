@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::time::{Duration, Instant};
 
 mod bit_pattern;
@@ -89,8 +90,8 @@ impl NonCryptographicRng {
     }
 
     #[inline(always)]
-    pub fn u16() -> u16 {
-        fastrand::u16(..)
+    pub fn u16_in(range: RangeInclusive<u16>) -> u16 {
+        fastrand::u16(range)
     }
 
     #[inline(always)]
@@ -44,6 +44,16 @@ where
         self.value
     }
 
+    /// Returns the sum of the values no older than max_time at `now`
+    /// Unlike sum(), this is correct even if there has been no push() for a while
+    pub fn sum_at(&self, now: Instant) -> T {
+        self.history
+            .iter()
+            .filter(|(t, _)| now.saturating_duration_since(*t) <= self.max_time)
+            .map(|(_, v)| *v)
+            .sum()
+    }
+
     fn drain(&mut self, t: Instant) -> Option<()> {
         while t.duration_since(self.history.front()?.0) > self.max_time {
             if let Some((_, v)) = self.history.pop_front() {
@@ -78,5 +88,7 @@ mod test {
         assert_eq!(h.sum(), 55);
         h.push(now, 0);
         assert_eq!(h.sum(), 33);
+        assert_eq!(h.sum_at(now), 22);
+        assert_eq!(h.sum_at(now + Duration::from_millis(600)), 0);
     }
 }
@@ -0,0 +1,163 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Event, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn add_recvonly() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let ufrag_first = ufrag(&offer.to_sdp_string());
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    run(&mut l, &mut r, mid_audio, None, Duration::from_secs(2))?;
+
+    let ice_events = |t: &TestRtc| {
+        t.events
+            .iter()
+            .filter(|(_, e)| matches!(e, Event::IceConnectionStateChange(_)))
+            .count()
+    };
+    let ice_before = (ice_events(&l), ice_events(&r));
+
+    // A viewer joins, add a recvonly track without disturbing the audio.
+    let mut change = l.sdp_api();
+    let mid_video = change.add_media(MediaKind::Video, Direction::RecvOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    // Bundled with the existing transport, no ICE restart.
+    let sdp = offer.to_sdp_string();
+    let bundle = format!("a=group:BUNDLE {} {}\r\n", mid_audio, mid_video);
+    assert!(sdp.contains(&bundle), "{}", sdp);
+    assert_eq!(ufrag(&sdp), ufrag_first);
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    assert!(answer.to_sdp_string().contains("a=sendonly"));
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    assert_eq!(l.media(mid_audio).unwrap().direction(), Direction::SendRecv);
+    assert_eq!(l.media(mid_video).unwrap().direction(), Direction::RecvOnly);
+    assert_eq!(r.media(mid_video).unwrap().direction(), Direction::SendOnly);
+
+    // No video arrives before the remote sends any.
+    let start_video = l.duration();
+    run(&mut l, &mut r, mid_audio, None, Duration::from_millis(500))?;
+    assert_eq!(count(&l, mid_video, start_video), 0);
+
+    let start = l.duration();
+    run(
+        &mut l,
+        &mut r,
+        mid_audio,
+        Some(mid_video),
+        Duration::from_secs(2),
+    )?;
+
+    // The transport is unchanged.
+    assert!(l.is_connected() && r.is_connected());
+    assert_eq!((ice_events(&l), ice_events(&r)), ice_before);
+
+    // Audio continues both ways, and video flows from R to L.
+    assert!(count(&l, mid_audio, start) > 80);
+    assert!(count(&r, mid_audio, start) > 80);
+    assert!(count(&l, mid_video, start) > 80);
+    assert_eq!(count(&r, mid_video, Duration::ZERO), 0);
+
+    // The StreamRx was activated by the inbound packets.
+    assert!(l.events.iter().any(|(_, e)| matches!(
+        e,
+        Event::StreamPaused(p) if p.mid == mid_video && !p.paused
+    )));
+
+    Ok(())
+}
+
+fn ufrag(sdp: &str) -> String {
+    let line = sdp.lines().find(|l| l.starts_with("a=ice-ufrag:"));
+    line.unwrap().to_string()
+}
+
+fn count(t: &TestRtc, mid: Mid, since: Duration) -> usize {
+    t.events
+        .iter()
+        .filter(|(at, e)| {
+            *at >= t.start + since && matches!(e, Event::MediaData(d) if d.mid == mid)
+        })
+        .count()
+}
+
+fn run(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    mid_audio: Mid,
+    mid_video: Option<Mid>,
+    duration: Duration,
+) -> Result<(), RtcError> {
+    let pt_audio = l.params_opus().pt();
+    let pt_video = r.params_vp8().pt();
+
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid_audio)
+                .unwrap()
+                .write(pt_audio, wallclock, time, vec![1_u8; 80])?;
+
+            let wallclock = r.start + r.duration();
+            let time = r.duration().into();
+            r.writer(mid_audio)
+                .unwrap()
+                .write(pt_audio, wallclock, time, vec![2_u8; 80])?;
+
+            if let Some(mid) = mid_video {
+                let time = r.duration().into();
+                r.writer(mid)
+                    .unwrap()
+                    .write(pt_video, wallclock, time, vec![3_u8; 800])?;
+            }
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
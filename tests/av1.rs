@@ -0,0 +1,111 @@
+use std::net::Ipv4Addr;
+
+use str0m::format::{Av1CodecExtra, Codec, CodecExtra, FormatParams};
+use str0m::media::{Direction, Frequency, MediaKind};
+use str0m::rtp::ExtensionValues;
+use str0m::{Candidate, Event, Rtc, RtcConfig, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+// OBU_FRAME without and with size field.
+const FRAME: u8 = 6 << 3;
+const FRAME_WITH_SIZE: u8 = FRAME | 0b0000_0010;
+
+fn av1_config() -> RtcConfig {
+    let mut config = Rtc::builder().clear_codecs();
+    config.codec_config().add_config(
+        45.into(),
+        None,
+        Codec::Av1,
+        Frequency::NINETY_KHZ,
+        None,
+        FormatParams::default(),
+    );
+    config
+}
+
+#[test]
+pub fn av1_depacketized() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), av1_config().build());
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), av1_config().build());
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let params = l.media(mid).unwrap().remote_pts().to_vec();
+    assert_eq!(params, vec![45.into()]);
+
+    // One OBU fragmented over two packets. The first starts a new coded video
+    // sequence (N) and continues in the next (Y), which continues the first (Z).
+    let packets: [(bool, &[u8]); 2] = [
+        (false, &[0b0101_1000, FRAME, 1, 2]),
+        (true, &[0b1001_0000, 3, 4]),
+    ];
+
+    for (index, (marker, data)) in packets.into_iter().enumerate() {
+        let wallclock = l.start + l.duration();
+
+        let mut direct = l.direct_api();
+        let tx = direct.stream_tx_by_mid(mid, None).unwrap();
+        tx.write_rtp(
+            45.into(),
+            (47_000 + index as u64).into(),
+            90_000,
+            wallclock,
+            marker,
+            ExtensionValues::default(),
+            true,
+            data.to_vec(),
+        )
+        .unwrap();
+
+        progress(&mut l, &mut r)?;
+    }
+
+    for _ in 0..10 {
+        progress(&mut l, &mut r)?;
+    }
+
+    let data: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::MediaData(d) => Some(d),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].params.spec().codec, Codec::Av1);
+    assert_eq!(data[0].data, &[FRAME_WITH_SIZE, 4, 1, 2, 3, 4]);
+    assert_eq!(
+        data[0].codec_extra,
+        CodecExtra::Av1(Av1CodecExtra { is_keyframe: true })
+    );
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use str0m::bwe::{BandwidthEstimator, Bitrate, BweKind, GoogCc, PacketFeedback};
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+/// Estimates a fixed bitrate, counting the feedback it gets.
+struct Fixed {
+    bitrate: Bitrate,
+    received: Arc<AtomicUsize>,
+    next: Option<Instant>,
+}
+
+impl BandwidthEstimator for Fixed {
+    fn handle_feedback(&mut self, feedback: &[PacketFeedback], _now: Instant) {
+        let received = feedback.iter().filter(|f| f.recv_time.is_some()).count();
+        self.received.fetch_add(received, Ordering::Relaxed);
+    }
+
+    fn handle_timeout(&mut self, now: Instant) {
+        self.next = Some(now + Duration::from_millis(100));
+    }
+
+    fn poll_timeout(&self) -> Option<Instant> {
+        self.next
+    }
+
+    fn estimate(&self) -> Option<Bitrate> {
+        Some(self.bitrate)
+    }
+
+    fn reset(&mut self, init_bitrate: Bitrate) {
+        self.bitrate = init_bitrate;
+    }
+}
+
+/// Caps the estimate of the built-in estimator.
+struct Capped {
+    inner: GoogCc,
+    max: Bitrate,
+}
+
+impl BandwidthEstimator for Capped {
+    fn handle_feedback(&mut self, feedback: &[PacketFeedback], now: Instant) {
+        self.inner.handle_feedback(feedback, now);
+    }
+
+    fn handle_timeout(&mut self, now: Instant) {
+        self.inner.handle_timeout(now);
+    }
+
+    fn poll_timeout(&self) -> Option<Instant> {
+        self.inner.poll_timeout()
+    }
+
+    fn estimate(&self) -> Option<Bitrate> {
+        self.inner.estimate().map(|e| e.min(self.max))
+    }
+
+    fn reset(&mut self, init_bitrate: Bitrate) {
+        self.inner.reset(init_bitrate);
+    }
+}
+
+#[test]
+pub fn bwe_custom() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, mid) = connect()?;
+
+    let received = Arc::new(AtomicUsize::new(0));
+    l.bwe().set_estimator(Fixed {
+        bitrate: Bitrate::mbps(7),
+        received: received.clone(),
+        next: None,
+    });
+    l.bwe().set_current_bitrate(Bitrate::kbps(250));
+
+    run(&mut l, &mut r, mid, Duration::from_secs(2))?;
+
+    // All sent packets, apart from those with feedback in flight, are reported.
+    let packets = received.load(Ordering::Relaxed);
+    assert!(packets > 40, "{}", packets);
+
+    let estimates: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::EgressBitrateEstimate(BweKind::Twcc(v)) => Some(*v),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(estimates, vec![Bitrate::mbps(7)]);
+
+    // Reset goes to the custom estimator.
+    l.bwe().reset(Bitrate::mbps(1));
+    run(&mut l, &mut r, mid, Duration::from_millis(200))?;
+
+    let last = l.events.iter().rev().find_map(|(_, e)| match e {
+        Event::EgressBitrateEstimate(BweKind::Twcc(v)) => Some(*v),
+        _ => None,
+    });
+    assert_eq!(last, Some(Bitrate::mbps(1)));
+
+    Ok(())
+}
+
+#[test]
+pub fn bwe_wrap_googcc() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, mid) = connect()?;
+
+    l.bwe().set_estimator(Capped {
+        inner: GoogCc::new(Bitrate::kbps(300)),
+        max: Bitrate::kbps(200),
+    });
+    l.bwe().set_current_bitrate(Bitrate::kbps(250));
+
+    run(&mut l, &mut r, mid, Duration::from_secs(2))?;
+
+    let estimates: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::EgressBitrateEstimate(BweKind::Twcc(v)) => Some(*v),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(estimates, vec![Bitrate::kbps(200)]);
+
+    Ok(())
+}
+
+fn connect() -> Result<(TestRtc, TestRtc, Mid), RtcError> {
+    let rtc_l = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    Ok((l, r, mid))
+}
+
+fn run(l: &mut TestRtc, r: &mut TestRtc, mid: Mid, duration: Duration) -> Result<(), RtcError> {
+    let pt = l.params_vp8().pt();
+
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(32);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid)
+                .unwrap()
+                .write(pt, wallclock, time, vec![1_u8; 1000])?;
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::bwe::{Bitrate, BweKind};
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn bwe_probe() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    l.bwe().set_current_bitrate(Bitrate::kbps(250));
+    l.bwe().set_desired_bitrate(Bitrate::mbps(3));
+
+    run(&mut l, &mut r, mid, Duration::from_secs(2))?;
+
+    let before = last_estimate(&l, Duration::MAX).expect("an estimate before the probe");
+    assert!(before < Bitrate::mbps(1), "{}", before);
+
+    let probe_start = l.duration();
+    l.bwe()
+        .start_probe(Bitrate::mbps(2), Duration::from_millis(300));
+
+    run(&mut l, &mut r, mid, Duration::from_millis(600))?;
+
+    // The probe raises the estimate far quicker than the delay based estimator would.
+    let after = last_estimate(&l, probe_start + Duration::from_millis(600)).unwrap();
+    assert!(after > Bitrate::kbps(1500), "{} -> {}", before, after);
+
+    // The probe is sent as padding.
+    let overhead = l.overhead_stats();
+    assert!(overhead.padding_packets > 0);
+    assert!(overhead.padding_percent().unwrap() > 0.0);
+
+    Ok(())
+}
+
+fn last_estimate(t: &TestRtc, until: Duration) -> Option<Bitrate> {
+    t.events
+        .iter()
+        .filter(|(at, _)| at.duration_since(t.start) <= until)
+        .filter_map(|(_, e)| match e {
+            Event::EgressBitrateEstimate(BweKind::Twcc(v)) => Some(*v),
+            _ => None,
+        })
+        .last()
+}
+
+fn run(l: &mut TestRtc, r: &mut TestRtc, mid: Mid, duration: Duration) -> Result<(), RtcError> {
+    let pt = l.params_vp8().pt();
+
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        // 250kbps in 1000 byte frames every 32ms.
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(32);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid)
+                .unwrap()
+                .write(pt, wallclock, time, vec![1_u8; 1000])?;
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
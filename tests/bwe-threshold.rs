@@ -0,0 +1,105 @@
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+use str0m::bwe::{BandwidthEstimator, Bitrate, CrossingDirection, PacketFeedback};
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+/// Estimates whatever it was last reset to.
+struct Fixed(Bitrate);
+
+impl BandwidthEstimator for Fixed {
+    fn handle_feedback(&mut self, _feedback: &[PacketFeedback], _now: Instant) {}
+
+    fn handle_timeout(&mut self, _now: Instant) {}
+
+    fn poll_timeout(&self) -> Option<Instant> {
+        None
+    }
+
+    fn estimate(&self) -> Option<Bitrate> {
+        Some(self.0)
+    }
+
+    fn reset(&mut self, init_bitrate: Bitrate) {
+        self.0 = init_bitrate;
+    }
+}
+
+#[test]
+pub fn bwe_threshold() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    l.bwe().set_estimator(Fixed(Bitrate::mbps(1)));
+    l.bwe().add_threshold(Bitrate::kbps(500), 0.1);
+    l.bwe().add_threshold(Bitrate::mbps(2), 0.1);
+
+    // The first estimate puts us between the thresholds.
+    progress(&mut l, &mut r)?;
+    assert!(!l
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::BweThresholdCrossed(_))));
+
+    let steps = [
+        // Within the hysteresis of 500kbit/s, no crossing.
+        Bitrate::kbps(460),
+        Bitrate::kbps(400),
+        Bitrate::kbps(540),
+        Bitrate::kbps(600),
+        Bitrate::mbps(3),
+        Bitrate::kbps(300),
+    ];
+
+    for step in steps {
+        l.bwe().reset(step);
+
+        let end = l.duration() + Duration::from_millis(100);
+        while l.duration() < end {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    let crossings: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::BweThresholdCrossed(c) => Some((c.threshold, c.direction, c.estimate)),
+            _ => None,
+        })
+        .collect();
+
+    use CrossingDirection::*;
+    assert_eq!(
+        crossings,
+        vec![
+            (Bitrate::kbps(500), Down, Bitrate::kbps(400)),
+            (Bitrate::kbps(500), Up, Bitrate::kbps(600)),
+            (Bitrate::mbps(2), Up, Bitrate::mbps(3)),
+            (Bitrate::kbps(500), Down, Bitrate::kbps(300)),
+            (Bitrate::mbps(2), Down, Bitrate::kbps(300)),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::{ColorSpace, Extension, HdrMetadata};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn color_space_hdr() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder()
+        .set_extension(8, Extension::ColorSpace)
+        .build();
+    let rtc_r = Rtc::builder()
+        .set_extension(8, Extension::ColorSpace)
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // BT.2020 PQ with mastering display metadata.
+    let color_space = ColorSpace {
+        primaries: 9,
+        transfer: 16,
+        matrix: 9,
+        range: 1,
+        hdr_metadata: Some(HdrMetadata {
+            primary_r: (35400, 14600),
+            primary_g: (8500, 39850),
+            primary_b: (6550, 2300),
+            white_point: (15635, 16450),
+            luminance_max: 1000,
+            luminance_min: 50,
+            max_content_light_level: 1000,
+            max_frame_average_light_level: 400,
+        }),
+        ..Default::default()
+    };
+
+    let data = vec![1_u8; 80];
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .color_space(color_space.clone())
+            .write(pt, wallclock, time, data.clone())?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let media: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| {
+            if let Event::MediaData(v) = e {
+                Some(v)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    assert!(media.len() > 20);
+
+    for m in media {
+        assert_eq!(m.ext_vals.color_space.as_deref(), Some(&color_space));
+    }
+
+    Ok(())
+}
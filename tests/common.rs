@@ -9,6 +9,7 @@ use pcap_file::pcap::PcapReader;
 use str0m::change::SdpApi;
 use str0m::format::Codec;
 use str0m::format::PayloadParams;
+use str0m::net::Ecn;
 use str0m::net::Protocol;
 use str0m::net::Receive;
 use str0m::rtp::ExtensionMap;
@@ -96,12 +97,7 @@ pub fn progress(l: &mut TestRtc, r: &mut TestRtc) -> Result<(), RtcError> {
                 let data = v.contents;
                 let input = Input::Receive(
                     f.last,
-                    Receive {
-                        proto: v.proto,
-                        source: v.source,
-                        destination: v.destination,
-                        contents: (&*data).try_into()?,
-                    },
+                    Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(v.ecn),
                 );
                 t.span.in_scope(|| t.rtc.handle_input(input))?;
             }
@@ -136,12 +132,40 @@ pub fn progress_with_loss(l: &mut TestRtc, r: &mut TestRtc, loss: f32) -> Result
                 let data = v.contents;
                 let input = Input::Receive(
                     f.last,
-                    Receive {
-                        proto: v.proto,
-                        source: v.source,
-                        destination: v.destination,
-                        contents: (&*data).try_into()?,
-                    },
+                    Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(v.ecn),
+                );
+                t.span.in_scope(|| t.rtc.handle_input(input))?;
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Like [`progress`], but ECN capable packets arrive marked CE (congestion experienced).
+pub fn progress_with_ce(l: &mut TestRtc, r: &mut TestRtc) -> Result<(), RtcError> {
+    let (f, t) = if l.last < r.last { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                let ecn = v.ecn.map(|e| if e == Ecn::NotEct { e } else { Ecn::Ce });
+
+                let data = v.contents;
+                let input = Input::Receive(
+                    f.last,
+                    Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(ecn),
                 );
                 t.span.in_scope(|| t.rtc.handle_input(input))?;
             }
@@ -158,6 +182,44 @@ pub fn progress_with_loss(l: &mut TestRtc, r: &mut TestRtc, loss: f32) -> Result
 ///
 /// The closure is passed the [`SdpApi`] for the offer side to make any changes, these are then
 /// applied locally and the offer is negotiated with the answerer.
+/// Like [`progress`], but flips a bit in the auth tag of RTP packets when `tamper` is set.
+pub fn progress_tampered(l: &mut TestRtc, r: &mut TestRtc, tamper: bool) -> Result<(), RtcError> {
+    let (f, t) = if l.last < r.last { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                let mut data = v.contents.to_vec();
+
+                let is_rtp =
+                    data.len() > 12 && data[0] >> 6 == 2 && !(192..=223).contains(&data[1]);
+                if tamper && is_rtp {
+                    *data.last_mut().unwrap() ^= 1;
+                }
+
+                let input = Input::Receive(
+                    f.last,
+                    Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(v.ecn),
+                );
+                t.span.in_scope(|| t.rtc.handle_input(input))?;
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn negotiate<F, R>(offerer: &mut TestRtc, answerer: &mut TestRtc, mut do_change: F) -> R
 where
     F: FnMut(&mut SdpApi) -> R,
@@ -231,6 +293,10 @@ pub fn connect_l_r() -> (TestRtc, TestRtc) {
         .set_reordering_size_audio(0)
         .build();
 
+    connect_l_r_with_rtc(rtc1, rtc2)
+}
+
+pub fn connect_l_r_with_rtc(rtc1: Rtc, rtc2: Rtc) -> (TestRtc, TestRtc) {
     let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc1);
     let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc2);
 
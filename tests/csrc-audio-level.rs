@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{Extension, ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn csrc_audio_level() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_extension(9, Extension::CsrcAudioLevel)
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_extension(9, Extension::CsrcAudioLevel)
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    for index in 0..50 {
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        // A mix of two speakers, where the second one goes quiet halfway through.
+        let quiet = if index < 25 { -30 } else { -127 };
+        let exts = ExtensionValues {
+            csrc_audio_levels: Some(vec![(1000.into(), -20), (2000.into(), quiet)].into()),
+            ..Default::default()
+        };
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                exts,
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    let packets: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(packets.len(), 50);
+
+    for (index, p) in packets.iter().enumerate() {
+        assert_eq!(*p.header.csrc, [1000.into(), 2000.into()]);
+        let quiet = if index < 25 { -30 } else { -127 };
+        assert_eq!(
+            p.header.ext_vals.csrc_audio_levels.as_deref(),
+            Some(&[(1000.into(), -20), (2000.into(), quiet)][..])
+        );
+        assert_eq!(p.payload, vec![1, 2, 3, 4]);
+    }
+
+    Ok(())
+}
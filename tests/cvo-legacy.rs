@@ -0,0 +1,50 @@
+use str0m::change::SdpOffer;
+use str0m::media::{Direction, MediaKind};
+use str0m::{Rtc, RtcError};
+
+fn answer_to(munge: impl Fn(&str) -> String) -> Result<String, RtcError> {
+    let mut l = Rtc::new();
+    let mut r = Rtc::new();
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, _) = change.apply().unwrap();
+
+    let offer = munge(&offer.to_sdp_string());
+    let offer = SdpOffer::from_sdp_string(&offer)?;
+
+    let answer = r.sdp_api().accept_offer(offer)?;
+
+    Ok(answer.to_sdp_string())
+}
+
+#[test]
+pub fn cvo_legacy_only() -> Result<(), RtcError> {
+    let answer = answer_to(|sdp| {
+        sdp.replace(
+            "a=extmap:13 urn:3gpp:video-orientation\r\n",
+            "a=extmap:7 urn:3gpp:video-orientation:6\r\n",
+        )
+    })?;
+
+    assert!(answer.contains("a=extmap:7 urn:3gpp:video-orientation:6\r\n"));
+    assert!(!answer.contains("urn:3gpp:video-orientation\r\n"));
+
+    Ok(())
+}
+
+#[test]
+pub fn cvo_both_variants() -> Result<(), RtcError> {
+    let answer = answer_to(|sdp| {
+        sdp.replace(
+            "a=extmap:13 urn:3gpp:video-orientation\r\n",
+            "a=extmap:7 urn:3gpp:video-orientation:6\r\n\
+             a=extmap:13 urn:3gpp:video-orientation\r\n",
+        )
+    })?;
+
+    assert!(answer.contains("a=extmap:13 urn:3gpp:video-orientation\r\n"));
+    assert!(!answer.contains("urn:3gpp:video-orientation:6"));
+
+    Ok(())
+}
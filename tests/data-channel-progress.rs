@@ -0,0 +1,171 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::channel::{ChannelConfig, DeliveryProgress, Reliability};
+use str0m::{Candidate, Event, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, progress_with_loss, TestRtc};
+
+#[test]
+pub fn data_channel_delivery_progress() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let cid = change.add_channel("My little channel".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        let open = l
+            .events
+            .iter()
+            .any(|(_, e)| matches!(e, Event::ChannelOpen(id, _) if *id == cid));
+        if open {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let mut chan = l.channel(cid).unwrap();
+    assert_eq!(chan.delivery_progress(), Some(DeliveryProgress::default()));
+
+    for _ in 0..10 {
+        chan.write(true, &[42; 100]).expect("to write");
+    }
+
+    // Nothing is acked before it has been sent.
+    let p = chan.delivery_progress().unwrap();
+    assert_eq!(p.written, 1000);
+    assert_eq!(p.acked, 0);
+
+    let start = l.duration();
+    loop {
+        progress(&mut l, &mut r)?;
+
+        let p = l.channel(cid).unwrap().delivery_progress().unwrap();
+        if p.acked == p.written {
+            break;
+        }
+
+        assert!(l.duration() - start < Duration::from_secs(5), "{:?}", p);
+    }
+
+    let received: usize = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::ChannelData(d) => Some(d.data.len()),
+            _ => None,
+        })
+        .sum();
+    assert_eq!(received, 1000);
+
+    Ok(())
+}
+
+#[test]
+pub fn data_channel_reliability_stats() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    l.add_remote_candidate(host2.clone());
+    r.add_local_candidate(host2);
+    r.add_remote_candidate(host1);
+
+    let finger_l = l.direct_api().local_dtls_fingerprint();
+    let finger_r = r.direct_api().local_dtls_fingerprint();
+
+    l.direct_api().set_remote_fingerprint(finger_r);
+    r.direct_api().set_remote_fingerprint(finger_l);
+
+    let creds_l = l.direct_api().local_ice_credentials();
+    let creds_r = r.direct_api().local_ice_credentials();
+
+    l.direct_api().set_remote_ice_credentials(creds_r);
+    r.direct_api().set_remote_ice_credentials(creds_l);
+
+    l.direct_api().set_ice_controlling(true);
+    r.direct_api().set_ice_controlling(false);
+
+    l.direct_api().start_dtls(true).unwrap();
+    r.direct_api().start_dtls(false).unwrap();
+
+    l.direct_api().start_sctp(true);
+    r.direct_api().start_sctp(false);
+
+    let config = ChannelConfig {
+        negotiated: Some(1),
+        label: "game-state".into(),
+        ordered: false,
+        reliability: Reliability::MaxRetransmits { retransmits: 0 },
+        ..Default::default()
+    };
+    let cid = l.direct_api().create_data_channel(config.clone());
+    r.direct_api().create_data_channel(config);
+
+    loop {
+        if l.channel(cid).is_some() && r.channel(cid).is_some() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+
+        assert!(l.duration() < Duration::from_secs(5), "channel to open");
+    }
+
+    let stats = l.channel(cid).unwrap().reliability_stats().unwrap();
+    assert_eq!(stats.delivered + stats.abandoned, 0);
+
+    // Without retransmits, lost messages are abandoned.
+    const COUNT: u64 = 100;
+    for _ in 0..COUNT {
+        l.channel(cid)
+            .unwrap()
+            .write(true, &[42; 100])
+            .expect("to write");
+        progress_with_loss(&mut l, &mut r, 0.2)?;
+    }
+
+    let start = l.duration();
+    let stats = loop {
+        progress(&mut l, &mut r)?;
+
+        let stats = l.channel(cid).unwrap().reliability_stats().unwrap();
+        if stats.delivered + stats.abandoned == COUNT {
+            break stats;
+        }
+
+        assert!(l.duration() - start < Duration::from_secs(5), "{:?}", stats);
+    };
+
+    let received = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::ChannelData(_)))
+        .count() as u64;
+
+    assert!(stats.abandoned > 0, "{:?}", stats);
+    assert!(stats.delivered > 0, "{:?}", stats);
+
+    // Delivered messages arrived, some of the abandoned might also have.
+    assert!(received >= stats.delivered, "{} {:?}", received, stats);
+    assert!(received <= COUNT);
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use str0m::media::{MediaKind, Mid};
+use str0m::rtp::{DemuxBy, DemuxPolicy, ExtensionValues};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress_tampered, TestRtc};
+
+#[test]
+pub fn demux_default_drops_without_mid() -> Result<(), RtcError> {
+    init_log();
+
+    let r = Rtc::builder().set_rtp_mode(true).build();
+    let (mut l, mut r) = setup(r);
+
+    send(&mut l, &mut r, 0..10, false)?;
+    assert_eq!(received(&r), vec![]);
+
+    Ok(())
+}
+
+#[test]
+pub fn demux_by_pt() -> Result<(), RtcError> {
+    init_log();
+
+    let policy = DemuxPolicy {
+        order: vec![DemuxBy::MidRid, DemuxBy::MidPt, DemuxBy::Pt],
+        ..Default::default()
+    };
+    let r = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_demux_policy(policy)
+        .build();
+    let (mut l, mut r) = setup(r);
+
+    send(&mut l, &mut r, 0..10, false)?;
+    assert_eq!(received(&r), (47_000..47_010).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+pub fn demux_buffer_until_mapped() -> Result<(), RtcError> {
+    init_log();
+
+    let policy = DemuxPolicy {
+        buffer_packets: 3,
+        buffer_time: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let r = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_demux_policy(policy)
+        .build();
+    let (mut l, mut r) = setup(r);
+
+    send(&mut l, &mut r, 0..5, false)?;
+    assert_eq!(received(&r), vec![]);
+
+    // Once the SSRC is known, the buffered packets are handled before the next packet.
+    // Only the last 3 fit in the buffer.
+    r.direct_api()
+        .expect_stream_rx(SSRC.into(), None, MID.into(), None);
+    send(&mut l, &mut r, 5..8, false)?;
+
+    assert_eq!(received(&r), (47_002..47_008).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+pub fn demux_buffer_drops_unauthenticated() -> Result<(), RtcError> {
+    init_log();
+
+    let policy = DemuxPolicy {
+        buffer_packets: 3,
+        buffer_time: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let r = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_demux_policy(policy)
+        .build();
+    let (mut l, mut r) = setup(r);
+
+    // Packets failing SRTP authentication are not buffered.
+    send(&mut l, &mut r, 0..3, true)?;
+    assert_eq!(r.rtc.transport_stats().srtp_auth_failures, 3);
+
+    r.direct_api()
+        .expect_stream_rx(SSRC.into(), None, MID.into(), None);
+    send(&mut l, &mut r, 3..5, false)?;
+
+    assert_eq!(received(&r), vec![47_003, 47_004]);
+
+    Ok(())
+}
+
+const MID: &str = "aud";
+const SSRC: u32 = 42;
+
+/// Connect R with a peer that doesn't send the mid header extension.
+fn setup(rtc_r: Rtc) -> (TestRtc, TestRtc) {
+    let rtc_l = Rtc::builder()
+        .set_rtp_mode(true)
+        .clear_extension_map()
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc_l, rtc_r);
+
+    let mid: Mid = MID.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api()
+        .declare_stream_tx(SSRC.into(), None, mid, None);
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+
+    // A video media doesn't make the audio PT ambiguous.
+    r.direct_api().declare_media("vid".into(), MediaKind::Video);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    (l, r)
+}
+
+fn send(l: &mut TestRtc, r: &mut TestRtc, range: Range<u64>, tamper: bool) -> Result<(), RtcError> {
+    let pt = l.params_opus().pt();
+
+    for i in range {
+        let wallclock = l.start + l.duration();
+        let mut direct = l.direct_api();
+        let stream = direct.stream_tx(&SSRC.into()).unwrap();
+
+        stream
+            .write_rtp(
+                pt,
+                (47_000 + i).into(),
+                47_000_000 + i as u32 * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        progress_for(l, r, Duration::from_millis(20), tamper)?;
+    }
+
+    progress_for(l, r, Duration::from_millis(200), tamper)
+}
+
+/// Sequence numbers of the received packets.
+fn received(r: &TestRtc) -> Vec<u64> {
+    r.events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(*p.seq_no),
+            _ => None,
+        })
+        .collect()
+}
+
+fn progress_for(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    duration: Duration,
+    tamper: bool,
+) -> Result<(), RtcError> {
+    let end = l.duration() + duration;
+    while l.duration() < end {
+        progress_tampered(l, r, tamper)?;
+    }
+    Ok(())
+}
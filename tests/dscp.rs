@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Input, Output, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn dscp_per_stream() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let mid_video = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    // Let the DTLS handshake complete.
+    while l.duration() < Duration::from_secs(1) {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mut api = l.direct_api();
+    let audio = api.stream_tx_by_mid(mid_audio, None).unwrap();
+    // DSCP is 6 bits.
+    audio.set_dscp(Some(200));
+    assert_eq!(audio.dscp(), Some(63));
+    audio.set_dscp(Some(46));
+    assert_eq!(audio.dscp(), Some(46));
+    api.stream_tx_by_mid(mid_video, None)
+        .unwrap()
+        .set_dscp(Some(34));
+
+    let wallclock = l.start + l.duration();
+    let time = l.duration().into();
+    let pt = l.params_opus().pt();
+    l.writer(mid_audio)
+        .unwrap()
+        .write(pt, wallclock, time, vec![1_u8; 80])?;
+    let pt = l.params_vp8().pt();
+    l.writer(mid_video)
+        .unwrap()
+        .write(pt, wallclock, time, vec![2_u8; 800])?;
+
+    let mut dscps = HashSet::new();
+
+    for _ in 0..10 {
+        l.last += Duration::from_millis(10);
+        l.rtc.handle_input(Input::Timeout(l.last))?;
+
+        loop {
+            match l.rtc.poll_output()? {
+                Output::Timeout(_) => break,
+                Output::Transmit(t) => {
+                    dscps.insert(t.dscp);
+                }
+                Output::Event(_) => {}
+            }
+        }
+    }
+
+    assert!(dscps.contains(&Some(46)), "{:?}", dscps);
+    assert!(dscps.contains(&Some(34)), "{:?}", dscps);
+
+    Ok(())
+}
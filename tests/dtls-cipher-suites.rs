@@ -0,0 +1,105 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::{Candidate, DtlsCipherSuite, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{connect_l_r, connect_l_r_with_rtc, init_log, progress, TestRtc};
+
+use DtlsCipherSuite::{EcdheRsaAes128GcmSha256, EcdheRsaAes256GcmSha384};
+
+#[test]
+pub fn dtls_cipher_suites_default() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    while !l.is_connected() || !r.is_connected() {
+        progress(&mut l, &mut r)?;
+    }
+
+    assert!(l.rtc.dtls_cipher_suite().is_some());
+    assert_eq!(l.rtc.dtls_cipher_suite(), r.rtc.dtls_cipher_suite());
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_cipher_suites_order() -> Result<(), RtcError> {
+    init_log();
+
+    // L is the DTLS client, R is the server and its order decides.
+    let rtc1 = Rtc::builder()
+        .set_dtls_cipher_suites(&[EcdheRsaAes256GcmSha384, EcdheRsaAes128GcmSha256])
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_dtls_cipher_suites(&[EcdheRsaAes128GcmSha256, EcdheRsaAes256GcmSha384])
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    while !l.is_connected() || !r.is_connected() {
+        progress(&mut l, &mut r)?;
+    }
+
+    assert_eq!(l.rtc.dtls_cipher_suite(), Some(EcdheRsaAes128GcmSha256));
+    assert_eq!(r.rtc.dtls_cipher_suite(), Some(EcdheRsaAes128GcmSha256));
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_cipher_suites_no_overlap() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_dtls_cipher_suites(&[EcdheRsaAes128GcmSha256])
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_dtls_cipher_suites(&[EcdheRsaAes256GcmSha384])
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc1);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc2);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    l.add_remote_candidate(host2.clone());
+    r.add_local_candidate(host2);
+    r.add_remote_candidate(host1);
+
+    let finger_l = l.direct_api().local_dtls_fingerprint();
+    let finger_r = r.direct_api().local_dtls_fingerprint();
+    l.direct_api().set_remote_fingerprint(finger_r);
+    r.direct_api().set_remote_fingerprint(finger_l);
+
+    let creds_l = l.direct_api().local_ice_credentials();
+    let creds_r = r.direct_api().local_ice_credentials();
+    l.direct_api().set_remote_ice_credentials(creds_r);
+    r.direct_api().set_remote_ice_credentials(creds_l);
+
+    l.direct_api().set_ice_controlling(true);
+    r.direct_api().set_ice_controlling(false);
+
+    l.direct_api().start_dtls(true)?;
+    r.direct_api().start_dtls(false)?;
+
+    // The handshake fails with an error instead of hanging until the retransmits run out.
+    let result = loop {
+        if let Err(e) = progress(&mut l, &mut r) {
+            break Some(e);
+        }
+        if l.duration() > Duration::from_secs(5) {
+            break None;
+        }
+    };
+
+    assert!(matches!(result, Some(RtcError::Dtls(_))), "{:?}", result);
+    assert!(!l.is_connected() && !r.is_connected());
+    assert_eq!(l.rtc.dtls_cipher_suite(), None);
+    assert_eq!(r.rtc.dtls_cipher_suite(), None);
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::net::Receive;
+use str0m::{Candidate, DtlsState, Input, Output, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, TestRtc};
+
+const MTU: usize = 400;
+const RECORD_HEADER_LEN: usize = 13;
+const CONTENT_TYPE_HANDSHAKE: u8 = 22;
+const HANDSHAKE_TYPE_CERTIFICATE: u8 = 11;
+
+fn is_dtls(buf: &[u8]) -> bool {
+    buf.first().map(|b| (20..=63).contains(b)).unwrap_or(false)
+}
+
+/// Number of unencrypted records in the datagram carrying (part of) a certificate.
+fn certificate_fragments(mut buf: &[u8]) -> usize {
+    let mut count = 0;
+    while buf.len() >= RECORD_HEADER_LEN {
+        let epoch = u16::from_be_bytes([buf[3], buf[4]]);
+        let len = u16::from_be_bytes([buf[11], buf[12]]) as usize;
+        let body = &buf[RECORD_HEADER_LEN..RECORD_HEADER_LEN + len];
+        if epoch == 0
+            && buf[0] == CONTENT_TYPE_HANDSHAKE
+            && body.first() == Some(&HANDSHAKE_TYPE_CERTIFICATE)
+        {
+            count += 1;
+        }
+        buf = &buf[RECORD_HEADER_LEN + len..];
+    }
+    count
+}
+
+/// Like `common::progress`, but asserting every DTLS datagram fits the MTU.
+fn progress_mtu(l: &mut TestRtc, r: &mut TestRtc, fragments: &mut usize) -> Result<(), RtcError> {
+    let (f, t) = if l.last < r.last { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                if is_dtls(&v.contents) {
+                    assert!(v.contents.len() <= MTU, "{}", v.contents.len());
+                    *fragments += certificate_fragments(&v.contents);
+                }
+
+                let input = Input::Receive(
+                    f.last,
+                    Receive::new(v.proto, v.source, v.destination, &v.contents)?.with_ecn(v.ecn),
+                );
+                t.span.in_scope(|| t.rtc.handle_input(input))?;
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_small_mtu() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = || Rtc::builder().set_dtls_mtu(MTU).build();
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc());
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc());
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_channel("dtls".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let mut fragments = 0;
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress_mtu(&mut l, &mut r, &mut fragments)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "Failed to connect");
+    }
+
+    assert_eq!(l.dtls_state(), DtlsState::Connected);
+    assert_eq!(r.dtls_state(), DtlsState::Connected);
+
+    // Both certificates are too big for a single datagram.
+    assert!(fragments >= 4, "{}", fragments);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::net::Receive;
+use str0m::{Candidate, DtlsState, Input, Output, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, TestRtc};
+
+const RECORD_HEADER_LEN: usize = 13;
+const HANDSHAKE_HEADER_LEN: usize = 12;
+const CONTENT_TYPE_HANDSHAKE: u8 = 22;
+
+fn is_dtls(buf: &[u8]) -> bool {
+    buf.first().map(|b| (20..=63).contains(b)).unwrap_or(false)
+}
+
+/// Split a DTLS datagram into its records.
+fn records(mut buf: &[u8]) -> Vec<Vec<u8>> {
+    let mut out = vec![];
+    while buf.len() >= RECORD_HEADER_LEN {
+        let len = u16::from_be_bytes([buf[11], buf[12]]) as usize;
+        let (record, rest) = buf.split_at(RECORD_HEADER_LEN + len);
+        out.push(record.to_vec());
+        buf = rest;
+    }
+    out
+}
+
+/// Split an unencrypted handshake record in two records, each with half of the message.
+///
+/// The record sequence numbers are doubled to keep them unique.
+fn fragment(record: &[u8]) -> Vec<Vec<u8>> {
+    let epoch = u16::from_be_bytes([record[3], record[4]]);
+    let mut seq = [0; 8];
+    seq[2..].copy_from_slice(&record[5..11]);
+    let seq = u64::from_be_bytes(seq);
+
+    let with_seq = |mut record: Vec<u8>, seq: u64| {
+        record[5..11].copy_from_slice(&seq.to_be_bytes()[2..]);
+        record
+    };
+
+    let body = &record[RECORD_HEADER_LEN..];
+
+    let is_single_message = body.len() >= HANDSHAKE_HEADER_LEN
+        && body.len() == HANDSHAKE_HEADER_LEN + u24(&body[9..12]) as usize;
+
+    if epoch != 0 || record[0] != CONTENT_TYPE_HANDSHAKE || !is_single_message {
+        // Encrypted records can't be changed, and have their own sequence numbers.
+        let seq = if epoch == 0 { seq * 2 } else { seq };
+        return vec![with_seq(record.to_vec(), seq)];
+    }
+
+    let offset = u24(&body[6..9]);
+    let data = &body[HANDSHAKE_HEADER_LEN..];
+    let (a, b) = data.split_at(data.len() / 2);
+
+    [(offset, a), (offset + a.len() as u32, b)]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (offset, part))| {
+            let mut r = record[..RECORD_HEADER_LEN].to_vec();
+            let len = (HANDSHAKE_HEADER_LEN + part.len()) as u16;
+            r[11..13].copy_from_slice(&len.to_be_bytes());
+            r.extend_from_slice(&body[..6]);
+            r.extend_from_slice(&offset.to_be_bytes()[1..]);
+            r.extend_from_slice(&(part.len() as u32).to_be_bytes()[1..]);
+            r.extend_from_slice(part);
+            with_seq(r, seq * 2 + i as u64)
+        })
+        .collect()
+}
+
+fn u24(b: &[u8]) -> u32 {
+    u32::from_be_bytes([0, b[0], b[1], b[2]])
+}
+
+/// The record without the sequence number, to recognize retransmits.
+fn without_seq(record: &[u8]) -> Vec<u8> {
+    let mut r = record.to_vec();
+    r[5..11].fill(0);
+    r
+}
+
+/// Like `common::progress`, but the handshake messages in DTLS datagrams are split into
+/// fragments that are delivered as separate datagrams in reverse order. This includes the
+/// ChangeCipherSpec arriving before the handshake messages it follows. Retransmits are
+/// delivered in order.
+fn progress_reorder(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    seen: &mut HashSet<Vec<u8>>,
+) -> Result<(), RtcError> {
+    let (f, t) = if l.last < r.last { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                let datagrams = if is_dtls(&v.contents) {
+                    let records = records(&v.contents);
+                    let is_retransmit = records.iter().any(|r| !seen.insert(without_seq(r)));
+
+                    let mut d: Vec<_> = records.iter().flat_map(|r| fragment(r)).collect();
+                    if !is_retransmit {
+                        d.reverse();
+                    }
+                    d
+                } else {
+                    vec![v.contents.to_vec()]
+                };
+
+                for data in datagrams {
+                    let input = Input::Receive(
+                        f.last,
+                        Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(v.ecn),
+                    );
+                    t.span.in_scope(|| t.rtc.handle_input(input))?;
+                }
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_reordered_fragments() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), Rtc::new());
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), Rtc::new());
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_channel("dtls".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let mut seen = HashSet::new();
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress_reorder(&mut l, &mut r, &mut seen)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "Failed to connect");
+    }
+
+    assert_eq!(l.dtls_state(), DtlsState::Connected);
+    assert_eq!(r.dtls_state(), DtlsState::Connected);
+
+    // The early ChangeCipherSpec is only recovered by a retransmit.
+    assert!(l.duration() > Duration::from_secs(1), "{:?}", l.duration());
+
+    Ok(())
+}
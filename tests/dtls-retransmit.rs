@@ -0,0 +1,196 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::net::Receive;
+use str0m::{Candidate, DtlsState, Event, Input, Output, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, TestRtc};
+
+fn is_dtls(buf: &[u8]) -> bool {
+    buf.first().map(|b| (20..=63).contains(b)).unwrap_or(false)
+}
+
+/// Like `common::progress`, but datagrams sent by r are passed through `drop_r` first.
+fn progress_drop(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    drop_r: &mut impl FnMut(&[u8]) -> bool,
+) -> Result<(), RtcError> {
+    let l_first = l.last < r.last;
+    let (f, t) = if l_first { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                if !l_first && drop_r(&v.contents) {
+                    continue;
+                }
+                let data = v.contents;
+                let input = Input::Receive(
+                    f.last,
+                    Receive::new(v.proto, v.source, v.destination, &data)?.with_ecn(v.ecn),
+                );
+                t.span.in_scope(|| t.rtc.handle_input(input))?;
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn setup(rtc_l: Rtc, rtc_r: Rtc) -> Result<(TestRtc, TestRtc), RtcError> {
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    assert_eq!(l.dtls_state(), DtlsState::Init);
+
+    let mut change = l.sdp_api();
+    change.add_channel("dtls".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    // R answers, and becomes the passive DTLS side sending the ServerHello flight.
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    assert_eq!(l.dtls_state(), DtlsState::Handshaking);
+
+    Ok((l, r))
+}
+
+#[test]
+pub fn dtls_retransmit_lost_server_hello() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = setup(Rtc::new(), Rtc::new())?;
+
+    // Drop the first DTLS datagram from R, which is the start of the ServerHello flight.
+    let mut dropped = false;
+    let mut drop_r = |buf: &[u8]| {
+        if !dropped && is_dtls(buf) {
+            dropped = true;
+            return true;
+        }
+        false
+    };
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress_drop(&mut l, &mut r, &mut drop_r)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "Failed to recover");
+    }
+
+    assert!(dropped);
+    assert_eq!(l.dtls_state(), DtlsState::Connected);
+    assert_eq!(r.dtls_state(), DtlsState::Connected);
+
+    // Recovering needs at least one retransmit timeout.
+    assert!(l.duration() > Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_handshake_timeout() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder()
+        .set_dtls_handshake_timeout(Duration::from_millis(100))
+        .set_dtls_max_retransmits(2)
+        .build();
+
+    let (mut l, mut r) = setup(rtc_l, Rtc::new())?;
+
+    // R never gets any DTLS through.
+    let mut drop_r = |buf: &[u8]| is_dtls(buf);
+
+    loop {
+        if l.dtls_state() == DtlsState::TimedOut {
+            break;
+        }
+        progress_drop(&mut l, &mut r, &mut drop_r)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "No timeout");
+    }
+
+    // Give the event a chance to be polled.
+    progress_drop(&mut l, &mut r, &mut drop_r)?;
+    progress_drop(&mut l, &mut r, &mut drop_r)?;
+
+    // 100ms + 200ms + 400ms
+    assert!(l.duration() >= Duration::from_millis(700));
+
+    let timeouts = l
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::DtlsHandshakeTimeout))
+        .count();
+    assert_eq!(timeouts, 1);
+
+    Ok(())
+}
+
+#[test]
+pub fn dtls_retransmit_lost_final_flight() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = setup(Rtc::new(), Rtc::new())?;
+
+    // Drop the first datagram from R with a ChangeCipherSpec, which is the flight that
+    // completes the handshake for R. L has to retransmit its flight with the epoch 1
+    // Finished, which R already has, and R must answer it with its final flight again.
+    let mut dropped = false;
+    let mut drop_r = |buf: &[u8]| {
+        if !dropped && is_dtls(buf) && has_change_cipher_spec(buf) {
+            dropped = true;
+            return true;
+        }
+        false
+    };
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress_drop(&mut l, &mut r, &mut drop_r)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "Failed to recover");
+    }
+
+    assert!(dropped);
+    assert_eq!(l.dtls_state(), DtlsState::Connected);
+    assert_eq!(r.dtls_state(), DtlsState::Connected);
+
+    Ok(())
+}
+
+fn has_change_cipher_spec(mut buf: &[u8]) -> bool {
+    while buf.len() >= 13 {
+        if buf[0] == 20 {
+            return true;
+        }
+        let len = u16::from_be_bytes([buf[11], buf[12]]) as usize;
+        buf = &buf[(13 + len).min(buf.len())..];
+    }
+    false
+}
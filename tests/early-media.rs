@@ -0,0 +1,119 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Event, Input, Output, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn early_media_buffer() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder()
+        .set_early_media_buffer(Duration::from_millis(100))
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let pt = l.params_opus().pt();
+
+    let mut index = 0_u8;
+    let mut write = |l: &mut TestRtc| -> Result<(), RtcError> {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, vec![index; 80])?;
+        index += 1;
+        Ok(())
+    };
+
+    // The app starts encoding 500ms before the connection is made. L can't reach
+    // R during this time.
+    while l.duration() < Duration::from_millis(500) {
+        write(&mut l)?;
+
+        l.span
+            .in_scope(|| l.rtc.handle_input(Input::Timeout(l.last)))?;
+
+        // Transmits are lost since R is not reachable.
+        loop {
+            match l.span.in_scope(|| l.rtc.poll_output())? {
+                Output::Timeout(_) => break,
+                Output::Transmit(_) => {}
+                Output::Event(e) => l.events.push((l.last, e)),
+            }
+        }
+
+        l.last += Duration::from_millis(20);
+    }
+
+    r.last = l.last;
+
+    let mut write_at = l.last;
+    let mut connected_at = None;
+
+    while l.duration() < Duration::from_secs(2) {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+            write(&mut l)?;
+        }
+        if connected_at.is_none() && l.is_connected() {
+            connected_at = Some(l.duration());
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let dropped: usize = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::PacketsDropped(d) => Some(d.packets),
+            _ => None,
+        })
+        .sum();
+
+    let received: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::MediaData(d) => Some(d),
+            _ => None,
+        })
+        .collect();
+
+    // Packets written up to 100ms before the connection are kept.
+    let connected_at = connected_at.unwrap().as_millis() as usize;
+    let expected = (connected_at - 100) / 20;
+    assert!(
+        dropped.abs_diff(expected) <= 1,
+        "connected at {}ms, dropped: {}",
+        connected_at,
+        dropped
+    );
+
+    // Media starts with the first kept packet, and has no gaps.
+    assert!(!received.is_empty());
+    assert_eq!(received[0].data[0] as usize, dropped);
+    for w in received.windows(2) {
+        assert_eq!(w[1].data[0], w[0].data[0] + 1);
+        assert!(w[1].contiguous);
+    }
+
+    Ok(())
+}
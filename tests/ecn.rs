@@ -0,0 +1,127 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::bwe::{Bitrate, BweKind};
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::rtp::rtcp::{EcnFeedback, Rtcp};
+use str0m::rtp::RawPacket;
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, progress_with_ce, TestRtc};
+
+#[test]
+pub fn ecn_feedback() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder()
+        .enable_ecn(true)
+        .enable_bwe(Some(Bitrate::kbps(300)))
+        .enable_raw_packets(true)
+        .build();
+    let rtc_r = Rtc::builder().enable_ecn(true).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    l.bwe().set_current_bitrate(Bitrate::kbps(250));
+    l.bwe().set_desired_bitrate(Bitrate::mbps(1));
+
+    run(&mut l, &mut r, mid, Duration::from_secs(3), false)?;
+
+    // The receiver reports the ECT(0) marked packets.
+    let fb = last_feedback(&l).expect("ECN feedback");
+    assert!(fb.ect0 > 50, "{:?}", fb);
+    assert_eq!(fb.ce, 0);
+
+    let before = last_estimate(&l).unwrap();
+
+    run(&mut l, &mut r, mid, Duration::from_secs(2), true)?;
+
+    let fb = last_feedback(&l).unwrap();
+    assert!(fb.ce > 0, "{:?}", fb);
+
+    // Congestion experienced lowers the estimate.
+    let after = last_estimate(&l).unwrap();
+    assert!(after < before, "{} -> {}", before, after);
+
+    Ok(())
+}
+
+fn last_feedback(t: &TestRtc) -> Option<EcnFeedback> {
+    t.events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtcpRx(Rtcp::EcnFeedback(v))) => Some(v.clone()),
+            _ => None,
+        })
+}
+
+fn last_estimate(t: &TestRtc) -> Option<Bitrate> {
+    t.events.iter().rev().find_map(|(_, e)| match e {
+        Event::EgressBitrateEstimate(BweKind::Twcc(v)) => Some(*v),
+        _ => None,
+    })
+}
+
+fn run(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    mid: Mid,
+    duration: Duration,
+    ce: bool,
+) -> Result<(), RtcError> {
+    let pt = l.params_vp8().pt();
+
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        // 250kbps in 1000 byte frames every 32ms.
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(32);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid)
+                .unwrap()
+                .write(pt, wallclock, time, vec![1_u8; 1000])?;
+        }
+
+        if ce {
+            progress_with_ce(l, r)?;
+        } else {
+            progress(l, r)?;
+        }
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::change::SdpAnswer;
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::{Extension, ExtensionMap};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn extension_id_out_of_range() -> Result<(), RtcError> {
+    init_log();
+
+    use Extension::*;
+
+    let mut exts = ExtensionMap::empty();
+    exts.set(3, VideoOrientation);
+    exts.set(8, ColorSpace);
+
+    let mut b = Rtc::builder();
+    *b.extension_map() = exts.clone();
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), b.build());
+
+    let mut b = Rtc::builder();
+    *b.extension_map() = exts;
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), b.build());
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+
+    // The answerer uses an id we have no room for.
+    let sdp = answer
+        .to_sdp_string()
+        .replace("a=extmap:3 ", "a=extmap:20 ");
+
+    let answer = SdpAnswer::from_sdp_string(&sdp)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    // The other extension is unaffected.
+    assert_eq!(
+        l.media(mid)
+            .unwrap()
+            .remote_extmap()
+            .iter_video()
+            .collect::<Vec<_>>(),
+        vec![(8, &ColorSpace)]
+    );
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .video_orientation(str0m::rtp::VideoOrientation::Deg90)
+            .write(pt, wallclock, time, vec![1, 2, 3])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(1) {
+            break;
+        }
+    }
+
+    let not_negotiated: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::ExtensionNotNegotiated(m, ext) => Some((*m, ext.clone())),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(not_negotiated, vec![(mid, VideoOrientation)]);
+
+    // R still has VideoOrientation at id 3, but L must not send it there.
+    let mut empty = true;
+    for (_, e) in &r.events {
+        if let Event::MediaData(d) = e {
+            empty = false;
+            assert_eq!(d.ext_vals.video_orientation, None);
+        }
+    }
+    assert!(!empty);
+
+    // Nor offer it at id 3 for new media when renegotiating.
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, _) = change.apply().unwrap();
+    assert!(!offer.to_sdp_string().contains("urn:3gpp:video-orientation"));
+
+    Ok(())
+}
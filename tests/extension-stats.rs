@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{Extension, ExtensionValues, Ssrc};
+use str0m::RtcError;
+
+mod common;
+use common::{connect_l_r, init_log, progress};
+
+#[test]
+pub fn extension_stats() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    for index in 0..100 {
+        let wallclock = l.start + l.duration();
+        let time = index * 960;
+        let seq_no = (47_000 + index as u64).into();
+
+        // Audio level on every other packet.
+        let exts = ExtensionValues {
+            audio_level: (index % 2 == 0).then_some(-42),
+            voice_activity: (index % 2 == 0).then_some(true),
+            ..Default::default()
+        };
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                time,
+                wallclock,
+                false,
+                exts,
+                true,
+                vec![0x1, 0x2, 0x3, 0x4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    let mut api = r.direct_api();
+    let stats = api.stream_rx(&ssrc).unwrap().extension_stats();
+
+    assert_eq!(stats.packets, 100);
+    assert_eq!(stats.count(&Extension::AudioLevel), 50);
+    assert_eq!(stats.count(&Extension::TransportSequenceNumber), 100);
+
+    // Negotiated, but never sent.
+    assert_eq!(stats.count(&Extension::VideoOrientation), 0);
+    assert!(stats.iter().all(|(e, _)| *e != Extension::VideoOrientation));
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, FrameBoundaryKind, Ssrc};
+use str0m::{Event, RtcError};
+
+mod common;
+use common::{connect_l_r, init_log, progress};
+
+#[test]
+pub fn frame_boundary() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc, None, mid, None)
+        .set_frame_boundary_events(true);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // Two packets per frame, the end of the third frame never arrives.
+    let mut to_write = vec![0, 1, 2, 3, 4, 6, 7];
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && !to_write.is_empty() {
+            write_at = l.last + Duration::from_millis(10);
+
+            let index = to_write.remove(0);
+            let wallclock = l.start + l.duration();
+            let time = ((index / 2) * 3000 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            let marker = index % 2 == 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    marker,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    let out: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::FrameBoundary(b) => {
+                let kind = match b.kind {
+                    FrameBoundaryKind::Start => "start",
+                    FrameBoundaryKind::End => "end",
+                };
+                let gap = if b.gap { " gap" } else { "" };
+                Some(format!("{} {}{}", kind, *b.seq_no - 47_000, gap))
+            }
+            Event::RtpPacket(p) => Some(format!("{}", *p.seq_no - 47_000)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        out,
+        vec![
+            "start 0",
+            "0",
+            "end 1",
+            "1",
+            "start 2",
+            "2",
+            "end 3",
+            "3",
+            "start 4",
+            "4",
+            "start 6 gap",
+            "6",
+            "end 7",
+            "7"
+        ]
+    );
+
+    Ok(())
+}
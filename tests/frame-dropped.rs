@@ -0,0 +1,133 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::ExtensionValues;
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+// FU-A indicator (type 28) and FU headers for an IDR slice (type 5).
+const FUA: u8 = 0x1c;
+const FU_START: u8 = 0x85;
+const FU_MIDDLE: u8 = 0x05;
+
+#[test]
+pub fn never_ending_fua_dropped() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder().build();
+    let rtc_r = Rtc::builder()
+        .set_max_frame_size(20_000)
+        .set_stats_interval(Some(Duration::from_millis(500)))
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_h264().pt();
+
+    // 100 fragments of 1000 bytes that never end, followed by a complete frame.
+    let mut packets: Vec<(u32, bool, Vec<u8>)> = (0..100)
+        .map(|i| {
+            let mut data = vec![0; 1000];
+            data[0] = FUA;
+            data[1] = if i == 0 { FU_START } else { FU_MIDDLE };
+            (1000, false, data)
+        })
+        .collect();
+    packets.push((4000, true, vec![0x65, 1, 2, 3]));
+
+    for (index, (time, marker, data)) in packets.into_iter().enumerate() {
+        let wallclock = l.start + l.duration();
+
+        let mut direct = l.direct_api();
+        let tx = direct.stream_tx_by_mid(mid, None).unwrap();
+        tx.write_rtp(
+            pt,
+            (47_000 + index as u64).into(),
+            time,
+            wallclock,
+            marker,
+            ExtensionValues::default(),
+            true,
+            data,
+        )
+        .unwrap();
+
+        progress(&mut l, &mut r)?;
+    }
+
+    let settle = l.duration() + Duration::from_secs(1);
+    while l.duration() < settle {
+        progress(&mut l, &mut r)?;
+    }
+
+    let dropped: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::FrameDropped(d) => Some(d),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(dropped[0].mid, mid);
+    assert_eq!(dropped[0].pt, pt);
+    assert_eq!(*dropped[0].seq_range.start(), 47_000.into());
+    assert_eq!(*dropped[0].seq_range.end(), 47_020.into());
+    assert_eq!(dropped[0].bytes, 21_000);
+
+    // Only the complete frame is emitted.
+    let data: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::MediaData(d) => Some(d),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].time.numer(), 4000);
+
+    let stats = r
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::PeerStats(s) => Some(s),
+            _ => None,
+        })
+        .expect("peer stats");
+
+    assert_eq!(stats.frames_dropped, 1);
+
+    Ok(())
+}
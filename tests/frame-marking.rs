@@ -0,0 +1,137 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::RawPacket;
+use str0m::rtp::{Extension, FrameMarking};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn frame_marking() -> Result<(), RtcError> {
+    init_log();
+
+    // L exposes the individual RTP packets it sends.
+    let rtc_l = Rtc::builder()
+        .set_extension(12, Extension::FrameMarking)
+        .enable_raw_packets(true)
+        .build();
+    let rtc_r = Rtc::builder()
+        .set_extension(12, Extension::FrameMarking)
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // Big enough to be split into several packets.
+    let data = vec![1_u8; 3000];
+
+    let marking = FrameMarking {
+        independent: true,
+        temporal_id: 1,
+        tl0_pic_idx: 7,
+        ..Default::default()
+    };
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .frame_marking(marking)
+            .write(pt, wallclock, time, data.clone())?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let packets: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| {
+            if let Event::RawPacket(p) = e {
+                if let RawPacket::RtpTx(header, _) = &**p {
+                    return Some(header);
+                }
+            }
+            None
+        })
+        .collect();
+
+    assert!(packets.len() > 20);
+
+    // The first packet after the end of a frame starts the next one.
+    let mut start = true;
+
+    for p in packets {
+        let v = p.ext_vals.frame_marking.expect("frame marking");
+
+        // Start and end of frame are set per packet, the rest is as written.
+        assert_eq!(v.start_of_frame, start);
+        assert_eq!(v.end_of_frame, p.marker);
+        start = p.marker;
+
+        assert_eq!(
+            FrameMarking {
+                start_of_frame: false,
+                end_of_frame: false,
+                ..v
+            },
+            marking
+        );
+    }
+
+    // The receiver sees the frame marking of the last packet in each frame.
+    let media: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| {
+            if let Event::MediaData(v) = e {
+                Some(v)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    assert!(!media.is_empty());
+
+    for m in media {
+        let v = m.ext_vals.frame_marking.expect("frame marking");
+        assert!(v.end_of_frame);
+        assert_eq!(v.tl0_pic_idx, 7);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::net::Ipv4Addr;
+
+use str0m::error::IceError;
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, IceCreds, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn ice_credentials() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let creds = IceCreds {
+        ufrag: "Ufrg".into(),
+        pass: "Reproducible+Password/1".into(),
+    };
+    l.direct_api().set_local_ice_credentials(creds.clone())?;
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let sdp = offer.to_sdp_string();
+    assert!(sdp.contains("a=ice-ufrag:Ufrg"));
+    assert!(sdp.contains("a=ice-pwd:Reproducible+Password/1"));
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    assert_eq!(l.direct_api().local_ice_credentials(), creds);
+
+    Ok(())
+}
+
+#[test]
+pub fn ice_credentials_invalid_config() {
+    let bad = IceCreds {
+        ufrag: "a:b".into(),
+        pass: "abcdefghijklmnopqrstuv".into(),
+    };
+    let r = Rtc::builder().set_local_ice_credentials(bad);
+    assert!(matches!(r, Err(IceError::BadCredentials(_))));
+}
+
+#[test]
+pub fn ice_credentials_invalid_direct() {
+    let bad = IceCreds {
+        ufrag: "abcd".into(),
+        pass: "short".into(),
+    };
+    let r = Rtc::new().direct_api().set_local_ice_credentials(bad);
+    assert!(matches!(r, Err(IceError::BadCredentials(_))));
+}
+
+#[test]
+pub fn ice_credentials_in_use() {
+    let mut rtc = Rtc::new();
+    let mut change = rtc.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    // The offer communicates the credentials to the remote.
+    let _ = change.apply();
+
+    let r = rtc.direct_api().set_local_ice_credentials(IceCreds::new());
+    assert!(matches!(r, Err(IceError::CredentialsInUse)));
+}
@@ -0,0 +1,68 @@
+use std::net::Ipv4Addr;
+
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+fn connect(offer_lite: bool) -> Result<(), RtcError> {
+    let rtc1 = Rtc::builder().set_ice_lite(offer_lite).build();
+    let rtc2 = Rtc::builder().set_ice_lite(!offer_lite).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc1);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc2);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_channel("lite".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    let offer_sdp = offer.to_sdp_string();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    let answer_sdp = answer.to_sdp_string();
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    // Only the ice-lite side signals it.
+    assert_eq!(offer_sdp.contains("a=ice-lite\r\n"), offer_lite);
+    assert_eq!(answer_sdp.contains("a=ice-lite\r\n"), !offer_lite);
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn ice_lite_answerer() -> Result<(), RtcError> {
+    init_log();
+    connect(false)
+}
+
+#[test]
+pub fn ice_lite_offerer() -> Result<(), RtcError> {
+    init_log();
+    connect(true)
+}
+
+#[test]
+pub fn ice_lite_direct_api_signalled() {
+    init_log();
+
+    let mut rtc = Rtc::new();
+    rtc.direct_api().set_ice_lite(true);
+
+    let mut change = rtc.sdp_api();
+    change.add_channel("lite".into());
+    let (offer, _) = change.apply().unwrap();
+
+    assert!(offer.to_sdp_string().contains("a=ice-lite\r\n"));
+}
@@ -0,0 +1,131 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn ice_migration() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc1);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc2);
+
+    // L starts out relayed, such as over TURN on a cellular network.
+    let relay1 = Candidate::relayed((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(relay1.clone());
+    l.add_remote_candidate(host2.clone());
+    r.add_local_candidate(host2);
+    r.add_remote_candidate(relay1);
+
+    let finger_l = l.direct_api().local_dtls_fingerprint();
+    let finger_r = r.direct_api().local_dtls_fingerprint();
+    l.direct_api().set_remote_fingerprint(finger_r);
+    r.direct_api().set_remote_fingerprint(finger_l);
+
+    let creds_l = l.direct_api().local_ice_credentials();
+    let creds_r = r.direct_api().local_ice_credentials();
+    l.direct_api().set_remote_ice_credentials(creds_r);
+    r.direct_api().set_remote_ice_credentials(creds_l);
+
+    l.direct_api().set_ice_controlling(true);
+    r.direct_api().set_ice_controlling(false);
+
+    l.direct_api().start_dtls(true)?;
+    r.direct_api().start_dtls(false)?;
+
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let first = l.rtc.selected_candidate_pair().unwrap();
+    assert_eq!(first.0, (Ipv4Addr::new(1, 1, 1, 1), 1000).into());
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    for index in 0..150 {
+        // Midway, L gets a direct path, such as when joining a WiFi network.
+        if index == 50 {
+            let host1 = Candidate::host((Ipv4Addr::new(3, 3, 3, 3), 3000).into(), "udp")?;
+            l.add_local_candidate(host1.clone());
+            r.add_remote_candidate(host1);
+        }
+
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    // L migrated to the direct path, without renegotiation.
+    let changes: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::SelectedCandidatePairChange(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(changes.len(), 2, "{:?}", changes);
+    assert_eq!(changes[0].local, first.0);
+    assert_eq!(changes[1].local, (Ipv4Addr::new(3, 3, 3, 3), 3000).into());
+    assert_eq!(changes[1].remote, (Ipv4Addr::new(2, 2, 2, 2), 2000).into());
+    assert_eq!(l.rtc.selected_candidate_pair().unwrap().0, changes[1].local);
+
+    // Media continued across the migration without gaps.
+    let seq_nos: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(*p.seq_no),
+            _ => None,
+        })
+        .collect();
+
+    let expected: Vec<_> = (47_000..47_150).collect();
+    assert_eq!(seq_nos, expected);
+
+    Ok(())
+}
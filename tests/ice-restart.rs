@@ -1,8 +1,9 @@
 use std::net::Ipv4Addr;
 use std::time::Duration;
 
+use str0m::media::{Direction, MediaKind};
 use str0m::RtcConfig;
-use str0m::{Candidate, RtcError};
+use str0m::{Candidate, Event, RtcError};
 use tracing::info_span;
 
 mod common;
@@ -19,8 +20,10 @@ pub fn ice_restart() -> Result<(), RtcError> {
 
     let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
     let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
-    l.add_local_candidate(host1);
-    r.add_local_candidate(host2);
+    l.add_local_candidate(host1.clone());
+    r.add_local_candidate(host2.clone());
+
+    assert_eq!(l.selected_candidate_pair(), None);
 
     let (offer, pending) = l.span.in_scope(|| {
         let mut change = l.rtc.sdp_api();
@@ -74,6 +77,16 @@ pub fn ice_restart() -> Result<(), RtcError> {
         progress(&mut l, &mut r)?;
     }
 
+    // The pair is nominated again after the restart.
+    assert_eq!(
+        l.selected_candidate_pair(),
+        Some((host1.addr(), host2.addr()))
+    );
+    assert_eq!(
+        r.selected_candidate_pair(),
+        Some((host2.addr(), host1.addr()))
+    );
+
     assert_ne!(
         r_creds,
         r._local_ice_creds(),
@@ -87,3 +100,68 @@ pub fn ice_restart() -> Result<(), RtcError> {
 
     Ok(())
 }
+
+#[test]
+pub fn ice_no_restart_on_renegotiation() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    r.add_local_candidate(host2.clone());
+
+    let mut change = l.rtc.sdp_api();
+    let _ = change.add_channel("My little channel".into());
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let l_creds = l._local_ice_creds();
+    let r_creds = r._local_ice_creds();
+    let pair = l.selected_candidate_pair();
+    let renegotiated_at = l.last;
+
+    // A restart requested and then withdrawn, along with another change.
+    let mut change = l.rtc.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    change.ice_restart(true);
+    change.cancel_ice_restart();
+    assert!(!change.has_ice_restart());
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    assert!(l.rtc.is_connected());
+    assert!(r.rtc.is_connected());
+
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    assert_eq!(l_creds, l._local_ice_creds());
+    assert_eq!(r_creds, r._local_ice_creds());
+    assert_eq!(l.selected_candidate_pair(), pair);
+
+    // The connection was never interrupted.
+    for t in [&l, &r] {
+        assert!(!t.events.iter().any(|(at, e)| {
+            *at >= renegotiated_at && matches!(e, Event::IceConnectionStateChange(_))
+        }));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::{Candidate, Event, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn ice_role_conflict_both_controlling() -> Result<(), RtcError> {
+    role_conflict(true)
+}
+
+#[test]
+pub fn ice_role_conflict_both_controlled() -> Result<(), RtcError> {
+    role_conflict(false)
+}
+
+fn role_conflict(controlling: bool) -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    l.add_remote_candidate(host2.clone());
+    r.add_local_candidate(host2);
+    r.add_remote_candidate(host1);
+
+    let finger_l = l.direct_api().local_dtls_fingerprint();
+    let finger_r = r.direct_api().local_dtls_fingerprint();
+
+    l.direct_api().set_remote_fingerprint(finger_r);
+    r.direct_api().set_remote_fingerprint(finger_l);
+
+    let creds_l = l.direct_api().local_ice_credentials();
+    let creds_r = r.direct_api().local_ice_credentials();
+
+    l.direct_api().set_remote_ice_credentials(creds_r);
+    r.direct_api().set_remote_ice_credentials(creds_l);
+
+    // Both sides start with the same role.
+    l.direct_api().set_ice_controlling(controlling);
+    r.direct_api().set_ice_controlling(controlling);
+
+    l.direct_api().start_dtls(true)?;
+    r.direct_api().start_dtls(false)?;
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+
+        assert!(l.duration() < Duration::from_secs(10), "Failed to connect");
+    }
+
+    let l_controlling = l.direct_api().ice_controlling();
+    let r_controlling = r.direct_api().ice_controlling();
+    assert_ne!(l_controlling, r_controlling);
+
+    let role_changes = |t: &TestRtc| -> Vec<bool> {
+        t.events
+            .iter()
+            .filter_map(|(_, e)| match e {
+                Event::IceRoleChange(v) => Some(*v),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // Exactly one side switched role.
+    let mut changes = [role_changes(&l), role_changes(&r)];
+    changes.sort();
+    assert_eq!(changes, [vec![], vec![!controlling]]);
+
+    Ok(())
+}
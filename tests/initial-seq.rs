@@ -0,0 +1,84 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::RawPacket;
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn initial_seq_range() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = Rtc::builder()
+        .enable_raw_packets(true)
+        .set_initial_seq_range(1000..=1000)
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, vec![1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(1) {
+            break;
+        }
+    }
+
+    let seq_nos: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpTx(h, _)) => Some(h.sequence_number),
+            _ => None,
+        })
+        .collect();
+
+    assert!(seq_nos.len() > 10);
+    assert_eq!(seq_nos[0], 1000);
+
+    let media_count = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::MediaData(_)))
+        .count();
+
+    assert!(media_count > 10);
+
+    Ok(())
+}
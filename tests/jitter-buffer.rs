@@ -0,0 +1,302 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::rtcp::MetricInterval;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r, connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn jitter_buffer() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc, None, mid, None)
+        .set_target_delay(Duration::from_millis(100));
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // Out of order, and 4 never arrives.
+    let mut to_write = vec![0, 2, 1, 3, 6, 5, 7];
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && !to_write.is_empty() {
+            write_at = l.last + Duration::from_millis(10);
+
+            let index = to_write.remove(0);
+            let wallclock = l.start + l.duration();
+            let time = (index * 1000 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    let out: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(format!("{}", *p.seq_no - 47_000)),
+            Event::RtpPacketsLost(lost) => Some(format!(
+                "lost {}-{}",
+                **lost.seq_range.start() - 47_000,
+                **lost.seq_range.end() - 47_000
+            )),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(out, ["0", "1", "2", "3", "lost 4-4", "5", "6", "7"]);
+
+    Ok(())
+}
+
+#[test]
+pub fn jitter_buffer_delay_reported() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_millis(500)))
+        .build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+    // With RTX, the receiver reports are sent at the faster video interval.
+    let rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, Some(rtx), mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc, Some(rtx), mid, None)
+        .set_target_delay(Duration::from_millis(100));
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800) as u32;
+            let seq_no = (index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    let mut api = r.direct_api();
+    let rx = api.stream_rx(&ssrc).unwrap();
+    assert_eq!(rx.jitter_buffer_delay(), Some(Duration::from_millis(100)));
+
+    let reported = l
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::MediaEgressStats(s) => s.remote_jitter_buffer,
+            _ => None,
+        })
+        .expect("jitter buffer metrics from remote");
+
+    assert_eq!(reported.ssrc, ssrc);
+    assert_eq!(reported.interval, MetricInterval::Interval);
+    assert!(!reported.adaptive);
+    assert_eq!(reported.nominal, Some(Duration::from_millis(100)));
+    assert_eq!(reported.maximum, Some(Duration::from_millis(100)));
+    assert_eq!(reported.high_water, Some(Duration::from_millis(100)));
+    assert_eq!(reported.low_water, Some(Duration::from_millis(100)));
+
+    Ok(())
+}
+
+#[test]
+pub fn jitter_buffer_underrun_overrun() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_millis(500)))
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    let mut api = r.direct_api();
+    let rx = api.expect_stream_rx(ssrc, None, mid, None);
+    rx.set_target_delay(Duration::from_millis(100));
+    rx.set_max_held_packets(Some(3));
+    rx.set_jitter_buffer_events(true);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // 2 never arrives. 5-9 arrive in a burst while 4 is held, overrunning the max of 3.
+    let bursts: &[&[u64]] = &[&[0], &[1], &[3], &[4], &[5, 6, 7, 8, 9]];
+    let mut bursts = bursts.iter();
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(50);
+
+            for index in bursts.next().copied().unwrap_or_default() {
+                let wallclock = l.start + l.duration();
+                let time = (index * 1000 + 47_000_000) as u32;
+                let seq_no = (47_000 + index).into();
+
+                l.direct_api()
+                    .stream_tx(&ssrc)
+                    .unwrap()
+                    .write_rtp(
+                        pt,
+                        seq_no,
+                        time,
+                        wallclock,
+                        false,
+                        ExtensionValues::default(),
+                        false,
+                        vec![1, 2, 3, 4],
+                    )
+                    .expect("clean write");
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    let out: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(format!("{}", *p.seq_no - 47_000)),
+            Event::RtpPacketsLost(lost) => Some(format!(
+                "lost {}-{}",
+                **lost.seq_range.start() - 47_000,
+                **lost.seq_range.end() - 47_000
+            )),
+            Event::JitterBuffer(e) => {
+                assert_eq!(e.ssrc, ssrc);
+                assert_eq!(e.delay, Duration::from_millis(100));
+                Some(format!("{:?} {}", e.kind, e.packets))
+            }
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        out,
+        [
+            "0",
+            "1",
+            "Underrun 1",
+            "lost 2-2",
+            "3",
+            "Overrun 1",
+            "Overrun 1",
+            "Overrun 1",
+            "lost 4-4",
+            "lost 5-5",
+            "lost 6-6",
+            "7",
+            "8",
+            "9"
+        ]
+    );
+
+    let stats = r
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::MediaIngressStats(s) => Some(s),
+            _ => None,
+        })
+        .expect("ingress stats");
+
+    assert_eq!(stats.jitter_buffer_underruns, 1);
+    assert_eq!(stats.jitter_buffer_overruns, 3);
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use str0m::media::{KeyframeRequestKind, MediaKind};
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn keyframe_request_debounce() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_millis(500)))
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc, None, mid, None)
+        .set_keyframe_request_interval(Duration::from_millis(500));
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+    let mut requests = 0;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![0x1, 0x2, 0x3, 0x4],
+                )
+                .expect("clean write");
+
+            // A burst of requests, like many viewers in an SFU. The first RTCP
+            // can arrive before the remote has set up SRTP, so wait a bit.
+            let burst = Duration::from_millis(100)..Duration::from_secs(2);
+            if burst.contains(&l.duration()) {
+                r.direct_api()
+                    .stream_rx(&ssrc)
+                    .unwrap()
+                    .request_keyframe(KeyframeRequestKind::Pli);
+                requests += 1;
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let received: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(t, e)| match e {
+            Event::KeyframeRequest(_) => Some(*t),
+            _ => None,
+        })
+        .collect();
+
+    // One request right away, then one per interval. The last one is for the requests
+    // held back at the end of the burst.
+    assert_eq!(received.len(), 5, "{:?}", received);
+
+    for w in received.windows(2) {
+        assert!(w[1] - w[0] >= Duration::from_millis(500));
+    }
+
+    let stats = r
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::MediaIngressStats(s) => Some(s),
+            _ => None,
+        })
+        .expect("ingress stats");
+
+    assert_eq!(stats.plis, received.len() as u64);
+    assert_eq!(stats.plis + stats.keyframe_requests_suppressed, requests);
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use str0m::media::{KeyframeRequestKind, MediaKind, Mid};
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, TestRtc};
+
+#[test]
+pub fn global_keyframe_request_rate() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, streams) = connect(4);
+
+    r.rtc
+        .set_global_keyframe_request_rate(2, Duration::from_millis(500));
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            for (_, ssrc) in &streams {
+                l.direct_api()
+                    .stream_tx(ssrc)
+                    .unwrap()
+                    .write_rtp(
+                        pt,
+                        seq_no,
+                        time,
+                        wallclock,
+                        false,
+                        ExtensionValues::default(),
+                        true,
+                        vec![0x1, 0x2, 0x3, 0x4],
+                    )
+                    .expect("clean write");
+            }
+
+            // All viewers of all streams want keyframes all the time. The first RTCP
+            // can arrive before the remote has set up SRTP, so wait a bit.
+            if l.duration() > Duration::from_millis(100) {
+                for (_, ssrc) in &streams {
+                    r.direct_api()
+                        .stream_rx(ssrc)
+                        .unwrap()
+                        .request_keyframe(KeyframeRequestKind::Pli);
+                }
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let received: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(t, e)| match e {
+            Event::KeyframeRequest(r) => Some((*t, r.mid)),
+            _ => None,
+        })
+        .collect();
+
+    // Without the limit, this would be one request per stream and timeout.
+    assert!(
+        (8..=12).contains(&received.len()),
+        "{}: {:?}",
+        received.len(),
+        received
+    );
+
+    for w in received.windows(3) {
+        assert!(w[2].0 - w[0].0 >= Duration::from_millis(490), "{:?}", w);
+    }
+
+    // Each stream gets its turn.
+    let mids: HashSet<_> = received.iter().map(|(_, m)| *m).collect();
+    assert_eq!(mids.len(), streams.len());
+
+    Ok(())
+}
+
+#[test]
+pub fn global_keyframe_request_rate_longest_waiting_first() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, streams) = connect(3);
+
+    r.rtc
+        .set_global_keyframe_request_rate(1, Duration::from_millis(500));
+
+    let pt = l.params_vp8().pt();
+
+    // The first request takes the budget. The other two are held back, the one
+    // with the higher SSRC is requested first.
+    let requests = [
+        (Duration::from_millis(100), streams[0].1),
+        (Duration::from_millis(150), streams[2].1),
+        (Duration::from_millis(250), streams[1].1),
+    ];
+
+    let mut index = 0;
+    let mut write_at = l.last;
+    let mut requested = 0;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            for (_, ssrc) in &streams {
+                l.direct_api()
+                    .stream_tx(ssrc)
+                    .unwrap()
+                    .write_rtp(
+                        pt,
+                        seq_no,
+                        time,
+                        wallclock,
+                        false,
+                        ExtensionValues::default(),
+                        true,
+                        vec![0x1, 0x2, 0x3, 0x4],
+                    )
+                    .expect("clean write");
+            }
+        }
+
+        if let Some((at, ssrc)) = requests.get(requested) {
+            if l.duration() > *at {
+                r.direct_api()
+                    .stream_rx(ssrc)
+                    .unwrap()
+                    .request_keyframe(KeyframeRequestKind::Pli);
+                requested += 1;
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_millis(1500) {
+            break;
+        }
+    }
+
+    let received: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::KeyframeRequest(r) => Some(r.mid),
+            _ => None,
+        })
+        .collect();
+
+    let expected: Vec<_> = requests
+        .iter()
+        .map(|(_, ssrc)| streams.iter().find(|(_, s)| s == ssrc).unwrap().0)
+        .collect();
+
+    assert_eq!(received, expected);
+
+    Ok(())
+}
+
+fn connect(count: u32) -> (TestRtc, TestRtc, Vec<(Mid, Ssrc)>) {
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let streams: Vec<(Mid, Ssrc)> = (0..count)
+        .map(|i| (format!("v{}", i).as_str().into(), (42 + i).into()))
+        .collect();
+
+    for (mid, ssrc) in &streams {
+        l.direct_api().declare_media(*mid, MediaKind::Video);
+        l.direct_api().declare_stream_tx(*ssrc, None, *mid, None);
+
+        r.direct_api().declare_media(*mid, MediaKind::Video);
+        r.direct_api().expect_stream_rx(*ssrc, None, *mid, None);
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    (l, r, streams)
+}
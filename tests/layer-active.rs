@@ -0,0 +1,88 @@
+use std::net::Ipv4Addr;
+
+use str0m::change::SdpAnswer;
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::{LayerActive, Ssrc};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn layer_stopped_by_remote_bitrate() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = Rtc::builder().set_rtp_mode(true).build();
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc);
+    let rtc = Rtc::builder().set_rtp_mode(true).build();
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+
+    // The remote peer limits the media to 300kbps.
+    let mut answer = answer.to_string();
+    let m_line = answer.find("m=video").unwrap();
+    let c_line = m_line + answer[m_line..].find("c=IN").unwrap();
+    let b_line = c_line + answer[c_line..].find("\r\n").unwrap() + 2;
+    answer.insert_str(b_line, "b=AS:300\r\n");
+
+    let answer = SdpAnswer::from_sdp_string(&answer).unwrap();
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let media = l.media(mid).unwrap();
+    assert_eq!(media.remote_bitrate(), Some(300_000.into()));
+
+    let ssrc_low: Ssrc = 42.into();
+    let ssrc_mid: Ssrc = 43.into();
+    let ssrc_high: Ssrc = 44.into();
+
+    for (ssrc, rid, kbps) in [
+        (ssrc_low, "l", 100),
+        (ssrc_mid, "m", 150),
+        (ssrc_high, "h", 1000),
+    ] {
+        l.direct_api()
+            .declare_stream_tx(ssrc, None, mid, Some(rid.into()))
+            .set_layer_bitrate((kbps * 1000).into());
+    }
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    for _ in 0..10 {
+        progress(&mut l, &mut r)?;
+    }
+
+    let layers: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::LayerActive(LayerActive { ssrc, active, .. }) => Some((*ssrc, *active)),
+            _ => None,
+        })
+        .collect();
+
+    // 100 + 150 = 250kbps fits in 300kbps with the default thresholds, 1250kbps does not.
+    assert_eq!(layers, [(ssrc_high, false)]);
+
+    let mut api = l.direct_api();
+    assert!(api.stream_tx(&ssrc_low).unwrap().is_layer_active());
+    assert!(api.stream_tx(&ssrc_mid).unwrap().is_layer_active());
+    assert!(!api.stream_tx(&ssrc_high).unwrap().is_layer_active());
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use str0m::media::{LossNotification, MediaKind};
+use str0m::rtp::{ExtensionValues, SeqNo, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+// Crosses the 16 bit sequence number wrap around.
+const LAST_DECODED: u64 = 65_520;
+const LAST_RECEIVED: u64 = 65_540;
+
+#[test]
+pub fn loss_notification() -> Result<(), RtcError> {
+    init_log();
+
+    let (received, delay) = run(true)?;
+
+    assert_eq!(received.len(), 1, "{:?}", received);
+
+    let n = &received[0];
+    assert_eq!(n.mid, "vid".into());
+    assert_eq!(n.rid, None);
+    assert_eq!(n.last_decoded, SeqNo::from(LAST_DECODED));
+    assert_eq!(n.last_received, SeqNo::from(LAST_RECEIVED));
+    assert!(n.decodable);
+
+    // Sent straight away, not with the next regular receiver report.
+    assert!(delay < Duration::from_millis(50), "{:?}", delay);
+
+    Ok(())
+}
+
+#[test]
+pub fn loss_notification_not_negotiated() -> Result<(), RtcError> {
+    init_log();
+
+    let (received, _) = run(false)?;
+
+    assert!(received.is_empty(), "{:?}", received);
+
+    Ok(())
+}
+
+/// The loss notifications L received and how long after the request the first arrived.
+fn run(fb_lntf: bool) -> Result<(Vec<LossNotification>, Duration), RtcError> {
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let mut config = Rtc::builder().set_rtp_mode(true);
+    for p in config.codec_config().iter_mut() {
+        p.set_fb_lntf(fb_lntf);
+    }
+    let rtc2 = config.build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+    let mut requested_at = None;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800) as u32;
+            let seq_no = (65_500 + index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![0x1, 0x2, 0x3, 0x4],
+                )
+                .expect("clean write");
+
+            if requested_at.is_none() && l.duration() > Duration::from_secs(1) {
+                r.direct_api()
+                    .stream_rx(&ssrc)
+                    .unwrap()
+                    .request_loss_notification(LAST_DECODED.into(), LAST_RECEIVED.into(), true);
+                requested_at = Some(r.last);
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    let received: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(at, e)| match e {
+            Event::LossNotification(v) => Some((*at, *v)),
+            _ => None,
+        })
+        .collect();
+
+    let delay = received
+        .first()
+        .map(|(at, _)| *at - requested_at.unwrap())
+        .unwrap_or_default();
+
+    Ok((received.into_iter().map(|(_, v)| v).collect(), delay))
+}
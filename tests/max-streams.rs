@@ -0,0 +1,87 @@
+use std::net::Ipv4Addr;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Event, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{connect_l_r, init_log, progress, TestRtc};
+
+#[test]
+pub fn max_streams_sdp() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    r.rtc.set_max_streams(1, 1024);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    // Each media announces an SSRC in the offer.
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let rejected: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::StreamRejected(v) => Some(v),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(rejected.len(), 1);
+
+    let ssrc = rejected[0].ssrc;
+    assert!(r.direct_api().stream_rx(&ssrc).is_none());
+
+    Ok(())
+}
+
+#[test]
+pub fn max_streams_direct() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, _r) = connect_l_r();
+
+    l.rtc.set_max_streams(1, 1);
+
+    let mid = "aud".into();
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+
+    // Explicitly added streams are not limited.
+    for ssrc in [1, 2] {
+        l.direct_api()
+            .declare_stream_tx(ssrc.into(), None, mid, None);
+        assert!(l.direct_api().stream_tx(&ssrc.into()).is_some());
+    }
+
+    for ssrc in [3, 4] {
+        l.direct_api()
+            .expect_stream_rx(ssrc.into(), None, mid, None);
+        assert!(l.direct_api().stream_rx(&ssrc.into()).is_some());
+    }
+
+    assert!(!l
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::StreamRejected(_))));
+
+    Ok(())
+}
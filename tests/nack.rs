@@ -3,12 +3,13 @@ use std::time::{Duration, Instant};
 
 use str0m::format::Codec;
 use str0m::media::MediaKind;
+use str0m::net::Receive;
 use str0m::rtp::rtcp::Rtcp;
 use str0m::rtp::{ExtensionValues, RawPacket, SeqNo, Ssrc};
-use str0m::RtcError;
+use str0m::{Event, Input, Output, Rtc, RtcError};
 
 mod common;
-use common::{connect_l_r, init_log, progress};
+use common::{connect_l_r, connect_l_r_with_rtc, init_log, progress, TestRtc};
 
 use crate::common::progress_with_loss;
 
@@ -165,6 +166,14 @@ pub fn loss_recovery() -> Result<(), RtcError> {
     assert_eq!(discontinuities.len(), 0);
     assert_eq!(packets_rx.len(), num_packets);
 
+    // The resends are accounted separately from the media.
+    let overhead = l.overhead_stats();
+    assert_eq!(overhead.media_packets, num_packets as u64);
+    assert_eq!(overhead.media_bytes, (num_packets * to_write.len()) as u64);
+    assert!(overhead.rtx_packets > 0);
+    let rtx_percent = overhead.rtx_percent().unwrap();
+    assert!(rtx_percent > 0.0 && rtx_percent < 50.0, "{}", rtx_percent);
+
     Ok(())
 }
 
@@ -318,3 +327,258 @@ pub fn nack_delay() -> Result<(), RtcError> {
 
     Ok(())
 }
+
+#[test]
+pub fn rtx_recovered_once() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .set_stats_interval(Some(Duration::from_secs(1)))
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc_tx: Ssrc = 42.into();
+    let ssrc_rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api()
+        .declare_stream_tx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let params = l.params_vp8();
+    let pt = params.pt();
+    let rtx_pt = params.resend().unwrap();
+
+    let num_packets = 20;
+    let dropped = 47_005;
+
+    let mut to_write = 0..num_packets;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(100);
+
+            if let Some(index) = to_write.next() {
+                let wallclock = l.start + l.duration();
+                let time = (index * 1000 + 47_000_000) as u32;
+                let seq_no = (47_000 + index as u64).into();
+
+                l.direct_api()
+                    .stream_tx(&ssrc_tx)
+                    .unwrap()
+                    .write_rtp(
+                        pt,
+                        seq_no,
+                        time,
+                        wallclock,
+                        false,
+                        ExtensionValues::default(),
+                        true,
+                        vec![0x1, 0x2, 0x3, 0x4],
+                    )
+                    .expect("clean write");
+            }
+        }
+
+        // Drop the main packet once, and deliver every resend twice.
+        progress_mangled(&mut l, &mut r, |data| match rtp_seq_pt(data) {
+            Some((seq, p)) if p == *pt && seq == dropped => 0,
+            Some((_, p)) if p == *rtx_pt => 2,
+            _ => 1,
+        })?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let mut seq_nos: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(*p.seq_no),
+            _ => None,
+        })
+        .collect();
+
+    let total = seq_nos.len();
+    seq_nos.sort();
+    seq_nos.dedup();
+
+    assert_eq!(total, num_packets, "every packet delivered exactly once");
+    assert_eq!(seq_nos.len(), num_packets);
+    assert!(seq_nos.contains(&dropped.into()));
+
+    let recovered = r.events.iter().rev().find_map(|(_, e)| match e {
+        Event::MediaIngressStats(s) => Some(s.rtx_recovered),
+        _ => None,
+    });
+
+    assert_eq!(recovered, Some(1));
+
+    Ok(())
+}
+
+#[test]
+pub fn nack_flood_capped() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_secs(1)))
+        .build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+    let ssrc_tx: Ssrc = 42.into();
+    let ssrc_rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api()
+        .declare_stream_tx(ssrc_tx, Some(ssrc_rtx), mid, None)
+        .set_max_rtx_ratio(0.1);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let params = l.params_vp8();
+    let pt = params.pt();
+    let rtx_pt = params.resend().unwrap();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    let mut sent = 0;
+    let mut resent = 0;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(10);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1000 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc_tx)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![0x1; 1000],
+                )
+                .expect("clean write");
+        }
+
+        // Drop every 5th main packet, and deliver all RTCP (the NACKs) 20 times.
+        progress_mangled(&mut l, &mut r, |data| {
+            let is_rtcp = data.len() > 2 && (192..=223).contains(&data[1]);
+            match rtp_seq_pt(data) {
+                Some((seq, p)) if p == *pt => {
+                    sent += 1;
+                    if seq % 5 == 0 {
+                        0
+                    } else {
+                        1
+                    }
+                }
+                Some((_, p)) if p == *rtx_pt => {
+                    resent += 1;
+                    1
+                }
+                _ if is_rtcp => 20,
+                _ => 1,
+            }
+        })?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    // The flood would be 20 resends per lost packet without the cap.
+    let ratio = resent as f32 / (sent + resent) as f32;
+    assert!(resent > 0);
+    assert!(ratio <= 0.11, "rtx ratio {} above cap", ratio);
+
+    let dropped = l.events.iter().rev().find_map(|(_, e)| match e {
+        Event::MediaEgressStats(s) => Some(s.resends_dropped),
+        _ => None,
+    });
+
+    assert!(dropped.unwrap() > 0);
+
+    Ok(())
+}
+
+/// Sequence number and payload type of an (unencrypted header) RTP packet.
+fn rtp_seq_pt(data: &[u8]) -> Option<(u16, u8)> {
+    let is_rtp = data.len() > 12 && data[0] >> 6 == 2 && !(192..=223).contains(&data[1]);
+    is_rtp.then(|| (u16::from_be_bytes([data[2], data[3]]), data[1] & 0x7f))
+}
+
+/// Like [`progress`], but the closure decides how many times each transmit is delivered.
+fn progress_mangled(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    mut deliveries: impl FnMut(&[u8]) -> usize,
+) -> Result<(), RtcError> {
+    let (f, t) = if l.last < r.last { (l, r) } else { (r, l) };
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) => {
+                for _ in 0..deliveries(&v.contents) {
+                    let input = Input::Receive(
+                        f.last,
+                        Receive::new(v.proto, v.source, v.destination, &v.contents)?
+                            .with_ecn(v.ecn),
+                    );
+                    t.span.in_scope(|| t.rtc.handle_input(input))?;
+                }
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
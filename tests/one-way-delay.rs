@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use str0m::media::MediaKind;
+use str0m::net::{Protocol, Receive};
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Input, Output, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, TestRtc};
+
+const DELAY: Duration = Duration::from_millis(30);
+
+#[test]
+pub fn one_way_delay() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let delays = send_audio(&mut l, &mut r)?;
+
+    assert!(delays.len() > 45);
+
+    // abs-send-time has a resolution of ~4 microseconds.
+    assert!(delays
+        .iter()
+        .all(|d| d.abs_diff(DELAY) < Duration::from_millis(1)));
+
+    Ok(())
+}
+
+#[test]
+pub fn one_way_delay_no_abs_send_time() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .clear_extension_map()
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .clear_extension_map()
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let delays = send_audio(&mut l, &mut r)?;
+
+    assert!(delays.is_empty());
+
+    Ok(())
+}
+
+/// Send audio from L to R, returning R's one way delay after each packet.
+fn send_audio(l: &mut TestRtc, r: &mut TestRtc) -> Result<Vec<Duration>, RtcError> {
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(l, r)?;
+    }
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    assert_eq!(
+        r.direct_api().stream_rx(&ssrc).unwrap().one_way_delay(),
+        None
+    );
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+    let mut in_flight = VecDeque::new();
+    let mut delays = vec![];
+
+    for index in 0..50 {
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress_delayed(l, r, &mut in_flight)?;
+        }
+
+        if let Some(d) = r.direct_api().stream_rx(&ssrc).unwrap().one_way_delay() {
+            delays.push(d);
+        }
+    }
+
+    let received = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::RtpPacket(_)))
+        .count();
+    assert!(received > 45);
+
+    Ok(delays)
+}
+
+type InFlight = VecDeque<(Instant, Protocol, SocketAddr, SocketAddr, Vec<u8>)>;
+
+/// Like [`progress`], but datagrams from L to R arrive [`DELAY`] after being sent.
+fn progress_delayed(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    in_flight: &mut InFlight,
+) -> Result<(), RtcError> {
+    let l_is_first = l.last < r.last;
+    let (f, t) = if l_is_first { (l, r) } else { (r, l) };
+
+    if !l_is_first {
+        while in_flight.front().map(|(at, ..)| *at <= f.last) == Some(true) {
+            let (at, proto, source, destination, data) = in_flight.pop_front().unwrap();
+            let input = Input::Receive(at, Receive::new(proto, source, destination, &data)?);
+            f.span.in_scope(|| f.rtc.handle_input(input))?;
+        }
+    }
+
+    loop {
+        f.span
+            .in_scope(|| f.rtc.handle_input(Input::Timeout(f.last)))?;
+
+        match f.span.in_scope(|| f.rtc.poll_output())? {
+            Output::Timeout(v) => {
+                let tick = f.last + Duration::from_millis(10);
+                f.last = if v == f.last { tick } else { tick.min(v) };
+                break;
+            }
+            Output::Transmit(v) if l_is_first => {
+                let data = v.contents.to_vec();
+                in_flight.push_back((f.last + DELAY, v.proto, v.source, v.destination, data));
+            }
+            Output::Transmit(v) => {
+                let input = Input::Receive(
+                    f.last,
+                    Receive::new(v.proto, v.source, v.destination, &v.contents)?.with_ecn(v.ecn),
+                );
+                t.span.in_scope(|| t.rtc.handle_input(input))?;
+            }
+            Output::Event(v) => {
+                f.events.push((f.last, v));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use str0m::bwe::Bitrate;
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, FrameMarking, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn pacer_drop() -> Result<(), RtcError> {
+    init_log();
+
+    // The pacer is limited by the low initial estimate.
+    let rtc = Rtc::builder().enable_bwe(Some(Bitrate::kbps(100))).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc, Rtc::new());
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api()
+        .declare_stream_tx(ssrc, None, mid, None)
+        .set_max_queue_age(Some(Duration::from_millis(200)));
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+    let mut max_queue_time = Duration::ZERO;
+
+    for i in 0..300 {
+        let wallclock = l.start + l.duration();
+        let time = (i / 3 * 3000) as u32;
+        let seq_no = (47_000 + i as u64).into();
+
+        // Every other frame is discardable.
+        let exts = ExtensionValues {
+            frame_marking: Some(FrameMarking {
+                discardable: (i / 3) % 2 == 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                time,
+                wallclock,
+                i % 3 == 2,
+                exts,
+                true,
+                vec![1; 1000],
+            )
+            .expect("clean write");
+
+        progress(&mut l, &mut r)?;
+
+        let stats = l.rtc.pacer_stats();
+        max_queue_time = max_queue_time.max(stats.max_queue_time.unwrap_or_default());
+    }
+
+    let stats = l.rtc.pacer_stats();
+    assert!(stats.dropped_packets > 0, "{:?}", stats);
+
+    let dropped: usize = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::PacketsDropped(d) => Some(d.packets),
+            _ => None,
+        })
+        .sum();
+
+    assert_eq!(dropped as u64, stats.dropped_packets);
+
+    // The queue time is bounded by the max age, with some leeway for the time
+    // between the checks.
+    assert!(
+        max_queue_time < Duration::from_millis(300),
+        "{:?}",
+        max_queue_time
+    );
+
+    Ok(())
+}
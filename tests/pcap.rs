@@ -0,0 +1,129 @@
+#![cfg(feature = "pcap")]
+
+use std::io::{self, Cursor, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use pcap_file::pcap::PcapReader;
+use pcap_file::DataLink;
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+pub fn pcap() -> Result<(), RtcError> {
+    init_log();
+
+    // Both sides write to the same capture.
+    let capture = Capture::default();
+    let config = Rtc::builder().set_pcap_writer(capture.clone());
+
+    let (mut l, mut r) = connect_l_r_with_rtc(config.clone().build(), config.build());
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+    let payload = vec![0x5a; 100];
+
+    for i in 0..10 {
+        let wallclock = l.start + l.duration();
+        let time = (i * 960) as u32;
+        let seq_no = (47_000 + i as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                time,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                payload.clone(),
+            )
+            .expect("clean write");
+
+        progress(&mut l, &mut r)?;
+    }
+
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let data = capture.0.lock().unwrap().clone();
+    let mut reader = PcapReader::new(Cursor::new(data)).expect("pcap header");
+    assert_eq!(reader.header().datalink, DataLink::RAW);
+
+    let mut stun = 0;
+    let mut dtls = 0;
+    let mut rtp = 0;
+    let mut rtcp = 0;
+
+    while let Some(pkt) = reader.next_packet() {
+        let pkt = pkt.unwrap();
+
+        // IPv4 + UDP header.
+        assert_eq!(pkt.data[0], 0x45);
+        assert_eq!(pkt.data[9], 17);
+        let udp = &pkt.data[28..];
+
+        match udp[0] {
+            0..=3 => stun += 1,
+            20..=63 => dtls += 1,
+            128..=191 if (192..=223).contains(&udp[1]) => rtcp += 1,
+            128..=191 => {
+                // Decrypted, the payload is as written, followed by any padding.
+                let pad = if udp[0] & 0x20 > 0 {
+                    udp[udp.len() - 1]
+                } else {
+                    0
+                };
+                assert!(udp[..udp.len() - pad as usize].ends_with(&payload));
+                rtp += 1;
+            }
+            _ => panic!("Unexpected packet: {:?}", udp),
+        }
+    }
+
+    assert!(stun > 0);
+    assert!(dtls > 0);
+    assert!(rtcp > 0);
+
+    // Every packet is both sent and received.
+    assert_eq!(rtp, 20);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+use str0m::bwe::Bitrate;
+use str0m::media::{Direction, MediaKind};
+use str0m::net::{Receive, Transmit};
+use str0m::{Candidate, Event, Input, Output, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, TestRtc};
+
+#[test]
+pub fn events_before_transmits() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+    let rtc_r = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let cid = change.add_channel("data".into());
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let pt = l.params_vp8().pt();
+
+    let mut inbox_l = vec![];
+    let mut inbox_r = vec![];
+    let mut write_at = l.last;
+
+    while l.duration() < Duration::from_secs(3) {
+        if l.is_connected() && l.last >= write_at {
+            write_at = l.last + Duration::from_millis(32);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid)
+                .unwrap()
+                .write(pt, wallclock, time, vec![1_u8; 1000])?;
+
+            if let Some(mut chan) = l.channel(cid) {
+                chan.write(false, b"hello")?;
+            }
+        }
+
+        if l.last <= r.last {
+            step(&mut l, &mut inbox_l, &mut inbox_r)?;
+        } else {
+            step(&mut r, &mut inbox_r, &mut inbox_l)?;
+        }
+    }
+
+    // The session did get to do all the things that produce events.
+    let has = |t: &TestRtc, f: fn(&Event) -> bool| t.events.iter().any(|(_, e)| f(e));
+    assert!(has(&r, |e| matches!(e, Event::MediaData(_))));
+    assert!(has(&r, |e| matches!(e, Event::ChannelData(_))));
+    assert!(has(&l, |e| matches!(e, Event::EgressBitrateEstimate(_))));
+
+    Ok(())
+}
+
+/// Deliver the datagrams sent to `f`, then the timeout. Datagrams sent by `f` go to `inbox_t`.
+fn step(
+    f: &mut TestRtc,
+    inbox_f: &mut Vec<Transmit>,
+    inbox_t: &mut Vec<Transmit>,
+) -> Result<(), RtcError> {
+    for v in inbox_f.drain(..) {
+        let input = Input::Receive(
+            f.last,
+            Receive::new(v.proto, v.source, v.destination, &v.contents)?.with_ecn(v.ecn),
+        );
+        let (sent, _) = drain(f, input)?;
+        inbox_t.extend(sent);
+    }
+
+    let (sent, timeout) = drain(f, Input::Timeout(f.last))?;
+    inbox_t.extend(sent);
+
+    let tick = f.last + Duration::from_millis(10);
+    f.last = if timeout == f.last {
+        tick
+    } else {
+        tick.min(timeout)
+    };
+
+    Ok(())
+}
+
+/// Handle one input and poll until timeout, checking all events come before any transmit.
+fn drain(t: &mut TestRtc, input: Input) -> Result<(Vec<Transmit>, Instant), RtcError> {
+    t.span.in_scope(|| t.rtc.handle_input(input))?;
+
+    let mut sent = vec![];
+
+    loop {
+        match t.span.in_scope(|| t.rtc.poll_output())? {
+            Output::Timeout(v) => return Ok((sent, v)),
+            Output::Transmit(v) => sent.push(v),
+            Output::Event(e) => {
+                assert!(sent.is_empty(), "Event after transmit: {:?}", e);
+                t.events.push((t.last, e));
+            }
+        }
+    }
+}
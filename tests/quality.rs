@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, QualityInput, Ssrc};
+use str0m::RtcError;
+
+mod common;
+use common::{connect_l_r, init_log, progress};
+
+#[test]
+pub fn quality() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let quality = r.direct_api().stream_rx(&ssrc).unwrap().quality();
+    assert_eq!(quality.input.loss, 0.0);
+    assert_eq!(quality.mos, 5.0);
+
+    fn always_bad(_: MediaKind, _: &QualityInput) -> f32 {
+        0.0
+    }
+
+    let mut api = r.direct_api();
+    let stream = api.stream_rx(&ssrc).unwrap();
+    stream.set_quality_estimator(always_bad);
+    assert_eq!(stream.quality().mos, 1.0);
+
+    Ok(())
+}
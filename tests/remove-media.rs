@@ -0,0 +1,160 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::change::SdpOffer;
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::rtp::rtcp::Rtcp;
+use str0m::rtp::RawPacket;
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn remove_media() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_r = Rtc::builder().enable_raw_packets(true).build();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid_video = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    run(
+        &mut l,
+        &mut r,
+        &[mid_video, mid_audio],
+        Duration::from_secs(1),
+    )?;
+
+    let ssrc_video = l
+        .direct_api()
+        .stream_tx_by_mid(mid_video, None)
+        .unwrap()
+        .ssrc();
+
+    // Remove the video, which is the first m-line.
+    let mut change = l.sdp_api();
+    change.remove_media(mid_video);
+    let (offer, pending) = change.apply().unwrap();
+
+    // Nothing changes until the answer is accepted.
+    assert!(!l.media(mid_video).unwrap().rejected());
+
+    let sdp = offer.to_sdp_string();
+    assert!(sdp.contains("m=video 0 "), "{}", sdp);
+    assert!(sdp.contains(&format!("a=group:BUNDLE {}\r\n", mid_audio)));
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+
+    let sdp = answer.to_sdp_string();
+    assert!(sdp.contains("m=video 0 "), "{}", sdp);
+    assert!(sdp.contains(&format!("a=group:BUNDLE {}\r\n", mid_audio)));
+
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    for t in [&l, &r] {
+        let media = t.media(mid_video).unwrap();
+        assert!(media.rejected());
+        assert_eq!(media.direction(), Direction::Inactive);
+    }
+
+    assert!(l.direct_api().stream_tx_by_mid(mid_video, None).is_none());
+    assert!(r.direct_api().stream_rx_by_mid(mid_video, None).is_none());
+
+    let start = l.duration();
+    run(&mut l, &mut r, &[mid_audio], Duration::from_secs(1))?;
+
+    // The audio continues, but no more video arrives.
+    assert!(count(&r, mid_audio, start) > 30);
+    assert_eq!(count(&r, mid_video, start), 0);
+
+    // The remote was told the SSRC is gone.
+    assert!(r.events.iter().any(|(_, e)| matches!(
+        e.as_raw_packet(),
+        Some(RawPacket::RtcpRx(Rtcp::Goodbye(g))) if g.reports.iter().any(|s| *s == ssrc_video)
+    )));
+
+    // Removing it again needs no negotiation.
+    let mut change = l.sdp_api();
+    change.remove_media(mid_video);
+    assert!(change.apply().is_none());
+
+    // A remote offer that has the removed m-line with a port doesn't revive it.
+    let mut change = r.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::RecvOnly, None, None);
+    let (offer, _pending) = change.apply().unwrap();
+    let sdp = offer.to_sdp_string().replace("m=video 0 ", "m=video 9 ");
+    let offer = SdpOffer::from_sdp_string(&sdp)?;
+
+    let answer = l.rtc.sdp_api().accept_offer(offer)?;
+    assert!(l.media(mid_video).unwrap().rejected());
+    assert!(answer.to_sdp_string().contains("m=video 0 "));
+
+    Ok(())
+}
+
+fn count(t: &TestRtc, mid: Mid, since: Duration) -> usize {
+    t.events
+        .iter()
+        .filter(|(at, e)| {
+            *at >= t.start + since && matches!(e, Event::MediaData(d) if d.mid == mid)
+        })
+        .count()
+}
+
+fn run(l: &mut TestRtc, r: &mut TestRtc, mids: &[Mid], duration: Duration) -> Result<(), RtcError> {
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+
+            for mid in mids {
+                let video = l.media(*mid).unwrap().kind().is_video();
+                let (pt, data) = if video {
+                    (l.params_vp8().pt(), vec![1_u8; 800])
+                } else {
+                    (l.params_opus().pt(), vec![2_u8; 80])
+                };
+
+                l.writer(*mid).unwrap().write(pt, wallclock, time, data)?;
+            }
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
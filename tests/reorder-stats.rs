@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, ReorderStats, Ssrc};
+use str0m::RtcError;
+
+mod common;
+use common::{connect_l_r, init_log, progress};
+
+#[test]
+pub fn reorder_stats() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    // 3 is late by one, 7 is late by two and 5 is sent twice.
+    let order = [0, 1, 2, 4, 3, 5, 6, 8, 9, 7, 5, 10, 11];
+
+    for index in order {
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    let stats = r.direct_api().stream_rx(&ssrc).unwrap().reorder_stats();
+    assert_eq!(
+        stats,
+        ReorderStats {
+            reordered: 2,
+            max_distance: 2,
+            duplicates: 1,
+        }
+    );
+
+    r.direct_api().stream_rx(&ssrc).unwrap().reset_stats();
+    let stats = r.direct_api().stream_rx(&ssrc).unwrap().reorder_stats();
+    assert_eq!(stats, ReorderStats::default());
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use str0m::rtp::rtcp::{App, Rtcp};
+use str0m::rtp::RawPacket;
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn rtcp_app() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let app1 = App {
+        subtype: 7,
+        ssrc: 42.into(),
+        name: *b"STRM",
+        data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+    };
+    let app2 = App {
+        subtype: 0,
+        ssrc: 42.into(),
+        name: *b"TEST",
+        data: vec![],
+    };
+
+    l.direct_api().send_rtcp_app(app1.clone())?;
+    l.direct_api().send_rtcp_app(app2.clone())?;
+
+    let settle = l.duration() + Duration::from_secs(1);
+    while l.duration() < settle {
+        progress(&mut l, &mut r)?;
+    }
+
+    let received: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtcpApp(a) => Some(a),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(received, vec![&app1, &app2]);
+
+    // APP packets are also visible among the raw incoming RTCP.
+    let raw = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e.as_raw_packet(), Some(RawPacket::RtcpRx(Rtcp::App(_)))))
+        .count();
+    assert_eq!(raw, 2);
+
+    // Nothing was sent the other way.
+    assert!(!l.events.iter().any(|(_, e)| matches!(e, Event::RtcpApp(_))));
+
+    Ok(())
+}
+
+#[test]
+pub fn rtcp_app_invalid() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, _r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let bad_subtype = App {
+        subtype: 32,
+        ssrc: 42.into(),
+        name: *b"STRM",
+        data: vec![],
+    };
+    let res = l.direct_api().send_rtcp_app(bad_subtype);
+    assert!(matches!(res, Err(RtcError::InvalidRtcpApp(_))));
+
+    let too_large = App {
+        subtype: 0,
+        ssrc: 42.into(),
+        name: *b"STRM",
+        data: vec![0; 1500],
+    };
+    let res = l.direct_api().send_rtcp_app(too_large);
+    assert!(matches!(res, Err(RtcError::InvalidRtcpApp(_))));
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::rtcp::{Rtcp, RtcpObserver};
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+/// Number of RTCP packets in each received datagram.
+#[derive(Debug, Default, Clone)]
+struct Collect(Arc<Mutex<Vec<usize>>>);
+
+impl RtcpObserver for Collect {
+    fn on_rtcp_rx(&self, _now: Instant, packets: &[Rtcp]) {
+        self.0.lock().unwrap().push(packets.len());
+    }
+}
+
+#[test]
+pub fn rtcp_compound() -> Result<(), RtcError> {
+    let (sdp, per_datagram) = run(true)?;
+
+    assert!(!sdp.contains("a=rtcp-rsize"));
+    assert!(per_datagram.iter().any(|n| *n > 1), "{:?}", per_datagram);
+
+    Ok(())
+}
+
+#[test]
+pub fn rtcp_non_compound() -> Result<(), RtcError> {
+    let (sdp, per_datagram) = run(false)?;
+
+    assert!(sdp.contains("a=rtcp-rsize"));
+    assert!(per_datagram.len() > 1);
+    assert!(per_datagram.iter().all(|n| *n == 1), "{:?}", per_datagram);
+
+    Ok(())
+}
+
+fn run(compound: bool) -> Result<(String, Vec<usize>), RtcError> {
+    run_with(compound, compound)
+}
+
+/// Returns the offer from L and the number of RTCP packets per datagram L receives.
+fn run_with(compound_l: bool, compound_r: bool) -> Result<(String, Vec<usize>), RtcError> {
+    init_log();
+
+    let observed = Collect::default();
+
+    let rtc_l = Rtc::builder()
+        .set_rtcp_compound(compound_l)
+        .set_rtcp_observer(observed.clone())
+        .build();
+    let rtc_r = Rtc::builder().set_rtcp_compound(compound_r).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let sdp = offer.to_sdp_string();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, vec![1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    let per_datagram = observed.0.lock().unwrap().clone();
+
+    Ok((sdp, per_datagram))
+}
+
+#[test]
+pub fn rtcp_non_compound_remote_compound() -> Result<(), RtcError> {
+    let (sdp, per_datagram) = run_with(true, false)?;
+
+    // R wants reduced-size, but L's offer doesn't have it.
+    assert!(!sdp.contains("a=rtcp-rsize"));
+    assert!(per_datagram.iter().any(|n| *n > 1), "{:?}", per_datagram);
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Input, Output, Reason, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn schedule_immediate_rtcp() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, vec![1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    // Audio RR are every 5 seconds, so the feedback is not due. Anything else that is due,
+    // such as TWCC, is handled first.
+    r.rtc.handle_input(Input::Timeout(r.last))?;
+    let timeout = poll_timeout(&mut r)?;
+    assert!(timeout > r.last);
+
+    r.rtc.schedule_immediate_rtcp();
+
+    let timeout = poll_timeout(&mut r)?;
+    assert!(timeout <= r.last);
+    assert_eq!(r.rtc.last_timeout_reason(), Reason::Feedback);
+
+    // Sends the RR.
+    r.rtc.handle_input(Input::Timeout(r.last))?;
+    let mut transmits = 0;
+    let timeout = loop {
+        match r.rtc.poll_output()? {
+            Output::Timeout(v) => break v,
+            Output::Transmit(_) => transmits += 1,
+            Output::Event(_) => {}
+        }
+    };
+    assert!(transmits > 0);
+
+    // Back to the regular interval.
+    assert!(timeout > r.last);
+
+    Ok(())
+}
+
+fn poll_timeout(rtc: &mut TestRtc) -> Result<std::time::Instant, RtcError> {
+    loop {
+        if let Output::Timeout(v) = rtc.rtc.poll_output()? {
+            return Ok(v);
+        }
+    }
+}
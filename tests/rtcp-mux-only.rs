@@ -0,0 +1,155 @@
+use std::net::Ipv4Addr;
+
+use str0m::change::SdpOffer;
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+fn pair(l_mux_only: bool) -> Result<(TestRtc, TestRtc), RtcError> {
+    let rtc_l = Rtc::builder().set_rtcp_mux_only(l_mux_only).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    Ok((l, r))
+}
+
+#[test]
+pub fn rtcp_mux_only_offer() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = pair(true)?;
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let sdp = offer.to_sdp_string();
+    assert!(sdp.contains("a=rtcp-mux\r\n"));
+    assert!(sdp.contains("a=rtcp-mux-only\r\n"));
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+
+    let sdp = answer.to_sdp_string();
+    assert!(sdp.contains("a=rtcp-mux\r\n"));
+    assert!(!sdp.contains("a=rtcp-mux-only"));
+
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let media = l.media(mid).unwrap();
+    assert!(!media.rejected());
+    assert_eq!(media.direction(), Direction::SendRecv);
+
+    Ok(())
+}
+
+#[test]
+pub fn rtcp_mux_only_reject_non_muxed() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = pair(true)?;
+
+    let mut change = r.sdp_api();
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let mid_video = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, _pending) = change.apply().unwrap();
+
+    // Remove rtcp-mux from the audio m-line only.
+    let sdp = offer.to_sdp_string();
+    let video_at = sdp.find("m=video").unwrap();
+    let (audio, video) = sdp.split_at(video_at);
+    let munged = format!("{}{}", audio.replace("a=rtcp-mux\r\n", ""), video);
+    let offer = SdpOffer::from_sdp_string(&munged)?;
+
+    let answer = l.rtc.sdp_api().accept_offer(offer)?;
+
+    let sdp = answer.to_sdp_string();
+    assert!(sdp.contains("m=audio 0 "));
+    assert!(sdp.contains("m=video 9 "));
+
+    let audio = l.media(mid_audio).unwrap();
+    assert!(audio.rejected());
+    assert_eq!(audio.direction(), Direction::Inactive);
+
+    let video = l.media(mid_video).unwrap();
+    assert!(!video.rejected());
+    assert_eq!(video.direction(), Direction::SendRecv);
+
+    Ok(())
+}
+
+#[test]
+pub fn rtcp_mux_not_required() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = pair(false)?;
+
+    let mut change = r.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, _pending) = change.apply().unwrap();
+
+    let sdp = offer.to_sdp_string();
+    assert!(!sdp.contains("a=rtcp-mux-only"));
+
+    let munged = sdp.replace("a=rtcp-mux\r\n", "");
+    let offer = SdpOffer::from_sdp_string(&munged)?;
+
+    let answer = l.rtc.sdp_api().accept_offer(offer)?;
+    assert!(answer.to_sdp_string().contains("m=audio 9 "));
+
+    let media = l.media(mid).unwrap();
+    assert!(!media.rejected());
+    assert_eq!(media.direction(), Direction::SendRecv);
+
+    Ok(())
+}
+
+#[test]
+pub fn rtcp_port_non_muxed() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = pair(false)?;
+
+    let mut change = r.sdp_api();
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let mid_video = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, _pending) = change.apply().unwrap();
+
+    // A legacy peer with RTCP on a separate port for audio. The video a=rtcp is only a
+    // placeholder, since it's muxed.
+    let sdp = offer.to_sdp_string();
+    let video_at = sdp.find("m=video").unwrap();
+    let (audio, video) = sdp.split_at(video_at);
+    let audio = audio.replace("a=rtcp-mux\r\n", "a=rtcp:50001 IN IP4 2.2.2.2\r\n");
+    let video = video.replace(
+        "a=rtcp-mux\r\n",
+        "a=rtcp-mux\r\na=rtcp:9 IN IP4 0.0.0.0\r\n",
+    );
+    let offer = SdpOffer::from_sdp_string(&format!("{}{}", audio, video))?;
+
+    l.rtc.sdp_api().accept_offer(offer)?;
+
+    let audio = l.media(mid_audio).unwrap();
+    assert!(!audio.rejected());
+    assert_eq!(audio.remote_rtcp_port(), Some(50001));
+
+    let video = l.media(mid_video).unwrap();
+    assert_eq!(video.remote_rtcp_port(), None);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::rtcp::{Rtcp, RtcpObserver};
+use str0m::rtp::RawPacket;
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[derive(Debug, Default, Clone)]
+struct Collect(Arc<Mutex<Vec<Rtcp>>>);
+
+impl RtcpObserver for Collect {
+    fn on_rtcp_rx(&self, _now: Instant, packets: &[Rtcp]) {
+        self.0.lock().unwrap().extend_from_slice(packets);
+    }
+}
+
+#[test]
+pub fn rtcp_observer() -> Result<(), RtcError> {
+    init_log();
+
+    let observed = Collect::default();
+
+    let rtc_r = Rtc::builder()
+        .set_rtcp_observer(observed.clone())
+        .enable_raw_packets(true)
+        .build();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, vec![1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    // Poll out any events from the last RTCP.
+    for _ in 0..10 {
+        progress(&mut l, &mut r)?;
+    }
+
+    let observed = observed.0.lock().unwrap();
+
+    assert!(observed.iter().any(|p| matches!(p, Rtcp::SenderReport(_))));
+
+    // The observer sees the same packets that are handled internally.
+    let handled: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtcpRx(v)) => Some(v.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(*observed, handled);
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::media::Mid;
+use str0m::rtp::rtcp::Rtcp;
+use str0m::rtp::{ExtensionValues, RawPacket, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, TestRtc};
+
+const STREAMS: u32 = 40;
+
+#[test]
+pub fn rtcp_report_blocks() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = || Rtc::builder().enable_raw_packets(true).build();
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc(), rtc());
+
+    // One stream per mid, since str0m only sends one stream per mid without rid.
+    let streams: Vec<(Mid, Ssrc)> = (1..=STREAMS)
+        .map(|i| (format!("v{i}").as_str().into(), i.into()))
+        .collect();
+
+    for (mid, ssrc) in &streams {
+        l.direct_api().declare_media(*mid, MediaKind::Video);
+        l.direct_api().declare_stream_tx(*ssrc, None, *mid, None);
+
+        r.direct_api().declare_media(*mid, MediaKind::Video);
+        r.direct_api().expect_stream_rx(*ssrc, None, *mid, None);
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let wallclock = l.start + l.duration();
+
+    for (_, ssrc) in &streams {
+        l.direct_api()
+            .stream_tx(ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                0.into(),
+                0,
+                wallclock,
+                true,
+                ExtensionValues::default(),
+                false,
+                vec![1; 10],
+            )
+            .expect("clean write");
+    }
+
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(7) {
+            break;
+        }
+    }
+
+    // Sent by R, as packed by str0m.
+    let sent = receiver_reports(&r, |p| match p {
+        RawPacket::RtcpTx(v) => Some(v),
+        _ => None,
+    });
+
+    // Received by L, as parsed from the wire.
+    let received = receiver_reports(&l, |p| match p {
+        RawPacket::RtcpRx(v) => Some(v),
+        _ => None,
+    });
+
+    for reports in [&sent, &received] {
+        assert!(!reports.is_empty());
+
+        // RFC 3550 allows max 31 report blocks per RR.
+        assert!(reports.iter().all(|r| r.len() <= 31), "{:?}", reports);
+
+        // The blocks for the 40 streams are split over chained RRs, filling the first.
+        let sizes: Vec<_> = reports.iter().map(|r| r.len()).collect();
+        assert_eq!(sizes, [31, 9]);
+
+        let reported: HashSet<_> = reports.iter().flatten().collect();
+        assert_eq!(reported.len(), STREAMS as usize);
+    }
+
+    assert_eq!(sent, received);
+
+    Ok(())
+}
+
+/// The SSRCs in the report blocks of each RR.
+fn receiver_reports(rtc: &TestRtc, f: impl Fn(&RawPacket) -> Option<&Rtcp>) -> Vec<Vec<Ssrc>> {
+    rtc.events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RawPacket(p) => f(p),
+            _ => None,
+        })
+        .filter_map(|p| match p {
+            Rtcp::ReceiverReport(rr) => Some(rr.reports.iter().map(|b| b.ssrc).collect()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::RawPacket;
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn rtp_mtu() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = Rtc::builder().enable_raw_packets(true).build();
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    const MTU: usize = 600;
+    l.set_rtp_mtu(MTU);
+
+    let ssrc = l.direct_api().stream_tx_by_mid(mid, None).unwrap().ssrc();
+    let max_payload = l.max_payload_size(ssrc).unwrap();
+    assert!(max_payload < MTU - 16 - 12, "max payload: {}", max_payload);
+
+    let pt = l.params_vp8().pt();
+    let data = vec![1_u8; 3000];
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, data.clone())?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    let sizes: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpTx(_, packet)) => Some(packet.len()),
+            _ => None,
+        })
+        .collect();
+
+    assert!(!sizes.is_empty());
+
+    // Packets fill up the MTU, including the 16 byte SRTP tag.
+    let largest = sizes.iter().max().unwrap();
+    assert!(largest + 16 <= MTU, "largest: {}", largest);
+    assert!(largest + 16 > MTU - 32, "largest: {}", largest);
+
+    // After sending, the max payload accounts for the actual headers.
+    assert!(l.max_payload_size(ssrc).unwrap() <= max_payload);
+
+    Ok(())
+}
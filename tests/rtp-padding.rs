@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, RtcError};
+
+mod common;
+use common::{connect_l_r, init_log, progress};
+
+#[test]
+pub fn rtp_padding_only() -> Result<(), RtcError> {
+    run(None)
+}
+
+#[test]
+pub fn rtp_padding_only_jitter_buffer() -> Result<(), RtcError> {
+    run(Some(Duration::from_millis(50)))
+}
+
+fn run(target_delay: Option<Duration>) -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    let mut api = r.direct_api();
+    let rx = api.expect_stream_rx(ssrc, None, mid, None);
+    if let Some(delay) = target_delay {
+        rx.set_target_delay(delay);
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // Every other packet is padding only, i.e. no media.
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && index < 10 {
+            write_at = l.last + Duration::from_millis(10);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1000 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            let payload = if index % 2 == 0 {
+                vec![index as u8; 5]
+            } else {
+                vec![]
+            };
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    true,
+                    ExtensionValues::default(),
+                    false,
+                    payload,
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_millis(500) {
+            break;
+        }
+    }
+
+    let received: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some((*p.seq_no - 47_000, p.payload.clone())),
+            _ => None,
+        })
+        .collect();
+
+    // The media payload is sent padded to the SRTP block size, and received without the
+    // padding. The padding only packets are not media.
+    let expected: Vec<_> = (0..10).step_by(2).map(|i| (i, vec![i as u8; 5])).collect();
+    assert_eq!(received, expected);
+
+    // The padding only packets are not lost.
+    assert!(!r
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::RtpPacketsLost(_))));
+
+    Ok(())
+}
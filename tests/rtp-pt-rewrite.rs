@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use str0m::format::Codec;
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, RawPacket, Ssrc};
+use str0m::{Event, RtcError};
+
+mod common;
+use common::{connect_l_r, init_log, progress, progress_with_loss};
+
+#[test]
+pub fn rtp_pt_rewrite() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    let mid = "vid".into();
+
+    let ssrc_tx: Ssrc = 42.into();
+    let ssrc_rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+
+    l.direct_api()
+        .declare_stream_tx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+
+    r.direct_api()
+        .expect_stream_rx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let params = l.params_vp8();
+    assert_eq!(params.spec().codec, Codec::Vp8);
+
+    // The PT as negotiated with some upstream peer, which is not a PT in this session.
+    let pt_upstream = 120.into();
+    assert!(l.codec_config().iter().all(|p| p.pt() != pt_upstream));
+
+    l.direct_api()
+        .stream_tx(&ssrc_tx)
+        .unwrap()
+        .set_pt_map([(pt_upstream, params.pt())]);
+
+    let to_write = &[0x1, 0x2, 0x3, 0x4];
+
+    for index in 0..300 {
+        let wallclock = l.start + l.duration();
+
+        let mut direct = l.direct_api();
+        let stream = direct.stream_tx(&ssrc_tx).unwrap();
+
+        let time = (index * 1000 + 47_000_000) as u32;
+        let seq_no = (47_000 + index as u64).into();
+
+        stream
+            .write_rtp(
+                pt_upstream,
+                seq_no,
+                time,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                true,
+                to_write.to_vec(),
+            )
+            .expect("clean write");
+
+        if !(10..=290).contains(&index) {
+            progress(&mut l, &mut r)?;
+        } else {
+            progress_with_loss(&mut l, &mut r, 0.05)?;
+        }
+    }
+
+    let settle_time = l.duration() + Duration::from_secs(2);
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > settle_time {
+            break;
+        }
+    }
+
+    let sent: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpTx(h, _)) => Some(h),
+            _ => None,
+        })
+        .collect();
+
+    let regular = sent.iter().filter(|h| h.ssrc == ssrc_tx).count();
+    assert_eq!(regular, 300);
+
+    // Resends happened, and all use the RTX PT associated with the rewritten PT.
+    let resends: Vec<_> = sent.iter().filter(|h| h.ssrc == ssrc_rtx).collect();
+    assert!(!resends.is_empty());
+
+    for h in sent {
+        let expected = if h.ssrc == ssrc_tx {
+            params.pt()
+        } else {
+            params.resend().unwrap()
+        };
+        assert_eq!(h.payload_type, expected);
+    }
+
+    let received = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(p),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!received.is_empty());
+    assert!(received
+        .iter()
+        .all(|p| p.header.payload_type == params.pt()));
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, progress_with_loss, TestRtc};
+
+#[test]
+pub fn rtp_time_jump_resync() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect(Some(Duration::from_secs(10)));
+    send_with_jump(&mut l, &mut r)?;
+
+    let jumps: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpTimeJump(j) => Some(j),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(jumps.len(), 1, "{:?}", jumps);
+    assert_eq!(jumps[0].timestamp, 10 * 960 + JUMP);
+
+    let times = packet_times(&r);
+    assert_eq!(times.len(), 20);
+
+    // The media time continues as if there was no jump.
+    for w in times.windows(2) {
+        let delta = w[1] - w[0];
+        assert!((900..=1020).contains(&delta), "{:?}", times);
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn rtp_time_jump_disabled() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect(None);
+    send_with_jump(&mut l, &mut r)?;
+
+    assert!(!r
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::RtpTimeJump(_))));
+
+    let times = packet_times(&r);
+    assert_eq!(times.len(), 20);
+
+    // The jump is passed on as is.
+    assert_eq!(times[10] - times[9], 960 + JUMP as u64);
+
+    Ok(())
+}
+
+#[test]
+pub fn rtp_time_jump_ignores_resends() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_secs(1)))
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    // Until the SRTP keying is applied, or the first packets are sent in a burst.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = SSRC.into();
+    let ssrc_rtx: Ssrc = (SSRC + 1).into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api()
+        .declare_stream_tx(ssrc, Some(ssrc_rtx), mid, None);
+
+    // Resends arrive late with the media time of when they were first sent.
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api()
+        .expect_stream_rx(ssrc, Some(ssrc_rtx), mid, None)
+        .set_max_time_jump(Some(Duration::from_millis(50)));
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index: u32 = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let time = index * 1800;
+            let wallclock = l.start + l.duration();
+            let seq_no = (index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress_with_loss(&mut l, &mut r, 0.1)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let recovered = r.events.iter().rev().find_map(|(_, e)| match e {
+        Event::MediaIngressStats(s) => Some(s.rtx_recovered),
+        _ => None,
+    });
+    assert!(recovered.unwrap_or_default() > 0);
+
+    assert!(!r
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::RtpTimeJump(_))));
+
+    Ok(())
+}
+
+const JUMP: u32 = 1 << 30;
+const SSRC: u32 = 42;
+
+fn connect(max_time_jump: Option<Duration>) -> (TestRtc, TestRtc) {
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = SSRC.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api()
+        .expect_stream_rx(ssrc, None, mid, None)
+        .set_max_time_jump(max_time_jump);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    (l, r)
+}
+
+fn send_with_jump(l: &mut TestRtc, r: &mut TestRtc) -> Result<(), RtcError> {
+    let ssrc: Ssrc = SSRC.into();
+    let pt = l.params_opus().pt();
+
+    let mut index: u32 = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && index < 20 {
+            write_at = l.last + Duration::from_millis(20);
+
+            // A broken sender that jumps ahead in time, and continues from there.
+            let time = index * 960 + if index >= 10 { JUMP } else { 0 };
+            let wallclock = l.start + l.duration();
+            let seq_no = (index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn packet_times(r: &TestRtc) -> Vec<u64> {
+    r.events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(p.time.numer()),
+            _ => None,
+        })
+        .collect()
+}
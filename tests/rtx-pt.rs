@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use str0m::format::{Codec, FormatParams};
+use str0m::media::{Frequency, MediaKind};
+use str0m::rtp::{ExtensionValues, RawPacket, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, progress_with_loss};
+
+#[test]
+pub fn rtx_pt_explicit() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .build();
+
+    // The RTX PT as negotiated with the receiver, which is not the default for VP8.
+    let pt_rtx = 118.into();
+    assert!(rtc1
+        .codec_config()
+        .iter()
+        .all(|p| p.pt() != pt_rtx && p.resend() != Some(pt_rtx)));
+
+    let params = rtc1
+        .codec_config()
+        .iter()
+        .find(|p| p.spec().codec == Codec::Vp8)
+        .cloned()
+        .unwrap();
+    let pt = params.pt();
+
+    let mut config = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .clear_codecs();
+    config.codec_config().add_config(
+        pt,
+        Some(pt_rtx),
+        Codec::Vp8,
+        Frequency::NINETY_KHZ,
+        None,
+        FormatParams::default(),
+    );
+    let rtc2 = config.build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+
+    let ssrc_tx: Ssrc = 42.into();
+    let ssrc_rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+
+    l.direct_api()
+        .declare_stream_tx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+
+    r.direct_api()
+        .expect_stream_rx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    {
+        let mut api = l.direct_api();
+        let stream = api.stream_tx(&ssrc_tx).unwrap();
+
+        assert_eq!(stream.rtx_pt(pt), None);
+
+        // Not a dynamic PT, or the same as the main PT.
+        assert!(matches!(
+            stream.set_rtx_pt(pt, 35.into()),
+            Err(RtcError::InvalidRtxPt(_))
+        ));
+        assert!(matches!(
+            stream.set_rtx_pt(pt, pt),
+            Err(RtcError::InvalidRtxPt(_))
+        ));
+        assert_eq!(stream.rtx_pt(pt), None);
+
+        stream.set_rtx_pt(pt, pt_rtx)?;
+        assert_eq!(stream.rtx_pt(pt), Some(pt_rtx));
+    }
+
+    let to_write = &[0x1, 0x2, 0x3, 0x4];
+
+    for index in 0..300 {
+        let wallclock = l.start + l.duration();
+
+        let mut direct = l.direct_api();
+        let stream = direct.stream_tx(&ssrc_tx).unwrap();
+
+        let time = (index * 1000 + 47_000_000) as u32;
+        let seq_no = (47_000 + index as u64).into();
+
+        stream
+            .write_rtp(
+                pt,
+                seq_no,
+                time,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                true,
+                to_write.to_vec(),
+            )
+            .expect("clean write");
+
+        if !(10..=290).contains(&index) {
+            progress(&mut l, &mut r)?;
+        } else {
+            progress_with_loss(&mut l, &mut r, 0.05)?;
+        }
+    }
+
+    let settle_time = l.duration() + Duration::from_secs(2);
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > settle_time {
+            break;
+        }
+    }
+
+    // Resends happened, and all use the explicit RTX PT.
+    let resends: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpTx(h, _)) if h.ssrc == ssrc_rtx => Some(h),
+            _ => None,
+        })
+        .collect();
+
+    assert!(!resends.is_empty());
+    assert!(resends.iter().all(|h| h.payload_type == pt_rtx));
+
+    // The receiver repaired the losses, and reports the RTX PT it saw.
+    let received = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::RtpPacket(_)))
+        .count();
+    assert_eq!(received, 300);
+
+    let mut api = r.direct_api();
+    let stream = api.stream_rx(&ssrc_tx).unwrap();
+    assert_eq!(stream.rtx_pt(pt), Some(pt_rtx));
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn sdes_cname() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc = Rtc::builder().set_cname("my-cname".into()).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid_audio = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let mid_video = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    // Both m-lines advertise the configured CNAME.
+    let sdp = offer.to_sdp_string();
+    assert_eq!(sdp.matches("cname:my-cname").count(), 3);
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt_audio = l.params_opus().pt();
+    let pt_video = l.params_vp8().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid_audio)
+            .unwrap()
+            .write(pt_audio, wallclock, time, vec![1_u8; 80])?;
+        l.writer(mid_video)
+            .unwrap()
+            .write(pt_video, wallclock, time, vec![1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(7) {
+            break;
+        }
+    }
+
+    let mut api = r.direct_api();
+    let audio = api.stream_rx_by_mid(mid_audio, None).unwrap().ssrc();
+    let video = api.stream_rx_by_mid(mid_video, None).unwrap().ssrc();
+
+    let mut expected = vec![audio, video];
+    expected.sort();
+
+    // Audio and video are grouped by the CNAME received in RTCP SDES, with mids and the
+    // NTP/RTP mapping from the SR.
+    let groups = r.rtc.sync_groups();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].cname, "my-cname");
+
+    let ssrcs: Vec<_> = groups[0].members.iter().map(|m| m.ssrc).collect();
+    assert_eq!(ssrcs, expected);
+
+    for m in &groups[0].members {
+        let expected_mid = if m.ssrc == audio {
+            mid_audio
+        } else {
+            mid_video
+        };
+        assert_eq!(m.mid, expected_mid);
+        assert_eq!(m.rid, None);
+
+        let info = m.sender_info.expect("sender info from SR");
+        assert_eq!(info.ssrc, m.ssrc);
+    }
+
+    Ok(())
+}
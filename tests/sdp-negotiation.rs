@@ -2,16 +2,24 @@ mod common;
 use common::init_log;
 use common::negotiate;
 use common::TestRtc;
+use str0m::change::BundlePolicy;
+use str0m::change::SdpAnswer;
+use str0m::change::SdpDescription;
 use str0m::change::SdpOffer;
+use str0m::error::SdpError;
 use str0m::format::Codec;
 use str0m::format::CodecSpec;
+use str0m::format::DecoderLimits;
 use str0m::format::FormatParams;
+use str0m::format::PacketTime;
 use str0m::format::PayloadParams;
 use str0m::media::Direction;
 use str0m::media::Frequency;
+use str0m::media::KeyframeRequestKind;
 use str0m::media::MediaKind;
 use str0m::rtp::{Extension, ExtensionMap};
 use str0m::Rtc;
+use str0m::RtcError;
 use tracing::info_span;
 use tracing::Span;
 
@@ -262,6 +270,229 @@ fn answer_remaps() {
     assert_eq!(a_r, vec![(3, &TransportSequenceNumber), (12, &AudioLevel)]);
 }
 
+#[test]
+fn answer_rtx_follows_offer() {
+    init_log();
+
+    // R has both primary and RTX on different PTs than the OFFER. Both must follow.
+    let (l, r) = with_params(
+        //
+        info_span!("L"),
+        &[vp8_rtx(96, 97)],
+        info_span!("R"),
+        &[vp8_rtx(100, 101)],
+    );
+
+    assert_eq!(&[vp8_rtx(96, 97)], &**l.codec_config());
+    assert_eq!(&[vp8_rtx(96, 97)], &**r.codec_config());
+}
+
+#[test]
+fn answer_renumbers_rtx() {
+    init_log();
+
+    let mut l = build_params(info_span!("L"), &[vp8_rtx(96, 97)]);
+    let mut r = build_params(info_span!("R"), &[vp8_rtx(96, 97)]);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+
+    // The answerer renumbers both the primary and the RTX PT.
+    let sdp = answer
+        .to_sdp_string()
+        .replace("SAVPF 96 97", "SAVPF 100 101")
+        .replace(":96 ", ":100 ")
+        .replace(":97 ", ":101 ")
+        .replace("apt=96", "apt=100");
+
+    let answer = SdpAnswer::from_sdp_string(&sdp).unwrap();
+    l.sdp_api().accept_answer(pending, answer).unwrap();
+
+    // The RTX linkage survives the renumbering.
+    assert_eq!(&[vp8_rtx(100, 101)], &**l.codec_config());
+    assert!(l.codec_config()[0]._is_locked());
+
+    let mid = l._mids()[0];
+    assert_eq!(l.media(mid).unwrap().remote_pts(), &[100.into()]);
+}
+
+#[test]
+fn answer_fb_round_trip() {
+    init_log();
+
+    let mut l = build_params(info_span!("L"), &[vp8(100)]);
+    let mut r = build_params(info_span!("R"), &[vp8(100)]);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let offer_sdp = offer.to_sdp_string();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+    let answer_sdp = answer.to_sdp_string();
+
+    l.sdp_api().accept_answer(pending, answer).unwrap();
+
+    for fb in [
+        "a=rtcp-fb:100 transport-cc\r\n",
+        "a=rtcp-fb:100 goog-remb\r\n",
+        "a=rtcp-fb:100 ccm fir\r\n",
+        "a=rtcp-fb:100 nack\r\n",
+        "a=rtcp-fb:100 nack pli\r\n",
+    ] {
+        assert!(offer_sdp.contains(fb), "Offer missing {fb:?}");
+        assert!(answer_sdp.contains(fb), "Answer missing {fb:?}");
+    }
+
+    assert_eq!(&[vp8(100)], &**l.codec_config());
+    assert_eq!(&[vp8(100)], &**r.codec_config());
+}
+
+#[test]
+fn answer_narrows_fb() {
+    init_log();
+
+    // R doesn't do PLI or REMB.
+    let mut vp8_r = vp8(100);
+    vp8_r.set_fb_pli(false);
+    vp8_r.set_fb_remb(false);
+
+    let mut l = build_params(info_span!("L"), &[vp8(100)]);
+    let mut r = build_params(info_span!("R"), &[vp8_r.clone()]);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+    let answer_sdp = answer.to_sdp_string();
+
+    l.sdp_api().accept_answer(pending, answer).unwrap();
+
+    assert!(!answer_sdp.contains("a=rtcp-fb:100 nack pli\r\n"));
+    assert!(!answer_sdp.contains("a=rtcp-fb:100 goog-remb\r\n"));
+    assert!(answer_sdp.contains("a=rtcp-fb:100 nack\r\n"));
+    assert!(answer_sdp.contains("a=rtcp-fb:100 ccm fir\r\n"));
+
+    // L adopts what R answered.
+    assert_eq!(&[vp8_r.clone()], &**l.codec_config());
+    assert_eq!(&[vp8_r], &**r.codec_config());
+
+    // PLI is not negotiated, so it can't be requested.
+    let writer = l.writer(mid).unwrap();
+    assert!(!writer.is_request_keyframe_possible(KeyframeRequestKind::Pli));
+    assert!(writer.is_request_keyframe_possible(KeyframeRequestKind::Fir));
+}
+
+#[test]
+fn negotiate_ptime() {
+    init_log();
+
+    // L prefers to receive 40ms packets, R can't receive more than 30ms.
+    let opus_l = opus_with_format(
+        100,
+        FormatParams {
+            packet_time: Some(Box::new(PacketTime {
+                p_time: Some(40),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+    );
+    let opus_r = opus_with_format(
+        100,
+        FormatParams {
+            packet_time: Some(Box::new(PacketTime {
+                max_p_time: Some(30),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+    );
+
+    let mut l = build_params(info_span!("L"), &[opus_l]);
+    let mut r = build_params(info_span!("R"), &[opus_r]);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let offer_sdp = offer.to_sdp_string();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+    let answer_sdp = answer.to_sdp_string();
+
+    l.sdp_api().accept_answer(pending, answer).unwrap();
+
+    assert!(offer_sdp.contains("a=ptime:40\r\n"));
+    assert!(!offer_sdp.contains("a=maxptime"));
+    assert!(answer_sdp.contains("a=maxptime:30\r\n"));
+    assert!(!answer_sdp.contains("a=ptime"));
+
+    // L prefers 40ms, but R can't go above 30ms.
+    assert_eq!(l.codec_config()[0].send_p_time(), Some(30));
+    // R follows what L prefers.
+    assert_eq!(r.codec_config()[0].send_p_time(), Some(40));
+}
+
+#[test]
+fn answer_tightens_max_fs() {
+    init_log();
+
+    // L can decode up to 1080p, R only up to 720p.
+    let vp8_l = vp8_with_format(
+        100,
+        FormatParams {
+            decoder_limits: Some(Box::new(DecoderLimits {
+                max_fs: Some(8160),
+                max_fr: Some(60),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+    );
+    let vp8_r = vp8_with_format(
+        100,
+        FormatParams {
+            decoder_limits: Some(Box::new(DecoderLimits {
+                max_fs: Some(3600),
+                max_fr: Some(30),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+    );
+
+    let mut l = build_params(info_span!("L"), &[vp8_l]);
+    let mut r = build_params(info_span!("R"), &[vp8_r]);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let offer_sdp = offer.to_sdp_string();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+    let answer_sdp = answer.to_sdp_string();
+
+    l.sdp_api().accept_answer(pending, answer).unwrap();
+
+    assert!(offer_sdp.contains("a=fmtp:100 max-fs=8160;max-fr=60\r\n"));
+    assert!(answer_sdp.contains("a=fmtp:100 max-fs=3600;max-fr=30\r\n"));
+
+    // L must encode within what R can decode, and vice versa.
+    let remote_l = l.codec_config().remote_format(100.into()).unwrap();
+    let remote_l = remote_l.decoder_limits.unwrap();
+    assert_eq!(remote_l.max_fs, Some(3600));
+    assert_eq!(remote_l.max_fr, Some(30));
+
+    let remote_r = r.codec_config().remote_format(100.into()).unwrap();
+    let remote_r = remote_r.decoder_limits.unwrap();
+    assert_eq!(remote_r.max_fs, Some(8160));
+    assert_eq!(remote_r.max_fr, Some(60));
+}
+
 #[test]
 fn offers_unsupported_extension() {
     init_log();
@@ -305,6 +536,168 @@ fn offers_unsupported_extension() {
         m_r.remote_extmap().iter_video().collect::<Vec<_>>(),
         vec![(3, &VideoOrientation)]
     );
+
+    // R configured 5, but uses the id of the offer.
+    assert_eq!(m_r.extension_id(VideoOrientation), Some(3));
+    assert_eq!(m_l.extension_id(ColorSpace), None);
+}
+
+#[test]
+fn offer_missing_fingerprint() {
+    init_log();
+
+    let mut l = build_params(info_span!("L"), &[vp8(100)]);
+    let mut r = build_params(info_span!("R"), &[vp8(100)]);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, _) = change.apply().unwrap();
+
+    let sdp: String = offer
+        .to_sdp_string()
+        .split_inclusive("\r\n")
+        .filter(|l| !l.starts_with("a=fingerprint"))
+        .collect();
+    let offer = SdpOffer::from_sdp_string(&sdp).unwrap();
+
+    let r = r.sdp_api().accept_offer(offer);
+    assert!(matches!(
+        r,
+        Err(RtcError::Sdp(SdpError::MissingFingerprint))
+    ));
+}
+
+#[test]
+fn bundle_policy() {
+    init_log();
+
+    // Which m-lines are bundle-only for audio, video, video, application.
+    let cases = [
+        (BundlePolicy::MaxBundle, [false, true, true, true]),
+        (BundlePolicy::Balanced, [false, false, true, false]),
+        (BundlePolicy::MaxCompat, [false, false, false, false]),
+    ];
+
+    for (policy, expected) in cases {
+        let mut l = TestRtc::new_with_rtc(
+            info_span!("L"),
+            Rtc::builder().set_bundle_policy(policy).build(),
+        );
+        let mut r = TestRtc::new(info_span!("R"));
+
+        let mut change = l.sdp_api();
+        let mids = [
+            change.add_media(MediaKind::Audio, Direction::SendRecv, None, None),
+            change.add_media(MediaKind::Video, Direction::SendRecv, None, None),
+            change.add_media(MediaKind::Video, Direction::SendRecv, None, None),
+        ];
+        change.add_channel("chan".into());
+        let (offer, pending) = change.apply().unwrap();
+
+        let sdp = offer.to_sdp_string();
+        let mlines: Vec<_> = sdp.split("m=").skip(1).collect();
+
+        let bundle_only: Vec<_> = mlines
+            .iter()
+            .map(|m| m.contains("a=bundle-only\r\n"))
+            .collect();
+        assert_eq!(bundle_only, expected, "{:?}", policy);
+
+        for (m, bundle_only) in mlines.iter().zip(expected) {
+            let port = if bundle_only { " 0 " } else { " 9 " };
+            assert!(m.contains(port), "{:?} {}", policy, m);
+        }
+
+        // All m-lines are in the BUNDLE group, whatever the policy.
+        let group = sdp
+            .lines()
+            .find(|l| l.starts_with("a=group:BUNDLE"))
+            .unwrap();
+        assert_eq!(group.split(' ').count(), 5, "{:?} {}", policy, group);
+        for mid in mids {
+            assert!(group.contains(&*mid));
+        }
+
+        // The remote uses bundle-only m-lines bundled, nothing is rejected.
+        let answer = r.sdp_api().accept_offer(offer).unwrap();
+        l.sdp_api().accept_answer(pending, answer).unwrap();
+
+        for mid in mids {
+            assert!(!r.media(mid).unwrap().rejected(), "{:?}", policy);
+            assert!(!l.media(mid).unwrap().rejected(), "{:?}", policy);
+        }
+    }
+}
+
+#[test]
+fn answer_unknown_mid() {
+    init_log();
+
+    let mut l = build_params(info_span!("L"), &[vp8(100)]);
+    let mut r = build_params(info_span!("R"), &[vp8(100)]);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.sdp_api().accept_offer(offer).unwrap();
+
+    let sdp = answer.to_sdp_string().replace(&*mid, "foo");
+    let answer = SdpAnswer::from_sdp_string(&sdp).unwrap();
+
+    let r = l.sdp_api().accept_answer(pending, answer);
+    assert!(matches!(
+        r,
+        Err(RtcError::Sdp(SdpError::AnswerUnknownMid(m))) if m == "foo".into()
+    ));
+}
+
+#[test]
+fn local_and_remote_description() {
+    init_log();
+
+    let mut l = build_params(info_span!("L"), &[vp8(100)]);
+    let mut r = build_params(info_span!("R"), &[vp8(100)]);
+
+    assert!(l.local_description().is_none());
+    assert!(l.remote_description().is_none());
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Video, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    // Not negotiated until the answer is accepted.
+    assert!(l.local_description().is_none());
+
+    let answer = r.sdp_api().accept_offer(offer.clone()).unwrap();
+    l.sdp_api().accept_answer(pending, answer.clone()).unwrap();
+
+    assert_eq!(
+        l.local_description(),
+        Some(&SdpDescription::Offer(offer.clone()))
+    );
+    assert_eq!(
+        l.remote_description(),
+        Some(&SdpDescription::Answer(answer.clone()))
+    );
+    assert_eq!(r.local_description(), Some(&SdpDescription::Answer(answer)));
+    assert_eq!(r.remote_description(), Some(&SdpDescription::Offer(offer)));
+
+    // R renegotiates, which flips the roles.
+    let mut change = r.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = l.sdp_api().accept_offer(offer).unwrap();
+    r.sdp_api().accept_answer(pending, answer).unwrap();
+
+    let Some(SdpDescription::Answer(local)) = l.local_description() else {
+        panic!("Expected answer as local description");
+    };
+    assert_eq!(local.to_sdp_string().matches("m=").count(), 2);
+    assert!(matches!(
+        r.remote_description(),
+        Some(SdpDescription::Answer(_))
+    ));
 }
 
 #[test]
@@ -417,6 +810,12 @@ fn build_params(span: Span, params: &[PayloadParams]) -> TestRtc {
             p.spec().channels,
             p.spec().format,
         );
+        let added = config.last_mut().unwrap();
+        added.set_fb_transport_cc(p.fb_transport_cc());
+        added.set_fb_nack(p.fb_nack());
+        added.set_fb_pli(p.fb_pli());
+        added.set_fb_fir(p.fb_fir());
+        added.set_fb_remb(p.fb_remb());
     }
     let rtc = b.build();
 
@@ -434,6 +833,10 @@ fn build_exts(span: Span, exts: ExtensionMap) -> TestRtc {
 }
 
 fn opus(pt: u8) -> PayloadParams {
+    opus_with_format(pt, FormatParams::default())
+}
+
+fn opus_with_format(pt: u8, format: FormatParams) -> PayloadParams {
     PayloadParams::new(
         pt.into(),
         None,
@@ -441,12 +844,16 @@ fn opus(pt: u8) -> PayloadParams {
             codec: Codec::Opus,
             channels: Some(2),
             clock_rate: Frequency::FORTY_EIGHT_KHZ,
-            format: FormatParams::default(),
+            format,
         },
     )
 }
 
 fn vp8(pt: u8) -> PayloadParams {
+    vp8_with_format(pt, FormatParams::default())
+}
+
+fn vp8_with_format(pt: u8, format: FormatParams) -> PayloadParams {
     PayloadParams::new(
         pt.into(),
         None,
@@ -454,7 +861,7 @@ fn vp8(pt: u8) -> PayloadParams {
             codec: Codec::Vp8,
             channels: None,
             clock_rate: Frequency::NINETY_KHZ,
-            format: FormatParams::default(),
+            format,
         },
     )
 }
@@ -471,3 +878,16 @@ fn h264(pt: u8) -> PayloadParams {
         },
     )
 }
+
+fn vp8_rtx(pt: u8, rtx: u8) -> PayloadParams {
+    PayloadParams::new(
+        pt.into(),
+        Some(rtx.into()),
+        CodecSpec {
+            codec: Codec::Vp8,
+            channels: None,
+            clock_rate: Frequency::NINETY_KHZ,
+            format: FormatParams::default(),
+        },
+    )
+}
@@ -0,0 +1,115 @@
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+use str0m::bwe::{BandwidthEstimator, Bitrate, PacketFeedback};
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Rtc, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+/// Estimates whatever it was last reset to.
+struct Fixed(Bitrate);
+
+impl BandwidthEstimator for Fixed {
+    fn handle_feedback(&mut self, _feedback: &[PacketFeedback], _now: Instant) {}
+
+    fn handle_timeout(&mut self, _now: Instant) {}
+
+    fn poll_timeout(&self) -> Option<Instant> {
+        None
+    }
+
+    fn estimate(&self) -> Option<Bitrate> {
+        Some(self.0)
+    }
+
+    fn reset(&mut self, init_bitrate: Bitrate) {
+        self.0 = init_bitrate;
+    }
+}
+
+#[test]
+pub fn send_headroom() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc_l = Rtc::builder().enable_bwe(Some(Bitrate::kbps(300))).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    l.bwe().set_estimator(Fixed(Bitrate::mbps(2)));
+
+    // Nothing sent yet, all of the estimate is headroom.
+    progress(&mut l, &mut r)?;
+    assert_eq!(l.rtc.send_headroom(), Some(Bitrate::mbps(2)));
+
+    // Roughly 250kbit/s of media.
+    run(&mut l, &mut r, mid, Duration::from_secs(2))?;
+
+    let headroom = l.rtc.send_headroom().unwrap().as_f64();
+    assert!(
+        (1_650_000.0..1_800_000.0).contains(&headroom),
+        "{}",
+        headroom
+    );
+
+    // The estimate drops below what we send.
+    l.bwe().reset(Bitrate::kbps(100));
+    progress(&mut l, &mut r)?;
+
+    let headroom = l.rtc.send_headroom().unwrap().as_f64();
+    assert!(headroom < 0.0, "{}", headroom);
+
+    Ok(())
+}
+
+fn run(l: &mut TestRtc, r: &mut TestRtc, mid: Mid, duration: Duration) -> Result<(), RtcError> {
+    let pt = l.params_vp8().pt();
+
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(32);
+
+            let wallclock = l.start + l.duration();
+            let time = l.duration().into();
+            l.writer(mid)
+                .unwrap()
+                .write(pt, wallclock, time, vec![1_u8; 1000])?;
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use str0m::media::{Frequency, MediaKind};
+use str0m::rtp::rtcp::Rtcp;
+use str0m::rtp::{ExtensionValues, RawPacket, Ssrc};
+use str0m::{Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn sender_reports_disabled() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid_on = "on".into();
+    let mid_off = "off".into();
+    let ssrc_on: Ssrc = 42.into();
+    let ssrc_off: Ssrc = 43.into();
+
+    for (mid, ssrc) in [(mid_on, ssrc_on), (mid_off, ssrc_off)] {
+        l.direct_api().declare_media(mid, MediaKind::Video);
+        r.direct_api().declare_media(mid, MediaKind::Video);
+        r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+    }
+
+    l.direct_api()
+        .declare_stream_tx(ssrc_on, None, mid_on, None);
+    l.direct_api()
+        .declare_stream_tx(ssrc_off, None, mid_off, None)
+        .set_sender_reports_enabled(false);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1000 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            for ssrc in [ssrc_on, ssrc_off] {
+                l.direct_api()
+                    .stream_tx(&ssrc)
+                    .unwrap()
+                    .write_rtp(
+                        pt,
+                        seq_no,
+                        time,
+                        wallclock,
+                        false,
+                        ExtensionValues::default(),
+                        true,
+                        vec![0x1, 0x2, 0x3, 0x4],
+                    )
+                    .expect("clean write");
+            }
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    let sr_ssrcs: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtcpRx(Rtcp::SenderReport(sr))) => Some(sr.sender_info.ssrc),
+            _ => None,
+        })
+        .collect();
+
+    assert!(sr_ssrcs.contains(&ssrc_on));
+    assert!(!sr_ssrcs.contains(&ssrc_off));
+
+    let mut api = r.direct_api();
+
+    let sr = api
+        .stream_rx(&ssrc_on)
+        .unwrap()
+        .last_sender_report()
+        .expect("sender report");
+    assert!(sr.received <= r.last);
+    assert_eq!(sr.rtp_time.frequency(), Frequency::NINETY_KHZ);
+
+    // The RTP time above advances at 50kHz instead of the nominal 90kHz.
+    let skew = r.direct_api().stream_rx(&ssrc_on).unwrap().clock_skew_ppm();
+    let expected = (50_000.0 / 90_000.0 - 1.0) * 1_000_000.0;
+    assert!(
+        (skew.expect("clock skew") - expected).abs() < 100.0,
+        "{:?}",
+        skew
+    );
+
+    let mut api = r.direct_api();
+    assert!(api
+        .stream_rx(&ssrc_off)
+        .unwrap()
+        .last_sender_report()
+        .is_none());
+    assert!(api.stream_rx(&ssrc_off).unwrap().clock_skew_ppm().is_none());
+
+    Ok(())
+}
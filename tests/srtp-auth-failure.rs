@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, RtcError};
+
+mod common;
+use common::{connect_l_r, init_log, progress, progress_tampered};
+
+#[test]
+pub fn srtp_auth_failure() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    for index in 0..100 {
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        // One second worth of packets are tampered with.
+        let tamper = (20..70).contains(&index);
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress_tampered(&mut l, &mut r, tamper)?;
+        }
+    }
+
+    let received = r
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::RtpPacket(_)))
+        .count();
+    assert_eq!(received, 50);
+
+    assert_eq!(r.rtc.transport_stats().srtp_auth_failures, 50);
+    assert_eq!(l.rtc.transport_stats().srtp_auth_failures, 0);
+
+    // Rate limited.
+    let events: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::SrtpAuthFailure(v) => Some(*v),
+            _ => None,
+        })
+        .collect();
+
+    assert!(!events.is_empty() && events.len() <= 2, "{:?}", events);
+    assert_eq!(events[0], 1);
+
+    Ok(())
+}
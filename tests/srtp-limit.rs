@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress};
+
+#[test]
+pub fn srtp_limit_approaching() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_srtp_limit_margin(1000)
+        .build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    while l.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    // Nothing is sent yet, only the SRTCP index counts.
+    let left = l.rtc.srtp_packets_left().unwrap();
+    assert!(left > (1 << 30), "{}", left);
+
+    let pt = l.params_opus().pt();
+
+    // A stream that has been going for a very, very long time.
+    let srtp_max = 1_u64 << 48;
+    let start_seq = srtp_max - 1010;
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && index < 20 {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 960) as u32;
+            let seq_no = (start_seq + index).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(1) {
+            break;
+        }
+    }
+
+    // The last packet written has seq_no srtp_max - 991.
+    assert_eq!(l.rtc.srtp_packets_left(), Some(991));
+
+    let warnings: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::SrtpLimitApproaching(v) => Some(*v),
+            _ => None,
+        })
+        .collect();
+
+    // Once, when passing the margin.
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0] <= 1000, "{:?}", warnings);
+
+    Ok(())
+}
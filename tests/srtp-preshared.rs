@@ -0,0 +1,117 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Candidate, DtlsState, Event, Rtc, RtcError, SrtpProfile};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn srtp_preshared() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+    let rtc2 = Rtc::builder().set_rtp_mode(true).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc1);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc2);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    l.add_remote_candidate(host2.clone());
+    r.add_local_candidate(host2);
+    r.add_remote_candidate(host1);
+
+    let creds_l = l.direct_api().local_ice_credentials();
+    let creds_r = r.direct_api().local_ice_credentials();
+    l.direct_api().set_remote_ice_credentials(creds_r);
+    r.direct_api().set_remote_ice_credentials(creds_l);
+
+    l.direct_api().set_ice_controlling(true);
+    r.direct_api().set_ice_controlling(false);
+
+    // No DTLS, both sides are given the same keys up front.
+    let material: Vec<u8> = (0..56).collect();
+    let profile = SrtpProfile::AeadAes128Gcm;
+    l.direct_api()
+        .set_srtp_keying_material(profile, &material, true);
+    r.direct_api()
+        .set_srtp_keying_material(profile, &material, false);
+
+    assert_eq!(l.rtc.srtp_profile(), Some(profile));
+    assert!(l.rtc.srtp_packets_left().is_some());
+
+    while l.rtc.selected_candidate_pair().is_none() || r.rtc.selected_candidate_pair().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    for index in 0..50 {
+        let wallclock = l.start + l.duration();
+        let seq_no = (47_000 + index as u64).into();
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 960,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(20);
+        while l.last < next {
+            progress(&mut l, &mut r)?;
+        }
+    }
+
+    let payloads: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(&p.payload),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(payloads.len(), 50);
+    assert!(payloads.iter().all(|p| **p == [1, 2, 3, 4]));
+
+    assert_eq!(r.rtc.transport_stats().srtp_auth_failures, 0);
+    assert_eq!(l.rtc.dtls_state(), DtlsState::Init);
+    assert!(!l.rtc.is_connected());
+
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+pub fn srtp_preshared_wrong_length() {
+    let mut rtc = Rtc::new();
+    rtc.direct_api()
+        .set_srtp_keying_material(SrtpProfile::Aes128CmSha1_80, &[0; 56], true);
+}
@@ -0,0 +1,46 @@
+use std::net::Ipv4Addr;
+
+use str0m::media::{Direction, MediaKind};
+use str0m::{Candidate, Event, RtcError, SrtpProfile};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn srtp_profile_after_handshake() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    // Not known before the handshake.
+    assert_eq!(l.srtp_profile(), None);
+    assert_eq!(r.srtp_profile(), None);
+
+    let connected = |t: &TestRtc| t.events.iter().any(|(_, e)| matches!(e, Event::Connected));
+
+    loop {
+        if connected(&l) && connected(&r) {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    // Both sides agree on the most preferred profile.
+    assert_eq!(l.srtp_profile(), Some(SrtpProfile::AeadAes128Gcm));
+    assert_eq!(r.srtp_profile(), Some(SrtpProfile::AeadAes128Gcm));
+
+    Ok(())
+}
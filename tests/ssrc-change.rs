@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use str0m::media::{MediaKind, Mid};
+use str0m::rtp::{ExtensionValues, Ssrc};
+use str0m::{Event, RtcError};
+
+mod common;
+use common::{connect_l_r, init_log, progress, TestRtc};
+
+#[test]
+pub fn ssrc_change() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect_l_r();
+
+    // Until the SRTP keying is applied.
+    while l.rtc.srtp_packets_left().is_none() || r.rtc.srtp_packets_left().is_none() {
+        progress(&mut l, &mut r)?;
+    }
+
+    let mid = "vid".into();
+    let ssrc_old: Ssrc = 42.into();
+    let ssrc_new: Ssrc = 43.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc_old, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api().expect_stream_rx(ssrc_old, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    send(&mut l, &mut r, ssrc_old, 47_000, 0..50)?;
+
+    // The sender restarts its encoder with a new SSRC for the same mid.
+    l.direct_api().remove_stream_tx(ssrc_old);
+    l.direct_api().declare_stream_tx(ssrc_new, None, mid, None);
+
+    send(&mut l, &mut r, ssrc_new, 1_000, 50..100)?;
+
+    // A stray packet from the old encoder must not bring back the old stream.
+    l.direct_api().declare_stream_tx(ssrc_old, None, mid, None);
+    send(&mut l, &mut r, ssrc_old, 47_000, 100..101)?;
+    l.direct_api().remove_stream_tx(ssrc_old);
+
+    send(&mut l, &mut r, ssrc_new, 1_000, 101..120)?;
+
+    let changes: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::StreamSsrcChanged(c) => Some(*c),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(changes.len(), 1, "{:?}", changes);
+    assert_eq!(changes[0].mid, mid);
+    assert_eq!(changes[0].rid, None);
+    assert_eq!(changes[0].from, ssrc_old);
+    assert_eq!(changes[0].to, ssrc_new);
+
+    assert!(r.direct_api().stream_rx(&ssrc_old).is_none());
+    assert!(r.direct_api().stream_rx(&ssrc_new).is_some());
+
+    let received: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(p.header.ssrc),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(received.iter().filter(|s| **s == ssrc_old).count(), 50);
+    assert_eq!(received.iter().filter(|s| **s == ssrc_new).count(), 69);
+
+    Ok(())
+}
+
+fn send(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    ssrc: Ssrc,
+    seq_base: u64,
+    range: std::ops::Range<u32>,
+) -> Result<(), RtcError> {
+    let pt = l.params_vp8().pt();
+    let mid: Mid = "vid".into();
+
+    for index in range {
+        let wallclock = l.start + l.duration();
+        let seq_no = (seq_base + index as u64).into();
+
+        let ext = ExtensionValues {
+            mid: Some(mid),
+            ..Default::default()
+        };
+
+        l.direct_api()
+            .stream_tx(&ssrc)
+            .unwrap()
+            .write_rtp(
+                pt,
+                seq_no,
+                index * 3000,
+                wallclock,
+                false,
+                ext,
+                false,
+                vec![1, 2, 3, 4],
+            )
+            .expect("clean write");
+
+        let next = l.last + Duration::from_millis(33);
+        while l.last < next {
+            progress(l, r)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use str0m::change::SdpOffer;
+use str0m::media::{Direction, MediaKind};
+use str0m::rtp::Ssrc;
+use str0m::{Rtc, RtcError};
+
+// Video m-line from Chrome with legacy simulcast, which signals the layers with
+// a=ssrc-group:SIM and pairs each layer with its RTX via a=ssrc-group:FID.
+const CHROME_OFFER: &str = "v=0\r\n\
+    o=- 7140862962362962185 2 IN IP4 127.0.0.1\r\n\
+    s=-\r\n\
+    t=0 0\r\n\
+    a=group:BUNDLE 0\r\n\
+    a=extmap-allow-mixed\r\n\
+    a=msid-semantic: WMS 2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK\r\n\
+    m=video 9 UDP/TLS/RTP/SAVPF 96 97\r\n\
+    c=IN IP4 0.0.0.0\r\n\
+    a=rtcp:9 IN IP4 0.0.0.0\r\n\
+    a=ice-ufrag:Nw7f\r\n\
+    a=ice-pwd:vKx0Qz6k2Uy3dSCvHSA8O1mL\r\n\
+    a=ice-options:trickle\r\n\
+    a=fingerprint:sha-256 8C:64:ED:03:76:D0:3D:B4:88:08:91:64:08:80:A8:C6:5A:BF:8B:4E:38:27:96:CA:08:49:25:73:46:60:20:DC\r\n\
+    a=setup:actpass\r\n\
+    a=mid:0\r\n\
+    a=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\n\
+    a=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+    a=sendonly\r\n\
+    a=msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=rtcp-mux\r\n\
+    a=rtcp-rsize\r\n\
+    a=rtpmap:96 VP8/90000\r\n\
+    a=rtcp-fb:96 goog-remb\r\n\
+    a=rtcp-fb:96 transport-cc\r\n\
+    a=rtcp-fb:96 ccm fir\r\n\
+    a=rtcp-fb:96 nack\r\n\
+    a=rtcp-fb:96 nack pli\r\n\
+    a=rtpmap:97 rtx/90000\r\n\
+    a=fmtp:97 apt=96\r\n\
+    a=ssrc-group:FID 3462331267 1271541541\r\n\
+    a=ssrc-group:FID 49866344 2954617493\r\n\
+    a=ssrc-group:FID 4153238006 3800561127\r\n\
+    a=ssrc-group:SIM 3462331267 49866344 4153238006\r\n\
+    a=ssrc:3462331267 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:3462331267 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=ssrc:1271541541 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:1271541541 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=ssrc:49866344 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:49866344 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=ssrc:2954617493 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:2954617493 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=ssrc:4153238006 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:4153238006 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    a=ssrc:3800561127 cname:Bx5vfbSVsLhWhJ9P\r\n\
+    a=ssrc:3800561127 msid:2ZTHM3ZKGkfJCK6GKKgLpDt4ifmHFq0Qj7XK 4c2e6a3e-8b67-4f0e-9c1e-0cf0b36b8f4e\r\n\
+    ";
+
+#[test]
+pub fn ssrc_group_chrome_fid_sim() -> Result<(), RtcError> {
+    let mut rtc = Rtc::new();
+
+    let offer = SdpOffer::from_sdp_string(CHROME_OFFER)?;
+    let answer = rtc.sdp_api().accept_offer(offer)?;
+
+    let pairs: [(u32, u32); 3] = [
+        (3462331267, 1271541541),
+        (49866344, 2954617493),
+        (4153238006, 3800561127),
+    ];
+
+    let mut direct = rtc.direct_api();
+
+    for (ssrc, rtx) in pairs {
+        let ssrc: Ssrc = ssrc.into();
+        let rtx: Ssrc = rtx.into();
+
+        let stream = direct.stream_rx(&ssrc).expect("stream for main SSRC");
+        assert_eq!(stream.rtx(), Some(rtx));
+        assert_eq!(stream.rid(), None);
+
+        // The RTX SSRCs are not streams of their own.
+        assert!(direct.stream_rx(&rtx).is_none());
+    }
+
+    // We are only receiving, so nothing to group in the answer.
+    assert!(!answer.to_sdp_string().contains("a=ssrc-group"));
+
+    Ok(())
+}
+
+#[test]
+pub fn ssrc_group_fid_per_stream() -> Result<(), RtcError> {
+    let mut l = Rtc::new();
+    let mut r = Rtc::new();
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Video, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.sdp_api().accept_offer(offer)?;
+    l.sdp_api().accept_answer(pending, answer)?;
+
+    // A second send stream with RTX in the same m-line.
+    l.direct_api()
+        .declare_stream_tx(1000.into(), Some(1001.into()), mid, None);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, _) = change.apply().unwrap();
+    let offer = offer.to_sdp_string();
+
+    assert_eq!(offer.matches("a=ssrc-group:FID ").count(), 2, "{}", offer);
+    assert!(offer.contains("a=ssrc-group:FID 1000 1001\r\n"));
+
+    Ok(())
+}
@@ -3,12 +3,13 @@ use std::time::Duration;
 
 use str0m::format::Codec;
 use str0m::media::{Direction, MediaKind};
+use str0m::rtp::{ExtensionValues, Ssrc};
 use str0m::stats::MediaEgressStats;
 use str0m::{Candidate, Event, RtcConfig, RtcError};
 use tracing::info_span;
 
 mod common;
-use common::{init_log, progress, TestRtc};
+use common::{connect_l_r_with_rtc, init_log, progress, TestRtc};
 
 #[test]
 pub fn stats() -> Result<(), RtcError> {
@@ -136,3 +137,99 @@ pub fn stats() -> Result<(), RtcError> {
 
     Ok(())
 }
+
+#[test]
+pub fn reset_stats() -> Result<(), RtcError> {
+    init_log();
+
+    let config = RtcConfig::new()
+        .set_rtp_mode(true)
+        .set_stats_interval(Some(Duration::from_secs(1)));
+
+    let (mut l, mut r) = connect_l_r_with_rtc(config.clone().build(), config.build());
+
+    let mid = "vid".into();
+    let ssrc: Ssrc = 42.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    let mut index = 0;
+    let mut write_at = l.last;
+
+    let mut write_until = |l: &mut common::TestRtc, r: &mut common::TestRtc, until: Duration| loop {
+        if l.last >= write_at {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let time = (index * 1800 + 47_000_000) as u32;
+            let seq_no = (47_000 + index as u64).into();
+            index += 1;
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    time,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    true,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > until {
+            return Ok::<_, RtcError>(());
+        }
+    };
+
+    write_until(&mut l, &mut r, Duration::from_secs(3))?;
+
+    l.direct_api().stream_tx(&ssrc).unwrap().reset_stats();
+    r.direct_api().stream_rx(&ssrc).unwrap().reset_stats();
+    l.events.clear();
+    r.events.clear();
+
+    write_until(&mut l, &mut r, Duration::from_secs(4))?;
+
+    let egress = l
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::MediaEgressStats(s) => Some(s.packets),
+            _ => None,
+        })
+        .unwrap();
+
+    let ingress = r
+        .events
+        .iter()
+        .rev()
+        .find_map(|(_, e)| match e {
+            Event::MediaIngressStats(s) => Some(s.packets),
+            _ => None,
+        })
+        .unwrap();
+
+    // One second worth of packets after the reset, not four.
+    assert!(egress > 0 && egress <= 60, "egress packets: {}", egress);
+    assert!(ingress > 0 && ingress <= 60, "ingress packets: {}", ingress);
+
+    Ok(())
+}
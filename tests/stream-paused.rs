@@ -0,0 +1,163 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Event, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn stream_paused_and_resumed() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, mid) = connect()?;
+
+    r.direct_api()
+        .stream_rx_by_mid(mid, None)
+        .unwrap()
+        .set_pause_threshold(Duration::from_millis(500));
+
+    run(&mut l, &mut r, Some(mid), Duration::from_secs(1))?;
+    run(&mut l, &mut r, None, Duration::from_secs(1))?;
+    run(&mut l, &mut r, Some(mid), Duration::from_secs(1))?;
+
+    let paused: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(at, e)| match e {
+            Event::StreamPaused(p) => Some((at.duration_since(r.start), p.paused)),
+            _ => None,
+        })
+        .collect();
+
+    // Active on the first packet, inactive after the threshold, and active again when
+    // the packets resume.
+    assert_eq!(
+        paused.iter().map(|(_, p)| *p).collect::<Vec<_>>(),
+        vec![false, true, false],
+        "{:?}",
+        paused
+    );
+
+    // Inactive 500ms after the last packet, give or take a tick.
+    let gap = paused[1].0 - paused[0].0;
+    assert!(gap > Duration::from_millis(1400), "{:?}", gap);
+    assert!(gap < Duration::from_millis(1600), "{:?}", gap);
+
+    Ok(())
+}
+
+#[test]
+pub fn stream_active_and_inactive() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r, mid) = connect()?;
+
+    r.direct_api()
+        .stream_rx_by_mid(mid, None)
+        .unwrap()
+        .set_inactive_timeout(Duration::from_millis(300));
+
+    run(&mut l, &mut r, Some(mid), Duration::from_secs(1))?;
+    run(&mut l, &mut r, None, Duration::from_secs(1))?;
+    run(&mut l, &mut r, Some(mid), Duration::from_secs(1))?;
+
+    let activity: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(at, e)| match e {
+            Event::StreamActive(a) => Some((at.duration_since(r.start), a.mid, true)),
+            Event::StreamInactive(a) => Some((at.duration_since(r.start), a.mid, false)),
+            _ => None,
+        })
+        .collect();
+
+    // Active on the first packet, inactive after the timeout, and active again when
+    // the packets resume.
+    assert_eq!(
+        activity
+            .iter()
+            .map(|(_, m, a)| (*m, *a))
+            .collect::<Vec<_>>(),
+        vec![(mid, true), (mid, false), (mid, true)],
+        "{:?}",
+        activity
+    );
+
+    // Inactive 300ms after the last packet, give or take a tick.
+    let gap = activity[1].0 - activity[0].0;
+    assert!(gap > Duration::from_millis(1200), "{:?}", gap);
+    assert!(gap < Duration::from_millis(1400), "{:?}", gap);
+
+    // No activity events without an inactive timeout.
+    assert!(!l
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::StreamActive(_) | Event::StreamInactive(_))));
+
+    Ok(())
+}
+
+fn connect() -> Result<(TestRtc, TestRtc, Mid), RtcError> {
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendOnly, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    Ok((l, r, mid))
+}
+
+fn run(
+    l: &mut TestRtc,
+    r: &mut TestRtc,
+    mid: Option<Mid>,
+    duration: Duration,
+) -> Result<(), RtcError> {
+    let end = l.duration() + duration;
+    let mut write_at = l.last;
+
+    loop {
+        if let Some(mid) = mid {
+            if l.last >= write_at {
+                write_at = l.last + Duration::from_millis(20);
+
+                let wallclock = l.start + l.duration();
+                let time = l.duration().into();
+                let pt = l.params_opus().pt();
+                l.writer(mid)
+                    .unwrap()
+                    .write(pt, wallclock, time, vec![1_u8; 80])?;
+            }
+        }
+
+        progress(l, r)?;
+
+        if l.duration() > end {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{Extension, ExtensionValues, RawPacket, Ssrc};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress, progress_with_loss};
+
+#[test]
+pub fn toffset() -> Result<(), RtcError> {
+    init_log();
+
+    let rtc1 = Rtc::builder()
+        .set_rtp_mode(true)
+        .enable_raw_packets(true)
+        .set_extension(14, Extension::TransmissionTimeOffset)
+        .build();
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .set_extension(14, Extension::TransmissionTimeOffset)
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "vid".into();
+
+    let ssrc_tx: Ssrc = 42.into();
+    let ssrc_rtx: Ssrc = 44.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Video);
+
+    l.direct_api()
+        .declare_stream_tx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Video);
+
+    r.direct_api()
+        .expect_stream_rx(ssrc_tx, Some(ssrc_rtx), mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_vp8().pt();
+
+    // Media is captured 20ms before it's written, every 10ms.
+    let capture_delay = Duration::from_millis(20);
+    let first_write = l.last;
+
+    for index in 0..300 {
+        let write_at = first_write + Duration::from_millis(10) * index;
+        while l.last < write_at {
+            if !(10..=290).contains(&index) {
+                progress(&mut l, &mut r)?;
+            } else {
+                progress_with_loss(&mut l, &mut r, 0.05)?;
+            }
+        }
+
+        let wallclock = write_at - capture_delay;
+
+        let mut direct = l.direct_api();
+        let stream = direct.stream_tx(&ssrc_tx).unwrap();
+
+        let time = index * 900 + 47_000_000;
+        let seq_no = (47_000 + index as u64).into();
+
+        stream
+            .write_rtp(
+                pt,
+                seq_no,
+                time,
+                wallclock,
+                false,
+                ExtensionValues::default(),
+                true,
+                vec![0x1, 0x2, 0x3, 0x4],
+            )
+            .expect("clean write");
+    }
+
+    let settle_time = l.duration() + Duration::from_secs(2);
+    loop {
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > settle_time {
+            break;
+        }
+    }
+
+    let sent: Vec<_> = l
+        .events
+        .iter()
+        .filter_map(|(t, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpTx(h, _)) => Some((*t, h)),
+            _ => None,
+        })
+        .collect();
+
+    // Every packet carries the offset from when it was captured.
+    assert!(sent
+        .iter()
+        .all(|(_, h)| h.ext_vals.tx_time_offs.unwrap() >= 1800));
+
+    // Resends are offset by the time since capture, which is the offset of the first
+    // send and then some.
+    let mut resends = 0;
+    for (t, h) in sent.iter().filter(|(_, h)| h.ssrc == ssrc_rtx) {
+        let (t_orig, h_orig) = sent
+            .iter()
+            .find(|(_, o)| o.ssrc == ssrc_tx && o.timestamp == h.timestamp)
+            .unwrap();
+
+        let since_orig = (*t - *t_orig).as_micros() as i32 * 90 / 1000;
+        let offset = h.ext_vals.tx_time_offs.unwrap();
+        let offset_orig = h_orig.ext_vals.tx_time_offs.unwrap();
+
+        assert!(offset + 1 >= offset_orig + since_orig);
+        resends += 1;
+    }
+    assert!(resends > 0);
+
+    let received: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::RtpPacket(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(received.len(), 300);
+    assert!(received
+        .iter()
+        .all(|p| p.header.ext_vals.tx_time_offs.unwrap() >= 0));
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "tokio")]
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use str0m::tokio::{run, Command};
+use str0m::{Candidate, Event, Rtc, RtcError};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+mod common;
+use common::init_log;
+
+#[test]
+pub fn tokio_data_channel() -> Result<(), RtcError> {
+    init_log();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(async {
+        let socket_l = UdpSocket::bind("127.0.0.1:0").await?;
+        let socket_r = UdpSocket::bind("127.0.0.1:0").await?;
+        let addr_r = socket_r.local_addr()?;
+
+        data_channel(socket_l, socket_r, addr_r).await
+    })
+}
+
+#[test]
+pub fn tokio_unspecified_bind() -> Result<(), RtcError> {
+    init_log();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(async {
+        let socket_l = UdpSocket::bind("127.0.0.1:0").await?;
+
+        // The destination of incoming datagrams is taken from the candidate.
+        let socket_r = UdpSocket::bind("0.0.0.0:0").await?;
+        let port = socket_r.local_addr()?.port();
+        let addr_r = (Ipv4Addr::LOCALHOST, port).into();
+
+        data_channel(socket_l, socket_r, addr_r).await
+    })
+}
+
+async fn data_channel(
+    socket_l: UdpSocket,
+    socket_r: UdpSocket,
+    addr_r: SocketAddr,
+) -> Result<(), RtcError> {
+    let mut l = Rtc::new();
+    let mut r = Rtc::new();
+    l.add_local_candidate(Candidate::host(socket_l.local_addr()?, "udp")?);
+    r.add_local_candidate(Candidate::host(addr_r, "udp")?);
+
+    let mut change = l.sdp_api();
+    let cid = change.add_channel("data".into());
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.sdp_api().accept_offer(offer)?;
+    l.sdp_api().accept_answer(pending, answer)?;
+
+    let (event_tx_l, mut event_rx_l) = mpsc::channel::<Event>(100);
+    let (command_tx_l, command_rx_l) = mpsc::channel::<Command>(100);
+    let (event_tx_r, mut event_rx_r) = mpsc::channel::<Event>(100);
+    let (_command_tx_r, command_rx_r) = mpsc::channel::<Command>(100);
+
+    tokio::spawn(run(l, socket_l, event_tx_l, command_rx_l));
+    tokio::spawn(run(r, socket_r, event_tx_r, command_rx_r));
+
+    let wait = Duration::from_secs(5);
+
+    // Wait for the channel to open on L.
+    loop {
+        let event = timeout(wait, event_rx_l.recv()).await.unwrap().unwrap();
+        if matches!(event, Event::ChannelOpen(id, _) if id == cid) {
+            break;
+        }
+    }
+
+    let command: Command = Box::new(move |rtc: &mut Rtc| {
+        rtc.channel(cid).unwrap().write(true, b"hello").unwrap();
+    });
+    command_tx_l.send(command).await.unwrap();
+
+    // R receives the data.
+    loop {
+        let event = timeout(wait, event_rx_r.recv()).await.unwrap().unwrap();
+        if let Event::ChannelData(data) = event {
+            assert_eq!(data.data, b"hello");
+            break;
+        }
+    }
+
+    // Dropping the command sender stops L.
+    drop(command_tx_l);
+    while timeout(wait, event_rx_l.recv()).await.unwrap().is_some() {}
+
+    Ok(())
+}
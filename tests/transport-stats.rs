@@ -0,0 +1,82 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::stats::TransportCounters;
+use str0m::{Candidate, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn transport_stats() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1.clone());
+    r.add_local_candidate(host2.clone());
+
+    let mut change = l.sdp_api();
+    let cid = change.add_channel("My little channel".into());
+    let (offer, pending) = change.apply().unwrap();
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    loop {
+        if let Some(mut chan) = l.channel(cid) {
+            chan.write(false, "Hello world! ".as_bytes())
+                .expect("to write string");
+        }
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let stats_l = l.transport_stats();
+    let stats_r = r.transport_stats();
+
+    // Nothing is lost in between, so what one side sends the other receives.
+    let mirrored = |c: TransportCounters| TransportCounters {
+        bytes_tx: c.bytes_rx,
+        packets_tx: c.packets_rx,
+        bytes_rx: c.bytes_tx,
+        packets_rx: c.packets_tx,
+    };
+    assert_eq!(stats_l.total, mirrored(stats_r.total));
+    assert_eq!(stats_l.stun, mirrored(stats_r.stun));
+    assert_eq!(stats_l.dtls, mirrored(stats_r.dtls));
+
+    assert!(stats_l.stun.packets_tx > 0);
+    assert!(stats_l.dtls.bytes_tx > 13 * 100);
+
+    // No media in this session.
+    assert_eq!(stats_l.srtp, TransportCounters::default());
+    assert_eq!(stats_l.srtcp, TransportCounters::default());
+
+    let sum = stats_l.stun.bytes_tx + stats_l.dtls.bytes_tx;
+    assert_eq!(stats_l.total.bytes_tx, sum);
+
+    let pair = stats_l.selected_pair.expect("selected pair");
+    assert_eq!(pair.local, host1.addr());
+    assert_eq!(pair.remote, host2.addr());
+
+    let pair = l.selected_candidate_pair();
+    assert_eq!(pair, Some((host1.addr(), host2.addr())));
+
+    Ok(())
+}
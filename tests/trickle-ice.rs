@@ -0,0 +1,154 @@
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use str0m::media::{Direction, MediaKind, Mid};
+use str0m::{Candidate, Event, IceConnectionState, Input, Output, RtcError};
+use tracing::info_span;
+
+mod common;
+use common::{init_log, progress, TestRtc};
+
+#[test]
+pub fn trickle_ice() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    // No candidates in the SDP.
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+
+    assert!(!offer.to_sdp_string().contains("a=candidate"));
+
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    r.add_local_candidate(host2);
+
+    let mut l_sent = 0;
+    let mut r_sent = 0;
+    let mut l_gathered = false;
+
+    loop {
+        // L gathers its candidate late, when R is already checking.
+        if !l_gathered && l.duration() >= Duration::from_millis(200) {
+            l_gathered = true;
+            let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+            l.add_local_candidate(host1);
+        }
+
+        progress(&mut l, &mut r)?;
+
+        l_sent = trickle(&l, &mut r, l_sent, mid)?;
+        r_sent = trickle(&r, &mut l, r_sent, mid)?;
+
+        if l.is_connected() && r.is_connected() {
+            break;
+        }
+
+        if l.duration() > Duration::from_secs(5) {
+            panic!("Failed to connect with trickled candidates");
+        }
+    }
+
+    assert_eq!(l_sent, 1);
+    assert_eq!(r_sent, 1);
+
+    Ok(())
+}
+
+#[test]
+pub fn trickle_ice_unknown_mid() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+
+    let c = "candidate:1 1 udp 2113929471 1.1.1.1 1000 typ host";
+    let err = l.add_remote_candidate_str(c, Some("foo".into()));
+    assert!(matches!(err, Err(RtcError::UnknownMid(_))));
+
+    l.add_remote_candidate_str(&format!("a={}", c), None)?;
+    l.add_remote_candidate_str("", Some("foo".into()))?;
+
+    Ok(())
+}
+
+#[test]
+pub fn end_of_candidates_fails_promptly() -> Result<(), RtcError> {
+    init_log();
+
+    let mut l = TestRtc::new(info_span!("L"));
+    let mut r = TestRtc::new(info_span!("R"));
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    l.set_local_end_of_candidates();
+
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, _pending) = change.apply().unwrap();
+
+    assert!(offer.to_sdp_string().contains("a=end-of-candidates"));
+
+    r.rtc.sdp_api().accept_offer(offer)?;
+
+    // Nothing R sends arrives at L.
+    loop {
+        r.rtc.handle_input(Input::Timeout(r.last))?;
+
+        match r.rtc.poll_output()? {
+            Output::Timeout(v) => {
+                let tick = r.last + Duration::from_millis(10);
+                r.last = if v == r.last { tick } else { tick.min(v) };
+            }
+            Output::Transmit(_) => {}
+            Output::Event(v) => {
+                if v == Event::IceConnectionStateChange(IceConnectionState::Failed) {
+                    break;
+                }
+                assert_ne!(
+                    v,
+                    Event::IceConnectionStateChange(IceConnectionState::Disconnected)
+                );
+            }
+        }
+
+        if r.duration() > Duration::from_secs(30) {
+            panic!("ICE did not fail after end-of-candidates");
+        }
+    }
+
+    // L emitted end-of-candidates for trickling.
+    l.rtc.handle_input(Input::Timeout(l.last))?;
+    let mut l_eoc = false;
+    while let Output::Event(v) = l.rtc.poll_output()? {
+        l_eoc |= matches!(v, Event::LocalEndOfCandidates);
+    }
+    assert!(l_eoc);
+
+    Ok(())
+}
+
+/// Forward any new local candidates of `from` as strings to `to`.
+fn trickle(from: &TestRtc, to: &mut TestRtc, sent: usize, mid: Mid) -> Result<usize, RtcError> {
+    let candidates: Vec<_> = from
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::LocalIceCandidate(c) => Some(c.to_sdp_string()),
+            _ => None,
+        })
+        .collect();
+
+    for c in &candidates[sent..] {
+        to.add_remote_candidate_str(c, Some(mid))?;
+    }
+
+    Ok(candidates.len())
+}
@@ -1,10 +1,12 @@
 use std::net::Ipv4Addr;
 use std::time::Duration;
 
+use str0m::bwe::Bitrate;
 use str0m::format::Codec;
 use str0m::media::{Direction, MediaKind};
-use str0m::rtp::rtcp::Twcc;
-use str0m::{Candidate, Rtc, RtcError};
+use str0m::rtp::rtcp::{Rtcp, Twcc};
+use str0m::rtp::{Extension, RawPacket};
+use str0m::{Candidate, Event, Rtc, RtcError};
 use tracing::info_span;
 
 mod common;
@@ -117,3 +119,100 @@ pub fn twcc() -> Result<(), RtcError> {
 
     Ok(())
 }
+
+#[test]
+pub fn twcc_two_byte_form() -> Result<(), RtcError> {
+    init_log();
+
+    // Ids above 14 require the two-byte header extension form.
+    let l_rtc = Rtc::builder()
+        .clear_extension_map()
+        .set_extension(15, Extension::TransportSequenceNumber)
+        .enable_bwe(Some(Bitrate::kbps(300)))
+        .enable_raw_packets(true)
+        .build();
+    let r_rtc = Rtc::builder()
+        .clear_extension_map()
+        .set_extension(15, Extension::TransportSequenceNumber)
+        .enable_raw_packets(true)
+        .build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), l_rtc);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), r_rtc);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mid = negotiate(&mut l, &mut r, |change| {
+        change.add_media(MediaKind::Video, Direction::SendOnly, None, None)
+    });
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let media = l.media(mid).unwrap();
+    assert_eq!(
+        media
+            .remote_extmap()
+            .id_of(Extension::TransportSequenceNumber),
+        Some(15)
+    );
+
+    let pt = l.params_vp8().pt();
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+        l.writer(mid)
+            .unwrap()
+            .write(pt, wallclock, time, [1_u8; 80])?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(5) {
+            break;
+        }
+    }
+
+    // R reads the transport sequence numbers at id 15.
+    let mut seqs: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e.as_raw_packet() {
+            Some(RawPacket::RtpRx(header, _)) => Some(header.ext_vals.transport_cc),
+            _ => None,
+        })
+        .collect();
+    assert!(!seqs.is_empty());
+    assert!(seqs.iter().all(|s| s.is_some()));
+    let len = seqs.len();
+    seqs.sort();
+    seqs.dedup();
+    assert_eq!(seqs.len(), len, "transport sequence numbers are unique");
+
+    // L gets TWCC feedback for them and correlates it with the sent packets.
+    let twcc_rx = l
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e.as_raw_packet(), Some(RawPacket::RtcpRx(Rtcp::Twcc(_)))))
+        .count();
+    assert!(twcc_rx > 0);
+
+    let estimate = l
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::EgressBitrateEstimate(_)));
+    assert!(estimate, "BWE from TWCC feedback");
+
+    Ok(())
+}
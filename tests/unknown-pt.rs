@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use str0m::media::MediaKind;
+use str0m::rtp::{ExtensionValues, Ssrc, UnknownPtPolicy};
+use str0m::{Event, Rtc, RtcError};
+
+mod common;
+use common::{connect_l_r_with_rtc, init_log, progress_tampered, TestRtc};
+
+#[test]
+pub fn unknown_pt_drop() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect(UnknownPtPolicy::Drop);
+    send(&mut l, &mut r, false)?;
+
+    assert_eq!(received(&r), 0);
+    assert!(!r
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::UnknownPt(_))));
+
+    let counted = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::PeerStats(s) => Some(s.unknown_pt_packets),
+            _ => None,
+        })
+        .max();
+
+    assert_eq!(counted, Some(20));
+
+    Ok(())
+}
+
+#[test]
+pub fn unknown_pt_event() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect(UnknownPtPolicy::Event);
+    send(&mut l, &mut r, false)?;
+
+    assert_eq!(received(&r), 0);
+
+    let unknown: Vec<_> = r
+        .events
+        .iter()
+        .filter_map(|(_, e)| match e {
+            Event::UnknownPt(u) => Some(u),
+            _ => None,
+        })
+        .collect();
+
+    // Once per SSRC and PT.
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].ssrc, SSRC.into());
+    assert_eq!(unknown[0].pt, l.params_opus().pt());
+
+    Ok(())
+}
+
+#[test]
+pub fn unknown_pt_unauthenticated() -> Result<(), RtcError> {
+    init_log();
+
+    let (mut l, mut r) = connect(UnknownPtPolicy::Event);
+    send(&mut l, &mut r, true)?;
+
+    // Packets failing SRTP authentication are not reported.
+    assert!(!r
+        .events
+        .iter()
+        .any(|(_, e)| matches!(e, Event::UnknownPt(_))));
+    assert_eq!(r.rtc.transport_stats().srtp_auth_failures, 20);
+
+    Ok(())
+}
+
+const SSRC: u32 = 42;
+
+fn connect(policy: UnknownPtPolicy) -> (TestRtc, TestRtc) {
+    let rtc1 = Rtc::builder().set_rtp_mode(true).build();
+
+    // R is not configured for any audio codec.
+    let rtc2 = Rtc::builder()
+        .set_rtp_mode(true)
+        .clear_codecs()
+        .enable_vp8(true)
+        .set_unknown_pt_policy(policy)
+        .set_stats_interval(Some(Duration::from_millis(200)))
+        .build();
+
+    let (mut l, mut r) = connect_l_r_with_rtc(rtc1, rtc2);
+
+    let mid = "aud".into();
+    let ssrc: Ssrc = SSRC.into();
+
+    l.direct_api().declare_media(mid, MediaKind::Audio);
+    l.direct_api().declare_stream_tx(ssrc, None, mid, None);
+
+    r.direct_api().declare_media(mid, MediaKind::Audio);
+    r.direct_api().expect_stream_rx(ssrc, None, mid, None);
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    (l, r)
+}
+
+fn send(l: &mut TestRtc, r: &mut TestRtc, tamper: bool) -> Result<(), RtcError> {
+    let ssrc: Ssrc = SSRC.into();
+    let pt = l.params_opus().pt();
+
+    let mut index: u32 = 0;
+    let mut write_at = l.last;
+
+    loop {
+        if l.last >= write_at && index < 20 {
+            write_at = l.last + Duration::from_millis(20);
+
+            let wallclock = l.start + l.duration();
+            let seq_no = (index as u64).into();
+
+            l.direct_api()
+                .stream_tx(&ssrc)
+                .unwrap()
+                .write_rtp(
+                    pt,
+                    seq_no,
+                    index * 960,
+                    wallclock,
+                    false,
+                    ExtensionValues::default(),
+                    false,
+                    vec![1, 2, 3, 4],
+                )
+                .expect("clean write");
+
+            index += 1;
+        }
+
+        progress_tampered(l, r, tamper)?;
+
+        if l.duration() > Duration::from_secs(2) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn received(r: &TestRtc) -> usize {
+    r.events
+        .iter()
+        .filter(|(_, e)| matches!(e, Event::RtpPacket(_)))
+        .count()
+}
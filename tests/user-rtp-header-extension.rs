@@ -303,3 +303,84 @@ pub fn user_rtp_header_extension_two_byte_form() -> Result<(), RtcError> {
 
     Ok(())
 }
+
+#[test]
+pub fn raw_rtp_header_extension() -> Result<(), RtcError> {
+    init_log();
+
+    let raw_ext = Extension::with_raw_values("http://my-special-extension");
+
+    let rtc_l = Rtc::builder().set_extension(9, raw_ext.clone()).build();
+    let rtc_r = Rtc::builder().set_extension(9, raw_ext).build();
+
+    let mut l = TestRtc::new_with_rtc(info_span!("L"), rtc_l);
+    let mut r = TestRtc::new_with_rtc(info_span!("R"), rtc_r);
+
+    let host1 = Candidate::host((Ipv4Addr::new(1, 1, 1, 1), 1000).into(), "udp")?;
+    let host2 = Candidate::host((Ipv4Addr::new(2, 2, 2, 2), 2000).into(), "udp")?;
+    l.add_local_candidate(host1);
+    r.add_local_candidate(host2);
+
+    let mut change = l.sdp_api();
+    let mid = change.add_media(MediaKind::Audio, Direction::SendRecv, None, None);
+    let (offer, pending) = change.apply().unwrap();
+    let answer = r.rtc.sdp_api().accept_offer(offer)?;
+    l.rtc.sdp_api().accept_answer(pending, answer)?;
+
+    loop {
+        if l.is_connected() || r.is_connected() {
+            break;
+        }
+        progress(&mut l, &mut r)?;
+    }
+
+    let max = l.last.max(r.last);
+    l.last = max;
+    r.last = max;
+
+    let pt = l.params_opus().pt();
+
+    let data_a = vec![1_u8; 80];
+
+    loop {
+        let wallclock = l.start + l.duration();
+        let time = l.duration().into();
+
+        l.writer(mid)
+            .unwrap()
+            .raw_extension_value(9, &[1, 2, 3])
+            // Id 7 is not negotiated and not sent.
+            .raw_extension_value(7, &[4, 5])
+            .write(pt, wallclock, time, data_a.clone())?;
+
+        progress(&mut l, &mut r)?;
+
+        if l.duration() > Duration::from_secs(3) {
+            break;
+        }
+    }
+
+    let datas = r.events.iter().filter_map(|(_, e)| {
+        if let Event::MediaData(d) = e {
+            Some(d)
+        } else {
+            None
+        }
+    });
+
+    let mut empty = true;
+    for data in datas {
+        empty = false;
+        let raw = &data.ext_vals.raw_values;
+
+        assert_eq!(raw.get(9), Some(&[1, 2, 3][..]));
+        assert_eq!(raw.get(7), None);
+
+        // Extensions str0m knows about are only in the typed fields.
+        assert_eq!(raw.get(4), None);
+        assert_eq!(data.ext_vals.mid, Some(mid));
+    }
+    assert!(!empty);
+
+    Ok(())
+}